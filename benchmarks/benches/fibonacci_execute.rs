@@ -29,6 +29,11 @@ fn benchmark_function(c: &mut Criterion) {
             executor.execute(exe.clone(), vec![]).unwrap();
         })
     });
+    group.bench_function("run_fast", |b| {
+        b.iter(|| {
+            executor.run_fast(exe.clone(), vec![]).unwrap();
+        })
+    });
 
     group.finish();
 }
@@ -38,6 +38,13 @@ fn benchmark_function(c: &mut Criterion) {
                 .unwrap();
         })
     });
+    group.bench_function("run_fast", |b| {
+        b.iter(|| {
+            executor
+                .run_fast(exe.clone(), StdIn::from_bytes(&fe_bytes))
+                .unwrap();
+        })
+    });
 
     group.finish();
 }
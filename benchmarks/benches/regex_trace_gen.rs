@@ -0,0 +1,71 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use openvm_benchmarks::utils::build_bench_program;
+use openvm_circuit::arch::{instructions::exe::VmExe, VmExecutor};
+use openvm_keccak256_circuit::Keccak256Rv32Config;
+use openvm_keccak256_transpiler::Keccak256TranspilerExtension;
+use openvm_rv32im_transpiler::{
+    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+};
+use openvm_sdk::StdIn;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Config, p3_baby_bear::BabyBear,
+};
+use openvm_transpiler::{transpiler::Transpiler, FromElf};
+use pprof::criterion::{Output, PProfProfiler};
+
+/// Only trace generation is timed, the segments are executed in the setup of each batch.
+///
+/// The segments hold their chips through `Rc`s, so they cannot be moved into a dedicated thread
+/// pool. Run the benchmark with different `RAYON_NUM_THREADS` to measure how trace generation
+/// scales with the number of cores; the benchmark id records the number of threads used.
+fn benchmark_function(c: &mut Criterion) {
+    let elf = build_bench_program("regex").unwrap();
+    let exe = VmExe::from_elf(
+        elf,
+        Transpiler::<BabyBear>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(Keccak256TranspilerExtension),
+    )
+    .unwrap();
+
+    let mut group = c.benchmark_group("regex");
+    group.sample_size(10);
+    let config = Keccak256Rv32Config::default();
+    let executor = VmExecutor::<BabyBear, Keccak256Rv32Config>::new(config);
+
+    let data = include_str!("../programs/regex/regex_email.txt");
+
+    let fe_bytes = data.to_owned().into_bytes();
+    group.bench_function(
+        BenchmarkId::new("trace_gen", rayon::current_num_threads()),
+        |b| {
+            b.iter_batched(
+                || {
+                    executor
+                        .execute_segments(exe.clone(), StdIn::from_bytes(&fe_bytes))
+                        .unwrap()
+                },
+                |segments| {
+                    segments
+                        .into_iter()
+                        .map(|segment| {
+                            segment.generate_proof_input::<BabyBearPoseidon2Config>(None)
+                        })
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::PerIteration,
+            );
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(10, Output::Flamegraph(None)));
+    targets = benchmark_function
+}
+criterion_main!(benches);
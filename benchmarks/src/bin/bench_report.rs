@@ -0,0 +1,65 @@
+use std::{
+    fs::{read_to_string, write},
+    path::PathBuf,
+    process::exit,
+};
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use openvm_benchmarks::report::BenchmarkReport;
+
+/// Builds benchmark reports from metrics JSON files and compares them.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert the metrics JSON of a run into a report.
+    FromMetrics {
+        metrics: PathBuf,
+        #[arg(long)]
+        name: String,
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Compare two reports and exit with code 1 if any entry regressed.
+    Compare {
+        baseline: PathBuf,
+        current: PathBuf,
+        /// Relative increase above which an entry is a regression.
+        #[arg(long, default_value_t = 0.05)]
+        threshold: f64,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::FromMetrics {
+            metrics,
+            name,
+            output,
+        } => {
+            let report = BenchmarkReport::from_metrics_file(name, metrics)?;
+            write(output, serde_json::to_string_pretty(&report)?)?;
+        }
+        Command::Compare {
+            baseline,
+            current,
+            threshold,
+        } => {
+            let baseline: BenchmarkReport = serde_json::from_str(&read_to_string(baseline)?)?;
+            let current: BenchmarkReport = serde_json::from_str(&read_to_string(current)?)?;
+            let regressions = current.regressions(&baseline, threshold);
+            for regression in &regressions {
+                println!("{regression}");
+            }
+            if !regressions.is_empty() {
+                exit(1);
+            }
+        }
+    }
+    Ok(())
+}
@@ -42,6 +42,7 @@ async fn main() -> Result<()> {
     let compiler_options = CompilerOptions {
         // For metric collection
         enable_cycle_tracker: true,
+        trace_ir: cli_args.trace_ir,
         ..Default::default()
     };
 
@@ -63,6 +64,8 @@ async fn main() -> Result<()> {
             leaf_fri_params,
             internal_fri_params,
             root_fri_params,
+            public_values_aggregation: Default::default(),
+            exit_policy: Default::default(),
             compiler_options,
         },
         halo2_config: Halo2Config {
@@ -47,6 +47,7 @@ fn main() -> Result<()> {
     let compiler_options = CompilerOptions {
         // For metric collection
         enable_cycle_tracker: true,
+        trace_ir: cli_args.trace_ir,
         ..Default::default()
     };
 
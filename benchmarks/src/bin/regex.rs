@@ -46,6 +46,7 @@ fn main() -> Result<()> {
             .into(),
         compiler_options: CompilerOptions {
             enable_cycle_tracker: true,
+            trace_ir: cli_args.trace_ir,
             ..Default::default()
         },
     };
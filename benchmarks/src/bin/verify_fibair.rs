@@ -45,6 +45,7 @@ fn main() -> Result<()> {
         .with_continuations();
         let compiler_options = CompilerOptions {
             enable_cycle_tracker: true,
+            trace_ir: cli_args.trace_ir,
             ..Default::default()
         };
         let app_config = AppConfig {
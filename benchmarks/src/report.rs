@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, fmt, fs::read_to_string, path::Path};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Machine-readable summary of a benchmark run, built from the metrics JSON written by
+/// `run_with_metric_collection`. Every entry is keyed by `group/...` so runs of the same benchmark
+/// can be compared entry by entry.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub name: String,
+    /// Trace cells per AIR, keyed by `group/air_name` and summed over segments.
+    pub air_cells: BTreeMap<String, u64>,
+    /// Timing gauges (`*_ms`), keyed by `group/metric` with any other label values appended.
+    pub timings_ms: BTreeMap<String, f64>,
+    /// Serialized proof sizes, keyed by `group`.
+    pub proof_size_bytes: BTreeMap<String, u64>,
+    /// Peak resident set size of each proving phase, keyed by `group/phase`.
+    pub peak_rss_bytes: BTreeMap<String, u64>,
+}
+
+/// A report entry which grew by more than the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub entry: String,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Regression {
+    pub fn relative_change(&self) -> f64 {
+        (self.current - self.baseline) / self.baseline
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} ({:+.2}%)",
+            self.entry,
+            self.baseline,
+            self.current,
+            self.relative_change() * 100.0
+        )
+    }
+}
+
+impl BenchmarkReport {
+    pub fn from_metrics_file(name: impl ToString, path: impl AsRef<Path>) -> Result<Self> {
+        let metrics: Value = serde_json::from_str(&read_to_string(path)?)?;
+        Ok(Self::from_metrics(name, &metrics))
+    }
+
+    pub fn from_metrics(name: impl ToString, metrics: &Value) -> Self {
+        let mut report = Self {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        for (metric, labels, value) in entries(metrics, "counter") {
+            if metric == "cells" {
+                if let Some(air_name) = labels.get("air_name") {
+                    let key = format!("{}/{air_name}", group(&labels));
+                    *report.air_cells.entry(key).or_default() += value as u64;
+                }
+            }
+        }
+        for (metric, labels, value) in entries(metrics, "gauge") {
+            match metric.as_str() {
+                "proof_size_bytes" => {
+                    *report
+                        .proof_size_bytes
+                        .entry(group(&labels).to_string())
+                        .or_default() += value as u64;
+                }
+                "peak_rss_bytes" => {
+                    let phase = labels.get("phase").map_or("", String::as_str);
+                    let peak = report
+                        .peak_rss_bytes
+                        .entry(format!("{}/{phase}", group(&labels)))
+                        .or_default();
+                    *peak = (*peak).max(value as u64);
+                }
+                _ if metric.ends_with("_ms") => {
+                    let key = labels
+                        .iter()
+                        .filter(|(key, _)| *key != "group")
+                        .fold(format!("{}/{metric}", group(&labels)), |key, (_, value)| {
+                            format!("{key}/{value}")
+                        });
+                    *report.timings_ms.entry(key).or_default() += value;
+                }
+                _ => {}
+            }
+        }
+        report
+    }
+
+    /// Entries of `self` which are more than `threshold` (relative) larger than in `baseline`.
+    /// Entries missing from either report are skipped.
+    pub fn regressions(&self, baseline: &Self, threshold: f64) -> Vec<Regression> {
+        let current = self.flatten();
+        baseline
+            .flatten()
+            .into_iter()
+            .filter_map(|(entry, baseline)| {
+                let current = *current.get(&entry)?;
+                (baseline > 0.0 && current > baseline * (1.0 + threshold)).then_some(Regression {
+                    entry,
+                    baseline,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    fn flatten(&self) -> BTreeMap<String, f64> {
+        let cells = self
+            .air_cells
+            .iter()
+            .map(|(key, &value)| (format!("cells/{key}"), value as f64));
+        let timings = self
+            .timings_ms
+            .iter()
+            .map(|(key, &value)| (format!("time_ms/{key}"), value));
+        let proof_sizes = self
+            .proof_size_bytes
+            .iter()
+            .map(|(key, &value)| (format!("proof_size_bytes/{key}"), value as f64));
+        let peak_rss = self
+            .peak_rss_bytes
+            .iter()
+            .map(|(key, &value)| (format!("peak_rss_bytes/{key}"), value as f64));
+        cells
+            .chain(timings)
+            .chain(proof_sizes)
+            .chain(peak_rss)
+            .collect()
+    }
+}
+
+fn group(labels: &BTreeMap<String, String>) -> &str {
+    labels.get("group").map_or("", String::as_str)
+}
+
+/// `(metric, labels, value)` of every entry of the given kind in a metrics JSON.
+fn entries(metrics: &Value, kind: &str) -> Vec<(String, BTreeMap<String, String>, f64)> {
+    let Some(entries) = metrics.get(kind).and_then(Value::as_array) else {
+        return vec![];
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let metric = entry.get("metric")?.as_str()?.to_string();
+            let value = match entry.get("value")? {
+                Value::String(value) => value.parse().ok()?,
+                value => value.as_f64()?,
+            };
+            let labels = entry
+                .get("labels")?
+                .as_array()?
+                .iter()
+                .filter_map(|pair| {
+                    let [key, value] = pair.as_array()?.as_slice() else {
+                        return None;
+                    };
+                    Some((key.as_str()?.to_string(), value.as_str()?.to_string()))
+                })
+                .collect();
+            Some((metric, labels, value))
+        })
+        .collect()
+}
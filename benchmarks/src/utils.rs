@@ -46,6 +46,12 @@ pub struct BenchmarkCli {
     /// Max segment length for continuations
     #[arg(short, long, alias = "max_segment_length")]
     pub max_segment_length: Option<usize>,
+
+    /// Log the DSL IR of compiled verifier programs at `debug` level, annotated with the builder
+    /// call sites.
+    /// Call sites are only recorded if RUST_BACKTRACE is set.
+    #[arg(long)]
+    pub trace_ir: bool,
 }
 
 fn get_programs_dir() -> PathBuf {
@@ -109,6 +115,7 @@ where
         .with_profiling()
         .with_program_name(bench_name.to_string());
     let app_proofs = prover.generate_app_proof(input_stream);
+    gauge!("proof_size_bytes").set(bitcode::serialize(&app_proofs)?.len() as f64);
     // 6. Verify STARK proofs.
     vm.verify(&vk, app_proofs.per_segment.clone())
         .expect("Verification failed");
@@ -0,0 +1,101 @@
+import json
+import argparse
+import os
+import time
+import urllib.request
+
+def parse_key_values(pairs):
+    """
+    Parses `key=value` strings, e.g. from OTEL_EXPORTER_OTLP_HEADERS, into a dict.
+    """
+    out = {}
+    for pair in pairs:
+        pair = pair.strip()
+        if not pair:
+            continue
+        key, _, value = pair.partition('=')
+        out[key.strip()] = value.strip()
+    return out
+
+def to_attributes(labels):
+    return [{"key": key, "value": {"stringValue": str(value)}} for key, value in labels]
+
+def to_data_points(entries, time_unix_nano):
+    return [
+        {
+            "attributes": to_attributes(entry['labels']),
+            "timeUnixNano": str(time_unix_nano),
+            "asDouble": float(entry['value']),
+        }
+        for entry in entries
+    ]
+
+def group_by_metric(entries):
+    groups = {}
+    for entry in entries:
+        groups.setdefault(entry['metric'], []).append(entry)
+    return groups
+
+def metrics_to_otlp(metrics_dict, resource_attributes, time_unix_nano):
+    """
+    Converts the JSON written by `run_with_metric_collection` into an OTLP/HTTP JSON request.
+    Counters (cells, rows, cycles) become cumulative monotonic sums and gauges (timings) become
+    gauges. Labels become data point attributes.
+    """
+    metrics = []
+    for name, entries in group_by_metric(metrics_dict.get('counter', [])).items():
+        metrics.append({
+            "name": name,
+            "sum": {
+                "dataPoints": to_data_points(entries, time_unix_nano),
+                "aggregationTemporality": 2,
+                "isMonotonic": True,
+            },
+        })
+    for name, entries in group_by_metric(metrics_dict.get('gauge', [])).items():
+        metrics.append({
+            "name": name,
+            "gauge": {"dataPoints": to_data_points(entries, time_unix_nano)},
+        })
+    return {
+        "resourceMetrics": [{
+            "resource": {"attributes": to_attributes(resource_attributes.items())},
+            "scopeMetrics": [{"scope": {"name": "openvm"}, "metrics": metrics}],
+        }]
+    }
+
+def push(endpoint, headers, body):
+    url = endpoint.rstrip('/')
+    if not url.endswith('/v1/metrics'):
+        url += '/v1/metrics'
+    request = urllib.request.Request(
+        url,
+        data=json.dumps(body).encode(),
+        headers={"Content-Type": "application/json", **headers},
+        method='POST',
+    )
+    with urllib.request.urlopen(request) as response:
+        print(f"Pushed metrics to {url}: {response.status}")
+
+def main():
+    argparser = argparse.ArgumentParser(
+        description="Push a metrics JSON to an OTLP/HTTP endpoint, e.g. an OpenTelemetry collector or Prometheus with OTLP ingestion enabled"
+    )
+    argparser.add_argument('metrics_json', type=str, help="Path to the metrics JSON")
+    argparser.add_argument('--endpoint', type=str, default=os.environ.get('OTEL_EXPORTER_OTLP_ENDPOINT'), help="OTLP/HTTP endpoint, defaults to $OTEL_EXPORTER_OTLP_ENDPOINT")
+    argparser.add_argument('--service-name', type=str, default=os.environ.get('OTEL_SERVICE_NAME', 'openvm-prover'), help="service.name resource attribute")
+    argparser.add_argument('--attribute', type=str, action='append', default=[], help="Extra resource attribute as key=value, may be repeated")
+    args = argparser.parse_args()
+
+    if not args.endpoint:
+        raise SystemExit("No OTLP endpoint given")
+
+    with open(args.metrics_json, 'r') as f:
+        metrics_dict = json.load(f)
+    resource_attributes = {"service.name": args.service_name, **parse_key_values(args.attribute)}
+    headers = parse_key_values(os.environ.get('OTEL_EXPORTER_OTLP_HEADERS', '').split(','))
+    body = metrics_to_otlp(metrics_dict, resource_attributes, time.time_ns())
+    push(args.endpoint, headers, body)
+
+if __name__ == '__main__':
+    main()
@@ -71,6 +71,11 @@ pub struct ExprBuilder {
 
     pub output_indices: Vec<usize>,
 
+    /// Whether to build the products in the constraints with Karatsuba's method, see
+    /// [OverflowInt::karatsuba_mul]. This doesn't change the trace, only the constraints, and
+    /// only pays off for field elements of many limbs, e.g. 32 or 48.
+    pub use_karatsuba: bool,
+
     /// Whether the builder has been finalized. Only after finalize, we can do generate_subrow and eval etc.
     finalized: bool,
 
@@ -101,6 +106,7 @@ impl ExprBuilder {
             constraints: vec![],
             computes: vec![],
             output_indices: vec![],
+            use_karatsuba: false,
             finalized: false,
             needs_setup: false,
         }
@@ -284,6 +290,11 @@ impl<AB: InteractionBuilder> SubAir<AB> for FieldExpr {
         } = self.load_vars(local);
 
         if self.builder.needs_setup() {
+            // A setup row is a valid row with no flag set, and its first input must be the
+            // modulus. Every flag must be an opcode flag, so that the setup opcode is exactly the
+            // rows with `is_setup = 1`, see `FieldExpressionCoreAir::new`. `is_valid` is
+            // constrained here as well so that the binding doesn't rely on the adapter.
+            builder.assert_bool(is_valid);
             let is_setup = flags.iter().fold(is_valid.into(), |acc, &x| acc - x);
             builder.assert_bool(is_setup.clone());
             for i in 0..inputs[0].len().max(self.builder.prime_limbs.len()) {
@@ -315,15 +326,17 @@ impl<AB: InteractionBuilder> SubAir<AB> for FieldExpr {
             })
             .collect();
 
-        // TODO: turn back on once we also support this in ecc and everywhere
-        // builder.when_first_row().assert_one(is_setup);
-
         for flag in flags.iter() {
             builder.assert_bool(*flag);
         }
         for i in 0..self.constraints.len() {
-            let expr = self.constraints[i]
-                .evaluate_overflow_expr::<AB>(&inputs, &vars, &constants, &flags);
+            let expr = self.constraints[i].evaluate_overflow_expr::<AB>(
+                &inputs,
+                &vars,
+                &constants,
+                &flags,
+                self.use_karatsuba,
+            );
             self.check_carry_mod_to_zero.eval(
                 builder,
                 (
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use itertools::Itertools;
 use num_bigint_dig::BigUint;
 use openvm_circuit::arch::{
-    AdapterAirContext, AdapterRuntimeContext, DynAdapterInterface, DynArray, MinimalInstruction,
-    Result, VmAdapterInterface, VmCoreAir, VmCoreChip,
+    AdapterAirContext, AdapterRuntimeContext, DynAdapterInterface, DynArray, ExecutionError,
+    MinimalInstruction, Result, VmAdapterInterface, VmCoreAir, VmCoreChip,
 };
 use openvm_circuit_primitives::{
     var_range::VariableRangeCheckerChip, SubAir, TraceSubRowGenerator,
@@ -62,6 +62,15 @@ impl FieldExpressionCoreAir {
             opcode_flag_idx
         };
         assert_eq!(opcode_flag_idx.len(), local_opcode_idx.len() - 1);
+        if expr.needs_setup() {
+            // The expression binds the modulus on rows with no flag set, so it is only bound on
+            // the setup opcode if every flag is an opcode flag.
+            assert_eq!(
+                opcode_flag_idx.iter().copied().sorted().collect_vec(),
+                (0..expr.builder.num_flags).collect_vec(),
+                "every flag of an expression with setup must be an opcode flag"
+            );
+        }
         Self {
             expr,
             offset,
@@ -212,7 +221,7 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let field_element_limbs = self.air.expr.canonical_num_limbs();
@@ -238,6 +247,14 @@ where
         // If the chip doesn't need setup, (right now) it must be single op chip and thus no flag is needed.
         // Otherwise, there is a flag for each opcode and will be derived by is_valid - sum(flags).
         if self.expr().needs_setup() {
+            let is_setup = Some(&local_opcode_idx) == self.air.local_opcode_idx.last();
+            if is_setup && inputs[0] != self.air.expr.prime {
+                return Err(ExecutionError::SetupModulusMismatch {
+                    pc: from_pc,
+                    opcode,
+                });
+            }
+
             flags = vec![false; self.air.num_flags()];
             self.air
                 .opcode_flag_idx
@@ -304,3 +321,383 @@ where
         }
     }
 }
+
+/// Defines a chip for a field expression on the Rv32 heap adapter from its formula and its
+/// opcodes. The formula is a function
+/// `fn(ExprBuilderConfig, VariableRangeCheckerBus, ...) -> FieldExpr` which is called with the
+/// extra arguments of the generated `new`.
+///
+/// The generated chip is generic over `F`, `BLOCKS` and `BLOCK_SIZE`, where every input and output
+/// is `BLOCKS` blocks of `BLOCK_SIZE` cells, and the calling crate must depend on the crates of the
+/// chip derives and of the adapter.
+///
+/// ```ignore
+/// field_expression_chip! {
+///     pub struct EcAddNeChip {
+///         num_reads: 2,
+///         expr: ec_add_ne_expr(),
+///         opcodes: [
+///             Rv32WeierstrassOpcode::EC_ADD_NE,
+///             Rv32WeierstrassOpcode::SETUP_EC_ADD_NE,
+///         ],
+///         name: "EcAddNe",
+///     }
+/// }
+/// ```
+///
+/// `opcodes` and `opcode_flags` are as in [FieldExpressionCoreAir], and `opcode_flags` can be
+/// omitted for single op chips. `should_finalize` defaults to false.
+///
+/// Only the chip is generated. [field_expression_extension] also generates the opcode enum,
+/// transpiler extension and VM extension of a new single op.
+#[macro_export]
+macro_rules! field_expression_chip {
+    (@or_false) => {
+        false
+    };
+    (@or_false $should_finalize:expr) => {
+        $should_finalize
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            num_reads: $num_reads:literal,
+            expr: $expr_fn:ident($($arg:ident: $arg_ty:ty),* $(,)?),
+            opcodes: [$($opcode:expr),+ $(,)?],
+            $(opcode_flags: [$($flag:expr),* $(,)?],)?
+            name: $op_name:literal
+            $(, should_finalize: $should_finalize:expr)?
+            $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(
+            ::openvm_circuit_primitives_derive::Chip,
+            ::openvm_circuit_primitives_derive::ChipUsageGetter,
+            ::openvm_circuit_derive::InstructionExecutor,
+        )]
+        $vis struct $name<
+            F: ::openvm_stark_backend::p3_field::PrimeField32,
+            const BLOCKS: usize,
+            const BLOCK_SIZE: usize,
+        >(
+            pub  ::openvm_circuit::arch::VmChipWrapper<
+                F,
+                ::openvm_rv32_adapters::Rv32VecHeapAdapterChip<
+                    F,
+                    $num_reads,
+                    BLOCKS,
+                    BLOCKS,
+                    BLOCK_SIZE,
+                    BLOCK_SIZE,
+                >,
+                $crate::FieldExpressionCoreChip,
+            >,
+        );
+
+        impl<
+                F: ::openvm_stark_backend::p3_field::PrimeField32,
+                const BLOCKS: usize,
+                const BLOCK_SIZE: usize,
+            > $name<F, BLOCKS, BLOCK_SIZE>
+        {
+            pub fn new(
+                adapter: ::openvm_rv32_adapters::Rv32VecHeapAdapterChip<
+                    F,
+                    $num_reads,
+                    BLOCKS,
+                    BLOCKS,
+                    BLOCK_SIZE,
+                    BLOCK_SIZE,
+                >,
+                memory_controller: ::openvm_circuit::system::memory::MemoryControllerRef<F>,
+                config: $crate::ExprBuilderConfig,
+                offset: usize,
+                $($arg: $arg_ty,)*
+            ) -> Self {
+                let range_checker = memory_controller.borrow().range_checker.clone();
+                let expr = $expr_fn(config, range_checker.bus(), $($arg),*);
+                let core = $crate::FieldExpressionCoreChip::new(
+                    expr,
+                    offset,
+                    vec![$($opcode as usize),+],
+                    vec![$($($flag),*)?],
+                    range_checker,
+                    $op_name,
+                    $crate::field_expression_chip!(@or_false $($should_finalize)?),
+                );
+                Self(::openvm_circuit::arch::VmChipWrapper::new(
+                    adapter,
+                    core,
+                    memory_controller,
+                ))
+            }
+        }
+    };
+}
+
+/// Defines a new single op from its formula: the opcode enum, the chip (with
+/// [field_expression_chip]), the transpiler extension and the VM extension which registers the
+/// chip. The guest binding is `openvm_platform::field_expression_guest`, since guest crates cannot
+/// depend on this crate, and must be declared with the same `insn` and
+/// `num_limbs = blocks * block_size`.
+///
+/// ```ignore
+/// field_expression_extension! {
+///     /// `x * x + y` modulo `modulus`.
+///     pub struct SquareAdd {
+///         opcode: SquareAddOpcode = 0x380,
+///         chip: SquareAddChip,
+///         executor: SquareAddExecutor,
+///         periphery: SquareAddPeriphery,
+///         transpiler: SquareAddTranspilerExtension,
+///         insn: (0x5b, 0b010),
+///         num_reads: 2,
+///         blocks: 1,
+///         block_size: 32,
+///         expr: square_add_expr(),
+///         name: "SquareAdd",
+///     }
+/// }
+/// ```
+///
+/// The formula is as in [field_expression_chip] without extra arguments, and must need setup.
+/// The op has the local opcodes `OP` and `SETUP`, with the global opcodes from the given offset,
+/// and the setup must be executed before the op. The guest emits R-type instructions with the
+/// major opcode and `funct3` of `insn`, `funct7` is the local opcode and `rd`, `rs1` and `rs2`
+/// are pointers into the heap, so `num_reads` is 1 or 2. The VmConfig derive expects the executor
+/// and periphery of the extension `Foo` to be named `FooExecutor` and `FooPeriphery`.
+///
+/// Besides the crates of [field_expression_chip], the calling crate must depend on
+/// `openvm_circuit_derive`, `openvm_instructions`, `openvm_transpiler`, `rrs_lib`,
+/// `num_bigint_dig` and `serde`.
+#[macro_export]
+macro_rules! field_expression_extension {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            opcode: $opcode:ident = $opcode_offset:literal,
+            chip: $chip:ident,
+            executor: $executor:ident,
+            periphery: $periphery:ident,
+            transpiler: $transpiler:ident,
+            insn: ($insn_opcode:expr, $insn_funct3:expr),
+            num_reads: $num_reads:literal,
+            blocks: $blocks:literal,
+            block_size: $block_size:literal,
+            expr: $expr_fn:ident(),
+            name: $op_name:literal
+            $(, should_finalize: $should_finalize:expr)?
+            $(,)?
+        }
+    ) => {
+        const _: () = assert!(
+            $num_reads == 1 || $num_reads == 2,
+            "the inputs of a field expression op are the heap pointers in rs1 and rs2"
+        );
+
+        #[doc = concat!("The local opcodes of [", stringify!($name), "], selected by `funct7`.")]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(usize)]
+        #[allow(non_camel_case_types)]
+        $vis enum $opcode {
+            OP,
+            SETUP,
+        }
+
+        impl $opcode {
+            pub const ALL: [Self; 2] = [Self::OP, Self::SETUP];
+        }
+
+        impl ::openvm_instructions::UsizeOpcode for $opcode {
+            fn default_offset() -> usize {
+                $opcode_offset
+            }
+
+            fn from_usize(value: usize) -> Self {
+                *Self::ALL.get(value).unwrap_or_else(|| {
+                    panic!("Failed to convert usize {} to opcode {}", value, stringify!($opcode))
+                })
+            }
+
+            fn as_usize(&self) -> usize {
+                *self as usize
+            }
+        }
+
+        $crate::field_expression_chip! {
+            #[doc = concat!("The chip of [", stringify!($name), "].")]
+            $vis struct $chip {
+                num_reads: $num_reads,
+                expr: $expr_fn(),
+                opcodes: [$opcode::OP, $opcode::SETUP],
+                name: $op_name
+                $(, should_finalize: $should_finalize)?
+            }
+        }
+
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        $vis struct $name {
+            pub modulus: ::num_bigint_dig::BigUint,
+        }
+
+        impl $name {
+            /// Panics if the elements of `modulus` do not fit in `blocks * block_size` bytes.
+            pub fn new(modulus: ::num_bigint_dig::BigUint) -> Self {
+                assert!(
+                    modulus.bits() <= $blocks * $block_size * 8,
+                    "the modulus does not fit in {} bytes",
+                    $blocks * $block_size
+                );
+                Self { modulus }
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&self.modulus)
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                let modulus = <String as ::serde::Deserialize>::deserialize(deserializer)?;
+                modulus
+                    .parse()
+                    .map(Self::new)
+                    .map_err(<D::Error as ::serde::de::Error>::custom)
+            }
+        }
+
+        #[derive(
+            ::openvm_circuit_primitives_derive::ChipUsageGetter,
+            ::openvm_circuit_primitives_derive::Chip,
+            ::openvm_circuit_derive::InstructionExecutor,
+            ::openvm_circuit_derive::AnyEnum,
+        )]
+        $vis enum $executor<F: ::openvm_stark_backend::p3_field::PrimeField32> {
+            $chip($chip<F, $blocks, $block_size>),
+        }
+
+        impl<F: ::openvm_stark_backend::p3_field::PrimeField32> From<$chip<F, $blocks, $block_size>>
+            for $executor<F>
+        {
+            fn from(chip: $chip<F, $blocks, $block_size>) -> Self {
+                Self::$chip(chip)
+            }
+        }
+
+        #[derive(
+            ::openvm_circuit_primitives_derive::ChipUsageGetter,
+            ::openvm_circuit_primitives_derive::Chip,
+            ::openvm_circuit_derive::AnyEnum,
+        )]
+        $vis enum $periphery<F: ::openvm_stark_backend::p3_field::PrimeField32> {
+            BitwiseOperationLookup(
+                ::std::sync::Arc<
+                    ::openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip<8>,
+                >,
+            ),
+            Phantom(::openvm_circuit::system::phantom::PhantomChip<F>),
+        }
+
+        impl<F: ::openvm_stark_backend::p3_field::PrimeField32>
+            From<
+                ::std::sync::Arc<
+                    ::openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip<8>,
+                >,
+            > for $periphery<F>
+        {
+            fn from(
+                chip: ::std::sync::Arc<
+                    ::openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip<8>,
+                >,
+            ) -> Self {
+                Self::BitwiseOperationLookup(chip)
+            }
+        }
+
+        impl<F: ::openvm_stark_backend::p3_field::PrimeField32>
+            ::openvm_circuit::arch::VmExtension<F> for $name
+        {
+            type Executor = $executor<F>;
+            type Periphery = $periphery<F>;
+
+            fn build(
+                &self,
+                builder: &mut ::openvm_circuit::arch::VmInventoryBuilder<F>,
+            ) -> Result<
+                ::openvm_circuit::arch::VmInventory<Self::Executor, Self::Periphery>,
+                ::openvm_circuit::arch::VmInventoryError,
+            > {
+                use ::openvm_instructions::UsizeOpcode;
+
+                let mut inventory = ::openvm_circuit::arch::VmInventory::new();
+                let ::openvm_circuit::arch::SystemPort {
+                    execution_bus,
+                    program_bus,
+                    memory_controller,
+                } = builder.system_port();
+                let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+
+                let adapter = ::openvm_rv32_adapters::Rv32VecHeapAdapterChip::new(
+                    execution_bus,
+                    program_bus,
+                    memory_controller.clone(),
+                    bitwise_lu_chip,
+                )
+                .with_setup_guard($opcode::SETUP.with_default_offset());
+                let config = $crate::ExprBuilderConfig {
+                    modulus: self.modulus.clone(),
+                    num_limbs: $blocks * $block_size,
+                    limb_bits: 8,
+                };
+                let chip = $chip::new(
+                    adapter,
+                    memory_controller,
+                    config,
+                    $opcode::default_offset(),
+                );
+                inventory.add_executor(
+                    chip,
+                    $opcode::ALL.map(::openvm_instructions::VmOpcode::with_default_offset),
+                )?;
+
+                Ok(inventory)
+            }
+        }
+
+        #[doc = concat!("The transpiler extension of [", stringify!($name), "].")]
+        #[derive(Clone, Copy, Debug, Default)]
+        $vis struct $transpiler;
+
+        impl<F: ::openvm_stark_backend::p3_field::PrimeField32>
+            ::openvm_transpiler::TranspilerExtension<F> for $transpiler
+        {
+            fn process_custom(
+                &self,
+                instruction_stream: &[u32],
+            ) -> Option<(::openvm_instructions::instruction::Instruction<F>, usize)> {
+                use ::openvm_instructions::UsizeOpcode;
+
+                let instruction_u32 = *instruction_stream.first()?;
+                let opcode = (instruction_u32 & 0x7f) as u8;
+                let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+                if (opcode, funct3) != ($insn_opcode, $insn_funct3) {
+                    return None;
+                }
+                let dec_insn = ::rrs_lib::instruction_formats::RType::new(instruction_u32);
+                let local_opcode = *$opcode::ALL.get(dec_insn.funct7 as usize)?;
+                let instruction = ::openvm_transpiler::util::from_r_type(
+                    local_opcode.with_default_offset(),
+                    2,
+                    &dec_insn,
+                );
+                Some((instruction, 1))
+            }
+        }
+    };
+}
@@ -352,6 +352,72 @@ impl FieldVariable {
             range_checker_bits: a.range_checker_bits,
         }
     }
+
+    /// Returns a new variable which is 1 if `a == b` (mod p) and 0 otherwise.
+    /// Introduces two variables: the flag `eq`, and a helper `inv` with the constraints
+    ///   eq * (a - b) = 0
+    ///   (a - b) * inv + eq - 1 = 0
+    /// so `inv` is the inverse of `a - b` when they differ, and `eq` is forced to 1 otherwise.
+    pub fn is_equal(a: &mut FieldVariable, b: &mut FieldVariable) -> FieldVariable {
+        assert!(Rc::ptr_eq(&a.builder, &b.builder));
+        // Keep the constraints small.
+        for x in [&mut *a, &mut *b] {
+            if !matches!(x.expr, SymbolicExpr::Var(_) | SymbolicExpr::Input(_)) {
+                x.save();
+            }
+        }
+        let diff = SymbolicExpr::Sub(Box::new(a.expr.clone()), Box::new(b.expr.clone()));
+
+        let mut builder = a.builder.borrow_mut();
+        let (eq_idx, eq) = builder.new_var();
+        builder.set_constraint(
+            eq_idx,
+            SymbolicExpr::Mul(Box::new(eq.clone()), Box::new(diff.clone())),
+        );
+        builder.set_compute(
+            eq_idx,
+            SymbolicExpr::IsEqual(Box::new(a.expr.clone()), Box::new(b.expr.clone())),
+        );
+
+        let (inv_idx, inv) = builder.new_var();
+        builder.set_constraint(
+            inv_idx,
+            SymbolicExpr::IntAdd(
+                Box::new(SymbolicExpr::Add(
+                    Box::new(SymbolicExpr::Mul(Box::new(diff.clone()), Box::new(inv))),
+                    Box::new(eq.clone()),
+                )),
+                -1,
+            ),
+        );
+        // (1 - eq) / (a - b + eq): the denominator is 1 when a == b, so it is never zero.
+        builder.set_compute(
+            inv_idx,
+            SymbolicExpr::Div(
+                Box::new(SymbolicExpr::IntAdd(
+                    Box::new(SymbolicExpr::IntMul(Box::new(eq.clone()), -1)),
+                    1,
+                )),
+                Box::new(SymbolicExpr::Add(Box::new(diff), Box::new(eq.clone()))),
+            ),
+        );
+        drop(builder);
+
+        FieldVariable::from_var(a.builder.clone(), eq)
+    }
+
+    /// Returns `a` if `cond` is 1 and `b` if `cond` is 0, where `cond` is a variable of the
+    /// expression, e.g. the output of [FieldVariable::is_equal]. Unlike [FieldVariable::select],
+    /// the condition is not an input flag and it is up to the caller to ensure it is boolean.
+    pub fn select_var(
+        cond: &mut FieldVariable,
+        a: &mut FieldVariable,
+        b: &mut FieldVariable,
+    ) -> FieldVariable {
+        let mut diff = a.sub(b);
+        let mut res = cond.mul(&mut diff);
+        res.add(b)
+    }
 }
 
 impl Add<&mut FieldVariable> for &mut FieldVariable {
@@ -1,6 +1,8 @@
 mod builder;
 mod core_chip;
 mod field_variable;
+mod partition;
+mod staged;
 mod symbolic_expr;
 
 #[cfg(test)]
@@ -9,6 +11,8 @@ mod tests;
 pub use builder::*;
 pub use core_chip::*;
 pub use field_variable::*;
+pub use partition::*;
+pub use staged::*;
 pub use symbolic_expr::*;
 pub mod utils;
 
@@ -0,0 +1,200 @@
+//! Splitting a [FieldExpr] into stages under a column budget.
+//!
+//! The stages alone don't prove the original expression, since they don't constrain the carried
+//! variables to match. [StagedFieldExpr](crate::StagedFieldExpr) links them.
+
+use std::{collections::BTreeSet, ops::Range};
+
+use num_bigint_dig::BigUint;
+
+use super::{ExprBuilder, ExprBuilderConfig, FieldExpr, SymbolicExpr};
+
+/// Where an input of a [FieldExprStage] comes from in the expression it was split from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StageInput {
+    Input(usize),
+    Var(usize),
+}
+
+/// One stage of a [FieldExpr] split by [FieldExpr::partition]. Each stage is a [FieldExpr] on
+/// its own and can be used as the AIR of a separate chip, or of a separate row.
+///
+/// The variables of earlier stages that a stage reads are inputs of that stage. These carried
+/// variables are range checked by the stage which owns them, and linked between stages by
+/// [StagedFieldExpr](crate::StagedFieldExpr).
+#[derive(Clone)]
+pub struct FieldExprStage {
+    pub expr: FieldExpr,
+    /// Source of each input of `expr`: all inputs of the original expression, in order, then the
+    /// carried variables.
+    pub inputs: Vec<StageInput>,
+    /// Index in the original expression of each variable of `expr`.
+    pub vars: Vec<usize>,
+}
+
+impl FieldExprStage {
+    /// The inputs of this stage, given the `inputs` and the executed `vars` of the original
+    /// expression, see [FieldExpr::execute].
+    pub fn stage_inputs(&self, inputs: &[BigUint], vars: &[BigUint]) -> Vec<BigUint> {
+        self.inputs
+            .iter()
+            .map(|input| match *input {
+                StageInput::Input(i) => inputs[i].clone(),
+                StageInput::Var(i) => vars[i].clone(),
+            })
+            .collect()
+    }
+}
+
+impl FieldExpr {
+    /// Splits the expression into stages of consecutive variables such that every stage has at
+    /// most `max_width` columns. A variable is kept in the same stage as any later variable its
+    /// constraint refers to, so a stage can be wider than `max_width` when such a group alone is.
+    ///
+    /// The stages keep the flags, constants and setup of the expression, and the outputs are
+    /// those of the stage owning each output variable.
+    pub fn partition(&self, max_width: usize) -> Vec<FieldExprStage> {
+        assert!(self.builder.is_finalized());
+        let num_vars = self.num_variables;
+        let refs: Vec<BTreeSet<usize>> = (0..num_vars)
+            .map(|i| {
+                let mut refs = BTreeSet::new();
+                collect_vars(&self.constraints[i], &mut refs);
+                collect_vars(&self.computes[i], &mut refs);
+                refs
+            })
+            .collect();
+
+        // Groups of variables which must be in the same stage.
+        let mut groups = vec![];
+        let mut start = 0;
+        let mut end = 0;
+        for i in 0..num_vars {
+            end = end.max(refs[i].last().map_or(i, |&last| last.max(i)) + 1);
+            if end == i + 1 {
+                groups.push(start..end);
+                start = end;
+            }
+        }
+
+        let base_width = 1 + self.num_flags + self.num_limbs * self.num_input;
+        let stage_width = |vars: &Range<usize>| {
+            let carried = vars
+                .clone()
+                .flat_map(|i| refs[i].iter().copied())
+                .filter(|j| !vars.contains(j))
+                .collect::<BTreeSet<_>>()
+                .len();
+            base_width
+                + self.num_limbs * (carried + vars.len())
+                + vars
+                    .clone()
+                    .map(|i| self.q_limbs[i] + self.carry_limbs[i])
+                    .sum::<usize>()
+        };
+
+        let mut stages: Vec<Range<usize>> = vec![];
+        for group in groups {
+            match stages.last_mut() {
+                Some(stage) if stage_width(&(stage.start..group.end)) <= max_width => {
+                    stage.end = group.end;
+                }
+                _ => stages.push(group),
+            }
+        }
+
+        stages
+            .into_iter()
+            .map(|vars| self.stage(vars, &refs))
+            .collect()
+    }
+
+    fn stage(&self, vars: Range<usize>, refs: &[BTreeSet<usize>]) -> FieldExprStage {
+        let carried: Vec<usize> = vars
+            .clone()
+            .flat_map(|i| refs[i].iter().copied())
+            .filter(|j| !vars.contains(j))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let inputs: Vec<StageInput> = (0..self.num_input)
+            .map(StageInput::Input)
+            .chain(carried.iter().copied().map(StageInput::Var))
+            .collect();
+
+        let remap_var = |j: usize| {
+            if vars.contains(&j) {
+                SymbolicExpr::Var(j - vars.start)
+            } else {
+                let k = carried.binary_search(&j).unwrap();
+                SymbolicExpr::Input(self.num_input + k)
+            }
+        };
+
+        let config = ExprBuilderConfig {
+            modulus: self.prime.clone(),
+            num_limbs: self.num_limbs,
+            limb_bits: self.limb_bits,
+        };
+        let mut builder = ExprBuilder::new(config, self.range_checker_bits);
+        builder.num_input = inputs.len();
+        builder.num_flags = self.num_flags;
+        builder.constants = self.constants.clone();
+        builder.use_karatsuba = self.use_karatsuba;
+        for i in vars.clone() {
+            let (index, _) = builder.new_var();
+            builder.set_constraint(index, remap(&self.constraints[i], &remap_var));
+            builder.set_compute(index, remap(&self.computes[i], &remap_var));
+        }
+        builder.output_indices = self
+            .output_indices
+            .iter()
+            .filter(|&&i| vars.contains(&i))
+            .map(|&i| i - vars.start)
+            .collect();
+
+        FieldExprStage {
+            expr: FieldExpr::new(builder, self.range_bus, self.builder.needs_setup()),
+            inputs,
+            vars: vars.collect(),
+        }
+    }
+}
+
+fn collect_vars(expr: &SymbolicExpr, vars: &mut BTreeSet<usize>) {
+    match expr {
+        SymbolicExpr::Var(i) => {
+            vars.insert(*i);
+        }
+        SymbolicExpr::Input(_) | SymbolicExpr::Const(..) => {}
+        SymbolicExpr::Add(lhs, rhs)
+        | SymbolicExpr::Sub(lhs, rhs)
+        | SymbolicExpr::Mul(lhs, rhs)
+        | SymbolicExpr::Div(lhs, rhs)
+        | SymbolicExpr::IsEqual(lhs, rhs)
+        | SymbolicExpr::Select(_, lhs, rhs) => {
+            collect_vars(lhs, vars);
+            collect_vars(rhs, vars);
+        }
+        SymbolicExpr::IntAdd(lhs, _) | SymbolicExpr::IntMul(lhs, _) => collect_vars(lhs, vars),
+    }
+}
+
+/// Replaces every variable of `expr` with `remap_var` of its index.
+fn remap(expr: &SymbolicExpr, remap_var: &impl Fn(usize) -> SymbolicExpr) -> SymbolicExpr {
+    let boxed = |expr: &SymbolicExpr| Box::new(remap(expr, remap_var));
+    match expr {
+        SymbolicExpr::Var(i) => remap_var(*i),
+        SymbolicExpr::Input(_) | SymbolicExpr::Const(..) => expr.clone(),
+        SymbolicExpr::Add(lhs, rhs) => SymbolicExpr::Add(boxed(lhs), boxed(rhs)),
+        SymbolicExpr::Sub(lhs, rhs) => SymbolicExpr::Sub(boxed(lhs), boxed(rhs)),
+        SymbolicExpr::Mul(lhs, rhs) => SymbolicExpr::Mul(boxed(lhs), boxed(rhs)),
+        SymbolicExpr::Div(lhs, rhs) => SymbolicExpr::Div(boxed(lhs), boxed(rhs)),
+        SymbolicExpr::IsEqual(lhs, rhs) => SymbolicExpr::IsEqual(boxed(lhs), boxed(rhs)),
+        SymbolicExpr::IntAdd(lhs, s) => SymbolicExpr::IntAdd(boxed(lhs), *s),
+        SymbolicExpr::IntMul(lhs, s) => SymbolicExpr::IntMul(boxed(lhs), *s),
+        SymbolicExpr::Select(flag_id, lhs, rhs) => {
+            SymbolicExpr::Select(*flag_id, boxed(lhs), boxed(rhs))
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Proving a [FieldExpr] split by [FieldExpr::partition], with one AIR per stage.
+//!
+//! Every operation has one row in the trace of each stage, at the same row index. The stages are
+//! linked by a permutation bus with messages `(op_id, key, values)`, where `op_id` is the row
+//! index:
+//! - the first stage sends the inputs and flags of the operation to every later stage;
+//! - the stage owning a variable sends it to every later stage which reads it.
+//!
+//! The flags message is sent even if there are no flags, so that every stage agrees on which
+//! rows are valid.
+
+use num_bigint_dig::BigUint;
+use openvm_circuit_primitives::{
+    var_range::VariableRangeCheckerChip, SubAir, TraceSubRowGenerator,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField64},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{FieldExpr, FieldExprCols, FieldExprStage, StageInput};
+
+/// The AIR of one stage of a [StagedFieldExpr]: the columns of the stage's [FieldExpr], then the
+/// operation id.
+#[derive(Clone)]
+pub struct FieldExprStageAir {
+    pub stage: FieldExprStage,
+    /// Index of the stage.
+    pub index: usize,
+    pub num_stages: usize,
+    /// The bus linking the stages.
+    pub bus_index: usize,
+    /// Number of inputs of the original expression.
+    num_original_input: usize,
+    /// Number of variables of the original expression.
+    num_original_variables: usize,
+    /// For each variable of the stage, the number of later stages which read it.
+    num_readers: Vec<usize>,
+}
+
+/// A [FieldExpr] proven by one [FieldExprStageAir] for each stage of [FieldExpr::partition].
+pub struct StagedFieldExpr {
+    pub expr: FieldExpr,
+    pub airs: Vec<FieldExprStageAir>,
+}
+
+impl StagedFieldExpr {
+    /// Splits `expr` into stages of at most `max_width` columns, see [FieldExpr::partition],
+    /// linked by the bus `bus_index`. Expressions with setup are not supported, since the setup
+    /// row is defined by the absence of flags in every stage.
+    pub fn new(expr: FieldExpr, max_width: usize, bus_index: usize) -> Self {
+        assert!(
+            !expr.needs_setup(),
+            "staged expressions with setup are not supported"
+        );
+        let stages = expr.partition(max_width);
+        let num_stages = stages.len();
+        let airs = stages
+            .iter()
+            .enumerate()
+            .map(|(index, stage)| {
+                let num_readers = stage
+                    .vars
+                    .iter()
+                    .map(|&var| {
+                        stages[index + 1..]
+                            .iter()
+                            .filter(|later| later.inputs.contains(&StageInput::Var(var)))
+                            .count()
+                    })
+                    .collect();
+                FieldExprStageAir {
+                    stage: stage.clone(),
+                    index,
+                    num_stages,
+                    bus_index,
+                    num_original_input: expr.num_input,
+                    num_original_variables: expr.num_variables,
+                    num_readers,
+                }
+            })
+            .collect();
+        Self { expr, airs }
+    }
+
+    /// Returns the trace of every stage for the operations with `(inputs, flags)`.
+    pub fn generate_traces<F: PrimeField64>(
+        &self,
+        range_checker: &VariableRangeCheckerChip,
+        ops: &[(Vec<BigUint>, Vec<bool>)],
+    ) -> Vec<RowMajorMatrix<F>> {
+        let vars: Vec<_> = ops
+            .iter()
+            .map(|(inputs, flags)| self.expr.execute(inputs.clone(), flags.clone()))
+            .collect();
+        self.airs
+            .iter()
+            .map(|air| {
+                let stage_ops = ops
+                    .iter()
+                    .zip(&vars)
+                    .map(|((inputs, flags), vars)| {
+                        (air.stage.stage_inputs(inputs, vars), flags.clone())
+                    })
+                    .collect();
+                air.generate_trace(range_checker, stage_ops)
+            })
+            .collect()
+    }
+}
+
+impl FieldExprStageAir {
+    /// Returns the trace of this stage for the operations with `(stage_inputs, flags)`, see
+    /// [FieldExprStage::stage_inputs].
+    pub fn generate_trace<F: PrimeField64>(
+        &self,
+        range_checker: &VariableRangeCheckerChip,
+        ops: Vec<(Vec<BigUint>, Vec<bool>)>,
+    ) -> RowMajorMatrix<F> {
+        let expr_width = BaseAir::<F>::width(&self.stage.expr);
+        let width = expr_width + 1;
+        let num_ops = ops.len();
+        let height = num_ops.next_power_of_two();
+        let mut values = F::zero_vec(height * width);
+        for (row, (inputs, flags)) in values.chunks_mut(width).zip(ops) {
+            self.stage
+                .expr
+                .generate_subrow((range_checker, inputs, flags), &mut row[..expr_width]);
+        }
+        // Padding rows copy the last operation with `is_valid = 0`, since all-zero rows don't
+        // satisfy every expression.
+        if num_ops > 0 {
+            let last_row = values[(num_ops - 1) * width..num_ops * width].to_vec();
+            for row in values.chunks_mut(width).skip(num_ops) {
+                row.copy_from_slice(&last_row);
+                row[0] = F::ZERO;
+            }
+        }
+        for (op_id, row) in values.chunks_mut(width).enumerate() {
+            row[expr_width] = F::from_canonical_usize(op_id);
+        }
+        RowMajorMatrix::new(values, width)
+    }
+
+    fn flags_key(&self) -> usize {
+        self.num_original_input + self.num_original_variables
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for FieldExprStageAir {}
+impl<F: Field> PartitionedBaseAir<F> for FieldExprStageAir {}
+impl<F: Field> BaseAir<F> for FieldExprStageAir {
+    fn width(&self) -> usize {
+        BaseAir::<F>::width(&self.stage.expr) + 1
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for FieldExprStageAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let expr_width = BaseAir::<AB::F>::width(&self.stage.expr);
+        let (local, op_id) = (&local[..expr_width], local[expr_width]);
+        let next_op_id = next[expr_width];
+
+        // Operations are identified by their row, which is the same in every stage.
+        builder.when_first_row().assert_zero(op_id);
+        builder
+            .when_transition()
+            .assert_eq(next_op_id, op_id + AB::Expr::ONE);
+
+        SubAir::eval(&self.stage.expr, builder, local);
+        let FieldExprCols {
+            is_valid,
+            inputs,
+            vars,
+            flags,
+            ..
+        } = self.stage.expr.load_vars(local);
+        builder.assert_bool(is_valid);
+
+        let message = |key: usize, values: &[AB::Var]| {
+            [op_id.into(), AB::Expr::from_canonical_usize(key)]
+                .into_iter()
+                .chain(values.iter().map(|&x| x.into()))
+                .collect::<Vec<AB::Expr>>()
+        };
+        let original_inputs = inputs.iter().take(self.num_original_input).enumerate();
+        if self.index == 0 {
+            let count = is_valid * AB::F::from_canonical_usize(self.num_stages - 1);
+            if self.num_stages > 1 {
+                for (i, input) in original_inputs {
+                    builder.push_send(self.bus_index, message(i, input), count.clone());
+                }
+                builder.push_send(self.bus_index, message(self.flags_key(), &flags), count);
+            }
+        } else {
+            for (i, input) in original_inputs {
+                builder.push_receive(self.bus_index, message(i, input), is_valid);
+            }
+            builder.push_receive(self.bus_index, message(self.flags_key(), &flags), is_valid);
+            for (input, source) in inputs.iter().zip(&self.stage.inputs) {
+                if let StageInput::Var(var) = *source {
+                    builder.push_receive(
+                        self.bus_index,
+                        message(self.num_original_input + var, input),
+                        is_valid,
+                    );
+                }
+            }
+        }
+        for ((var, &original_var), &num_readers) in
+            vars.iter().zip(&self.stage.vars).zip(&self.num_readers)
+        {
+            if num_readers > 0 {
+                builder.push_send(
+                    self.bus_index,
+                    message(self.num_original_input + original_var, var),
+                    is_valid * AB::F::from_canonical_usize(num_readers),
+                );
+            }
+        }
+    }
+}
@@ -31,6 +31,9 @@ pub enum SymbolicExpr {
     // Select one of the two expressions based on the flag.
     // The two expressions must have the same structure (number of limbs etc), e.g. a+b and a-b.
     Select(usize, Box<SymbolicExpr>, Box<SymbolicExpr>),
+    // 1 if the two expressions are equal mod p, 0 otherwise.
+    // Like division, it is not allowed in "constraints" and can only be used in "computes".
+    IsEqual(Box<SymbolicExpr>, Box<SymbolicExpr>),
 }
 
 impl std::fmt::Display for SymbolicExpr {
@@ -48,6 +51,7 @@ impl std::fmt::Display for SymbolicExpr {
             SymbolicExpr::Select(flag_id, lhs, rhs) => {
                 write!(f, "(if {} then {} else {})", flag_id, lhs, rhs)
             }
+            SymbolicExpr::IsEqual(lhs, rhs) => write!(f, "({} == {})", lhs, rhs),
         }
     }
 }
@@ -208,7 +212,7 @@ impl SymbolicExpr {
                     max(&lhs_max_pos * &rhs_max_neg, &lhs_max_neg * &rhs_max_pos),
                 )
             }
-            SymbolicExpr::Div(_, _) => {
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
                 // Should not have division in expression when calling this.
                 unreachable!()
             }
@@ -265,7 +269,7 @@ impl SymbolicExpr {
                 lhs.constraint_limb_max_abs(limb_bits, num_limbs),
                 rhs.constraint_limb_max_abs(limb_bits, num_limbs),
             ),
-            SymbolicExpr::Div(_, _) => {
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
                 unreachable!("should not have division when calling limb_max_abs")
             }
         }
@@ -301,7 +305,7 @@ impl SymbolicExpr {
             SymbolicExpr::Mul(lhs, rhs) => {
                 lhs.expr_limbs(num_limbs) + rhs.expr_limbs(num_limbs) - 1
             }
-            SymbolicExpr::Div(_, _) => {
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
                 unimplemented!()
             }
             SymbolicExpr::IntAdd(lhs, _) => lhs.expr_limbs(num_limbs),
@@ -379,7 +383,10 @@ impl SymbolicExpr {
                     rhs.evaluate_bigint(inputs, variables, flags)
                 }
             }
-            SymbolicExpr::Div(_, _) => unreachable!(), // Division is not allowed in constraints.
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
+                // Division and equality are not allowed in constraints.
+                unreachable!()
+            }
         }
     }
 
@@ -422,7 +429,10 @@ impl SymbolicExpr {
                     rhs.evaluate_overflow_isize(inputs, variables, constants, flags)
                 }
             }
-            SymbolicExpr::Div(_, _) => unreachable!(), // Division is not allowed in constraints.
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
+                // Division and equality are not allowed in constraints.
+                unreachable!()
+            }
         }
     }
 
@@ -441,34 +451,88 @@ impl SymbolicExpr {
         variables: &[OverflowInt<AB::Expr>],
         constants: &[OverflowInt<AB::Expr>],
         flags: &[AB::Var],
+        use_karatsuba: bool,
     ) -> OverflowInt<AB::Expr> {
         match self {
             SymbolicExpr::IntAdd(lhs, s) => {
-                let left = lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags);
+                let left = lhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
                 left.int_add(*s, Self::isize_to_expr::<AB>)
             }
             SymbolicExpr::IntMul(lhs, s) => {
-                let left = lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags);
+                let left = lhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
                 left.int_mul(*s, Self::isize_to_expr::<AB>)
             }
             SymbolicExpr::Input(i) => inputs[*i].clone(),
             SymbolicExpr::Var(i) => variables[*i].clone(),
             SymbolicExpr::Const(i, _, _) => constants[*i].clone(),
             SymbolicExpr::Add(lhs, rhs) => {
-                lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
-                    + rhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
+                lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags, use_karatsuba)
+                    + rhs.evaluate_overflow_expr::<AB>(
+                        inputs,
+                        variables,
+                        constants,
+                        flags,
+                        use_karatsuba,
+                    )
             }
             SymbolicExpr::Sub(lhs, rhs) => {
-                lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
-                    - rhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
+                lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags, use_karatsuba)
+                    - rhs.evaluate_overflow_expr::<AB>(
+                        inputs,
+                        variables,
+                        constants,
+                        flags,
+                        use_karatsuba,
+                    )
             }
             SymbolicExpr::Mul(lhs, rhs) => {
-                lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
-                    * rhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags)
+                let left = lhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
+                let right = rhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
+                if use_karatsuba {
+                    left.karatsuba_mul(right)
+                } else {
+                    left * right
+                }
             }
             SymbolicExpr::Select(flag_id, lhs, rhs) => {
-                let left = lhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags);
-                let right = rhs.evaluate_overflow_expr::<AB>(inputs, variables, constants, flags);
+                let left = lhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
+                let right = rhs.evaluate_overflow_expr::<AB>(
+                    inputs,
+                    variables,
+                    constants,
+                    flags,
+                    use_karatsuba,
+                );
                 let num_limbs = max(left.num_limbs(), right.num_limbs());
                 let flag = flags[*flag_id];
                 let mut res = vec![];
@@ -492,7 +556,10 @@ impl SymbolicExpr {
                     max(left.max_overflow_bits(), right.max_overflow_bits()),
                 )
             }
-            SymbolicExpr::Div(_, _) => unreachable!(), // Division is not allowed in constraints.
+            SymbolicExpr::Div(_, _) | SymbolicExpr::IsEqual(_, _) => {
+                // Division and equality are not allowed in constraints.
+                unreachable!()
+            }
         }
     }
 
@@ -554,6 +621,11 @@ impl SymbolicExpr {
                     rhs.compute(inputs, variables, flags, prime)
                 }
             }
+            SymbolicExpr::IsEqual(lhs, rhs) => {
+                let left = lhs.compute(inputs, variables, flags, prime);
+                let right = rhs.compute(inputs, variables, flags, prime);
+                BigUint::from(u8::from(left == right))
+            }
         };
         assert!(
             res < prime.clone(),
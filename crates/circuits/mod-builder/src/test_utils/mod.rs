@@ -1,17 +1,18 @@
 use std::{array, cell::RefCell, rc::Rc, sync::Arc};
 
 use num_bigint_dig::BigUint;
-use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
 use openvm_circuit::utils::generate_long_number;
 use openvm_circuit_primitives::{
     bigint::utils::big_uint_to_limbs,
     var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
 };
-use openvm_stark_backend::p3_field::PrimeField64;
+use openvm_stark_backend::{p3_air::BaseAir, p3_field::PrimeField64};
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use proptest::{collection::vec, prelude::*, sample::select};
 use rand::{rngs::StdRng, RngCore};
 
-use crate::{ExprBuilder, ExprBuilderConfig};
+use crate::{utils::limbs_to_biguint, ExprBuilder, ExprBuilderConfig, FieldExpr, FieldExprCols};
 
 mod bls12381;
 mod bn254;
@@ -80,3 +81,39 @@ pub fn generate_field_element<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     let vec = big_uint_to_limbs(&bigint, LIMB_BITS);
     array::from_fn(|i| if i < vec.len() { vec[i] as u32 } else { 0 })
 }
+
+/// The column indices of `expr`, relative to its first column (`is_valid`). Useful to tamper with
+/// the carries, quotients or variables of a chip's trace in negative tests.
+pub fn field_expr_col_indices(expr: &FieldExpr) -> FieldExprCols<usize> {
+    let width = BaseAir::<BabyBear>::width(expr);
+    expr.load_vars(&(0..width).collect::<Vec<_>>())
+}
+
+/// The edge values 0, 1, p - 1, p and 2^(num_limbs * limb_bits) - 1. The last two aren't reduced,
+/// the chips must accept any input that fits in the limbs.
+pub fn edge_field_elements(modulus: &BigUint, num_limbs: usize, limb_bits: usize) -> Vec<BigUint> {
+    let max = (BigUint::one() << (num_limbs * limb_bits)) - BigUint::one();
+    vec![
+        BigUint::zero(),
+        BigUint::one(),
+        modulus - BigUint::one(),
+        modulus.clone(),
+        max,
+    ]
+}
+
+/// A proptest strategy for the inputs of field expression chips: an edge value from
+/// [edge_field_elements] half of the time, a random reduced element otherwise.
+pub fn field_element_strategy(
+    modulus: BigUint,
+    num_limbs: usize,
+    limb_bits: usize,
+) -> BoxedStrategy<BigUint> {
+    let edges = edge_field_elements(&modulus, num_limbs, limb_bits);
+    prop_oneof![
+        select(edges),
+        vec(0..(1u32 << limb_bits), num_limbs)
+            .prop_map(move |limbs| limbs_to_biguint(&limbs, limb_bits) % &modulus),
+    ]
+    .boxed()
+}
@@ -1,16 +1,24 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 use num_bigint_dig::BigUint;
-use openvm_circuit_primitives::{bigint::utils::*, TraceSubRowGenerator};
+use openvm_circuit_primitives::{
+    bigint::{utils::*, OverflowInt},
+    var_range::VariableRangeCheckerChip,
+    TraceSubRowGenerator,
+};
 use openvm_stark_backend::{
-    p3_air::BaseAir, p3_field::AbstractField, p3_matrix::dense::RowMajorMatrix,
+    p3_air::BaseAir, p3_field::AbstractField, p3_matrix::dense::RowMajorMatrix, rap::AnyRap,
+    utils::disable_debug_builder, verifier::VerificationError,
 };
 use openvm_stark_sdk::{
     any_rap_arc_vec, config::baby_bear_blake3::BabyBearBlake3Engine, engine::StarkFriEngine,
     p3_baby_bear::BabyBear,
 };
 
-use crate::{test_utils::*, ExprBuilder, FieldExpr, FieldExprCols, FieldVariable, SymbolicExpr};
+use crate::{
+    test_utils::*, ExprBuilder, FieldExpr, FieldExprCols, FieldVariable, StageInput,
+    StagedFieldExpr, SymbolicExpr,
+};
 
 const LIMB_BITS: usize = 8;
 
@@ -85,6 +93,49 @@ fn test_div() {
     .expect("Verification failed");
 }
 
+#[test]
+fn test_karatsuba() {
+    let prime = secp256k1_coord_prime();
+    let x = generate_random_biguint(&prime);
+    let y = generate_random_biguint(&prime);
+    let x_overflow = OverflowInt::<isize>::from_biguint(&x, LIMB_BITS, Some(32));
+    let y_overflow = OverflowInt::<isize>::from_biguint(&y, LIMB_BITS, Some(32));
+    assert_eq!(
+        x_overflow.clone().karatsuba_mul(y_overflow.clone()).limbs(),
+        (x_overflow * y_overflow).limbs()
+    );
+
+    // x3 = x1 * x2 + x1 on 32 limbs.
+    let (range_checker, builder) = setup(&prime);
+    let x1 = ExprBuilder::new_input(builder.clone());
+    let x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = x1.clone() * x2 + x1;
+    x3.save();
+    let mut builder = builder.borrow().clone();
+    builder.use_karatsuba = true;
+    let expr = FieldExpr::new(builder, range_checker.bus(), false);
+    let width = BaseAir::<BabyBear>::width(&expr);
+
+    let expected = (&x * &y + &x) % &prime;
+    let inputs = vec![x, y];
+
+    let mut row = BabyBear::zero_vec(width);
+    expr.generate_subrow((&range_checker, inputs, vec![]), &mut row);
+    let FieldExprCols { vars, .. } = expr.load_vars(&row);
+    assert_eq!(vars.len(), 1);
+    let generated = evaluate_biguint(&vars[0], LIMB_BITS);
+    assert_eq!(generated, expected);
+
+    let trace = RowMajorMatrix::new(row, width);
+    let range_trace = range_checker.generate_trace();
+
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+        any_rap_arc_vec![expr, range_checker.air],
+        vec![trace, range_trace],
+    )
+    .expect("Verification failed");
+}
+
 #[test]
 fn test_auto_carry_mul() {
     let prime = secp256k1_coord_prime();
@@ -342,6 +393,89 @@ fn test_select2() {
     .expect("Verification failed");
 }
 
+#[test]
+fn test_setup_modulus() {
+    let prime = secp256k1_coord_prime();
+    let (range_checker, builder) = setup(&prime);
+    let builder = make_addsub_chip(builder);
+    let expr = FieldExpr::new(builder, range_checker.bus(), true);
+    let width = BaseAir::<BabyBear>::width(&expr);
+
+    // A setup row is a valid row with no flags set, so its first input must be the modulus.
+    // Proves a single setup row whose first input is `x`.
+    let prove_setup = |x: BigUint| {
+        let (range_checker, _) = setup(&prime);
+        let y = generate_random_biguint(&prime);
+        let mut row = BabyBear::zero_vec(width);
+        expr.generate_subrow((&range_checker, vec![x, y], vec![false, false]), &mut row);
+        let trace = RowMajorMatrix::new(row, width);
+        let range_trace = range_checker.generate_trace();
+        BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+            any_rap_arc_vec![expr.clone(), range_checker.air],
+            vec![trace, range_trace],
+        )
+    };
+
+    prove_setup(prime.clone()).expect("Verification failed");
+
+    disable_debug_builder();
+    assert_eq!(
+        prove_setup(&prime - 1u32).err(),
+        Some(VerificationError::OodEvaluationMismatch),
+        "Expected the setup modulus constraint to fail"
+    );
+}
+
+#[test]
+fn test_is_equal() {
+    let prime = secp256k1_coord_prime();
+    let x = generate_random_biguint(&prime);
+    let y = generate_random_biguint(&prime);
+    for (x, y) in [(x.clone(), x.clone()), (x, y)] {
+        let (range_checker, builder) = setup(&prime);
+
+        // x3 = if x1 == x2 { x1 + x2 } else { x1 - x2 }
+        let mut x1 = ExprBuilder::new_input(builder.clone());
+        let mut x2 = ExprBuilder::new_input(builder.clone());
+        let mut eq = FieldVariable::is_equal(&mut x1, &mut x2);
+        let mut sum = &mut x1 + &mut x2;
+        let mut diff = &mut x1 - &mut x2;
+        let mut x3 = FieldVariable::select_var(&mut eq, &mut sum, &mut diff);
+        x3.save_output();
+        let builder = builder.borrow().clone();
+
+        let expr = FieldExpr::new(builder, range_checker.bus(), false);
+        let width = BaseAir::<BabyBear>::width(&expr);
+
+        let is_equal = x == y;
+        let expected = if is_equal {
+            (&x + &y) % &prime
+        } else {
+            (&x + &prime - &y) % &prime
+        };
+        let inputs = vec![x, y];
+
+        let mut row = BabyBear::zero_vec(width);
+        expr.generate_subrow((&range_checker, inputs, vec![]), &mut row);
+        let FieldExprCols { vars, .. } = expr.load_vars(&row);
+        assert_eq!(vars.len(), 3);
+        assert_eq!(
+            evaluate_biguint(&vars[0], LIMB_BITS),
+            BigUint::from(u8::from(is_equal))
+        );
+        assert_eq!(evaluate_biguint(&vars[2], LIMB_BITS), expected);
+
+        let trace = RowMajorMatrix::new(row, width);
+        let range_trace = range_checker.generate_trace();
+
+        BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+            any_rap_arc_vec![expr, range_checker.air],
+            vec![trace, range_trace],
+        )
+        .expect("Verification failed");
+    }
+}
+
 fn test_symbolic_limbs(expr: SymbolicExpr, expected_q: usize, expected_carry: usize) {
     let prime = secp256k1_coord_prime();
     let (q, carry) = expr.constraint_limbs(&prime, LIMB_BITS, 32);
@@ -349,6 +483,168 @@ fn test_symbolic_limbs(expr: SymbolicExpr, expected_q: usize, expected_carry: us
     assert_eq!(carry, expected_carry);
 }
 
+#[test]
+fn test_partition() {
+    let prime = secp256k1_coord_prime();
+    let (range_checker, builder) = setup(&prime);
+
+    // x5 = (x1 * x2 + x1) * x2 / x1, with every step saved
+    let mut x1 = ExprBuilder::new_input(builder.clone());
+    let mut x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = &mut x1 * &mut x2;
+    x3.save();
+    let mut x4 = &mut x3 + &mut x1;
+    x4.save();
+    let mut x5 = &mut x4 * &mut x2;
+    x5.save();
+    let mut x6 = &mut x5 / &mut x1;
+    x6.save_output();
+    let builder = builder.borrow().clone();
+    let expr = FieldExpr::new(builder, range_checker.bus(), false);
+    let width = BaseAir::<BabyBear>::width(&expr);
+
+    let max_width = width * 3 / 4;
+    let stages = expr.partition(max_width);
+    assert!(stages.len() > 1);
+    for stage in &stages {
+        assert!(BaseAir::<BabyBear>::width(&stage.expr) <= max_width);
+    }
+    assert_eq!(
+        stages
+            .iter()
+            .flat_map(|stage| stage.vars.clone())
+            .collect::<Vec<_>>(),
+        (0..expr.num_variables).collect::<Vec<_>>()
+    );
+
+    let x = generate_random_biguint(&prime);
+    let y = generate_random_biguint(&prime);
+    let inputs = vec![x, y];
+    let vars = expr.execute(inputs.clone(), vec![]);
+
+    let mut airs: Vec<Arc<dyn AnyRap<_>>> = vec![];
+    let mut traces = vec![];
+    for stage in &stages {
+        let stage_inputs = stage.stage_inputs(&inputs, &vars);
+        let stage_vars = stage.expr.execute(stage_inputs.clone(), vec![]);
+        for (&var, stage_var) in stage.vars.iter().zip(&stage_vars) {
+            assert_eq!(&vars[var], stage_var);
+        }
+
+        let width = BaseAir::<BabyBear>::width(&stage.expr);
+        let mut row = BabyBear::zero_vec(width);
+        stage
+            .expr
+            .generate_subrow((&range_checker, stage_inputs, vec![]), &mut row);
+        airs.push(Arc::new(stage.expr.clone()) as Arc<dyn AnyRap<_>>);
+        traces.push(RowMajorMatrix::new(row, width));
+    }
+    assert_eq!(
+        stages
+            .last()
+            .unwrap()
+            .expr
+            .execute_with_output(stages.last().unwrap().stage_inputs(&inputs, &vars), vec![]),
+        expr.execute_with_output(inputs, vec![])
+    );
+
+    airs.push(Arc::new(range_checker.air));
+    traces.push(range_checker.generate_trace());
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(airs, traces).expect("Verification failed");
+}
+
+const STAGE_BUS: usize = 2;
+
+/// The expression of [test_partition], split into stages linked by [STAGE_BUS].
+fn setup_staged(prime: &BigUint) -> (Arc<VariableRangeCheckerChip>, StagedFieldExpr) {
+    let (range_checker, builder) = setup(prime);
+    let mut x1 = ExprBuilder::new_input(builder.clone());
+    let mut x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = &mut x1 * &mut x2;
+    x3.save();
+    let mut x4 = &mut x3 + &mut x1;
+    x4.save();
+    let mut x5 = &mut x4 * &mut x2;
+    x5.save();
+    let mut x6 = &mut x5 / &mut x1;
+    x6.save_output();
+    let builder = builder.borrow().clone();
+    let expr = FieldExpr::new(builder, range_checker.bus(), false);
+    let max_width = BaseAir::<BabyBear>::width(&expr) * 3 / 4;
+    let staged = StagedFieldExpr::new(expr, max_width, STAGE_BUS);
+    assert!(staged.airs.len() > 1);
+    (range_checker, staged)
+}
+
+fn run_staged_test(
+    range_checker: &VariableRangeCheckerChip,
+    staged: &StagedFieldExpr,
+    mut traces: Vec<RowMajorMatrix<BabyBear>>,
+) -> Result<(), VerificationError> {
+    let mut airs: Vec<Arc<dyn AnyRap<_>>> = staged
+        .airs
+        .iter()
+        .map(|air| Arc::new(air.clone()) as Arc<dyn AnyRap<_>>)
+        .collect();
+    airs.push(Arc::new(range_checker.air));
+    traces.push(range_checker.generate_trace());
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(airs, traces).map(|_| ())
+}
+
+#[test]
+fn test_staged() {
+    let prime = secp256k1_coord_prime();
+    let (range_checker, staged) = setup_staged(&prime);
+    let ops: Vec<_> = (0..3u32)
+        .map(|i| {
+            let inputs = vec![BigUint::from(i + 2), generate_random_biguint(&prime)];
+            (inputs, vec![])
+        })
+        .collect();
+    let traces = staged.generate_traces(&range_checker, &ops);
+    run_staged_test(&range_checker, &staged, traces).expect("Verification failed");
+}
+
+/// A stage reads a wrong value of a variable owned by an earlier stage. The stage is otherwise
+/// consistent, so only the bus between the stages catches it.
+#[test]
+fn test_staged_wrong_carried_var_negative() {
+    let prime = secp256k1_coord_prime();
+    let (range_checker, staged) = setup_staged(&prime);
+    let inputs = vec![BigUint::from(2u32), generate_random_biguint(&prime)];
+    let vars = staged.expr.execute(inputs.clone(), vec![]);
+    let (reader, carried) = staged
+        .airs
+        .iter()
+        .enumerate()
+        .find_map(|(i, air)| {
+            air.stage
+                .inputs
+                .iter()
+                .position(|input| matches!(input, StageInput::Var(_)))
+                .map(|position| (i, position))
+        })
+        .expect("a later stage should read a variable of an earlier stage");
+
+    let traces = staged
+        .airs
+        .iter()
+        .enumerate()
+        .map(|(i, air)| {
+            let mut stage_inputs = air.stage.stage_inputs(&inputs, &vars);
+            if i == reader {
+                stage_inputs[carried] = (&stage_inputs[carried] + 1u32) % &prime;
+            }
+            air.generate_trace(&range_checker, vec![(stage_inputs, vec![])])
+        })
+        .collect();
+    disable_debug_builder();
+    assert_eq!(
+        run_staged_test(&range_checker, &staged, traces).err(),
+        Some(VerificationError::ChallengePhaseError)
+    );
+}
+
 #[test]
 fn test_symbolic_limbs_add() {
     let expr = SymbolicExpr::Add(
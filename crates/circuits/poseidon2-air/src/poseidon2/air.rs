@@ -11,7 +11,7 @@ use openvm_stark_sdk::p3_baby_bear::BabyBear;
 use zkhash::{
     ark_ff::PrimeField as _,
     fields::babybear::FpBabyBear as HorizenBabyBear,
-    poseidon2::poseidon2_instance_babybear::{MAT_DIAG16_M_1, RC16},
+    poseidon2::poseidon2_instance_babybear::{MAT_DIAG16_M_1, MAT_DIAG24_M_1, RC16, RC24},
 };
 
 use super::{
@@ -28,6 +28,10 @@ pub const SBOX_DEGREE: usize = 7;
 /// This AIR only supports:
 /// - sbox of degree 7
 /// - WIDTH is multiple of 4 and >= 8
+/// - an even, nonzero number of external rounds and at least one internal round
+///
+/// The round constants and internal diagonal are supplied by the caller, so any width and round
+/// split meeting these requirements can be used, e.g. [Poseidon2Config::new_p3_baby_bear_24].
 ///
 /// Spec is at https://hackmd.io/_I1lx-6GROWbKbDi_Vz-pw?view .
 #[derive(Clone, Debug)]
@@ -60,6 +64,18 @@ impl<const WIDTH: usize, F: AbstractField> Poseidon2Air<WIDTH, F> {
         assert!(
             max_constraint_degree == 3 || max_constraint_degree == 5 || max_constraint_degree == 7
         );
+        assert!(
+            WIDTH >= 8 && WIDTH % 4 == 0,
+            "WIDTH must be a multiple of 4 and at least 8"
+        );
+        assert!(
+            !external_constants.is_empty() && external_constants.len() % 2 == 0,
+            "number of external rounds must be even and nonzero"
+        );
+        assert!(
+            !internal_constants.is_empty(),
+            "there must be at least one internal round"
+        );
 
         Self {
             rounds_f: external_constants.len(),
@@ -137,7 +153,23 @@ impl<const WIDTH: usize, F: AbstractField> Poseidon2Air<WIDTH, F> {
 
     pub(crate) fn horizen_round_consts_16() -> (Vec<[BabyBear; 16]>, Vec<BabyBear>, [BabyBear; 16])
     {
-        let p3_rc16: Vec<Vec<BabyBear>> = RC16
+        Self::horizen_round_consts::<16>(&RC16, &MAT_DIAG16_M_1, 8, 13)
+    }
+
+    pub(crate) fn horizen_round_consts_24() -> (Vec<[BabyBear; 24]>, Vec<BabyBear>, [BabyBear; 24])
+    {
+        Self::horizen_round_consts::<24>(&RC24, &MAT_DIAG24_M_1, 8, 21)
+    }
+
+    /// Splits HorizenLabs round constants, which have a full row of constants for every round,
+    /// into the external and internal round constants used by this AIR.
+    fn horizen_round_consts<const W: usize>(
+        rc: &[Vec<HorizenBabyBear>],
+        mat_diag_m_1: &[HorizenBabyBear],
+        rounds_f: usize,
+        rounds_p: usize,
+    ) -> (Vec<[BabyBear; W]>, Vec<BabyBear>, [BabyBear; W]) {
+        let p3_rc: Vec<Vec<BabyBear>> = rc
             .iter()
             .map(|round| {
                 round
@@ -147,23 +179,21 @@ impl<const WIDTH: usize, F: AbstractField> Poseidon2Air<WIDTH, F> {
             })
             .collect();
 
-        let rounds_f = 8;
-        let rounds_p = 13;
         let rounds_f_beginning = rounds_f / 2;
         let p_end = rounds_f_beginning + rounds_p;
-        let external_round_constants: Vec<[BabyBear; 16]> = p3_rc16[..rounds_f_beginning]
+        let external_round_constants: Vec<[BabyBear; W]> = p3_rc[..rounds_f_beginning]
             .iter()
-            .chain(p3_rc16[p_end..].iter())
+            .chain(p3_rc[p_end..].iter())
             .cloned()
             .map(|round| round.try_into().unwrap())
             .collect();
-        let internal_round_constants: Vec<BabyBear> = p3_rc16[rounds_f_beginning..p_end]
+        let internal_round_constants: Vec<BabyBear> = p3_rc[rounds_f_beginning..p_end]
             .iter()
             .map(|round| round[0])
             .collect();
-        let horizen_int_diag: [BabyBear; 16] = {
-            let mut array = [BabyBear::ZERO; 16];
-            for (i, elem) in MAT_DIAG16_M_1.iter().enumerate() {
+        let horizen_int_diag: [BabyBear; W] = {
+            let mut array = [BabyBear::ZERO; W];
+            for (i, elem) in mat_diag_m_1.iter().enumerate() {
                 array[i] = BabyBear::from_canonical_u32(elem.into_bigint().0[0] as u32);
             }
             array
@@ -304,7 +334,7 @@ impl<const WIDTH: usize, F: Field> Poseidon2Air<WIDTH, F> {
     }
 
     /// Returns value^SBOX_DEGREE
-    fn sbox_p_air<AB: AirBuilder<F = F>>(
+    pub(crate) fn sbox_p_air<AB: AirBuilder<F = F>>(
         &self,
         builder: &mut AB,
         value: AB::Expr,
@@ -0,0 +1,228 @@
+use std::borrow::Borrow;
+
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::columns::Poseidon2FoldedCols;
+use crate::poseidon2::{Poseidon2Air, Poseidon2Config};
+
+/// Air for Poseidon2 with the permutation folded over consecutive rows: each row computes one
+/// round, so a permutation takes `rounds_f + rounds_p` rows. The round constants, linear layers
+/// and sbox are those of the wrapped [Poseidon2Air], and the supported parameters are the same.
+///
+/// Compared to [Poseidon2Air], which stores the state after every round in a single row, the
+/// trace is roughly `rounds_f + rounds_p` times taller and as many times narrower.
+///
+/// The input and output of exported permutations are received on the bus of the wrapped air,
+/// in the same format as [Poseidon2Air]. Padding permutations are not exported.
+#[derive(Clone, Debug)]
+pub struct Poseidon2FoldedAir<const WIDTH: usize, F> {
+    pub inner: Poseidon2Air<WIDTH, F>,
+}
+
+impl<const WIDTH: usize, F: AbstractField> Poseidon2FoldedAir<WIDTH, F> {
+    pub fn new(inner: Poseidon2Air<WIDTH, F>) -> Self {
+        Self { inner }
+    }
+
+    pub fn from_config(
+        config: Poseidon2Config<WIDTH, F>,
+        max_constraint_degree: usize,
+        bus_index: usize,
+    ) -> Self {
+        Self::new(Poseidon2Air::from_config(
+            config,
+            max_constraint_degree,
+            bus_index,
+        ))
+    }
+}
+
+impl<const WIDTH: usize, F> Poseidon2FoldedAir<WIDTH, F> {
+    /// Number of rows of one permutation.
+    pub fn num_rounds(&self) -> usize {
+        self.inner.rounds_f + self.inner.rounds_p
+    }
+
+    pub fn get_width(&self) -> usize
+    where
+        F: Clone,
+    {
+        Poseidon2FoldedCols::<WIDTH, F>::width(self)
+    }
+
+    /// Whether `round` is an internal round, which only applies the sbox to the first element.
+    pub(super) fn is_internal_round(&self, round: usize) -> bool {
+        let rounds_f_beginning = self.inner.rounds_f / 2;
+        (rounds_f_beginning..rounds_f_beginning + self.inner.rounds_p).contains(&round)
+    }
+
+    /// Whether `round` starts with the internal linear layer, which is the case iff the previous
+    /// round was internal. All other rounds start with the external linear layer.
+    pub(super) fn uses_int_lin_layer(&self, round: usize) -> bool {
+        round > 0 && self.is_internal_round(round - 1)
+    }
+
+    /// The constants added to the state in `round`, after the linear layer.
+    pub(super) fn round_constants(&self, round: usize) -> [F; WIDTH]
+    where
+        F: AbstractField,
+    {
+        let rounds_f_beginning = self.inner.rounds_f / 2;
+        if self.is_internal_round(round) {
+            core::array::from_fn(|i| {
+                if i == 0 {
+                    self.inner.internal_constants[round - rounds_f_beginning].clone()
+                } else {
+                    F::ZERO
+                }
+            })
+        } else if round < rounds_f_beginning {
+            self.inner.external_constants[round].clone()
+        } else {
+            self.inner.external_constants[round - self.inner.rounds_p].clone()
+        }
+    }
+}
+
+impl<const WIDTH: usize, F: Field> BaseAirWithPublicValues<F> for Poseidon2FoldedAir<WIDTH, F> {}
+impl<const WIDTH: usize, F: Field> PartitionedBaseAir<F> for Poseidon2FoldedAir<WIDTH, F> {}
+impl<const WIDTH: usize, F: Field> BaseAir<F> for Poseidon2FoldedAir<WIDTH, F> {
+    fn width(&self) -> usize {
+        self.get_width()
+    }
+}
+
+impl<AB: InteractionBuilder, const WIDTH: usize> Air<AB> for Poseidon2FoldedAir<WIDTH, AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &[AB::Var] = (*local).borrow();
+        let next: &[AB::Var] = (*next).borrow();
+
+        let local = Poseidon2FoldedCols::from_slice(local, self);
+        let next = Poseidon2FoldedCols::from_slice(next, self);
+
+        self.eval_interactions(builder, &local);
+        self.eval_without_interactions(builder, &local, &next);
+    }
+}
+
+impl<const WIDTH: usize, F: Field> Poseidon2FoldedAir<WIDTH, F> {
+    // Receives input and output columns of the last round in one interaction
+    pub fn eval_interactions<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2FoldedCols<WIDTH, AB::Var>,
+    ) {
+        // The last round is external, so its output is the sbox output.
+        let mut output: [AB::Expr; WIDTH] = local.sbox_output.map(Into::into);
+        self.inner.ext_lin_layer(&mut output);
+        let fields = local
+            .input
+            .map(Into::<AB::Expr>::into)
+            .into_iter()
+            .chain(output);
+        builder.push_receive(self.inner.bus_index, fields, local.export);
+    }
+
+    pub fn eval_without_interactions<AB: AirBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2FoldedCols<WIDTH, AB::Var>,
+        next: &Poseidon2FoldedCols<WIDTH, AB::Var>,
+    ) {
+        let num_rounds = self.num_rounds();
+        let flag_sum = |predicate: &dyn Fn(usize) -> bool| -> AB::Expr {
+            (0..num_rounds)
+                .filter(|&round| predicate(round))
+                .map(|round| local.round_flags[round].into())
+                .sum()
+        };
+
+        // The round flags are one-hot, start at the first round and advance by one round per row.
+        for &flag in &local.round_flags {
+            builder.assert_bool(flag);
+        }
+        builder.assert_one(flag_sum(&|_| true));
+        builder.when_first_row().assert_one(local.round_flags[0]);
+        for round in 0..num_rounds {
+            builder.when_transition().assert_eq(
+                next.round_flags[(round + 1) % num_rounds],
+                local.round_flags[round],
+            );
+        }
+
+        let is_first_round = local.round_flags[0];
+        let is_last_round = local.round_flags[num_rounds - 1];
+        let not_last_round = AB::Expr::ONE - is_last_round.into();
+
+        builder.assert_bool(local.export);
+        builder
+            .when(not_last_round.clone())
+            .assert_zero(local.export);
+
+        // The input is kept for the whole permutation and is the state of its first round.
+        for i in 0..WIDTH {
+            builder
+                .when_transition()
+                .when(not_last_round.clone())
+                .assert_eq(next.input[i], local.input[i]);
+            builder
+                .when(is_first_round)
+                .assert_eq(local.state[i], local.input[i]);
+        }
+
+        // Linear layer and round constants.
+        let is_int_lin = flag_sum(&|round| self.uses_int_lin_layer(round));
+        let mut ext_state: [AB::Expr; WIDTH] = local.state.map(Into::into);
+        let mut int_state = ext_state.clone();
+        self.inner.ext_lin_layer(&mut ext_state);
+        self.inner.int_lin_layer(&mut int_state);
+        let round_constants: Vec<[F; WIDTH]> = (0..num_rounds)
+            .map(|round| self.round_constants(round))
+            .collect();
+        for i in 0..WIDTH {
+            let constant: AB::Expr = (0..num_rounds)
+                .map(|round| local.round_flags[round] * round_constants[round][i])
+                .sum();
+            builder.assert_eq(
+                local.sbox_input[i],
+                ext_state[i].clone()
+                    + is_int_lin.clone() * (int_state[i].clone() - ext_state[i].clone())
+                    + constant,
+            );
+        }
+
+        // Sbox of every element.
+        for i in 0..WIDTH {
+            let sbox_output = self.inner.sbox_p_air(
+                builder,
+                local.sbox_input[i].into(),
+                local.intermediate_sbox_powers[i].map(Into::into),
+            );
+            builder.assert_eq(sbox_output, local.sbox_output[i]);
+        }
+
+        // The round output is the state of the next round. Internal rounds only apply the sbox
+        // to the first element.
+        let is_internal = flag_sum(&|round| self.is_internal_round(round));
+        for i in 0..WIDTH {
+            let round_output = if i == 0 {
+                local.sbox_output[0].into()
+            } else {
+                local.sbox_output[i].into()
+                    + is_internal.clone() * (local.sbox_input[i] - local.sbox_output[i])
+            };
+            builder
+                .when_transition()
+                .when(not_last_round.clone())
+                .assert_eq(next.state[i], round_output);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use openvm_stark_backend::p3_field::Field;
+
+use super::Poseidon2FoldedAir;
+use crate::poseidon2::air::SBOX_DEGREE;
+
+/// Columns of one row of [Poseidon2FoldedAir]. Each row computes one round of a permutation.
+#[derive(Clone, Debug)]
+pub struct Poseidon2FoldedCols<const WIDTH: usize, T> {
+    // One-hot flags of the round computed by this row, one flag for each of the
+    // `rounds_f + rounds_p` rounds.
+    pub round_flags: Vec<T>,
+    // 1 on the last row of a permutation which is received on the bus, 0 elsewhere.
+    pub export: T,
+    // The input of the permutation, repeated on every row of it.
+    pub input: [T; WIDTH],
+    // The state before the linear layer of this round.
+    pub state: [T; WIDTH],
+    // The state after the linear layer and the round constants, i.e. the input of the sbox.
+    pub sbox_input: [T; WIDTH],
+    // Those are helper columns to store intermediate powers to reduce the degree
+    // for the SBOX constraints. When max_constraint_degree (in the AIR) is less than SBOX_DEGREE,
+    // those columns are set to the value^max_constraint_degree. Otherwise, they are set to None.
+    pub intermediate_sbox_powers: [Option<T>; WIDTH],
+    // The sbox applied to every element of sbox_input. Internal rounds only keep the first one.
+    pub sbox_output: [T; WIDTH],
+}
+
+/// Returns true iff we need to store intermediate powers for the SBOX constraints
+fn need_intermediate_sbox_powers<const WIDTH: usize, T>(
+    p2_air: &Poseidon2FoldedAir<WIDTH, T>,
+) -> bool {
+    p2_air.inner.max_constraint_degree < SBOX_DEGREE
+}
+
+impl<const WIDTH: usize, T: Clone> Poseidon2FoldedCols<WIDTH, T> {
+    pub fn width<F>(p2_air: &Poseidon2FoldedAir<WIDTH, F>) -> usize {
+        let sbox_powers_width = if need_intermediate_sbox_powers(p2_air) {
+            WIDTH
+        } else {
+            0
+        };
+        p2_air.num_rounds() + 1 + 4 * WIDTH + sbox_powers_width
+    }
+
+    pub fn from_slice<F>(slice: &[T], p2_air: &Poseidon2FoldedAir<WIDTH, F>) -> Self {
+        assert!(slice.len() == Self::width(p2_air));
+
+        let num_rounds = p2_air.num_rounds();
+        let (round_flags, slice) = slice.split_at(num_rounds);
+        let export = slice[0].clone();
+        let slice = &slice[1..];
+        let input = core::array::from_fn(|i| slice[i].clone());
+        let state = core::array::from_fn(|i| slice[WIDTH + i].clone());
+        let sbox_input = core::array::from_fn(|i| slice[2 * WIDTH + i].clone());
+        let slice = &slice[3 * WIDTH..];
+        let (intermediate_sbox_powers, slice) = if need_intermediate_sbox_powers(p2_air) {
+            (
+                core::array::from_fn(|i| Some(slice[i].clone())),
+                &slice[WIDTH..],
+            )
+        } else {
+            (core::array::from_fn(|_| None), slice)
+        };
+        let sbox_output = core::array::from_fn(|i| slice[i].clone());
+
+        Self {
+            round_flags: round_flags.to_vec(),
+            export,
+            input,
+            state,
+            sbox_input,
+            intermediate_sbox_powers,
+            sbox_output,
+        }
+    }
+}
+
+impl<const WIDTH: usize, T> Poseidon2FoldedCols<WIDTH, T> {
+    pub fn flatten(self) -> Vec<T> {
+        self.round_flags
+            .into_iter()
+            .chain([self.export])
+            .chain(self.input)
+            .chain(self.state)
+            .chain(self.sbox_input)
+            .chain(self.intermediate_sbox_powers.into_iter().flatten())
+            .chain(self.sbox_output)
+            .collect()
+    }
+}
+
+impl<const WIDTH: usize, F: Field> Poseidon2FoldedCols<WIDTH, F> {
+    pub fn blank_rows(p2_air: &Poseidon2FoldedAir<WIDTH, F>) -> Vec<Self> {
+        p2_air.generate_permutation_rows([F::ZERO; WIDTH], false)
+    }
+}
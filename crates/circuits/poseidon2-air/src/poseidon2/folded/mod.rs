@@ -0,0 +1,7 @@
+//! Multi-row layout of the Poseidon2 permutation, with one round per row.
+
+pub mod air;
+pub mod columns;
+pub mod trace;
+
+pub use self::{air::Poseidon2FoldedAir, columns::Poseidon2FoldedCols};
@@ -0,0 +1,72 @@
+use openvm_stark_backend::{p3_field::Field, p3_matrix::dense::RowMajorMatrix};
+
+use super::{columns::Poseidon2FoldedCols, Poseidon2FoldedAir};
+
+impl<const WIDTH: usize, F: Field> Poseidon2FoldedAir<WIDTH, F> {
+    /// Generate the trace of the permutations of `input_states`. The trace is padded to a power
+    /// of two height with permutations of the zero state, which are not exported; the last of
+    /// them may be cut short.
+    pub fn generate_trace(&self, input_states: Vec<[F; WIDTH]>) -> RowMajorMatrix<F> {
+        let height = (input_states.len() * self.num_rounds()).next_power_of_two();
+        let mut rows: Vec<Poseidon2FoldedCols<WIDTH, F>> = input_states
+            .into_iter()
+            .flat_map(|input_state| self.generate_permutation_rows(input_state, true))
+            .collect();
+        while rows.len() < height {
+            rows.extend(Poseidon2FoldedCols::blank_rows(self));
+        }
+        rows.truncate(height);
+
+        RowMajorMatrix::new(
+            rows.into_iter()
+                .flat_map(Poseidon2FoldedCols::flatten)
+                .collect(),
+            self.get_width(),
+        )
+    }
+
+    /// Generate the rows of one permutation of `input_state`, one row per round.
+    pub fn generate_permutation_rows(
+        &self,
+        input_state: [F; WIDTH],
+        export: bool,
+    ) -> Vec<Poseidon2FoldedCols<WIDTH, F>> {
+        let num_rounds = self.num_rounds();
+        let mut state = input_state;
+        (0..num_rounds)
+            .map(|round| {
+                let round_state = state;
+                let mut sbox_input = state;
+                if self.uses_int_lin_layer(round) {
+                    self.inner.int_lin_layer(&mut sbox_input);
+                } else {
+                    self.inner.ext_lin_layer(&mut sbox_input);
+                }
+                for (s, c) in sbox_input.iter_mut().zip(self.round_constants(round)) {
+                    *s += c;
+                }
+
+                let mut intermediate_sbox_powers = core::array::from_fn(|_| None);
+                let sbox_output = core::array::from_fn(|i| {
+                    self.inner
+                        .sbox_p_gen(sbox_input[i], &mut intermediate_sbox_powers[i])
+                });
+
+                state = sbox_output;
+                if self.is_internal_round(round) {
+                    state[1..].copy_from_slice(&sbox_input[1..]);
+                }
+
+                Poseidon2FoldedCols {
+                    round_flags: (0..num_rounds).map(|r| F::from_bool(r == round)).collect(),
+                    export: F::from_bool(export && round == num_rounds - 1),
+                    input: input_state,
+                    state: round_state,
+                    sbox_input,
+                    intermediate_sbox_powers,
+                    sbox_output,
+                }
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,7 @@
 pub mod air;
 pub mod bridge;
 pub mod columns;
+pub mod folded;
 pub mod trace;
 
 #[cfg(test)]
@@ -11,7 +12,11 @@ use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 use openvm_stark_sdk::p3_baby_bear::{BabyBear, BabyBearInternalLayerParameters};
 use p3_monty_31::InternalLayerBaseParameters;
 
-pub use self::{air::Poseidon2Air, columns::Poseidon2Cols};
+pub use self::{
+    air::Poseidon2Air,
+    columns::Poseidon2Cols,
+    folded::{Poseidon2FoldedAir, Poseidon2FoldedCols},
+};
 
 #[derive(Clone)]
 pub struct Poseidon2Config<const WIDTH: usize, F: Clone> {
@@ -23,6 +28,23 @@ pub struct Poseidon2Config<const WIDTH: usize, F: Clone> {
 }
 
 impl<const WIDTH: usize, F: Clone> Poseidon2Config<WIDTH, F> {
+    /// Config with externally supplied round constants, in the format of [Poseidon2Air::new].
+    pub fn new(
+        external_constants: Vec<[F; WIDTH]>,
+        internal_constants: Vec<F>,
+        ext_mds_matrix: [[u32; 4]; 4],
+        int_diag_m1_matrix: [F; WIDTH],
+        reduction_factor: F,
+    ) -> Self {
+        Self {
+            external_constants,
+            internal_constants,
+            ext_mds_matrix,
+            int_diag_m1_matrix,
+            reduction_factor,
+        }
+    }
+
     pub fn rounds_f(&self) -> usize {
         self.external_constants.len()
     }
@@ -121,6 +143,47 @@ impl<F: PrimeField32> Poseidon2Config<16, F> {
     }
 }
 
+impl<F: PrimeField32> Poseidon2Config<24, F> {
+    /// Using HorizenLab's round constants: https://github.com/HorizenLabs/poseidon2
+    pub fn new_hl_baby_bear_24() -> Self {
+        Self {
+            external_constants: HL_BABYBEAR_EXT_CONST_24
+                .iter()
+                .map(|round| round.map(convert_baby_bear))
+                .collect(),
+            internal_constants: HL_BABYBEAR_INT_CONST_24
+                .iter()
+                .copied()
+                .map(convert_baby_bear)
+                .collect(),
+            ext_mds_matrix: HL_MDS_MAT_4,
+            int_diag_m1_matrix: HL_BABYBEAR_INT_DIAG_24.map(convert_baby_bear),
+            reduction_factor: F::ONE,
+        }
+    }
+
+    /// Plonky3 does not fix round constants for width 24, so they must be supplied, e.g. those
+    /// of a `Poseidon2BabyBear<24>` permutation: the initial and then the terminal external
+    /// constants, and the internal constants.
+    pub fn new_p3_baby_bear_24(
+        external_constants: Vec<[F; 24]>,
+        internal_constants: Vec<F>,
+    ) -> Self {
+        let p3_int_diag: [BabyBear; 24] = BabyBearInternalLayerParameters::INTERNAL_DIAG_MONTY;
+        Self {
+            external_constants,
+            internal_constants,
+            ext_mds_matrix: MDS_MAT_4,
+            int_diag_m1_matrix: p3_int_diag.map(convert_baby_bear),
+            reduction_factor: F::ONE,
+        }
+    }
+}
+
+fn convert_baby_bear<F: PrimeField32>(babybear: BabyBear) -> F {
+    F::from_canonical_u32(babybear.as_canonical_u32())
+}
+
 impl Default for Poseidon2Config<16, BabyBear> {
     fn default() -> Self {
         Self::new_p3_baby_bear_16()
@@ -134,4 +197,10 @@ lazy_static! {
         Poseidon2Air::<16, BabyBear>::horizen_round_consts_16().1;
     pub static ref HL_BABYBEAR_INT_DIAG_16: [BabyBear; 16] =
         Poseidon2Air::<16, BabyBear>::horizen_round_consts_16().2;
+    pub static ref HL_BABYBEAR_EXT_CONST_24: Vec<[BabyBear; 24]> =
+        Poseidon2Air::<24, BabyBear>::horizen_round_consts_24().0;
+    pub static ref HL_BABYBEAR_INT_CONST_24: Vec<BabyBear> =
+        Poseidon2Air::<24, BabyBear>::horizen_round_consts_24().1;
+    pub static ref HL_BABYBEAR_INT_DIAG_24: [BabyBear; 24] =
+        Poseidon2Air::<24, BabyBear>::horizen_round_consts_24().2;
 }
@@ -1,7 +1,10 @@
+use std::iter::repeat;
+
 use ark_ff::PrimeField as _;
 use openvm_stark_backend::{
     p3_field::{AbstractField, PrimeField32},
-    p3_matrix::dense::RowMajorMatrix,
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_util::log2_ceil_usize,
     utils::disable_debug_builder,
     verifier::VerificationError,
 };
@@ -20,16 +23,17 @@ use p3_monty_31::InternalLayerBaseParameters;
 use p3_poseidon2::{ExternalLayerConstants, Poseidon2};
 use p3_symmetric::Permutation;
 use rand::{Rng, RngCore};
+use test_case::test_case;
 use zkhash::{
     fields::babybear::FpBabyBear as HorizenBabyBear,
     poseidon2::{
         poseidon2::Poseidon2 as HorizenPoseidon2,
-        poseidon2_instance_babybear::POSEIDON2_BABYBEAR_16_PARAMS,
+        poseidon2_instance_babybear::{POSEIDON2_BABYBEAR_16_PARAMS, POSEIDON2_BABYBEAR_24_PARAMS},
     },
 };
 
 use super::{HL_BABYBEAR_EXT_CONST_16, HL_BABYBEAR_INT_CONST_16, HL_MDS_MAT_4, MDS_MAT_4};
-use crate::poseidon2::Poseidon2Air;
+use crate::poseidon2::{Poseidon2Air, Poseidon2Config, Poseidon2FoldedAir};
 
 #[test]
 fn test_poseidon2_default() {
@@ -258,3 +262,211 @@ fn test_horizen_poseidon2() {
         .collect::<Vec<_>>();
     assert_eq!(air_u32_result, horizen_u32_result);
 }
+
+#[test]
+fn test_poseidon2_24() {
+    // config
+    let num_rows = 1 << 4;
+    let num_ext_rounds = 8;
+    let num_int_rounds = 21;
+
+    // random constants, state generation
+    let mut rng = create_seeded_rng();
+    let external_constants = ExternalLayerConstants::new_from_rng(num_ext_rounds, &mut rng);
+    let internal_constants: Vec<BabyBear> = (0..num_int_rounds)
+        .map(|_| BabyBear::from_wrapped_u32(rng.next_u32()))
+        .collect();
+    let states: Vec<[BabyBear; 24]> = (0..num_rows)
+        .map(|_| {
+            let vec: Vec<BabyBear> = (0..24)
+                .map(|_| BabyBear::from_canonical_u32(rng.next_u32() % (1 << 30)))
+                .collect();
+            vec.try_into().unwrap()
+        })
+        .collect();
+
+    // air and trace generation
+    let config = Poseidon2Config::new_p3_baby_bear_24(
+        [
+            &external_constants.get_initial_constants()[..],
+            &external_constants.get_terminal_constants()[..],
+        ]
+        .concat(),
+        internal_constants.clone(),
+    );
+    let poseidon2_air = Poseidon2Air::<24, BabyBear>::from_config(config, 3, 0);
+    let mut poseidon2_trace = poseidon2_air.generate_trace(states.clone());
+    let mut outputs = states.clone();
+    let poseidon2: Poseidon2BabyBear<24> = Poseidon2::new(external_constants, internal_constants);
+    for output in outputs.iter_mut() {
+        poseidon2.permute_mut(output);
+    }
+
+    // dummy interaction air and trace generation
+    let page_requester = DummyInteractionAir::new(2 * 24, true, poseidon2_air.bus_index);
+    let dummy_trace = RowMajorMatrix::new(
+        states
+            .into_iter()
+            .zip(outputs.iter())
+            .flat_map(|(state, output)| {
+                [BabyBear::ONE]
+                    .into_iter()
+                    .chain(state.to_vec())
+                    .chain(output.to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        2 * 24 + 1,
+    );
+
+    let traces = vec![poseidon2_trace.clone(), dummy_trace.clone()];
+
+    // engine generation
+    let perm = random_perm();
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(3);
+    let engine = engine_from_perm(perm, fri_params);
+
+    // positive test
+    engine
+        .run_simple_test_impl(
+            any_rap_arc_vec![poseidon2_air.clone(), page_requester],
+            traces,
+            vec![vec![]; 2],
+        )
+        .expect("Verification failed");
+
+    // negative test
+    disable_debug_builder();
+    for _ in 0..10 {
+        let width = rng.gen_range(0..poseidon2_air.get_width());
+        let height = rng.gen_range(0..num_rows);
+        let rand = BabyBear::from_canonical_u32(rng.gen_range(1..=1 << 27));
+        poseidon2_trace.row_mut(height)[width] += rand;
+        assert_eq!(
+            engine
+                .run_simple_test_impl(
+                    any_rap_arc_vec![poseidon2_air.clone(), page_requester],
+                    vec![poseidon2_trace.clone(), dummy_trace.clone()],
+                    vec![vec![]; 2],
+                )
+                .err(),
+            Some(VerificationError::OodEvaluationMismatch),
+            "Expected constraint to fail"
+        );
+        poseidon2_trace.row_mut(height)[width] -= rand;
+    }
+}
+
+#[test]
+fn test_horizen_poseidon2_24() {
+    let horizen_permut = HorizenPoseidon2::new(&POSEIDON2_BABYBEAR_24_PARAMS);
+    let mut rng = create_seeded_rng();
+    let mut air_permut =
+        Poseidon2Air::<24, BabyBear>::from_config(Poseidon2Config::new_hl_baby_bear_24(), 3, 0);
+    let u32state = (0..24)
+        .map(|_| rng.gen_range(1..=1 << 27))
+        .collect::<Vec<_>>();
+    let horizen_state: Vec<HorizenBabyBear> =
+        u32state.into_iter().map(HorizenBabyBear::from).collect();
+    let p3_state: [BabyBear; 24] = horizen_state
+        .iter()
+        .copied()
+        .map(Poseidon2Air::<24, BabyBear>::horizen_to_p3)
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let air_result: Vec<BabyBear> = air_permut.request_trace(&[p3_state])[0].clone();
+    let horizen_result = horizen_permut.permutation(&horizen_state);
+    let air_u32_result = air_result
+        .iter()
+        .map(BabyBear::as_canonical_u32)
+        .collect::<Vec<_>>();
+    let horizen_u32_result = horizen_result
+        .into_iter()
+        .map(|elem| elem.into_bigint().0[0] as u32)
+        .collect::<Vec<_>>();
+    assert_eq!(air_u32_result, horizen_u32_result);
+}
+
+#[test_case(3)]
+#[test_case(7)]
+fn test_poseidon2_folded(max_constraint_degree: usize) {
+    // config
+    let num_perms = 5;
+
+    // random state generation
+    let mut rng = create_seeded_rng();
+    let states: Vec<[BabyBear; 16]> = (0..num_perms)
+        .map(|_| core::array::from_fn(|_| BabyBear::from_wrapped_u32(rng.next_u32())))
+        .collect();
+
+    // air and trace generation
+    let poseidon2_air = Poseidon2FoldedAir::<16, BabyBear>::from_config(
+        Poseidon2Config::default(),
+        max_constraint_degree,
+        0,
+    );
+    let mut poseidon2_trace = poseidon2_air.generate_trace(states.clone());
+    assert_eq!(
+        poseidon2_trace.height(),
+        (num_perms * poseidon2_air.num_rounds()).next_power_of_two()
+    );
+    let outputs: Vec<[BabyBear; 16]> = states
+        .iter()
+        .map(|&state| poseidon2_air.inner.generate_trace_row(state).io.output)
+        .collect();
+
+    // dummy interaction air and trace generation
+    let page_requester = DummyInteractionAir::new(2 * 16, true, poseidon2_air.inner.bus_index);
+    let dummy_trace = RowMajorMatrix::new(
+        states
+            .into_iter()
+            .zip(outputs)
+            .flat_map(|(state, output)| {
+                [BabyBear::ONE]
+                    .into_iter()
+                    .chain(state)
+                    .chain(output)
+                    .collect::<Vec<_>>()
+            })
+            .chain(repeat(BabyBear::ZERO).take((8 - num_perms) * (2 * 16 + 1)))
+            .collect(),
+        2 * 16 + 1,
+    );
+
+    // engine generation
+    let perm = random_perm();
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(log2_ceil_usize(
+        max_constraint_degree - 1,
+    ));
+    let engine = engine_from_perm(perm, fri_params);
+
+    // positive test
+    engine
+        .run_simple_test_impl(
+            any_rap_arc_vec![poseidon2_air.clone(), page_requester],
+            vec![poseidon2_trace.clone(), dummy_trace.clone()],
+            vec![vec![]; 2],
+        )
+        .expect("Verification failed");
+
+    // negative test
+    disable_debug_builder();
+    for _ in 0..10 {
+        let width = rng.gen_range(0..poseidon2_air.get_width());
+        let height = rng.gen_range(0..poseidon2_trace.height());
+        let rand = BabyBear::from_canonical_u32(rng.gen_range(1..=1 << 27));
+        poseidon2_trace.row_mut(height)[width] += rand;
+        assert!(
+            engine
+                .run_simple_test_impl(
+                    any_rap_arc_vec![poseidon2_air.clone(), page_requester],
+                    vec![poseidon2_trace.clone(), dummy_trace.clone()],
+                    vec![vec![]; 2],
+                )
+                .is_err(),
+            "Expected verification to fail"
+        );
+        poseidon2_trace.row_mut(height)[width] -= rand;
+    }
+}
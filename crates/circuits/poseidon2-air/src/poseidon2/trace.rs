@@ -143,7 +143,11 @@ impl<const WIDTH: usize, F: Field> Poseidon2Air<WIDTH, F> {
         }
     }
 
-    fn sbox_p_gen<T: AbstractField>(&self, value: T, intermediate_power: &mut Option<T>) -> T {
+    pub(crate) fn sbox_p_gen<T: AbstractField>(
+        &self,
+        value: T,
+        intermediate_power: &mut Option<T>,
+    ) -> T {
         if self.max_constraint_degree < SBOX_DEGREE {
             // In this case, we compute and set intermediate_power to value^max_constraint_degree
             let mut val_p = T::ONE;
@@ -18,6 +18,7 @@
 //! - [is_less_than]
 //! - [is_less_than_array]
 //! - [is_zero]
+//! - [rotation]
 
 /// Derive macros
 pub use openvm_circuit_primitives_derive::*;
@@ -34,6 +35,7 @@ pub mod is_zero;
 pub mod range;
 pub mod range_gate;
 pub mod range_tuple;
+pub mod rotation;
 pub mod utils;
 pub mod var_range;
 pub mod xor;
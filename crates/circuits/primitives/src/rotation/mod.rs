@@ -0,0 +1,154 @@
+//! Constraints can only reference the current and the next row of a trace, since the prover
+//! opens every trace at `zeta` and `g * zeta` only. [RotationSubAir] gives an AIR the values of
+//! the row `offset` rows ahead, cyclically, in auxiliary columns which are tied to the trace by a
+//! permutation argument over a [RotationBus]:
+//!
+//! - every row sends `(row_idx, values)`,
+//! - every row receives `((row_idx + offset) % height, rotated)`.
+//!
+//! The row indices are distinct, so the bus balances if and only if `rotated` holds the values of
+//! the row `(row_idx + offset) % height`. Several rotations of one AIR use one sub-AIR each.
+
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    interaction::{InteractionBuilder, InteractionType},
+    p3_air::AirBuilder,
+    p3_field::{AbstractField, Field},
+};
+
+use crate::{SubAir, TraceSubRowGenerator};
+
+#[cfg(test)]
+mod tests;
+
+/// Bus of a [RotationSubAir], carrying `(row_idx, values)`. It must not be shared with any other
+/// AIR or rotation, since their messages would take part in the permutation argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RotationBus {
+    pub index: usize,
+}
+
+impl RotationBus {
+    pub const fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct RotationAuxCols<T, const NUM: usize> {
+    pub row_idx: T,
+    /// `(row_idx + offset) % height`.
+    pub rotated_idx: T,
+    /// Whether `rotated_idx` has wrapped around to 0, on this row or on an earlier one.
+    pub wrapped: T,
+    /// The values of the row `rotated_idx`.
+    pub rotated: [T; NUM],
+}
+
+/// Constrains `rotated` to the `NUM` values of the row `offset` rows ahead, cyclically. The trace
+/// height must be greater than `offset`, and every row, including padding rows, is constrained.
+#[derive(Clone, Copy, Debug)]
+pub struct RotationSubAir<const NUM: usize> {
+    pub bus: RotationBus,
+    pub offset: usize,
+}
+
+impl<const NUM: usize> RotationSubAir<NUM> {
+    pub fn new(bus: RotationBus, offset: usize) -> Self {
+        assert!(offset > 0, "rotation offset must be positive");
+        Self { bus, offset }
+    }
+}
+
+impl<AB: InteractionBuilder, const NUM: usize> SubAir<AB> for RotationSubAir<NUM> {
+    /// `(values, local, next)`, where `values` are the values of the current row and `local` and
+    /// `next` are the auxiliary columns of the current and the next row.
+    type AirContext<'a>
+        = (
+        [AB::Expr; NUM],
+        &'a RotationAuxCols<AB::Var, NUM>,
+        &'a RotationAuxCols<AB::Var, NUM>,
+    )
+    where
+        AB::Expr: 'a,
+        AB::Var: 'a,
+        AB: 'a;
+
+    fn eval<'a>(
+        &'a self,
+        builder: &'a mut AB,
+        (values, local, next): (
+            [AB::Expr; NUM],
+            &'a RotationAuxCols<AB::Var, NUM>,
+            &'a RotationAuxCols<AB::Var, NUM>,
+        ),
+    ) where
+        AB::Var: 'a,
+        AB::Expr: 'a,
+    {
+        let offset = AB::Expr::from_canonical_usize(self.offset);
+        builder.when_first_row().assert_zero(local.row_idx);
+        builder
+            .when_first_row()
+            .assert_eq(local.rotated_idx, offset.clone());
+        builder.when_first_row().assert_zero(local.wrapped);
+        builder.assert_bool(local.wrapped);
+        builder
+            .when_transition()
+            .assert_eq(next.row_idx, local.row_idx + AB::Expr::ONE);
+
+        // `rotated_idx` counts up from `offset` and wraps around to 0 exactly once, since
+        // `wrapped` is monotonic. As it ends at `offset - 1` on the last row, it wraps after
+        // `height - 1`, so it is `(row_idx + offset) % height` on every row.
+        let wraps = next.wrapped - local.wrapped;
+        builder.when_transition().assert_bool(wraps.clone());
+        builder.when_transition().assert_eq(
+            next.rotated_idx,
+            (local.rotated_idx + AB::Expr::ONE) * (AB::Expr::ONE - wraps),
+        );
+        builder.when_last_row().assert_one(local.wrapped);
+        builder
+            .when_last_row()
+            .assert_eq(local.rotated_idx, offset - AB::Expr::ONE);
+
+        builder.push_interaction(
+            self.bus.index,
+            [local.row_idx.into()].into_iter().chain(values),
+            AB::Expr::ONE,
+            InteractionType::Send,
+        );
+        builder.push_interaction(
+            self.bus.index,
+            [local.rotated_idx.into()]
+                .into_iter()
+                .chain(local.rotated.map(Into::into)),
+            AB::Expr::ONE,
+            InteractionType::Receive,
+        );
+    }
+}
+
+impl<F: Field, const NUM: usize> TraceSubRowGenerator<F> for RotationSubAir<NUM> {
+    /// `(row_idx, values)`, where `values` are the values of every row of the trace.
+    type TraceContext<'a> = (usize, &'a [[F; NUM]]);
+    type ColsMut<'a> = &'a mut RotationAuxCols<F, NUM>;
+
+    fn generate_subrow<'a>(
+        &'a self,
+        (row_idx, values): (usize, &'a [[F; NUM]]),
+        aux: &'a mut RotationAuxCols<F, NUM>,
+    ) {
+        let height = values.len();
+        assert!(
+            self.offset < height,
+            "rotation offset {} is not less than the trace height {height}",
+            self.offset
+        );
+        let rotated_idx = (row_idx + self.offset) % height;
+        aux.row_idx = F::from_canonical_usize(row_idx);
+        aux.rotated_idx = F::from_canonical_usize(rotated_idx);
+        aux.wrapped = F::from_bool(row_idx + self.offset >= height);
+        aux.rotated = values[rotated_idx];
+    }
+}
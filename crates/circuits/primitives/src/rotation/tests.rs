@@ -0,0 +1,133 @@
+use std::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    any_rap_arc_vec, config::baby_bear_poseidon2::BabyBearPoseidon2Engine, engine::StarkFriEngine,
+    p3_baby_bear::BabyBear, utils::create_seeded_rng,
+};
+use rand::Rng;
+use test_case::test_case;
+
+use super::{RotationAuxCols, RotationBus, RotationSubAir};
+use crate::{SubAir, TraceSubRowGenerator};
+
+const NUM: usize = 2;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct RotationCols<T> {
+    values: [T; NUM],
+    aux: RotationAuxCols<T, NUM>,
+}
+
+#[derive(Clone, Copy)]
+pub struct RotationTestAir(RotationSubAir<NUM>);
+
+impl<F: Field> BaseAirWithPublicValues<F> for RotationTestAir {}
+impl<F: Field> PartitionedBaseAir<F> for RotationTestAir {}
+impl<F: Field> BaseAir<F> for RotationTestAir {
+    fn width(&self) -> usize {
+        RotationCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for RotationTestAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &RotationCols<AB::Var> = (*local).borrow();
+        let next: &RotationCols<AB::Var> = (*next).borrow();
+        self.0.eval(
+            builder,
+            (local.values.map(Into::into), &local.aux, &next.aux),
+        );
+    }
+}
+
+fn generate_trace(air: &RotationTestAir, values: &[[BabyBear; NUM]]) -> RowMajorMatrix<BabyBear> {
+    let width = RotationCols::<BabyBear>::width();
+    let mut trace = BabyBear::zero_vec(width * values.len());
+    for (row_idx, row) in trace.chunks_exact_mut(width).enumerate() {
+        let row: &mut RotationCols<BabyBear> = row.borrow_mut();
+        row.values = values[row_idx];
+        air.0.generate_subrow((row_idx, values), &mut row.aux);
+    }
+    RowMajorMatrix::new(trace, width)
+}
+
+fn random_values(height: usize) -> Vec<[BabyBear; NUM]> {
+    let mut rng = create_seeded_rng();
+    (0..height)
+        .map(|_| from_fn(|_| BabyBear::from_wrapped_u32(rng.gen())))
+        .collect()
+}
+
+#[test_case(1, 2)]
+#[test_case(1, 8)]
+#[test_case(3, 8)]
+#[test_case(7, 8)]
+#[test_case(5, 32)]
+fn test_rotation(offset: usize, height: usize) {
+    let air = RotationTestAir(RotationSubAir::new(RotationBus::new(0), offset));
+    let values = random_values(height);
+    let trace = generate_trace(&air, &values);
+    for (row_idx, row) in trace.rows().enumerate() {
+        let row: Vec<_> = row.collect();
+        let row: &RotationCols<BabyBear> = row[..].borrow();
+        assert_eq!(row.aux.rotated, values[(row_idx + offset) % height]);
+    }
+
+    BabyBearPoseidon2Engine::run_simple_test_no_pis_fast(any_rap_arc_vec![air], vec![trace])
+        .expect("Verification failed");
+}
+
+#[test]
+fn test_rotation_wrong_value_fail() {
+    let air = RotationTestAir(RotationSubAir::new(RotationBus::new(0), 3));
+    let mut trace = generate_trace(&air, &random_values(8));
+    let row: &mut RotationCols<BabyBear> = trace.row_mut(5).borrow_mut();
+    row.aux.rotated[1] += BabyBear::ONE;
+
+    disable_debug_builder();
+    assert_eq!(
+        BabyBearPoseidon2Engine::run_simple_test_no_pis_fast(any_rap_arc_vec![air], vec![trace])
+            .err(),
+        Some(VerificationError::ChallengePhaseError),
+        "Expected interaction to fail"
+    );
+}
+
+/// Swapping the rotations of two rows keeps the bus balanced, so the index constraints must
+/// reject it.
+#[test]
+fn test_rotation_wrong_index_fail() {
+    let (offset, height) = (2, 8);
+    let air = RotationTestAir(RotationSubAir::new(RotationBus::new(0), offset));
+    let values = random_values(height);
+    let mut trace = generate_trace(&air, &values);
+    for (row_idx, rotated_idx) in [(0, offset + 1), (1, offset)] {
+        let row: &mut RotationCols<BabyBear> = trace.row_mut(row_idx).borrow_mut();
+        row.aux.rotated_idx = BabyBear::from_canonical_usize(rotated_idx);
+        row.aux.rotated = values[rotated_idx];
+    }
+
+    disable_debug_builder();
+    assert_eq!(
+        BabyBearPoseidon2Engine::run_simple_test_no_pis_fast(any_rap_arc_vec![air], vec![trace])
+            .err(),
+        Some(VerificationError::OodEvaluationMismatch),
+        "Expected constraint to fail"
+    );
+}
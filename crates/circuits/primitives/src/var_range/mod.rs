@@ -128,17 +128,34 @@ impl VariableRangeCheckerChip {
         level = "trace"
     )]
     pub fn add_count(&self, value: u32, max_bits: usize) {
-        // index is 2^max_bits + value - 1 + 1 for the extra [0, 0] row
-        // if each [value, max_bits] is valid, the sends multiset will be exactly the receives multiset
-        let idx = (1 << max_bits) + (value as usize);
-        assert!(
-            idx < self.count.len(),
-            "range exceeded: {} >= {}",
-            idx,
-            self.count.len()
-        );
+        self.add_count_mult(value, max_bits, 1);
+    }
+
+    /// Adds `mult` range checks that `value` has `max_bits` bits.
+    pub fn add_count_mult(&self, value: u32, max_bits: usize, mult: u32) {
+        let idx = count_index(value, max_bits, self.count.len());
         let val_atomic = &self.count[idx];
-        val_atomic.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        val_atomic.fetch_add(mult, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Returns empty counts for this chip, to be accumulated without synchronization and
+    /// submitted with [Self::add_counts].
+    pub fn new_counts(&self) -> VariableRangeCounts {
+        VariableRangeCounts::new(self.range_max_bits())
+    }
+
+    /// Adds all range checks accumulated in `counts`.
+    pub fn add_counts(&self, counts: &VariableRangeCounts) {
+        assert_eq!(
+            counts.count.len(),
+            self.count.len(),
+            "counts were created for a different range_max_bits"
+        );
+        for (val_atomic, &mult) in self.count.iter().zip(&counts.count) {
+            if mult != 0 {
+                val_atomic.fetch_add(mult, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
     }
 
     pub fn clear(&self) {
@@ -180,6 +197,48 @@ impl VariableRangeCheckerChip {
     }
 }
 
+/// Range check multiplicities of a [VariableRangeCheckerChip], accumulated locally without
+/// atomics. During parallel trace generation, each thread or task can accumulate its own shard of
+/// counts and submit it once with [VariableRangeCheckerChip::add_counts], instead of contending on
+/// the chip's shared counters for every value. Shards can also be combined with [Self::merge].
+#[derive(Clone, Debug)]
+pub struct VariableRangeCounts {
+    count: Vec<u32>,
+}
+
+impl VariableRangeCounts {
+    pub fn new(range_max_bits: usize) -> Self {
+        Self {
+            count: vec![0; 1 << (range_max_bits + 1)],
+        }
+    }
+
+    pub fn add_count(&mut self, value: u32, max_bits: usize) {
+        self.add_count_mult(value, max_bits, 1);
+    }
+
+    pub fn add_count_mult(&mut self, value: u32, max_bits: usize, mult: u32) {
+        let idx = count_index(value, max_bits, self.count.len());
+        self.count[idx] += mult;
+    }
+
+    /// Adds the counts of `other` to `self`.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(self.count.len(), other.count.len());
+        for (count, &mult) in self.count.iter_mut().zip(&other.count) {
+            *count += mult;
+        }
+    }
+}
+
+fn count_index(value: u32, max_bits: usize, num_rows: usize) -> usize {
+    // index is 2^max_bits + value - 1 + 1 for the extra [0, 0] row
+    // if each [value, max_bits] is valid, the sends multiset will be exactly the receives multiset
+    let idx = (1 << max_bits) + (value as usize);
+    assert!(idx < num_rows, "range exceeded: {} >= {}", idx, num_rows);
+    idx
+}
+
 impl<SC: StarkGenericConfig> Chip<SC> for VariableRangeCheckerChip
 where
     Val<SC>: PrimeField32,
@@ -243,3 +243,59 @@ fn negative_test_variable_range_checker_chip_range_check() {
         "Expected constraint to fail"
     );
 }
+
+#[test]
+fn test_variable_range_checker_chip_add_counts() {
+    let mut rng = create_seeded_rng();
+
+    const MAX_BITS: usize = 4;
+    const LIST_LEN: usize = 1 << 8;
+
+    let bus = VariableRangeCheckerBus::new(0, MAX_BITS);
+    let var_range_checker = VariableRangeCheckerChip::new(bus);
+    let batched_var_range_checker = VariableRangeCheckerChip::new(bus);
+
+    let num_lists = 10;
+    let lists_vals = (0..num_lists)
+        .map(|_| {
+            (0..LIST_LEN)
+                .map(|_| {
+                    let bits = rng.gen_range(0..=MAX_BITS);
+                    (rng.gen_range(0..(1 << bits)), bits)
+                })
+                .collect::<Vec<(u32, usize)>>()
+        })
+        .collect::<Vec<_>>();
+
+    for &(val, bits) in lists_vals.iter().flatten() {
+        var_range_checker.add_count(val, bits);
+    }
+
+    // one shard of counts per list, merged in parallel and submitted once
+    let counts = lists_vals
+        .par_iter()
+        .map(|list| {
+            let mut counts = batched_var_range_checker.new_counts();
+            for &(val, bits) in list {
+                counts.add_count(val, bits);
+            }
+            counts
+        })
+        .reduce(
+            || batched_var_range_checker.new_counts(),
+            |mut lhs, rhs| {
+                lhs.merge(&rhs);
+                lhs
+            },
+        );
+    batched_var_range_checker.add_counts(&counts);
+    batched_var_range_checker.add_count_mult(0, 0, 3);
+    var_range_checker.add_count_mult(0, 0, 3);
+
+    assert_eq!(
+        var_range_checker.generate_trace::<BabyBear>().values,
+        batched_var_range_checker
+            .generate_trace::<BabyBear>()
+            .values
+    );
+}
@@ -1,5 +1,7 @@
 use cargo_openvm::{
-    commands::{BenchCmd, BuildCmd, EvmProvingSetupCmd, KeygenCmd, ProveCmd, RunCmd, VerifyCmd},
+    commands::{
+        BenchCmd, BuildCmd, EvmProvingSetupCmd, KeygenCmd, ProveCmd, RunCmd, ServeCmd, VerifyCmd,
+    },
     OPENVM_VERSION_MESSAGE,
 };
 use clap::{Parser, Subcommand};
@@ -28,6 +30,7 @@ pub enum VmCliCommands {
     Keygen(KeygenCmd),
     Prove(ProveCmd),
     Run(RunCmd),
+    Serve(ServeCmd),
     Setup(EvmProvingSetupCmd),
     Verify(VerifyCmd),
 }
@@ -43,6 +46,7 @@ async fn main() -> Result<()> {
         VmCliCommands::Run(cmd) => cmd.run(),
         VmCliCommands::Keygen(cmd) => cmd.run(),
         VmCliCommands::Prove(cmd) => cmd.run(),
+        VmCliCommands::Serve(cmd) => cmd.run(),
         VmCliCommands::Setup(cmd) => cmd.run().await,
         VmCliCommands::Verify(cmd) => cmd.run(),
     }
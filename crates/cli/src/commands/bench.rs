@@ -89,7 +89,7 @@ where
     // 1. Generate proving key from config.
     tracing::info!("fri.log_blowup: {}", engine.fri_params().log_blowup);
     let vm = VirtualMachine::<SC, E, VC>::new(engine, config);
-    let pk = vm.keygen();
+    let pk = vm.keygen()?;
     // 2. Commit to the exe by generating cached trace for program.
     let committed_exe = vm.commit_exe(exe);
     // 3. Executes runtime again without metric collection and generate trace.
@@ -5,11 +5,18 @@ use eyre::Result;
 use openvm_build::{
     build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
 };
-use openvm_sdk::{fs::write_exe_to_file, Sdk};
+use openvm_sdk::{
+    commit::AppProgramManifest,
+    fs::{write_app_manifest_to_file, write_exe_to_file},
+    Sdk,
+};
 use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE};
 
 use crate::{
-    default::{DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_MANIFEST_DIR},
+    default::{
+        DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_MANIFEST_PATH,
+        DEFAULT_MANIFEST_DIR,
+    },
     util::read_config_toml_or_default,
 };
 
@@ -39,6 +46,18 @@ pub struct BuildArgs {
     #[arg(long, value_delimiter = ',', help = "Feature flags passed to cargo")]
     pub features: Vec<String>,
 
+    #[arg(
+        long,
+        help = "Start address of the guest heap. Defaults to the end of the program's sections"
+    )]
+    pub heap_start: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Size of the guest heap in bytes. The guest exits with code 3 when it runs out of heap"
+    )]
+    pub heap_size: Option<u32>,
+
     #[clap(flatten, help = "Filter the target to build")]
     pub bin_type_filter: BinTypeFilter,
 
@@ -66,6 +85,13 @@ pub struct BuildArgs {
     )]
     pub exe_output: PathBuf,
 
+    #[arg(
+        long,
+        default_value = DEFAULT_APP_MANIFEST_PATH,
+        help = "Output path for the program commitment of the transpiled program"
+    )]
+    pub manifest_output: PathBuf,
+
     #[arg(long, default_value = "release", help = "Build profile")]
     pub profile: String,
 }
@@ -101,6 +127,8 @@ pub(crate) fn build(build_args: &BuildArgs) -> Result<Option<PathBuf>> {
     };
     let guest_options = GuestOptions {
         features: build_args.features.clone(),
+        heap_start: build_args.heap_start,
+        heap_size: build_args.heap_size,
         ..Default::default()
     };
 
@@ -123,17 +151,26 @@ pub(crate) fn build(build_args: &BuildArgs) -> Result<Option<PathBuf>> {
         println!("[openvm] Transpiling the package...");
         let output_path = &build_args.exe_output;
         let app_config = read_config_toml_or_default(&build_args.config)?;
-        let transpiler = app_config.app_vm_config.transpiler();
 
         let data = read(elf_path.clone())?;
         let elf = Elf::decode(&data, MEM_SIZE as u32)?;
-        let exe = Sdk.transpile(elf, transpiler)?;
+        let exe = Sdk.transpile_for_config(elf, &app_config.app_vm_config)?;
+        let app_fri_params = app_config.app_fri_params.fri_params;
+        let committed_exe = Sdk.commit_app_exe(app_fri_params, exe.clone())?;
         write_exe_to_file(exe, output_path)?;
+        write_app_manifest_to_file(
+            AppProgramManifest::new(app_fri_params, &committed_exe),
+            &build_args.manifest_output,
+        )?;
 
         println!(
             "[openvm] Successfully transpiled to {}",
             output_path.display()
         );
+        println!(
+            "[openvm] Program commitment written to {}",
+            build_args.manifest_output.display()
+        );
         Ok(Some(elf_path))
     } else if let Ok(elf_path) = elf_path {
         println!(
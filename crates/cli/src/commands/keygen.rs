@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use eyre::Result;
+use openvm_circuit::metrics::air_stats::AirStatsReport;
 use openvm_sdk::{
     fs::{write_app_pk_to_file, write_app_vk_to_file},
     Sdk,
@@ -33,12 +34,22 @@ pub struct KeygenCmd {
         default_value = DEFAULT_APP_VK_PATH
     )]
     vk_output: PathBuf,
+
+    #[clap(
+        long,
+        action,
+        help = "Print per-AIR constraint counts, degrees, trace widths, and bus interactions"
+    )]
+    stats: bool,
 }
 
 impl KeygenCmd {
     pub fn run(&self) -> Result<()> {
         let app_config = read_config_toml_or_default(&self.config)?;
         let app_pk = Sdk.app_keygen(app_config)?;
+        if self.stats {
+            print!("{}", AirStatsReport::new(&app_pk.app_vm_pk.vm_pk));
+        }
         write_app_vk_to_file(app_pk.get_vk(), &self.vk_output)?;
         write_app_pk_to_file(app_pk, &self.output)?;
         Ok(())
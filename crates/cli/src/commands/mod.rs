@@ -13,6 +13,9 @@ pub use prove::*;
 mod run;
 pub use run::*;
 
+mod serve;
+pub use serve::*;
+
 mod setup;
 pub use setup::*;
 
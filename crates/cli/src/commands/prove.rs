@@ -2,7 +2,6 @@ use std::{path::PathBuf, sync::Arc};
 
 use clap::Parser;
 use eyre::Result;
-use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
 use openvm_sdk::{
     commit::AppExecutionCommit,
     config::SdkVmConfig,
@@ -11,6 +10,7 @@ use openvm_sdk::{
         write_evm_proof_to_file,
     },
     keygen::AppProvingKey,
+    srs::SrsManager,
     NonRootCommittedExe, Sdk, StdIn,
 };
 
@@ -78,7 +78,7 @@ impl ProveCmd {
                 input,
                 output,
             } => {
-                let params_reader = CacheHalo2ParamsReader::new(DEFAULT_PARAMS_DIR);
+                let params_reader = SrsManager::new(DEFAULT_PARAMS_DIR);
                 let (app_pk, committed_exe, input) = Self::prepare_execution(app_pk, exe, input)?;
                 println!("Generating EVM proof, this may take a lot of compute and memory...");
                 let agg_pk = read_agg_pk_from_file(DEFAULT_AGG_PK_PATH).map_err(|e| {
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{fs::File, path::PathBuf};
 
 use clap::Parser;
 use eyre::Result;
@@ -20,13 +20,56 @@ pub struct RunCmd {
 
     #[clap(long, value_parser, help = "Input to OpenVM program")]
     input: Option<Input>,
+
+    #[clap(
+        long,
+        action,
+        help = "Write the guest call graph to this path in the callgrind format"
+    )]
+    callgrind: Option<PathBuf>,
+
+    #[clap(
+        long,
+        action,
+        conflicts_with = "callgrind",
+        help = "Only count cycles and trace heights, without keeping anything needed to prove the \
+                execution"
+    )]
+    fast: bool,
 }
 
 impl RunCmd {
     pub fn run(&self) -> Result<()> {
         let exe = read_exe_from_file(&self.exe)?;
         let app_config = read_config_toml_or_default(&self.config)?;
-        let output = Sdk.execute(exe, app_config.app_vm_config, read_to_stdin(&self.input)?)?;
+        let input = read_to_stdin(&self.input)?;
+        if self.fast {
+            let result = Sdk.run_fast(exe, app_config.app_vm_config, input)?;
+            println!(
+                "Executed {} cycles in {} segments, exit code {}",
+                result.cycles, result.num_segments, result.exit_code
+            );
+            for (air_name, height) in result
+                .summary
+                .air_names
+                .iter()
+                .zip(result.summary.max_trace_heights())
+            {
+                if height > 0 {
+                    println!("{air_name}: {height}");
+                }
+            }
+            return Ok(());
+        }
+        let output = match &self.callgrind {
+            Some(path) => Sdk.execute_with_callgrind(
+                exe,
+                app_config.app_vm_config,
+                input,
+                File::create(path)?,
+            )?,
+            None => Sdk.execute(exe, app_config.app_vm_config, input)?,
+        };
         println!("Execution output: {:?}", output);
         Ok(())
     }
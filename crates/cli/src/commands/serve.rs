@@ -0,0 +1,72 @@
+use std::{
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{
+    config::SdkVmConfig,
+    fs::{read_app_pk_from_file, read_exe_from_file},
+    keygen::AppProvingKey,
+    prover::{AppProver, AppProvingService, ProvingServer, DEFAULT_MAX_QUEUED_JOBS},
+    Sdk,
+};
+
+use crate::default::{DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH};
+
+#[derive(Parser)]
+#[command(name = "serve", about = "Serve app proofs of a program over HTTP")]
+pub struct ServeCmd {
+    #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
+    app_pk: PathBuf,
+
+    #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+    exe: PathBuf,
+
+    #[clap(
+        long,
+        action,
+        help = "Address to listen on",
+        default_value = "127.0.0.1:3000"
+    )]
+    addr: SocketAddr,
+
+    #[clap(
+        long,
+        action,
+        help = "Number of jobs which can wait to be proven",
+        default_value_t = DEFAULT_MAX_QUEUED_JOBS
+    )]
+    max_queued_jobs: usize,
+
+    #[cfg(feature = "bench-metrics")]
+    #[clap(
+        long,
+        action,
+        help = "Address to serve the prover metrics on, in the Prometheus format"
+    )]
+    prometheus_addr: Option<SocketAddr>,
+}
+
+impl ServeCmd {
+    pub fn run(&self) -> Result<()> {
+        #[cfg(feature = "bench-metrics")]
+        if let Some(addr) = self.prometheus_addr {
+            openvm_sdk::prometheus::install_prometheus_exporter(addr)?;
+            println!("Serving metrics on http://{addr}/metrics");
+        }
+        let app_pk: Arc<AppProvingKey<SdkVmConfig>> =
+            Arc::new(read_app_pk_from_file(&self.app_pk)?);
+        let app_exe = read_exe_from_file(&self.exe)?;
+        let committed_exe = Sdk.commit_app_exe(app_pk.app_fri_params(), app_exe)?;
+        let app_prover = AppProver::new(app_pk.app_vm_pk.clone(), committed_exe);
+        let service =
+            Arc::new(AppProvingService::new(app_prover).with_max_queued_jobs(self.max_queued_jobs));
+
+        let listener = TcpListener::bind(self.addr)?;
+        println!("Serving app proofs on http://{}", listener.local_addr()?);
+        ProvingServer::new(service).serve(listener)
+    }
+}
@@ -1,16 +1,11 @@
-use std::{
-    fs::{create_dir_all, write},
-    path::PathBuf,
-};
+use std::path::PathBuf;
 
-use aws_config::{defaults, BehaviorVersion, Region};
-use aws_sdk_s3::Client;
 use clap::Parser;
 use eyre::{eyre, Result};
-use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
 use openvm_sdk::{
     config::AggConfig,
     fs::{write_agg_pk_to_file, write_evm_verifier_to_file},
+    srs::SrsManager,
     Sdk,
 };
 
@@ -36,8 +31,8 @@ impl EvmProvingSetupCmd {
             ));
         }
 
-        Self::download_params(10, 24).await?;
-        let params_reader = CacheHalo2ParamsReader::new(DEFAULT_PARAMS_DIR);
+        // Missing SRS files are downloaded as keygen needs them.
+        let params_reader = SrsManager::new(DEFAULT_PARAMS_DIR);
         let agg_config = AggConfig::default();
 
         println!("Generating proving key...");
@@ -61,33 +56,4 @@ impl EvmProvingSetupCmd {
             .output()
             .is_ok()
     }
-
-    async fn download_params(min_k: u32, max_k: u32) -> Result<()> {
-        create_dir_all(DEFAULT_PARAMS_DIR)?;
-        let config = defaults(BehaviorVersion::latest())
-            .region(Region::new("us-east-1"))
-            .no_credentials()
-            .load()
-            .await;
-        let client = Client::new(&config);
-
-        for k in min_k..=max_k {
-            let file_name = format!("kzg_bn254_{}.srs", k);
-            let local_file_path = PathBuf::from(DEFAULT_PARAMS_DIR).join(&file_name);
-            if !local_file_path.exists() {
-                println!("Downloading {}", file_name);
-                let key = format!("challenge_0085/{}", file_name);
-                let resp = client
-                    .get_object()
-                    .bucket("axiom-crypto")
-                    .key(&key)
-                    .send()
-                    .await?;
-                let data = resp.body.collect().await?;
-                write(local_file_path, data.into_bytes())?;
-            }
-        }
-
-        Ok(())
-    }
 }
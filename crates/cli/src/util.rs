@@ -10,7 +10,6 @@ use openvm_sdk::{
     config::{AppConfig, SdkVmConfig},
     StdIn,
 };
-use serde::de::DeserializeOwned;
 
 use crate::default::default_app_config;
 
@@ -65,12 +64,6 @@ pub(crate) fn classical_exe_path(elf_path: &Path) -> PathBuf {
     elf_path.with_extension("vmexe")
 }
 
-pub(crate) fn read_to_struct_toml<T: DeserializeOwned>(path: &PathBuf) -> Result<T> {
-    let toml = read_to_string(path.as_ref() as &Path)?;
-    let ret = toml::from_str(&toml)?;
-    Ok(ret)
-}
-
 pub(crate) fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
     match input {
         Some(Input::FilePath(path)) => {
@@ -84,7 +77,7 @@ pub(crate) fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
 
 pub(crate) fn read_config_toml_or_default(config: &PathBuf) -> Result<AppConfig<SdkVmConfig>> {
     if config.exists() {
-        read_to_struct_toml(config)
+        Ok(AppConfig::from_toml(&read_to_string(config)?)?)
     } else {
         println!(
             "{:?} not found, using default application configuration",
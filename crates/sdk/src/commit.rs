@@ -20,14 +20,20 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
     p3_bn254_fr::Bn254Fr,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    keygen::AppProvingKey, verifier::leaf::LeafVmVerifierConfig, NonRootCommittedExe, F, SC,
+    keygen::AppProvingKey,
+    verifier::leaf::{
+        app_commit_with_config_digest, app_commit_with_vk_index, LeafVmVerifierConfig,
+    },
+    NonRootCommittedExe, F, SC,
 };
 
 /// `AppExecutionCommit` has all the commitments users should check against the final proof.
 pub struct AppExecutionCommit<T> {
-    /// Commitment of the leaf VM verifier program which commits the VmConfig of App VM.
+    /// Commitment of the leaf VM verifier program which commits the VmConfig of App VM: the
+    /// verifying key of the App VM is a constant of the program.
     /// Internal verifier will verify `leaf_vm_verifier_commit`.
     pub leaf_vm_verifier_commit: [T; DIGEST_SIZE],
     /// Commitment of the executable. It's computed as
@@ -39,6 +45,13 @@ pub struct AppExecutionCommit<T> {
     ///     hash(right_pad(pc_start, 0))
     /// )
     /// `right_pad` example, if pc_start = 123, right_pad(pc_start, 0) = [123,0,0,0,0,0,0,0]
+    /// `app_program_commit` is the commitment of the program compressed with the
+    /// [app_config_digest] of the App VM, see [app_commit_with_config_digest].
+    ///
+    /// Because the config digest is part of it, the `exe_commit` of an exe changes with the App VM
+    /// config, except for the knobs which only tune the prover, and with
+    /// [APP_CONFIG_DIGEST_VERSION]. Commits of existing exes computed without the
+    /// digest, or with another version of it, no longer match proofs and must be recomputed.
     pub exe_commit: [T; DIGEST_SIZE],
 }
 
@@ -49,14 +62,54 @@ impl AppExecutionCommit<F> {
         app_vm_config: &VC,
         app_exe: &NonRootCommittedExe,
         leaf_vm_verifier_exe: &NonRootCommittedExe,
+    ) -> Self {
+        Self::compute_impl(
+            app_vm_config,
+            app_exe,
+            leaf_vm_verifier_exe,
+            app_config_digest(app_vm_config),
+            None,
+        )
+    }
+
+    /// Same as [AppExecutionCommit::compute] for proofs aggregated by a
+    /// [multi-app](LeafVmVerifierConfig::build_multi_app_program) leaf verifier for the app VMs
+    /// with `app_vm_configs`, where `app_exe` runs on the one at `app_vk_index`. The leaf verifier
+    /// must be built with the [multi_app_config_digest] of `app_vm_configs`.
+    pub fn compute_for_app_vk_index<VC: VmConfig<F>>(
+        app_vm_configs: &[VC],
+        app_vk_index: usize,
+        app_exe: &NonRootCommittedExe,
+        leaf_vm_verifier_exe: &NonRootCommittedExe,
+    ) -> Self {
+        Self::compute_impl(
+            &app_vm_configs[app_vk_index],
+            app_exe,
+            leaf_vm_verifier_exe,
+            multi_app_config_digest(app_vm_configs),
+            Some(app_vk_index),
+        )
+    }
+
+    fn compute_impl<VC: VmConfig<F>>(
+        app_vm_config: &VC,
+        app_exe: &NonRootCommittedExe,
+        leaf_vm_verifier_exe: &NonRootCommittedExe,
+        leaf_app_config_digest: [F; DIGEST_SIZE],
+        app_vk_index: Option<usize>,
     ) -> Self {
         assert!(
             app_exe.exe.program.max_num_public_values <= app_vm_config.system().num_public_values
         );
         let hasher = vm_poseidon2_hasher();
         let memory_dimensions = app_vm_config.system().memory_config.memory_dimensions();
-        let app_program_commit: [F; DIGEST_SIZE] =
+        let mut app_program_commit: [F; DIGEST_SIZE] =
             app_exe.committed_program.prover_data.commit.into();
+        if let Some(app_vk_index) = app_vk_index {
+            app_program_commit = app_commit_with_vk_index(app_program_commit, app_vk_index);
+        }
+        app_program_commit =
+            app_commit_with_config_digest(app_program_commit, leaf_app_config_digest);
         let leaf_verifier_program_commit: [F; DIGEST_SIZE] = leaf_vm_verifier_exe
             .committed_program
             .prover_data
@@ -92,6 +145,66 @@ impl AppExecutionCommit<F> {
     }
 }
 
+/// Version of the encoding hashed by [app_config_digest]. It must be bumped whenever the encoding
+/// changes, including by upgrading bitcode to a version with another serialization format.
+pub const APP_CONFIG_DIGEST_VERSION: u8 = 2;
+
+/// Digest of the parts of the App VM config which affect what a proof proves: the enabled
+/// extensions and their options, such as the moduli and the curves, and the system config. This
+/// includes parts which do not change the shape of the AIRs. The leaf verifier program compresses
+/// it into the `app_commit` it exposes, so proofs of VMs with different configs have different
+/// `leaf_vm_verifier_commit`s and `exe_commit`s even if their verifying keys are the same.
+///
+/// The knobs of [SystemConfig](openvm_circuit::arch::SystemConfig) which only tune the prover, `max_segment_len`, `collect_metrics`
+/// and `profile_call_graph`, are left out, so changing them keeps the commits.
+///
+/// The hashed encoding is the byte [APP_CONFIG_DIGEST_VERSION] followed by the bitcode
+/// serialization of the config: its fields in declaration order, without field names, so it
+/// does not depend on formatting or map ordering. The bytes, one per field element and prefixed
+/// with their number, are absorbed [DIGEST_SIZE] at a time by compressing them into the digest.
+pub fn app_config_digest<VC: VmConfig<F>>(app_vm_config: &VC) -> [F; DIGEST_SIZE] {
+    config_digest(&without_prover_knobs(app_vm_config))
+}
+
+/// Same as [app_config_digest] for a
+/// [multi-app](crate::verifier::leaf::LeafVmVerifierConfig::build_multi_app_program) leaf
+/// verifier, where the config is the list of the configs of all app VMs.
+pub fn multi_app_config_digest<VC: VmConfig<F>>(app_vm_configs: &[VC]) -> [F; DIGEST_SIZE] {
+    config_digest(
+        &app_vm_configs
+            .iter()
+            .map(without_prover_knobs)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// `config` with the knobs of its system config which only tune the prover set to fixed values.
+fn without_prover_knobs<VC: VmConfig<F>>(config: &VC) -> VC {
+    let mut config = config.clone();
+    let system = config.system_mut();
+    system.max_segment_len = 0;
+    system.collect_metrics = false;
+    system.profile_call_graph = false;
+    config
+}
+
+fn config_digest<VC: Serialize + ?Sized>(config: &VC) -> [F; DIGEST_SIZE] {
+    let mut bytes = vec![APP_CONFIG_DIGEST_VERSION];
+    bytes.extend(bitcode::serialize(config).expect("failed to serialize the VM config"));
+    let hasher = vm_poseidon2_hasher();
+    let mut len = [F::ZERO; DIGEST_SIZE];
+    len[0] = F::from_canonical_usize(bytes.len());
+    bytes
+        .chunks(DIGEST_SIZE)
+        .fold(hasher.hash(&len), |digest, chunk| {
+            let mut block = [F::ZERO; DIGEST_SIZE];
+            for (x, &byte) in block.iter_mut().zip(chunk) {
+                *x = F::from_canonical_u8(byte);
+            }
+            hasher.compress(&digest, &block)
+        })
+}
+
 pub(crate) fn babybear_digest_to_bn254(digest: &[F; DIGEST_SIZE]) -> Bn254Fr {
     let mut ret = Bn254Fr::ZERO;
     let order = Bn254Fr::from_canonical_u32(BabyBear::ORDER_U32);
@@ -113,6 +226,7 @@ pub fn generate_leaf_committed_exe<VC: VmConfig<F>>(
     let leaf_program = LeafVmVerifierConfig {
         app_fri_params: app_pk.app_vm_pk.fri_params,
         app_system_config: app_pk.app_vm_pk.vm_config.system().clone(),
+        app_config_digest: app_config_digest(&app_pk.app_vm_pk.vm_config),
         compiler_options,
     }
     .build_program(&app_vm_vk);
@@ -130,3 +244,21 @@ pub fn commit_app_exe(
     let app_engine = BabyBearPoseidon2Engine::new(app_fri_params);
     Arc::new(VmCommittedExe::<SC>::commit(exe, app_engine.config.pcs()))
 }
+
+/// Program commitment of a transpiled app exe, written next to the exe by `cargo openvm build` so
+/// builds can be reproduced and checked against proofs without recommitting the exe.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppProgramManifest {
+    pub app_fri_params: FriParameters,
+    pub app_program_commit: [u32; DIGEST_SIZE],
+}
+
+impl AppProgramManifest {
+    pub fn new(app_fri_params: FriParameters, app_committed_exe: &NonRootCommittedExe) -> Self {
+        let app_program_commit: [F; DIGEST_SIZE] = app_committed_exe.get_program_commit().into();
+        Self {
+            app_fri_params,
+            app_program_commit: app_program_commit.map(|x| x.as_canonical_u32()),
+        }
+    }
+}
@@ -1,43 +1,81 @@
+use std::{collections::BTreeSet, str::FromStr};
+
 use bon::Builder;
 use derive_more::derive::From;
+#[cfg(feature = "aes")]
+use openvm_aes_circuit::{Aes, AesExecutor, AesPeriphery};
+#[cfg(feature = "aes")]
+use openvm_aes_transpiler::AesTranspilerExtension;
 use openvm_algebra_circuit::{
-    Fp2Extension, Fp2ExtensionExecutor, Fp2ExtensionPeriphery, ModularExtension,
-    ModularExtensionExecutor, ModularExtensionPeriphery,
+    BabyBearExt4Extension, BabyBearExt4ExtensionExecutor, BabyBearExt4ExtensionPeriphery,
+    BabyBearExtension, BabyBearExtensionExecutor, BabyBearExtensionPeriphery, Fp2Extension,
+    Fp2ExtensionExecutor, Fp2ExtensionPeriphery, ModularExtension, ModularExtensionExecutor,
+    ModularExtensionPeriphery,
+};
+use openvm_algebra_guest::{
+    BABYBEAR_EXT4_FUNCT3, BABYBEAR_FUNCT3, COMPLEX_EXT_FIELD_FUNCT3, MODULAR_ARITHMETIC_FUNCT3,
+    MODULAR_MULADD_FUNCT3,
+};
+use openvm_algebra_transpiler::{
+    BabyBearExt4TranspilerExtension, BabyBearTranspilerExtension, Fp2TranspilerExtension,
+    ModularTranspilerExtension,
 };
-use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256Executor, Int256Periphery};
+use openvm_bigint_guest::{BEQ256_FUNCT3, INT256_FUNCT3};
 use openvm_bigint_transpiler::Int256TranspilerExtension;
+#[cfg(feature = "chacha")]
+use openvm_chacha_circuit::{ChaCha, ChaChaExecutor, ChaChaPeriphery};
+#[cfg(feature = "chacha")]
+use openvm_chacha_transpiler::ChaChaTranspilerExtension;
 use openvm_circuit::{
     arch::{
-        SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig, VmInventoryError,
+        SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig, VmConfigError,
+        VmInventoryError,
     },
     circuit_derive::{Chip, ChipUsageGetter},
     derive::{AnyEnum, InstructionExecutor},
 };
 use openvm_ecc_circuit::{
-    WeierstrassExtension, WeierstrassExtensionExecutor, WeierstrassExtensionPeriphery,
+    CurveConfig, WeierstrassExtension, WeierstrassExtensionExecutor, WeierstrassExtensionPeriphery,
+    SECP256K1_CONFIG,
 };
+use openvm_ecc_guest::SW_FUNCT3;
 use openvm_ecc_transpiler::EccTranspilerExtension;
 use openvm_keccak256_circuit::{Keccak256, Keccak256Executor, Keccak256Periphery};
 use openvm_keccak256_transpiler::Keccak256TranspilerExtension;
 use openvm_native_circuit::{Native, NativeExecutor, NativePeriphery};
 use openvm_pairing_circuit::{
-    PairingExtension, PairingExtensionExecutor, PairingExtensionPeriphery,
+    PairingCurve, PairingExtension, PairingExtensionExecutor, PairingExtensionPeriphery,
 };
+use openvm_pairing_guest::PAIRING_FUNCT3;
 use openvm_pairing_transpiler::PairingTranspilerExtension;
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
     Rv32MExecutor, Rv32MPeriphery,
 };
+use openvm_rv32im_guest::{
+    HINT_STORE_W_FUNCT3, PHANTOM_FUNCT3, REVEAL_FUNCT3, SYSTEM_OPCODE, TERMINATE_FUNCT3,
+};
 use openvm_rv32im_transpiler::{
     Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
 };
+#[cfg(feature = "sort")]
+use openvm_sort_circuit::{Sort, SortExecutor, SortPeriphery};
+#[cfg(feature = "sort")]
+use openvm_sort_transpiler::SortTranspilerExtension;
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::transpiler::Transpiler;
+#[cfg(feature = "table")]
+use openvm_table_circuit::{TableExecutor, TableExtension, TablePeriphery};
+#[cfg(feature = "table")]
+use openvm_table_transpiler::TableTranspilerExtension;
+use openvm_transpiler::{manifest::GuestManifest, transpiler::Transpiler};
 use serde::{Deserialize, Serialize};
 
 use crate::F;
 
+/// Moduli are represented with at most 48 bytes by the algebra and ecc chips.
+const MAX_MODULUS_BITS: u64 = 48 * 8;
+
 #[derive(Builder, Clone, Debug, Serialize, Deserialize)]
 pub struct SdkVmConfig {
     #[serde(default)]
@@ -46,7 +84,15 @@ pub struct SdkVmConfig {
     pub rv32i: Option<UnitStruct>,
     pub io: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
+    #[cfg(feature = "aes")]
+    pub aes: Option<UnitStruct>,
+    #[cfg(feature = "chacha")]
+    pub chacha: Option<UnitStruct>,
+    #[cfg(feature = "sort")]
+    pub sort: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
+    pub babybear: Option<UnitStruct>,
+    pub babybear_ext4: Option<UnitStruct>,
 
     pub rv32m: Option<Rv32M>,
     pub bigint: Option<Int256>,
@@ -54,6 +100,8 @@ pub struct SdkVmConfig {
     pub fp2: Option<Fp2Extension>,
     pub pairing: Option<PairingExtension>,
     pub ecc: Option<WeierstrassExtension>,
+    #[cfg(feature = "table")]
+    pub table: Option<TableExtension>,
 }
 
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
@@ -66,9 +114,22 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     Io(Rv32IoExecutor<F>),
     #[any_enum]
     Keccak(Keccak256Executor<F>),
+    #[cfg(feature = "aes")]
+    #[any_enum]
+    Aes(AesExecutor<F>),
+    #[cfg(feature = "chacha")]
+    #[any_enum]
+    ChaCha(ChaChaExecutor<F>),
+    #[cfg(feature = "sort")]
+    #[any_enum]
+    Sort(SortExecutor<F>),
     #[any_enum]
     Native(NativeExecutor<F>),
     #[any_enum]
+    BabyBear(BabyBearExtensionExecutor<F>),
+    #[any_enum]
+    BabyBearExt4(BabyBearExt4ExtensionExecutor<F>),
+    #[any_enum]
     Rv32m(Rv32MExecutor<F>),
     #[any_enum]
     BigInt(Int256Executor<F>),
@@ -80,6 +141,9 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     Pairing(PairingExtensionExecutor<F>),
     #[any_enum]
     Ecc(WeierstrassExtensionExecutor<F>),
+    #[cfg(feature = "table")]
+    #[any_enum]
+    Table(TableExecutor<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
@@ -92,9 +156,22 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     Io(Rv32IoPeriphery<F>),
     #[any_enum]
     Keccak(Keccak256Periphery<F>),
+    #[cfg(feature = "aes")]
+    #[any_enum]
+    Aes(AesPeriphery<F>),
+    #[cfg(feature = "chacha")]
+    #[any_enum]
+    ChaCha(ChaChaPeriphery<F>),
+    #[cfg(feature = "sort")]
+    #[any_enum]
+    Sort(SortPeriphery<F>),
     #[any_enum]
     Native(NativePeriphery<F>),
     #[any_enum]
+    BabyBear(BabyBearExtensionPeriphery<F>),
+    #[any_enum]
+    BabyBearExt4(BabyBearExt4ExtensionPeriphery<F>),
+    #[any_enum]
     Rv32m(Rv32MPeriphery<F>),
     #[any_enum]
     BigInt(Int256Periphery<F>),
@@ -106,6 +183,9 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     Pairing(PairingExtensionPeriphery<F>),
     #[any_enum]
     Ecc(WeierstrassExtensionPeriphery<F>),
+    #[cfg(feature = "table")]
+    #[any_enum]
+    Table(TablePeriphery<F>),
 }
 
 impl SdkVmConfig {
@@ -120,6 +200,18 @@ impl SdkVmConfig {
         if self.keccak.is_some() {
             transpiler = transpiler.with_extension(Keccak256TranspilerExtension);
         }
+        #[cfg(feature = "aes")]
+        if self.aes.is_some() {
+            transpiler = transpiler.with_extension(AesTranspilerExtension);
+        }
+        #[cfg(feature = "chacha")]
+        if self.chacha.is_some() {
+            transpiler = transpiler.with_extension(ChaChaTranspilerExtension);
+        }
+        #[cfg(feature = "sort")]
+        if self.sort.is_some() {
+            transpiler = transpiler.with_extension(SortTranspilerExtension);
+        }
         if self.rv32m.is_some() {
             transpiler = transpiler.with_extension(Rv32MTranspilerExtension);
         }
@@ -132,14 +224,267 @@ impl SdkVmConfig {
         if self.fp2.is_some() {
             transpiler = transpiler.with_extension(Fp2TranspilerExtension);
         }
+        if self.babybear.is_some() {
+            transpiler = transpiler.with_extension(BabyBearTranspilerExtension);
+        }
+        if self.babybear_ext4.is_some() {
+            transpiler = transpiler.with_extension(BabyBearExt4TranspilerExtension);
+        }
         if self.pairing.is_some() {
             transpiler = transpiler.with_extension(PairingTranspilerExtension);
         }
         if self.ecc.is_some() {
             transpiler = transpiler.with_extension(EccTranspilerExtension);
         }
+        #[cfg(feature = "table")]
+        if self.table.is_some() {
+            transpiler = transpiler.with_extension(TableTranspilerExtension);
+        }
         transpiler
     }
+
+    /// Checks the [GuestManifest] of a guest ELF against this config: every custom instruction
+    /// must belong to an enabled extension, and the moduli and curves set up by the guest must
+    /// match the configured ones at the same index. All mismatches are reported together.
+    pub fn check_guest_manifest(&self, manifest: &GuestManifest) -> Result<(), VmConfigError> {
+        let mut mismatches = vec![];
+
+        let mut missing_extensions = BTreeSet::new();
+        for &(opcode, funct3) in &manifest.custom_instructions {
+            let (extension, enabled) = match (opcode, funct3) {
+                (SYSTEM_OPCODE, TERMINATE_FUNCT3 | PHANTOM_FUNCT3) => continue,
+                (SYSTEM_OPCODE, HINT_STORE_W_FUNCT3 | REVEAL_FUNCT3) => ("io", self.io.is_some()),
+                (openvm_keccak256_guest::OPCODE, openvm_keccak256_guest::FUNCT3) => {
+                    ("keccak", self.keccak.is_some())
+                }
+                #[cfg(feature = "aes")]
+                (openvm_aes_guest::OPCODE, openvm_aes_guest::FUNCT3) => ("aes", self.aes.is_some()),
+                (openvm_bigint_guest::OPCODE, INT256_FUNCT3 | BEQ256_FUNCT3) => {
+                    ("bigint", self.bigint.is_some())
+                }
+                (
+                    openvm_algebra_guest::OPCODE,
+                    MODULAR_ARITHMETIC_FUNCT3 | MODULAR_MULADD_FUNCT3,
+                ) => ("modular", self.modular.is_some()),
+                (openvm_algebra_guest::OPCODE, COMPLEX_EXT_FIELD_FUNCT3) => {
+                    ("fp2", self.fp2.is_some())
+                }
+                (openvm_algebra_guest::OPCODE, BABYBEAR_FUNCT3) => {
+                    ("babybear", self.babybear.is_some())
+                }
+                (openvm_algebra_guest::OPCODE, BABYBEAR_EXT4_FUNCT3) => {
+                    ("babybear_ext4", self.babybear_ext4.is_some())
+                }
+                (openvm_pairing_guest::OPCODE, PAIRING_FUNCT3) => {
+                    ("pairing", self.pairing.is_some())
+                }
+                (openvm_ecc_guest::OPCODE, SW_FUNCT3) => ("ecc", self.ecc.is_some()),
+                #[cfg(feature = "chacha")]
+                (openvm_chacha_guest::OPCODE, openvm_chacha_guest::FUNCT3) => {
+                    ("chacha", self.chacha.is_some())
+                }
+                #[cfg(feature = "sort")]
+                (openvm_sort_guest::OPCODE, openvm_sort_guest::FUNCT3) => {
+                    ("sort", self.sort.is_some())
+                }
+                #[cfg(feature = "table")]
+                (openvm_table_guest::OPCODE, openvm_table_guest::FUNCT3) => {
+                    ("table", self.table.is_some())
+                }
+                _ => {
+                    mismatches.push(format!(
+                        "unknown custom instruction with opcode {opcode:#x} and funct3 {funct3:#b}"
+                    ));
+                    continue;
+                }
+            };
+            if !enabled {
+                missing_extensions.insert(extension);
+            }
+        }
+        if !missing_extensions.is_empty() {
+            mismatches.push(format!(
+                "the guest uses extensions that are not enabled: {}",
+                missing_extensions
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let supported_moduli = self
+            .modular
+            .as_ref()
+            .map(|modular| modular.supported_modulus.as_slice())
+            .unwrap_or_default();
+        for (&idx, modulus) in &manifest.moduli {
+            match supported_moduli.get(idx) {
+                Some(supported) if supported == modulus => {}
+                Some(supported) => mismatches.push(format!(
+                    "modulus {idx} is {modulus} in the guest but {supported} in the config"
+                )),
+                None => mismatches.push(format!(
+                    "modulus {idx} ({modulus}) is set up by the guest but not in the config"
+                )),
+            }
+        }
+
+        let supported_curves = self
+            .ecc
+            .as_ref()
+            .map(|ecc| ecc.supported_curves.as_slice())
+            .unwrap_or_default();
+        for (&idx, modulus) in &manifest.curves {
+            match supported_curves.get(idx) {
+                Some(curve) if &curve.modulus == modulus => {}
+                Some(curve) => mismatches.push(format!(
+                    "curve {idx} has coordinate modulus {modulus} in the guest but {} in the config",
+                    curve.modulus
+                )),
+                None => mismatches.push(format!(
+                    "curve {idx} (coordinate modulus {modulus}) is set up by the guest but not in the config"
+                )),
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(VmConfigError::GuestMismatch(mismatches))
+        }
+    }
+}
+
+impl SdkVmConfig {
+    /// Returns the config of the preset named `name`. See [VmPreset] for the names.
+    pub fn preset(name: &str) -> Result<Self, VmConfigError> {
+        Ok(name.parse::<VmPreset>()?.into())
+    }
+
+    /// Computes the smallest config that can run a guest with the given [GuestManifest]: only
+    /// the extensions whose instructions the guest uses are enabled, with the moduli and curves
+    /// that the guest sets up.
+    ///
+    /// The Fp2 extension supports every modulus of the guest, since its instructions are indexed
+    /// like the moduli. The pairing extension supports the pairing curves up to the last one whose
+    /// coordinate modulus is set up by the guest. A guest using lookup tables is rejected, since
+    /// the table entries are not part of the manifest.
+    pub fn from_guest_manifest(manifest: &GuestManifest) -> Result<Self, VmConfigError> {
+        let mut config = Self::builder()
+            .system(Default::default())
+            .rv32i(Default::default())
+            .build();
+        if manifest.uses_rv32m {
+            config.rv32m = Some(Rv32M::default());
+        }
+
+        let moduli: Vec<_> = manifest.moduli.values().cloned().collect();
+        if manifest.moduli.keys().copied().ne(0..moduli.len()) {
+            return Err(VmConfigError::Invalid(
+                "the moduli set up by the guest are not numbered from 0 without gaps".to_string(),
+            ));
+        }
+        for &(opcode, funct3) in &manifest.custom_instructions {
+            match (opcode, funct3) {
+                (SYSTEM_OPCODE, TERMINATE_FUNCT3 | PHANTOM_FUNCT3) => {}
+                (SYSTEM_OPCODE, HINT_STORE_W_FUNCT3 | REVEAL_FUNCT3) => {
+                    config.io = Some(UnitStruct {})
+                }
+                (openvm_keccak256_guest::OPCODE, openvm_keccak256_guest::FUNCT3) => {
+                    config.keccak = Some(UnitStruct {})
+                }
+                #[cfg(feature = "aes")]
+                (openvm_aes_guest::OPCODE, openvm_aes_guest::FUNCT3) => {
+                    config.aes = Some(UnitStruct {})
+                }
+                (openvm_bigint_guest::OPCODE, INT256_FUNCT3 | BEQ256_FUNCT3) => {
+                    config.bigint = Some(Int256::default())
+                }
+                (
+                    openvm_algebra_guest::OPCODE,
+                    MODULAR_ARITHMETIC_FUNCT3 | MODULAR_MULADD_FUNCT3,
+                ) => config.modular = Some(ModularExtension::new(moduli.clone())),
+                (openvm_algebra_guest::OPCODE, COMPLEX_EXT_FIELD_FUNCT3) => {
+                    config.fp2 = Some(Fp2Extension::new(moduli.clone()))
+                }
+                (openvm_algebra_guest::OPCODE, BABYBEAR_FUNCT3) => {
+                    config.babybear = Some(UnitStruct {})
+                }
+                (openvm_algebra_guest::OPCODE, BABYBEAR_EXT4_FUNCT3) => {
+                    config.babybear_ext4 = Some(UnitStruct {})
+                }
+                (openvm_pairing_guest::OPCODE, PAIRING_FUNCT3) => {
+                    let num_curves = [PairingCurve::Bn254, PairingCurve::Bls12_381]
+                        .iter()
+                        .rposition(|curve| moduli.contains(&curve.curve_config().modulus))
+                        .ok_or_else(|| {
+                            VmConfigError::Invalid(
+                                "the guest uses pairings but sets up no pairing curve modulus"
+                                    .to_string(),
+                            )
+                        })?
+                        + 1;
+                    config.pairing = Some(PairingExtension::new(
+                        (0..num_curves)
+                            .map(|idx| PairingCurve::from_repr(idx).unwrap())
+                            .collect(),
+                    ));
+                }
+                (openvm_ecc_guest::OPCODE, SW_FUNCT3) => {
+                    if manifest.curves.keys().copied().ne(0..manifest.curves.len()) {
+                        return Err(VmConfigError::Invalid(
+                            "the curves set up by the guest are not numbered from 0 without gaps"
+                                .to_string(),
+                        ));
+                    }
+                    let known_curves = [
+                        SECP256K1_CONFIG.clone(),
+                        PairingCurve::Bn254.curve_config(),
+                        PairingCurve::Bls12_381.curve_config(),
+                    ];
+                    let curves = manifest
+                        .curves
+                        .iter()
+                        .map(|(idx, modulus)| {
+                            known_curves
+                                .iter()
+                                .find(|curve| &curve.modulus == modulus)
+                                .cloned()
+                                .ok_or_else(|| {
+                                    VmConfigError::Invalid(format!(
+                                        "curve {idx} has the unknown coordinate modulus {modulus}"
+                                    ))
+                                })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    config.ecc = Some(WeierstrassExtension::new(curves));
+                }
+                #[cfg(feature = "chacha")]
+                (openvm_chacha_guest::OPCODE, openvm_chacha_guest::FUNCT3) => {
+                    config.chacha = Some(UnitStruct {})
+                }
+                #[cfg(feature = "sort")]
+                (openvm_sort_guest::OPCODE, openvm_sort_guest::FUNCT3) => {
+                    config.sort = Some(UnitStruct {})
+                }
+                #[cfg(feature = "table")]
+                (openvm_table_guest::OPCODE, openvm_table_guest::FUNCT3) => {
+                    return Err(VmConfigError::Invalid(
+                        "the guest uses lookup tables, whose entries must be configured by hand"
+                            .to_string(),
+                    ));
+                }
+                _ => {
+                    return Err(VmConfigError::Invalid(format!(
+                        "unknown custom instruction with opcode {opcode:#x} and funct3 {funct3:#b}"
+                    )));
+                }
+            }
+        }
+
+        config.check_guest_manifest(manifest)?;
+        Ok(config)
+    }
 }
 
 impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
@@ -168,8 +513,20 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if self.keccak.is_some() {
             complex = complex.extend(&Keccak256)?;
         }
+        #[cfg(feature = "aes")]
+        if self.aes.is_some() {
+            complex = complex.extend(&Aes)?;
+        }
+        #[cfg(feature = "chacha")]
+        if self.chacha.is_some() {
+            complex = complex.extend(&ChaCha)?;
+        }
+        #[cfg(feature = "sort")]
+        if self.sort.is_some() {
+            complex = complex.extend(&Sort)?;
+        }
         if self.native.is_some() {
-            complex = complex.extend(&Native)?;
+            complex = complex.extend(&Native::default())?;
         }
 
         if let Some(rv32m) = self.rv32m {
@@ -198,15 +555,176 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if let Some(ref fp2) = self.fp2 {
             complex = complex.extend(fp2)?;
         }
+        if self.babybear.is_some() {
+            complex = complex.extend(&BabyBearExtension)?;
+        }
+        if self.babybear_ext4.is_some() {
+            complex = complex.extend(&BabyBearExt4Extension)?;
+        }
         if let Some(ref pairing) = self.pairing {
             complex = complex.extend(pairing)?;
         }
         if let Some(ref ecc) = self.ecc {
             complex = complex.extend(ecc)?;
         }
+        #[cfg(feature = "table")]
+        if let Some(ref table) = self.table {
+            complex = complex.extend(table)?;
+        }
 
         Ok(complex)
     }
+
+    fn validate(&self) -> Result<(), VmConfigError> {
+        self.system.config.validate()?;
+
+        let mut moduli = vec![];
+        if let Some(ref modular) = self.modular {
+            moduli.extend(
+                modular
+                    .supported_modulus
+                    .iter()
+                    .map(|m| ("modular", m.bits())),
+            );
+        }
+        if let Some(ref fp2) = self.fp2 {
+            moduli.extend(fp2.supported_modulus.iter().map(|m| ("fp2", m.bits())));
+        }
+        if let Some(ref ecc) = self.ecc {
+            for curve in &ecc.supported_curves {
+                moduli.push(("ecc", curve.modulus.bits()));
+                moduli.push(("ecc", curve.scalar.bits()));
+            }
+        }
+        for (extension, bits) in moduli {
+            if bits <= 1 || bits > MAX_MODULUS_BITS {
+                return Err(VmConfigError::Invalid(format!(
+                    "{extension} moduli must have between 2 and {MAX_MODULUS_BITS} bits, got {bits}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Named [SdkVmConfig]s for common kinds of guest programs. Each preset contains the previous
+/// one.
+///
+/// The presets with curves expect the guest to set up, with `moduli_init!`, the coordinate
+/// moduli of the pairing curves, then the scalar moduli of the pairing curves, then the
+/// coordinate and scalar moduli of secp256k1. `complex_init!` uses the coordinate moduli of the
+/// pairing curves, and `sw_init!` sets up the pairing curves and then secp256k1. The pairing
+/// curves are BN254, followed by BLS12-381 in [VmPreset::CryptoFull].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VmPreset {
+    /// `"minimal"`: RV32I and the IO instructions.
+    Minimal,
+    /// `"rv32im"`: adds RV32M.
+    Rv32im,
+    /// `"keccak"`: adds Keccak-256.
+    Keccak,
+    /// `"evm"`: adds 256-bit integers, modular arithmetic, the BN254 and secp256k1 curves and
+    /// BN254 pairings, as used by the EVM precompiles.
+    Evm,
+    /// `"crypto-full"`: adds AES, ChaCha20, sorting and the BLS12-381 curve and pairing. AES,
+    /// ChaCha20 and sorting are only added if the `aes`, `chacha` and `sort` features are enabled.
+    CryptoFull,
+}
+
+impl VmPreset {
+    pub const ALL: [VmPreset; 5] = [
+        VmPreset::Minimal,
+        VmPreset::Rv32im,
+        VmPreset::Keccak,
+        VmPreset::Evm,
+        VmPreset::CryptoFull,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VmPreset::Minimal => "minimal",
+            VmPreset::Rv32im => "rv32im",
+            VmPreset::Keccak => "keccak",
+            VmPreset::Evm => "evm",
+            VmPreset::CryptoFull => "crypto-full",
+        }
+    }
+}
+
+impl FromStr for VmPreset {
+    type Err = VmConfigError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| {
+                VmConfigError::Invalid(format!(
+                    "unknown preset {name:?}, expected one of: {}",
+                    Self::ALL.map(|preset| preset.name()).join(", ")
+                ))
+            })
+    }
+}
+
+impl From<VmPreset> for SdkVmConfig {
+    fn from(preset: VmPreset) -> Self {
+        let mut config = SdkVmConfig::builder()
+            .system(Default::default())
+            .rv32i(Default::default())
+            .io(Default::default())
+            .build();
+        if preset >= VmPreset::Rv32im {
+            config.rv32m = Some(Rv32M::default());
+        }
+        if preset >= VmPreset::Keccak {
+            config.keccak = Some(UnitStruct {});
+        }
+        if preset >= VmPreset::Evm {
+            let mut pairing_curves = vec![PairingCurve::Bn254];
+            if preset >= VmPreset::CryptoFull {
+                pairing_curves.push(PairingCurve::Bls12_381);
+            }
+            let curves: Vec<CurveConfig> = pairing_curves
+                .iter()
+                .map(PairingCurve::curve_config)
+                .chain([SECP256K1_CONFIG.clone()])
+                .collect();
+            let fp2_moduli: Vec<_> = curves[..pairing_curves.len()]
+                .iter()
+                .map(|curve| curve.modulus.clone())
+                .collect();
+            let moduli = fp2_moduli
+                .iter()
+                .cloned()
+                .chain(
+                    curves[..pairing_curves.len()]
+                        .iter()
+                        .map(|curve| curve.scalar.clone()),
+                )
+                .chain([
+                    SECP256K1_CONFIG.modulus.clone(),
+                    SECP256K1_CONFIG.scalar.clone(),
+                ])
+                .collect();
+
+            config.bigint = Some(Int256::default());
+            config.modular = Some(ModularExtension::new(moduli));
+            config.fp2 = Some(Fp2Extension::new(fp2_moduli));
+            config.ecc = Some(WeierstrassExtension::new(curves));
+            config.pairing = Some(PairingExtension::new(pairing_curves));
+        }
+        #[cfg(any(feature = "aes", feature = "chacha", feature = "sort"))]
+        if preset >= VmPreset::CryptoFull {
+            #[cfg(feature = "aes")]
+            config.aes = Some(UnitStruct {});
+            #[cfg(feature = "chacha")]
+            config.chacha = Some(UnitStruct {});
+            #[cfg(feature = "sort")]
+            config.sort = Some(UnitStruct {});
+        }
+        config
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -251,8 +769,41 @@ impl From<Keccak256> for UnitStruct {
     }
 }
 
+#[cfg(feature = "aes")]
+impl From<Aes> for UnitStruct {
+    fn from(_: Aes) -> Self {
+        UnitStruct {}
+    }
+}
+
+#[cfg(feature = "sort")]
+impl From<Sort> for UnitStruct {
+    fn from(_: Sort) -> Self {
+        UnitStruct {}
+    }
+}
+
+#[cfg(feature = "chacha")]
+impl From<ChaCha> for UnitStruct {
+    fn from(_: ChaCha) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Native> for UnitStruct {
     fn from(_: Native) -> Self {
         UnitStruct {}
     }
 }
+
+impl From<BabyBearExtension> for UnitStruct {
+    fn from(_: BabyBearExtension) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<BabyBearExt4Extension> for UnitStruct {
+    fn from(_: BabyBearExt4Extension) -> Self {
+        UnitStruct {}
+    }
+}
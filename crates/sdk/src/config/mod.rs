@@ -1,8 +1,12 @@
-use openvm_circuit::arch::instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES;
-use openvm_native_compiler::conversion::CompilerOptions;
+use openvm_circuit::arch::{
+    instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES, VmConfig, VmConfigError,
+};
+use openvm_native_compiler::{conversion::CompilerOptions, ir::DIGEST_SIZE};
 use openvm_stark_sdk::config::FriParameters;
 use serde::{Deserialize, Serialize};
 
+use crate::F;
+
 mod global;
 pub use global::*;
 
@@ -37,10 +41,71 @@ pub struct AggStarkConfig {
     pub leaf_fri_params: FriParameters,
     pub internal_fri_params: FriParameters,
     pub root_fri_params: FriParameters,
+    /// How the root verifier exposes the user public values of the App VM.
+    #[serde(default)]
+    pub public_values_aggregation: PublicValuesAggregation,
+    /// Which App VM executions the root verifier accepts.
+    #[serde(default)]
+    pub exit_policy: ExitPolicy,
     /// Only for AggVM debugging.
     pub compiler_options: CompilerOptions,
 }
 
+/// How the root verifier turns the user public values of the App VM into the public values of
+/// the root proof, and therefore of the final EVM proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicValuesAggregation {
+    /// Expose every user public value as is.
+    #[default]
+    Concatenate,
+    /// Expose a single digest of the user public values. Starting from zero, the digest is
+    /// compressed with each chunk of `DIGEST_SIZE` public values in order, see
+    /// [hash_chain_public_values](crate::verifier::root::hash_chain_public_values).
+    HashChain,
+    /// Expose the `num_outputs` values returned by a fold function over the user public values.
+    /// The function is written in the DSL and supplied at keygen, see
+    /// [AggStarkProvingKey::keygen_with_public_values_fold](crate::keygen::AggStarkProvingKey::keygen_with_public_values_fold).
+    Fold { num_outputs: usize },
+}
+
+/// Which App VM executions the root verifier accepts. Aggregation always rejects chains where a
+/// segment other than the last one terminated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitPolicy {
+    /// Only accept executions which terminated with exit code 0.
+    #[default]
+    RequireSuccess,
+    /// Accept executions which terminated with any exit code. The root verifier exposes
+    /// `[is_terminate, exit_code]` before the public values.
+    RequireTermination,
+    /// Also accept executions which were suspended before terminating. The root verifier exposes
+    /// `[is_terminate, exit_code]` before the public values, which are only meaningful if
+    /// `is_terminate` is 1.
+    AllowSuspended,
+}
+
+impl ExitPolicy {
+    /// Number of values the root verifier exposes about the exit of the execution.
+    pub fn num_exit_values(&self) -> usize {
+        match self {
+            Self::RequireSuccess => 0,
+            Self::RequireTermination | Self::AllowSuspended => 2,
+        }
+    }
+}
+
+impl PublicValuesAggregation {
+    /// Number of public values exposed by the root verifier for `num_user_public_values` user
+    /// public values.
+    pub fn num_outputs(&self, num_user_public_values: usize) -> usize {
+        match self {
+            Self::Concatenate => num_user_public_values,
+            Self::HashChain => DIGEST_SIZE,
+            Self::Fold { num_outputs } => *num_outputs,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Halo2Config {
     /// Log degree for the outer recursion verifier circuit.
@@ -73,6 +138,41 @@ impl<VC> AppConfig<VC> {
     }
 }
 
+impl<VC: VmConfig<F>> AppConfig<VC> {
+    pub fn validate(&self) -> Result<(), VmConfigError> {
+        validate_fri_params("app_fri_params", &self.app_fri_params.fri_params)?;
+        validate_fri_params("leaf_fri_params", &self.leaf_fri_params.fri_params)?;
+        self.app_vm_config.validate()
+    }
+
+    /// Parses and validates an application configuration, e.g. from `openvm.toml`.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, VmConfigError> {
+        let config: Self = toml::from_str(toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, VmConfigError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+fn validate_fri_params(name: &str, fri_params: &FriParameters) -> Result<(), VmConfigError> {
+    if fri_params.log_blowup == 0 {
+        return Err(VmConfigError::Invalid(format!(
+            "{name}.fri_params.log_blowup must be positive"
+        )));
+    }
+    if fri_params.num_queries == 0 {
+        return Err(VmConfigError::Invalid(format!(
+            "{name}.fri_params.num_queries must be positive"
+        )));
+    }
+    Ok(())
+}
+
 impl Default for AggStarkConfig {
     fn default() -> Self {
         Self {
@@ -86,6 +186,8 @@ impl Default for AggStarkConfig {
             root_fri_params: FriParameters::standard_with_100_bits_conjectured_security(
                 DEFAULT_ROOT_BLOWUP,
             ),
+            public_values_aggregation: Default::default(),
+            exit_policy: Default::default(),
             compiler_options: Default::default(),
         }
     }
@@ -0,0 +1,233 @@
+pub use openvm_native_recursion::halo2::wrapper::NUM_ACCUMULATOR_INSTANCES;
+use openvm_native_recursion::halo2::EvmProof;
+use openvm_stark_sdk::{openvm_stark_backend::p3_field::PrimeField, p3_bn254_fr::Bn254Fr};
+use serde_json::json;
+use snark_verifier_sdk::snark_verifier::loader::evm::encode_calldata as encode_evm_calldata;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{commit::AppExecutionCommit, static_verifier::NUM_APP_COMMIT_PUBLIC_VALUES, F};
+
+/// Generates the Solidity source of a contract which only accepts EVM proofs of a specific app.
+///
+/// The generic EVM verifier contract accepts proofs of any app exe and any app VM config; the
+/// app commitments are only part of its instances. The generated contract fixes the instance
+/// layout (accumulator, `exe_commit`, `leaf_verifier_commit`, user public values), pins both
+/// commitments to `app_commit`, and forwards the calldata to the deployed generic verifier.
+pub fn generate_app_verifier_contract(
+    app_commit: &AppExecutionCommit<F>,
+    num_public_values: usize,
+) -> String {
+    let exe_commit = bn254_to_hex(app_commit.exe_commit_to_bn254());
+    let leaf_verifier_commit = bn254_to_hex(app_commit.app_config_commit_to_bn254());
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity 0.8.19;
+
+contract OpenVmAppVerifier {{
+    uint256 public constant APP_EXE_COMMIT = {exe_commit};
+    uint256 public constant APP_VM_COMMIT = {leaf_verifier_commit};
+    uint256 public constant NUM_PUBLIC_VALUES = {num_public_values};
+
+    address public immutable verifier;
+
+    constructor(address _verifier) {{
+        verifier = _verifier;
+    }}
+
+    function verify(
+        uint256[{NUM_ACCUMULATOR_INSTANCES}] calldata accumulator,
+        uint256[{num_public_values}] calldata publicValues,
+        bytes calldata proof
+    ) external view returns (bool) {{
+        (bool success, ) = verifier.staticcall(
+            abi.encodePacked(accumulator, APP_EXE_COMMIT, APP_VM_COMMIT, publicValues, proof)
+        );
+        return success;
+    }}
+}}
+"#
+    )
+}
+
+/// Returns the JSON ABI of the contract generated by [generate_app_verifier_contract].
+pub fn generate_app_verifier_abi(num_public_values: usize) -> String {
+    let abi = json!([
+        {
+            "type": "constructor",
+            "inputs": [{ "name": "_verifier", "type": "address", "internalType": "address" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "verify",
+            "inputs": [
+                {
+                    "name": "accumulator",
+                    "type": format!("uint256[{NUM_ACCUMULATOR_INSTANCES}]"),
+                    "internalType": format!("uint256[{NUM_ACCUMULATOR_INSTANCES}]")
+                },
+                {
+                    "name": "publicValues",
+                    "type": format!("uint256[{num_public_values}]"),
+                    "internalType": format!("uint256[{num_public_values}]")
+                },
+                { "name": "proof", "type": "bytes", "internalType": "bytes" }
+            ],
+            "outputs": [{ "name": "", "type": "bool", "internalType": "bool" }],
+            "stateMutability": "view"
+        },
+        constant_getter_abi("APP_EXE_COMMIT", "uint256"),
+        constant_getter_abi("APP_VM_COMMIT", "uint256"),
+        constant_getter_abi("NUM_PUBLIC_VALUES", "uint256"),
+        constant_getter_abi("verifier", "address"),
+    ]);
+    serde_json::to_string_pretty(&abi).unwrap()
+}
+
+fn constant_getter_abi(name: &str, ty: &str) -> serde_json::Value {
+    json!({
+        "type": "function",
+        "name": name,
+        "inputs": [],
+        "outputs": [{ "name": "", "type": ty, "internalType": ty }],
+        "stateMutability": "view"
+    })
+}
+
+/// Encodes `evm_proof` as calldata for the generic EVM verifier contract: every instance as a
+/// big-endian 32-byte word, followed by the proof.
+pub fn encode_calldata(evm_proof: &EvmProof) -> Vec<u8> {
+    encode_evm_calldata(&evm_proof.instances, &evm_proof.proof)
+}
+
+/// Encodes `evm_proof` as calldata for `verify` of the contract generated by
+/// [generate_app_verifier_contract]. The app commitments are left out since the contract
+/// supplies them itself.
+pub fn encode_app_verifier_calldata(evm_proof: &EvmProof) -> Vec<u8> {
+    const WORD: usize = 32;
+    let words = encode_evm_calldata(&evm_proof.instances, &[]);
+    assert!(
+        words.len() >= (NUM_ACCUMULATOR_INSTANCES + NUM_APP_COMMIT_PUBLIC_VALUES) * WORD,
+        "EVM proof is missing the accumulator or app commitments"
+    );
+    let (accumulator, rest) = words.split_at(NUM_ACCUMULATOR_INSTANCES * WORD);
+    // Skip `exe_commit` and `leaf_verifier_commit`.
+    let public_values = &rest[NUM_APP_COMMIT_PUBLIC_VALUES * WORD..];
+    let num_public_values = public_values.len() / WORD;
+
+    let mut calldata = keccak256(
+        format!("verify(uint256[{NUM_ACCUMULATOR_INSTANCES}],uint256[{num_public_values}],bytes)")
+            .as_bytes(),
+    )[..4]
+        .to_vec();
+    calldata.extend_from_slice(accumulator);
+    calldata.extend_from_slice(public_values);
+    // Static arrays are encoded in place, so the head ends with the offset of `proof`.
+    let proof_offset = (NUM_ACCUMULATOR_INSTANCES + num_public_values + 1) * WORD;
+    calldata.extend_from_slice(&usize_to_word(proof_offset));
+    calldata.extend_from_slice(&usize_to_word(evm_proof.proof.len()));
+    calldata.extend_from_slice(&evm_proof.proof);
+    let padding = evm_proof.proof.len().next_multiple_of(WORD) - evm_proof.proof.len();
+    calldata.resize(calldata.len() + padding, 0);
+    calldata
+}
+
+fn usize_to_word(x: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[32 - std::mem::size_of::<usize>()..].copy_from_slice(&x.to_be_bytes());
+    word
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+fn bn254_to_hex(x: Bn254Fr) -> String {
+    format!("0x{:064x}", x.as_canonical_biguint())
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_native_recursion::halo2::EvmProof;
+    use openvm_stark_sdk::openvm_stark_backend::p3_field::AbstractField;
+    use snark_verifier_sdk::snark_verifier::halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    use super::{
+        encode_app_verifier_calldata, encode_calldata, generate_app_verifier_abi,
+        generate_app_verifier_contract, keccak256, usize_to_word, NUM_ACCUMULATOR_INSTANCES,
+        NUM_APP_COMMIT_PUBLIC_VALUES,
+    };
+    use crate::{commit::AppExecutionCommit, F};
+
+    #[test]
+    fn test_evm_instance_layout() {
+        // The KZG accumulator of the Halo2 wrapper, followed by `exe_commit` and
+        // `leaf_verifier_commit`.
+        assert_eq!(NUM_ACCUMULATOR_INSTANCES, 12);
+        assert_eq!(NUM_APP_COMMIT_PUBLIC_VALUES, 2);
+    }
+
+    /// Regenerate the snapshots in `tests/snapshots` if the contract changes on purpose.
+    #[test]
+    fn test_app_verifier_contract_snapshot() {
+        let mut app_commit = AppExecutionCommit {
+            leaf_vm_verifier_commit: [F::ZERO; 8],
+            exe_commit: [F::ZERO; 8],
+        };
+        app_commit.exe_commit[0] = F::ONE;
+        app_commit.leaf_vm_verifier_commit[0] = F::TWO;
+        assert_eq!(
+            generate_app_verifier_contract(&app_commit, 3),
+            include_str!("../tests/snapshots/app_verifier.sol")
+        );
+        let abi: serde_json::Value = serde_json::from_str(&generate_app_verifier_abi(3)).unwrap();
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/snapshots/app_verifier.abi.json")).unwrap();
+        assert_eq!(abi, expected);
+    }
+
+    #[test]
+    fn test_app_verifier_calldata() {
+        let num_public_values = 3;
+        let evm_proof = EvmProof {
+            instances: vec![(0..NUM_ACCUMULATOR_INSTANCES
+                + NUM_APP_COMMIT_PUBLIC_VALUES
+                + num_public_values)
+                .map(|i| Fr::from(i as u64 + 1))
+                .collect()],
+            proof: vec![0xab; 40],
+        };
+        let calldata = encode_app_verifier_calldata(&evm_proof);
+        let generic = encode_calldata(&evm_proof);
+
+        assert_eq!(
+            calldata[..4],
+            keccak256(b"verify(uint256[12],uint256[3],bytes)")[..4]
+        );
+        let args = &calldata[4..];
+        assert_eq!(args.len() % 32, 0);
+        let word = |i: usize| &args[32 * i..32 * (i + 1)];
+        // The contract packs the arguments back into the generic calldata, with the app
+        // commitments after the accumulator.
+        for i in 0..NUM_ACCUMULATOR_INSTANCES {
+            assert_eq!(word(i), &generic[32 * i..32 * (i + 1)]);
+        }
+        for i in 0..num_public_values {
+            let generic_i = NUM_ACCUMULATOR_INSTANCES + NUM_APP_COMMIT_PUBLIC_VALUES + i;
+            assert_eq!(
+                word(NUM_ACCUMULATOR_INSTANCES + i),
+                &generic[32 * generic_i..32 * (generic_i + 1)]
+            );
+        }
+        let head_len = NUM_ACCUMULATOR_INSTANCES + num_public_values + 1;
+        assert_eq!(word(head_len - 1), usize_to_word(32 * head_len));
+        assert_eq!(word(head_len), usize_to_word(40));
+        assert_eq!(args[32 * (head_len + 1)..][..40], evm_proof.proof);
+        assert_eq!(args.len(), 32 * (head_len + 1) + 64);
+        assert_eq!(generic[generic.len() - 40..], evm_proof.proof);
+    }
+}
@@ -9,6 +9,7 @@ use openvm_native_recursion::halo2::{wrapper::EvmVerifier, EvmProof};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
+    commit::AppProgramManifest,
     keygen::{AggProvingKey, AppProvingKey, AppVerifyingKey},
     prover::vm::ContinuationVmProof,
     F, SC,
@@ -54,6 +55,17 @@ pub fn write_app_proof_to_file<P: AsRef<Path>>(
     write_to_file_bitcode(path, proof)
 }
 
+pub fn read_app_manifest_from_file<P: AsRef<Path>>(path: P) -> Result<AppProgramManifest> {
+    read_from_file_json(path)
+}
+
+pub fn write_app_manifest_to_file<P: AsRef<Path>>(
+    manifest: AppProgramManifest,
+    path: P,
+) -> Result<()> {
+    write_to_file_json(path, manifest)
+}
+
 pub fn read_agg_pk_from_file<P: AsRef<Path>>(path: P) -> Result<AggProvingKey> {
     read_from_file_bitcode(path)
 }
@@ -93,6 +105,21 @@ pub(crate) fn write_to_file_bitcode<T: Serialize, P: AsRef<Path>>(path: P, data:
     Ok(())
 }
 
+pub(crate) fn read_from_file_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+    let data = std::fs::read(path)?;
+    let ret = serde_json::from_slice(&data)?;
+    Ok(ret)
+}
+
+pub(crate) fn write_to_file_json<T: Serialize, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
+    let bytes = serde_json::to_vec_pretty(&data)?;
+    if let Some(parent) = path.as_ref().parent() {
+        create_dir_all(parent)?;
+    }
+    write(path, bytes)?;
+    Ok(())
+}
+
 pub(crate) fn read_from_file_bytes<T: From<Vec<u8>>, P: AsRef<Path>>(path: P) -> Result<T> {
     let bytes = read(path)?;
     Ok(T::from(bytes))
@@ -12,7 +12,6 @@ use openvm_circuit::{
     utils::next_power_of_two_or_zero,
 };
 use openvm_native_circuit::NativeConfig;
-use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::hints::Hintable;
 use openvm_rv32im_circuit::Rv32ImConfig;
 use openvm_stark_sdk::{
@@ -27,6 +26,7 @@ use openvm_stark_sdk::{
 };
 
 use crate::{
+    commit::app_config_digest,
     prover::vm::{
         local::VmLocalProver, types::VmProvingKey, ContinuationVmProof, ContinuationVmProver,
         SingleSegmentVmProver,
@@ -48,8 +48,8 @@ pub(super) fn compute_root_proof_heights(
     root_vm_config: NativeConfig,
     root_exe: VmExe<F>,
     dummy_internal_proof: &Proof<SC>,
+    num_user_public_values: usize,
 ) -> (Vec<usize>, VmComplexTraceHeights) {
-    let num_user_public_values = root_vm_config.system.num_public_values - 2 * DIGEST_SIZE;
     let root_input = RootVmVerifierInput {
         proofs: vec![dummy_internal_proof.clone()],
         public_values: vec![F::ZERO; num_user_public_values],
@@ -127,6 +127,7 @@ fn dummy_leaf_proof_impl<VC: VmConfig<F>>(
     let leaf_program = LeafVmVerifierConfig {
         app_fri_params: app_vm_pk.fri_params,
         app_system_config: app_vm_pk.vm_config.system().clone(),
+        app_config_digest: app_config_digest(&app_vm_pk.vm_config),
         compiler_options: Default::default(),
     }
     .build_program(&app_vm_pk.vm_pk.get_vk());
@@ -153,7 +154,7 @@ fn dummy_riscv_app_vm_pk(
 ) -> VmProvingKey<SC, Rv32ImConfig> {
     let vm_config = Rv32ImConfig::with_public_values(num_public_values);
     let vm = VirtualMachine::new(BabyBearPoseidon2Engine::new(fri_params), vm_config.clone());
-    let vm_pk = vm.keygen();
+    let vm_pk = vm.keygen().unwrap();
     VmProvingKey {
         fri_params,
         vm_config,
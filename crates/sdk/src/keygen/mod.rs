@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use derivative::Derivative;
 use dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm};
+use eyre::Result;
 use openvm_circuit::{
     arch::{VirtualMachine, VmConfig},
     system::program::trace::VmCommittedExe,
@@ -27,13 +28,17 @@ use openvm_stark_sdk::{
 };
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "static-verifier-store")]
+use crate::static_verifier::StaticVerifierStore;
 use crate::{
-    commit::babybear_digest_to_bn254,
-    config::{AggConfig, AggStarkConfig, AppConfig},
+    commit::{app_config_digest, babybear_digest_to_bn254},
+    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
     keygen::perm::AirIdPermutation,
     prover::vm::types::VmProvingKey,
     verifier::{
-        internal::InternalVmVerifierConfig, leaf::LeafVmVerifierConfig, root::RootVmVerifierConfig,
+        internal::InternalVmVerifierConfig,
+        leaf::LeafVmVerifierConfig,
+        root::{PublicValuesFold, RootVmVerifierConfig},
     },
     NonRootCommittedExe, RootSC, F, SC,
 };
@@ -86,7 +91,7 @@ where
         let app_engine = BabyBearPoseidon2Engine::new(config.app_fri_params.fri_params);
         let app_vm_pk = {
             let vm = VirtualMachine::new(app_engine, config.app_vm_config.clone());
-            let vm_pk = vm.keygen();
+            let vm_pk = vm.keygen().unwrap();
             assert!(
                 vm_pk.max_constraint_degree
                     <= config.app_fri_params.fri_params.max_constraint_degree()
@@ -103,6 +108,7 @@ where
             let leaf_program = LeafVmVerifierConfig {
                 app_fri_params: config.app_fri_params.fri_params,
                 app_system_config: config.app_vm_config.system().clone(),
+                app_config_digest: app_config_digest(&config.app_vm_config),
                 compiler_options: config.compiler_options,
             }
             .build_program(&app_vm_pk.vm_pk.get_vk());
@@ -148,7 +154,21 @@ impl AggStarkProvingKey {
             .in_scope(|| Self::dummy_proof_and_keygen(config).0)
     }
 
+    /// Keygen for [PublicValuesAggregation::Fold](crate::config::PublicValuesAggregation::Fold),
+    /// where the root verifier exposes the outputs of `fold` over the user public values.
+    pub fn keygen_with_public_values_fold(config: AggStarkConfig, fold: PublicValuesFold) -> Self {
+        tracing::info_span!("agg_stark_keygen", group = "agg_stark_keygen")
+            .in_scope(|| Self::dummy_proof_and_keygen_impl(config, Some(fold)).0)
+    }
+
     pub fn dummy_proof_and_keygen(config: AggStarkConfig) -> (Self, Proof<SC>) {
+        Self::dummy_proof_and_keygen_impl(config, None)
+    }
+
+    fn dummy_proof_and_keygen_impl(
+        config: AggStarkConfig,
+        public_values_fold: Option<PublicValuesFold>,
+    ) -> (Self, Proof<SC>) {
         let leaf_vm_config = config.leaf_vm_config();
         let internal_vm_config = config.internal_vm_config();
         let root_vm_config = config.root_verifier_vm_config();
@@ -156,7 +176,7 @@ impl AggStarkProvingKey {
         let leaf_engine = BabyBearPoseidon2Engine::new(config.leaf_fri_params);
         let leaf_vm_pk = Arc::new({
             let vm = VirtualMachine::new(leaf_engine, leaf_vm_config.clone());
-            let vm_pk = vm.keygen();
+            let vm_pk = vm.keygen().unwrap();
             assert!(vm_pk.max_constraint_degree <= config.leaf_fri_params.max_constraint_degree());
             VmProvingKey {
                 fri_params: config.leaf_fri_params,
@@ -169,7 +189,7 @@ impl AggStarkProvingKey {
         let internal_engine = BabyBearPoseidon2Engine::new(config.internal_fri_params);
         let internal_vm = VirtualMachine::new(internal_engine, internal_vm_config.clone());
         let internal_vm_pk = Arc::new({
-            let vm_pk = internal_vm.keygen();
+            let vm_pk = internal_vm.keygen().unwrap();
             assert!(
                 vm_pk.max_constraint_degree <= config.internal_fri_params.max_constraint_degree()
             );
@@ -206,6 +226,9 @@ impl AggStarkProvingKey {
                 internal_fri_params: config.internal_fri_params,
                 num_public_values: config.max_num_user_public_values,
                 internal_vm_verifier_commit: internal_committed_exe.get_program_commit().into(),
+                public_values_aggregation: config.public_values_aggregation,
+                public_values_fold,
+                exit_policy: config.exit_policy,
                 compiler_options: config.compiler_options,
             }
             .build_program(&leaf_vm_vk, &internal_vm_vk);
@@ -215,13 +238,14 @@ impl AggStarkProvingKey {
             ));
 
             let vm = VirtualMachine::new(root_engine, root_vm_config.clone());
-            let mut vm_pk = vm.keygen();
+            let mut vm_pk = vm.keygen().unwrap();
             assert!(vm_pk.max_constraint_degree <= config.root_fri_params.max_constraint_degree());
 
             let (air_heights, _internal_heights) = compute_root_proof_heights(
                 root_vm_config.clone(),
                 root_committed_exe.exe.clone(),
                 &internal_proof,
+                config.max_num_user_public_values,
             );
             let root_air_perm = AirIdPermutation::compute(&air_heights);
             root_air_perm.permute(&mut vm_pk.per_air);
@@ -234,6 +258,7 @@ impl AggStarkProvingKey {
                 }),
                 root_committed_exe,
                 air_heights,
+                num_user_public_values: config.max_num_user_public_values,
             }
         };
 
@@ -252,13 +277,20 @@ impl AggStarkProvingKey {
         self.internal_committed_exe.get_program_commit().into()
     }
 
+    /// Generates the dummy internal proof that [Self::dummy_proof_and_keygen] returns, for a
+    /// proving key that has been loaded instead of generated.
+    pub fn dummy_internal_proof(&self) -> Proof<SC> {
+        dummy_internal_proof_riscv_app_vm(
+            self.leaf_vm_pk.clone(),
+            self.internal_vm_pk.clone(),
+            self.internal_committed_exe.clone(),
+            self.root_verifier_pk.num_user_public_values,
+        )
+    }
+
+    /// Number of user public values of the App VM the root verifier accepts.
     pub fn num_public_values(&self) -> usize {
-        self.root_verifier_pk
-            .vm_pk
-            .vm_config
-            .system
-            .num_public_values
-            - (2 * DIGEST_SIZE)
+        self.root_verifier_pk.num_user_public_values
     }
 }
 
@@ -278,6 +310,10 @@ pub struct RootVerifierProvingKey {
     pub root_committed_exe: Arc<VmCommittedExe<RootSC>>,
     /// The constant trace heights, ordered by AIR ID.
     pub air_heights: Vec<usize>,
+    /// Number of user public values of the App VM. The root proof exposes them aggregated
+    /// according to the [PublicValuesAggregation](crate::config::PublicValuesAggregation) of
+    /// the root verifier.
+    pub num_user_public_values: usize,
     // The following is currently not used:
     // The constant trace heights, ordered according to an internal ordering determined by the `NativeConfig`.
     // pub internal_heights: VmComplexTraceHeights,
@@ -301,14 +337,52 @@ impl AggProvingKey {
         } = config;
         let (agg_stark_pk, dummy_internal_proof) =
             AggStarkProvingKey::dummy_proof_and_keygen(agg_stark_config);
-        let dummy_root_proof = agg_stark_pk
-            .root_verifier_pk
-            .generate_dummy_root_proof(dummy_internal_proof);
+        let root_verifier_pk = &agg_stark_pk.root_verifier_pk;
         // FIXME: Halo2VerifierProvingKey is not Send + Sync because Array/Usize use Rc<RefCell>.
-        let verifier = agg_stark_pk.root_verifier_pk.keygen_static_verifier(
+        let verifier = root_verifier_pk.keygen_static_verifier(
             &reader.read_params(halo2_config.verifier_k),
-            dummy_root_proof,
+            root_verifier_pk.generate_dummy_root_proof(dummy_internal_proof),
         );
+        Self::keygen_wrapper(agg_stark_pk, verifier, halo2_config, reader)
+    }
+
+    /// Same as [AggProvingKey::keygen], but loads the aggregation STARK proving key and the
+    /// static verifier from `store` if they have been generated before, and stores them
+    /// otherwise. Only the wrapper circuit is generated on a full cache hit.
+    #[cfg(feature = "static-verifier-store")]
+    #[tracing::instrument(level = "info", fields(group = "agg_keygen"), skip_all)]
+    pub fn keygen_with_store(
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+        store: &StaticVerifierStore,
+    ) -> Result<Self> {
+        let AggConfig {
+            agg_stark_config,
+            halo2_config,
+        } = config;
+        let (agg_stark_pk, dummy_internal_proof) =
+            store.load_or_keygen_agg_stark(agg_stark_config)?;
+        let root_verifier_pk = &agg_stark_pk.root_verifier_pk;
+        let verifier =
+            store.load_or_keygen(root_verifier_pk, reader, halo2_config.verifier_k, || {
+                let dummy_internal_proof =
+                    dummy_internal_proof.unwrap_or_else(|| agg_stark_pk.dummy_internal_proof());
+                root_verifier_pk.generate_dummy_root_proof(dummy_internal_proof)
+            })?;
+        Ok(Self::keygen_wrapper(
+            agg_stark_pk,
+            verifier,
+            halo2_config,
+            reader,
+        ))
+    }
+
+    fn keygen_wrapper(
+        agg_stark_pk: AggStarkProvingKey,
+        verifier: Halo2VerifierProvingKey,
+        halo2_config: Halo2Config,
+        reader: &impl Halo2ParamsReader,
+    ) -> Self {
         let dummy_snark = verifier.generate_dummy_snark(reader);
         let wrapper = if let Some(wrapper_k) = halo2_config.wrapper_k {
             Halo2WrapperProvingKey::keygen(&reader.read_params(wrapper_k), dummy_snark)
@@ -330,7 +404,9 @@ pub fn leaf_keygen(fri_params: FriParameters) -> Arc<VmProvingKey<SC, NativeConf
     };
     let vm_config = agg_config.leaf_vm_config();
     let leaf_engine = BabyBearPoseidon2Engine::new(fri_params);
-    let leaf_vm_pk = VirtualMachine::new(leaf_engine, vm_config.clone()).keygen();
+    let leaf_vm_pk = VirtualMachine::new(leaf_engine, vm_config.clone())
+        .keygen()
+        .unwrap();
     Arc::new(VmProvingKey {
         fri_params,
         vm_config,
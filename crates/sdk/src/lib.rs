@@ -1,8 +1,8 @@
 extern crate core;
 
-use std::{fs::read, panic::catch_unwind, path::Path, sync::Arc};
+use std::{fs::read, io::Write, mem, panic::catch_unwind, path::Path, sync::Arc};
 
-use commit::commit_app_exe;
+use commit::{commit_app_exe, AppExecutionCommit};
 use config::AppConfig;
 use eyre::Result;
 use keygen::{AppProvingKey, AppVerifyingKey};
@@ -10,7 +10,7 @@ use openvm_build::{
     build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
 };
 use openvm_circuit::{
-    arch::{instructions::exe::VmExe, ExecutionError, VmConfig, VmExecutor},
+    arch::{instructions::exe::VmExe, ExecutionError, FastExecutionResult, VmConfig, VmExecutor},
     system::{memory::tree::public_values::extract_public_values, program::trace::VmCommittedExe},
 };
 use openvm_native_recursion::{
@@ -42,7 +42,15 @@ use prover::vm::ContinuationVmProof;
 
 pub mod commit;
 pub mod config;
+#[cfg(feature = "evm-contract")]
+pub mod evm;
+#[cfg(feature = "bench-metrics")]
+pub mod prometheus;
+pub mod proof_size;
 pub mod prover;
+pub mod receipt;
+#[cfg(feature = "srs-download")]
+pub mod srs;
 pub mod static_verifier;
 
 pub mod keygen;
@@ -53,7 +61,7 @@ pub use stdin::*;
 pub mod fs;
 
 use crate::{
-    config::AggConfig,
+    config::{AggConfig, SdkVmConfig},
     keygen::AggProvingKey,
     prover::{AppProver, ContinuationProver},
 };
@@ -99,6 +107,16 @@ impl Sdk {
         VmExe::from_elf(elf, transpiler)
     }
 
+    /// Transpiles `elf` with the transpiler of `vm_config`. Before transpiling, checks that the
+    /// guest only uses extensions, moduli and curves that `vm_config` supports, so that a
+    /// mismatch is reported with the missing extensions instead of failing during execution.
+    pub fn transpile_for_config(&self, elf: Elf, vm_config: &SdkVmConfig) -> Result<VmExe<F>> {
+        vm_config.check_guest_manifest(elf.manifest())?;
+        let exe = VmExe::from_elf(elf, vm_config.transpiler())?;
+        VmConfig::<F>::check_program(vm_config, &exe.program)?;
+        Ok(exe)
+    }
+
     pub fn execute<VC: VmConfig<F>>(
         &self,
         exe: VmExe<F>,
@@ -119,6 +137,45 @@ impl Sdk {
         Ok(public_values)
     }
 
+    /// Executes `exe` without keeping anything needed to prove it, see [VmExecutor::run_fast].
+    /// Use it to iterate on guest logic and to count cycles and trace heights.
+    pub fn run_fast<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+        inputs: StdIn,
+    ) -> Result<FastExecutionResult<F>, ExecutionError> {
+        VmExecutor::new(vm_config).run_fast(exe, inputs)
+    }
+
+    /// Like [Self::execute], and also writes the guest call graph to `callgrind` in the callgrind
+    /// format, see
+    /// [CallGraphProfiler](openvm_circuit::metrics::call_graph::CallGraphProfiler). Guest
+    /// functions are only named if `exe` was transpiled with the transpiler's `function-span`
+    /// feature.
+    pub fn execute_with_callgrind<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        mut vm_config: VC,
+        inputs: StdIn,
+        callgrind: impl Write,
+    ) -> Result<Vec<F>> {
+        vm_config.system_mut().profile_call_graph = true;
+        let vm = VmExecutor::new(vm_config);
+        let mut segments = vm.execute_segments(exe, inputs)?;
+        let last = segments.last_mut().unwrap();
+        last.call_graph
+            .as_ref()
+            .expect("call graph profiling is enabled")
+            .write_callgrind(callgrind)?;
+        let public_values = extract_public_values(
+            &vm.config.system().memory_config.memory_dimensions(),
+            vm.config.system().num_public_values,
+            mem::take(&mut last.final_memory).as_ref().unwrap(),
+        );
+        Ok(public_values)
+    }
+
     pub fn commit_app_exe(
         &self,
         app_fri_params: FriParameters,
@@ -201,6 +258,33 @@ impl Sdk {
         Ok(evm_verifier)
     }
 
+    /// Generates the Solidity source of a contract which forwards proofs of the app with
+    /// `app_commit` to the generic verifier contract of `agg_pk`.
+    #[cfg(feature = "evm-contract")]
+    pub fn generate_app_verifier_contract(
+        &self,
+        agg_pk: &AggProvingKey,
+        app_commit: &AppExecutionCommit<F>,
+    ) -> String {
+        evm::generate_app_verifier_contract(app_commit, Self::num_evm_public_values(agg_pk))
+    }
+
+    /// Returns the JSON ABI of the contract from [Sdk::generate_app_verifier_contract].
+    #[cfg(feature = "evm-contract")]
+    pub fn generate_app_verifier_abi(&self, agg_pk: &AggProvingKey) -> String {
+        evm::generate_app_verifier_abi(Self::num_evm_public_values(agg_pk))
+    }
+
+    /// Number of user public values exposed by EVM proofs of `agg_pk`.
+    #[cfg(feature = "evm-contract")]
+    fn num_evm_public_values(agg_pk: &AggProvingKey) -> usize {
+        // The root verifier exposes `exe_commit` and `leaf_verifier_commit` before the user
+        // public values.
+        agg_pk.halo2_pk.wrapper.pinning.metadata.num_pvs[0]
+            - evm::NUM_ACCUMULATOR_INSTANCES
+            - static_verifier::NUM_APP_COMMIT_PUBLIC_VALUES
+    }
+
     pub fn verify_evm_proof(&self, evm_verifier: &EvmVerifier, evm_proof: &EvmProof) -> bool {
         // FIXME: we should return the concrete error.
         catch_unwind(|| {
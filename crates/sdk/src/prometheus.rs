@@ -0,0 +1,19 @@
+//! Exposes the metrics emitted with the `bench-metrics` feature, such as trace generation and
+//! proving times and trace cells, to Prometheus, so that a long-running prover can be monitored.
+
+use std::net::SocketAddr;
+
+use eyre::Result;
+use metrics_exporter_prometheus::PrometheusBuilder;
+
+/// Installs a global metrics recorder which serves the metrics in the Prometheus text format over
+/// HTTP on `addr`, at any path.
+///
+/// Runs the listener on the current tokio runtime, or on a background thread if there is none.
+/// Fails if a metrics recorder is already installed, e.g. by `run_with_metric_collection`.
+pub fn install_prometheus_exporter(addr: SocketAddr) -> Result<()> {
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()?;
+    Ok(())
+}
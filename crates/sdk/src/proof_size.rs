@@ -0,0 +1,217 @@
+use std::{collections::BTreeMap, mem::size_of};
+
+use openvm_native_compiler::ir::{DIGEST_SIZE, HASH_RATE};
+use openvm_stark_backend::{
+    config::StarkGenericConfig, keygen::types::MultiStarkVerifyingKey,
+    p3_field::AbstractExtensionField,
+};
+use openvm_stark_sdk::config::FriParameters;
+use serde::{Deserialize, Serialize};
+
+use crate::{F, SC};
+
+type EF = <SC as StarkGenericConfig>::Challenge;
+
+const EXT_DEGREE: usize = <EF as AbstractExtensionField<F>>::D;
+
+/// Expected shape of a STARK proof, computed from the verifying key without proving.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofSizeEstimate {
+    /// Number of trace commitments, FRI commitments and Merkle path siblings.
+    pub num_digests: usize,
+    /// Number of base field elements: opened LDE rows, public values and the proof of work
+    /// witness.
+    pub num_base_elements: usize,
+    /// Number of extension field elements: out-of-domain openings, exposed values and FRI
+    /// folding values.
+    pub num_ext_elements: usize,
+    /// Number of Poseidon2 permutations the verifier spends on Merkle proof verification. This
+    /// excludes the permutations of the Fiat-Shamir transcript.
+    pub num_merkle_hashes: usize,
+}
+
+impl ProofSizeEstimate {
+    /// Size of all field elements in the proof in bytes. Serialization adds a small overhead for
+    /// length prefixes and AIR metadata on top of this.
+    pub fn size_in_bytes(&self) -> usize {
+        let num_felts = self.num_digests * DIGEST_SIZE
+            + self.num_base_elements
+            + self.num_ext_elements * EXT_DEGREE;
+        num_felts * size_of::<u32>()
+    }
+}
+
+/// Computes the size of a proof of `vk` under `fri_params` and the number of hashes needed to
+/// verify it, without generating the proof.
+///
+/// `log_trace_heights` lists `(air_id, log2 of trace height)` of every AIR that is part of the
+/// proof. AIRs with empty traces are not part of the proof and should be omitted.
+pub fn estimate_proof_size(
+    vk: &MultiStarkVerifyingKey<SC>,
+    fri_params: &FriParameters,
+    log_trace_heights: &[(usize, usize)],
+) -> ProofSizeEstimate {
+    let log_blowup = fri_params.log_blowup;
+    let mut estimate = ProofSizeEstimate::default();
+
+    // Each round is committed separately and holds `(log2 of LDE height, width)` per matrix.
+    let mut preprocessed_rounds = vec![];
+    let mut cached_main_rounds = vec![];
+    let mut common_main_round = vec![];
+    let mut after_challenge_round = vec![];
+    let mut quotient_round = vec![];
+    for &(air_id, log_height) in log_trace_heights {
+        let air_vk = &vk.per_air[air_id];
+        let width = &air_vk.params.width;
+        let log_lde_height = log_height + log_blowup;
+
+        // Trace openings at `zeta` and `zeta * g`.
+        if let Some(preprocessed_width) = width.preprocessed {
+            preprocessed_rounds.push(vec![(log_lde_height, preprocessed_width)]);
+            estimate.num_ext_elements += 2 * preprocessed_width;
+        }
+        for &cached_main_width in &width.cached_mains {
+            cached_main_rounds.push(vec![(log_lde_height, cached_main_width)]);
+            estimate.num_ext_elements += 2 * cached_main_width;
+        }
+        if width.common_main != 0 {
+            common_main_round.push((log_lde_height, width.common_main));
+            estimate.num_ext_elements += 2 * width.common_main;
+        }
+        // Only a single challenge phase is supported.
+        if let Some(&after_challenge_width) = width.after_challenge.first() {
+            let after_challenge_width = after_challenge_width * EXT_DEGREE;
+            after_challenge_round.push((log_lde_height, after_challenge_width));
+            estimate.num_ext_elements += 2 * after_challenge_width;
+        }
+        estimate.num_ext_elements += air_vk
+            .params
+            .num_exposed_values_after_challenge
+            .iter()
+            .sum::<usize>();
+        estimate.num_base_elements += air_vk.params.num_public_values;
+
+        // Quotient chunks have the same height as the trace and are opened at `zeta` only.
+        for _ in 0..air_vk.quotient_degree {
+            quotient_round.push((log_lde_height, EXT_DEGREE));
+            estimate.num_ext_elements += EXT_DEGREE;
+        }
+    }
+
+    // Preprocessed commitments are part of the verifying key, not the proof.
+    let committed_rounds: Vec<_> = cached_main_rounds
+        .into_iter()
+        .chain([common_main_round, after_challenge_round, quotient_round])
+        .filter(|round| !round.is_empty())
+        .collect();
+    estimate.num_digests += committed_rounds.len();
+    let rounds: Vec<_> = preprocessed_rounds
+        .into_iter()
+        .chain(committed_rounds)
+        .collect();
+
+    let Some(log_max_lde_height) = rounds.iter().flatten().map(|&(h, _)| h).max() else {
+        return estimate;
+    };
+    let num_fri_rounds = log_max_lde_height - log_blowup;
+
+    // FRI commit phase commitments, the final polynomial and the proof of work witness.
+    estimate.num_digests += num_fri_rounds;
+    estimate.num_ext_elements += 1;
+    estimate.num_base_elements += 1;
+
+    let mut query = ProofSizeEstimate::default();
+    for round in &rounds {
+        // Matrices of the same height are hashed together and injected into the Merkle path at
+        // their height.
+        let mut widths_by_height = BTreeMap::<usize, usize>::new();
+        for &(log_lde_height, width) in round {
+            *widths_by_height.entry(log_lde_height).or_default() += width;
+        }
+        let log_round_height = *widths_by_height.keys().last().unwrap();
+        query.num_base_elements += widths_by_height.values().sum::<usize>();
+        query.num_digests += log_round_height;
+        query.num_merkle_hashes += log_round_height
+            + widths_by_height
+                .values()
+                .map(|width| width.div_ceil(HASH_RATE))
+                .sum::<usize>()
+            + (widths_by_height.len() - 1);
+    }
+    for i in 0..num_fri_rounds {
+        // The sibling of the queried evaluation, and the path of the folded codeword.
+        let log_folded_height = log_max_lde_height - i - 1;
+        query.num_ext_elements += 1;
+        query.num_digests += log_folded_height;
+        query.num_merkle_hashes += (2 * EXT_DEGREE).div_ceil(HASH_RATE) + log_folded_height;
+    }
+
+    let num_queries = fri_params.num_queries;
+    estimate.num_digests += num_queries * query.num_digests;
+    estimate.num_base_elements += num_queries * query.num_base_elements;
+    estimate.num_ext_elements += num_queries * query.num_ext_elements;
+    estimate.num_merkle_hashes += num_queries * query.num_merkle_hashes;
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_backend::{
+        engine::StarkEngine, p3_util::log2_strict_usize, prover::types::ProofInput, Chip,
+    };
+    use openvm_stark_sdk::{
+        config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+        dummy_airs::{
+            fib_air::chip::FibonacciChip,
+            interaction::dummy_interaction_air::{DummyInteractionChip, DummyInteractionData},
+        },
+        engine::StarkFriEngine,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_estimate_proof_size_matches_serialized_proof() {
+        let fri_params = FriParameters::standard_fast();
+        let engine = BabyBearPoseidon2Engine::new(fri_params);
+        // AIRs of different heights, with and without interactions.
+        let fib_chip = FibonacciChip::new(0, 1, 16);
+        let mut send_chip = DummyInteractionChip::new_without_partition(1, true, 0);
+        let mut recv_chip = DummyInteractionChip::new_without_partition(1, false, 0);
+        let data = || DummyInteractionData {
+            count: vec![1, 2, 4],
+            fields: vec![vec![1], vec![2], vec![3]],
+        };
+        send_chip.load_data(data());
+        recv_chip.load_data(data());
+
+        let mut keygen_builder = engine.keygen_builder();
+        let fib_id = keygen_builder.add_air(fib_chip.air());
+        let send_id = keygen_builder.add_air(send_chip.air());
+        let recv_id = keygen_builder.add_air(recv_chip.air());
+        let pk = keygen_builder.generate_pk();
+        let vk = pk.get_vk();
+
+        let per_air = vec![
+            fib_chip.generate_air_proof_input_with_id(fib_id),
+            send_chip.generate_air_proof_input_with_id(send_id),
+            recv_chip.generate_air_proof_input_with_id(recv_id),
+        ];
+        let log_trace_heights: Vec<_> = per_air
+            .iter()
+            .map(|(air_id, input)| (*air_id, log2_strict_usize(input.raw.height())))
+            .collect();
+        let proof = engine.prove(&pk, ProofInput { per_air });
+        engine.verify(&vk, &proof).unwrap();
+
+        let estimate = estimate_proof_size(&vk, &fri_params, &log_trace_heights);
+        let estimated = estimate.size_in_bytes();
+        let serialized = bitcode::serialize(&proof).unwrap().len();
+        // The estimate only counts field elements. Serialization adds length prefixes and AIR
+        // metadata, which must stay within 10% of the estimate.
+        assert!(
+            serialized.abs_diff(estimated) * 10 <= estimated,
+            "estimated {estimated} bytes, serialized proof has {serialized} bytes"
+        );
+    }
+}
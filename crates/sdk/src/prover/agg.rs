@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc};
 
 #[cfg(feature = "bench-metrics")]
 use openvm_circuit::arch::SingleSegmentVmExecutor;
@@ -9,6 +9,8 @@ use openvm_stark_sdk::{
     config::baby_bear_poseidon2::BabyBearPoseidon2Engine, engine::StarkFriEngine,
     openvm_stark_backend::prover::types::Proof,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use tracing::info_span;
 
 // #[cfg(feature = "bench-metrics")]
@@ -17,7 +19,7 @@ use crate::{
     keygen::AggStarkProvingKey,
     prover::{
         vm::{local::VmLocalProver, ContinuationVmProof, SingleSegmentVmProver},
-        RootVerifierLocalProver,
+        AggJobScheduler, AggProvingJob, RootVerifierLocalProver,
     },
     verifier::{
         internal::types::InternalVmVerifierInput, leaf::types::LeafVmVerifierInput,
@@ -29,6 +31,7 @@ use crate::{
 const DEFAULT_NUM_CHILDREN_LEAF: usize = 2;
 const DEFAULT_NUM_CHILDREN_INTERNAL: usize = 2;
 const DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS: usize = 4;
+const DEFAULT_NUM_CONCURRENT_PROOFS: usize = 1;
 
 pub struct AggStarkProver {
     leaf_prover: LeafProver,
@@ -37,12 +40,16 @@ pub struct AggStarkProver {
 
     pub num_children_internal: usize,
     pub max_internal_wrapper_layers: usize,
+    /// Maximum number of internal proofs of the same layer generated at the same time.
+    pub num_concurrent_proofs: usize,
 
     pub profile: bool,
 }
 pub struct LeafProver {
     prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
     pub num_children_leaf: usize,
+    /// Maximum number of leaf proofs generated at the same time.
+    pub num_concurrent_proofs: usize,
     pub profile: bool,
 }
 
@@ -63,6 +70,7 @@ impl AggStarkProver {
             root_prover,
             num_children_internal: DEFAULT_NUM_CHILDREN_INTERNAL,
             max_internal_wrapper_layers: DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS,
+            num_concurrent_proofs: DEFAULT_NUM_CONCURRENT_PROOFS,
             profile: false,
         }
     }
@@ -82,6 +90,15 @@ impl AggStarkProver {
         self
     }
 
+    /// Prove up to `num_concurrent_proofs` nodes of the same layer of the aggregation tree at the
+    /// same time. Each proof is already parallelized internally, so this mostly helps when the
+    /// proofs are too small to saturate all threads. Peak memory grows accordingly.
+    pub fn with_num_concurrent_proofs(mut self, num_concurrent_proofs: usize) -> Self {
+        self.num_concurrent_proofs = num_concurrent_proofs;
+        self.leaf_prover.num_concurrent_proofs = num_concurrent_proofs;
+        self
+    }
+
     pub fn set_profile(&mut self, profile: bool) -> &mut Self {
         self.profile = profile;
         self.leaf_prover.profile = profile;
@@ -96,19 +113,89 @@ impl AggStarkProver {
     pub fn generate_agg_proof(&self, app_proofs: ContinuationVmProof<SC>) -> Proof<RootSC> {
         let leaf_proofs = self.leaf_prover.generate_proof(&app_proofs);
         let public_values = app_proofs.user_public_values.public_values;
-        let internal_proof = self.generate_internal_proof_impl(leaf_proofs, &public_values);
+        let internal_proof = self.generate_internal_proof_impl(
+            leaf_proofs,
+            &public_values,
+            |internal_inputs, first_node_idx, internal_node_height| {
+                let proofs = info_span!("internal verifier", group = "internal").in_scope(|| {
+                    #[cfg(feature = "bench-metrics")]
+                    metrics::counter!("fri.log_blowup")
+                        .absolute(self.internal_prover.pk.fri_params.log_blowup as u64);
+                    prove_layer(internal_inputs, self.num_concurrent_proofs, |i, input| {
+                        info_span!(
+                            "Internal verifier proof",
+                            idx = first_node_idx + i,
+                            hgt = internal_node_height
+                        )
+                        .in_scope(|| {
+                            single_segment_prove(&self.internal_prover, input.write(), self.profile)
+                        })
+                    })
+                });
+                Ok::<_, Infallible>(proofs)
+            },
+        );
+        let internal_proof = internal_proof.unwrap_or_else(|never| match never {});
         self.generate_root_proof_impl(RootVmVerifierInput {
             proofs: vec![internal_proof],
             public_values,
         })
     }
 
-    fn generate_internal_proof_impl(
+    /// Generate a proof to aggregate app proofs, delegating the leaf and internal proofs to
+    /// `scheduler`. Only the root proof is generated locally. Fails if the scheduler fails to
+    /// prove a layer.
+    pub fn generate_agg_proof_with_scheduler(
+        &self,
+        app_proofs: ContinuationVmProof<SC>,
+        scheduler: &impl AggJobScheduler,
+    ) -> eyre::Result<Proof<RootSC>> {
+        let leaf_jobs = LeafVmVerifierInput::chunk_continuation_vm_proof(
+            &app_proofs,
+            self.leaf_prover.num_children_leaf,
+        )
+        .into_iter()
+        .enumerate()
+        .map(|(idx, input)| AggProvingJob::Leaf { idx, input })
+        .collect();
+        let leaf_proofs = scheduler.prove_layer(leaf_jobs)?;
+        let public_values = app_proofs.user_public_values.public_values;
+        let internal_proof = self.generate_internal_proof_impl(
+            leaf_proofs,
+            &public_values,
+            |internal_inputs, first_node_idx, height| {
+                let jobs = internal_inputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, input)| AggProvingJob::Internal {
+                        idx: first_node_idx + i,
+                        height,
+                        input,
+                    })
+                    .collect();
+                scheduler.prove_layer(jobs)
+            },
+        )?;
+        Ok(self.generate_root_proof_impl(RootVmVerifierInput {
+            proofs: vec![internal_proof],
+            public_values,
+        }))
+    }
+
+    /// Aggregates `leaf_proofs` into a single internal proof which the root verifier can verify.
+    /// `prove_internal_layer` is called with the inputs of each layer, the index of the first
+    /// node of the layer and the height of the layer, and fails if proving a layer fails.
+    fn generate_internal_proof_impl<E>(
         &self,
         leaf_proofs: Vec<Proof<SC>>,
         public_values: &[F],
-    ) -> Proof<SC> {
-        let mut internal_node_idx = -1;
+        mut prove_internal_layer: impl FnMut(
+            Vec<InternalVmVerifierInput<SC>>,
+            usize,
+            usize,
+        ) -> Result<Vec<Proof<SC>>, E>,
+    ) -> Result<Proof<SC>, E> {
+        let mut internal_node_idx = 0;
         let mut internal_node_height = 0;
         let mut proofs = leaf_proofs;
         let mut wrapper_layers = 0;
@@ -142,28 +229,14 @@ impl AggStarkProver {
                 &proofs,
                 self.num_children_internal,
             );
-            proofs = info_span!("internal verifier", group = "internal").in_scope(|| {
-                #[cfg(feature = "bench-metrics")]
-                metrics::counter!("fri.log_blowup")
-                    .absolute(self.internal_prover.pk.fri_params.log_blowup as u64);
-                internal_inputs
-                    .into_iter()
-                    .map(|input| {
-                        internal_node_idx += 1;
-                        info_span!(
-                            "Internal verifier proof",
-                            idx = internal_node_idx,
-                            hgt = internal_node_height
-                        )
-                        .in_scope(|| {
-                            single_segment_prove(&self.internal_prover, input.write(), self.profile)
-                        })
-                    })
-                    .collect()
-            });
+            let num_nodes = internal_inputs.len();
+            proofs =
+                prove_internal_layer(internal_inputs, internal_node_idx, internal_node_height)?;
+            assert_eq!(proofs.len(), num_nodes);
+            internal_node_idx += num_nodes;
             internal_node_height += 1;
         }
-        proofs.pop().unwrap()
+        Ok(proofs.pop().unwrap())
     }
 
     fn generate_root_proof_impl(&self, root_input: RootVmVerifierInput<SC>) -> Proof<RootSC> {
@@ -207,6 +280,7 @@ impl LeafProver {
         Self {
             prover,
             num_children_leaf: DEFAULT_NUM_CHILDREN_LEAF,
+            num_concurrent_proofs: DEFAULT_NUM_CONCURRENT_PROOFS,
             profile: false,
         }
     }
@@ -214,6 +288,10 @@ impl LeafProver {
         self.num_children_leaf = num_children_leaf;
         self
     }
+    pub fn with_num_concurrent_proofs(mut self, num_concurrent_proofs: usize) -> Self {
+        self.num_concurrent_proofs = num_concurrent_proofs;
+        self
+    }
     pub fn with_profile(mut self) -> Self {
         self.profile = true;
         self
@@ -227,15 +305,15 @@ impl LeafProver {
                 app_proofs,
                 self.num_children_leaf,
             );
-            leaf_inputs
-                .into_iter()
-                .enumerate()
-                .map(|(leaf_node_idx, input)| {
+            prove_layer(
+                leaf_inputs,
+                self.num_concurrent_proofs,
+                |leaf_node_idx, input| {
                     info_span!("leaf verifier proof", idx = leaf_node_idx).in_scope(|| {
                         single_segment_prove(&self.prover, input.write_to_stream(), self.profile)
                     })
-                })
-                .collect::<Vec<_>>()
+                },
+            )
         })
     }
 }
@@ -257,6 +335,39 @@ fn single_segment_prove<E: StarkFriEngine<SC>>(
     SingleSegmentVmProver::prove(prover, input)
 }
 
+/// Proves the nodes of one layer of the aggregation tree, at most `num_concurrent_proofs` at a
+/// time. Proofs are returned in the order of `inputs`.
+fn prove_layer<I: Send>(
+    inputs: Vec<I>,
+    num_concurrent_proofs: usize,
+    prove: impl Fn(usize, I) -> Proof<SC> + Sync,
+) -> Vec<Proof<SC>> {
+    #[cfg(feature = "parallel")]
+    if num_concurrent_proofs > 1 {
+        // Spans are not inherited by rayon threads, so re-enter the layer's span explicitly.
+        let span = tracing::Span::current();
+        let mut proofs = Vec::with_capacity(inputs.len());
+        let mut inputs = inputs.into_iter().enumerate().peekable();
+        while inputs.peek().is_some() {
+            let batch: Vec<_> = inputs.by_ref().take(num_concurrent_proofs).collect();
+            proofs.extend(
+                batch
+                    .into_par_iter()
+                    .map(|(idx, input)| span.in_scope(|| prove(idx, input)))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        return proofs;
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = num_concurrent_proofs;
+    inputs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, input)| prove(idx, input))
+        .collect()
+}
+
 fn heights_le(a: &[usize], b: &[usize]) -> bool {
     assert_eq!(a.len(), b.len());
     a.iter().zip(b.iter()).all(|(a, b)| a <= b)
@@ -84,6 +84,31 @@ impl<VC> AppProver<VC> {
             ContinuationVmProver::prove(&self.app_prover, input)
         })
     }
+
+    /// Same as [Self::generate_app_proof], but proves each segment as soon as it is executed and
+    /// calls `on_segment_proven` with the index of each proven segment.
+    pub fn generate_app_proof_with_progress(
+        &self,
+        input: StdIn,
+        on_segment_proven: impl FnMut(usize),
+    ) -> ContinuationVmProof<SC>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        info_span!(
+            "app proof",
+            group = self
+                .program_name
+                .as_ref()
+                .unwrap_or(&"app_proof".to_string())
+        )
+        .in_scope(|| {
+            self.app_prover
+                .prove_with_progress(input, on_segment_proven)
+        })
+    }
 }
 
 #[cfg(feature = "bench-metrics")]
@@ -94,5 +119,11 @@ where
 {
     vm_config.system_mut().collect_metrics = true;
     let vm = VmExecutor::new(vm_config);
-    vm.execute_segments(exe, input).unwrap();
+    let segments = vm.execute_segments(exe, input).unwrap();
+    if let Some(segment) = segments.last() {
+        tracing::info!(
+            "cycle tracker spans:\n{}",
+            segment.cycle_tracker.span_tree()
+        );
+    }
 }
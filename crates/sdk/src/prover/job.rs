@@ -0,0 +1,472 @@
+use std::{
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use openvm_native_circuit::NativeConfig;
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    openvm_stark_backend::prover::types::Proof,
+};
+#[cfg(feature = "parallel")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::{
+    keygen::AggStarkProvingKey,
+    prover::{
+        transport::{read_message, write_message},
+        vm::{local::VmLocalProver, SingleSegmentVmProver},
+    },
+    verifier::{internal::types::InternalVmVerifierInput, leaf::types::LeafVmVerifierInput},
+    NonRootCommittedExe, SC,
+};
+
+/// A single node of the aggregation tree below the root. Jobs are self-contained and
+/// serializable, so they can be proven on a different machine than the one driving the
+/// aggregation.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum AggProvingJob {
+    Leaf {
+        /// Index of the node within the leaf layer.
+        idx: usize,
+        input: LeafVmVerifierInput<SC>,
+    },
+    Internal {
+        /// Index of the node among all internal nodes.
+        idx: usize,
+        /// Height of the node above the leaf layer, starting from 0.
+        height: usize,
+        input: InternalVmVerifierInput<SC>,
+    },
+}
+
+/// Proves the jobs of the aggregation tree, one layer at a time.
+///
+/// Implementations may distribute the jobs of a layer over a pool of [AggJobWorker]s, e.g. over
+/// the network. Jobs of the same layer are independent of each other; jobs of the next layer
+/// depend on all proofs of the current layer.
+pub trait AggJobScheduler {
+    /// Proves all `jobs` of one layer. Proofs must be returned in the order of `jobs`.
+    fn prove_layer(&self, jobs: Vec<AggProvingJob>) -> eyre::Result<Vec<Proof<SC>>>;
+}
+
+/// Proves [AggProvingJob]s locally. Only requires the leaf and internal verifier proving keys.
+pub struct AggJobWorker {
+    leaf_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+    internal_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+}
+
+impl AggJobWorker {
+    pub fn new(
+        agg_stark_pk: &AggStarkProvingKey,
+        leaf_committed_exe: Arc<NonRootCommittedExe>,
+    ) -> Self {
+        Self {
+            leaf_prover: VmLocalProver::new(agg_stark_pk.leaf_vm_pk.clone(), leaf_committed_exe),
+            internal_prover: VmLocalProver::new(
+                agg_stark_pk.internal_vm_pk.clone(),
+                agg_stark_pk.internal_committed_exe.clone(),
+            ),
+        }
+    }
+
+    pub fn prove(&self, job: AggProvingJob) -> Proof<SC> {
+        match job {
+            AggProvingJob::Leaf { idx, input } => {
+                info_span!("leaf verifier proof", idx).in_scope(|| {
+                    SingleSegmentVmProver::prove(&self.leaf_prover, input.write_to_stream())
+                })
+            }
+            AggProvingJob::Internal { idx, height, input } => {
+                info_span!("Internal verifier proof", idx, hgt = height)
+                    .in_scope(|| SingleSegmentVmProver::prove(&self.internal_prover, input.write()))
+            }
+        }
+    }
+}
+
+impl AggJobScheduler for AggJobWorker {
+    fn prove_layer(&self, jobs: Vec<AggProvingJob>) -> eyre::Result<Vec<Proof<SC>>> {
+        Ok(jobs.into_iter().map(|job| self.prove(job)).collect())
+    }
+}
+
+/// Proves a single [AggProvingJob] on a worker which may fail, e.g. a remote worker whose
+/// connection is lost. A failed job can be retried on another worker.
+pub trait AggJobExecutor {
+    fn try_prove(&self, job: &AggProvingJob) -> eyre::Result<Proof<SC>>;
+}
+
+impl AggJobExecutor for AggJobWorker {
+    fn try_prove(&self, job: &AggProvingJob) -> eyre::Result<Proof<SC>> {
+        Ok(self.prove(job.clone()))
+    }
+}
+
+/// Default timeout of reads from and writes to a connection of an [AggJobServer].
+pub const DEFAULT_AGG_JOB_IO_TIMEOUT: Duration = Duration::from_secs(60);
+/// Default upper bound on the number of jobs an [AggJobServer] proves at the same time.
+pub const DEFAULT_MAX_AGG_JOB_CONNECTIONS: usize = 1;
+/// Default timeout of connecting to an [AggJobServer].
+pub const DEFAULT_AGG_JOB_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves [AggProvingJob]s sent by [RemoteAggJobWorker]s over TCP.
+///
+/// Each connection carries a single job and its result, so a client that loses its connection
+/// can retry the job on another server. Connections are served on their own thread, up to
+/// [AggJobServer::with_max_connections]; further connections are answered with an error right
+/// away, so their client retries the job elsewhere.
+pub struct AggJobServer<E> {
+    executor: Arc<E>,
+    io_timeout: Duration,
+    max_connections: usize,
+    /// Number of connections currently being served.
+    connections: Arc<AtomicUsize>,
+}
+
+impl<E> Clone for AggJobServer<E> {
+    fn clone(&self) -> Self {
+        Self {
+            executor: self.executor.clone(),
+            io_timeout: self.io_timeout,
+            max_connections: self.max_connections,
+            connections: self.connections.clone(),
+        }
+    }
+}
+
+impl<E: AggJobExecutor + Send + Sync + 'static> AggJobServer<E> {
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor: Arc::new(executor),
+            io_timeout: DEFAULT_AGG_JOB_IO_TIMEOUT,
+            max_connections: DEFAULT_MAX_AGG_JOB_CONNECTIONS,
+            connections: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Sets the timeout of each read of the job and write of the result, so idle clients do not
+    /// hold on to a connection forever. Proving itself is not limited.
+    pub fn with_io_timeout(mut self, io_timeout: Duration) -> Self {
+        self.io_timeout = io_timeout;
+        self
+    }
+
+    /// Sets the number of jobs proven at the same time. Each job is a whole proof, so this is
+    /// bounded by the memory of the machine.
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Serves connections on `listener` until accepting a connection fails.
+    pub fn serve(&self, listener: TcpListener) -> eyre::Result<()> {
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            if self.connections.fetch_add(1, Ordering::SeqCst) >= self.max_connections {
+                self.connections.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(self.io_timeout));
+                let busy: Result<Proof<SC>, String> = Err("too many connections".to_string());
+                if let Err(err) = write_message(&mut stream, &busy) {
+                    tracing::warn!("failed to reject aggregation job: {err}");
+                }
+                continue;
+            }
+            let server = self.clone();
+            thread::spawn(move || {
+                if let Err(err) = server.handle(stream) {
+                    tracing::warn!("failed to serve aggregation job: {err}");
+                }
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads one job from `stream`, proves it and writes back the proof or the error.
+    pub fn handle(&self, mut stream: TcpStream) -> eyre::Result<()> {
+        stream.set_read_timeout(Some(self.io_timeout))?;
+        stream.set_write_timeout(Some(self.io_timeout))?;
+        let job: AggProvingJob = read_message(&mut stream)?;
+        let result = self.executor.try_prove(&job).map_err(|err| err.to_string());
+        write_message(&mut stream, &result)
+    }
+}
+
+/// Proves jobs on a remote [AggJobServer].
+#[derive(Clone, Debug)]
+pub struct RemoteAggJobWorker {
+    addr: SocketAddr,
+    /// Read timeout for the proof. `None` waits for the proof indefinitely.
+    timeout: Option<Duration>,
+    connect_timeout: Duration,
+}
+
+impl RemoteAggJobWorker {
+    pub fn new(addr: SocketAddr, timeout: Option<Duration>) -> Self {
+        Self {
+            addr,
+            timeout,
+            connect_timeout: DEFAULT_AGG_JOB_CONNECT_TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout of connecting to the server and of sending it the job.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+}
+
+impl AggJobExecutor for RemoteAggJobWorker {
+    fn try_prove(&self, job: &AggProvingJob) -> eyre::Result<Proof<SC>> {
+        let mut stream = TcpStream::connect_timeout(&self.addr, self.connect_timeout)?;
+        stream.set_write_timeout(Some(self.connect_timeout))?;
+        stream.set_read_timeout(self.timeout)?;
+        write_message(&mut stream, job)?;
+        let result: Result<Proof<SC>, String> = read_message(&mut stream)?;
+        result.map_err(|err| eyre::eyre!("worker {} failed: {err}", self.addr))
+    }
+}
+
+/// Schedules the jobs of each layer over a pool of [AggJobExecutor]s.
+///
+/// Job `i` of a layer is first sent to worker `i % workers.len()`. If it fails, it is retried on
+/// the next worker of the pool, for at most `max_attempts` attempts in total. Proving a layer
+/// fails if one of its jobs fails on every attempt.
+pub struct AggJobPool<W> {
+    workers: Vec<W>,
+    max_attempts: usize,
+}
+
+impl<W: AggJobExecutor + Sync> AggJobPool<W> {
+    pub fn new(workers: Vec<W>, max_attempts: usize) -> Self {
+        assert!(!workers.is_empty(), "the worker pool must not be empty");
+        assert!(max_attempts > 0, "max_attempts must be positive");
+        Self {
+            workers,
+            max_attempts,
+        }
+    }
+
+    fn prove_with_retries(&self, i: usize, job: &AggProvingJob) -> eyre::Result<Proof<SC>> {
+        let mut errors = Vec::with_capacity(self.max_attempts);
+        for attempt in 0..self.max_attempts {
+            let worker = (i + attempt) % self.workers.len();
+            match self.workers[worker].try_prove(job) {
+                Ok(proof) => return Ok(proof),
+                Err(err) => {
+                    tracing::warn!("aggregation job {i} failed on worker {worker}: {err}");
+                    errors.push(err.to_string());
+                }
+            }
+        }
+        eyre::bail!(
+            "aggregation job {i} failed after {} attempts: {errors:?}",
+            self.max_attempts
+        )
+    }
+}
+
+impl<W: AggJobExecutor + Sync> AggJobScheduler for AggJobPool<W> {
+    fn prove_layer(&self, jobs: Vec<AggProvingJob>) -> eyre::Result<Vec<Proof<SC>>> {
+        #[cfg(feature = "parallel")]
+        let iter = jobs.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = jobs.iter();
+        iter.enumerate()
+            .map(|(i, job)| self.prove_with_retries(i, job))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use openvm_stark_sdk::{
+        config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+        dummy_airs::fib_air::chip::FibonacciChip, engine::StarkFriEngine,
+        openvm_stark_backend::Chip,
+    };
+
+    use super::*;
+
+    /// Returns a precomputed proof for each leaf job, or fails every job if it is down.
+    struct MockWorker {
+        proofs: Vec<Proof<SC>>,
+        down: bool,
+        attempts: Mutex<Vec<usize>>,
+    }
+
+    impl MockWorker {
+        fn new(proofs: &[Proof<SC>], down: bool) -> Self {
+            Self {
+                proofs: proofs.to_vec(),
+                down,
+                attempts: Mutex::new(vec![]),
+            }
+        }
+
+        fn attempts(&self) -> Vec<usize> {
+            let mut attempts = self.attempts.lock().unwrap().clone();
+            attempts.sort();
+            attempts
+        }
+    }
+
+    impl AggJobExecutor for MockWorker {
+        fn try_prove(&self, job: &AggProvingJob) -> eyre::Result<Proof<SC>> {
+            let AggProvingJob::Leaf { idx, .. } = job else {
+                unreachable!("only leaf jobs are scheduled in these tests");
+            };
+            self.attempts.lock().unwrap().push(*idx);
+            if self.down {
+                eyre::bail!("worker is down");
+            }
+            Ok(self.proofs[*idx].clone())
+        }
+    }
+
+    /// Proofs of Fibonacci traces of heights 4, 8, ..., which tell the jobs apart by degree.
+    fn proofs(num_jobs: usize) -> Vec<Proof<SC>> {
+        (0..num_jobs)
+            .map(|i| {
+                let chip = FibonacciChip::new(0, 1, 4 << i);
+                BabyBearPoseidon2Engine::run_test_fast(vec![chip.generate_air_proof_input()])
+                    .unwrap()
+                    .data
+                    .proof
+            })
+            .collect()
+    }
+
+    fn leaf_jobs(num_jobs: usize) -> Vec<AggProvingJob> {
+        (0..num_jobs)
+            .map(|idx| AggProvingJob::Leaf {
+                idx,
+                input: LeafVmVerifierInput {
+                    proofs: vec![],
+                    public_values_root_proof: None,
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_agg_job_pool_retries_failed_jobs() {
+        let proofs = proofs(4);
+        let pool = AggJobPool::new(
+            vec![
+                MockWorker::new(&proofs, false),
+                MockWorker::new(&proofs, true),
+            ],
+            2,
+        );
+        let layer = pool.prove_layer(leaf_jobs(4)).unwrap();
+
+        // Proofs come back in job order, even though odd jobs were retried.
+        let degrees = |proofs: &[Proof<SC>]| {
+            proofs
+                .iter()
+                .map(|proof| proof.per_air[0].degree)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(degrees(&layer), degrees(&proofs));
+        assert_eq!(pool.workers[0].attempts(), vec![0, 1, 2, 3]);
+        assert_eq!(pool.workers[1].attempts(), vec![1, 3]);
+    }
+
+    /// Starts a server for `worker` on a local port, which is left running after the test.
+    fn spawn_server(worker: MockWorker) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Jobs of a layer are proven in parallel, and may all be retried on the same server.
+        let server = AggJobServer::new(worker).with_max_connections(4);
+        std::thread::spawn(move || server.serve(listener));
+        addr
+    }
+
+    #[test]
+    fn test_remote_agg_job_workers() {
+        let proofs = proofs(3);
+        let up = spawn_server(MockWorker::new(&proofs, false));
+        let failing = spawn_server(MockWorker::new(&proofs, true));
+        // Nothing listens on the address of a dropped listener.
+        let unreachable = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let remote = |addr| RemoteAggJobWorker::new(addr, Some(Duration::from_secs(60)));
+        let err = remote(failing)
+            .try_prove(&leaf_jobs(1)[0])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("worker is down"), "{err}");
+
+        let pool = AggJobPool::new(vec![remote(failing), remote(unreachable), remote(up)], 3);
+        let layer = pool.prove_layer(leaf_jobs(3)).unwrap();
+        let degrees = |proofs: &[Proof<SC>]| {
+            proofs
+                .iter()
+                .map(|proof| proof.per_air[0].degree)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(degrees(&layer), degrees(&proofs));
+    }
+
+    #[test]
+    fn test_agg_job_server_limits() {
+        let proofs = proofs(1);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AggJobServer::new(MockWorker::new(&proofs, false))
+            .with_max_connections(1)
+            .with_io_timeout(Duration::from_millis(200));
+        std::thread::spawn(move || server.serve(listener));
+
+        // An idle client holds the only connection until its read times out.
+        let idle = TcpStream::connect(addr).unwrap();
+        let remote = RemoteAggJobWorker::new(addr, Some(Duration::from_secs(60)))
+            .with_connect_timeout(Duration::from_secs(1));
+        let err = remote.try_prove(&leaf_jobs(1)[0]).unwrap_err().to_string();
+        assert!(err.contains("too many connections"), "{err}");
+        let mut rest = vec![];
+        std::io::Read::read_to_end(&mut &idle, &mut rest).unwrap();
+
+        let mut result = remote.try_prove(&leaf_jobs(1)[0]);
+        for _ in 0..100 {
+            if result.is_ok() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            result = remote.try_prove(&leaf_jobs(1)[0]);
+        }
+        assert_eq!(
+            result.unwrap().per_air[0].degree,
+            proofs[0].per_air[0].degree
+        );
+    }
+
+    #[test]
+    fn test_agg_job_pool_gives_up() {
+        let pool = AggJobPool::new(
+            vec![MockWorker::new(&[], true), MockWorker::new(&[], true)],
+            3,
+        );
+        let err = pool.prove_layer(leaf_jobs(1)).unwrap_err().to_string();
+        assert!(
+            err.contains("aggregation job 0 failed after 3 attempts"),
+            "{err}"
+        );
+    }
+}
@@ -15,9 +15,16 @@ use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
 mod halo2;
 #[allow(unused_imports)]
 pub use halo2::*;
+mod job;
+pub use job::*;
 mod root;
 pub use root::*;
+#[cfg(feature = "service")]
+mod service;
+#[cfg(feature = "service")]
+pub use service::*;
 mod stark;
+pub mod transport;
 pub mod vm;
 
 #[allow(unused_imports)]
@@ -0,0 +1,594 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use eyre::{bail, ensure, eyre, Result};
+use serde::Serialize;
+
+use super::{ProvingJobId, ProvingService};
+use crate::StdIn;
+
+/// Default upper bound on the size of a request body, i.e. of a submitted input.
+pub const DEFAULT_MAX_REQUEST_BODY_LEN: usize = 64 << 20;
+/// Default upper bound on the number of connections served at the same time.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+/// Default timeout of reads from and writes to a connection.
+pub const DEFAULT_IO_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_HEADERS: usize = 64;
+const MAX_LINE_LEN: u64 = 8192;
+
+/// Serves a [ProvingService] over HTTP/1.1. Each connection carries a single request.
+///
+/// Endpoints:
+/// - `POST /proofs` queues a job and responds `202 {"id": <id>}`. The body is a JSON [StdIn], or
+///   with `Content-Type: application/octet-stream` the bytes of a single input. Responds `503`
+///   if the queue of the service is full.
+/// - `GET /proofs/<id>` responds with the JSON [ProvingJobStatus](super::ProvingJobStatus).
+/// - `GET /proofs/<id>/events` streams the status as server-sent events each time it changes,
+///   until the job is finished.
+/// - `GET /proofs/<id>/proof` removes a finished job and responds with its `bitcode` encoded
+///   proof, `409` if the job is not finished, or `500` with the error if proving failed.
+///
+/// Connections beyond [ProvingServer::with_max_connections] are answered with `503` right away,
+/// and requests with a body larger than [ProvingServer::with_max_body_len] with `400`.
+pub struct ProvingServer<P> {
+    service: Arc<ProvingService<P>>,
+    /// How often `/events` polls the job status.
+    poll_interval: Duration,
+    io_timeout: Duration,
+    max_body_len: usize,
+    max_connections: usize,
+    /// Number of connections currently being served.
+    connections: Arc<AtomicUsize>,
+}
+
+impl<P> Clone for ProvingServer<P> {
+    fn clone(&self) -> Self {
+        Self {
+            service: self.service.clone(),
+            poll_interval: self.poll_interval,
+            io_timeout: self.io_timeout,
+            max_body_len: self.max_body_len,
+            max_connections: self.max_connections,
+            connections: self.connections.clone(),
+        }
+    }
+}
+
+impl<P: Serialize + Send + 'static> ProvingServer<P> {
+    pub fn new(service: Arc<ProvingService<P>>) -> Self {
+        Self {
+            service,
+            poll_interval: Duration::from_millis(100),
+            io_timeout: DEFAULT_IO_TIMEOUT,
+            max_body_len: DEFAULT_MAX_REQUEST_BODY_LEN,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            connections: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets the timeout of each read from and write to a connection, so idle clients do not hold
+    /// on to a connection forever.
+    pub fn with_io_timeout(mut self, io_timeout: Duration) -> Self {
+        self.io_timeout = io_timeout;
+        self
+    }
+
+    pub fn with_max_body_len(mut self, max_body_len: usize) -> Self {
+        self.max_body_len = max_body_len;
+        self
+    }
+
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Serves connections on `listener` until accepting a connection fails. Each connection is
+    /// handled on its own thread, up to the maximum number of connections.
+    pub fn serve(&self, listener: TcpListener) -> Result<()> {
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            if self.connections.fetch_add(1, Ordering::SeqCst) >= self.max_connections {
+                self.connections.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(self.io_timeout));
+                if let Err(err) =
+                    write_response(&mut stream, 503, "text/plain", b"too many connections")
+                {
+                    tracing::warn!("failed to reject proving request: {err}");
+                }
+                continue;
+            }
+            let server = self.clone();
+            thread::spawn(move || {
+                if let Err(err) = server.handle(stream) {
+                    tracing::warn!("failed to serve proving request: {err}");
+                }
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads one request from `stream` and writes its response.
+    pub fn handle(&self, mut stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(self.io_timeout))?;
+        stream.set_write_timeout(Some(self.io_timeout))?;
+        let request = match read_request(&mut BufReader::new(&mut stream), self.max_body_len) {
+            Ok(request) => request,
+            Err(err) => {
+                write_response(&mut stream, 400, "text/plain", err.to_string().as_bytes())?;
+                return Ok(());
+            }
+        };
+        let path = request
+            .path
+            .trim_matches('/')
+            .split('/')
+            .collect::<Vec<_>>();
+        let id = match path.as_slice() {
+            ["proofs", id, ..] => match id.parse::<ProvingJobId>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    write_response(&mut stream, 400, "text/plain", b"invalid job id")?;
+                    return Ok(());
+                }
+            },
+            _ => None,
+        };
+        match (request.method.as_str(), path.as_slice(), id) {
+            ("POST", ["proofs"], _) => {
+                let input = if request.content_type() == Some("application/octet-stream") {
+                    StdIn::from_bytes(&request.body)
+                } else {
+                    match serde_json::from_slice(&request.body) {
+                        Ok(input) => input,
+                        Err(err) => {
+                            let err = format!("invalid input: {err}");
+                            write_response(&mut stream, 400, "text/plain", err.as_bytes())?;
+                            return Ok(());
+                        }
+                    }
+                };
+                match self.service.submit(input) {
+                    Ok(id) => {
+                        let body = serde_json::json!({ "id": id }).to_string();
+                        write_response(&mut stream, 202, "application/json", body.as_bytes())?;
+                    }
+                    Err(err) => {
+                        write_response(&mut stream, 503, "text/plain", err.to_string().as_bytes())?
+                    }
+                }
+            }
+            ("GET", ["proofs", _], Some(id)) => match self.service.status(id) {
+                Some(status) => {
+                    let body = serde_json::to_vec(&status)?;
+                    write_response(&mut stream, 200, "application/json", &body)?;
+                }
+                None => write_response(&mut stream, 404, "text/plain", b"unknown job")?,
+            },
+            ("GET", ["proofs", _, "events"], Some(id)) => self.stream_events(&mut stream, id)?,
+            ("GET", ["proofs", _, "proof"], Some(id)) => match self.service.status(id) {
+                None => write_response(&mut stream, 404, "text/plain", b"unknown job")?,
+                Some(status) if !status.is_finished() => {
+                    write_response(&mut stream, 409, "text/plain", b"job is not finished")?
+                }
+                Some(_) => match self.service.take_proof(id) {
+                    Some(Ok(proof)) => {
+                        let body = bitcode::serialize(&proof)?;
+                        write_response(&mut stream, 200, "application/octet-stream", &body)?;
+                    }
+                    Some(Err(err)) => {
+                        write_response(&mut stream, 500, "text/plain", err.as_bytes())?
+                    }
+                    // Taken by a concurrent request.
+                    None => write_response(&mut stream, 404, "text/plain", b"unknown job")?,
+                },
+            },
+            _ => write_response(&mut stream, 404, "text/plain", b"not found")?,
+        }
+        Ok(())
+    }
+
+    fn stream_events(&self, stream: &mut TcpStream, id: ProvingJobId) -> Result<()> {
+        let Some(mut status) = self.service.status(id) else {
+            write_response(stream, 404, "text/plain", b"unknown job")?;
+            return Ok(());
+        };
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\
+             Connection: close\r\n\r\n"
+        )?;
+        loop {
+            write!(stream, "data: {}\n\n", serde_json::to_string(&status)?)?;
+            stream.flush()?;
+            if status.is_finished() {
+                return Ok(());
+            }
+            let previous = status.clone();
+            while status == previous {
+                thread::sleep(self.poll_interval);
+                // The proof was taken by another request, so the job is done.
+                status = self
+                    .service
+                    .status(id)
+                    .unwrap_or(super::ProvingJobStatus::Done);
+            }
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+            .map(|value| value.split(';').next().unwrap().trim())
+    }
+}
+
+/// Reads a line of at most [MAX_LINE_LEN] bytes into `line`.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> Result<()> {
+    line.clear();
+    reader.by_ref().take(MAX_LINE_LEN).read_line(line)?;
+    ensure!(line.ends_with('\n'), "malformed or truncated request");
+    Ok(())
+}
+
+fn read_request(reader: &mut impl BufRead, max_body_len: usize) -> Result<Request> {
+    let mut line = String::new();
+    read_line(reader, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("malformed request line");
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut headers = vec![];
+    loop {
+        read_line(reader, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        ensure!(headers.len() < MAX_HEADERS, "too many headers");
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| eyre!("malformed header"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    let mut request = Request {
+        method,
+        path,
+        headers,
+        body: vec![],
+    };
+    let len = match request.header("content-length") {
+        Some(len) => len.parse::<usize>()?,
+        None => 0,
+    };
+    ensure!(
+        len <= max_body_len,
+        "request body of {len} bytes exceeds the limit of {max_body_len} bytes"
+    );
+    // The body is read as it arrives rather than allocated up front, so a large
+    // `Content-Length` alone does not allocate.
+    reader.take(len as u64).read_to_end(&mut request.body)?;
+    ensure!(request.body.len() == len, "truncated request body");
+    Ok(request)
+}
+
+fn write_response(
+    stream: &mut impl Write,
+    status: u16,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{mpsc, Mutex},
+    };
+
+    use super::{super::ProvingJobStatus, *};
+
+    /// Starts a server on a local port, which is left running after the test. Jobs prove two
+    /// segments, each after a signal on the returned sender, and return the number of inputs.
+    fn spawn_server() -> (SocketAddr, mpsc::Sender<()>) {
+        spawn_server_with(|server| server)
+    }
+
+    /// Same as [spawn_server], with the server configured by `configure`.
+    fn spawn_server_with(
+        configure: impl FnOnce(ProvingServer<usize>) -> ProvingServer<usize>,
+    ) -> (SocketAddr, mpsc::Sender<()>) {
+        spawn_server_with_service(|service| service, configure)
+    }
+
+    /// Same as [spawn_server_with], with the service configured by `configure_service`.
+    fn spawn_server_with_service(
+        configure_service: impl FnOnce(ProvingService<usize>) -> ProvingService<usize>,
+        configure: impl FnOnce(ProvingServer<usize>) -> ProvingServer<usize>,
+    ) -> (SocketAddr, mpsc::Sender<()>) {
+        let (gate, gate_rx) = mpsc::channel::<()>();
+        let gate_rx = Mutex::new(gate_rx);
+        let service = ProvingService::from_fn(move |input: StdIn, on_segment_proven| {
+            assert!(!input.buffer.is_empty(), "empty input");
+            for seg_idx in 0..2 {
+                gate_rx.lock().unwrap().recv().unwrap();
+                on_segment_proven(seg_idx);
+            }
+            input.buffer.len()
+        });
+        let service = Arc::new(configure_service(service));
+        let server = configure(ProvingServer::new(service).with_poll_interval(Duration::ZERO));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || server.serve(listener));
+        (addr, gate)
+    }
+
+    /// Sends a request and returns the status code and the reader positioned at the body.
+    fn send(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> (u16, BufReader<TcpStream>) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let status = line.split_whitespace().nth(1).unwrap().parse().unwrap();
+        while line.trim_end() != "" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        (status, reader)
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16, Vec<u8>) {
+        let (status, mut reader) = send(addr, method, path, "application/json", body);
+        let mut body = vec![];
+        reader.read_to_end(&mut body).unwrap();
+        (status, body)
+    }
+
+    /// Writes `request` as is and returns the whole response. Requests here have no body, since
+    /// the server would reset a connection it closes with unread data.
+    fn raw_request(addr: SocketAddr, request: &[u8]) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn submit(addr: SocketAddr, input: &StdIn) -> ProvingJobId {
+        let (status, body) = request(addr, "POST", "/proofs", &serde_json::to_vec(input).unwrap());
+        assert_eq!(status, 202);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        body["id"].as_str().unwrap().parse().unwrap()
+    }
+
+    fn job_status(addr: SocketAddr, id: ProvingJobId) -> ProvingJobStatus {
+        let (status, body) = request(addr, "GET", &format!("/proofs/{id}"), &[]);
+        assert_eq!(status, 200);
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[test]
+    fn test_proving_server_streams_progress() {
+        let (addr, gate) = spawn_server();
+        let mut input = StdIn::default();
+        input.write(&1u32);
+        input.write(&2u32);
+        let id = submit(addr, &input);
+
+        let (status, mut events) = send(addr, "GET", &format!("/proofs/{id}/events"), "", &[]);
+        assert_eq!(status, 200);
+        let mut seen = vec![];
+        let mut released = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            events.read_line(&mut line).unwrap();
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+            let status: ProvingJobStatus = serde_json::from_str(data).unwrap();
+            // Let the job prove its next segment once the current progress has been seen.
+            if status
+                == (ProvingJobStatus::Proving {
+                    segments_proven: released,
+                })
+            {
+                gate.send(()).unwrap();
+                released += 1;
+            }
+            let finished = status.is_finished();
+            seen.push(status);
+            if finished {
+                break;
+            }
+        }
+        assert!(seen.contains(&ProvingJobStatus::Proving { segments_proven: 0 }));
+        assert!(seen.contains(&ProvingJobStatus::Proving { segments_proven: 1 }));
+        assert_eq!(seen.last(), Some(&ProvingJobStatus::Done));
+
+        let (status, body) = request(addr, "GET", &format!("/proofs/{id}"), &[]);
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_slice::<ProvingJobStatus>(&body).unwrap(),
+            ProvingJobStatus::Done
+        );
+        let (status, body) = request(addr, "GET", &format!("/proofs/{id}/proof"), &[]);
+        assert_eq!(status, 200);
+        assert_eq!(bitcode::deserialize::<usize>(&body).unwrap(), 2);
+        // The proof can only be taken once.
+        let (status, _) = request(addr, "GET", &format!("/proofs/{id}/proof"), &[]);
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_proving_server_errors() {
+        let (addr, gate) = spawn_server();
+
+        // Raw bytes are a single input.
+        let (status, mut body) = send(addr, "POST", "/proofs", "application/octet-stream", &[1]);
+        assert_eq!(status, 202);
+        let mut id = String::new();
+        body.read_to_string(&mut id).unwrap();
+        let id: serde_json::Value = serde_json::from_str(&id).unwrap();
+        let id: ProvingJobId = id["id"].as_str().unwrap().parse().unwrap();
+        let (status, _) = request(addr, "GET", &format!("/proofs/{id}/proof"), &[]);
+        assert_eq!(status, 409);
+        gate.send(()).unwrap();
+        gate.send(()).unwrap();
+
+        // A job which panics fails with the panic message.
+        let failing = submit(addr, &StdIn::default());
+        let (_, mut events) = send(addr, "GET", &format!("/proofs/{failing}/events"), "", &[]);
+        let mut events_body = String::new();
+        events.read_to_string(&mut events_body).unwrap();
+        assert!(
+            events_body.contains("\"status\":\"failed\""),
+            "{events_body}"
+        );
+        let (status, body) = request(addr, "GET", &format!("/proofs/{failing}/proof"), &[]);
+        assert_eq!(status, 500);
+        assert!(String::from_utf8(body).unwrap().contains("empty input"));
+
+        let (status, body) = request(addr, "GET", &format!("/proofs/{id}/proof"), &[]);
+        assert_eq!(status, 200);
+        assert_eq!(bitcode::deserialize::<usize>(&body).unwrap(), 1);
+
+        let unknown = format!("/proofs/{}", "0".repeat(32));
+        assert_eq!(request(addr, "GET", &unknown, &[]).0, 404);
+        assert_eq!(request(addr, "GET", "/proofs/1000", &[]).0, 400);
+        assert_eq!(request(addr, "GET", "/proofs/abc", &[]).0, 400);
+        assert_eq!(request(addr, "GET", "/jobs", &[]).0, 404);
+        assert_eq!(request(addr, "POST", "/proofs", b"not json").0, 400);
+    }
+
+    #[test]
+    fn test_proving_server_limits() {
+        let (addr, _gate) = spawn_server_with(|server| {
+            server
+                .with_max_body_len(16)
+                .with_max_connections(1)
+                .with_io_timeout(Duration::from_millis(200))
+        });
+
+        // An idle client holds the only connection until its reads time out.
+        let idle = TcpStream::connect(addr).unwrap();
+        let rejected = raw_request(addr, b"");
+        assert!(rejected.starts_with("HTTP/1.1 503"), "{rejected}");
+        assert!(rejected.ends_with("too many connections"), "{rejected}");
+        let mut timed_out = String::new();
+        BufReader::new(idle).read_line(&mut timed_out).unwrap();
+        assert!(timed_out.starts_with("HTTP/1.1 400"), "{timed_out}");
+
+        // Once the idle connection is released, a body larger than allowed is rejected from its
+        // `Content-Length` alone.
+        let too_large = b"POST /proofs HTTP/1.1\r\nContent-Length: 32\r\n\r\n";
+        let mut rejected = raw_request(addr, too_large);
+        for _ in 0..100 {
+            if !rejected.starts_with("HTTP/1.1 503") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+            rejected = raw_request(addr, too_large);
+        }
+        assert!(rejected.starts_with("HTTP/1.1 400"), "{rejected}");
+        assert!(
+            rejected.contains("exceeds the limit of 16 bytes"),
+            "{rejected}"
+        );
+    }
+
+    #[test]
+    fn test_proving_server_queue_full() {
+        let (addr, gate) =
+            spawn_server_with_service(|service| service.with_max_queued_jobs(1), |server| server);
+        let mut input = StdIn::default();
+        input.write(&1u32);
+        let proving = submit(addr, &input);
+        // Wait for the worker to take the first job off the queue.
+        while job_status(addr, proving) == ProvingJobStatus::Queued {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let queued = submit(addr, &input);
+        let (status, body) = request(
+            addr,
+            "POST",
+            "/proofs",
+            &serde_json::to_vec(&input).unwrap(),
+        );
+        assert_eq!(status, 503);
+        assert_eq!(body, b"the proving queue is full");
+
+        // The queue has room again once the queued job is being proven.
+        gate.send(()).unwrap();
+        gate.send(()).unwrap();
+        while job_status(addr, queued) == ProvingJobStatus::Queued {
+            thread::sleep(Duration::from_millis(10));
+        }
+        submit(addr, &input);
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    str::FromStr,
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use openvm_circuit::arch::VmConfig;
+use openvm_stark_backend::Chip;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    prover::{vm::ContinuationVmProof, AppProver},
+    StdIn, F, SC,
+};
+
+mod http;
+pub use http::*;
+
+/// Default upper bound on the number of jobs waiting to be proven.
+pub const DEFAULT_MAX_QUEUED_JOBS: usize = 64;
+/// Default time a finished job is kept before it is evicted.
+pub const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Random id of a job, written as 32 hex digits. Ids are drawn from a cryptographically secure
+/// generator, so knowing the id of a job does not help finding the ids of other jobs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProvingJobId(u128);
+
+impl ProvingJobId {
+    fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl fmt::Display for ProvingJobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+impl FromStr for ProvingJobId {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        eyre::ensure!(
+            s.len() == 32 && s.bytes().all(|b| b.is_ascii_hexdigit()),
+            "job id must be 32 hex digits"
+        );
+        Ok(Self(u128::from_str_radix(s, 16)?))
+    }
+}
+
+impl Serialize for ProvingJobId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProvingJobId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProvingJobStatus {
+    Queued,
+    /// The exe is executing and its segments are proven as they finish executing.
+    Proving {
+        segments_proven: usize,
+    },
+    Done,
+    Failed {
+        error: String,
+    },
+}
+
+impl ProvingJobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed { .. })
+    }
+}
+
+/// Why [ProvingService::submit] rejected a job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmitError {
+    /// [ProvingService::with_max_queued_jobs] jobs are already waiting to be proven.
+    QueueFull,
+    /// [ProvingService::shutdown] was called.
+    ShutDown,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QueueFull => write!(f, "the proving queue is full"),
+            Self::ShutDown => write!(f, "the proving service is shut down"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+enum ProvingJob<P> {
+    Queued,
+    Proving { segments_proven: usize },
+    Done(P, Instant),
+    Failed(String, Instant),
+}
+
+impl<P> ProvingJob<P> {
+    fn finished_at(&self) -> Option<Instant> {
+        match self {
+            Self::Queued | Self::Proving { .. } => None,
+            Self::Done(_, at) | Self::Failed(_, at) => Some(*at),
+        }
+    }
+}
+
+struct State<P> {
+    jobs: HashMap<ProvingJobId, ProvingJob<P>>,
+    /// Jobs waiting to be proven, in the order they were submitted.
+    queue: VecDeque<(ProvingJobId, StdIn)>,
+    shut_down: bool,
+}
+
+struct Shared<P> {
+    state: Mutex<State<P>>,
+    /// Notified when a job is queued or the service is shut down.
+    queue_changed: Condvar,
+}
+
+/// Proves one input. The callback is called with the index of each proven segment.
+type ProveFn<P> = Box<dyn Fn(StdIn, &mut dyn FnMut(usize)) -> P + Send>;
+
+/// Long-running prover. Jobs are proven one at a time on a background thread, in the order they
+/// were submitted.
+///
+/// At most [ProvingService::with_max_queued_jobs] jobs wait to be proven at a time, and finished
+/// jobs whose proof is not taken are evicted after [ProvingService::with_result_ttl]. Shutting
+/// the service down, or dropping it, cancels the queued jobs.
+///
+/// [AppProvingService] proves app proofs with a proving key and committed exe which are loaded
+/// once and shared by all jobs. [ProvingServer] exposes a service over HTTP.
+pub struct ProvingService<P> {
+    shared: Arc<Shared<P>>,
+    max_queued_jobs: usize,
+    result_ttl: Duration,
+    worker: Option<JoinHandle<()>>,
+}
+
+pub type AppProvingService = ProvingService<ContinuationVmProof<SC>>;
+
+impl AppProvingService {
+    pub fn new<VC>(app_prover: AppProver<VC>) -> Self
+    where
+        VC: VmConfig<F> + Send + Sync + 'static,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        Self::from_fn(move |input, on_segment_proven| {
+            app_prover.generate_app_proof_with_progress(input, on_segment_proven)
+        })
+    }
+}
+
+impl<P: Send + 'static> ProvingService<P> {
+    /// Creates a service which proves each job with `prove`. `prove` reports progress by calling
+    /// its second argument with the index of each proven segment.
+    pub fn from_fn(prove: impl Fn(StdIn, &mut dyn FnMut(usize)) -> P + Send + 'static) -> Self {
+        let prove: ProveFn<P> = Box::new(prove);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                jobs: HashMap::new(),
+                queue: VecDeque::new(),
+                shut_down: false,
+            }),
+            queue_changed: Condvar::new(),
+        });
+        let worker_shared = shared.clone();
+        let worker = thread::spawn(move || {
+            while let Some((id, input)) = worker_shared.next_job() {
+                let set = |job| worker_shared.state.lock().unwrap().jobs.insert(id, job);
+                set(ProvingJob::Proving { segments_proven: 0 });
+                let mut on_segment_proven = |seg_idx: usize| {
+                    set(ProvingJob::Proving {
+                        segments_proven: seg_idx + 1,
+                    });
+                };
+                let job =
+                    match catch_unwind(AssertUnwindSafe(|| prove(input, &mut on_segment_proven))) {
+                        Ok(proof) => ProvingJob::Done(proof, Instant::now()),
+                        Err(err) => ProvingJob::Failed(panic_message(err), Instant::now()),
+                    };
+                set(job);
+            }
+        });
+        Self {
+            shared,
+            max_queued_jobs: DEFAULT_MAX_QUEUED_JOBS,
+            result_ttl: DEFAULT_RESULT_TTL,
+            worker: Some(worker),
+        }
+    }
+}
+
+impl<P> ProvingService<P> {
+    /// Sets the number of jobs which can wait to be proven, not counting the job being proven.
+    pub fn with_max_queued_jobs(mut self, max_queued_jobs: usize) -> Self {
+        self.max_queued_jobs = max_queued_jobs;
+        self
+    }
+
+    /// Sets how long a finished job is kept for its proof to be taken.
+    pub fn with_result_ttl(mut self, result_ttl: Duration) -> Self {
+        self.result_ttl = result_ttl;
+        self
+    }
+
+    /// Queues a proof of the exe on `input` and returns the id to poll it with.
+    pub fn submit(&self, input: StdIn) -> Result<ProvingJobId, SubmitError> {
+        let mut state = self.lock_state();
+        if state.shut_down {
+            return Err(SubmitError::ShutDown);
+        }
+        if state.queue.len() >= self.max_queued_jobs {
+            return Err(SubmitError::QueueFull);
+        }
+        let id = loop {
+            let id = ProvingJobId::random();
+            if !state.jobs.contains_key(&id) {
+                break id;
+            }
+        };
+        state.jobs.insert(id, ProvingJob::Queued);
+        state.queue.push_back((id, input));
+        self.shared.queue_changed.notify_one();
+        Ok(id)
+    }
+
+    /// Returns `None` if the id is unknown, or its proof was already taken or evicted.
+    pub fn status(&self, id: ProvingJobId) -> Option<ProvingJobStatus> {
+        self.lock_state().jobs.get(&id).map(|job| match job {
+            ProvingJob::Queued => ProvingJobStatus::Queued,
+            ProvingJob::Proving { segments_proven } => ProvingJobStatus::Proving {
+                segments_proven: *segments_proven,
+            },
+            ProvingJob::Done(..) => ProvingJobStatus::Done,
+            ProvingJob::Failed(error, _) => ProvingJobStatus::Failed {
+                error: error.clone(),
+            },
+        })
+    }
+
+    /// Removes a finished job and returns its proof, or the reason it failed.
+    pub fn take_proof(&self, id: ProvingJobId) -> Option<Result<P, String>> {
+        let mut state = self.lock_state();
+        state.jobs.get(&id)?.finished_at()?;
+        match state.jobs.remove(&id).unwrap() {
+            ProvingJob::Done(proof, _) => Some(Ok(proof)),
+            ProvingJob::Failed(err, _) => Some(Err(err)),
+            ProvingJob::Queued | ProvingJob::Proving { .. } => unreachable!(),
+        }
+    }
+
+    /// Stops accepting jobs and fails the queued jobs. The job being proven, if any, still
+    /// finishes.
+    pub fn shutdown(&self) {
+        let mut state = self.lock_state();
+        state.shut_down = true;
+        let now = Instant::now();
+        let cancelled: Vec<_> = state.queue.drain(..).map(|(id, _)| id).collect();
+        for id in cancelled {
+            state
+                .jobs
+                .insert(id, ProvingJob::Failed("cancelled".to_string(), now));
+        }
+        self.shared.queue_changed.notify_all();
+    }
+
+    /// Locks the state after evicting the finished jobs older than the result TTL.
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State<P>> {
+        let mut state = self.shared.state.lock().unwrap();
+        let ttl = self.result_ttl;
+        state.jobs.retain(|_, job| {
+            job.finished_at()
+                .map_or(true, |finished_at| finished_at.elapsed() < ttl)
+        });
+        state
+    }
+}
+
+impl<P> Shared<P> {
+    /// Waits for the next queued job, or returns `None` once the service is shut down.
+    fn next_job(&self) -> Option<(ProvingJobId, StdIn)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.shut_down {
+                return None;
+            }
+            if let Some(job) = state.queue.pop_front() {
+                return Some(job);
+            }
+            state = self.queue_changed.wait(state).unwrap();
+        }
+    }
+}
+
+impl<P> Drop for ProvingService<P> {
+    /// Cancels the queued jobs and waits for the job being proven, if any.
+    fn drop(&mut self) {
+        self.shutdown();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn panic_message(err: Box<dyn std::any::Any + Send>) -> String {
+    err.downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| err.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "proving panicked".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// A service whose jobs each wait for a signal on the returned sender.
+    fn gated_service() -> (ProvingService<()>, mpsc::Sender<()>) {
+        let (gate, gate_rx) = mpsc::channel::<()>();
+        let gate_rx = Mutex::new(gate_rx);
+        let service = ProvingService::from_fn(move |_, _| {
+            let _ = gate_rx.lock().unwrap().recv();
+        });
+        (service, gate)
+    }
+
+    fn wait_until(service: &ProvingService<()>, id: ProvingJobId, status: ProvingJobStatus) {
+        while service.status(id).as_ref() != Some(&status) {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_job_ids_are_random() {
+        let (service, gate) = gated_service();
+        let ids: Vec<_> = (0..2)
+            .map(|_| service.submit(StdIn::default()).unwrap())
+            .collect();
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(ids[0].to_string().parse::<ProvingJobId>().unwrap(), ids[0]);
+        assert!("1".parse::<ProvingJobId>().is_err());
+        drop(gate);
+    }
+
+    #[test]
+    fn test_finished_jobs_expire() {
+        let (service, gate) = gated_service();
+        let service = service.with_result_ttl(Duration::from_millis(50));
+        let id = service.submit(StdIn::default()).unwrap();
+        gate.send(()).unwrap();
+        wait_until(&service, id, ProvingJobStatus::Done);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(service.status(id), None);
+        assert!(service.take_proof(id).is_none());
+    }
+
+    #[test]
+    fn test_shutdown_cancels_queued_jobs() {
+        let (service, gate) = gated_service();
+        let service = service.with_max_queued_jobs(1);
+        let proving = service.submit(StdIn::default()).unwrap();
+        wait_until(
+            &service,
+            proving,
+            ProvingJobStatus::Proving { segments_proven: 0 },
+        );
+        let queued = service.submit(StdIn::default()).unwrap();
+        assert_eq!(
+            service.submit(StdIn::default()),
+            Err(SubmitError::QueueFull)
+        );
+
+        service.shutdown();
+        assert_eq!(
+            service.status(queued),
+            Some(ProvingJobStatus::Failed {
+                error: "cancelled".to_string()
+            })
+        );
+        assert_eq!(service.submit(StdIn::default()), Err(SubmitError::ShutDown));
+        // The job being proven still finishes.
+        gate.send(()).unwrap();
+        wait_until(&service, proving, ProvingJobStatus::Done);
+    }
+}
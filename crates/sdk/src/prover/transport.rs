@@ -0,0 +1,68 @@
+use std::io::{Read, Write};
+
+use eyre::{ensure, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Upper bound on the size of a single message, to reject corrupted or malicious length prefixes
+/// before reading. Aggregation jobs and their proofs are well below this.
+pub const MAX_MESSAGE_LEN: u64 = 1 << 28;
+
+/// Writes `msg` as a little-endian `u64` length prefix followed by its `bitcode` encoding.
+pub fn write_message<T: Serialize>(writer: &mut impl Write, msg: &T) -> Result<()> {
+    let bytes = bitcode::serialize(msg)?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a message written by [write_message].
+pub fn read_message<T: DeserializeOwned>(reader: &mut impl Read) -> Result<T> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    ensure!(
+        len <= MAX_MESSAGE_LEN,
+        "message of {len} bytes exceeds the limit of {MAX_MESSAGE_LEN} bytes"
+    );
+    // The message is read as it arrives rather than allocated up front, so a large length prefix
+    // alone does not allocate.
+    let mut bytes = vec![];
+    reader.take(len).read_to_end(&mut bytes)?;
+    ensure!(bytes.len() as u64 == len, "truncated message");
+    Ok(bitcode::deserialize(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut buf = vec![];
+        write_message(&mut buf, &(7u32, "job".to_string())).unwrap();
+        write_message(&mut buf, &vec![1u64, 2, 3]).unwrap();
+
+        let mut reader = buf.as_slice();
+        let first: (u32, String) = read_message(&mut reader).unwrap();
+        let second: Vec<u64> = read_message(&mut reader).unwrap();
+        assert_eq!(first, (7, "job".to_string()));
+        assert_eq!(second, vec![1, 2, 3]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_message_rejects_oversized_prefix() {
+        let buf = (MAX_MESSAGE_LEN + 1).to_le_bytes();
+        assert!(read_message::<Vec<u8>>(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_message_rejects_truncated_message() {
+        let mut buf = vec![];
+        write_message(&mut buf, &vec![1u64, 2, 3]).unwrap();
+        buf.pop();
+        let err = read_message::<Vec<u64>>(&mut buf.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("truncated message"), "{err}");
+    }
+}
@@ -6,6 +6,7 @@ use openvm_circuit::{
         hasher::poseidon2::vm_poseidon2_hasher, Streams, VirtualMachine, VmComplexTraceHeights,
         VmConfig,
     },
+    metrics::memory::track_peak_rss,
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
 use openvm_stark_backend::{
@@ -57,6 +58,45 @@ where
     pub fn set_override_trace_heights(&mut self, overridden_heights: VmComplexTraceHeights) {
         self.overridden_heights = Some(overridden_heights);
     }
+
+    /// Same as [ContinuationVmProver::prove], but proves each segment as soon as its trace is
+    /// generated and calls `on_segment_proven` with the index of each proven segment.
+    pub fn prove_with_progress(
+        &self,
+        input: impl Into<Streams<Val<SC>>>,
+        mut on_segment_proven: impl FnMut(usize),
+    ) -> ContinuationVmProof<SC>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        assert!(self.pk.vm_config.system().continuation_enabled);
+        let e = E::new(self.pk.fri_params);
+        let vm = VirtualMachine::new_with_overridden_trace_heights(
+            e,
+            self.pk.vm_config.clone(),
+            self.overridden_heights.clone(),
+        );
+        let mut per_segment = vec![];
+        let final_memory = vm
+            .execute_and_generate_streaming(self.committed_exe.clone(), input, |seg_idx, input| {
+                let proof = tracing::info_span!("prove_segment", segment = seg_idx)
+                    .in_scope(|| vm.prove_single(&self.pk.vm_pk, input));
+                per_segment.push(proof);
+                on_segment_proven(seg_idx);
+            })
+            .unwrap();
+        let user_public_values = UserPublicValuesProof::compute(
+            self.pk.vm_config.system().memory_config.memory_dimensions(),
+            self.pk.vm_config.system().num_public_values,
+            &vm_poseidon2_hasher(),
+            final_memory.as_ref().unwrap(),
+        );
+        ContinuationVmProof {
+            per_segment,
+            user_public_values,
+        }
+    }
 }
 
 impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> ContinuationVmProver<SC>
@@ -74,16 +114,17 @@ where
             self.pk.vm_config.clone(),
             self.overridden_heights.clone(),
         );
-        let results = vm
-            .execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
-            .unwrap();
+        let results = track_peak_rss("execute_and_trace_gen", || {
+            vm.execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
+                .unwrap()
+        });
         let user_public_values = UserPublicValuesProof::compute(
             self.pk.vm_config.system().memory_config.memory_dimensions(),
             self.pk.vm_config.system().num_public_values,
             &vm_poseidon2_hasher(),
             results.final_memory.as_ref().unwrap(),
         );
-        let per_segment = vm.prove(&self.pk.vm_pk, results);
+        let per_segment = track_peak_rss("stark_prove", || vm.prove(&self.pk.vm_pk, results));
         ContinuationVmProof {
             per_segment,
             user_public_values,
@@ -119,11 +160,12 @@ where
         assert!(!self.pk.vm_config.system().continuation_enabled);
         let e = E::new(self.pk.fri_params);
         let vm = VirtualMachine::new(e, self.pk.vm_config.clone());
-        let mut results = vm
-            .execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
-            .unwrap();
+        let mut results = track_peak_rss("execute_and_trace_gen", || {
+            vm.execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
+                .unwrap()
+        });
         let segment = results.per_segment.pop().unwrap();
-        vm.prove_single(&self.pk.vm_pk, segment)
+        track_peak_rss("stark_prove", || vm.prove_single(&self.pk.vm_pk, segment))
     }
 }
 
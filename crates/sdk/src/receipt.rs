@@ -0,0 +1,190 @@
+use std::borrow::Borrow;
+
+use eyre::{eyre, Result};
+use openvm_circuit::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        ExitCode, SystemConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID, PROGRAM_CACHED_TRACE_INDEX,
+    },
+    system::{
+        connector::VmConnectorPvs,
+        memory::{memory_image_to_equipartition, merkle::MemoryMerklePvs, tree::MemoryNode, CHUNK},
+    },
+};
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    engine::StarkFriEngine,
+    openvm_stark_backend::{
+        p3_field::{AbstractField, PrimeField32},
+        prover::types::Proof,
+    },
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{keygen::AppVerifyingKey, prover::vm::ContinuationVmProof, NonRootCommittedExe, F, SC};
+
+/// An app proof bundled with the exe and verifying key it was generated for, so the pieces
+/// cannot be mixed up on the way to the verifier.
+#[derive(Serialize, Deserialize)]
+pub struct Receipt {
+    pub proof: ContinuationVmProof<SC>,
+    /// Commitment of the app program. Every segment proof commits to it as its cached program
+    /// trace.
+    pub app_program_commit: [F; DIGEST_SIZE],
+    /// Hash of the app verifying key, see [vk_hash].
+    pub vk_hash: [F; DIGEST_SIZE],
+}
+
+impl Receipt {
+    pub fn new(
+        proof: ContinuationVmProof<SC>,
+        app_committed_exe: &NonRootCommittedExe,
+        app_vk: &AppVerifyingKey,
+    ) -> Result<Self> {
+        Ok(Self {
+            proof,
+            app_program_commit: app_committed_exe.get_program_commit().into(),
+            vk_hash: vk_hash(app_vk)?,
+        })
+    }
+
+    /// Verifies the segment proofs against `app_vk` and checks that the receipt is bound to
+    /// `app_vk` and to `app_committed_exe`:
+    /// - the first segment starts at the `pc_start` of the exe, from the memory root of its
+    ///   initial memory,
+    /// - every other segment starts at the pc and from the memory root the previous one ended
+    ///   with,
+    /// - only the last segment terminates, and it exits successfully.
+    ///
+    /// The public values are checked against the final memory root of the last segment. The
+    /// memory layout is that of `app_system_config`.
+    pub fn verify(
+        &self,
+        app_vk: &AppVerifyingKey,
+        app_committed_exe: &NonRootCommittedExe,
+        app_system_config: &SystemConfig,
+    ) -> Result<()> {
+        if self.vk_hash != vk_hash(app_vk)? {
+            return Err(eyre!("receipt is for a different verifying key"));
+        }
+        let expected_commit: [F; DIGEST_SIZE] = app_committed_exe.get_program_commit().into();
+        if self.app_program_commit != expected_commit {
+            return Err(eyre!("receipt is for a different exe"));
+        }
+        let num_segments = self.proof.per_segment.len();
+        if num_segments == 0 {
+            return Err(eyre!("receipt has no segment proofs"));
+        }
+
+        let hasher = vm_poseidon2_hasher();
+        let memory_dimensions = app_system_config.memory_config.memory_dimensions();
+        let mut expected_pc = F::from_canonical_u32(app_committed_exe.exe.pc_start);
+        let mut expected_root = MemoryNode::tree_from_memory(
+            memory_dimensions,
+            &memory_image_to_equipartition(app_committed_exe.exe.init_memory.clone()),
+            &hasher,
+        )
+        .hash();
+        let engine = BabyBearPoseidon2Engine::new(app_vk.fri_params);
+        for (i, segment_proof) in self.proof.per_segment.iter().enumerate() {
+            let program_commit: [F; DIGEST_SIZE] = segment_proof.commitments.main_trace
+                [PROGRAM_CACHED_TRACE_INDEX]
+                .clone()
+                .into();
+            if program_commit != self.app_program_commit {
+                return Err(eyre!("segment {i} proves a different program"));
+            }
+            engine
+                .verify(&app_vk.app_vm_vk, segment_proof)
+                .map_err(|e| eyre!("segment {i} failed to verify: {e:?}"))?;
+
+            let connector_pvs: &VmConnectorPvs<_> =
+                air_public_values(segment_proof, CONNECTOR_AIR_ID, i)?.borrow();
+            let merkle_pvs: &MemoryMerklePvs<_, CHUNK> =
+                air_public_values(segment_proof, MERKLE_AIR_ID, i)?.borrow();
+            if connector_pvs.initial_pc != expected_pc {
+                return Err(eyre!(
+                    "segment {i} starts at pc {} instead of {expected_pc}",
+                    connector_pvs.initial_pc
+                ));
+            }
+            if merkle_pvs.initial_root != expected_root {
+                return Err(eyre!("segment {i} starts from a different memory root"));
+            }
+            if i + 1 < num_segments && connector_pvs.is_terminate != F::ZERO {
+                return Err(eyre!("segment {i} terminates before the last segment"));
+            }
+            expected_pc = connector_pvs.final_pc;
+            expected_root = merkle_pvs.final_root;
+        }
+
+        let last_segment_proof = self.proof.per_segment.last().unwrap();
+        let connector_pvs: &VmConnectorPvs<_> =
+            air_public_values(last_segment_proof, CONNECTOR_AIR_ID, num_segments - 1)?.borrow();
+        if connector_pvs.is_terminate != F::ONE {
+            return Err(eyre!("the last segment does not terminate"));
+        }
+        if connector_pvs.exit_code != F::from_canonical_u32(ExitCode::Success as u32) {
+            return Err(eyre!(
+                "program exited with code {}",
+                connector_pvs.exit_code
+            ));
+        }
+
+        self.proof
+            .user_public_values
+            .verify(
+                memory_dimensions,
+                app_system_config.num_public_values,
+                &hasher,
+                expected_root,
+            )
+            .map_err(|e| eyre!("invalid public values: {e}"))
+    }
+
+    /// Public values as bytes, one byte per field element as revealed by the guest.
+    pub fn public_values_bytes(&self) -> Result<Vec<u8>> {
+        self.proof
+            .user_public_values
+            .public_values
+            .iter()
+            .map(|value| {
+                u8::try_from(value.as_canonical_u32())
+                    .map_err(|_| eyre!("public value {value} is not a byte"))
+            })
+            .collect()
+    }
+
+    /// Decodes the first `num_bytes` public value bytes with bitcode. Public values are padded
+    /// with zeros, so the length of the value revealed by the guest must be given.
+    pub fn decode_public_values<T: DeserializeOwned>(&self, num_bytes: usize) -> Result<T> {
+        let bytes = self.public_values_bytes()?;
+        let bytes = bytes
+            .get(..num_bytes)
+            .ok_or_else(|| eyre!("only {} public value bytes", bytes.len()))?;
+        Ok(bitcode::deserialize(bytes)?)
+    }
+}
+
+fn air_public_values(proof: &Proof<SC>, air_id: usize, segment_idx: usize) -> Result<&[F]> {
+    proof
+        .per_air
+        .iter()
+        .find(|air_proof_data| air_proof_data.air_id == air_id)
+        .map(|air_proof_data| air_proof_data.public_values.as_slice())
+        .ok_or_else(|| eyre!("segment {segment_idx} has no proof of required AIR {air_id}"))
+}
+
+/// Poseidon2 hash of the bitcode serialization of the app verifying key, one byte per field
+/// element.
+pub fn vk_hash(app_vk: &AppVerifyingKey) -> Result<[F; DIGEST_SIZE]> {
+    let mut values: Vec<F> = bitcode::serialize(app_vk)?
+        .into_iter()
+        .map(F::from_canonical_u8)
+        .collect();
+    // `merkle_root` expects a full binary tree of chunks.
+    let len = values.len().div_ceil(DIGEST_SIZE).next_power_of_two() * DIGEST_SIZE;
+    values.resize(len, F::ZERO);
+    Ok(vm_poseidon2_hasher().merkle_root(&values))
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{create_dir_all, read, read_dir, rename, File},
+    io::{copy, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use eyre::{bail, eyre, Result};
+use openvm_native_recursion::halo2::{
+    utils::{Halo2ParamsReader, DK},
+    Halo2Params,
+};
+use snark_verifier_sdk::snark_verifier::halo2_base::halo2_proofs::poly::commitment::Params;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::fs::{read_from_file_json, write_to_file_json};
+
+/// Location of the SRS files of the perpetual powers of tau ceremony (challenge 0085), converted
+/// to the Halo2 format. The file for log degree `k` is `kzg_bn254_{k}.srs`.
+pub const DEFAULT_SRS_URL: &str = "https://axiom-crypto.s3.amazonaws.com/challenge_0085";
+
+/// Keccak-256 hashes of the SRS files seen so far, keyed by log degree.
+const HASHES_FILE: &str = "hashes.json";
+
+/// Manages the KZG trusted setup in a local directory, in the same layout as
+/// [CacheHalo2ParamsReader](openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader).
+///
+/// The SRS for a log degree is taken from the directory if present. Otherwise it is sliced
+/// from the SRS of the smallest larger degree in the directory, or downloaded if there is none.
+/// Every SRS is checked against the trusted setup the verifier contracts are built for, and
+/// against its Keccak-256 hash if one was pinned with [SrsManager::with_expected_hash] or
+/// recorded when the file was first added to the directory.
+pub struct SrsManager {
+    dir: PathBuf,
+    url: String,
+    expected_hashes: BTreeMap<usize, String>,
+    cached_params: Mutex<HashMap<usize, Arc<Halo2Params>>>,
+}
+
+impl SrsManager {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            url: DEFAULT_SRS_URL.to_string(),
+            expected_hashes: BTreeMap::new(),
+            cached_params: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Downloads SRS files from `url` instead of [DEFAULT_SRS_URL].
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Requires the SRS file for log degree `k` to have the hex encoded Keccak-256 `hash`.
+    pub fn with_expected_hash(mut self, k: usize, hash: impl Into<String>) -> Self {
+        self.expected_hashes.insert(k, hash.into().to_lowercase());
+        self
+    }
+
+    pub fn srs_path(&self, k: usize) -> PathBuf {
+        self.dir.join(format!("kzg_bn254_{k}.srs"))
+    }
+
+    /// Returns the SRS for log degree `k`, adding it to the directory first if needed.
+    pub fn fetch(&self, k: usize) -> Result<Arc<Halo2Params>> {
+        if let Some(params) = self.cached_params.lock().unwrap().get(&k) {
+            return Ok(params.clone());
+        }
+        let params = Arc::new(self.load_or_add(k)?);
+        self.cached_params.lock().unwrap().insert(k, params.clone());
+        Ok(params)
+    }
+
+    fn load_or_add(&self, k: usize) -> Result<Halo2Params> {
+        if !self.srs_path(k).exists() {
+            let larger_k = self
+                .local_degrees()?
+                .into_iter()
+                .find(|&local_k| local_k > k);
+            if let Some(larger_k) = larger_k {
+                tracing::info!("slicing SRS for k={k} from k={larger_k}");
+                let mut params = self.load(larger_k)?;
+                params.downsize(k as u32);
+                self.write_srs(k, &params)?;
+                self.record_hash(k, &read(self.srs_path(k))?)?;
+                return Ok(params);
+            }
+            self.download(k)?;
+        }
+        self.load(k)
+    }
+
+    /// Reads and validates the SRS file for log degree `k`.
+    fn load(&self, k: usize) -> Result<Halo2Params> {
+        let path = self.srs_path(k);
+        let bytes = read(&path)?;
+        self.record_hash(k, &bytes)?;
+        let params = Halo2Params::read(&mut bytes.as_slice())?;
+        if params.k() as usize != k {
+            bail!(
+                "{} has log degree {}, expected {k}",
+                path.display(),
+                params.k()
+            );
+        }
+        if params.get_g()[0] != DK.svk.g || params.g2() != DK.g2 || params.s_g2() != DK.s_g2 {
+            bail!("{} is not from the expected trusted setup", path.display());
+        }
+        Ok(params)
+    }
+
+    /// Checks the hash of the SRS file for log degree `k` against the pinned or recorded hash,
+    /// and records it if there is none.
+    fn record_hash(&self, k: usize, bytes: &[u8]) -> Result<()> {
+        let hash = keccak256_hex(bytes);
+        let hashes_path = self.dir.join(HASHES_FILE);
+        let mut hashes: BTreeMap<usize, String> = if hashes_path.exists() {
+            read_from_file_json(&hashes_path)?
+        } else {
+            BTreeMap::new()
+        };
+        for expected in [self.expected_hashes.get(&k), hashes.get(&k)]
+            .into_iter()
+            .flatten()
+        {
+            if *expected != hash {
+                bail!(
+                    "{} has hash {hash}, expected {expected}. Delete it to fetch it again",
+                    self.srs_path(k).display()
+                );
+            }
+        }
+        if hashes.insert(k, hash).is_none() {
+            write_to_file_json(hashes_path, hashes)?;
+        }
+        Ok(())
+    }
+
+    fn download(&self, k: usize) -> Result<()> {
+        let url = format!("{}/kzg_bn254_{k}.srs", self.url);
+        tracing::info!("downloading {url}");
+        let response = ureq::get(&url)
+            .call()
+            .map_err(|e| eyre!("failed to download {url}: {e}"))?;
+        create_dir_all(&self.dir)?;
+        // Download next to the destination first so an interrupted download is never used.
+        let partial_path = self.srs_path(k).with_extension("srs.part");
+        copy(
+            &mut response.into_reader(),
+            &mut File::create(&partial_path)?,
+        )?;
+        rename(partial_path, self.srs_path(k))?;
+        Ok(())
+    }
+
+    fn write_srs(&self, k: usize, params: &Halo2Params) -> Result<()> {
+        create_dir_all(&self.dir)?;
+        let partial_path = self.srs_path(k).with_extension("srs.part");
+        let mut writer = BufWriter::new(File::create(&partial_path)?);
+        params.write(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        rename(partial_path, self.srs_path(k))?;
+        Ok(())
+    }
+
+    /// Log degrees of the SRS files in the directory, in increasing order.
+    fn local_degrees(&self) -> Result<Vec<usize>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut degrees = vec![];
+        for entry in read_dir(&self.dir)? {
+            let file_name = entry?.file_name();
+            let k = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("kzg_bn254_"))
+                .and_then(|name| name.strip_suffix(".srs"))
+                .and_then(|k| k.parse().ok());
+            degrees.extend(k);
+        }
+        degrees.sort();
+        Ok(degrees)
+    }
+}
+
+impl Halo2ParamsReader for SrsManager {
+    fn read_params(&self, k: usize) -> Arc<Halo2Params> {
+        self.fetch(k)
+            .unwrap_or_else(|e| panic!("failed to fetch SRS for k={k}: {e}"))
+    }
+}
+
+fn keccak256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
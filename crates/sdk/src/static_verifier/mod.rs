@@ -5,6 +5,7 @@ use openvm_native_recursion::{
     config::outer::{new_from_outer_multi_vk, OuterConfig},
     digest::DigestVariable,
     fri::TwoAdicFriPcsVariable,
+    gnark::{GnarkCircuit, GnarkWitness},
     halo2::{verifier::Halo2VerifierProvingKey, DslOperations, Halo2Params, Halo2Prover},
     hints::Hintable,
     stark::StarkVerifier,
@@ -30,6 +31,11 @@ use crate::{
     RootSC, F, SC,
 };
 
+#[cfg(feature = "static-verifier-store")]
+mod store;
+#[cfg(feature = "static-verifier-store")]
+pub use store::*;
+
 impl RootVerifierProvingKey {
     /// Keygen the static verifier for this root verifier.
     pub fn keygen_static_verifier(
@@ -46,16 +52,17 @@ impl RootVerifierProvingKey {
         }
     }
 
+    /// Compiles the static verifier for this root verifier to a gnark circuit, which a Groth16
+    /// prover can prove in place of the Halo2 verifier and wrapper circuits. Like the Halo2
+    /// keygen, the circuit only depends on the shape of `root_proof`.
+    pub fn gnark_static_verifier(&self, root_proof: &Proof<RootSC>) -> GnarkCircuit {
+        let dsl_operations = build_static_verifier_operations(self, root_proof);
+        GnarkCircuit::compile(dsl_operations.operations, dsl_operations.num_public_values)
+    }
+
     pub fn generate_dummy_root_proof(&self, dummy_internal_proof: Proof<SC>) -> Proof<RootSC> {
         let prover = RootVerifierLocalProver::new(self.clone());
-        // 2 * DIGEST_SIZE for exe_commit and leaf_commit
-        let num_public_values = prover
-            .root_verifier_pk
-            .vm_pk
-            .vm_config
-            .system
-            .num_public_values
-            - 2 * DIGEST_SIZE;
+        let num_public_values = prover.root_verifier_pk.num_user_public_values;
         SingleSegmentVmProver::prove(
             &prover,
             RootVmVerifierInput {
@@ -67,6 +74,17 @@ impl RootVerifierProvingKey {
     }
 }
 
+/// The witness of the gnark static verifier circuit for `root_proof`.
+pub fn gnark_static_verifier_witness(root_proof: &Proof<RootSC>) -> GnarkWitness {
+    let mut witness = Witness::default();
+    root_proof.write(&mut witness);
+    GnarkWitness::from(&witness)
+}
+
+/// Number of public values of the static verifier before the user public values: `exe_commit`
+/// and `leaf_verifier_commit`, each compressed into one BN254 element.
+pub const NUM_APP_COMMIT_PUBLIC_VALUES: usize = 2;
+
 fn build_static_verifier_operations(
     root_verifier_pk: &RootVerifierProvingKey,
     proof: &Proof<RootSC>,
@@ -120,11 +138,11 @@ fn build_static_verifier_operations(
         let pvs = RootVmVerifierPvs::from_flatten(public_values);
         let exe_commit = compress_babybear_var_to_bn254(&mut builder, pvs.exe_commit);
         let leaf_commit = compress_babybear_var_to_bn254(&mut builder, pvs.leaf_verifier_commit);
-        let num_public_values = 2 + pvs.public_values.len();
+        let num_public_values = NUM_APP_COMMIT_PUBLIC_VALUES + pvs.public_values.len();
         builder.static_commit_public_value(0, exe_commit);
         builder.static_commit_public_value(1, leaf_commit);
         for (i, x) in pvs.public_values.into_iter().enumerate() {
-            builder.static_commit_public_value(i + 2, x);
+            builder.static_commit_public_value(i + NUM_APP_COMMIT_PUBLIC_VALUES, x);
         }
         builder.cycle_tracker_end("VerifierProgram");
         num_public_values
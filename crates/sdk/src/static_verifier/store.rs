@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use openvm_native_recursion::halo2::{utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey};
+use openvm_stark_sdk::openvm_stark_backend::prover::types::Proof;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    config::AggStarkConfig,
+    fs::{read_from_file_bitcode, write_to_file_bitcode},
+    keygen::{AggStarkProvingKey, RootVerifierProvingKey},
+    RootSC, SC,
+};
+
+/// Directory of static verifier artifacts, so the Halo2 circuit synthesis and keygen for a
+/// root verifier only run once.
+///
+/// Each static verifier artifact is a [Halo2VerifierProvingKey] stored under the [static verifier
+/// digest](RootVerifierProvingKey::static_verifier_digest) of the root verifier and the log
+/// degree of the circuit. An artifact holds the proving key, the circuit configuration and
+/// break points, and the DSL operations of the circuit, which is everything needed to prove.
+/// The KZG parameters are not copied into the store and are read through a [Halo2ParamsReader]
+/// as usual.
+///
+/// The [AggStarkProvingKey] the root verifier belongs to is stored as well, under the
+/// [digest](agg_stark_config_digest) of its [AggStarkConfig], so a cache hit also skips the
+/// aggregation keygen and dummy proofs.
+#[derive(Clone, Debug)]
+pub struct StaticVerifierStore {
+    dir: PathBuf,
+}
+
+/// An [AggStarkProvingKey] along with the digest of the config it was generated for, to detect
+/// artifacts which are not at the path of their config. Generic so it can be saved by reference.
+#[derive(Serialize, Deserialize)]
+struct AggStarkArtifact<PK> {
+    config_digest: String,
+    pk: PK,
+}
+
+impl StaticVerifierStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Path of the aggregation STARK proving key for the given config digest.
+    pub fn agg_stark_path(&self, config_digest: &str) -> PathBuf {
+        self.dir
+            .join("agg_stark")
+            .join(format!("{config_digest}.pk"))
+    }
+
+    /// Loads the aggregation STARK proving key generated for `config`, or returns `None` if it
+    /// has not been stored. A stale artifact, which was generated for another config, is
+    /// treated as missing.
+    pub fn load_agg_stark(&self, config: &AggStarkConfig) -> Result<Option<AggStarkProvingKey>> {
+        let config_digest = agg_stark_config_digest(config)?;
+        let path = self.agg_stark_path(&config_digest);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let artifact: AggStarkArtifact<AggStarkProvingKey> = read_from_file_bitcode(&path)?;
+        if artifact.config_digest != config_digest {
+            tracing::warn!(
+                "ignoring stale aggregation proving key at {}",
+                path.display()
+            );
+            return Ok(None);
+        }
+        Ok(Some(artifact.pk))
+    }
+
+    /// Stores the aggregation STARK proving key generated for `config`, replacing any previous
+    /// artifact.
+    pub fn save_agg_stark(&self, config: &AggStarkConfig, pk: &AggStarkProvingKey) -> Result<()> {
+        let config_digest = agg_stark_config_digest(config)?;
+        let path = self.agg_stark_path(&config_digest);
+        write_to_file_bitcode(path, AggStarkArtifact { config_digest, pk })
+    }
+
+    /// Loads the aggregation STARK proving key for `config`, running keygen and storing the
+    /// result if there is no artifact yet. The dummy internal proof of the keygen is returned
+    /// if keygen ran, see [AggStarkProvingKey::dummy_proof_and_keygen].
+    pub fn load_or_keygen_agg_stark(
+        &self,
+        config: AggStarkConfig,
+    ) -> Result<(AggStarkProvingKey, Option<Proof<SC>>)> {
+        if let Some(pk) = self.load_agg_stark(&config)? {
+            return Ok((pk, None));
+        }
+        let (pk, dummy_internal_proof) = AggStarkProvingKey::dummy_proof_and_keygen(config);
+        self.save_agg_stark(&config, &pk)?;
+        Ok((pk, Some(dummy_internal_proof)))
+    }
+
+    /// Path of the artifact for the given static verifier digest and log degree.
+    pub fn artifact_path(&self, digest: &str, k: usize) -> PathBuf {
+        self.dir
+            .join(digest)
+            .join(format!("static_verifier_{k}.pk"))
+    }
+
+    /// Loads the static verifier of `root_verifier_pk` with log degree `k`, or returns `None`
+    /// if it has not been stored.
+    pub fn load(
+        &self,
+        root_verifier_pk: &RootVerifierProvingKey,
+        k: usize,
+    ) -> Result<Option<Halo2VerifierProvingKey>> {
+        let path = self.artifact_path(&root_verifier_pk.static_verifier_digest()?, k);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let verifier: Halo2VerifierProvingKey = read_from_file_bitcode(path)?;
+        let stored_k = verifier.pinning.metadata.config_params.k;
+        if stored_k != k {
+            eyre::bail!("static verifier artifact has log degree {stored_k}, expected {k}");
+        }
+        Ok(Some(verifier))
+    }
+
+    /// Stores the static verifier of `root_verifier_pk`, replacing any previous artifact with
+    /// the same log degree.
+    pub fn save(
+        &self,
+        root_verifier_pk: &RootVerifierProvingKey,
+        verifier: &Halo2VerifierProvingKey,
+    ) -> Result<()> {
+        let k = verifier.pinning.metadata.config_params.k;
+        let path = self.artifact_path(&root_verifier_pk.static_verifier_digest()?, k);
+        write_to_file_bitcode(path, verifier)
+    }
+
+    /// Loads the static verifier of `root_verifier_pk` with log degree `k`, running keygen and
+    /// storing the result if there is no artifact yet. The KZG parameters are only read and
+    /// `root_proof` is only called when keygen is needed.
+    pub fn load_or_keygen(
+        &self,
+        root_verifier_pk: &RootVerifierProvingKey,
+        reader: &impl Halo2ParamsReader,
+        k: usize,
+        root_proof: impl FnOnce() -> Proof<RootSC>,
+    ) -> Result<Halo2VerifierProvingKey> {
+        if let Some(verifier) = self.load(root_verifier_pk, k)? {
+            return Ok(verifier);
+        }
+        let verifier =
+            root_verifier_pk.keygen_static_verifier(&reader.read_params(k), root_proof());
+        self.save(root_verifier_pk, &verifier)?;
+        Ok(verifier)
+    }
+}
+
+/// Hex encoded Keccak-256 digest of `config` and the version of this crate. The version is
+/// included because the aggregation programs, and therefore the keys, change between versions
+/// even for the same config.
+pub fn agg_stark_config_digest(config: &AggStarkConfig) -> Result<String> {
+    let mut hasher = Keccak::v256();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(&bitcode::serialize(config)?);
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    Ok(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+impl RootVerifierProvingKey {
+    /// Hex encoded Keccak-256 digest of everything the static verifier circuit of this root
+    /// verifier depends on: the verifying key, the FRI parameters, the AIR heights and the
+    /// root program commitment.
+    pub fn static_verifier_digest(&self) -> Result<String> {
+        let mut hasher = Keccak::v256();
+        hasher.update(&bitcode::serialize(&self.vm_pk.vm_pk.get_vk())?);
+        hasher.update(&bitcode::serialize(&self.vm_pk.fri_params)?);
+        hasher.update(&bitcode::serialize(&self.air_heights)?);
+        hasher.update(&bitcode::serialize(
+            &self.root_committed_exe.get_program_commit(),
+        )?);
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+        Ok(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+}
@@ -28,6 +28,14 @@ impl StdIn {
         self.write_bytes(&bytes);
     }
 
+    /// Like [Self::write], with a header checked by `openvm::io::read_framed` in the guest so
+    /// that reading the input as a type with a different [openvm::serde::Framed] tag fails.
+    pub fn write_framed<T: Serialize + openvm::serde::Framed>(&mut self, data: &T) {
+        let words = openvm::serde::to_framed_vec(data).unwrap();
+        let bytes: Vec<u8> = words.into_iter().flat_map(|w| w.to_le_bytes()).collect();
+        self.write_bytes(&bytes);
+    }
+
     pub fn write_bytes(&mut self, data: &[u8]) {
         let field_data = data.iter().map(|b| F::from_canonical_u8(*b)).collect();
         self.buffer.push_back(field_data);
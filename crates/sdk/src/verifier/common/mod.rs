@@ -172,13 +172,9 @@ pub fn assert_required_air_for_agg_vm_present<C: Config>(
     builder: &mut Builder<C>,
     proof: &StarkProofVariable<C>,
 ) {
-    // FIXME: what if PUBLIC_VALUES_AIR_ID(3) >= proof.per_air.len()?
-    let program_air = builder.get(&proof.per_air, PROGRAM_AIR_ID);
-    builder.assert_eq::<Usize<_>>(program_air.air_id, RVar::from(PROGRAM_AIR_ID));
-    let connector_air = builder.get(&proof.per_air, CONNECTOR_AIR_ID);
-    builder.assert_eq::<Usize<_>>(connector_air.air_id, RVar::from(CONNECTOR_AIR_ID));
-    let public_values_air = builder.get(&proof.per_air, PUBLIC_VALUES_AIR_ID);
-    builder.assert_eq::<Usize<_>>(public_values_air.air_id, RVar::from(PUBLIC_VALUES_AIR_ID));
+    assert_air_present(builder, proof, PROGRAM_AIR_ID);
+    assert_air_present(builder, proof, CONNECTOR_AIR_ID);
+    assert_air_present(builder, proof, PUBLIC_VALUES_AIR_ID);
 }
 
 // TODO: This is a temporary solution. VK should be able to specify which AIRs are required. Once
@@ -187,11 +183,23 @@ pub fn assert_required_air_for_app_vm_present<C: Config>(
     builder: &mut Builder<C>,
     proof: &StarkProofVariable<C>,
 ) {
-    // FIXME: what if MERKLE_AIR_ID(4) >= proof.per_air.len()?
-    let program_air = builder.get(&proof.per_air, PROGRAM_AIR_ID);
-    builder.assert_eq::<Usize<_>>(program_air.air_id, RVar::from(PROGRAM_AIR_ID));
-    let connector_air = builder.get(&proof.per_air, CONNECTOR_AIR_ID);
-    builder.assert_eq::<Usize<_>>(connector_air.air_id, RVar::from(CONNECTOR_AIR_ID));
-    let public_values_air = builder.get(&proof.per_air, MERKLE_AIR_ID);
-    builder.assert_eq::<Usize<_>>(public_values_air.air_id, RVar::from(MERKLE_AIR_ID));
+    assert_air_present(builder, proof, PROGRAM_AIR_ID);
+    assert_air_present(builder, proof, CONNECTOR_AIR_ID);
+    assert_air_present(builder, proof, MERKLE_AIR_ID);
+}
+
+/// Asserts that the AIR with `air_id` is part of the proof and stored at index `air_id`.
+///
+/// Proofs only contain AIRs with non-empty traces, sorted by AIR ID. Since AIR IDs are unique,
+/// the AIR at index `air_id` can only have ID `air_id` if all AIRs before it are present too.
+/// The length check is necessary because out-of-bounds reads are only caught in debug mode.
+fn assert_air_present<C: Config>(
+    builder: &mut Builder<C>,
+    proof: &StarkProofVariable<C>,
+    air_id: usize,
+) {
+    let in_bounds = builder.lt(RVar::from(air_id), proof.per_air.len());
+    builder.assert_var_eq(in_bounds, C::N::ONE);
+    let air_proof_data = builder.get(&proof.per_air, air_id);
+    builder.assert_eq::<Usize<_>>(air_proof_data.air_id, RVar::from(air_id));
 }
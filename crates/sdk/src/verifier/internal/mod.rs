@@ -24,6 +24,11 @@ pub mod types;
 mod vars;
 
 /// Config to generate internal VM verifier program.
+///
+/// An internal verifier verifies both leaf verifier proofs and internal verifier proofs. They can
+/// be generated with different FRI parameters (e.g. a smaller blowup for leaf proofs): each kind of
+/// proof is verified against its own FRI config, which is embedded in the program as constants
+/// along with the verifying key advice of the corresponding VM.
 pub struct InternalVmVerifierConfig {
     pub leaf_fri_params: FriParameters,
     pub internal_fri_params: FriParameters,
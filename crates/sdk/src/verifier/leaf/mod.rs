@@ -1,5 +1,11 @@
+use std::array;
+
 use openvm_circuit::{
-    arch::{instructions::program::Program, SystemConfig},
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        instructions::program::Program,
+        SystemConfig,
+    },
     system::memory::tree::public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
 };
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
@@ -32,10 +38,35 @@ use crate::{
 pub mod types;
 mod vars;
 
+/// The `app_commit` exposed by a [multi-app](LeafVmVerifierConfig::build_multi_app_program) leaf
+/// verifier for proofs of the app VM at `app_vk_index` and a program with `app_program_commit`.
+pub fn app_commit_with_vk_index(
+    app_program_commit: [F; DIGEST_SIZE],
+    app_vk_index: usize,
+) -> [F; DIGEST_SIZE] {
+    let mut padded_index = [F::ZERO; DIGEST_SIZE];
+    padded_index[0] = F::from_canonical_usize(app_vk_index);
+    vm_poseidon2_hasher().compress(&app_program_commit, &padded_index)
+}
+
+/// The `app_commit` exposed by a leaf verifier with `app_config_digest`, for proofs with
+/// `app_commit` before the digest is bound.
+pub fn app_commit_with_config_digest(
+    app_commit: [F; DIGEST_SIZE],
+    app_config_digest: [F; DIGEST_SIZE],
+) -> [F; DIGEST_SIZE] {
+    vm_poseidon2_hasher().compress(&app_commit, &app_config_digest)
+}
+
 /// Config to generate leaf VM verifier program.
 pub struct LeafVmVerifierConfig {
     pub app_fri_params: FriParameters,
     pub app_system_config: SystemConfig,
+    /// [app_config_digest](crate::commit::app_config_digest) of the config of the app VM. For a
+    /// [multi-app](Self::build_multi_app_program) program, the
+    /// [multi_app_config_digest](crate::commit::multi_app_config_digest) of the list of configs. It
+    /// is bound to the exposed `app_commit`, see [app_commit_with_config_digest].
+    pub app_config_digest: [F; DIGEST_SIZE],
     pub compiler_options: CompilerOptions,
 }
 
@@ -44,7 +75,31 @@ impl LeafVmVerifierConfig {
         &self,
         app_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
     ) -> Program<F> {
-        let m_advice = new_from_inner_multi_vk(app_vm_vk);
+        self.build_program_impl(std::slice::from_ref(app_vm_vk), false)
+    }
+
+    /// Builds a leaf verifier program which accepts proofs of any of the app VMs with
+    /// `app_vm_vks`, so executions of different guest programs can be aggregated into one root
+    /// proof. The VMs must share the FRI parameters and system config of this config.
+    ///
+    /// The index of the app VM is read from the input before the proofs, see
+    /// [types::LeafVmVerifierInput::write_to_stream_with_app_vk_index], and all proofs of one
+    /// leaf must be of the same app VM. The index is committed by replacing `app_commit` with
+    /// [app_commit_with_vk_index].
+    pub fn build_multi_app_program(
+        &self,
+        app_vm_vks: &[MultiStarkVerifyingKey<BabyBearPoseidon2Config>],
+    ) -> Program<F> {
+        assert!(!app_vm_vks.is_empty(), "At least 1 app VM is required");
+        self.build_program_impl(app_vm_vks, true)
+    }
+
+    fn build_program_impl(
+        &self,
+        app_vm_vks: &[MultiStarkVerifyingKey<BabyBearPoseidon2Config>],
+        select_app_vk: bool,
+    ) -> Program<F> {
+        let m_advices: Vec<_> = app_vm_vks.iter().map(new_from_inner_multi_vk).collect();
         let mut builder = Builder::<C>::default();
 
         {
@@ -54,6 +109,7 @@ impl LeafVmVerifierConfig {
             };
             builder.cycle_tracker_end("InitializePcsConst");
             builder.cycle_tracker_start("ReadProofsFromInput");
+            let app_vk_index = select_app_vk.then(|| builder.hint_felt());
             let proofs: Array<C, StarkProofVariable<_>> =
                 <Vec<Proof<BabyBearPoseidon2Config>> as Hintable<C>>::read(&mut builder);
             // At least 1 proof should be provided.
@@ -65,9 +121,23 @@ impl LeafVmVerifierConfig {
             builder.range(0, proofs.len()).for_each(|i, builder| {
                 let proof = builder.get(&proofs, i);
                 assert_required_air_for_app_vm_present(builder, &proof);
-                StarkVerifier::verify::<DuplexChallengerVariable<C>>(
-                    builder, &pcs, &m_advice, &proof,
-                );
+                if let Some(app_vk_index) = app_vk_index {
+                    let app_vk_index = builder.cast_felt_to_var(app_vk_index);
+                    StarkVerifier::verify_with_selected_advice::<DuplexChallengerVariable<C>>(
+                        builder,
+                        &pcs,
+                        &m_advices,
+                        app_vk_index,
+                        &proof,
+                    );
+                } else {
+                    StarkVerifier::verify::<DuplexChallengerVariable<C>>(
+                        builder,
+                        &pcs,
+                        &m_advices[0],
+                        &proof,
+                    );
+                }
                 {
                     let commit = get_program_commit(builder, &proof);
                     builder.if_eq(i, RVar::zero()).then_or_else(
@@ -84,6 +154,20 @@ impl LeafVmVerifierConfig {
                 let proof_memory_pvs = get_memory_pvs(builder, &proof);
                 assert_or_assign_memory_pvs(builder, &pvs.memory, i, &proof_memory_pvs);
             });
+            let compressor = VariableP2Compressor::new(&mut builder);
+            if let Some(app_vk_index) = app_vk_index {
+                let mut padded_index: [Felt<F>; DIGEST_SIZE] =
+                    array::from_fn(|_| builder.eval(F::ZERO));
+                padded_index[0] = app_vk_index;
+                let app_commit = compressor.compress(&mut builder, &pvs.app_commit, &padded_index);
+                builder.assign(&pvs.app_commit, app_commit);
+            }
+            // Binding the config digest to a public value keeps it in the program even when the
+            // IR is optimized, and lets the final proof attest to the config.
+            let app_config_digest: [Felt<F>; DIGEST_SIZE] =
+                self.app_config_digest.map(|x| builder.eval(x));
+            let app_commit = compressor.compress(&mut builder, &pvs.app_commit, &app_config_digest);
+            builder.assign(&pvs.app_commit, app_commit);
             builder.cycle_tracker_end("VerifyProofs");
             builder.cycle_tracker_start("ExtractPublicValuesCommit");
             let is_terminate = builder.cast_felt_to_var(pvs.connector.is_terminate);
@@ -37,6 +37,21 @@ impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
         }
         ret
     }
+
+    /// Writes the input of a [multi-app](crate::verifier::leaf::LeafVmVerifierConfig::build_multi_app_program)
+    /// leaf verifier for proofs of the app VM at `app_vk_index`.
+    pub fn write_to_stream_with_app_vk_index<C: Config<N = Val<SC>>>(
+        &self,
+        app_vk_index: usize,
+    ) -> Vec<Vec<Val<SC>>>
+    where
+        Vec<Proof<SC>>: Hintable<C>,
+        UserPublicValuesRootProof<Val<SC>>: Hintable<C>,
+    {
+        let mut ret = vec![vec![Val::<SC>::from_canonical_usize(app_vk_index)]];
+        ret.extend(self.write_to_stream::<C>());
+        ret
+    }
 }
 
 impl Hintable<C> for UserPublicValuesRootProof<F> {
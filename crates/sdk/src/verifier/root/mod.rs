@@ -1,6 +1,9 @@
-use std::array;
+use std::{array, sync::Arc};
 
-use openvm_circuit::arch::instructions::program::Program;
+use openvm_circuit::arch::{
+    hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+    instructions::program::Program,
+};
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
     fri::TwoAdicFriPcsVariable, hints::Hintable, types::new_from_inner_multi_vk,
@@ -12,6 +15,7 @@ use openvm_stark_sdk::{
 };
 
 use crate::{
+    config::{ExitPolicy, PublicValuesAggregation},
     verifier::{
         common::non_leaf::NonLeafVerifierVariables,
         root::{
@@ -26,12 +30,21 @@ use crate::{
 pub mod types;
 mod vars;
 
+/// DSL function folding the user public values of the App VM into the public values exposed by
+/// the root verifier, for [PublicValuesAggregation::Fold].
+pub type PublicValuesFold = Arc<dyn Fn(&mut Builder<C>, &[Felt<F>]) -> Vec<Felt<F>> + Send + Sync>;
+
 /// Config to generate Root VM verifier program.
 pub struct RootVmVerifierConfig {
     pub leaf_fri_params: FriParameters,
     pub internal_fri_params: FriParameters,
+    /// Number of user public values of the App VM.
     pub num_public_values: usize,
     pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
+    pub public_values_aggregation: PublicValuesAggregation,
+    /// Required if `public_values_aggregation` is [PublicValuesAggregation::Fold].
+    pub public_values_fold: Option<PublicValuesFold>,
+    pub exit_policy: ExitPolicy,
     pub compiler_options: CompilerOptions,
 }
 impl RootVmVerifierConfig {
@@ -73,10 +86,21 @@ impl RootVmVerifierConfig {
                 non_leaf_verifier.verify_internal_or_leaf_verifier_proofs(&mut builder, &proofs);
             builder.cycle_tracker_end("VerifyProofs");
 
-            // App Program should terminate
-            builder.assert_felt_eq(merged_pvs.connector.is_terminate, F::ONE);
-            // App Program should exit successfully
-            builder.assert_felt_eq(merged_pvs.connector.exit_code, F::ZERO);
+            let is_terminate = merged_pvs.connector.is_terminate;
+            let exit_code = merged_pvs.connector.exit_code;
+            match self.exit_policy {
+                ExitPolicy::RequireSuccess => {
+                    // App Program should terminate
+                    builder.assert_felt_eq(is_terminate, F::ONE);
+                    // App Program should exit successfully
+                    builder.assert_felt_eq(exit_code, F::ZERO);
+                }
+                ExitPolicy::RequireTermination => {
+                    // App Program should terminate
+                    builder.assert_felt_eq(is_terminate, F::ONE);
+                }
+                ExitPolicy::AllowSuspended => {}
+            }
 
             builder.cycle_tracker_start("ExtractPublicValues");
             builder.assert_eq::<Usize<_>>(public_values.len(), RVar::from(self.num_public_values));
@@ -85,9 +109,28 @@ impl RootVmVerifierConfig {
                 .collect();
             let hasher = VariableP2Hasher::new(&mut builder);
             let pv_commit = hasher.merkle_root(&mut builder, &public_values_vec);
-            builder.assert_eq::<[_; DIGEST_SIZE]>(merged_pvs.public_values_commit, pv_commit);
+            if self.exit_policy == ExitPolicy::AllowSuspended {
+                // Public values are only committed once the App Program terminates.
+                let is_terminate = builder.cast_felt_to_var(is_terminate);
+                builder.if_eq(is_terminate, F::ONE).then(|builder| {
+                    builder
+                        .assert_eq::<[_; DIGEST_SIZE]>(merged_pvs.public_values_commit, pv_commit);
+                });
+            } else {
+                builder.assert_eq::<[_; DIGEST_SIZE]>(merged_pvs.public_values_commit, pv_commit);
+            }
             builder.cycle_tracker_end("ExtractPublicValues");
 
+            builder.cycle_tracker_start("AggregatePublicValues");
+            let public_values_vec =
+                self.aggregate_public_values(&mut builder, &hasher, public_values_vec);
+            let public_values_vec = if self.exit_policy.num_exit_values() > 0 {
+                [vec![is_terminate, exit_code], public_values_vec].concat()
+            } else {
+                public_values_vec
+            };
+            builder.cycle_tracker_end("AggregatePublicValues");
+
             let pvs = RootVmVerifierPvs {
                 exe_commit: compute_exe_commit(
                     &mut builder,
@@ -108,6 +151,52 @@ impl RootVmVerifierConfig {
 
         builder.compile_isa_with_options(self.compiler_options)
     }
+
+    fn aggregate_public_values(
+        &self,
+        builder: &mut Builder<C>,
+        hasher: &VariableP2Hasher<C>,
+        public_values: Vec<Felt<F>>,
+    ) -> Vec<Felt<F>> {
+        match self.public_values_aggregation {
+            PublicValuesAggregation::Concatenate => public_values,
+            PublicValuesAggregation::HashChain => {
+                let const_zero = hasher.const_zero;
+                let mut digest = [const_zero; DIGEST_SIZE];
+                for chunk in public_values.chunks(DIGEST_SIZE) {
+                    let padded_chunk =
+                        array::from_fn(|i| chunk.get(i).copied().unwrap_or(const_zero));
+                    digest = hasher.compressor.compress(builder, &digest, &padded_chunk);
+                }
+                digest.to_vec()
+            }
+            PublicValuesAggregation::Fold { num_outputs } => {
+                let fold = self
+                    .public_values_fold
+                    .as_ref()
+                    .expect("PublicValuesAggregation::Fold requires a public values fold");
+                let outputs = fold(builder, &public_values);
+                assert_eq!(
+                    outputs.len(),
+                    num_outputs,
+                    "public values fold returned the wrong number of outputs"
+                );
+                outputs
+            }
+        }
+    }
+}
+
+/// Computes the digest exposed by the root verifier with [PublicValuesAggregation::HashChain]
+/// for the given user public values.
+pub fn hash_chain_public_values(public_values: &[F]) -> [F; DIGEST_SIZE] {
+    let hasher = vm_poseidon2_hasher();
+    public_values
+        .chunks(DIGEST_SIZE)
+        .fold([F::ZERO; DIGEST_SIZE], |digest, chunk| {
+            let padded_chunk = array::from_fn(|i| chunk.get(i).copied().unwrap_or(F::ZERO));
+            hasher.compress(&digest, &padded_chunk)
+        })
 }
 
 fn compute_exe_commit<C: Config>(
@@ -19,7 +19,10 @@ pub struct RootVmVerifierPvs<T> {
     pub exe_commit: [T; DIGEST_SIZE],
     /// The commitment of the leaf verifier program, which commits the VM config of App VM.
     pub leaf_verifier_commit: [T; DIGEST_SIZE],
-    /// Raw public values from App VM execution.
+    /// Public values from App VM execution, aggregated according to the
+    /// [PublicValuesAggregation](crate::config::PublicValuesAggregation) of the root verifier.
+    /// Unless the [ExitPolicy](crate::config::ExitPolicy) is `RequireSuccess`, they are preceded
+    /// by `[is_terminate, exit_code]` of the execution.
     pub public_values: Vec<T>,
 }
 
@@ -3,8 +3,10 @@ use std::{borrow::Borrow, path::PathBuf, sync::Arc};
 use openvm_build::GuestOptions;
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, SingleSegmentVmExecutor,
-        SystemConfig, VmConfig, VmExecutor,
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        instructions::{exe::FnBound, program::DEFAULT_PC_STEP},
+        ExecutionError, SingleSegmentVmExecutor, SystemConfig, VirtualMachine, VmConfig,
+        VmExecutor,
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
@@ -12,12 +14,20 @@ use openvm_native_circuit::{Native, NativeConfig};
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{halo2::utils::CacheHalo2ParamsReader, types::InnerConfig};
 use openvm_rv32im_transpiler::{Rv32ITranspilerExtension, Rv32MTranspilerExtension};
+#[cfg(feature = "static-verifier-store")]
+use openvm_sdk::static_verifier::{agg_stark_config_digest, StaticVerifierStore};
 use openvm_sdk::{
-    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
+    commit::app_config_digest,
+    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config, SdkVmConfig, VmPreset},
     keygen::AppProvingKey,
+    receipt::Receipt,
     verifier::{
         common::types::VmVerifierPvs,
-        leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+        leaf::{
+            app_commit_with_config_digest,
+            types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+            LeafVmVerifierConfig,
+        },
     },
     Sdk, StdIn,
 };
@@ -25,12 +35,13 @@ use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
         fri_params::standard_fri_params_with_100_bits_conjectured_security,
+        FriParameters,
     },
     engine::{StarkEngine, StarkFriEngine},
     openvm_stark_backend::{p3_field::AbstractField, Chip},
     p3_baby_bear::BabyBear,
 };
-use openvm_transpiler::transpiler::Transpiler;
+use openvm_transpiler::{manifest::GuestManifest, transpiler::Transpiler};
 
 type SC = BabyBearPoseidon2Config;
 type C = InnerConfig;
@@ -102,6 +113,8 @@ fn agg_stark_config_for_test() -> AggStarkConfig {
             INTERNAL_LOG_BLOWUP,
         ),
         root_fri_params: standard_fri_params_with_100_bits_conjectured_security(ROOT_LOG_BLOWUP),
+        public_values_aggregation: Default::default(),
+        exit_policy: Default::default(),
         compiler_options: CompilerOptions {
             enable_cycle_tracker: true,
             compile_prints: true,
@@ -119,7 +132,7 @@ fn small_test_app_config(app_log_blowup: usize) -> AppConfig<NativeConfig> {
                 .with_max_segment_len(200)
                 .with_continuations()
                 .with_public_values(16),
-            Native,
+            Native::default(),
         ),
         leaf_fri_params: standard_fri_params_with_100_bits_conjectured_security(LEAF_LOG_BLOWUP)
             .into(),
@@ -156,7 +169,10 @@ fn test_public_values_and_leaf_verification() {
         .collect();
     let app_last_proof = app_vm_seg_proofs.pop().unwrap();
 
-    let expected_app_commit: [F; DIGEST_SIZE] = app_committed_exe.get_program_commit().into();
+    let expected_app_commit = app_commit_with_config_digest(
+        app_committed_exe.get_program_commit().into(),
+        app_config_digest(&app_pk.app_vm_pk.vm_config),
+    );
 
     // Verify all segments except the last one.
     let (first_seg_final_pc, first_seg_final_mem_root) = {
@@ -277,6 +293,76 @@ fn test_e2e_proof_generation_and_verification() {
     assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof));
 }
 
+#[test]
+fn test_receipt_verification() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+    let app_vk = app_pk.get_vk();
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let proof = Sdk
+        .generate_app_proof(app_pk.clone(), app_committed_exe.clone(), StdIn::default())
+        .unwrap();
+
+    let app_system_config = app_pk.app_vm_pk.vm_config.system();
+
+    let mut receipt = Receipt::new(proof, &app_committed_exe, &app_vk).unwrap();
+    receipt
+        .verify(&app_vk, &app_committed_exe, app_system_config)
+        .unwrap();
+
+    let other_exe = app_committed_exe_for_test(app_log_blowup + 1);
+    assert!(receipt
+        .verify(&app_vk, &other_exe, app_system_config)
+        .is_err());
+
+    // An exe with the same program but another initial memory or pc_start has the same program
+    // commit, so the first segment must be checked against it.
+    let verify_error = |receipt: &Receipt, exe: &Arc<VmCommittedExe<SC>>| {
+        receipt
+            .verify(&app_vk, exe, app_system_config)
+            .unwrap_err()
+            .to_string()
+    };
+    let mut other_exe = app_committed_exe.exe.clone();
+    other_exe
+        .init_memory
+        .insert((F::ONE, F::ZERO), F::from_canonical_u32(7));
+    let other_exe = Sdk
+        .commit_app_exe(app_pk.app_fri_params(), other_exe)
+        .unwrap();
+    assert!(verify_error(&receipt, &other_exe)
+        .contains("segment 0 starts from a different memory root"));
+    let mut other_exe = app_committed_exe.exe.clone();
+    other_exe.pc_start += DEFAULT_PC_STEP;
+    let other_exe = Sdk
+        .commit_app_exe(app_pk.app_fri_params(), other_exe)
+        .unwrap();
+    assert!(verify_error(&receipt, &other_exe).contains("segment 0 starts at pc"));
+
+    // Segments must continue where the previous one ended.
+    let segments = receipt.proof.per_segment.clone();
+    assert!(segments.len() > 2);
+    receipt.proof.per_segment.remove(1);
+    assert!(verify_error(&receipt, &app_committed_exe).contains("segment 1 starts"));
+
+    // The last segment must terminate.
+    receipt.proof.per_segment = segments[..segments.len() - 1].to_vec();
+    assert!(
+        verify_error(&receipt, &app_committed_exe).contains("the last segment does not terminate")
+    );
+    receipt.proof.per_segment = segments;
+
+    // Tampered public values are rejected even with a matching commitment.
+    let user_public_values = &mut receipt.proof.user_public_values;
+    user_public_values.public_values[0] += F::ONE;
+    user_public_values.public_values_commit =
+        vm_poseidon2_hasher().merkle_root(&user_public_values.public_values);
+    assert!(receipt
+        .verify(&app_vk, &app_committed_exe, app_system_config)
+        .is_err());
+}
+
 #[test]
 fn test_sdk_guest_build_and_transpile() {
     let sdk = Sdk;
@@ -299,3 +385,256 @@ fn test_sdk_guest_build_and_transpile() {
         .with_extension(Rv32MTranspilerExtension);
     let _exe = sdk.transpile(one, transpiler).unwrap();
 }
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_app_config_toml() {
+    let toml = r#"
+[app_vm_config.rv32i]
+[app_vm_config.rv32m]
+range_tuple_checker_sizes = [256, 2048]
+[app_vm_config.modular]
+supported_modulus = ["21888242871839275222246405745257275088696311157297823662689037894645226208583"]
+"#;
+    let config = AppConfig::<SdkVmConfig>::from_toml(toml).unwrap();
+    assert!(config.app_vm_config.rv32i.is_some());
+    assert_eq!(config.app_vm_config.system.config.max_segment_len, 4194204);
+
+    let round_trip = AppConfig::<SdkVmConfig>::from_toml(&config.to_toml().unwrap()).unwrap();
+    assert_eq!(round_trip.to_toml().unwrap(), config.to_toml().unwrap());
+
+    let too_large = toml.replace("2188", &"1".repeat(120));
+    assert!(AppConfig::<SdkVmConfig>::from_toml(&too_large).is_err());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_check_guest_manifest() {
+    let toml = r#"
+[app_vm_config.rv32i]
+[app_vm_config.io]
+[app_vm_config.modular]
+supported_modulus = ["21888242871839275222246405745257275088696311157297823662689037894645226208583"]
+"#;
+    let config = AppConfig::<SdkVmConfig>::from_toml(toml)
+        .unwrap()
+        .app_vm_config;
+    let modulus = config.modular.as_ref().unwrap().supported_modulus[0].clone();
+
+    let mut manifest = GuestManifest::default();
+    manifest.custom_instructions.insert((0x0b, 0b010)); // reveal
+    manifest.custom_instructions.insert((0x2b, 0b000)); // modular arithmetic
+    manifest.moduli.insert(0, modulus.clone());
+    config.check_guest_manifest(&manifest).unwrap();
+
+    manifest.custom_instructions.insert((0x0b, 0b100)); // keccak
+    manifest.moduli.insert(0, modulus.clone() + 2u32);
+    manifest.curves.insert(0, modulus);
+    let Err(openvm_circuit::arch::VmConfigError::GuestMismatch(mismatches)) =
+        config.check_guest_manifest(&manifest)
+    else {
+        panic!("expected the manifest not to match the config");
+    };
+    assert_eq!(mismatches.len(), 3);
+    assert!(mismatches[0].ends_with("not enabled: keccak"));
+    assert!(mismatches[1].starts_with("modulus 0"));
+    assert!(mismatches[2].starts_with("curve 0"));
+}
+
+#[test]
+fn test_vm_presets() {
+    for preset in VmPreset::ALL {
+        let config = SdkVmConfig::preset(preset.name()).unwrap();
+        VmConfig::<F>::validate(&config).unwrap();
+        VmConfig::<F>::create_chip_complex(&config).unwrap();
+    }
+    let evm = SdkVmConfig::preset("evm").unwrap();
+    assert!(evm.keccak.is_some() && evm.pairing.is_some());
+    #[cfg(feature = "aes")]
+    assert!(evm.aes.is_none());
+    assert!(SdkVmConfig::preset("full").is_err());
+}
+
+#[test]
+fn test_vm_presets_bus_consistency() {
+    for preset in VmPreset::ALL {
+        let config = SdkVmConfig::preset(preset.name()).unwrap();
+        let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+        VirtualMachine::new(engine, config).keygen().unwrap();
+    }
+}
+
+#[test]
+fn test_config_from_guest_manifest() {
+    let mut manifest = GuestManifest::default();
+    manifest.custom_instructions.insert((0x0b, 0b000)); // terminate
+    let config = SdkVmConfig::from_guest_manifest(&manifest).unwrap();
+    assert!(config.rv32i.is_some());
+    assert!(config.io.is_none() && config.rv32m.is_none());
+
+    let evm = SdkVmConfig::preset("evm").unwrap();
+    let moduli = evm.modular.unwrap().supported_modulus;
+    manifest.uses_rv32m = true;
+    manifest.custom_instructions.insert((0x0b, 0b010)); // reveal
+    manifest.custom_instructions.insert((0x2b, 0b000)); // modular arithmetic
+    manifest.custom_instructions.insert((0x2b, 0b001)); // short Weierstrass
+    manifest.moduli.extend(moduli.iter().cloned().enumerate());
+    manifest.curves.insert(0, moduli[2].clone()); // secp256k1
+    let config = SdkVmConfig::from_guest_manifest(&manifest).unwrap();
+    assert!(config.io.is_some() && config.rv32m.is_some());
+    assert_eq!(config.modular.unwrap().supported_modulus, moduli);
+    assert_eq!(config.ecc.unwrap().supported_curves.len(), 1);
+    assert!(config.keccak.is_none() && config.fp2.is_none() && config.pairing.is_none());
+
+    manifest.moduli.remove(&0);
+    assert!(SdkVmConfig::from_guest_manifest(&manifest).is_err());
+}
+
+#[test]
+fn test_app_config_digest_in_leaf_commit() {
+    let app_config = small_test_app_config(1);
+    // Knobs which only tune the prover are not part of the digest.
+    let mut tuned_app_config = app_config.clone();
+    tuned_app_config.app_vm_config.system.max_segment_len += 1;
+    tuned_app_config.app_vm_config.system.collect_metrics = true;
+    tuned_app_config.app_vm_config.system.profile_call_graph = true;
+    assert_eq!(
+        app_config_digest(&app_config.app_vm_config),
+        app_config_digest(&tuned_app_config.app_vm_config)
+    );
+    let mut other_app_config = app_config.clone();
+    other_app_config
+        .app_vm_config
+        .system
+        .memory_config
+        .pointer_max_bits -= 1;
+    assert_ne!(
+        app_config_digest(&app_config.app_vm_config),
+        app_config_digest(&other_app_config.app_vm_config)
+    );
+    // The digest only depends on the config, not on how it was written.
+    let round_trip: NativeConfig =
+        serde_json::from_str(&serde_json::to_string_pretty(&app_config.app_vm_config).unwrap())
+            .unwrap();
+    assert_eq!(
+        app_config_digest(&app_config.app_vm_config),
+        app_config_digest(&round_trip)
+    );
+
+    let app_pk = AppProvingKey::keygen(app_config.clone());
+    let other_app_pk = AppProvingKey::keygen(other_app_config.clone());
+    let commit: [F; DIGEST_SIZE] = app_pk.leaf_committed_exe.get_program_commit().into();
+    let other_commit: [F; DIGEST_SIZE] =
+        other_app_pk.leaf_committed_exe.get_program_commit().into();
+    assert_ne!(commit, other_commit);
+
+    // The digest is bound to the exposed app commit, so dead code elimination keeps it.
+    let app_vm_vk = app_pk.app_vm_pk.vm_pk.get_vk();
+    let build_leaf_program = |app_config: &AppConfig<NativeConfig>| {
+        LeafVmVerifierConfig {
+            app_fri_params: app_config.app_fri_params.fri_params,
+            app_system_config: app_config.app_vm_config.system.clone(),
+            app_config_digest: app_config_digest(&app_config.app_vm_config),
+            compiler_options: CompilerOptions {
+                optimize_ir: true,
+                ..Default::default()
+            },
+        }
+        .build_program(&app_vm_vk)
+        .instructions()
+    };
+    assert_ne!(
+        build_leaf_program(&app_config),
+        build_leaf_program(&other_app_config)
+    );
+}
+
+#[test]
+fn test_execute_with_callgrind() {
+    let app_config = small_test_app_config(1);
+    let mut exe = app_committed_exe_for_test(1).exe.clone();
+    let end = exe.program.pc_base + exe.program.step * (exe.program.len() as u32 - 1);
+    exe.fn_bounds.insert(
+        exe.program.pc_base,
+        FnBound {
+            start: exe.program.pc_base,
+            end,
+            name: "main".to_string(),
+        },
+    );
+
+    let mut out = vec![];
+    Sdk.execute_with_callgrind(exe, app_config.app_vm_config, StdIn::default(), &mut out)
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let mut lines = out.lines();
+    assert_eq!(lines.next(), Some("# callgrind format"));
+    assert_eq!(lines.next(), Some("version: 1"));
+    assert_eq!(lines.next(), Some("creator: openvm"));
+    assert_eq!(lines.next(), Some("positions: instr"));
+    let events = lines.next().unwrap().strip_prefix("events: ").unwrap();
+    assert_eq!(events.split(' ').next(), Some("Cycles"));
+    let summary: Vec<u64> = lines
+        .next()
+        .unwrap()
+        .strip_prefix("summary: ")
+        .unwrap()
+        .split(' ')
+        .map(|cost| cost.parse().unwrap())
+        .collect();
+    assert_eq!(summary.len(), events.split(' ').count());
+    // The program runs over several segments, and all of its cycles are attributed to `main`,
+    // whose costs are listed per pc.
+    assert!(summary[0] > 200);
+    assert_eq!(lines.next(), Some(""));
+    assert_eq!(lines.next(), Some("fn=main"));
+    let mut main_costs = vec![0; summary.len()];
+    for line in lines {
+        let mut fields = line.split(' ');
+        let pc = fields.next().unwrap();
+        assert!(pc.starts_with("0x"), "unexpected line {line}");
+        for (total, cost) in main_costs.iter_mut().zip(fields) {
+            *total += cost.parse::<u64>().unwrap();
+        }
+    }
+    assert_eq!(main_costs[0], summary[0]);
+}
+
+#[cfg(feature = "static-verifier-store")]
+#[test]
+fn test_static_verifier_store_agg_stark() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = StaticVerifierStore::new(dir.path());
+    let config = agg_stark_config_for_test();
+
+    // Miss: keygen runs and also returns its dummy internal proof.
+    assert!(store.load_agg_stark(&config).unwrap().is_none());
+    let (pk, dummy_internal_proof) = store.load_or_keygen_agg_stark(config).unwrap();
+    assert!(dummy_internal_proof.is_some());
+
+    // Hit: the stored key is loaded and keygen is skipped.
+    let (loaded_pk, dummy_internal_proof) = store.load_or_keygen_agg_stark(config).unwrap();
+    assert!(dummy_internal_proof.is_none());
+    assert_eq!(
+        loaded_pk.internal_program_commit(),
+        pk.internal_program_commit()
+    );
+    assert_eq!(
+        loaded_pk.root_verifier_pk.static_verifier_digest().unwrap(),
+        pk.root_verifier_pk.static_verifier_digest().unwrap()
+    );
+
+    // Stale: a key of another config is not used, even at the path of this config.
+    let other_config = AggStarkConfig {
+        max_num_user_public_values: NUM_PUB_VALUES / 2,
+        ..config
+    };
+    assert!(store.load_agg_stark(&other_config).unwrap().is_none());
+    std::fs::copy(
+        store.agg_stark_path(&agg_stark_config_digest(&config).unwrap()),
+        store.agg_stark_path(&agg_stark_config_digest(&other_config).unwrap()),
+    )
+    .unwrap();
+    assert!(store.load_agg_stark(&other_config).unwrap().is_none());
+}
@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.19;
+
+contract OpenVmAppVerifier {
+    uint256 public constant APP_EXE_COMMIT = 0x0000000000000000000000000000000000000000000000000000000000000001;
+    uint256 public constant APP_VM_COMMIT = 0x0000000000000000000000000000000000000000000000000000000000000002;
+    uint256 public constant NUM_PUBLIC_VALUES = 3;
+
+    address public immutable verifier;
+
+    constructor(address _verifier) {
+        verifier = _verifier;
+    }
+
+    function verify(
+        uint256[12] calldata accumulator,
+        uint256[3] calldata publicValues,
+        bytes calldata proof
+    ) external view returns (bool) {
+        (bool success, ) = verifier.staticcall(
+            abi.encodePacked(accumulator, APP_EXE_COMMIT, APP_VM_COMMIT, publicValues, proof)
+        );
+        return success;
+    }
+}
@@ -18,6 +18,10 @@ pub struct GuestOptions {
     pub profile: Option<String>,
     /// Target directory
     pub target_dir: Option<PathBuf>,
+    /// Start address of the guest heap. Defaults to the end of the guest's ELF sections.
+    pub heap_start: Option<u32>,
+    /// Size of the guest heap in bytes. Defaults to the rest of guest memory.
+    pub heap_size: Option<u32>,
 }
 
 impl GuestOptions {
@@ -54,6 +58,20 @@ impl GuestOptions {
         self
     }
 
+    /// Set the start address of the guest heap. It must lie after the guest's ELF sections,
+    /// otherwise the guest exits with the out-of-memory exit code on its first allocation.
+    pub fn with_heap_start(mut self, heap_start: u32) -> Self {
+        self.heap_start = Some(heap_start);
+        self
+    }
+
+    /// Limit the guest heap to `heap_size` bytes. Allocations beyond the limit make the guest
+    /// exit with the out-of-memory exit code.
+    pub fn with_heap_size(mut self, heap_size: u32) -> Self {
+        self.heap_size = Some(heap_size);
+        self
+    }
+
     #[allow(dead_code)]
     pub(crate) fn with_metadata(mut self, metadata: GuestMetadata) -> Self {
         self.rustc_flags = metadata.rustc_flags.unwrap_or_default();
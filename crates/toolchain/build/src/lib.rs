@@ -290,6 +290,14 @@ pub fn build_guest_package(
 
     cmd.args(&guest_opts.options);
 
+    // The guest runtime reads the heap layout at compile time, see `openvm_platform::memory`.
+    if let Some(heap_start) = guest_opts.heap_start {
+        cmd.env("OPENVM_GUEST_HEAP_START", format!("{heap_start:#x}"));
+    }
+    if let Some(heap_size) = guest_opts.heap_size {
+        cmd.env("OPENVM_GUEST_HEAP_SIZE", format!("{heap_size:#x}"));
+    }
+
     let command_string = format!(
         "{} {}",
         cmd.get_program().to_string_lossy(),
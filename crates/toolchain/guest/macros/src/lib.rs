@@ -0,0 +1,32 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse::Nothing, parse_macro_input, spanned::Spanned, ItemFn, ReturnType};
+
+/// Marks the entrypoint of a guest program. See `openvm_guest::entry`.
+#[proc_macro_attribute]
+pub fn entry(attr: TokenStream, item: TokenStream) -> TokenStream {
+    parse_macro_input!(attr as Nothing);
+    let func = parse_macro_input!(item as ItemFn);
+    let sig = &func.sig;
+    let error = if sig.asyncness.is_some() {
+        Some((sig.asyncness.span(), "the entrypoint cannot be async"))
+    } else if !sig.generics.params.is_empty() {
+        Some((sig.generics.span(), "the entrypoint cannot be generic"))
+    } else if !sig.inputs.is_empty() {
+        Some((sig.inputs.span(), "the entrypoint cannot take arguments"))
+    } else if !matches!(sig.output, ReturnType::Default) {
+        Some((sig.output.span(), "the entrypoint must return `()`"))
+    } else {
+        None
+    };
+    if let Some((span, message)) = error {
+        return syn::Error::new(span, message).to_compile_error().into();
+    }
+
+    let name = &sig.ident;
+    quote! {
+        #func
+        ::openvm_guest::__private::entry!(#name);
+    }
+    .into()
+}
@@ -0,0 +1,53 @@
+//! # OpenVM guest facade
+//!
+//! A single dependency for guest programs. It re-exports the [`openvm`] standard library (io,
+//! hints, serde, process) and, behind a Cargo feature per extension, the guest bindings of that
+//! extension:
+//!
+//! | feature     | module                 |
+//! |-------------|------------------------|
+//! | `algebra`   | [`algebra`]            |
+//! | `bigint`    | [`bigint`]             |
+//! | `ecc`       | [`ecc`]                |
+//! | `keccak256` | [`keccak256`]          |
+//! | `pairing`   | [`pairing`]            |
+//!
+//! Only enable the extensions that the VM the program runs on also enables.
+//! `VmConfig::check_program` rejects programs that use opcodes of other extensions at transpile
+//! time, and `cargo openvm build` runs this check against the app config.
+//!
+//! ```ignore
+//! #![no_main]
+//! #![no_std]
+//!
+//! #[openvm_guest::entry]
+//! fn main() {
+//!     let n: u64 = openvm_guest::io::read();
+//!     openvm_guest::io::reveal(n as u32, 0);
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub use openvm::*;
+#[cfg(feature = "algebra")]
+pub use openvm_algebra_guest as algebra;
+#[cfg(feature = "bigint")]
+pub use openvm_bigint_guest as bigint;
+#[cfg(feature = "ecc")]
+pub use openvm_ecc_guest as ecc;
+/// Marks the entrypoint of a guest program, as an attribute alternative to [`openvm::entry!`].
+///
+/// The function must take no arguments and return `()`. With the `std` feature the Rust `main`
+/// function is used as the entrypoint, so the function must be named `main`.
+pub use openvm_guest_macros::entry;
+#[cfg(feature = "keccak256")]
+pub use openvm_keccak256_guest as keccak256;
+#[cfg(feature = "pairing")]
+pub use openvm_pairing_guest as pairing;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use openvm::entry;
+}
@@ -0,0 +1,241 @@
+//! Textual assembly for [`Program`]s, so that programs can be stored, diffed, and patched as text.
+//!
+//! The printer and parser round-trip every instruction exactly. Debug infos are not part of the
+//! text and are dropped. The format is line based:
+//!
+//! ```text
+//! .pc_base 0
+//! .step 4
+//! .max_num_public_values 32
+//! 0: STOREW 6 0 0 0 1 0 0
+//! 4: BEQ 0 0 12 1 0 0 0    # comment
+//! 8: op0x101 0 0 1 1 1 0 0
+//! ```
+//!
+//! Each instruction line is `pc: opcode a b c d e f g`. The opcode is either a name from the
+//! [`OpcodeNames`] passed in, or `op` followed by the global opcode in decimal or hex. Operands
+//! are field elements; values in the upper half of the field are printed as negative integers.
+//! Phantom instructions are printed like any other, with the discriminant in the low 16 bits of
+//! `c`. Gaps in the program are represented by skipped pcs. Everything after `#` is a comment.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Write},
+};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use crate::{
+    instruction::{Instruction, NUM_OPERANDS},
+    program::Program,
+    VmOpcode,
+};
+
+/// Names used by the printer and parser for global opcodes, usually from the opcode map of the
+/// VM the program is written for. Names that belong to more than one opcode are ambiguous and are
+/// not used; those opcodes are printed by number.
+#[derive(Clone, Debug, Default)]
+pub struct OpcodeNames {
+    names: BTreeMap<usize, String>,
+    opcodes: HashMap<String, Option<usize>>,
+}
+
+impl OpcodeNames {
+    pub fn new(names: impl IntoIterator<Item = (usize, String)>) -> Self {
+        let names: BTreeMap<usize, String> = names.into_iter().collect();
+        let mut opcodes = HashMap::new();
+        for (&opcode, name) in &names {
+            opcodes
+                .entry(name.clone())
+                .and_modify(|entry| *entry = None)
+                .or_insert(Some(opcode));
+        }
+        Self { names, opcodes }
+    }
+
+    fn name(&self, opcode: usize) -> Option<&str> {
+        let name = self.names.get(&opcode)?;
+        self.opcodes[name].map(|_| name.as_str())
+    }
+
+    fn opcode(&self, name: &str) -> Option<usize> {
+        self.opcodes.get(name).copied().flatten()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+    /// 1-based line number of the error.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Prints `program` in the assembly format described in the [module docs](self).
+pub fn program_to_asm<F: PrimeField32>(program: &Program<F>, names: &OpcodeNames) -> String {
+    let mut asm = String::new();
+    writeln!(asm, ".pc_base {}", program.pc_base).unwrap();
+    writeln!(asm, ".step {}", program.step).unwrap();
+    writeln!(
+        asm,
+        ".max_num_public_values {}",
+        program.max_num_public_values
+    )
+    .unwrap();
+    for (pc, instruction, _) in program.enumerate_by_pc() {
+        let opcode = instruction.opcode.as_usize();
+        match names.name(opcode) {
+            Some(name) => write!(asm, "{pc}: {name}").unwrap(),
+            None => write!(asm, "{pc}: op{opcode:#x}").unwrap(),
+        }
+        let Instruction {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            ..
+        } = instruction;
+        for operand in [a, b, c, d, e, f, g] {
+            write!(asm, " {}", operand_to_asm(operand)).unwrap();
+        }
+        asm.push('\n');
+    }
+    asm
+}
+
+/// Parses a program in the assembly format described in the [module docs](self).
+pub fn parse_asm<F: PrimeField32>(asm: &str, names: &OpcodeNames) -> Result<Program<F>, AsmError> {
+    let mut pc_base = 0;
+    let mut step = crate::program::DEFAULT_PC_STEP;
+    let mut max_num_public_values = crate::program::DEFAULT_MAX_NUM_PUBLIC_VALUES;
+    let mut instructions: Vec<(u32, Instruction<F>)> = vec![];
+
+    for (idx, line) in asm.lines().enumerate() {
+        let error = |message: String| AsmError {
+            line: idx + 1,
+            message,
+        };
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(directive) = line.strip_prefix('.') {
+            if !instructions.is_empty() {
+                return Err(error(
+                    "directives must come before instructions".to_string(),
+                ));
+            }
+            let (key, value) = directive
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| error(format!("directive `.{directive}` has no value")))?;
+            let value = parse_u32(value.trim())
+                .ok_or_else(|| error(format!("invalid value for `.{key}`")))?;
+            match key {
+                "pc_base" => pc_base = value,
+                "step" => step = value,
+                "max_num_public_values" => max_num_public_values = value as usize,
+                _ => return Err(error(format!("unknown directive `.{key}`"))),
+            }
+            continue;
+        }
+
+        let (pc, rest) = line
+            .split_once(':')
+            .ok_or_else(|| error("expected `pc: opcode operands`".to_string()))?;
+        let pc = parse_u32(pc.trim()).ok_or_else(|| error(format!("invalid pc `{pc}`")))?;
+        let mut tokens = rest.split_whitespace();
+        let opcode = tokens
+            .next()
+            .ok_or_else(|| error("missing opcode".to_string()))?;
+        let opcode = names
+            .opcode(opcode)
+            .or_else(|| {
+                opcode
+                    .strip_prefix("op")
+                    .and_then(parse_u32)
+                    .map(|op| op as usize)
+            })
+            .ok_or_else(|| error(format!("unknown opcode `{opcode}`")))?;
+        let operands = tokens
+            .map(|token| {
+                operand_from_asm::<F>(token)
+                    .ok_or_else(|| error(format!("invalid operand `{token}`")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if operands.len() != NUM_OPERANDS {
+            return Err(error(format!(
+                "expected {NUM_OPERANDS} operands, found {}",
+                operands.len()
+            )));
+        }
+        if let Some(&(prev_pc, _)) = instructions.last() {
+            if pc <= prev_pc {
+                return Err(error(format!(
+                    "pc {pc} is not after the previous pc {prev_pc}"
+                )));
+            }
+        }
+        if pc < pc_base || step == 0 || (pc - pc_base) % step != 0 {
+            return Err(error(format!(
+                "pc {pc} is not of the form pc_base + k * step"
+            )));
+        }
+        instructions.push((
+            pc,
+            Instruction::new(
+                VmOpcode::from_usize(opcode),
+                operands[0],
+                operands[1],
+                operands[2],
+                operands[3],
+                operands[4],
+                operands[5],
+                operands[6],
+            ),
+        ));
+    }
+
+    let mut program = Program::new_empty(step, pc_base, max_num_public_values);
+    for (pc, instruction) in instructions {
+        let index = ((pc - pc_base) / step) as usize;
+        program.instructions_and_debug_infos.resize(index, None);
+        program.push_instruction(instruction);
+    }
+    Ok(program)
+}
+
+fn operand_to_asm<F: PrimeField32>(value: &F) -> String {
+    let value = value.as_canonical_u32();
+    if value > F::ORDER_U32 / 2 {
+        format!("-{}", F::ORDER_U32 - value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn operand_from_asm<F: PrimeField32>(token: &str) -> Option<F> {
+    let (negative, abs) = match token.strip_prefix('-') {
+        Some(abs) => (true, abs),
+        None => (false, token),
+    };
+    let abs = parse_u32(abs).filter(|&abs| abs < F::ORDER_U32)?;
+    let value = F::from_canonical_u32(abs);
+    Some(if negative { -value } else { value })
+}
+
+fn parse_u32(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
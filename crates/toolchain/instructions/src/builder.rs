@@ -0,0 +1,83 @@
+//! Construction of [`Instruction`]s for programs that are generated directly rather than
+//! transpiled from RISC-V, such as DSL backends and test harnesses.
+//!
+//! [`InstructionBuilder`] handles the operand conventions shared by all RV32 based extensions:
+//! registers are passed by index and converted to pointers into the register address space, and
+//! the address spaces of the operands are filled in. Extension crates add their instructions as
+//! extension traits on [`InstructionBuilder`], using the same conventions as their transpiler.
+
+use std::marker::PhantomData;
+
+use openvm_stark_backend::p3_field::Field;
+
+use crate::{
+    instruction::Instruction,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    SystemOpcode, UsizeOpcode, VmOpcode,
+};
+
+/// Number of RISC-V registers, `x0` to `x31`.
+pub const RV32_NUM_REGISTERS: usize = 32;
+
+pub struct InstructionBuilder<F>(PhantomData<F>);
+
+impl<F: Field> InstructionBuilder<F> {
+    /// Pointer to register `x{reg}` in the register address space.
+    ///
+    /// Panics if `reg` is not a valid register index.
+    pub fn register_ptr(reg: usize) -> F {
+        assert!(
+            reg < RV32_NUM_REGISTERS,
+            "register x{reg} does not exist, expected an index below {RV32_NUM_REGISTERS}"
+        );
+        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * reg)
+    }
+
+    /// Instruction with destination register `rd` and source registers `rs1` and `rs2`, in the
+    /// layout of an R-type instruction. `rd` and `rs1` are in the register address space; `e_as`
+    /// is the address space accessed through `rs2`, which is [`RV32_REGISTER_AS`] for ALU
+    /// instructions and [`RV32_MEMORY_AS`] for instructions that read operands from memory
+    /// through the register pointers.
+    pub fn r_type(
+        opcode: VmOpcode,
+        rd: usize,
+        rs1: usize,
+        rs2: usize,
+        e_as: u32,
+    ) -> Instruction<F> {
+        assert!(
+            e_as == RV32_REGISTER_AS || e_as == RV32_MEMORY_AS,
+            "address space {e_as} is not a register or memory address space"
+        );
+        Instruction::new(
+            opcode,
+            Self::register_ptr(rd),
+            Self::register_ptr(rs1),
+            Self::register_ptr(rs2),
+            F::from_canonical_u32(RV32_REGISTER_AS),
+            F::from_canonical_u32(e_as),
+            F::ZERO,
+            F::ZERO,
+        )
+    }
+
+    /// Terminates execution with `exit_code`.
+    pub fn terminate(exit_code: u8) -> Instruction<F> {
+        Instruction {
+            opcode: VmOpcode::with_default_offset(SystemOpcode::TERMINATE),
+            c: F::from_canonical_u8(exit_code),
+            ..Default::default()
+        }
+    }
+
+    /// Global opcode of `local_opcode` for the class instance `idx`, for instruction classes that
+    /// are instantiated once per configured modulus or curve. Instance `idx` owns the opcodes
+    /// starting at `default_offset + idx * class_size`.
+    pub fn indexed_opcode<Opcode: UsizeOpcode>(
+        local_opcode: Opcode,
+        idx: usize,
+        class_size: usize,
+    ) -> VmOpcode {
+        VmOpcode::from_usize(local_opcode.with_default_offset() + idx * class_size)
+    }
+}
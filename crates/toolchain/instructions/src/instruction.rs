@@ -120,4 +120,57 @@ impl DebugInfo {
             trace,
         }
     }
+
+    /// Returns the location of the builder call which emitted the DSL instruction, if the
+    /// program was built with `RUST_BACKTRACE` set.
+    pub fn call_site(&self) -> Option<String> {
+        self.trace.as_ref().and_then(call_site)
+    }
+}
+
+/// Frames of these crates belong to the DSL compiler or to the standard library and are skipped
+/// when looking for the call site of an instruction.
+const INTERNAL_CRATES: [&str; 5] = [
+    "backtrace",
+    "openvm_native_compiler",
+    "core",
+    "alloc",
+    "std",
+];
+
+/// Returns the crate whose code runs in the frame of the demangled symbol `name`.
+///
+/// For a trait method `<T as Trait>::f` this is the crate of `T`, so that e.g. the operator
+/// impls of DSL variables count as compiler frames.
+pub fn frame_crate(name: &str) -> &str {
+    let name = name.trim_start_matches(['<', '&', '*', '[', '(']);
+    let name = name
+        .strip_prefix("mut ")
+        .or_else(|| name.strip_prefix("const "))
+        .unwrap_or(name);
+    let end = name
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+/// Returns `file:line` of the innermost frame of `trace` outside of the DSL compiler.
+pub fn call_site(trace: &Backtrace) -> Option<String> {
+    let mut trace = trace.clone();
+    trace.resolve();
+    trace
+        .frames()
+        .iter()
+        .flat_map(|frame| frame.symbols())
+        .find_map(|symbol| {
+            let name = format!("{:#}", symbol.name()?);
+            if INTERNAL_CRATES.contains(&frame_crate(&name)) {
+                return None;
+            }
+            Some(format!(
+                "{}:{}",
+                symbol.filename()?.display(),
+                symbol.lineno()?
+            ))
+        })
 }
@@ -7,6 +7,8 @@ use openvm_stark_backend::p3_field::Field;
 use serde::{Deserialize, Serialize};
 use strum_macros::{EnumCount, EnumIter, FromRepr};
 
+pub mod asm;
+pub mod builder;
 pub mod exe;
 pub mod instruction;
 mod phantom;
@@ -96,6 +98,12 @@ pub enum PublishOpcode {
 pub enum Poseidon2Opcode {
     PERM_POS2,
     COMP_POS2,
+    /// Absorbs a chunk into a sponge state in place: the state is set to the permutation of
+    /// the chunk followed by the capacity half of the state.
+    ABSORB_POS2,
+    /// One level of a Merkle authentication path: the node is replaced in place by the
+    /// compression of the node and its sibling, ordered by the index bit of the level.
+    MERKLE_STEP_POS2,
 }
 
 // =================================================================================================
@@ -11,8 +11,13 @@ pub enum SysPhantom {
     Nop = 0,
     /// Causes the runtime to panic, on host machine and prints a backtrace.
     DebugPanic,
-    /// Start tracing
+    /// Start a cycle tracker span. The span name is taken from the debug info, or, when
+    /// `c_upper = 1`, from the string in memory at address `[a]_1` with length `[b]_1`, which is
+    /// how RV32 guests pass it.
     CtStart,
-    /// End tracing
+    /// End the cycle tracker span started by the matching `CtStart`, named in the same way.
     CtEnd,
+    /// Print a backtrace reported by an RV32 guest: the return addresses stored as 32-bit words
+    /// in `[[a]_1..[a]_1 + [b]_1]_2`, preceded by the pc of this instruction.
+    Backtrace,
 }
@@ -0,0 +1,49 @@
+//! Raw backtraces of the guest call stack, reported to the host on panic.
+//!
+//! The call stack is walked through the frame pointer chain, so the guest must be built with
+//! `-C force-frame-pointers=yes` (e.g. with `GuestOptions::with_rustc_flags`) for the backtrace to
+//! be complete. Without frame pointers the walk stops as soon as the chain looks invalid. The
+//! host prints the return addresses, symbolized with the function bounds of the executable when
+//! they were extracted from the ELF.
+
+/// Maximum number of frames in a reported backtrace.
+const MAX_FRAMES: usize = 64;
+
+/// Reports the return addresses of the current call stack to the host, which prints them.
+#[inline(never)]
+pub fn print_backtrace() {
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut frames = [0u32; MAX_FRAMES];
+        let mut len = 0;
+        let mut fp: u32;
+        // SAFETY: only reads the frame pointer register.
+        unsafe { core::arch::asm!("mv {}, s0", out(reg) fp) };
+        // In the RISC-V frame layout the return address is stored just below the frame pointer,
+        // followed by the caller's frame pointer. Frames only get older towards the top of the
+        // stack, so the walk stops at the first frame pointer that does not move up.
+        while len < MAX_FRAMES && fp % 4 == 0 && fp >= 8 && fp <= openvm_platform::memory::STACK_TOP
+        {
+            // SAFETY: `fp` is a word-aligned address in the stack region, and all guest memory
+            // is readable.
+            let (ra, prev_fp) = unsafe {
+                (
+                    core::ptr::read_volatile((fp - 4) as *const u32),
+                    core::ptr::read_volatile((fp - 8) as *const u32),
+                )
+            };
+            if ra == 0 {
+                break;
+            }
+            frames[len] = ra;
+            len += 1;
+            if prev_fp <= fp {
+                break;
+            }
+            fp = prev_fp;
+        }
+        openvm_rv32im_guest::raw_print_backtrace(frames.as_ptr() as *const u8, len * 4);
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    let _ = MAX_FRAMES;
+}
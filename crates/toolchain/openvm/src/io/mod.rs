@@ -11,7 +11,7 @@ use serde::de::DeserializeOwned;
 
 #[cfg(not(target_os = "zkvm"))]
 use crate::host::{hint_input, read_n_bytes, read_u32};
-use crate::serde::Deserializer;
+use crate::serde::{from_framed_reader, Deserializer, Framed};
 
 mod read;
 
@@ -28,6 +28,15 @@ pub fn read<T: DeserializeOwned>() -> T {
     T::deserialize(&mut deserializer).unwrap()
 }
 
+/// Read the next vec written by the host with `StdIn::write_framed` and deserialize it into a
+/// type `T`. Panics if it was written with a different type or encoding version.
+pub fn read_framed<T: DeserializeOwned + Framed>() -> T {
+    match from_framed_reader(read::Reader::new()) {
+        Ok(value) => value,
+        Err(err) => panic!("failed to read framed input: {err}"),
+    }
+}
+
 /// Read the next 4 bytes from the hint stream into a register.
 /// Because [hint_store_u32] stores a word to memory, this function first reads to memory and then
 /// loads from memory to register.
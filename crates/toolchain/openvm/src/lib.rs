@@ -20,11 +20,16 @@ use openvm_platform::rust_rt;
 #[cfg(target_os = "zkvm")]
 pub use openvm_rv32im_guest::*;
 
+pub mod backtrace;
 pub mod io;
+pub mod merkle;
 #[cfg(all(feature = "std", target_os = "zkvm"))]
 pub mod pal_abi;
 pub mod process;
+pub mod profile;
 pub mod serde;
+/// Lookups into the constant tables of the VM configuration.
+pub use openvm_table_guest as table;
 
 #[cfg(not(target_os = "zkvm"))]
 pub mod utils;
@@ -161,6 +166,7 @@ fn panic_impl(panic_info: &core::panic::PanicInfo) -> ! {
     use core::fmt::Write;
     let mut writer = crate::io::Writer;
     let _ = write!(writer, "{}\n", panic_info);
+    backtrace::print_backtrace();
     openvm_platform::rust_rt::terminate::<1>();
     unreachable!()
 }
@@ -0,0 +1,59 @@
+//! Merkle inclusion proofs against BabyBear-native commitments, such as the memory and program
+//! commitments of other OpenVM proofs.
+//!
+//! Digests are [`DIGEST_SIZE`] canonical BabyBear elements. The path convention is the one of the
+//! native `MERKLE_STEP_POS2` instruction: bit `i` of the leaf index is `1` when the node at level
+//! `i` is the right child, in which case its sibling is compressed on the left.
+//!
+//! RV32 guests cannot issue the Poseidon2 instructions, which work on native field cells, so the
+//! compression function is supplied by the caller.
+
+/// Number of BabyBear elements in a digest.
+pub const DIGEST_SIZE: usize = 8;
+
+/// Digest of canonical BabyBear elements.
+pub type Digest = [u32; DIGEST_SIZE];
+
+/// Authentication path of a leaf, from the leaf level up to the children of the root.
+#[derive(Clone, Copy, Debug)]
+pub struct MerklePath<'a> {
+    /// Index of the leaf among the leaves of the tree.
+    pub index: u64,
+    /// Sibling of the node at each level, starting at the leaf.
+    pub siblings: &'a [Digest],
+}
+
+/// Returns the root obtained by hashing `leaf` up along `path` with `compress(left, right)`.
+///
+/// Panics if the index does not fit in a tree of the path's height.
+pub fn compute_root(
+    leaf: &Digest,
+    path: &MerklePath,
+    compress: impl Fn(&Digest, &Digest) -> Digest,
+) -> Digest {
+    assert!(
+        path.siblings.len() >= 64 || path.index >> path.siblings.len() == 0,
+        "leaf index {} is out of range for a path of height {}",
+        path.index,
+        path.siblings.len()
+    );
+    let mut node = *leaf;
+    for (level, sibling) in path.siblings.iter().enumerate() {
+        node = if (path.index >> level) & 1 == 0 {
+            compress(&node, sibling)
+        } else {
+            compress(sibling, &node)
+        };
+    }
+    node
+}
+
+/// Returns whether `path` proves the inclusion of `leaf` under `root`.
+pub fn verify(
+    root: &Digest,
+    leaf: &Digest,
+    path: &MerklePath,
+    compress: impl Fn(&Digest, &Digest) -> Digest,
+) -> bool {
+    compute_root(leaf, path, compress) == *root
+}
@@ -13,6 +13,7 @@ pub mod exit_code {
     pub const SUCCESS: u8 = 0;
     pub const PANIC: u8 = 1;
     pub const UNIMP: u8 = 2;
+    pub const OUT_OF_MEMORY: u8 = openvm_platform::memory::OUT_OF_MEMORY_EXIT_CODE;
     // Temporarily use 4 to detect if halt is called.
     pub const HALT: u8 = 4;
     pub const PAUSE: u8 = 5;
@@ -82,6 +83,7 @@ pub unsafe extern "C" fn sys_rand(_recv_buf: *mut u32, _words: usize) {
 #[no_mangle]
 unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     raw_print_str_from_bytes(msg_ptr, len);
+    crate::backtrace::print_backtrace();
     terminate::<{ exit_code::PANIC }>();
     unreachable!()
 }
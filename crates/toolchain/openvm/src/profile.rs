@@ -0,0 +1,68 @@
+//! Cycle tracking for guest programs.
+//!
+//! Spans started here are reported by the host's cycle tracker in the same way as the spans of
+//! native programs: nested span names are joined with `;`, and the instructions and trace cells of
+//! each span are emitted as metrics when the host collects them. Outside the zkVM the functions
+//! do nothing.
+
+/// Starts a cycle tracker span. Every `start` must be matched by an [`end`] with the same name,
+/// and spans must be properly nested.
+#[inline(always)]
+pub fn start(name: &str) {
+    #[cfg(target_os = "zkvm")]
+    openvm_rv32im_guest::raw_cycle_tracker_start(name.as_ptr(), name.len());
+    #[cfg(not(target_os = "zkvm"))]
+    let _ = name;
+}
+
+/// Ends the cycle tracker span started by [`start`] with the same name.
+#[inline(always)]
+pub fn end(name: &str) {
+    #[cfg(target_os = "zkvm")]
+    openvm_rv32im_guest::raw_cycle_tracker_end(name.as_ptr(), name.len());
+    #[cfg(not(target_os = "zkvm"))]
+    let _ = name;
+}
+
+/// Cycle tracker span that ends when dropped. Usually created with [`span!`].
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct SpanGuard {
+    name: &'static str,
+}
+
+impl SpanGuard {
+    pub fn new(name: &'static str) -> Self {
+        start(name);
+        Self { name }
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        end(self.name);
+    }
+}
+
+/// Tracks the cycles of the rest of the enclosing scope under the span `name`.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     openvm::profile::span!("main");
+///     {
+///         openvm::profile::span!("hash");
+///         // ...
+///     }
+/// }
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __profile_span {
+    ($name:expr) => {
+        let _span_guard = $crate::profile::SpanGuard::new($name);
+    };
+}
+
+#[doc(inline)]
+pub use crate::__profile_span as span;
@@ -0,0 +1,133 @@
+//! Versioned framing for values passed from the host to the guest.
+//!
+//! A frame is the serialized value prefixed with [FRAME_VERSION] and the [Framed::TYPE_TAG] of its
+//! type, so that reading a value with a different type or encoding version than it was written
+//! with fails instead of silently misinterpreting the words.
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{to_vec, Deserializer, Error, Result, WordRead};
+
+/// Version of the frame header and of the word encoding of [super::Serializer].
+pub const FRAME_VERSION: u32 = 1;
+
+/// A type which can be passed in a frame. The tag identifies the type across host and guest
+/// builds, so it must be chosen explicitly and kept stable, e.g. with [type_tag] of a name that
+/// does not change when the type is moved or renamed:
+///
+/// ```
+/// use openvm::serde::{type_tag, Framed};
+///
+/// struct Input;
+///
+/// impl Framed for Input {
+///     const TYPE_TAG: u32 = type_tag("my-app/Input/v1");
+/// }
+/// ```
+pub trait Framed {
+    const TYPE_TAG: u32;
+}
+
+const FNV_OFFSET_BASIS: u32 = 0x811c9dc5;
+const FNV_PRIME: u32 = 0x01000193;
+
+const fn fnv1a(mut hash: u32, bytes: &[u8]) -> u32 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u32).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// FNV-1a hash of `name`, for use as a [Framed::TYPE_TAG].
+pub const fn type_tag(name: &str) -> u32 {
+    fnv1a(FNV_OFFSET_BASIS, name.as_bytes())
+}
+
+/// Tag of a generic type `name` applied to the type arguments with tags `args`.
+pub const fn generic_type_tag(name: &str, args: &[u32]) -> u32 {
+    let mut hash = type_tag(name);
+    let mut i = 0;
+    while i < args.len() {
+        hash = fnv1a(hash, &args[i].to_le_bytes());
+        i += 1;
+    }
+    hash
+}
+
+macro_rules! impl_framed {
+    ($($ty:ty),*) => {
+        $(
+            impl Framed for $ty {
+                const TYPE_TAG: u32 = type_tag(stringify!($ty));
+            }
+        )*
+    };
+}
+
+impl_framed!(
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    String,
+    ()
+);
+
+impl<T: Framed> Framed for Vec<T> {
+    const TYPE_TAG: u32 = generic_type_tag("Vec", &[T::TYPE_TAG]);
+}
+
+impl<T: Framed> Framed for Option<T> {
+    const TYPE_TAG: u32 = generic_type_tag("Option", &[T::TYPE_TAG]);
+}
+
+impl<A: Framed, B: Framed> Framed for (A, B) {
+    const TYPE_TAG: u32 = generic_type_tag("(,)", &[A::TYPE_TAG, B::TYPE_TAG]);
+}
+
+impl<A: Framed, B: Framed, C: Framed> Framed for (A, B, C) {
+    const TYPE_TAG: u32 = generic_type_tag("(,,)", &[A::TYPE_TAG, B::TYPE_TAG, C::TYPE_TAG]);
+}
+
+/// Serialize `value` into a frame.
+pub fn to_framed_vec<T: Serialize + Framed + ?Sized>(value: &T) -> Result<Vec<u32>> {
+    let mut words = vec![FRAME_VERSION, T::TYPE_TAG];
+    words.extend(to_vec(value)?);
+    Ok(words)
+}
+
+/// Deserialize a value of type `T` from a frame.
+pub fn from_framed_reader<T: DeserializeOwned + Framed, R: WordRead>(mut reader: R) -> Result<T> {
+    let mut header = [0u32; 2];
+    reader.read_words(&mut header)?;
+    let [version, tag] = header;
+    if version != FRAME_VERSION {
+        return Err(Error::Custom(format!(
+            "frame version {version} does not match the expected version {FRAME_VERSION}"
+        )));
+    }
+    if tag != T::TYPE_TAG {
+        return Err(Error::Custom(format!(
+            "frame has type tag {tag:#010x}, expected {:#010x}",
+            T::TYPE_TAG
+        )));
+    }
+    T::deserialize(&mut Deserializer::new(reader))
+}
+
+/// Deserialize a value of type `T` from a frame.
+pub fn from_framed_slice<T: DeserializeOwned + Framed>(words: &[u32]) -> Result<T> {
+    from_framed_reader(words)
+}
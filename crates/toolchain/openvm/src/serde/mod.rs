@@ -5,10 +5,15 @@
 
 mod deserializer;
 mod err;
+mod frame;
 mod serializer;
 
 pub use deserializer::{from_slice, Deserializer, WordRead};
 pub use err::{Error, Result};
+pub use frame::{
+    from_framed_reader, from_framed_slice, generic_type_tag, to_framed_vec, type_tag, Framed,
+    FRAME_VERSION,
+};
 pub use serializer::{to_vec, to_vec_with_capacity, Serializer, WordWrite};
 
 #[cfg(test)]
@@ -16,8 +21,11 @@ mod tests {
     use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 
     use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
 
-    use crate::serde::{from_slice, to_vec};
+    use crate::serde::{
+        from_framed_slice, from_slice, to_framed_vec, to_vec, type_tag, Error, Framed,
+    };
 
     #[test]
     fn test_vec_round_trip() {
@@ -44,6 +52,53 @@ mod tests {
         assert_eq!(input, output);
     }
 
+    #[test]
+    fn test_framed_round_trip() {
+        let input: (u32, String) = (1, "foo".into());
+        let data = to_framed_vec(&input).unwrap();
+        let output: (u32, String) = from_framed_slice(&data).unwrap();
+        assert_eq!(input, output);
+
+        assert!(matches!(
+            from_framed_slice::<(u64, String)>(&data),
+            Err(Error::Custom(_))
+        ));
+        let mut data = data;
+        data[0] += 1;
+        assert!(matches!(
+            from_framed_slice::<(u32, String)>(&data),
+            Err(Error::Custom(_))
+        ));
+    }
+
+    #[test]
+    fn test_framed_explicit_tag() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Input {
+            a: u32,
+        }
+        impl Framed for Input {
+            const TYPE_TAG: u32 = type_tag("test/Input/v1");
+        }
+        // Same layout as `Input`, but a different type.
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Other {
+            a: u32,
+        }
+        impl Framed for Other {
+            const TYPE_TAG: u32 = type_tag("test/Other/v1");
+        }
+
+        let data = to_framed_vec(&Input { a: 7 }).unwrap();
+        // The tag is part of the format, so it must not depend on the compiler.
+        assert_eq!(data[1], 0xdd6ff166);
+        assert_eq!(from_framed_slice::<Input>(&data).unwrap(), Input { a: 7 });
+        assert!(matches!(
+            from_framed_slice::<Other>(&data),
+            Err(Error::Custom(_))
+        ));
+    }
+
     #[test]
     fn naive_date_round_trip() {
         let input: NaiveDate = NaiveDate::parse_from_str("2015-09-05", "%Y-%m-%d").unwrap();
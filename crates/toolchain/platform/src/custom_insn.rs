@@ -74,3 +74,17 @@ macro_rules! custom_insn_r {
     };
     // TODO: implement more variants with like rs1 = in(reg) $y etc
 }
+
+/// R4-type custom instruction, with a third source register `rs3`. Transpiled with
+/// `openvm_transpiler::util::from_r4_type`.
+#[macro_export]
+macro_rules! custom_insn_r4 {
+    ($opcode:expr, $funct3:expr, $funct2:expr, $rd:expr, $rs1:expr, $rs2:expr, $rs3:expr) => {
+        // Note: rd = in(reg) because we expect rd to be a pointer
+        unsafe {
+            core::arch::asm!(
+                ".insn r4 {opcode}, {funct3}, {funct2}, {rd}, {rs1}, {rs2}, {rs3}",
+            opcode = const $opcode, funct3 = const $funct3, funct2 = const $funct2, rd = in(reg) $rd, rs1 = in(reg) $rs1, rs2 = in(reg) $rs2, rs3 = in(reg) $rs3)
+        }
+    };
+}
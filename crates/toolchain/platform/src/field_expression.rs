@@ -0,0 +1,88 @@
+//! Guest bindings of field expression ops.
+
+/// Declares the guest functions of an op defined with
+/// `openvm_mod_circuit_builder::field_expression_extension`, which must have the same `insn` and
+/// `blocks * block_size = num_limbs`.
+///
+/// ```ignore
+/// openvm_platform::field_expression_guest! {
+///     /// `x * x + y` modulo the modulus of the extension.
+///     pub fn square_add(x, y) {
+///         setup: setup_square_add,
+///         insn: (0x5b, 0b010),
+///         num_limbs: 32,
+///     }
+/// }
+/// ```
+///
+/// declares `square_add(out: &mut [u8; 32], x: &[u8; 32], y: &[u8; 32])` and
+/// `setup_square_add(modulus: &[u8; 32])`. Field elements are little-endian bytes, and the op has
+/// one or two inputs. The setup must be called before the op, and both panic outside of the zkVM.
+#[macro_export]
+macro_rules! field_expression_guest {
+    (@insn $opcode:expr, $funct3:expr, $funct7:expr, $rd:expr, $rs1:expr) => {
+        $crate::custom_insn_r!($opcode, $funct3, $funct7, $rd, $rs1, "x0")
+    };
+    (@insn $opcode:expr, $funct3:expr, $funct7:expr, $rd:expr, $rs1:expr, $rs2:expr) => {
+        $crate::custom_insn_r!($opcode, $funct3, $funct7, $rd, $rs1, $rs2)
+    };
+    (@same $ptr:expr, $input:ident) => {
+        $ptr
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($a:ident $(, $b:ident)? $(,)?) {
+            setup: $setup:ident,
+            insn: ($opcode:expr, $funct3:expr),
+            num_limbs: $num_limbs:literal
+            $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[inline(always)]
+        $vis fn $name(
+            out: &mut [u8; $num_limbs],
+            $a: &[u8; $num_limbs],
+            $($b: &[u8; $num_limbs],)?
+        ) {
+            #[cfg(target_os = "zkvm")]
+            $crate::field_expression_guest!(
+                @insn $opcode,
+                $funct3,
+                0,
+                out.as_mut_ptr(),
+                $a.as_ptr()
+                $(, $b.as_ptr())?
+            );
+            #[cfg(not(target_os = "zkvm"))]
+            {
+                let _ = (out, $a $(, $b)?);
+                unimplemented!(concat!(stringify!($name), " is only available in the zkVM"));
+            }
+        }
+
+        #[doc = concat!("Sets up the modulus of [", stringify!($name), "].")]
+        #[inline(always)]
+        $vis fn $setup(modulus: &[u8; $num_limbs]) {
+            #[cfg(target_os = "zkvm")]
+            {
+                // The setup checks that the first input is the modulus and writes the result of
+                // the op on the inputs, which is not needed.
+                let mut uninit = core::mem::MaybeUninit::<[u8; $num_limbs]>::uninit();
+                $crate::field_expression_guest!(
+                    @insn $opcode,
+                    $funct3,
+                    1,
+                    uninit.as_mut_ptr(),
+                    modulus.as_ptr()
+                    $(, $crate::field_expression_guest!(@same modulus.as_ptr(), $b))?
+                );
+            }
+            #[cfg(not(target_os = "zkvm"))]
+            {
+                let _ = modulus;
+                unimplemented!(concat!(stringify!($setup), " is only available in the zkVM"));
+            }
+        }
+    };
+}
@@ -1,8 +1,33 @@
+use core::alloc::{GlobalAlloc, Layout};
+
 use critical_section::RawRestoreState;
 use embedded_alloc::LlffHeap as Heap;
 
+use crate::memory::OUT_OF_MEMORY_EXIT_CODE;
+
 #[global_allocator]
-pub static HEAP: Heap = Heap::empty();
+pub static HEAP: EmbeddedAlloc = EmbeddedAlloc {
+    heap: Heap::empty(),
+};
+
+/// Linked-list heap that terminates with [OUT_OF_MEMORY_EXIT_CODE] when the heap is exhausted.
+pub struct EmbeddedAlloc {
+    heap: Heap,
+}
+
+unsafe impl GlobalAlloc for EmbeddedAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.heap.alloc(layout);
+        if ptr.is_null() {
+            crate::rust_rt::terminate::<OUT_OF_MEMORY_EXIT_CODE>();
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.heap.dealloc(ptr, layout)
+    }
+}
 
 struct CriticalSection;
 critical_section::set_impl!(CriticalSection);
@@ -18,10 +43,6 @@ unsafe impl critical_section::Impl for CriticalSection {
 }
 
 pub fn init() {
-    extern "C" {
-        static _end: u8;
-    }
-    let heap_pos: usize = unsafe { (&_end) as *const u8 as usize };
-    let heap_size: usize = crate::memory::GUEST_MAX_MEM - heap_pos;
-    unsafe { HEAP.init(heap_pos, heap_size) }
+    let (heap_start, heap_end) = crate::memory::heap_bounds();
+    unsafe { HEAP.heap.init(heap_start, heap_end - heap_start) }
 }
@@ -0,0 +1,88 @@
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    ptr::{self, addr_of_mut},
+};
+
+use crate::memory::sys_alloc_aligned;
+
+#[global_allocator]
+pub static HEAP: FreeListAlloc = FreeListAlloc;
+
+/// Bump allocator that keeps freed blocks in power-of-two size classes for reuse.
+///
+/// Allocation pops a block from the free list of its size class, and only bumps the heap pointer
+/// if that list is empty. Blocks are never split or coalesced, so this is much cheaper than a
+/// general purpose allocator, at the cost of rounding every allocation up to a power of two.
+/// Allocations aligned to more than [MAX_REUSE_ALIGN] bypass the free lists and are never reused.
+pub struct FreeListAlloc;
+
+/// Largest alignment that blocks in the free lists satisfy.
+const MAX_REUSE_ALIGN: usize = 16;
+/// The smallest size class holds blocks of `1 << MIN_CLASS_BITS` bytes, enough for a
+/// [FreeBlock] link.
+const MIN_CLASS_BITS: u32 = 3;
+const NUM_CLASSES: usize = (usize::BITS - MIN_CLASS_BITS) as usize;
+
+struct FreeBlock {
+    next: *mut FreeBlock,
+}
+
+static mut FREE_LISTS: [*mut FreeBlock; NUM_CLASSES] = [ptr::null_mut(); NUM_CLASSES];
+
+/// Returns the size class of `layout`, or `None` if it cannot be served from the free lists.
+fn size_class(layout: Layout) -> Option<usize> {
+    if layout.align() > MAX_REUSE_ALIGN {
+        return None;
+    }
+    let size = layout
+        .size()
+        .max(1 << MIN_CLASS_BITS)
+        .checked_next_power_of_two()?;
+    Some((size.trailing_zeros() - MIN_CLASS_BITS) as usize)
+}
+
+impl FreeListAlloc {
+    /// Allocates a block, returning whether it was reused from a free list.
+    unsafe fn alloc_block(&self, layout: Layout) -> (*mut u8, bool) {
+        let Some(class) = size_class(layout) else {
+            return (sys_alloc_aligned(layout.size(), layout.align()), false);
+        };
+        // SAFETY: Single threaded, so nothing else can touch the free lists while we're working.
+        let free_lists = unsafe { &mut *addr_of_mut!(FREE_LISTS) };
+        let head = free_lists[class];
+        if head.is_null() {
+            let size = 1 << (class as u32 + MIN_CLASS_BITS);
+            (sys_alloc_aligned(size, MAX_REUSE_ALIGN), false)
+        } else {
+            free_lists[class] = unsafe { (*head).next };
+            (head as *mut u8, true)
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_block(layout).0
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(class) = size_class(layout) {
+            let block = ptr as *mut FreeBlock;
+            // SAFETY: Single threaded, and `ptr` was allocated with at least the size and
+            // alignment of a `FreeBlock` because of the minimum size class.
+            let free_lists = unsafe { &mut *addr_of_mut!(FREE_LISTS) };
+            unsafe { (*block).next = free_lists[class] };
+            free_lists[class] = block;
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let (ptr, reused) = self.alloc_block(layout);
+        // NOTE: Fresh blocks come from the bump allocator and the zkVM memory is zero-initialized,
+        //       so only reused blocks need to be cleared.
+        if reused {
+            unsafe { ptr::write_bytes(ptr, 0, layout.size()) };
+        }
+        ptr
+    }
+}
@@ -1,5 +1,11 @@
-#[cfg(not(feature = "heap-embedded-alloc"))]
+#[cfg(all(feature = "heap-embedded-alloc", feature = "heap-free-list"))]
+compile_error!("features `heap-embedded-alloc` and `heap-free-list` are mutually exclusive");
+
+#[cfg(not(any(feature = "heap-embedded-alloc", feature = "heap-free-list")))]
 mod bump;
 
 #[cfg(feature = "heap-embedded-alloc")]
 pub mod embedded;
+
+#[cfg(feature = "heap-free-list")]
+mod free_list;
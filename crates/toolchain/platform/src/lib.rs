@@ -6,6 +6,7 @@
 
 #[cfg(all(feature = "rust-runtime", target_os = "zkvm"))]
 pub mod custom_insn;
+mod field_expression;
 #[cfg(all(feature = "export-getrandom", target_os = "zkvm"))]
 mod getrandom;
 #[cfg(all(feature = "rust-runtime", target_os = "zkvm"))]
@@ -55,33 +55,126 @@ pub fn is_guest_memory(addr: u32) -> bool {
     GUEST_MIN_MEM <= (addr as usize) && (addr as usize) < GUEST_MAX_MEM
 }
 
+/// Exit code used when the guest heap is exhausted, distinct from the panic exit code `1` so that
+/// hosts can tell an undersized heap apart from other failures.
+pub const OUT_OF_MEMORY_EXIT_CODE: u8 = 3;
+
+/// Heap start address set at guest build time through the `OPENVM_GUEST_HEAP_START` environment
+/// variable. If unset, the heap starts right after the program's ELF sections.
+pub const HEAP_START: Option<usize> = parse_heap_env(option_env!("OPENVM_GUEST_HEAP_START"));
+/// Heap size in bytes set at guest build time through the `OPENVM_GUEST_HEAP_SIZE` environment
+/// variable. If unset, the heap extends up to [GUEST_MAX_MEM].
+pub const HEAP_SIZE: Option<usize> = parse_heap_env(option_env!("OPENVM_GUEST_HEAP_SIZE"));
+
+const _: () = {
+    if let Some(start) = HEAP_START {
+        assert!(
+            TEXT_START as usize <= start && start < GUEST_MAX_MEM,
+            "OPENVM_GUEST_HEAP_START is outside of guest memory"
+        );
+    }
+    if let Some(size) = HEAP_SIZE {
+        assert!(
+            size <= GUEST_MAX_MEM,
+            "OPENVM_GUEST_HEAP_SIZE is larger than guest memory"
+        );
+        if let Some(start) = HEAP_START {
+            assert!(
+                start + size <= GUEST_MAX_MEM,
+                "guest heap extends past the end of guest memory"
+            );
+        }
+    }
+};
+
+/// Parses a decimal or `0x` prefixed hexadecimal address at compile time.
+const fn parse_heap_env(value: Option<&str>) -> Option<usize> {
+    let Some(value) = value else {
+        return None;
+    };
+    let bytes = value.as_bytes();
+    let (radix, mut i) = if bytes.len() > 2 && bytes[0] == b'0' && bytes[1] == b'x' {
+        (16, 2)
+    } else {
+        (10, 0)
+    };
+    assert!(i < bytes.len(), "empty guest heap address");
+    let mut result: usize = 0;
+    while i < bytes.len() {
+        let digit = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' if radix == 16 => bytes[i] - b'a' + 10,
+            b'A'..=b'F' if radix == 16 => bytes[i] - b'A' + 10,
+            b'_' => {
+                i += 1;
+                continue;
+            }
+            _ => panic!("invalid guest heap address"),
+        };
+        result = match result.checked_mul(radix) {
+            Some(result) => match result.checked_add(digit as usize) {
+                Some(result) => result,
+                None => panic!("guest heap address overflows"),
+            },
+            None => panic!("guest heap address overflows"),
+        };
+        i += 1;
+    }
+    Some(result)
+}
+
+/// Returns the `[start, end)` bounds of the guest heap.
+///
+/// Terminates with [OUT_OF_MEMORY_EXIT_CODE] if the configured heap overlaps the program's ELF
+/// sections or extends past [GUEST_MAX_MEM], since allocating there would corrupt memory.
+#[cfg(feature = "rust-runtime")]
+pub fn heap_bounds() -> (usize, usize) {
+    #[cfg(target_os = "zkvm")]
+    let sections_end = {
+        extern "C" {
+            // This symbol is defined by the loader and marks the end
+            // of all elf sections, so this is where we start our
+            // heap.
+            //
+            // This is generated automatically by the linker; see
+            // https://lld.llvm.org/ELF/linker_script.html#sections-command
+            static _end: u8;
+        }
+        unsafe { (&_end) as *const u8 as usize }
+    };
+    #[cfg(not(target_os = "zkvm"))]
+    let sections_end = TEXT_START as usize;
+
+    let start = HEAP_START.unwrap_or(sections_end);
+    let end = match HEAP_SIZE {
+        Some(size) => start.saturating_add(size),
+        None => GUEST_MAX_MEM,
+    };
+    if start < sections_end || end > GUEST_MAX_MEM {
+        super::rust_rt::terminate::<OUT_OF_MEMORY_EXIT_CODE>();
+    }
+    (start, end)
+}
+
 /// # Safety
 ///
 /// This function should be safe to call, but clippy complains if it is not marked as `unsafe`.
 #[cfg(feature = "rust-runtime")]
 #[no_mangle]
 pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8 {
-    #[cfg(target_os = "zkvm")]
-    extern "C" {
-        // This symbol is defined by the loader and marks the end
-        // of all elf sections, so this is where we start our
-        // heap.
-        //
-        // This is generated automatically by the linker; see
-        // https://lld.llvm.org/ELF/linker_script.html#sections-command
-        static _end: u8;
-    }
-
     // Pointer to next heap address to use, or 0 if the heap has not yet been
     // initialized.
     static mut HEAP_POS: usize = 0;
+    // End of the heap, set together with `HEAP_POS`.
+    static mut HEAP_END: usize = 0;
 
     // SAFETY: Single threaded, so nothing else can touch this while we're working.
     let mut heap_pos = unsafe { HEAP_POS };
 
-    #[cfg(target_os = "zkvm")]
     if heap_pos == 0 {
-        heap_pos = unsafe { (&_end) as *const u8 as usize };
+        let (start, end) = heap_bounds();
+        heap_pos = start;
+        unsafe { HEAP_END = end };
     }
 
     // Honor requested alignment if larger than word size.
@@ -94,11 +187,12 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
     }
 
     let ptr = heap_pos as *mut u8;
-    heap_pos += bytes;
 
-    // Check to make sure heap doesn't collide with SYSTEM memory.
-    if crate::memory::SYSTEM.start() < heap_pos {
-        super::rust_rt::terminate::<1>();
+    // Check to make sure the allocation stays within the heap, which ends at SYSTEM memory
+    // unless a smaller heap was configured.
+    match heap_pos.checked_add(bytes) {
+        Some(new_pos) if new_pos <= unsafe { HEAP_END } => heap_pos = new_pos,
+        _ => super::rust_rt::terminate::<OUT_OF_MEMORY_EXIT_CODE>(),
     }
 
     unsafe { HEAP_POS = heap_pos };
@@ -0,0 +1,22 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm::profile;
+
+openvm::entry!(main);
+
+pub fn main() {
+    profile::span!("main");
+    let mut x: u32 = 1;
+    {
+        profile::span!("loop");
+        for i in 0..100 {
+            x = x.wrapping_mul(3).wrapping_add(i);
+        }
+    }
+    profile::start("check");
+    if x == 0 {
+        openvm::process::panic();
+    }
+    profile::end("check");
+}
@@ -0,0 +1,55 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::hint::black_box;
+
+use hex_literal::hex;
+use openvm_ecc_guest::k256::{ecrecover, ecrecover_precompile, Secp256k1Coord};
+use openvm_keccak256_guest::keccak256;
+openvm::entry!(main);
+
+openvm_algebra_moduli_setup::moduli_init! {
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F",
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE BAAEDCE6 AF48A03B BFD25E8C D0364141"
+}
+openvm_ecc_sw_setup::sw_init! {
+    Secp256k1Coord,
+}
+
+pub fn main() {
+    setup_all_moduli();
+    setup_all_curves();
+
+    // Same signature as the `ecdsa` example.
+    let msg_hash = keccak256(black_box(b"example message"));
+    let sig = hex!(
+        "46c05b6368a44b8810d79859441d819b8e7cdc8bfd371e35c53196f4bcacdb5135c7facce2a97b95eacba8a586d87b7958aaf8368ab29cee481f76e871dbd9cb"
+    );
+    let public_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&hex!(
+        "0200866db99873b09fc2fb1e3ba549b156e96d1a567e3284f5f0e859a83320cb8b"
+    ))
+    .unwrap();
+    let expected_address = keccak256(&public_key.to_encoded_point(false).as_bytes()[1..]);
+
+    let address = ecrecover(&msg_hash, &sig, 1).unwrap();
+    assert_eq!(address, expected_address[12..]);
+
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(&msg_hash);
+    input[63] = 28;
+    input[64..].copy_from_slice(&sig);
+    let output = ecrecover_precompile(&input).unwrap();
+    assert_eq!(output[..12], [0u8; 12]);
+    assert_eq!(output[12..], expected_address[12..]);
+
+    // Invalid `v`, `r` and `s` give empty output.
+    input[63] = 29;
+    assert!(ecrecover_precompile(&input).is_none());
+    assert!(ecrecover(&msg_hash, &sig, 2).is_none());
+    let mut zero_r = sig;
+    zero_r[..32].fill(0);
+    assert!(ecrecover(&msg_hash, &zero_r, 1).is_none());
+    let mut large_s = sig;
+    large_s[32..].fill(0xff);
+    assert!(ecrecover(&msg_hash, &large_s, 1).is_none());
+}
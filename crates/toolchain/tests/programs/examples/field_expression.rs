@@ -0,0 +1,51 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+openvm::entry!(main);
+
+openvm_platform::field_expression_guest! {
+    /// `x * x + y` modulo the secp256k1 coordinate prime.
+    pub fn square_add(x, y) {
+        setup: setup_square_add,
+        insn: (0x5b, 0b010),
+        num_limbs: 32,
+    }
+}
+
+/// The secp256k1 coordinate prime, in little-endian bytes.
+const MODULUS: [u8; 32] = {
+    let mut modulus = [0xff; 32];
+    modulus[0] = 0x2f;
+    modulus[1] = 0xfc;
+    modulus[4] = 0xfe;
+    modulus
+};
+
+fn from_u32(x: u32) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    bytes[..4].copy_from_slice(&x.to_le_bytes());
+    bytes
+}
+
+pub fn main() {
+    setup_square_add(&MODULUS);
+
+    let mut out = [0; 32];
+    square_add(&mut out, &from_u32(1234), &from_u32(5678));
+    assert_eq!(out, from_u32(1234 * 1234 + 5678));
+
+    // (p - 1)^2 + 1 = 2 mod p
+    let mut minus_one = MODULUS;
+    minus_one[0] -= 1;
+    square_add(&mut out, &minus_one, &from_u32(1));
+    assert_eq!(out, from_u32(2));
+
+    // The output may be one of the inputs
+    let mut x = from_u32(3);
+    for _ in 0..4 {
+        let input = x;
+        square_add(&mut x, &input, &from_u32(1));
+    }
+    // 3 -> 10 -> 101 -> 10202 -> 104080805
+    assert_eq!(x, from_u32(104080805));
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+use alloc::vec::Vec;
+
+openvm::entry!(main);
+
+pub fn main() {
+    // Repeatedly allocate and free blocks of the same size. The free-list allocator reuses them,
+    // while the bump allocator runs out of a small heap.
+    for i in 0..64u32 {
+        let v = (0..4096).map(|j| i + j).collect::<Vec<u32>>();
+        assert_eq!(v[4095], i + 4095);
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm_algebra_guest::IntMod;
+
+openvm::entry!(main);
+
+openvm_algebra_moduli_setup::moduli_declare! {
+    Secp256k1Coord { modulus = "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F" }
+}
+
+openvm_algebra_moduli_setup::moduli_init!(
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F"
+);
+
+pub fn main() {
+    setup_all_moduli();
+    let mut a = Secp256k1Coord::from_u32(1234);
+    let mut b = Secp256k1Coord::from_u32(5678);
+    let mut c = Secp256k1Coord::from_u32(91011);
+
+    for _ in 0..16 {
+        let expected = a.clone() * &b + &c;
+        assert_eq!(a.mul_add(&b, &c), expected);
+        // The output may be one of the inputs
+        c = a.mul_add(&b, &c);
+        assert_eq!(c, expected);
+        a = b.clone();
+        b = c.clone();
+    }
+
+    // -1 * 1 + 1 wraps around to zero
+    let minus_one = Secp256k1Coord::ZERO - &Secp256k1Coord::ONE;
+    assert_eq!(
+        minus_one.mul_add(&Secp256k1Coord::ONE, &Secp256k1Coord::ONE),
+        Secp256k1Coord::ZERO
+    );
+}
@@ -0,0 +1,61 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use hex_literal::hex;
+use openvm_ecc_guest::k256::{
+    verify_schnorr, verify_schnorr_batch, SchnorrBatchItem, Secp256k1Coord,
+};
+openvm::entry!(main);
+
+openvm_algebra_moduli_setup::moduli_init! {
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE FFFFFC2F",
+    "0xFFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE BAAEDCE6 AF48A03B BFD25E8C D0364141"
+}
+openvm_ecc_sw_setup::sw_init! {
+    Secp256k1Coord,
+}
+
+pub fn main() {
+    setup_all_moduli();
+    setup_all_curves();
+
+    // Test vectors 0 and 1 of BIP-340.
+    let pubkey_0 = hex!("F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+    let msg_0 = [0u8; 32];
+    let sig_0 = hex!(
+        "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"
+    );
+    let pubkey_1 = hex!("DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659");
+    let msg_1 = hex!("243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89");
+    let sig_1 = hex!(
+        "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A"
+    );
+    assert!(verify_schnorr(&pubkey_0, &msg_0, &sig_0));
+    assert!(verify_schnorr(&pubkey_1, &msg_1, &sig_1));
+
+    // Wrong message, wrong key and out-of-range `s`.
+    assert!(!verify_schnorr(&pubkey_0, &msg_1, &sig_0));
+    assert!(!verify_schnorr(&pubkey_1, &msg_0, &sig_0));
+    let mut large_s = sig_0;
+    large_s[32..].fill(0xff);
+    assert!(!verify_schnorr(&pubkey_0, &msg_0, &large_s));
+
+    let items = [
+        SchnorrBatchItem {
+            pubkey: &pubkey_0,
+            msg: &msg_0,
+            sig: &sig_0,
+        },
+        SchnorrBatchItem {
+            pubkey: &pubkey_1,
+            msg: &msg_1,
+            sig: &sig_1,
+        },
+    ];
+    assert!(verify_schnorr_batch(&items));
+    assert!(verify_schnorr_batch(&[]));
+
+    let mut bad_items = items;
+    bad_items[1].msg = &msg_0;
+    assert!(!verify_schnorr_batch(&bad_items));
+}
@@ -1,8 +1,11 @@
 use eyre::Result;
 use openvm_bigint_circuit::Int256Rv32Config;
 use openvm_bigint_transpiler::Int256TranspilerExtension;
+use openvm_build::GuestOptions;
 use openvm_circuit::{
-    arch::{hasher::poseidon2::vm_poseidon2_hasher, instructions::exe::VmExe, VmExecutor},
+    arch::{
+        hasher::poseidon2::vm_poseidon2_hasher, instructions::exe::VmExe, ExitCode, VmExecutor,
+    },
     system::memory::tree::public_values::UserPublicValuesProof,
     utils::new_air_test_with_min_segments,
 };
@@ -16,7 +19,9 @@ use openvm_stark_sdk::{openvm_stark_backend::p3_field::AbstractField, p3_baby_be
 use openvm_transpiler::{elf::ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES, transpiler::Transpiler, FromElf};
 use test_case::test_case;
 
-use crate::utils::{build_example_program, build_example_program_with_features};
+use crate::utils::{
+    build_example_program, build_example_program_with_features, build_example_program_with_options,
+};
 
 type F = BabyBear;
 
@@ -177,6 +182,22 @@ fn test_print_runtime() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cycle_tracker_runtime() -> Result<()> {
+    let elf = build_example_program("cycle-tracker")?;
+    let exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension),
+    )?;
+    let config = Rv32IConfig::default();
+    let executor = VmExecutor::<F, _>::new(config);
+    executor.execute(exe, vec![])?;
+    Ok(())
+}
+
 #[test]
 fn test_matrix_power_runtime() -> Result<()> {
     let elf = build_example_program("matrix-power")?;
@@ -226,3 +247,33 @@ fn test_tiny_mem_test_runtime() -> Result<()> {
     executor.execute(exe, vec![])?;
     Ok(())
 }
+
+#[test_case(&[], ExitCode::OutOfMemory)]
+#[test_case(&["heap-free-list"], ExitCode::Success)]
+fn test_heap_limit_runtime(features: &[&str], expected: ExitCode) -> Result<()> {
+    // 64 KiB fits one 16 KiB vector at a time, but not all 64 of them.
+    let guest_opts = GuestOptions::default()
+        .with_features(features)
+        .with_heap_size(1 << 16);
+    let elf = build_example_program_with_options("heap-limit", guest_opts)?;
+    let exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension),
+    )?;
+    let config = Rv32ImConfig::default();
+    let executor = VmExecutor::<F, _>::new(config);
+    let segments = executor.execute_segments(exe, vec![])?;
+    let end_state = segments
+        .last()
+        .unwrap()
+        .chip_complex
+        .connector_chip()
+        .boundary_states[1]
+        .expect("end state must be set");
+    assert_eq!(end_state.is_terminate, 1);
+    assert_eq!(end_state.exit_code, expected as u32);
+    Ok(())
+}
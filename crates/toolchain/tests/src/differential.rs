@@ -0,0 +1,197 @@
+//! Differential testing of the RV32IM execution against a reference RISC-V simulator, comparing
+//! the registers and the written memory after every instruction.
+
+use std::collections::BTreeMap;
+
+use eyre::{bail, eyre, Result};
+use openvm_circuit::{
+    arch::{instructions::exe::VmExe, ExecutionSegment, ExecutionState, Streams, VmConfig},
+    system::memory::memory_image_to_equipartition,
+};
+use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+use rrs_lib::{instruction_executor::InstructionExecutor, HartState, MemAccessSize, Memory};
+
+/// A RISC-V simulator to compare the OpenVM execution with.
+pub trait ReferenceSimulator {
+    /// Executes the instruction at the current pc.
+    fn step(&mut self) -> Result<()>;
+
+    fn pc(&self) -> u32;
+
+    fn register(&self, index: usize) -> u32;
+
+    /// The byte addresses written by the last step.
+    fn last_writes(&self) -> &[u32];
+
+    fn read_byte(&self, addr: u32) -> u8;
+}
+
+/// Byte addressed memory, zero where never written, which records the writes of the last step.
+#[derive(Default)]
+struct SparseMemory {
+    bytes: BTreeMap<u32, u8>,
+    last_writes: Vec<u32>,
+}
+
+fn access_len(size: MemAccessSize) -> u32 {
+    match size {
+        MemAccessSize::Byte => 1,
+        MemAccessSize::HalfWord => 2,
+        MemAccessSize::Word => 4,
+    }
+}
+
+impl Memory for SparseMemory {
+    fn read_mem(&mut self, addr: u32, size: MemAccessSize) -> Option<u32> {
+        let value = (0..access_len(size)).rev().fold(0, |acc, i| {
+            (acc << 8) | *self.bytes.get(&(addr + i)).unwrap_or(&0) as u32
+        });
+        Some(value)
+    }
+
+    fn write_mem(&mut self, addr: u32, size: MemAccessSize, store_data: u32) -> bool {
+        for (i, byte) in store_data.to_le_bytes()[..access_len(size) as usize]
+            .iter()
+            .enumerate()
+        {
+            self.bytes.insert(addr + i as u32, *byte);
+            self.last_writes.push(addr + i as u32);
+        }
+        true
+    }
+}
+
+/// [ReferenceSimulator] backed by the rrs-lib RISC-V simulator.
+pub struct RrsSimulator {
+    hart_state: HartState,
+    memory: SparseMemory,
+}
+
+impl RrsSimulator {
+    /// Starts at the pc and with the initial memory of `exe`.
+    pub fn new<F: PrimeField32>(exe: &VmExe<F>) -> Self {
+        let mut hart_state = HartState::new();
+        hart_state.pc = exe.pc_start;
+        let bytes = exe
+            .init_memory
+            .iter()
+            .filter(|((addr_space, _), _)| *addr_space == RV32_MEMORY_AS)
+            .map(|(&(_, ptr), value)| (ptr, value.as_canonical_u32() as u8))
+            .collect();
+        Self {
+            hart_state,
+            memory: SparseMemory {
+                bytes,
+                last_writes: vec![],
+            },
+        }
+    }
+}
+
+impl ReferenceSimulator for RrsSimulator {
+    fn step(&mut self) -> Result<()> {
+        self.memory.last_writes.clear();
+        let mut executor = InstructionExecutor {
+            mem: &mut self.memory,
+            hart_state: &mut self.hart_state,
+        };
+        executor
+            .step()
+            .map_err(|e| eyre!("reference simulator failed: {e:?}"))
+    }
+
+    fn pc(&self) -> u32 {
+        self.hart_state.pc
+    }
+
+    fn register(&self, index: usize) -> u32 {
+        self.hart_state.registers[index]
+    }
+
+    fn last_writes(&self) -> &[u32] {
+        &self.memory.last_writes
+    }
+
+    fn read_byte(&self, addr: u32) -> u8 {
+        *self.memory.bytes.get(&addr).unwrap_or(&0)
+    }
+}
+
+/// Executes `exe` in OpenVM and in `reference` side by side until TERMINATE, and fails at the
+/// first instruction after which the pc, a register or a byte written by the reference differs.
+/// The program must only use RV32IM instructions besides TERMINATE, since the reference can't
+/// execute the OpenVM specific ones. Returns the number of compared instructions.
+pub fn execute_differential<F: PrimeField32, VC: VmConfig<F>>(
+    config: &VC,
+    exe: VmExe<F>,
+    reference: &mut impl ReferenceSimulator,
+) -> Result<usize> {
+    let mut segment = ExecutionSegment::new(
+        config,
+        exe.program.clone(),
+        Streams::default(),
+        Some(memory_image_to_equipartition(exe.init_memory)),
+        exe.fn_bounds,
+    );
+    let timestamp = segment
+        .chip_complex
+        .memory_controller()
+        .borrow()
+        .timestamp();
+    let mut state = ExecutionState::new(exe.pc_start, timestamp);
+    if reference.pc() != state.pc {
+        bail!(
+            "start pc differs: openvm {:#x}, reference {:#x}",
+            state.pc,
+            reference.pc()
+        );
+    }
+
+    let mut num_instructions = 0;
+    while let Some(next_state) = segment.step(state)? {
+        let pc = state.pc;
+        reference
+            .step()
+            .map_err(|e| e.wrap_err(format!("at pc {pc:#x}")))?;
+        state = next_state;
+        num_instructions += 1;
+
+        if reference.pc() != state.pc {
+            bail!(
+                "after pc {pc:#x}: next pc differs: openvm {:#x}, reference {:#x}",
+                state.pc,
+                reference.pc()
+            );
+        }
+        let memory = segment.chip_complex.memory_controller().borrow();
+        for index in 0..32 {
+            let limbs = memory.unsafe_read::<RV32_REGISTER_NUM_LIMBS>(
+                F::from_canonical_u32(RV32_REGISTER_AS),
+                F::from_canonical_usize(index * RV32_REGISTER_NUM_LIMBS),
+            );
+            let value = u32::from_le_bytes(limbs.map(|x| x.as_canonical_u32() as u8));
+            if value != reference.register(index) {
+                bail!(
+                    "after pc {pc:#x}: x{index} differs: openvm {value:#x}, reference {:#x}",
+                    reference.register(index)
+                );
+            }
+        }
+        for &addr in reference.last_writes() {
+            let value = memory
+                .unsafe_read_cell(
+                    F::from_canonical_u32(RV32_MEMORY_AS),
+                    F::from_canonical_u32(addr),
+                )
+                .as_canonical_u32();
+            if value != reference.read_byte(addr) as u32 {
+                bail!(
+                    "after pc {pc:#x}: byte at {addr:#x} differs: openvm {value:#x}, reference {:#x}",
+                    reference.read_byte(addr)
+                );
+            }
+        }
+    }
+    Ok(num_instructions)
+}
@@ -11,7 +11,7 @@ use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtensi
 use openvm_circuit::{
     arch::{
         instructions::exe::VmExe, SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex,
-        VmConfig, VmInventoryError,
+        VmConfig, VmExecutor, VmInventoryError,
     },
     derive::{AnyEnum, InstructionExecutor, VmConfig},
     utils::new_air_test_with_min_segments,
@@ -75,6 +75,41 @@ fn test_modular_runtime() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_modular_runtime_multi_segment() -> Result<()> {
+    let elf = build_example_program("little")?;
+    let openvm_exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(ModularTranspilerExtension),
+    )?;
+    // The moduli are set up once at the start, so the operations in the later segments run on
+    // chips which never see the setup.
+    let mut config = Rv32ModularConfig::new(vec![SECP256K1_CONFIG.modulus.clone()]);
+    config.system = config.system.with_max_segment_len(1000);
+    new_air_test_with_min_segments(config, openvm_exe, vec![], 2, true);
+    Ok(())
+}
+
+#[test]
+fn test_modular_muladd_runtime() -> Result<()> {
+    let elf = build_example_program("muladd")?;
+    let openvm_exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(ModularTranspilerExtension),
+    )?;
+    let config = Rv32ModularConfig::new(vec![SECP256K1_CONFIG.modulus.clone()]);
+    new_air_test_with_min_segments(config, openvm_exe, vec![], 1, true);
+    Ok(())
+}
+
 #[test]
 fn test_complex_runtime() -> Result<()> {
     let elf = build_example_program("complex")?;
@@ -130,6 +165,21 @@ fn test_ec_runtime() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ec_manifest() -> Result<()> {
+    let elf = build_example_program_with_features("ec", ["k256"])?;
+    let manifest = elf.manifest();
+    assert!(manifest
+        .custom_instructions
+        .contains(&(openvm_ecc_guest::OPCODE, openvm_ecc_guest::SW_FUNCT3)));
+    assert_eq!(manifest.moduli.len(), 2);
+    assert_eq!(manifest.moduli[&0], SECP256K1_CONFIG.modulus);
+    assert_eq!(manifest.moduli[&1], SECP256K1_CONFIG.scalar);
+    assert_eq!(manifest.curves.len(), 1);
+    assert_eq!(manifest.curves[&0], SECP256K1_CONFIG.modulus);
+    Ok(())
+}
+
 #[test]
 fn test_decompress() -> Result<()> {
     use openvm_ecc_guest::halo2curves::{group::Curve, secp256k1::Secp256k1Affine};
@@ -212,3 +262,42 @@ fn test_ecdsa_runtime() -> Result<()> {
     new_air_test_with_min_segments(config, openvm_exe, vec![], 1, true);
     Ok(())
 }
+
+#[test]
+fn test_ecrecover_runtime() -> Result<()> {
+    let elf = build_example_program_with_features("ecrecover", ["k256"])?;
+    let config = Rv32ModularKeccak256Config::new(vec![SECP256K1_CONFIG.clone()]);
+
+    let openvm_exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(Keccak256TranspilerExtension)
+            .with_extension(EccTranspilerExtension)
+            .with_extension(ModularTranspilerExtension),
+    )?;
+    let executor = VmExecutor::<F, _>::new(config);
+    executor.execute(openvm_exe, vec![])?;
+    Ok(())
+}
+
+#[test]
+fn test_schnorr_runtime() -> Result<()> {
+    let elf = build_example_program_with_features("schnorr", ["k256"])?;
+    let config = Rv32WeierstrassConfig::new(vec![SECP256K1_CONFIG.clone()]);
+
+    let openvm_exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(EccTranspilerExtension)
+            .with_extension(ModularTranspilerExtension),
+    )?;
+    let executor = VmExecutor::<F, _>::new(config);
+    executor.execute(openvm_exe, vec![])?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::{cell::RefCell, rc::Rc};
+
+use derive_more::derive::From;
+use eyre::Result;
+use openvm_circuit::{
+    arch::{
+        instructions::exe::VmExe, SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex,
+        VmConfig, VmInventoryError,
+    },
+    derive::{AnyEnum, InstructionExecutor, VmConfig},
+    utils::new_air_test_with_min_segments,
+};
+use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_ecc_circuit::SECP256K1_CONFIG;
+use openvm_instructions::UsizeOpcode;
+use openvm_mod_circuit_builder::{
+    field_expression_extension, ExprBuilder, ExprBuilderConfig, FieldExpr,
+};
+use openvm_rv32im_circuit::{
+    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+    Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_rv32im_transpiler::{
+    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+use openvm_transpiler::{transpiler::Transpiler, FromElf, TranspilerExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::build_example_program;
+
+type F = BabyBear;
+
+fn square_add_expr(config: ExprBuilderConfig, range_bus: VariableRangeCheckerBus) -> FieldExpr {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let x = ExprBuilder::new_input(builder.clone());
+    let y = ExprBuilder::new_input(builder.clone());
+    let mut r = x.clone() * x + y;
+    r.save_output();
+
+    let builder = builder.borrow().clone();
+    FieldExpr::new(builder, range_bus, true)
+}
+
+// Everything but the formula is generated. The guest binding is declared in the
+// `field_expression` example with the same instruction encoding.
+field_expression_extension! {
+    /// `x * x + y` modulo `modulus`.
+    pub struct SquareAdd {
+        opcode: SquareAddOpcode = 0x380,
+        chip: SquareAddChip,
+        executor: SquareAddExecutor,
+        periphery: SquareAddPeriphery,
+        transpiler: SquareAddTranspilerExtension,
+        insn: (0x5b, 0b010),
+        num_reads: 2,
+        blocks: 1,
+        block_size: 32,
+        expr: square_add_expr(),
+        name: "SquareAdd",
+    }
+}
+
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct Rv32SquareAddConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub mul: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub square_add: SquareAdd,
+}
+
+#[test]
+fn test_field_expression_extension_runtime() -> Result<()> {
+    let elf = build_example_program("field_expression")?;
+    let openvm_exe = VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension)
+            .with_extension(SquareAddTranspilerExtension),
+    )?;
+    let config = Rv32SquareAddConfig {
+        system: SystemConfig::default().with_continuations(),
+        base: Default::default(),
+        mul: Default::default(),
+        io: Default::default(),
+        square_add: SquareAdd::new(SECP256K1_CONFIG.modulus.clone()),
+    };
+    new_air_test_with_min_segments(config, openvm_exe, vec![], 1, true);
+    Ok(())
+}
+
+#[test]
+fn test_field_expression_extension_transpiler() {
+    // An R-type instruction with rd = x10, rs1 = x11 and rs2 = x12.
+    let encode = |opcode: u32, funct3: u32, funct7: u32| {
+        (funct7 << 25) | (12 << 20) | (11 << 15) | (funct3 << 12) | (10 << 7) | opcode
+    };
+    let extension = SquareAddTranspilerExtension;
+
+    for local_opcode in SquareAddOpcode::ALL {
+        let word = encode(0x5b, 0b010, local_opcode as u32);
+        let (instruction, len) =
+            TranspilerExtension::<F>::process_custom(&extension, &[word]).unwrap();
+        assert_eq!(len, 1);
+        assert_eq!(
+            instruction.opcode.as_usize(),
+            local_opcode.with_default_offset()
+        );
+    }
+    for word in [
+        encode(0x5b, 0b010, 2),
+        encode(0x5b, 0b001, 0),
+        encode(0x2b, 0b010, 0),
+    ] {
+        assert!(TranspilerExtension::<F>::process_custom(&extension, &[word]).is_none());
+    }
+}
+
+#[test]
+fn test_field_expression_extension_serde() {
+    let extension = SquareAdd::new(SECP256K1_CONFIG.modulus.clone());
+    let json = serde_json::to_string(&extension).unwrap();
+    assert_eq!(serde_json::from_str::<SquareAdd>(&json).unwrap(), extension);
+}
@@ -1,5 +1,6 @@
 //! Unit tests for OpenVM toolchain starting from rust
 
+pub mod differential;
 pub mod utils;
 
 #[cfg(test)]
@@ -7,4 +8,6 @@ pub mod basic_tests;
 #[cfg(test)]
 pub mod ecc_tests;
 #[cfg(test)]
+pub mod field_expression_tests;
+#[cfg(test)]
 pub mod pairing_tests;
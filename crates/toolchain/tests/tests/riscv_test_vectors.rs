@@ -10,11 +10,37 @@ use openvm_rv32im_transpiler::{
     Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
 };
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
-use openvm_toolchain_tests::utils::decode_elf;
+use openvm_toolchain_tests::{
+    differential::{execute_differential, RrsSimulator},
+    utils::decode_elf,
+};
 use openvm_transpiler::{transpiler::Transpiler, FromElf};
+use test_case::test_case;
 
 type F = BabyBear;
 
+fn rv32im_exe(elf_path: &PathBuf) -> Result<VmExe<F>> {
+    let elf = decode_elf(elf_path)?;
+    Ok(VmExe::from_elf(
+        elf,
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension),
+    )?)
+}
+
+#[test_case("tests/data/rv32im-exp-from-as")]
+#[test_case("tests/data/rv32im-fib-from-as")]
+fn test_rv32im_differential(elf_path: &str) -> Result<()> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let exe = rv32im_exe(&dir.join(elf_path))?;
+    let mut reference = RrsSimulator::new(&exe);
+    let num_instructions = execute_differential(&Rv32ImConfig::default(), exe, &mut reference)?;
+    assert!(num_instructions > 0);
+    Ok(())
+}
+
 #[test]
 #[ignore = "must run makefile"]
 fn test_rv32im_riscv_vector_runtime() -> Result<()> {
@@ -92,3 +118,33 @@ fn test_rv32im_riscv_vector_prove() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[ignore = "must run makefile"]
+fn test_rv32im_riscv_vector_differential() -> Result<()> {
+    let skip_list = ["rv32ui-p-ma_data", "rv32ui-p-fence_i"];
+    let config = Rv32ImConfig::default();
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("rv32im-test-vectors/tests");
+    let mut failures = vec![];
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().unwrap_or_default() == "" {
+            let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+            if skip_list.contains(&file_name.as_str()) {
+                continue;
+            }
+            let exe = rv32im_exe(&path)?;
+            let mut reference = RrsSimulator::new(&exe);
+            match execute_differential(&config, exe, &mut reference) {
+                Ok(_) => println!("Passed!: {}", file_name),
+                Err(e) => {
+                    println!("Failed: {} with error: {:?}", file_name, e);
+                    failures.push(file_name);
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "differential failures: {failures:?}");
+
+    Ok(())
+}
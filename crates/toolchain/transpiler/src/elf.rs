@@ -14,6 +14,8 @@ use openvm_instructions::exe::FnBound;
 use openvm_instructions::exe::FnBounds;
 use openvm_platform::WORD_SIZE;
 
+use crate::manifest::GuestManifest;
+
 pub const ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES: usize = 32;
 
 /// RISC-V 32IM ELF (Executable and Linkable Format) File.
@@ -40,6 +42,8 @@ pub struct Elf {
     pub(crate) max_num_public_values: usize,
     /// Debug info for spanning benchmark metrics by function.
     pub(crate) fn_bounds: FnBounds,
+    /// What the program needs from the VM.
+    pub(crate) manifest: GuestManifest,
 }
 
 impl Elf {
@@ -50,6 +54,7 @@ impl Elf {
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
         fn_bounds: FnBounds,
+        manifest: GuestManifest,
     ) -> Self {
         Self {
             instructions,
@@ -58,9 +63,15 @@ impl Elf {
             memory_image,
             max_num_public_values: ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES,
             fn_bounds,
+            manifest,
         }
     }
 
+    /// The custom instructions, moduli and curves the program needs from the VM.
+    pub fn manifest(&self) -> &GuestManifest {
+        &self.manifest
+    }
+
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory
     /// address.
     ///
@@ -194,12 +205,20 @@ impl Elf {
             }
         }
 
+        // The moduli and curves set up by the guest are placed in the `.openvm` section.
+        let openvm_section = match elf.section_header_by_name(".openvm")? {
+            Some(header) => elf.section_data(&header)?.0,
+            None => &[],
+        };
+        let manifest = GuestManifest::new(&instructions, openvm_section)?;
+
         Ok(Elf::new(
             instructions,
             entry,
             base_address,
             image,
             fn_bounds,
+            manifest,
         ))
     }
 }
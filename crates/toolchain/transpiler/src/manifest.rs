@@ -0,0 +1,91 @@
+//! What a guest program needs from the VM, read from its ELF so that it can be checked against a
+//! VM config before execution.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use eyre::{bail, ContextCompat};
+use num_bigint_dig::BigUint;
+
+/// Opcode of the custom instructions in the `custom-0` slot of RISC-V.
+pub const CUSTOM_0_OPCODE: u8 = 0x0b;
+/// Opcode of the custom instructions in the `custom-1` slot of RISC-V.
+pub const CUSTOM_1_OPCODE: u8 = 0x2b;
+/// Opcode of the custom instructions in the `custom-2` slot of RISC-V.
+pub const CUSTOM_2_OPCODE: u8 = 0x5b;
+/// Opcode of the custom instructions in the `custom-3` slot of RISC-V.
+pub const CUSTOM_3_OPCODE: u8 = 0x7b;
+/// Opcode and `funct7` of the RISC-V `M` extension instructions.
+const RV32M_OPCODE: u8 = 0x33;
+const RV32M_FUNCT7: u32 = 0x01;
+
+/// Kind tag of the entries placed in the `.openvm` section by `moduli_init!`.
+const SECTION_KIND_MODULUS: u8 = 1;
+/// Kind tag of the entries placed in the `.openvm` section by `sw_init!`.
+const SECTION_KIND_CURVE: u8 = 2;
+
+/// Requirements of a guest program on the VM it runs on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuestManifest {
+    /// `(opcode, funct3)` of every custom instruction in the program, which identifies the VM
+    /// extension that handles it.
+    pub custom_instructions: BTreeSet<(u8, u8)>,
+    /// Whether the program uses the multiplication and division instructions of RV32M.
+    pub uses_rv32m: bool,
+    /// Moduli set up by the guest with `moduli_init!`, by modulus index.
+    pub moduli: BTreeMap<usize, BigUint>,
+    /// Coordinate moduli of the curves set up by the guest with `sw_init!`, by curve index.
+    pub curves: BTreeMap<usize, BigUint>,
+}
+
+impl GuestManifest {
+    /// Builds the manifest from the executable words of the program and the contents of its
+    /// `.openvm` section. The section holds entries of the form
+    /// `[kind: u8, index: u8, len: u32 (little-endian), value: [u8; len] (little-endian)]`.
+    pub(crate) fn new(instructions: &[u32], openvm_section: &[u8]) -> eyre::Result<Self> {
+        let custom_instructions = instructions
+            .iter()
+            .map(|&insn| ((insn & 0x7f) as u8, ((insn >> 12) & 0b111) as u8))
+            .filter(|(opcode, _)| {
+                [
+                    CUSTOM_0_OPCODE,
+                    CUSTOM_1_OPCODE,
+                    CUSTOM_2_OPCODE,
+                    CUSTOM_3_OPCODE,
+                ]
+                .contains(opcode)
+            })
+            .collect();
+        let uses_rv32m = instructions
+            .iter()
+            .any(|&insn| (insn & 0x7f) as u8 == RV32M_OPCODE && insn >> 25 == RV32M_FUNCT7);
+
+        let mut moduli = BTreeMap::new();
+        let mut curves = BTreeMap::new();
+        let mut rest = openvm_section;
+        while !rest.is_empty() {
+            let header = rest.get(..6).context("truncated .openvm section entry")?;
+            let (kind, index) = (header[0], header[1] as usize);
+            let len = u32::from_le_bytes(header[2..6].try_into().unwrap()) as usize;
+            let value = rest
+                .get(6..6 + len)
+                .context("truncated .openvm section entry")?;
+            let value = BigUint::from_bytes_le(value);
+            let entries = match kind {
+                SECTION_KIND_MODULUS => &mut moduli,
+                SECTION_KIND_CURVE => &mut curves,
+                _ => bail!("unknown .openvm section entry kind {kind}"),
+            };
+            if entries.insert(index, value).is_some() {
+                bail!("duplicate .openvm section entry of kind {kind} for index {index}");
+            }
+            rest = &rest[6 + len..];
+        }
+
+        Ok(Self {
+            custom_instructions,
+            uses_rv32m,
+            moduli,
+            curves,
+        })
+    }
+}
@@ -32,6 +32,53 @@ pub fn from_r_type<F: PrimeField32>(
     )
 }
 
+/// An R4-type instruction, the R-type with a third source register `rs3` in place of the upper
+/// five bits of `funct7`, as used by the RISC-V fused multiply-add instructions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct R4Type {
+    pub rs3: usize,
+    pub funct2: u32,
+    pub rs2: usize,
+    pub rs1: usize,
+    pub funct3: u32,
+    pub rd: usize,
+}
+
+impl R4Type {
+    pub fn new(insn: u32) -> Self {
+        Self {
+            rs3: ((insn >> 27) & 0x1f) as usize,
+            funct2: (insn >> 25) & 0x3,
+            rs2: ((insn >> 20) & 0x1f) as usize,
+            rs1: ((insn >> 15) & 0x1f) as usize,
+            funct3: (insn >> 12) & 0x7,
+            rd: ((insn >> 7) & 0x1f) as usize,
+        }
+    }
+}
+
+/// Create a new [`Instruction`] from an R4-type instruction. Like [from_r_type], with the pointer
+/// to `rs3` in operand `f`.
+pub fn from_r4_type<F: PrimeField32>(
+    opcode: usize,
+    e_as: usize,
+    dec_insn: &R4Type,
+) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs2),
+        F::ONE,
+        F::from_canonical_usize(e_as),
+        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs3),
+        F::ZERO,
+    )
+}
+
 /// Create a new [`Instruction`] from an I-type instruction. Should only be used for ALU instructions because `imm` is transpiled in a special way.
 pub fn from_i_type<F: PrimeField32>(opcode: usize, dec_insn: &IType) -> Instruction<F> {
     if dec_insn.rd == 0 {
@@ -46,6 +46,14 @@ pub fn instruction_executor_derive(input: TokenStream) -> TokenStream {
                     fn get_opcode_name(&self, opcode: usize) -> String {
                         self.0.get_opcode_name(opcode)
                     }
+
+                    fn reserve_records(&mut self, additional: usize) {
+                        self.0.reserve_records(additional)
+                    }
+
+                    fn skip_records(&mut self) {
+                        self.0.skip_records()
+                    }
                 }
             }
             .into()
@@ -74,7 +82,7 @@ pub fn instruction_executor_derive(input: TokenStream) -> TokenStream {
                 .expect("First generic must be type for Field");
             // Use full path ::openvm_circuit... so it can be used either within or outside the vm crate.
             // Assume F is already generic of the field.
-            let (execute_arms, get_opcode_name_arms): (Vec<_>, Vec<_>) =
+            let (execute_arms, get_opcode_name_arms, reserve_records_arms, skip_records_arms): (Vec<_>, Vec<_>, Vec<_>, Vec<_>) =
                 multiunzip(variants.iter().map(|(variant_name, field)| {
                     let field_ty = &field.ty;
                     let execute_arm = quote! {
@@ -83,8 +91,15 @@ pub fn instruction_executor_derive(input: TokenStream) -> TokenStream {
                     let get_opcode_name_arm = quote! {
                         #name::#variant_name(x) => <#field_ty as ::openvm_circuit::arch::InstructionExecutor<#first_ty_generic>>::get_opcode_name(x, opcode)
                     };
+                    let reserve_records_arm = quote! {
+                        #name::#variant_name(x) => <#field_ty as ::openvm_circuit::arch::InstructionExecutor<#first_ty_generic>>::reserve_records(x, additional)
+                    };
+
+                    let skip_records_arm = quote! {
+                        #name::#variant_name(x) => <#field_ty as ::openvm_circuit::arch::InstructionExecutor<#first_ty_generic>>::skip_records(x)
+                    };
 
-                    (execute_arm, get_opcode_name_arm)
+                    (execute_arm, get_opcode_name_arm, reserve_records_arm, skip_records_arm)
                 }));
             quote! {
                 impl #impl_generics ::openvm_circuit::arch::InstructionExecutor<#first_ty_generic> for #name #ty_generics {
@@ -103,6 +118,18 @@ pub fn instruction_executor_derive(input: TokenStream) -> TokenStream {
                             #(#get_opcode_name_arms,)*
                         }
                     }
+
+                    fn reserve_records(&mut self, additional: usize) {
+                        match self {
+                            #(#reserve_records_arms,)*
+                        }
+                    }
+
+                    fn skip_records(&mut self) {
+                        match self {
+                            #(#skip_records_arms,)*
+                        }
+                    }
                 }
             }
             .into()
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
+use openvm_stark_backend::{
+    config::StarkGenericConfig, interaction::InteractionType, keygen::types::MultiStarkProvingKey,
+};
+
+/// One interaction of an AIR on a bus.
+#[derive(Clone, Debug)]
+pub struct BusInteraction {
+    pub air_id: usize,
+    pub air_name: String,
+    /// Number of fields of the message.
+    pub arity: usize,
+}
+
+/// All interactions on a single bus, grouped by side.
+#[derive(Clone, Debug, Default)]
+pub struct BusUsage {
+    pub sends: Vec<BusInteraction>,
+    pub receives: Vec<BusInteraction>,
+}
+
+impl BusUsage {
+    fn arities(side: &[BusInteraction]) -> BTreeSet<usize> {
+        side.iter().map(|i| i.arity).collect()
+    }
+
+    fn air_names<'a>(side: impl IntoIterator<Item = &'a BusInteraction>) -> Vec<String> {
+        side.into_iter()
+            .map(|i| i.air_name.clone())
+            .unique()
+            .collect()
+    }
+
+    fn airs_with_arity(side: &[BusInteraction], arity: usize) -> Vec<String> {
+        Self::air_names(side.iter().filter(|i| i.arity == arity))
+    }
+}
+
+/// Which buses [check_bus_consistency] treats specially. Every other bus must be used on both
+/// sides, with the same message lengths on both sides.
+#[derive(Clone, Debug, Default)]
+pub struct BusLintRules {
+    /// Buses whose receiving AIRs are lookup tables. A table no AIR looks up balances with zero
+    /// multiplicities, so these buses may have receives without sends.
+    pub lookup_buses: Vec<usize>,
+    /// Buses with signed multiplicities, such as the memory bus. Which side an interaction is on
+    /// says nothing about its direction, so every message length must instead be used by at least
+    /// two AIRs.
+    pub signed_buses: Vec<usize>,
+    /// AIRs which may be the only user of a message length on a signed bus, because they are only
+    /// used when another AIR uses the same length. For example the memory access adapters for
+    /// block sizes that no chip accesses.
+    pub optional_air_ids: Vec<usize>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BusLintError {
+    #[error("bus {bus} has sends but no receives (from {airs:?})")]
+    Unreceived { bus: usize, airs: Vec<String> },
+    #[error("bus {bus} has receives but no sends (from {airs:?})")]
+    Unsent { bus: usize, airs: Vec<String> },
+    #[error("bus {bus}: sends with {arity} fields (from {airs:?}) have no receive with the same number of fields")]
+    ArityMismatch {
+        bus: usize,
+        arity: usize,
+        airs: Vec<String>,
+    },
+    #[error("bus {bus}: receives with {arity} fields (from {airs:?}) have no send with the same number of fields")]
+    UnsentArity {
+        bus: usize,
+        arity: usize,
+        airs: Vec<String>,
+    },
+    #[error("bus {bus}: messages with {arity} fields are only used by {air}")]
+    UnpairedArity {
+        bus: usize,
+        arity: usize,
+        air: String,
+    },
+}
+
+/// Tallies the interactions of every AIR in `pk` per bus index.
+pub fn bus_usage<SC: StarkGenericConfig>(
+    pk: &MultiStarkProvingKey<SC>,
+) -> BTreeMap<usize, BusUsage> {
+    let mut usage = BTreeMap::<usize, BusUsage>::new();
+    for (air_id, air_pk) in pk.per_air.iter().enumerate() {
+        for interaction in &air_pk.vk.symbolic_constraints.interactions {
+            let bus = usage.entry(interaction.bus_index).or_default();
+            let entry = BusInteraction {
+                air_id,
+                air_name: air_pk.air_name.clone(),
+                arity: interaction.fields.len(),
+            };
+            match interaction.interaction_type {
+                InteractionType::Send => bus.sends.push(entry),
+                InteractionType::Receive => bus.receives.push(entry),
+            }
+        }
+    }
+    usage
+}
+
+/// Statically checks that the interactions of all AIRs in a VM circuit can balance.
+///
+/// Every bus must have both sends and receives, and every message length (number of fields)
+/// sent on a bus must also be received on it and vice versa. Such mistakes otherwise only
+/// surface at proving time as a non-zero cumulative sum. See [BusLintRules] for the exceptions.
+pub fn check_bus_consistency<SC: StarkGenericConfig>(
+    pk: &MultiStarkProvingKey<SC>,
+    rules: &BusLintRules,
+) -> Result<(), Vec<BusLintError>> {
+    let mut errors = vec![];
+    for (bus, usage) in bus_usage(pk) {
+        if rules.signed_buses.contains(&bus) {
+            check_signed_bus(bus, &usage, rules, &mut errors);
+            continue;
+        }
+        let is_lookup = rules.lookup_buses.contains(&bus);
+        if usage.receives.is_empty() {
+            errors.push(BusLintError::Unreceived {
+                bus,
+                airs: BusUsage::air_names(&usage.sends),
+            });
+            continue;
+        }
+        if usage.sends.is_empty() {
+            if !is_lookup {
+                errors.push(BusLintError::Unsent {
+                    bus,
+                    airs: BusUsage::air_names(&usage.receives),
+                });
+            }
+            continue;
+        }
+        let send_arities = BusUsage::arities(&usage.sends);
+        let receive_arities = BusUsage::arities(&usage.receives);
+        for &arity in send_arities.difference(&receive_arities) {
+            errors.push(BusLintError::ArityMismatch {
+                bus,
+                arity,
+                airs: BusUsage::airs_with_arity(&usage.sends, arity),
+            });
+        }
+        if !is_lookup {
+            for &arity in receive_arities.difference(&send_arities) {
+                errors.push(BusLintError::UnsentArity {
+                    bus,
+                    arity,
+                    airs: BusUsage::airs_with_arity(&usage.receives, arity),
+                });
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn check_signed_bus(
+    bus: usize,
+    usage: &BusUsage,
+    rules: &BusLintRules,
+    errors: &mut Vec<BusLintError>,
+) {
+    let mut airs_per_arity = BTreeMap::<usize, BTreeMap<usize, &str>>::new();
+    for interaction in usage.sends.iter().chain(&usage.receives) {
+        airs_per_arity
+            .entry(interaction.arity)
+            .or_default()
+            .insert(interaction.air_id, &interaction.air_name);
+    }
+    for (arity, airs) in airs_per_arity {
+        if let Ok((&air_id, &air)) = airs.iter().exactly_one() {
+            if !rules.optional_air_ids.contains(&air_id) {
+                errors.push(BusLintError::UnpairedArity {
+                    bus,
+                    arity,
+                    air: air.to_string(),
+                });
+            }
+        }
+    }
+}
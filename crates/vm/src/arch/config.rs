@@ -1,8 +1,11 @@
 use derive_new::new;
 use openvm_circuit::system::memory::MemoryTraceHeights;
-use openvm_instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES;
+use openvm_instructions::{
+    program::{Program, DEFAULT_MAX_NUM_PUBLIC_VALUES},
+    SystemOpcode, VmOpcode,
+};
 use openvm_poseidon2_air::poseidon2::Poseidon2Config;
-use openvm_stark_backend::{p3_field::PrimeField32, ChipUsageGetter};
+use openvm_stark_backend::{p3_field::PrimeField32, p3_util::log2_strict_usize, ChipUsageGetter};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 // TODO[jpw]: re-exporting hardcoded bus constants for tests. Import paths should be
@@ -13,10 +16,10 @@ pub use super::testing::{
     POSEIDON2_DIRECT_BUS, RANGE_TUPLE_CHECKER_BUS, READ_INSTRUCTION_BUS,
 };
 use super::{
-    AnyEnum, InstructionExecutor, SystemComplex, SystemExecutor, SystemPeriphery, VmChipComplex,
-    VmInventoryError, PUBLIC_VALUES_AIR_ID,
+    AnyEnum, InstructionExecutor, OpcodeMapEntry, SystemComplex, SystemExecutor, SystemPeriphery,
+    VmChipComplex, VmInventoryError, PUBLIC_VALUES_AIR_ID,
 };
-use crate::system::memory::BOUNDARY_AIR_OFFSET;
+use crate::system::memory::{BOUNDARY_AIR_OFFSET, CHUNK};
 
 const DEFAULT_MAX_SEGMENT_LEN: usize = (1 << 22) - 100;
 // sbox is decomposed to have this max degree for Poseidon2. We set to 3 so quotient_degree = 2
@@ -40,6 +43,69 @@ pub trait VmConfig<F: PrimeField32>: Clone + Serialize + DeserializeOwned {
     fn create_chip_complex(
         &self,
     ) -> Result<VmChipComplex<F, Self::Executor, Self::Periphery>, VmInventoryError>;
+
+    /// Checks the configuration before any chip is created. Configs with extension options
+    /// should extend this to check them as well.
+    fn validate(&self) -> Result<(), VmConfigError> {
+        self.system().validate()
+    }
+
+    /// Parses and validates a configuration, e.g. from a project's config file.
+    fn from_toml(toml: &str) -> Result<Self, VmConfigError> {
+        let config: Self = toml::from_str(toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn to_toml(&self) -> Result<String, VmConfigError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Builds the chip complex and returns the opcode map of this VM, sorted by opcode.
+    /// Building fails with [`VmInventoryError::ExecutorExists`] if two extensions claim the same
+    /// opcode, so this also checks that the configured opcode offsets do not overlap.
+    /// The map is serializable for use by the transpiler, debugger, and docs tooling.
+    fn opcode_map(&self) -> Result<Vec<OpcodeMapEntry>, VmInventoryError> {
+        Ok(self.create_chip_complex()?.inventory.opcode_map::<F>())
+    }
+
+    /// Checks that every opcode used by `program` has an executor in this VM. Guests can call
+    /// the intrinsics of any extension they link, so this catches a guest using an extension the
+    /// VM does not enable when the program is transpiled, instead of partway through execution.
+    /// Phantom sub-executors, i.e. hints, are not checked.
+    fn check_program(&self, program: &Program<F>) -> Result<(), VmConfigError> {
+        let inventory = self.create_chip_complex()?.inventory;
+        let terminate = VmOpcode::with_default_offset(SystemOpcode::TERMINATE);
+        let mut unsupported = program
+            .instructions()
+            .into_iter()
+            .map(|instruction| instruction.opcode)
+            .filter(|&opcode| opcode != terminate && inventory.get_executor(opcode).is_none())
+            .map(|opcode| opcode.as_usize())
+            .collect::<Vec<_>>();
+        if unsupported.is_empty() {
+            return Ok(());
+        }
+        unsupported.sort_unstable();
+        unsupported.dedup();
+        Err(VmConfigError::UnsupportedOpcodes(unsupported))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VmConfigError {
+    #[error("failed to parse config: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("failed to serialize config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("invalid config: {0}")]
+    Invalid(String),
+    #[error("failed to build the VM: {0}")]
+    Inventory(#[from] VmInventoryError),
+    #[error("program uses opcodes {0:?} that no extension of the VM handles")]
+    UnsupportedOpcodes(Vec<usize>),
+    #[error("guest program does not match the config:\n  {}", .0.join("\n  "))]
+    GuestMismatch(Vec<String>),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, new, Copy)]
@@ -54,6 +120,35 @@ pub struct MemoryConfig {
     pub decomp: usize,
     /// Maximum N AccessAdapter AIR to support.
     pub max_access_adapter_n: usize,
+    /// Number of children of a node of the memory Merkle tree in continuation mode: 2, 4 or 8.
+    /// The tree has the same root for every arity, see
+    /// [MemoryMerkleAir](crate::system::memory::merkle::MemoryMerkleAir).
+    #[new(value = "2")]
+    #[serde(default = "default_merkle_arity")]
+    pub merkle_arity: usize,
+    /// Hash function of the memory Merkle tree in continuation mode.
+    #[new(default)]
+    #[serde(default)]
+    pub merkle_hasher: MemoryMerkleHasher,
+}
+
+/// Hash function of the memory Merkle tree, which compresses two `CHUNK` element children into
+/// their parent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemoryMerkleHasher {
+    /// Width-16 Poseidon2, proven by the system's Poseidon2 chip. This is the hasher the
+    /// aggregation program supports.
+    #[default]
+    Poseidon2,
+    /// Keccak-256, proven by a chip the Keccak-256 extension adds to the VM. The children are
+    /// hashed as the little-endian bytes of their canonical elements, and the element `i` of the
+    /// parent is the `i`-th little-endian `u32` word of the digest reduced modulo the field
+    /// characteristic, so that a root can be recomputed on the EVM.
+    Keccak,
+}
+
+fn default_merkle_arity() -> usize {
+    2
 }
 
 impl Default for MemoryConfig {
@@ -89,6 +184,22 @@ pub struct SystemConfig {
     /// Whether to collect metrics.
     /// **Warning**: this slows down the runtime.
     pub collect_metrics: bool,
+    /// Whether to reconstruct the guest call graph during execution, see
+    /// [CallGraphProfiler](crate::metrics::call_graph::CallGraphProfiler). Needs the function
+    /// bounds of the executable, which the transpiler only extracts with the `function-span`
+    /// feature. Does not affect constraints.
+    #[serde(default)]
+    pub profile_call_graph: bool,
+    /// Whether extensions consuming hints bind them to a running digest exposed as public values
+    /// of their digest AIR, so that a proof commits to the non-determinism it used. Off by
+    /// default, in which case hints are not bound. Only supported in single segment mode.
+    #[serde(default)]
+    pub hint_digest: bool,
+    /// Whether executors which support it chain consecutive rows executing a straight-line block
+    /// of instructions, so that a block only interacts with the execution bus when it is entered
+    /// and when it is left. Off by default, in which case every instruction does.
+    #[serde(default)]
+    pub basic_block_execution: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -110,6 +221,9 @@ impl SystemConfig {
             num_public_values,
             max_segment_len: DEFAULT_MAX_SEGMENT_LEN,
             collect_metrics: false,
+            profile_call_graph: false,
+            hint_digest: false,
+            basic_block_execution: false,
         }
     }
 
@@ -148,10 +262,82 @@ impl SystemConfig {
         self
     }
 
+    pub fn with_call_graph_profiling(mut self) -> Self {
+        self.profile_call_graph = true;
+        self
+    }
+
+    pub fn with_hint_digest(mut self) -> Self {
+        self.hint_digest = true;
+        self
+    }
+
+    pub fn with_basic_block_execution(mut self) -> Self {
+        self.basic_block_execution = true;
+        self
+    }
+
     pub fn has_public_values_chip(&self) -> bool {
         !self.continuation_enabled && self.num_public_values > 0
     }
 
+    pub fn validate(&self) -> Result<(), VmConfigError> {
+        let memory = &self.memory_config;
+        if memory.as_offset != 1 {
+            return Err(VmConfigError::Invalid(format!(
+                "memory_config.as_offset must be 1, got {}",
+                memory.as_offset
+            )));
+        }
+        if memory.pointer_max_bits < log2_strict_usize(CHUNK) {
+            return Err(VmConfigError::Invalid(format!(
+                "memory_config.pointer_max_bits must be at least {}, got {}",
+                log2_strict_usize(CHUNK),
+                memory.pointer_max_bits
+            )));
+        }
+        if memory.decomp == 0 {
+            return Err(VmConfigError::Invalid(
+                "memory_config.decomp must be positive".to_string(),
+            ));
+        }
+        if !memory.max_access_adapter_n.is_power_of_two() || memory.max_access_adapter_n > 64 {
+            return Err(VmConfigError::Invalid(format!(
+                "memory_config.max_access_adapter_n must be a power of two of at most 64, got {}",
+                memory.max_access_adapter_n
+            )));
+        }
+        if !matches!(memory.merkle_arity, 2 | 4 | 8) {
+            return Err(VmConfigError::Invalid(format!(
+                "memory_config.merkle_arity must be 2, 4 or 8, got {}",
+                memory.merkle_arity
+            )));
+        }
+        let log_arity = log2_strict_usize(memory.merkle_arity);
+        let dimensions = memory.memory_dimensions();
+        if dimensions.as_height % log_arity != 0 || dimensions.address_height % log_arity != 0 {
+            return Err(VmConfigError::Invalid(format!(
+                "memory_config.as_height ({}) and memory_config.pointer_max_bits - {} ({}) must be \
+                 multiples of {log_arity} for a Merkle arity of {}",
+                dimensions.as_height,
+                log2_strict_usize(CHUNK),
+                dimensions.address_height,
+                memory.merkle_arity
+            )));
+        }
+        if self.continuation_enabled && self.max_segment_len == 0 {
+            return Err(VmConfigError::Invalid(
+                "max_segment_len must be positive when continuations are enabled".to_string(),
+            ));
+        }
+        if self.continuation_enabled && self.hint_digest {
+            return Err(VmConfigError::Invalid(
+                "hint_digest is not supported when continuations are enabled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Returns the AIR ID of the memory boundary AIR. Panic if the boundary AIR is not enabled.
     pub fn memory_boundary_air_id(&self) -> usize {
         let mut ret = PUBLIC_VALUES_AIR_ID;
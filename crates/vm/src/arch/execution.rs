@@ -30,10 +30,23 @@ pub enum ExecutionError {
         pc_base: u32,
         program_len: usize,
     },
+    #[error("pc {pc} is not a multiple of step = {step} from pc_base {pc_base}")]
+    PcMisaligned { pc: u32, step: u32, pc_base: u32 },
+    #[error("instruction at pc {from_pc} jumped to {to_pc}, which is not an instruction of the program in [{pc_base}, {pc_end})")]
+    JumpOutsideProgram {
+        from_pc: u32,
+        to_pc: u32,
+        pc_base: u32,
+        pc_end: u32,
+    },
     #[error("at pc {pc}, opcode {opcode} was not enabled")]
     DisabledOperation { pc: u32, opcode: VmOpcode },
     #[error("at pc = {pc}")]
     HintOutOfBounds { pc: u32 },
+    #[error("at pc {pc}, opcode {opcode} was executed before the setup of its chip")]
+    SetupNotDone { pc: u32, opcode: VmOpcode },
+    #[error("at pc {pc}, setup opcode {opcode} was given a modulus that doesn't match the chip's")]
+    SetupModulusMismatch { pc: u32, opcode: VmOpcode },
     #[error("at pc {pc}, tried to publish into index {public_value_index} when num_public_values = {num_public_values}")]
     PublicValueIndexOutOfBounds {
         pc: u32,
@@ -52,6 +65,8 @@ pub enum ExecutionError {
         pc: u32,
         discriminant: PhantomDiscriminant,
     },
+    #[error("initial memory does not match the given initial memory root")]
+    InitialMemoryRootMismatch,
     #[error("at pc {pc}, discriminant {}, phantom error: {inner}", .discriminant.0)]
     Phantom {
         pc: u32,
@@ -72,6 +87,16 @@ pub trait InstructionExecutor<F> {
     /// For display purposes. From absolute opcode as `usize`, return the string name of the opcode
     /// if it is a supported opcode by the present executor.
     fn get_opcode_name(&self, opcode: usize) -> String;
+
+    /// Hint that about `additional` more instructions will be executed by this executor in the
+    /// current segment, so that record storage can be allocated up front instead of growing
+    /// while executing. The default implementation does nothing.
+    fn reserve_records(&mut self, _additional: usize) {}
+
+    /// Stop keeping records of further executions, for execution that is never proven. The trace
+    /// height still counts the skipped executions, but the chip can no longer generate its trace.
+    /// The default implementation keeps the records.
+    fn skip_records(&mut self) {}
 }
 
 impl<F, C: InstructionExecutor<F>> InstructionExecutor<F> for RefCell<C> {
@@ -86,6 +111,14 @@ impl<F, C: InstructionExecutor<F>> InstructionExecutor<F> for RefCell<C> {
     fn get_opcode_name(&self, opcode: usize) -> String {
         self.borrow().get_opcode_name(opcode)
     }
+
+    fn reserve_records(&mut self, additional: usize) {
+        self.borrow_mut().reserve_records(additional);
+    }
+
+    fn skip_records(&mut self) {
+        self.borrow_mut().skip_records();
+    }
 }
 
 impl<F, C: InstructionExecutor<F>> InstructionExecutor<F> for Rc<RefCell<C>> {
@@ -100,6 +133,14 @@ impl<F, C: InstructionExecutor<F>> InstructionExecutor<F> for Rc<RefCell<C>> {
     fn get_opcode_name(&self, opcode: usize) -> String {
         self.borrow().get_opcode_name(opcode)
     }
+
+    fn reserve_records(&mut self, additional: usize) {
+        self.borrow_mut().reserve_records(additional);
+    }
+
+    fn skip_records(&mut self) {
+        self.borrow_mut().skip_records();
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Default, AlignedBorrow)]
@@ -184,15 +225,33 @@ impl ExecutionBus {
         next_state: ExecutionState<impl Into<AB::Expr>>,
     ) {
         let multiplicity = multiplicity.into();
+        self.execute_with_multiplicities(
+            builder,
+            multiplicity.clone(),
+            multiplicity,
+            prev_state,
+            next_state,
+        );
+    }
+
+    /// Receives `prev_state` and sends `next_state` with separate multiplicities.
+    pub fn execute_with_multiplicities<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        receive_multiplicity: impl Into<AB::Expr>,
+        send_multiplicity: impl Into<AB::Expr>,
+        prev_state: ExecutionState<impl Into<AB::Expr>>,
+        next_state: ExecutionState<impl Into<AB::Expr>>,
+    ) {
         builder.push_receive(
             self.0,
             [prev_state.pc.into(), prev_state.timestamp.into()],
-            multiplicity.clone(),
+            receive_multiplicity,
         );
         builder.push_send(
             self.0,
             [next_state.pc.into(), next_state.timestamp.into()],
-            multiplicity,
+            send_multiplicity,
         );
     }
 }
@@ -272,6 +331,44 @@ impl<AB: InteractionBuilder> ExecutionBridgeInteractor<AB> {
         self.execution_bus
             .execute(builder, multiplicity, self.from_state, self.to_state);
     }
+
+    /// Like [Self::eval], for an instruction of a straight-line block executed by consecutive
+    /// rows of the same AIR. The instruction is still looked up on the program bus, but the
+    /// execution bus only receives `from_state` if `continues_block` is zero and only sends
+    /// `to_state` if `next_continues_block` is zero, so a block of `n` instructions has two
+    /// execution bus interactions instead of `2n`.
+    ///
+    /// The caller must constrain that `continues_block` is boolean and zero on padding rows, and
+    /// that the `from_state` of a row continuing the block is the `to_state` of the row before.
+    pub fn eval_in_block(
+        self,
+        builder: &mut AB,
+        multiplicity: impl Into<AB::Expr>,
+        continues_block: impl Into<AB::Expr>,
+        next_continues_block: impl Into<AB::Expr>,
+    ) {
+        let multiplicity = multiplicity.into();
+
+        self.program_bus.send_instruction(
+            builder,
+            self.from_state.pc.clone(),
+            self.opcode,
+            self.operands,
+            multiplicity.clone(),
+        );
+
+        self.execution_bus.execute_with_multiplicities(
+            builder,
+            multiplicity.clone() - continues_block.into(),
+            multiplicity - next_continues_block.into(),
+            self.from_state,
+            self.to_state,
+        );
+    }
+
+    pub fn to_state(&self) -> &ExecutionState<AB::Expr> {
+        &self.to_state
+    }
 }
 
 impl<T: AbstractField> From<(u32, Option<T>)> for PcIncOrSet<T> {
@@ -2,8 +2,10 @@ use std::{any::Any, cell::RefCell, iter::once, rc::Rc, sync::Arc};
 
 use derive_more::derive::From;
 use getset::Getters;
+use itertools::Itertools;
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
 use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
     utils::next_power_of_two_or_zero,
     var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
 };
@@ -15,6 +17,7 @@ use openvm_instructions::{
 use openvm_poseidon2_air::poseidon2::air::SBOX_DEGREE;
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
+    keygen::types::MultiStarkProvingKey,
     p3_commit::PolynomialSpace,
     p3_field::{AbstractField, PrimeField32},
     p3_matrix::Matrix,
@@ -27,8 +30,9 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    vm_poseidon2_config, ExecutionBus, InstructionExecutor, PhantomSubExecutor, Streams,
-    SystemConfig, SystemTraceHeights,
+    check_bus_consistency, vm_poseidon2_config, BusLintError, BusLintRules, ExecutionBus,
+    InstructionExecutor, MemoryMerkleHasher, PhantomSubExecutor, Streams, SystemConfig,
+    SystemTraceHeights,
 };
 use crate::system::{
     connector::VmConnectorChip,
@@ -68,7 +72,13 @@ const RANGE_CHECKER_BUS: usize = 3;
 ///
 /// There are two associated types:
 /// - `Executor`: enum for chips that are [`InstructionExecutor`]s.
-/// -
+/// - `Periphery`: enum for chips that are not, such as lookup tables shared between executors.
+///
+/// Extensions are plugged in statically: a downstream crate implements this trait for its own
+/// chips and adds it as an `#[extension]` field of a config deriving `VmConfig`, which claims
+/// the extension's opcodes and buses when the chip complex is built. There is no list of boxed
+/// extensions because the executor and periphery enums must implement `Chip<SC>` for every
+/// `StarkGenericConfig`, which cannot be expressed by a trait object.
 pub trait VmExtension<F: PrimeField32> {
     /// Enum of chips that implement [`InstructionExecutor`] for instruction execution.
     /// `Executor` **must** implement `Chip<SC>` but the trait bound is omitted to omit the
@@ -100,6 +110,8 @@ pub struct VmInventoryBuilder<'a, F: PrimeField32> {
     streams: &'a Arc<Mutex<Streams<F>>>,
     /// Bus indices are in range [0, bus_idx_max)
     bus_idx_max: usize,
+    /// Buses allocated by [Self::new_lookup_bus_idx].
+    lookup_buses: Vec<usize>,
     /// Chips that are already included in the chipset and may be used
     /// as dependencies. The order should be that depended-on chips are ordered
     /// **before** their dependents.
@@ -118,6 +130,7 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
             system,
             streams,
             bus_idx_max,
+            lookup_buses: Vec::new(),
             chips: Vec::new(),
         }
     }
@@ -148,6 +161,14 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
         idx
     }
 
+    /// Allocates a new bus for a lookup table. Unlike other buses, a lookup bus may have no sends
+    /// when no chip of the VM uses the table, see [BusLintRules::lookup_buses].
+    pub fn new_lookup_bus_idx(&mut self) -> usize {
+        let idx = self.new_bus_idx();
+        self.lookup_buses.push(idx);
+        idx
+    }
+
     /// Looks through built chips to see if there exists any of type `C` by downcasting.
     /// Returns all chips of type `C` in the chipset.
     ///
@@ -159,6 +180,50 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
             .collect()
     }
 
+    /// Returns the chip of type `C` shared by all extensions: the one already in the chipset or in
+    /// `inventory` if there is one, and otherwise the chip returned by `create`, which is added
+    /// as a periphery chip of `inventory`.
+    ///
+    /// Note: the type `C` will usually be a smart pointer to a chip.
+    pub fn find_or_add_periphery_chip<C, E, P>(
+        &mut self,
+        inventory: &mut VmInventory<E, P>,
+        create: impl FnOnce(&mut Self) -> C,
+    ) -> C
+    where
+        C: Clone + 'static,
+        P: AnyEnum + From<C>,
+    {
+        let existing = self.find_chip::<C>().first().copied().or_else(|| {
+            inventory
+                .periphery
+                .iter()
+                .find_map(|chip| chip.as_any_kind().downcast_ref())
+        });
+        if let Some(chip) = existing {
+            return chip.clone();
+        }
+        let chip = create(self);
+        inventory.add_periphery_chip(chip.clone());
+        chip
+    }
+
+    /// Returns the 8-bit [BitwiseOperationLookupChip] shared by all extensions, so that their
+    /// bitwise lookups use a single bus and table. It is created on a new bus by the first
+    /// extension which needs it.
+    pub fn shared_bitwise_lookup_chip<E, P>(
+        &mut self,
+        inventory: &mut VmInventory<E, P>,
+    ) -> Arc<BitwiseOperationLookupChip<8>>
+    where
+        P: AnyEnum + From<Arc<BitwiseOperationLookupChip<8>>>,
+    {
+        self.find_or_add_periphery_chip(inventory, |builder| {
+            let bus = BitwiseOperationLookupBus::new(builder.new_lookup_bus_idx());
+            Arc::new(BitwiseOperationLookupChip::new(bus))
+        })
+    }
+
     /// The generic `F` must match that of the `PhantomChip<F>`.
     pub fn add_phantom_sub_executor<PE: PhantomSubExecutor<F> + 'static>(
         &self,
@@ -209,6 +274,16 @@ pub struct VmComplexTraceHeights {
 
 type ExecutorId = usize;
 
+/// One entry of the opcode map of a VM: a global opcode, its display name, and the executor that
+/// owns it. The map is collected from the opcodes claimed by each extension when the chip complex
+/// is built, so it reflects the actual offsets in use rather than the default constants.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpcodeMapEntry {
+    pub opcode: usize,
+    pub name: String,
+    pub executor_id: ExecutorId,
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChipId {
     Executor(usize),
@@ -223,6 +298,8 @@ pub enum VmInventoryError {
     PhantomSubExecutorExists { discriminant: PhantomDiscriminant },
     #[error("Chip {name} not found")]
     ChipNotFound { name: String },
+    #[error("Inconsistent bus interactions: {}", .0.iter().join("; "))]
+    InconsistentBuses(Vec<BusLintError>),
 }
 
 impl<E, P> Default for VmInventory<E, P> {
@@ -302,6 +379,26 @@ impl<E, P> VmInventory<E, P> {
         Ok(())
     }
 
+    /// Returns every claimed opcode with its name and owning executor, sorted by opcode.
+    /// Overlapping claims are rejected by [`add_executor`](Self::add_executor) and
+    /// [`append`](Self::append), so each opcode appears exactly once.
+    pub fn opcode_map<F>(&self) -> Vec<OpcodeMapEntry>
+    where
+        E: InstructionExecutor<F>,
+    {
+        let mut map = self
+            .instruction_lookup
+            .iter()
+            .map(|(opcode, &executor_id)| OpcodeMapEntry {
+                opcode: opcode.as_usize(),
+                name: self.executors[executor_id].get_opcode_name(opcode.as_usize()),
+                executor_id,
+            })
+            .collect::<Vec<_>>();
+        map.sort_by_key(|entry| entry.opcode);
+        map
+    }
+
     pub fn add_periphery_chip(&mut self, periphery_chip: impl Into<P>) {
         let id = self.periphery.len();
         self.periphery.push(periphery_chip.into());
@@ -355,6 +452,31 @@ impl<E, P> VmInventory<E, P> {
         }
     }
 
+    /// Reserve record storage in each executor for its predicted trace height, usually taken from
+    /// a previous segment. Heights of periphery chips are ignored.
+    pub fn reserve_executor_records<F>(&mut self, predicted_heights: &VmInventoryTraceHeights)
+    where
+        E: InstructionExecutor<F>,
+    {
+        for (chip_id, &height) in predicted_heights.chips.iter() {
+            if let ChipId::Executor(id) = *chip_id {
+                if let Some(executor) = self.executors.get_mut(id) {
+                    executor.reserve_records(height);
+                }
+            }
+        }
+    }
+
+    /// Make every executor stop keeping records, see [InstructionExecutor::skip_records].
+    pub fn skip_executor_records<F>(&mut self)
+    where
+        E: InstructionExecutor<F>,
+    {
+        for executor in self.executors.iter_mut() {
+            executor.skip_records();
+        }
+    }
+
     /// Return the dummy trace heights of the inventory. This is used for generating a dummy proof.
     /// Regular users should not need this.
     pub fn get_dummy_trace_heights(&self) -> VmInventoryTraceHeights
@@ -421,13 +543,15 @@ pub struct VmChipComplex<F: PrimeField32, E, P> {
     /// System ensures it contains:
     /// - PhantomChip
     /// - PublicValuesChip if continuations disabled
-    /// - Poseidon2Chip if continuations enabled
+    /// - Poseidon2Chip if continuations enabled with the Poseidon2 memory Merkle hasher
     pub inventory: VmInventory<E, P>,
     overridden_inventory_heights: Option<VmInventoryTraceHeights>,
 
     streams: Arc<Mutex<Streams<F>>>,
     /// System buses use indices [0, bus_idx_max)
     bus_idx_max: usize,
+    /// Buses of lookup tables, see [BusLintRules::lookup_buses].
+    lookup_buses: Vec<usize>,
 }
 
 /// The base [VmChipComplex] with only system chips.
@@ -543,7 +667,11 @@ impl<F: PrimeField32> SystemComplex<F> {
                 )
                 .unwrap();
         }
-        if config.continuation_enabled {
+        // With another memory Merkle hasher, an extension adds the chip receiving the compressions
+        // and sets the hasher of the memory controller.
+        if config.continuation_enabled
+            && config.memory_config.merkle_hasher == MemoryMerkleHasher::Poseidon2
+        {
             assert_eq!(inventory.periphery().len(), Self::POSEIDON2_PERIPHERY_IDX);
             // Add direct poseidon2 chip for persistent memory.
             // This is **not** an instruction executor.
@@ -592,6 +720,7 @@ impl<F: PrimeField32> SystemComplex<F> {
             base,
             inventory,
             bus_idx_max,
+            lookup_buses: vec![RANGE_CHECKER_BUS],
             streams,
             overridden_inventory_heights: None,
         }
@@ -640,6 +769,7 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         let mut builder = self.inventory_builder();
         let inventory_ext = config.build(&mut builder)?;
         self.bus_idx_max = builder.bus_idx_max;
+        self.lookup_buses.extend(builder.lookup_buses);
         let mut ext_complex = self.transmute();
         ext_complex.append(inventory_ext.transmute())?;
         Ok(ext_complex)
@@ -655,6 +785,7 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             base: self.base,
             inventory: self.inventory.transmute(),
             bus_idx_max: self.bus_idx_max,
+            lookup_buses: self.lookup_buses,
             streams: self.streams,
             overridden_inventory_heights: self.overridden_inventory_heights,
         }
@@ -916,6 +1047,33 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             .collect()
     }
 
+    /// The rules to [check_bus_consistency] the AIRs of this chip complex with: the buses of
+    /// lookup tables, and the memory bus, whose access adapters are only needed for the block
+    /// sizes other chips use.
+    pub fn bus_lint_rules(&self) -> BusLintRules {
+        let memory_controller = self.memory_controller().borrow();
+        let memory_air_ids_end = PUBLIC_VALUES_AIR_ID
+            + self.public_values_chip_idx().is_some() as usize
+            + memory_controller.num_airs();
+        let adapter_air_ids_start = memory_air_ids_end - memory_controller.num_access_adapters();
+        BusLintRules {
+            lookup_buses: self.lookup_buses.clone(),
+            signed_buses: vec![self.base.memory_bus().0],
+            optional_air_ids: (adapter_air_ids_start..memory_air_ids_end).collect(),
+        }
+    }
+
+    /// Checks that the interactions of the AIRs in `pk`, generated from this chip complex, can
+    /// balance.
+    /// Errors name the bus and the AIRs using it.
+    pub fn check_bus_consistency<SC: StarkGenericConfig>(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+    ) -> Result<(), VmInventoryError> {
+        check_bus_consistency(pk, &self.bus_lint_rules())
+            .map_err(VmInventoryError::InconsistentBuses)
+    }
+
     pub(crate) fn generate_proof_input<SC: StarkGenericConfig>(
         mut self,
         cached_program: Option<CommittedTraceData<SC>>,
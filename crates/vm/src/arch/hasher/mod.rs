@@ -2,15 +2,23 @@ pub mod poseidon2;
 
 use openvm_stark_backend::p3_field::Field;
 
-pub trait Hasher<const CHUNK: usize, F: Field> {
+// The traits do not bound `F` so that structs which are not bounded by `Field` can hold a
+// `dyn HasherChip`, see `MemoryController::set_memory_hasher`.
+pub trait Hasher<const CHUNK: usize, F> {
     /// Statelessly compresses two chunks of data into a single chunk.
     fn compress(&self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK];
-    fn hash(&self, values: &[F; CHUNK]) -> [F; CHUNK] {
+    fn hash(&self, values: &[F; CHUNK]) -> [F; CHUNK]
+    where
+        F: Field,
+    {
         self.compress(values, &[F::ZERO; CHUNK])
     }
     /// Chunk a list of fields. Use chunks as leaves to computes the root of the Merkle tree.
     /// Assumption: the number of public values is a power of two * CHUNK.
-    fn merkle_root(&self, values: &[F]) -> [F; CHUNK] {
+    fn merkle_root(&self, values: &[F]) -> [F; CHUNK]
+    where
+        F: Field,
+    {
         let mut leaves: Vec<_> = chunk_public_values(values)
             .into_iter()
             .map(|c| self.hash(&c))
@@ -24,14 +32,29 @@ pub trait Hasher<const CHUNK: usize, F: Field> {
         leaves[0]
     }
 }
-pub trait HasherChip<const CHUNK: usize, F: Field>: Hasher<CHUNK, F> {
+pub trait HasherChip<const CHUNK: usize, F>: Hasher<CHUNK, F> {
     /// Stateful version of `hash` for recording the event in the chip.
     fn compress_and_record(&mut self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK];
-    fn hash_and_record(&mut self, values: &[F; CHUNK]) -> [F; CHUNK] {
+    fn hash_and_record(&mut self, values: &[F; CHUNK]) -> [F; CHUNK]
+    where
+        F: Field,
+    {
         self.compress_and_record(values, &[F::ZERO; CHUNK])
     }
 }
 
+impl<const CHUNK: usize, F, H: Hasher<CHUNK, F> + ?Sized> Hasher<CHUNK, F> for Box<H> {
+    fn compress(&self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK] {
+        (**self).compress(left, right)
+    }
+}
+
+impl<const CHUNK: usize, F, H: HasherChip<CHUNK, F> + ?Sized> HasherChip<CHUNK, F> for Box<H> {
+    fn compress_and_record(&mut self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK] {
+        (**self).compress_and_record(left, right)
+    }
+}
+
 fn chunk_public_values<const CHUNK: usize, F: Field>(public_values: &[F]) -> Vec<[F; CHUNK]> {
     public_values
         .chunks_exact(CHUNK)
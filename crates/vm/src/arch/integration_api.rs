@@ -11,13 +11,12 @@ use openvm_stark_backend::{
     p3_air::{Air, AirBuilder, BaseAir},
     p3_field::{AbstractField, PrimeField32},
     p3_matrix::{dense::RowMajorMatrix, Matrix},
-    p3_maybe_rayon::prelude::*,
     prover::types::AirProofInput,
     rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
     Chip, ChipUsageGetter,
 };
 
-use super::{ExecutionState, InstructionExecutor, Result};
+use super::{ExecutionState, InstructionExecutor, RecordArena, Result};
 use crate::system::memory::{MemoryAuxColsFactory, MemoryController, MemoryControllerRef};
 
 /// The interface between primitive AIR and machine adapter AIR.
@@ -183,10 +182,53 @@ pub struct AdapterAirContext<T, I: VmAdapterInterface<T>> {
 pub struct VmChipWrapper<F, A: VmAdapterChip<F>, C: VmCoreChip<F, A::Interface>> {
     pub adapter: A,
     pub core: C,
-    pub records: Vec<(A::ReadRecord, A::WriteRecord, C::Record)>,
+    /// One record per execution, in execution order, stored as a struct of arrays of the adapter
+    /// and core records.
+    pub records: VmChipRecords<A::ReadRecord, A::WriteRecord, C::Record>,
+    /// Number of executions not kept in `records`, see [InstructionExecutor::skip_records].
+    /// `None` while records are kept.
+    num_skipped_records: Option<usize>,
     memory: MemoryControllerRef<F>,
 }
 
+/// Records of a [VmChipWrapper]. The adapter and core records are kept in separate
+/// [RecordArena]s, so storing them never moves the records of earlier executions, and each pass of
+/// trace generation only reads the records it needs.
+#[derive(Debug)]
+pub struct VmChipRecords<R, W, C> {
+    pub adapter_records: RecordArena<(R, W)>,
+    pub core_records: RecordArena<C>,
+}
+
+impl<R, W, C> Default for VmChipRecords<R, W, C> {
+    fn default() -> Self {
+        Self {
+            adapter_records: RecordArena::new(),
+            core_records: RecordArena::new(),
+        }
+    }
+}
+
+impl<R, W, C> VmChipRecords<R, W, C> {
+    pub fn len(&self) -> usize {
+        self.core_records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.core_records.is_empty()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.adapter_records.reserve(additional);
+        self.core_records.reserve(additional);
+    }
+
+    pub fn push(&mut self, (read_record, write_record, core_record): (R, W, C)) {
+        self.adapter_records.push((read_record, write_record));
+        self.core_records.push(core_record);
+    }
+}
+
 impl<F, A, C> VmChipWrapper<F, A, C>
 where
     A: VmAdapterChip<F>,
@@ -196,7 +238,8 @@ where
         Self {
             adapter,
             core,
-            records: vec![],
+            records: VmChipRecords::default(),
+            num_skipped_records: None,
             memory,
         }
     }
@@ -225,13 +268,26 @@ where
             output,
             &read_record,
         )?;
-        self.records.push((read_record, write_record, core_record));
+        match &mut self.num_skipped_records {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.records.push((read_record, write_record, core_record)),
+        }
         Ok(to_state)
     }
 
     fn get_opcode_name(&self, opcode: usize) -> String {
         self.core.get_opcode_name(opcode)
     }
+
+    fn reserve_records(&mut self, additional: usize) {
+        if self.num_skipped_records.is_none() {
+            self.records.reserve(additional);
+        }
+    }
+
+    fn skip_records(&mut self) {
+        self.num_skipped_records.get_or_insert(0);
+    }
 }
 
 // Note[jpw]: the statement we want is:
@@ -273,6 +329,10 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        assert!(
+            self.num_skipped_records.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
         let air = self.air();
         let num_records = self.records.len();
         let height = next_power_of_two_or_zero(num_records);
@@ -282,21 +342,28 @@ where
         let mut values = Val::<SC>::zero_vec(height * width);
 
         let memory_aux_cols_factory = RefCell::borrow(&self.memory).aux_cols_factory();
-        // This zip only goes through records.
+        let VmChipRecords {
+            adapter_records,
+            core_records,
+        } = self.records;
+        // These only go through records.
         // The padding rows between records.len()..height are filled with zeros.
-        values
-            .par_chunks_mut(width)
-            .zip(self.records.into_par_iter())
-            .for_each(|(row_slice, record)| {
-                let (adapter_row, core_row) = row_slice.split_at_mut(adapter_width);
+        adapter_records.par_fill_rows(
+            &mut values,
+            width,
+            |row_slice, (read_record, write_record)| {
                 self.adapter.generate_trace_row(
-                    adapter_row,
-                    record.0,
-                    record.1,
+                    &mut row_slice[..adapter_width],
+                    read_record,
+                    write_record,
                     &memory_aux_cols_factory,
                 );
-                self.core.generate_trace_row(core_row, record.2);
-            });
+            },
+        );
+        core_records.par_fill_rows(&mut values, width, |row_slice, record| {
+            self.core
+                .generate_trace_row(&mut row_slice[adapter_width..], record);
+        });
 
         let mut trace = RowMajorMatrix::new(values, width);
         self.core.finalize(&mut trace, num_records);
@@ -318,7 +385,7 @@ where
         )
     }
     fn current_trace_height(&self) -> usize {
-        self.records.len()
+        self.records.len() + self.num_skipped_records.unwrap_or(0)
     }
     fn trace_width(&self) -> usize {
         self.adapter.air().width() + self.core.air().width()
@@ -1,3 +1,5 @@
+/// Static consistency checks of bus interactions.
+mod bus_lint;
 mod config;
 /// Instruction execution traits and types.
 /// Execution bus and interface.
@@ -6,6 +8,8 @@ mod execution;
 mod extensions;
 /// Traits and wrappers to facilitate VM chip integration
 mod integration_api;
+/// Chunked storage of execution records.
+mod record_arena;
 /// Runtime execution and segmentation
 pub mod segment;
 /// Top level [VirtualMachine] constructor and API.
@@ -18,9 +22,11 @@ pub mod hasher;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod testing;
 
+pub use bus_lint::*;
 pub use config::*;
 pub use execution::*;
 pub use extensions::*;
 pub use integration_api::*;
+pub use record_arena::*;
 pub use segment::*;
 pub use vm::*;
@@ -0,0 +1,148 @@
+use openvm_stark_backend::p3_maybe_rayon::prelude::*;
+
+/// Append-only storage of records in chunks which are allocated once and never grown. Unlike a
+/// `Vec`, pushing never moves the records already stored, so large records are not copied while
+/// executing and the allocated memory overshoots the stored records by at most one chunk.
+#[derive(Debug)]
+pub struct RecordArena<T> {
+    chunks: Vec<Vec<T>>,
+    len: usize,
+}
+
+impl<T> Default for RecordArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RecordArena<T> {
+    /// Capacity of the chunks allocated when no reservation covers a push.
+    pub const CHUNK_CAPACITY: usize = 1 << 10;
+
+    pub fn new() -> Self {
+        Self {
+            chunks: vec![],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Allocates a chunk for at least `additional` more records, unless the last chunk already
+    /// has room for them.
+    pub fn reserve(&mut self, additional: usize) {
+        let available = self
+            .chunks
+            .last()
+            .map_or(0, |chunk| chunk.capacity() - chunk.len());
+        if available < additional {
+            self.chunks.push(Vec::with_capacity(additional));
+        }
+    }
+
+    pub fn push(&mut self, record: T) {
+        let has_room = self
+            .chunks
+            .last()
+            .is_some_and(|chunk| chunk.len() < chunk.capacity());
+        if !has_room {
+            self.chunks.push(Vec::with_capacity(Self::CHUNK_CAPACITY));
+        }
+        self.chunks.last_mut().unwrap().push(record);
+        self.len += 1;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.chunks.iter().flatten()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.chunks.iter().rev().find_map(|chunk| chunk.last())
+    }
+
+    /// Calls `fill_row` on each record with its row, where `rows` is split into rows of
+    /// `row_width` values in record order. The rows after the last record are left as they are.
+    pub fn par_fill_rows<V: Send>(
+        self,
+        rows: &mut [V],
+        row_width: usize,
+        fill_row: impl Fn(&mut [V], T) + Send + Sync,
+    ) where
+        T: Send,
+    {
+        let mut rows = rows;
+        let mut chunk_rows = Vec::with_capacity(self.chunks.len());
+        for chunk in &self.chunks {
+            let (head, tail) = rows.split_at_mut(chunk.len() * row_width);
+            chunk_rows.push(head);
+            rows = tail;
+        }
+        chunk_rows
+            .into_par_iter()
+            .zip(self.chunks.into_par_iter())
+            .for_each(|(rows, chunk)| {
+                rows.par_chunks_mut(row_width)
+                    .zip(chunk.into_par_iter())
+                    .for_each(|(row, record)| fill_row(row, record));
+            });
+    }
+}
+
+impl<T> IntoIterator for RecordArena<T> {
+    type Item = T;
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Vec<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunks.into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordArena;
+
+    #[test]
+    fn test_record_arena_chunks() {
+        let mut arena = RecordArena::new();
+        arena.reserve(3);
+        for i in 0..3 {
+            arena.push(i);
+        }
+        assert_eq!(arena.chunks.len(), 1);
+        // A full chunk is never grown, so the stored records are not moved.
+        let first = arena.iter().next().unwrap() as *const i32;
+        arena.push(3);
+        assert_eq!(arena.chunks.len(), 2);
+        assert_eq!(arena.iter().next().unwrap() as *const i32, first);
+        assert_eq!(
+            arena.chunks[1].capacity(),
+            RecordArena::<i32>::CHUNK_CAPACITY
+        );
+
+        // A reservation which fits in the last chunk allocates nothing.
+        arena.reserve(2);
+        assert_eq!(arena.chunks.len(), 2);
+        assert_eq!(arena.len(), 4);
+        assert_eq!(arena.last(), Some(&3));
+        assert_eq!(arena.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_record_arena_fill_rows() {
+        let mut arena = RecordArena::new();
+        arena.reserve(2);
+        for i in 1..=5u32 {
+            arena.push(i);
+        }
+        // Two rows of padding after the records.
+        let mut rows = vec![0u32; 2 * 7];
+        arena.par_fill_rows(&mut rows, 2, |row, record| row.fill(record));
+        assert_eq!(rows, [1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 0, 0, 0, 0]);
+    }
+}
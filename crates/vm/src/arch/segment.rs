@@ -1,7 +1,14 @@
-use backtrace::Backtrace;
+use std::iter::once;
+
+use backtrace::{Backtrace, SymbolName};
 #[cfg(feature = "function-span")]
 use openvm_instructions::exe::FnBound;
-use openvm_instructions::{exe::FnBounds, instruction::DebugInfo, program::Program};
+use openvm_instructions::{
+    exe::FnBounds,
+    instruction::{call_site, DebugInfo, Instruction},
+    program::Program,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+};
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
@@ -11,13 +18,14 @@ use openvm_stark_backend::{
 };
 
 use super::{
-    AnyEnum, ExecutionError, Streams, SystemConfig, VmChipComplex, VmComplexTraceHeights, VmConfig,
+    AnyEnum, ExecutionError, MemoryMerkleHasher, Streams, SystemConfig, VmChipComplex,
+    VmComplexTraceHeights, VmConfig,
 };
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
 use crate::{
     arch::{instructions::*, ExecutionState, InstructionExecutor},
-    metrics::cycle_tracker::CycleTracker,
+    metrics::{call_graph::CallGraphProfiler, cycle_tracker::CycleTracker},
     system::{
         memory::{Equipartition, CHUNK},
         poseidon2::Poseidon2Chip,
@@ -40,6 +48,8 @@ where
     pub cycle_tracker: CycleTracker,
     #[cfg(feature = "bench-metrics")]
     pub(crate) collected_metrics: VmMetrics,
+    /// Only set when `config.profile_call_graph` is true.
+    pub call_graph: Option<CallGraphProfiler>,
 
     #[allow(dead_code)]
     pub(crate) fn_bounds: FnBounds,
@@ -69,6 +79,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         } else {
             program
         };
+        let program_step = program.step;
         chip_complex.set_program(program);
 
         if let Some(initial_memory) = initial_memory {
@@ -78,6 +89,10 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 .set_initial_memory(initial_memory);
         }
         let air_names = chip_complex.air_names();
+        let call_graph = config
+            .system()
+            .profile_call_graph
+            .then(|| CallGraphProfiler::new(fn_bounds.clone(), program_step));
 
         Self {
             chip_complex,
@@ -85,12 +100,83 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             cycle_tracker: CycleTracker::new(),
             #[cfg(feature = "bench-metrics")]
             collected_metrics: Default::default(),
+            call_graph,
             fn_bounds,
             air_names,
             since_last_segment_check: 0,
         }
     }
 
+    /// Name of the cycle tracker span started or ended by a `CtStart` or `CtEnd` phantom.
+    /// Native programs carry the name in the debug info, prefixed by "CT-". Guest programs set
+    /// `c_upper = 1` and pass the registers holding the address and length of the name in memory.
+    #[cfg(not(feature = "function-span"))]
+    fn cycle_tracker_span_name(
+        &self,
+        instruction: &Instruction<F>,
+        dsl_instr: &Option<String>,
+    ) -> String {
+        if instruction.c.as_canonical_u32() >> 16 == 1 {
+            let bytes = self.unsafe_read_guest_bytes(instruction.a, instruction.b);
+            return String::from_utf8_lossy(&bytes).into_owned();
+        }
+        // hack to remove "CT-" prefix
+        dsl_instr.clone().unwrap_or("CT-Default".to_string())[3..].to_string()
+    }
+
+    /// Prints the return addresses reported by a guest in a `Backtrace` phantom, symbolized with
+    /// the function bounds of the executable when they are available.
+    fn print_guest_backtrace(&self, pc: u32, instruction: &Instruction<F>) {
+        let bytes = self.unsafe_read_guest_bytes(instruction.a, instruction.b);
+        eprintln!("guest backtrace:");
+        let pcs = once(pc).chain(
+            bytes
+                .chunks_exact(4)
+                .map(|ra| u32::from_le_bytes(ra.try_into().unwrap())),
+        );
+        for (i, pc) in pcs.enumerate() {
+            let symbol = self
+                .fn_bounds
+                .range(..=pc)
+                .next_back()
+                .filter(|(_, bound)| pc <= bound.end)
+                .map(|(&start, bound)| {
+                    format!(
+                        "{}+{:#x}",
+                        SymbolName::new(bound.name.as_bytes()),
+                        pc - start
+                    )
+                })
+                .unwrap_or_default();
+            eprintln!("{i:>4}: {pc:#010x} {symbol}");
+        }
+    }
+
+    /// Reads the guest memory `[[ptr_reg]_1..[ptr_reg]_1 + [len_reg]_1]_2` without recording the
+    /// accesses, for phantoms that pass a byte string by pointer and length registers.
+    fn unsafe_read_guest_bytes(&self, ptr_reg: F, len_reg: F) -> Vec<u8> {
+        let memory = self.chip_complex.memory_controller().borrow();
+        let read_register = |ptr: F| {
+            let limbs = memory.unsafe_read::<RV32_REGISTER_NUM_LIMBS>(
+                F::from_canonical_u32(RV32_REGISTER_AS),
+                ptr,
+            );
+            u32::from_le_bytes(limbs.map(|limb| limb.as_canonical_u32() as u8))
+        };
+        let ptr = read_register(ptr_reg);
+        let len = read_register(len_reg);
+        (0..len)
+            .map(|i| {
+                memory
+                    .unsafe_read_cell(
+                        F::from_canonical_u32(RV32_MEMORY_AS),
+                        F::from_canonical_u32(ptr + i),
+                    )
+                    .as_canonical_u32() as u8
+            })
+            .collect()
+    }
+
     pub fn system_config(&self) -> &SystemConfig {
         self.chip_complex.config()
     }
@@ -163,6 +249,9 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 match phantom {
                     Some(SysPhantom::DebugPanic) => {
                         if let Some(mut backtrace) = prev_backtrace {
+                            if let Some(call_site) = call_site(&backtrace) {
+                                tracing::error!("openvm program failure at {call_site}");
+                            }
                             backtrace.resolve();
                             eprintln!("openvm program failure; backtrace:\n{:?}", backtrace);
                         } else {
@@ -171,24 +260,23 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                         return Err(ExecutionError::Fail { pc });
                     }
                     Some(SysPhantom::CtStart) => {
-                        // hack to remove "CT-" prefix
                         #[cfg(not(feature = "function-span"))]
-                        self.cycle_tracker.start(
-                            dsl_instr.clone().unwrap_or("CT-Default".to_string())[3..].to_string(),
-                        )
+                        {
+                            let name = self.cycle_tracker_span_name(&instruction, &dsl_instr);
+                            self.cycle_tracker.start(name)
+                        }
                     }
+                    Some(SysPhantom::Backtrace) => self.print_guest_backtrace(pc, &instruction),
                     Some(SysPhantom::CtEnd) => {
-                        // hack to remove "CT-" prefix
                         #[cfg(not(feature = "function-span"))]
-                        self.cycle_tracker.end(
-                            dsl_instr.clone().unwrap_or("CT-Default".to_string())[3..].to_string(),
-                        )
+                        {
+                            let name = self.cycle_tracker_span_name(&instruction, &dsl_instr);
+                            self.cycle_tracker.end(name)
+                        }
                     }
                     _ => {}
                 }
             }
-            prev_backtrace = trace;
-
             #[cfg(feature = "function-span")]
             if !self.fn_bounds.is_empty() && (pc < current_fn.start || pc > current_fn.end) {
                 current_fn = self
@@ -211,8 +299,26 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                     executor,
                     instruction,
                     ExecutionState::new(pc, timestamp),
-                )?;
+                )
+                .inspect_err(|_| {
+                    if let Some(dsl_instr) = &dsl_instr {
+                        let call_site = trace.as_ref().and_then(call_site).unwrap_or_else(|| {
+                            "unknown location (build the program with RUST_BACKTRACE=1)"
+                                .to_string()
+                        });
+                        tracing::error!(
+                            "openvm program failure at pc {pc:#x} in {dsl_instr}, emitted at {call_site}"
+                        );
+                    }
+                })?;
                 assert!(next_state.timestamp > timestamp);
+                if let Some(call_graph) = &mut self.call_graph {
+                    call_graph.record_instruction(
+                        pc,
+                        next_state.pc,
+                        &executor.get_opcode_name(opcode.as_usize()),
+                    );
+                }
                 #[cfg(feature = "bench-metrics")]
                 {
                     metrics::counter!("total_cycles").increment(1u64);
@@ -220,11 +326,15 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                         opcode_name = Some(executor.get_opcode_name(opcode.as_usize()));
                     }
                 }
+                self.chip_complex
+                    .program_chip()
+                    .check_jump(pc, next_state.pc)?;
                 pc = next_state.pc;
                 timestamp = next_state.timestamp;
             } else {
                 return Err(ExecutionError::DisabledOperation { pc, opcode });
             };
+            prev_backtrace = trace;
 
             #[cfg(feature = "bench-metrics")]
             if collect_metrics {
@@ -254,28 +364,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 break;
             }
         }
-        // Finalize memory.
-        {
-            // Need some partial borrows, so code is ugly:
-            let mut memory_controller = self.chip_complex.base.memory_controller.borrow_mut();
-            self.final_memory = if self.system_config().continuation_enabled {
-                let chip = self
-                    .chip_complex
-                    .inventory
-                    .periphery
-                    .get_mut(
-                        VmChipComplex::<F, VC::Executor, VC::Periphery>::POSEIDON2_PERIPHERY_IDX,
-                    )
-                    .expect("Poseidon2 chip required for persistent memory");
-                let hasher: &mut Poseidon2Chip<F> = chip
-                    .as_any_kind_mut()
-                    .downcast_mut()
-                    .expect("Poseidon2 chip required for persistent memory");
-                memory_controller.finalize(Some(hasher))
-            } else {
-                memory_controller.finalize(None::<&mut Poseidon2Chip<F>>)
-            };
-        }
+        self.finalize_memory();
         #[cfg(feature = "bench-metrics")]
         if collect_metrics {
             self.collected_metrics.chip_heights =
@@ -292,6 +381,124 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         })
     }
 
+    /// Like [Self::execute_from_pc], but skips cycle tracking, metrics, backtraces and tracing.
+    /// The segment is only cut when continuations are enabled. Returns the number of executed
+    /// instructions along with the state.
+    pub fn execute_fast_from_pc(
+        &mut self,
+        mut pc: u32,
+    ) -> Result<(ExecutionSegmentState, u64), ExecutionError> {
+        let mut timestamp = self.chip_complex.memory_controller().borrow().timestamp();
+        let continuation_enabled = self.system_config().continuation_enabled;
+        let terminate = VmOpcode::with_default_offset(SystemOpcode::TERMINATE);
+        let phantom = VmOpcode::with_default_offset(SystemOpcode::PHANTOM);
+
+        self.chip_complex
+            .connector_chip_mut()
+            .begin(ExecutionState::new(pc, timestamp));
+
+        let mut cycles = 0;
+        let mut did_terminate = false;
+        loop {
+            let (instruction, _) = self.chip_complex.program_chip_mut().get_instruction(pc)?;
+            let opcode = instruction.opcode;
+
+            if opcode == terminate {
+                did_terminate = true;
+                self.chip_complex.connector_chip_mut().end(
+                    ExecutionState::new(pc, timestamp),
+                    Some(instruction.c.as_canonical_u32()),
+                );
+                break;
+            }
+            if opcode == phantom
+                && SysPhantom::from_repr(instruction.c.as_canonical_u32() as u16)
+                    == Some(SysPhantom::DebugPanic)
+            {
+                return Err(ExecutionError::Fail { pc });
+            }
+
+            let Some(executor) = self.chip_complex.inventory.get_mut_executor(&opcode) else {
+                return Err(ExecutionError::DisabledOperation { pc, opcode });
+            };
+            let next_state = InstructionExecutor::execute(
+                executor,
+                instruction,
+                ExecutionState::new(pc, timestamp),
+            )?;
+            self.chip_complex
+                .program_chip()
+                .check_jump(pc, next_state.pc)?;
+            pc = next_state.pc;
+            timestamp = next_state.timestamp;
+            cycles += 1;
+
+            if continuation_enabled && self.should_segment() {
+                self.chip_complex
+                    .connector_chip_mut()
+                    .end(ExecutionState::new(pc, timestamp), None);
+                break;
+            }
+        }
+        self.finalize_memory();
+
+        Ok((
+            ExecutionSegmentState {
+                pc,
+                is_terminated: did_terminate,
+            },
+            cycles,
+        ))
+    }
+
+    /// Executes the single instruction at `from_state.pc`, for tools that compare the execution
+    /// instruction by instruction, e.g. against a reference RISC-V simulator. Returns `None` on
+    /// TERMINATE. There is no segmentation, cycle tracking or metrics, and the connector chip
+    /// isn't updated, so the segment can't be proven afterwards.
+    pub fn step(
+        &mut self,
+        from_state: ExecutionState<u32>,
+    ) -> Result<Option<ExecutionState<u32>>, ExecutionError> {
+        let pc = from_state.pc;
+        let (instruction, _) = self.chip_complex.program_chip_mut().get_instruction(pc)?;
+        let opcode = instruction.opcode;
+        if opcode == VmOpcode::with_default_offset(SystemOpcode::TERMINATE) {
+            return Ok(None);
+        }
+        let Some(executor) = self.chip_complex.inventory.get_mut_executor(&opcode) else {
+            return Err(ExecutionError::DisabledOperation { pc, opcode });
+        };
+        InstructionExecutor::execute(executor, instruction, from_state).map(Some)
+    }
+
+    fn finalize_memory(&mut self) {
+        // Need some partial borrows, so code is ugly:
+        let mut memory_controller = self.chip_complex.base.memory_controller.borrow_mut();
+        self.final_memory = if !self.system_config().continuation_enabled {
+            memory_controller.finalize(None::<&mut Poseidon2Chip<F>>)
+        } else if let Some(mut hasher) = memory_controller.memory_hasher.take() {
+            memory_controller.finalize(Some(&mut hasher))
+        } else {
+            let merkle_hasher = self.system_config().memory_config.merkle_hasher;
+            assert_eq!(
+                merkle_hasher,
+                MemoryMerkleHasher::Poseidon2,
+                "no extension set the {merkle_hasher:?} memory Merkle hasher"
+            );
+            let chip = self
+                .chip_complex
+                .inventory
+                .periphery
+                .get_mut(VmChipComplex::<F, VC::Executor, VC::Periphery>::POSEIDON2_PERIPHERY_IDX)
+                .expect("Poseidon2 chip required for persistent memory");
+            let hasher: &mut Poseidon2Chip<F> = chip
+                .as_any_kind_mut()
+                .downcast_mut()
+                .expect("Poseidon2 chip required for persistent memory");
+            memory_controller.finalize(Some(hasher))
+        };
+    }
+
     /// Generate ProofInput to prove the segment. Should be called after ::execute
     pub fn generate_proof_input<SC: StarkGenericConfig>(
         self,
@@ -34,10 +34,12 @@ use crate::{
 pub mod execution;
 pub mod memory;
 pub mod program;
+pub mod tamper;
 pub mod test_adapter;
 
 pub use execution::ExecutionTester;
 pub use memory::MemoryTester;
+pub use tamper::Tamper;
 pub use test_adapter::TestAdapterChip;
 
 use super::{ExecutionBus, InstructionExecutor};
@@ -314,6 +316,18 @@ where
         self
     }
 
+    /// Loads `chip` with `tampers` applied to its valid main trace. Use with
+    /// `simple_test_with_expected_error` to check that the tampered trace is rejected. A broken
+    /// constraint gives `OodEvaluationMismatch`, even if the cell is also in an interaction, and
+    /// a cell only in interactions gives `ChallengePhaseError`.
+    pub fn load_and_tamper<C: Chip<SC>>(self, chip: C, tampers: &[Tamper<Val<SC>>]) -> Self {
+        self.load_and_prank_trace(chip, |trace| {
+            for tamper in tampers {
+                tamper.apply(trace);
+            }
+        })
+    }
+
     /// Given a function to produce an engine from the max trace height,
     /// runs a simple test on that engine
     pub fn test<E: StarkEngine<SC>, P: Fn() -> E>(
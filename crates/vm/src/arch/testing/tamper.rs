@@ -0,0 +1,54 @@
+use openvm_stark_backend::{
+    p3_field::Field,
+    p3_matrix::{dense::DenseMatrix, Matrix},
+};
+
+/// A change of a single cell of a chip's main trace, made after the valid trace was generated, to
+/// check that the constraints reject a malicious prover. `col` is the index in the whole row, so
+/// for a [VmChipWrapper](crate::arch::VmChipWrapper) the core columns start after the adapter
+/// width.
+#[derive(Clone, Copy, Debug)]
+pub enum Tamper<F> {
+    /// Overwrites the cell with `value`.
+    Set { row: usize, col: usize, value: F },
+    /// Adds `delta` to the cell.
+    Add { row: usize, col: usize, delta: F },
+}
+
+impl<F: Field> Tamper<F> {
+    /// Makes the cell off by one, e.g. a wrong carry or a wrong quotient limb.
+    pub fn off_by_one(row: usize, col: usize) -> Self {
+        Self::Add {
+            row,
+            col,
+            delta: F::ONE,
+        }
+    }
+
+    /// Sets the cell to `2^bits`, the smallest value failing a `bits`-bit range check, as if the
+    /// prover skipped the range check of the cell.
+    pub fn skipped_range_check(row: usize, col: usize, bits: usize) -> Self {
+        Self::Set {
+            row,
+            col,
+            value: F::from_canonical_u64(1 << bits),
+        }
+    }
+
+    pub fn apply(&self, trace: &mut DenseMatrix<F>) {
+        let (row, col) = match *self {
+            Self::Set { row, col, .. } | Self::Add { row, col, .. } => (row, col),
+        };
+        assert!(
+            row < trace.height() && col < trace.width(),
+            "cell ({row}, {col}) is outside of the {}x{} trace",
+            trace.height(),
+            trace.width()
+        );
+        let cell = &mut trace.values[row * trace.width + col];
+        match *self {
+            Self::Set { value, .. } => *cell = value,
+            Self::Add { delta, .. } => *cell += delta,
+        }
+    }
+}
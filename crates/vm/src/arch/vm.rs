@@ -1,4 +1,4 @@
-use std::{borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, sync::Arc};
+use std::{array, borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, sync::Arc};
 
 use openvm_instructions::exe::VmExe;
 use openvm_stark_backend::{
@@ -11,9 +11,13 @@ use openvm_stark_backend::{
     verifier::VerificationError,
     Chip,
 };
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{ExecutionError, VmComplexTraceHeights, VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID};
+use super::{
+    hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, VmComplexTraceHeights, VmConfig,
+    VmInventoryError, CONNECTOR_AIR_ID, MERKLE_AIR_ID, PROGRAM_AIR_ID,
+};
 use crate::{
     arch::segment::ExecutionSegment,
     system::{
@@ -21,6 +25,7 @@ use crate::{
         memory::{memory_image_to_equipartition, merkle::MemoryMerklePvs, Equipartition, CHUNK},
         program::trace::VmCommittedExe,
     },
+    utils::{check_constraints, ConstraintViolation, SkippedConstraint},
 };
 
 /// VM memory state for continuations.
@@ -30,6 +35,19 @@ pub type VmMemoryState<F> = Equipartition<F, CHUNK>;
 pub struct Streams<F> {
     pub input_stream: VecDeque<Vec<F>>,
     pub hint_stream: VecDeque<F>,
+    pub hint_tape: HintTape<F>,
+}
+
+/// Recording or replay of the hints consumed by instructions, see [Streams::pop_hints].
+#[derive(Clone, Default, Debug)]
+pub enum HintTape<F> {
+    #[default]
+    Off,
+    /// Every consumed hint is appended to the tape.
+    Record(Vec<F>),
+    /// Hints are consumed from the tape instead of the hint stream, so the values written to the
+    /// hint stream by phantom instructions are ignored.
+    Replay(VecDeque<F>),
 }
 
 impl<F> Streams<F> {
@@ -37,8 +55,31 @@ impl<F> Streams<F> {
         Self {
             input_stream: input_stream.into(),
             hint_stream: VecDeque::default(),
+            hint_tape: HintTape::Off,
+        }
+    }
+}
+
+impl<F: Copy> Streams<F> {
+    /// Pops the next `N` hints for an instruction which reads hints into memory. Returns `None`
+    /// if fewer than `N` hints are left.
+    pub fn pop_hints<const N: usize>(&mut self) -> Option<[F; N]> {
+        let hints = match &mut self.hint_tape {
+            HintTape::Replay(tape) => pop_array(tape)?,
+            _ => pop_array(&mut self.hint_stream)?,
+        };
+        if let HintTape::Record(tape) = &mut self.hint_tape {
+            tape.extend(hints);
         }
+        Some(hints)
+    }
+}
+
+fn pop_array<F, const N: usize>(queue: &mut VecDeque<F>) -> Option<[F; N]> {
+    if queue.len() < N {
+        return None;
     }
+    Some(array::from_fn(|_| queue.pop_front().unwrap()))
 }
 
 impl<F> From<VecDeque<Vec<F>>> for Streams<F> {
@@ -53,6 +94,25 @@ impl<F> From<Vec<Vec<F>>> for Streams<F> {
     }
 }
 
+/// Input and consumed hints of an execution, recorded by [VmExecutor::record_hints]. Executing
+/// with the [Streams] converted from it reproduces the execution exactly, without depending on
+/// host-side hint logic, so it can be shipped to a remote prover.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ReplayBundle<F> {
+    pub input_stream: Vec<Vec<F>>,
+    pub hints: Vec<F>,
+}
+
+impl<F> From<ReplayBundle<F>> for Streams<F> {
+    fn from(bundle: ReplayBundle<F>) -> Self {
+        Self {
+            input_stream: bundle.input_stream.into(),
+            hint_stream: VecDeque::default(),
+            hint_tape: HintTape::Replay(bundle.hints.into()),
+        }
+    }
+}
+
 pub struct VmExecutor<F, VC> {
     pub config: VC,
     pub overridden_heights: Option<VmComplexTraceHeights>,
@@ -63,6 +123,8 @@ pub struct VmExecutor<F, VC> {
 pub enum ExitCode {
     Success = 0,
     Error = 1,
+    /// The guest exhausted its heap, see `openvm_platform::memory::OUT_OF_MEMORY_EXIT_CODE`.
+    OutOfMemory = 3,
     Suspended = -1, // Continuations
 }
 
@@ -72,6 +134,41 @@ pub struct VmExecutorResult<SC: StarkGenericConfig> {
     pub final_memory: Option<VmMemoryState<Val<SC>>>,
 }
 
+/// Result of [VmExecutor::run_fast].
+#[derive(Clone, Debug)]
+pub struct FastExecutionResult<F> {
+    /// Number of executed instructions, excluding the final `TERMINATE`.
+    pub cycles: u64,
+    pub num_segments: usize,
+    pub exit_code: u32,
+    /// Final memory if continuations are enabled.
+    pub final_memory: Option<VmMemoryState<F>>,
+    pub summary: ExecutionSummary,
+}
+
+/// Trace heights of every segment of an execution, known before any trace is generated.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    /// AIR names in the order of the AIR ids.
+    pub air_names: Vec<String>,
+    /// `segment_heights[i][j]` is the number of rows AIR `j` uses in segment `i`, before padding
+    /// to a power of two.
+    pub segment_heights: Vec<Vec<usize>>,
+}
+
+impl ExecutionSummary {
+    /// Maximum height of each AIR over all segments.
+    pub fn max_trace_heights(&self) -> Vec<usize> {
+        let mut max_heights = vec![0; self.air_names.len()];
+        for heights in &self.segment_heights {
+            for (max_height, &height) in max_heights.iter_mut().zip(heights) {
+                *max_height = (*max_height).max(height);
+            }
+        }
+        max_heights
+    }
+}
+
 impl<F, VC> VmExecutor<F, VC>
 where
     F: PrimeField32,
@@ -108,26 +205,86 @@ where
         exe: impl Into<VmExe<F>>,
         input: impl Into<Streams<F>>,
     ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
+        let mut segments = vec![];
+        self.execute_segments_impl(exe.into(), input.into(), None, |segment| {
+            segments.push(segment)
+        })?;
+        Ok(segments)
+    }
+
+    /// Executes `exe` starting from `initial_memory` instead of the initial memory of `exe`, e.g.
+    /// the final memory of a previous execution. The proofs expose the Merkle root of the initial
+    /// memory as a public value of the memory Merkle AIR, so stateful applications can prove the
+    /// transition from an externally known `initial_root` to the final root.
+    ///
+    /// Returns [ExecutionError::InitialMemoryRootMismatch] if `initial_memory` does not hash to
+    /// `initial_root`. Requires continuations, since the initial root is only committed with
+    /// persistent memory.
+    pub fn execute_segments_from_memory(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+        initial_memory: VmMemoryState<F>,
+        initial_root: [F; CHUNK],
+    ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
+        assert!(
+            self.continuation_enabled(),
+            "initial memory roots require to enable continuations"
+        );
+        let mut segments = vec![];
+        self.execute_segments_impl(
+            exe.into(),
+            input.into(),
+            Some((initial_memory, initial_root)),
+            |segment| segments.push(segment),
+        )?;
+        Ok(segments)
+    }
+
+    /// Executes the segments in order, passing each one to `on_segment` as soon as it has
+    /// finished executing. Only the last segment keeps its final memory.
+    fn execute_segments_impl(
+        &self,
+        exe: VmExe<F>,
+        streams: Streams<F>,
+        initial_memory: Option<(VmMemoryState<F>, [F; CHUNK])>,
+        mut on_segment: impl FnMut(ExecutionSegment<F, VC>),
+    ) -> Result<(), ExecutionError> {
         #[cfg(feature = "bench-metrics")]
         let start = std::time::Instant::now();
+        // Time spent in `on_segment`, which is not part of the execution time.
+        #[cfg(feature = "bench-metrics")]
+        let mut on_segment_time = std::time::Duration::ZERO;
 
-        let exe = exe.into();
-        let streams = input.into();
-        let mut segments = vec![];
+        let (initial_memory, initial_root) = match initial_memory {
+            Some((memory, root)) => (memory, Some(root)),
+            None => (memory_image_to_equipartition(exe.init_memory), None),
+        };
+        let mut num_segments = 0;
         let mut segment = ExecutionSegment::new(
             &self.config,
             exe.program.clone(),
             streams,
-            Some(memory_image_to_equipartition(exe.init_memory)),
+            Some(initial_memory),
             exe.fn_bounds.clone(),
         );
+        if let Some(initial_root) = initial_root {
+            let memory_controller = segment.chip_complex.memory_controller().borrow();
+            let actual_root = match &memory_controller.memory_hasher {
+                Some(hasher) => memory_controller.initial_memory_root(hasher),
+                None => memory_controller.initial_memory_root(&vm_poseidon2_hasher()),
+            };
+            if actual_root != Some(initial_root) {
+                return Err(ExecutionError::InitialMemoryRootMismatch);
+            }
+        }
         if let Some(overridden_heights) = self.overridden_heights.as_ref() {
             segment.set_override_trace_heights(overridden_heights.clone());
         }
         let mut pc = exe.pc_start;
 
         loop {
-            let state = tracing::info_span!("execute_segment", segment = segments.len())
+            let state = tracing::info_span!("execute_segment", segment = num_segments)
                 .in_scope(|| segment.execute_from_pc(pc))?;
             pc = state.pc;
 
@@ -148,11 +305,22 @@ where
             );
 
             let cycle_tracker = mem::take(&mut segment.cycle_tracker);
+            let call_graph = segment.call_graph.take();
             let final_memory = mem::take(&mut segment.final_memory)
                 .expect("final memory should be set in continuations segment");
             let streams = segment.chip_complex.take_streams();
-
-            segments.push(segment);
+            // The previous segment is the best prediction of how many records each executor will
+            // store in the next one, so allocate record storage up front.
+            let predicted_heights = segment.chip_complex.inventory.get_trace_heights();
+
+            #[cfg(feature = "bench-metrics")]
+            let on_segment_start = std::time::Instant::now();
+            on_segment(segment);
+            #[cfg(feature = "bench-metrics")]
+            {
+                on_segment_time += on_segment_start.elapsed();
+            }
+            num_segments += 1;
 
             segment = ExecutionSegment::new(
                 &self.config,
@@ -164,16 +332,24 @@ where
             if let Some(overridden_heights) = self.overridden_heights.as_ref() {
                 segment.set_override_trace_heights(overridden_heights.clone());
             }
+            segment
+                .chip_complex
+                .inventory
+                .reserve_executor_records(&predicted_heights);
             segment.cycle_tracker = cycle_tracker;
+            segment.call_graph = call_graph;
         }
-        segments.push(segment);
-        tracing::debug!("Number of continuation segments: {}", segments.len());
+        num_segments += 1;
+        tracing::debug!("Number of continuation segments: {}", num_segments);
         #[cfg(feature = "bench-metrics")]
-        metrics::gauge!("execute_time_ms").set(start.elapsed().as_millis() as f64);
-        #[cfg(feature = "bench-metrics")]
-        tracing::info!("execute_time [all segments]: {:?}", start.elapsed());
+        {
+            let execute_time = start.elapsed() - on_segment_time;
+            metrics::gauge!("execute_time_ms").set(execute_time.as_millis() as f64);
+            tracing::info!("execute_time [all segments]: {:?}", execute_time);
+        }
+        on_segment(segment);
 
-        Ok(segments)
+        Ok(())
     }
 
     pub fn execute(
@@ -196,6 +372,94 @@ where
         Ok(final_memory)
     }
 
+    /// Executes the program without keeping anything needed to prove it, for iterating on guest
+    /// logic, counting cycles and sizing traces. Skips cycle tracking, metrics and backtraces, and
+    /// drops each segment as soon as it is done. Without continuations the program runs in a
+    /// single segment of unbounded length.
+    ///
+    /// Executors skip their execution records (see [super::InstructionExecutor::skip_records]) and
+    /// memory accesses are not logged for the access adapters, so the memory used by a segment
+    /// does not grow with its length. Trace heights are still counted, for segmentation and the
+    /// [ExecutionSummary].
+    pub fn run_fast(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<FastExecutionResult<F>, ExecutionError> {
+        self.run_fast_impl(exe.into(), input.into())
+            .map(|(result, _)| result)
+    }
+
+    /// Executes the program like [Self::run_fast] and records the hints it consumes.
+    pub fn record_hints(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<ReplayBundle<F>, ExecutionError> {
+        let mut streams = input.into();
+        let input_stream = streams.input_stream.iter().cloned().collect();
+        streams.hint_tape = HintTape::Record(vec![]);
+        let (_, streams) = self.run_fast_impl(exe.into(), streams)?;
+        let HintTape::Record(hints) = streams.hint_tape else {
+            unreachable!("hint tape must stay in record mode");
+        };
+        Ok(ReplayBundle {
+            input_stream,
+            hints,
+        })
+    }
+
+    fn run_fast_impl(
+        &self,
+        exe: VmExe<F>,
+        mut streams: Streams<F>,
+    ) -> Result<(FastExecutionResult<F>, Streams<F>), ExecutionError> {
+        let mut memory = Some(memory_image_to_equipartition(exe.init_memory));
+        let mut pc = exe.pc_start;
+        let mut cycles = 0;
+        let mut num_segments = 0;
+        let mut summary = ExecutionSummary::default();
+        loop {
+            let mut segment = ExecutionSegment::new(
+                &self.config,
+                exe.program.clone(),
+                streams,
+                memory,
+                exe.fn_bounds.clone(),
+            );
+            segment.chip_complex.inventory.skip_executor_records();
+            segment
+                .chip_complex
+                .memory_controller()
+                .borrow_mut()
+                .skip_access_adapter_records();
+            let (state, segment_cycles) = segment.execute_fast_from_pc(pc)?;
+            cycles += segment_cycles;
+            num_segments += 1;
+            if summary.air_names.is_empty() {
+                summary.air_names = segment.air_names.clone();
+            }
+            summary
+                .segment_heights
+                .push(segment.current_trace_heights());
+            pc = state.pc;
+            memory = mem::take(&mut segment.final_memory);
+            if state.is_terminated {
+                let end_state = segment.chip_complex.connector_chip().boundary_states[1]
+                    .expect("end state must be set");
+                let result = FastExecutionResult {
+                    cycles,
+                    num_segments,
+                    exit_code: end_state.exit_code,
+                    final_memory: memory,
+                    summary,
+                };
+                return Ok((result, segment.chip_complex.take_streams()));
+            }
+            streams = segment.chip_complex.take_streams();
+        }
+    }
+
     pub fn execute_and_generate<SC: StarkGenericConfig>(
         &self,
         exe: impl Into<VmExe<F>>,
@@ -206,7 +470,33 @@ where
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        self.execute_and_generate_impl(exe.into(), None, input)
+        self.execute_and_generate_impl(exe.into(), None, input.into(), None)
+    }
+
+    /// Like [Self::execute_and_generate], starting from `initial_memory`. See
+    /// [Self::execute_segments_from_memory].
+    pub fn execute_and_generate_from_memory<SC: StarkGenericConfig>(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+        initial_memory: VmMemoryState<F>,
+        initial_root: [F; CHUNK],
+    ) -> Result<VmExecutorResult<SC>, ExecutionError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        assert!(
+            self.continuation_enabled(),
+            "initial memory roots require to enable continuations"
+        );
+        self.execute_and_generate_impl(
+            exe.into(),
+            None,
+            input.into(),
+            Some((initial_memory, initial_root)),
+        )
     }
 
     pub fn execute_and_generate_with_cached_program<SC: StarkGenericConfig>(
@@ -222,21 +512,61 @@ where
         self.execute_and_generate_impl(
             commited_exe.exe.clone(),
             Some(commited_exe.committed_program.clone()),
-            input,
+            input.into(),
+            None,
         )
     }
+
+    /// Executes the program and generates the proof input of each segment as soon as the segment
+    /// has finished executing, instead of after the whole execution. `on_segment` is called with
+    /// the index and proof input of each segment in order, e.g. to hand it to a proving thread so
+    /// proving overlaps with executing the next segments. The records of a segment are dropped
+    /// once its proof input is generated.
+    ///
+    /// Returns the final memory if persistent.
+    pub fn execute_and_generate_streaming<SC: StarkGenericConfig>(
+        &self,
+        commited_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        mut on_segment: impl FnMut(usize, ProofInput<SC>),
+    ) -> Result<Option<VmMemoryState<F>>, ExecutionError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let committed_program = commited_exe.committed_program.clone();
+        let mut seg_idx = 0;
+        let mut final_memory = None;
+        self.execute_segments_impl(
+            commited_exe.exe.clone(),
+            input.into(),
+            None,
+            |mut segment| {
+                final_memory = mem::take(&mut segment.final_memory);
+                let proof_input = tracing::info_span!("trace_gen", segment = seg_idx)
+                    .in_scope(|| segment.generate_proof_input(Some(committed_program.clone())));
+                on_segment(seg_idx, proof_input);
+                seg_idx += 1;
+            },
+        )?;
+        Ok(final_memory)
+    }
+
     fn execute_and_generate_impl<SC: StarkGenericConfig>(
         &self,
         exe: VmExe<F>,
         committed_program: Option<CommittedTraceData<SC>>,
-        input: impl Into<Streams<F>>,
+        input: Streams<F>,
+        initial_memory: Option<(VmMemoryState<F>, [F; CHUNK])>,
     ) -> Result<VmExecutorResult<SC>, ExecutionError>
     where
         Domain<SC>: PolynomialSpace<Val = F>,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let mut segments = self.execute_segments(exe, input)?;
+        let mut segments = vec![];
+        self.execute_segments_impl(exe, input, initial_memory, |segment| segments.push(segment))?;
         let final_memory = mem::take(&mut segments.last_mut().unwrap().final_memory);
 
         #[allow(unused_variables)]
@@ -387,6 +717,9 @@ pub enum VmVerificationError {
     #[error("number of public values mismatch (expected: {expected}, actual: {actual})")]
     NumPublicValuesMismatch { expected: usize, actual: usize },
 
+    #[error("required AIR {air_id} is missing from the proof")]
+    MissingRequiredAir { air_id: usize },
+
     #[error("stark verification error: {0}")]
     StarkError(#[from] VerificationError),
 }
@@ -435,13 +768,19 @@ where
         &self.executor.config
     }
 
-    pub fn keygen(&self) -> MultiStarkProvingKey<SC> {
+    /// Generates the proving key of the circuit.
+    ///
+    /// Returns [VmInventoryError::InconsistentBuses] if the bus interactions of the config can
+    /// never balance; see [super::VmChipComplex::check_bus_consistency].
+    pub fn keygen(&self) -> Result<MultiStarkProvingKey<SC>, VmInventoryError> {
         let mut keygen_builder = self.engine.keygen_builder();
-        let chip_complex = self.config().create_chip_complex().unwrap();
+        let chip_complex = self.config().create_chip_complex()?;
         for air in chip_complex.airs() {
             keygen_builder.add_air(air);
         }
-        keygen_builder.generate_pk()
+        let pk = keygen_builder.generate_pk();
+        chip_complex.check_bus_consistency(&pk)?;
+        Ok(pk)
     }
 
     pub fn commit_exe(&self, exe: impl Into<VmExe<F>>) -> Arc<VmCommittedExe<SC>> {
@@ -465,6 +804,31 @@ where
         self.executor.execute_and_generate(exe, input)
     }
 
+    /// See [VmExecutor::execute_and_generate_streaming].
+    pub fn execute_and_generate_streaming(
+        &self,
+        committed_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        on_segment: impl FnMut(usize, ProofInput<SC>),
+    ) -> Result<Option<VmMemoryState<F>>, ExecutionError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+    {
+        self.executor
+            .execute_and_generate_streaming(committed_exe, input, on_segment)
+    }
+
+    pub fn execute_and_generate_from_memory(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+        initial_memory: VmMemoryState<F>,
+        initial_root: [F; CHUNK],
+    ) -> Result<VmExecutorResult<SC>, ExecutionError> {
+        self.executor
+            .execute_and_generate_from_memory(exe, input, initial_memory, initial_root)
+    }
+
     pub fn execute_and_generate_with_cached_program(
         &self,
         committed_exe: Arc<VmCommittedExe<SC>>,
@@ -477,6 +841,26 @@ where
             .execute_and_generate_with_cached_program(committed_exe, input)
     }
 
+    /// Evaluates the AIR constraints of every segment on the generated traces and returns the
+    /// first violation, naming the AIR, constraint, row and referenced column values. Otherwise
+    /// returns the constraints which could not be checked, once per AIR and constraint.
+    ///
+    /// This is much slower than proving and is only meant for debugging chips.
+    pub fn debug_constraints(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        results: &VmExecutorResult<SC>,
+    ) -> Result<Vec<SkippedConstraint>, ConstraintViolation<F>> {
+        let mut skipped = vec![];
+        for proof_input in &results.per_segment {
+            skipped.extend(check_constraints(pk, proof_input)?);
+        }
+        Ok(skipped
+            .into_iter()
+            .unique_by(|c| (c.air_id, c.constraint_idx))
+            .collect())
+    }
+
     pub fn prove_single(
         &self,
         pk: &MultiStarkProvingKey<SC>,
@@ -548,6 +932,14 @@ where
                 Err(e) => return Err(VmVerificationError::StarkError(e)),
             };
 
+            // Proofs only contain AIRs with non-empty traces. The boundary conditions below are
+            // only checked if the AIRs exposing them are present, so they must be required.
+            for air_id in [PROGRAM_AIR_ID, CONNECTOR_AIR_ID, MERKLE_AIR_ID] {
+                if !proof.per_air.iter().any(|data| data.air_id == air_id) {
+                    return Err(VmVerificationError::MissingRequiredAir { air_id });
+                }
+            }
+
             // Check public values.
             for air_proof_data in proof.per_air.iter() {
                 let pvs = &air_proof_data.public_values;
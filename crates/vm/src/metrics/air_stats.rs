@@ -0,0 +1,207 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use openvm_stark_backend::{
+    config::StarkGenericConfig,
+    interaction::InteractionType,
+    keygen::types::{MultiStarkProvingKey, StarkProvingKey, TraceWidth},
+};
+use serde::{Deserialize, Serialize};
+
+/// Number of sends and receives an AIR makes on a single bus.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusInteractionCount {
+    pub sends: usize,
+    pub receives: usize,
+}
+
+/// Keygen-time statistics of the symbolic constraints of a single AIR.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AirStats {
+    pub air_id: usize,
+    pub air_name: String,
+    /// Number of constraints, including the constraints of the interaction argument.
+    pub num_constraints: usize,
+    /// Maximum degree of any constraint.
+    pub max_constraint_degree: usize,
+    /// Number of chunks the quotient polynomial is split into.
+    pub quotient_degree: usize,
+    /// Interaction counts keyed by bus index.
+    pub interactions_per_bus: BTreeMap<usize, BusInteractionCount>,
+    pub width: TraceWidth,
+}
+
+impl AirStats {
+    pub fn from_pk<SC: StarkGenericConfig>(air_id: usize, pk: &StarkProvingKey<SC>) -> Self {
+        let symbolic_constraints = &pk.vk.symbolic_constraints;
+        let max_constraint_degree = symbolic_constraints
+            .constraints
+            .iter()
+            .map(|c| c.degree_multiple())
+            .max()
+            .unwrap_or(0);
+        let mut interactions_per_bus = BTreeMap::<usize, BusInteractionCount>::new();
+        for interaction in &symbolic_constraints.interactions {
+            let count = interactions_per_bus
+                .entry(interaction.bus_index)
+                .or_default();
+            match interaction.interaction_type {
+                InteractionType::Send => count.sends += 1,
+                InteractionType::Receive => count.receives += 1,
+            }
+        }
+        Self {
+            air_id,
+            air_name: pk.air_name.clone(),
+            num_constraints: symbolic_constraints.constraints.len(),
+            max_constraint_degree,
+            quotient_degree: pk.vk.quotient_degree,
+            interactions_per_bus,
+            width: pk.vk.params.width.clone(),
+        }
+    }
+
+    /// Total width of all main trace partitions.
+    pub fn main_width(&self) -> usize {
+        self.width.cached_mains.iter().sum::<usize>() + self.width.common_main
+    }
+
+    /// Total width of all after-challenge (permutation) traces.
+    pub fn perm_width(&self) -> usize {
+        self.width.after_challenge.iter().sum()
+    }
+
+    pub fn num_interactions(&self) -> usize {
+        self.interactions_per_bus
+            .values()
+            .map(|c| c.sends + c.receives)
+            .sum()
+    }
+}
+
+/// Per-AIR constraint statistics of a proving key, ordered by AIR ID.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AirStatsReport {
+    pub per_air: Vec<AirStats>,
+}
+
+impl AirStatsReport {
+    pub fn new<SC: StarkGenericConfig>(pk: &MultiStarkProvingKey<SC>) -> Self {
+        Self {
+            per_air: pk
+                .per_air
+                .iter()
+                .enumerate()
+                .map(|(air_id, air_pk)| AirStats::from_pk(air_id, air_pk))
+                .collect(),
+        }
+    }
+
+    /// The maximum constraint degree over all AIRs. This determines the minimum `log_blowup`.
+    pub fn max_constraint_degree(&self) -> usize {
+        self.per_air
+            .iter()
+            .map(|s| s.max_constraint_degree)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// AIRs whose maximum constraint degree exceeds `max_constraint_degree`.
+    pub fn airs_exceeding_degree(&self, max_constraint_degree: usize) -> Vec<&AirStats> {
+        self.per_air
+            .iter()
+            .filter(|s| s.max_constraint_degree > max_constraint_degree)
+            .collect()
+    }
+}
+
+impl Display for AirStatsReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4}  {:<48} {:>11} {:>6} {:>9} {:>10} {:>10}  interactions (bus: sends/receives)",
+            "id", "air", "constraints", "degree", "quotient", "main width", "perm width"
+        )?;
+        for s in &self.per_air {
+            let interactions = s
+                .interactions_per_bus
+                .iter()
+                .map(|(bus, c)| format!("{bus}: {}/{}", c.sends, c.receives))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "{:>4}  {:<48} {:>11} {:>6} {:>9} {:>10} {:>10}  {}",
+                s.air_id,
+                s.air_name,
+                s.num_constraints,
+                s.max_constraint_degree,
+                s.quotient_degree,
+                s.main_width(),
+                s.perm_width(),
+                interactions
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_backend::{engine::StarkEngine, Chip};
+    use openvm_stark_sdk::{
+        config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
+        dummy_airs::{
+            fib_air::chip::FibonacciChip, interaction::dummy_interaction_air::DummyInteractionChip,
+        },
+        engine::StarkFriEngine,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_air_stats_report() {
+        let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+        let fib_chip = FibonacciChip::new(0, 1, 8);
+        let send_chip = DummyInteractionChip::new_without_partition(2, true, 3);
+
+        let mut keygen_builder = engine.keygen_builder();
+        let fib_id = keygen_builder.add_air(fib_chip.air());
+        let send_id = keygen_builder.add_air(send_chip.air());
+        let report = AirStatsReport::new(&keygen_builder.generate_pk());
+
+        // The Fibonacci AIR has two first row, two transition and one last row constraint, each
+        // a degree 1 expression multiplied by a row selector.
+        let fib = &report.per_air[fib_id];
+        assert_eq!(fib.air_id, fib_id);
+        assert_eq!(fib.num_constraints, 5);
+        assert_eq!(fib.max_constraint_degree, 2);
+        assert_eq!(fib.quotient_degree, 1);
+        assert_eq!(fib.main_width(), 2);
+        assert_eq!(fib.perm_width(), 0);
+        assert_eq!(fib.num_interactions(), 0);
+
+        let send = &report.per_air[send_id];
+        assert_eq!(
+            send.interactions_per_bus,
+            BTreeMap::from([(
+                3,
+                BusInteractionCount {
+                    sends: 1,
+                    receives: 0
+                }
+            )])
+        );
+        assert_eq!(send.main_width(), 3);
+        assert!(send.perm_width() > 0);
+
+        assert_eq!(report.max_constraint_degree(), 2);
+        assert!(report.airs_exceeding_degree(2).is_empty());
+        assert_eq!(report.airs_exceeding_degree(1).len(), 1);
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.contains("3: 1/0"));
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+};
+
+use openvm_instructions::exe::FnBounds;
+
+/// Name used for instructions outside of every known function.
+const UNKNOWN_FN: &str = "<unknown>";
+/// Event counting every executed instruction. Each opcode gets its own event as well.
+const CYCLES_EVENT: &str = "Cycles";
+
+/// Reconstructs the guest call graph from the executed program counters and the function
+/// bounds of the ELF symbol table, without touching the program or its constraints.
+///
+/// Jumping to the start of a function is treated as a call, which returns once execution
+/// reaches the instruction after the call site. Returns that skip frames, e.g. from tail
+/// calls, pop every frame above the matching one.
+#[derive(Clone, Debug)]
+pub struct CallGraphProfiler {
+    fn_bounds: FnBounds,
+    pc_step: u32,
+    fn_names: Vec<String>,
+    fn_indices: HashMap<String, usize>,
+    event_names: Vec<String>,
+    event_indices: HashMap<String, usize>,
+    /// Costs of everything executed so far, indexed by event.
+    totals: Vec<u64>,
+    /// Costs excluding callees, keyed by (function, pc).
+    self_costs: BTreeMap<(usize, u32), Vec<u64>>,
+    /// Costs including callees of completed calls, keyed by (caller, call pc, callee, callee
+    /// entry pc), along with the number of calls.
+    calls: BTreeMap<(usize, u32, usize, u32), (u64, Vec<u64>)>,
+    stack: Vec<Frame>,
+}
+
+#[derive(Clone, Debug)]
+struct Frame {
+    fn_idx: usize,
+    entry_pc: u32,
+    /// Caller and pc of the call, `None` for the frame execution started in.
+    call_site: Option<(usize, u32)>,
+    return_pc: u32,
+    /// Value of `totals` when the frame was entered.
+    entry_costs: Vec<u64>,
+}
+
+impl CallGraphProfiler {
+    pub fn new(fn_bounds: FnBounds, pc_step: u32) -> Self {
+        Self {
+            fn_bounds,
+            pc_step,
+            fn_names: vec![],
+            fn_indices: HashMap::new(),
+            event_names: vec![CYCLES_EVENT.to_string()],
+            event_indices: HashMap::from([(CYCLES_EVENT.to_string(), 0)]),
+            totals: vec![0],
+            self_costs: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            stack: vec![],
+        }
+    }
+
+    /// Records that the instruction at `pc` with opcode `opcode_name` was executed and
+    /// moved execution to `next_pc`.
+    pub fn record_instruction(&mut self, pc: u32, next_pc: u32, opcode_name: &str) {
+        if self.stack.is_empty() {
+            let fn_idx = self.fn_at(pc);
+            self.stack.push(Frame {
+                fn_idx,
+                entry_pc: pc,
+                call_site: None,
+                return_pc: pc,
+                entry_costs: self.totals.clone(),
+            });
+        }
+        let fn_idx = self.stack.last().unwrap().fn_idx;
+
+        let event = self.event_idx(opcode_name);
+        let costs = self.self_costs.entry((fn_idx, pc)).or_default();
+        add_event(costs, 0, 1);
+        add_event(costs, event, 1);
+        add_event(&mut self.totals, 0, 1);
+        add_event(&mut self.totals, event, 1);
+
+        if next_pc == pc.wrapping_add(self.pc_step) {
+            return;
+        }
+        if let Some(depth) = self
+            .stack
+            .iter()
+            .rposition(|frame| frame.call_site.is_some() && frame.return_pc == next_pc)
+        {
+            while self.stack.len() > depth {
+                let frame = self.stack.pop().unwrap();
+                self.record_call(&frame);
+            }
+        } else if self.fn_bounds.contains_key(&next_pc) {
+            let callee = self.fn_at(next_pc);
+            self.stack.push(Frame {
+                fn_idx: callee,
+                entry_pc: next_pc,
+                call_site: Some((fn_idx, pc)),
+                return_pc: pc.wrapping_add(self.pc_step),
+                entry_costs: self.totals.clone(),
+            });
+        }
+    }
+
+    /// Writes the profile in the callgrind format, for use with tools such as
+    /// `callgrind_annotate` or KCachegrind. Calls that have not returned yet are included
+    /// with their costs so far.
+    pub fn write_callgrind(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut profile = self.clone();
+        while let Some(frame) = profile.stack.pop() {
+            profile.record_call(&frame);
+        }
+
+        writeln!(writer, "# callgrind format")?;
+        writeln!(writer, "version: 1")?;
+        writeln!(writer, "creator: openvm")?;
+        writeln!(writer, "positions: instr")?;
+        writeln!(writer, "events: {}", profile.event_names.join(" "))?;
+        writeln!(writer, "summary: {}", format_costs(&profile.totals))?;
+
+        for (fn_idx, fn_name) in profile.fn_names.iter().enumerate() {
+            writeln!(writer)?;
+            writeln!(writer, "fn={fn_name}")?;
+            for ((_, pc), costs) in profile.self_costs.range((fn_idx, 0)..=(fn_idx, u32::MAX)) {
+                writeln!(writer, "{pc:#x} {}", format_costs(costs))?;
+            }
+            for ((_, call_pc, callee, entry_pc), (count, costs)) in profile
+                .calls
+                .range((fn_idx, 0, 0, 0)..=(fn_idx, u32::MAX, usize::MAX, u32::MAX))
+            {
+                writeln!(writer, "cfn={}", profile.fn_names[*callee])?;
+                writeln!(writer, "calls={count} {entry_pc:#x}")?;
+                writeln!(writer, "{call_pc:#x} {}", format_costs(costs))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn record_call(&mut self, frame: &Frame) {
+        let Some((caller, call_pc)) = frame.call_site else {
+            return;
+        };
+        let (count, costs) = self
+            .calls
+            .entry((caller, call_pc, frame.fn_idx, frame.entry_pc))
+            .or_default();
+        *count += 1;
+        for (event, &total) in self.totals.iter().enumerate() {
+            let entry = frame.entry_costs.get(event).copied().unwrap_or(0);
+            add_event(costs, event, total - entry);
+        }
+    }
+
+    fn fn_at(&mut self, pc: u32) -> usize {
+        let name = self
+            .fn_bounds
+            .range(..=pc)
+            .next_back()
+            .filter(|(_, bound)| pc <= bound.end)
+            .map_or(UNKNOWN_FN, |(_, bound)| bound.name.as_str());
+        if let Some(&idx) = self.fn_indices.get(name) {
+            return idx;
+        }
+        self.fn_names.push(name.to_string());
+        self.fn_indices
+            .insert(name.to_string(), self.fn_names.len() - 1);
+        self.fn_names.len() - 1
+    }
+
+    fn event_idx(&mut self, opcode_name: &str) -> usize {
+        // Callgrind event names may not contain whitespace or separators.
+        let name: String = opcode_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if let Some(&idx) = self.event_indices.get(&name) {
+            return idx;
+        }
+        self.event_names.push(name.clone());
+        self.event_indices.insert(name, self.event_names.len() - 1);
+        self.event_names.len() - 1
+    }
+}
+
+fn add_event(costs: &mut Vec<u64>, event: usize, value: u64) {
+    if costs.len() <= event {
+        costs.resize(event + 1, 0);
+    }
+    costs[event] += value;
+}
+
+fn format_costs(costs: &[u64]) -> String {
+    costs
+        .iter()
+        .map(|cost| cost.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::exe::{FnBound, FnBounds};
+
+    use super::CallGraphProfiler;
+
+    #[test]
+    fn test_call_graph() {
+        let mut fn_bounds = FnBounds::new();
+        for (start, end, name) in [(0, 8, "main"), (100, 104, "callee")] {
+            fn_bounds.insert(
+                start,
+                FnBound {
+                    start,
+                    end,
+                    name: name.to_string(),
+                },
+            );
+        }
+        let mut profiler = CallGraphProfiler::new(fn_bounds, 4);
+        profiler.record_instruction(0, 100, "JAL");
+        profiler.record_instruction(100, 104, "MUL");
+        profiler.record_instruction(104, 4, "JALR");
+        profiler.record_instruction(4, 100, "JAL");
+        profiler.record_instruction(100, 104, "MUL");
+
+        let mut out = vec![];
+        profiler.write_callgrind(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("events: Cycles JAL MUL JALR\n"));
+        assert!(out.contains("summary: 5 2 2 1\n"));
+        assert!(out.contains("fn=callee\n0x64 2 0 2\n0x68 1 0 0 1\n"));
+        // The returned call and the one still open when execution stopped.
+        assert!(out.contains("cfn=callee\ncalls=1 0x64\n0x0 2 0 1 1\n"));
+        assert!(out.contains("cfn=callee\ncalls=1 0x64\n0x4 1 0 1 0\n"));
+    }
+}
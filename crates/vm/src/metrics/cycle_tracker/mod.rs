@@ -1,7 +1,99 @@
+use std::{collections::BTreeMap, fmt};
+
 #[derive(Clone, Debug, Default)]
 pub struct CycleTracker {
     /// Stack of span names, with most recent at the end
     stack: Vec<String>,
+    /// `stack` joined by ";", kept up to date so that recording usage does not allocate.
+    full_name: String,
+    /// Usage of each span excluding its children, keyed by full name. Usage outside of any span
+    /// is keyed by the empty string.
+    usage: BTreeMap<String, SpanUsage>,
+    /// Interned metric label values, so that emitting per-instruction metrics does not allocate.
+    #[cfg(feature = "bench-metrics")]
+    labels: std::collections::HashSet<std::sync::Arc<str>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanUsage {
+    /// Number of executed instructions per opcode.
+    pub instructions: BTreeMap<String, u64>,
+    /// Trace cells used per AIR.
+    pub cells: BTreeMap<String, u64>,
+}
+
+impl SpanUsage {
+    pub fn cycles(&self) -> u64 {
+        self.instructions.values().sum()
+    }
+
+    pub fn total_cells(&self) -> u64 {
+        self.cells.values().sum()
+    }
+
+    fn add(&mut self, other: &SpanUsage) {
+        for (opcode, count) in &other.instructions {
+            *self.instructions.entry(opcode.clone()).or_default() += count;
+        }
+        for (air_name, cells) in &other.cells {
+            *self.cells.entry(air_name.clone()).or_default() += cells;
+        }
+    }
+}
+
+/// A cycle tracker span with the usage of the span and all of its children.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanNode {
+    pub name: String,
+    pub usage: SpanUsage,
+    pub children: Vec<SpanNode>,
+}
+
+impl SpanNode {
+    fn child_mut(&mut self, name: &str) -> &mut SpanNode {
+        let idx = match self.children.iter().position(|child| child.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.children.push(SpanNode {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[idx]
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        writeln!(
+            f,
+            "{indent}{}: {} cycles, {} cells",
+            self.name,
+            self.usage.cycles(),
+            self.usage.total_cells()
+        )?;
+        let mut instructions: Vec<_> = self.usage.instructions.iter().collect();
+        instructions.sort_by(|a, b| b.1.cmp(a.1));
+        for (opcode, count) in instructions {
+            writeln!(f, "{indent}  | opcode {opcode}: {count}")?;
+        }
+        let mut cells: Vec<_> = self.usage.cells.iter().collect();
+        cells.sort_by(|a, b| b.1.cmp(a.1));
+        for (air_name, count) in cells {
+            writeln!(f, "{indent}  | cells {air_name}: {count}")?;
+        }
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SpanNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
 }
 
 impl CycleTracker {
@@ -13,6 +105,7 @@ impl CycleTracker {
     /// If a span already exists for the given name, it ends the existing span and pushes a new one to the vec.
     pub fn start(&mut self, name: String) {
         self.stack.push(name);
+        self.update_full_name();
     }
 
     /// Ends the cycle tracker span for the given name.
@@ -20,50 +113,161 @@ impl CycleTracker {
     pub fn end(&mut self, name: String) {
         let stack_top = self.stack.pop();
         assert_eq!(stack_top.unwrap(), name, "Stack top does not match name");
+        self.update_full_name();
     }
 
     /// Ends the current cycle tracker span.
     pub fn force_end(&mut self) {
         self.stack.pop();
+        self.update_full_name();
+    }
+
+    fn update_full_name(&mut self) {
+        self.full_name = self.stack.join(";");
     }
 
     /// Get full name of span with all parent names separated by ";" in flamegraph format
     pub fn get_full_name(&self) -> String {
-        self.stack.join(";")
+        self.full_name.clone()
+    }
+
+    /// Attributes an executed instruction to the current span. Only allocates the first time an
+    /// opcode is seen in a span.
+    pub fn record_instruction(&mut self, opcode: &str) {
+        *counter_mut(&mut self.span_usage_mut().instructions, opcode) += 1;
+    }
+
+    /// Attributes trace cells used by an AIR to the current span.
+    pub fn record_cells(&mut self, air_name: &str, cells: usize) {
+        *counter_mut(&mut self.span_usage_mut().cells, air_name) += cells as u64;
+    }
+
+    fn span_usage_mut(&mut self) -> &mut SpanUsage {
+        if !self.usage.contains_key(&self.full_name) {
+            self.usage
+                .insert(self.full_name.clone(), SpanUsage::default());
+        }
+        self.usage.get_mut(&self.full_name).unwrap()
+    }
+
+    /// Tree of all spans seen so far. The root is unnamed and holds the whole execution.
+    pub fn span_tree(&self) -> SpanNode {
+        let mut root = SpanNode::default();
+        for (full_name, usage) in &self.usage {
+            root.usage.add(usage);
+            if full_name.is_empty() {
+                continue;
+            }
+            let mut node = &mut root;
+            for name in full_name.split(';') {
+                node = node.child_mut(name);
+                node.usage.add(usage);
+            }
+        }
+        root
     }
 }
 
+/// Counter for `key` in `counters`, looked up without allocating if it already exists.
+fn counter_mut<'a>(counters: &'a mut BTreeMap<String, u64>, key: &str) -> &'a mut u64 {
+    if !counters.contains_key(key) {
+        counters.insert(key.to_string(), 0);
+    }
+    counters.get_mut(key).unwrap()
+}
+
 #[cfg(feature = "bench-metrics")]
 mod emit {
+    use std::{collections::HashSet, sync::Arc};
+
     use metrics::counter;
 
     use super::CycleTracker;
 
+    /// Shared label value for `value`, only allocated the first time it is seen.
+    fn intern(labels: &mut HashSet<Arc<str>>, value: &str) -> Arc<str> {
+        if let Some(label) = labels.get(value) {
+            return label.clone();
+        }
+        let label: Arc<str> = value.into();
+        labels.insert(label.clone());
+        label
+    }
+
     impl CycleTracker {
-        pub fn increment_opcode(&self, (dsl_ir, opcode): &(Option<String>, String)) {
+        pub fn increment_opcode(&mut self, (dsl_ir, opcode): &(Option<String>, String)) {
+            self.record_instruction(opcode);
             let labels = [
-                ("opcode", opcode.clone()),
-                ("dsl_ir", dsl_ir.clone().unwrap_or_default()),
-                ("cycle_tracker_span", self.get_full_name()),
+                ("opcode", intern(&mut self.labels, opcode)),
+                (
+                    "dsl_ir",
+                    intern(&mut self.labels, dsl_ir.as_deref().unwrap_or_default()),
+                ),
+                (
+                    "cycle_tracker_span",
+                    intern(&mut self.labels, &self.full_name),
+                ),
             ];
             counter!("frequency", &labels).increment(1u64);
         }
 
         pub fn increment_cells_used(
-            &self,
+            &mut self,
             (dsl_ir, opcode, air_name): &(Option<String>, String, String),
             trace_cells_used: usize,
         ) {
             if trace_cells_used == 0 {
                 return;
             }
+            self.record_cells(air_name, trace_cells_used);
             let labels = [
-                ("air_name", air_name.clone()),
-                ("opcode", opcode.clone()),
-                ("dsl_ir", dsl_ir.clone().unwrap_or_default()),
-                ("cycle_tracker_span", self.get_full_name()),
+                ("air_name", intern(&mut self.labels, air_name)),
+                ("opcode", intern(&mut self.labels, opcode)),
+                (
+                    "dsl_ir",
+                    intern(&mut self.labels, dsl_ir.as_deref().unwrap_or_default()),
+                ),
+                (
+                    "cycle_tracker_span",
+                    intern(&mut self.labels, &self.full_name),
+                ),
             ];
             counter!("cells_used", &labels).increment(trace_cells_used as u64);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CycleTracker;
+
+    #[test]
+    fn test_span_tree() {
+        let mut tracker = CycleTracker::new();
+        tracker.record_instruction("ADD");
+        tracker.start("outer".to_string());
+        tracker.record_instruction("ADD");
+        tracker.start("inner".to_string());
+        tracker.record_instruction("MUL");
+        tracker.record_cells("FieldArithmetic", 10);
+        tracker.end("inner".to_string());
+        tracker.end("outer".to_string());
+
+        let root = tracker.span_tree();
+        assert_eq!(root.usage.cycles(), 3);
+        let outer = &root.children[0];
+        assert_eq!(outer.name, "outer");
+        assert_eq!(outer.usage.cycles(), 2);
+        assert_eq!(outer.usage.cells["FieldArithmetic"], 10);
+        let inner = &outer.children[0];
+        assert_eq!(inner.name, "inner");
+        assert_eq!(inner.usage.instructions["MUL"], 1);
+        assert!(!inner.usage.instructions.contains_key("ADD"));
+
+        tracker.start("outer".to_string());
+        tracker.start("inner".to_string());
+        assert_eq!(tracker.get_full_name(), "outer;inner");
+        tracker.force_end();
+        assert_eq!(tracker.get_full_name(), "outer");
+    }
+}
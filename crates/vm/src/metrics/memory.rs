@@ -0,0 +1,65 @@
+//! Peak memory of proving phases.
+//!
+//! Peak resident set size is read from the kernel rather than tracked by the allocator, so it
+//! works with any of the `mimalloc`, `jemalloc` or system allocators. The peak is that of the
+//! whole process: work running concurrently with a phase, on other threads, counts towards the
+//! phase. Only Linux is supported; elsewhere no measurement is returned.
+
+/// Peak resident set size of the process since it started or since the last [reset_peak_rss].
+#[cfg(target_os = "linux")]
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+/// Peak resident set size is not measured on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn peak_rss_bytes() -> Option<u64> {
+    None
+}
+
+/// Resets the peak resident set size to the current one. This is process-wide, so measurements
+/// must not overlap.
+#[cfg(target_os = "linux")]
+pub fn reset_peak_rss() {
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Peak resident set size is not measured on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn reset_peak_rss() {}
+
+/// Runs `f` and returns the peak resident set size of the process reached while it ran.
+pub fn measure_peak_rss<R>(f: impl FnOnce() -> R) -> (R, Option<u64>) {
+    reset_peak_rss();
+    let res = f();
+    (res, peak_rss_bytes())
+}
+
+/// Runs the proving phase `f`. With `bench-metrics`, the peak resident set size of the process
+/// reached during `f` is emitted as the `peak_rss_bytes` gauge with labels `phase` and
+/// `scope = "process"`. It is not the memory allocated by `f` alone.
+pub fn track_peak_rss<R>(phase: &'static str, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "bench-metrics")]
+    {
+        let (res, peak) = measure_peak_rss(f);
+        if let Some(peak) = peak {
+            metrics::gauge!("peak_rss_bytes", "phase" => phase, "scope" => "process")
+                .set(peak as f64);
+        }
+        res
+    }
+    #[cfg(not(feature = "bench-metrics"))]
+    {
+        let _ = phase;
+        f()
+    }
+}
@@ -2,7 +2,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+pub mod air_stats;
+pub mod call_graph;
 pub mod cycle_tracker;
+pub mod memory;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct VmMetrics {
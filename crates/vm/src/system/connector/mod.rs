@@ -25,8 +25,7 @@ use crate::{
 #[cfg(test)]
 mod tests;
 
-/// When a program hasn't terminated. There is no constraints on the exit code.
-/// But we will use this value when generating the proof.
+/// The exit code of a segment which hasn't terminated.
 pub const DEFAULT_SUSPEND_EXIT_CODE: u32 = 42;
 
 #[derive(Debug, Clone, Copy)]
@@ -42,8 +41,8 @@ pub struct VmConnectorPvs<F> {
     pub initial_pc: F,
     /// The final PC of this segment.
     pub final_pc: F,
-    /// The exit code of the whole program. 0 means exited normally. This is only meaningful when
-    /// `is_terminate` is 1.
+    /// The exit code of the whole program. 0 means exited normally. When `is_terminate` is 0,
+    /// this is [DEFAULT_SUSPEND_EXIT_CODE].
     pub exit_code: F,
     /// Whether the whole program is terminated. 0 means not terminated. 1 means terminated.
     /// Only the last segment of an execution can have `is_terminate` = 1.
@@ -116,6 +115,12 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
         builder
             .when_transition()
             .assert_eq(end.is_terminate, is_terminate);
+        builder.assert_bool(is_terminate);
+        // Pin the exit code of a suspended segment so it is meaningful for every segment.
+        builder.when_ne(is_terminate, AB::Expr::ONE).assert_eq(
+            exit_code,
+            AB::Expr::from_canonical_u32(DEFAULT_SUSPEND_EXIT_CODE),
+        );
 
         self.execution_bus.execute(
             builder,
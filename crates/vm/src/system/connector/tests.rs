@@ -71,7 +71,7 @@ fn test_impl(
     let engine =
         BabyBearPoseidon2Engine::new(standard_fri_params_with_100_bits_conjectured_security(3));
     let vm = VirtualMachine::new(engine, vm_config.clone());
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
 
     {
         let instructions = vec![Instruction::from_isize(
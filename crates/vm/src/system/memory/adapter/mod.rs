@@ -68,6 +68,13 @@ impl<F> AccessAdapterInventory<F> {
             chip.set_override_trace_heights(oh);
         }
     }
+    /// Stop keeping records, for execution that is never proven. The trace heights still count
+    /// the skipped records, but the traces can no longer be generated.
+    pub fn skip_records(&mut self) {
+        for chip in self.chips.iter_mut() {
+            chip.skip_records();
+        }
+    }
     pub fn add_record(&mut self, record: AccessAdapterRecord<F>) {
         let n = record.data.len();
         let idx = log2_strict_usize(n) - 1;
@@ -106,9 +113,17 @@ impl<F> AccessAdapterInventory<F> {
         F: PrimeField32,
         Domain<SC>: PolynomialSpace<Val = F>,
     {
-        self.chips
-            .into_iter()
-            .map(|chip| chip.generate_air_proof_input())
+        // The adapters only share the range checker, whose multiplicities are atomic, so each
+        // trace is generated in its own task.
+        let airs: Vec<Arc<dyn AnyRap<SC>>> = self.chips.iter().map(|chip| chip.air()).collect();
+        let traces: Vec<_> = self
+            .chips
+            .into_par_iter()
+            .map(|chip| chip.generate_trace())
+            .collect();
+        airs.into_iter()
+            .zip(traces)
+            .map(|(air, trace)| AirProofInput::simple(air, trace, vec![]))
             .collect()
     }
 
@@ -152,6 +167,7 @@ pub struct AccessAdapterRecord<T> {
 pub trait GenericAccessAdapterChipTrait<F> {
     fn set_override_trace_heights(&mut self, overridden_height: usize);
     fn add_record(&mut self, record: AccessAdapterRecord<F>);
+    fn skip_records(&mut self);
     fn n(&self) -> usize;
     fn generate_trace(self) -> RowMajorMatrix<F>
     where
@@ -195,6 +211,9 @@ pub struct AccessAdapterChip<F, const N: usize> {
     air: AccessAdapterAir<N>,
     range_checker: Arc<VariableRangeCheckerChip>,
     records: Vec<AccessAdapterRecord<F>>,
+    /// Number of records not kept in `records`, see [AccessAdapterInventory::skip_records].
+    /// `None` while records are kept.
+    num_skipped_records: Option<usize>,
     overridden_height: Option<usize>,
 }
 impl<F, const N: usize> AccessAdapterChip<F, N> {
@@ -208,6 +227,7 @@ impl<F, const N: usize> AccessAdapterChip<F, N> {
             air: AccessAdapterAir::<N> { memory_bus, lt_air },
             range_checker,
             records: vec![],
+            num_skipped_records: None,
             overridden_height: None,
         }
     }
@@ -217,7 +237,13 @@ impl<F, const N: usize> GenericAccessAdapterChipTrait<F> for AccessAdapterChip<F
         self.overridden_height = Some(overridden_height);
     }
     fn add_record(&mut self, record: AccessAdapterRecord<F>) {
-        self.records.push(record);
+        match &mut self.num_skipped_records {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.records.push(record),
+        }
+    }
+    fn skip_records(&mut self) {
+        self.num_skipped_records.get_or_insert(0);
     }
     fn n(&self) -> usize {
         N
@@ -226,6 +252,10 @@ impl<F, const N: usize> GenericAccessAdapterChipTrait<F> for AccessAdapterChip<F
     where
         F: PrimeField32,
     {
+        assert!(
+            self.num_skipped_records.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
         let width = BaseAir::<F>::width(&self.air);
         let height = if let Some(oh) = self.overridden_height {
             assert!(
@@ -292,7 +322,7 @@ impl<F, const N: usize> ChipUsageGetter for AccessAdapterChip<F, N> {
     }
 
     fn current_trace_height(&self) -> usize {
-        self.records.len()
+        self.records.len() + self.num_skipped_records.unwrap_or(0)
     }
 
     fn trace_width(&self) -> usize {
@@ -8,6 +8,7 @@ use std::{
     sync::Arc,
 };
 
+use derivative::Derivative;
 use getset::Getters;
 pub use memory::{MemoryReadRecord, MemoryWriteRecord};
 use openvm_circuit_primitives::{
@@ -33,7 +34,10 @@ use serde::{Deserialize, Serialize};
 use self::interface::MemoryInterface;
 use super::{merkle::DirectCompressionBus, volatile::VolatileBoundaryChip};
 use crate::{
-    arch::{hasher::HasherChip, MemoryConfig},
+    arch::{
+        hasher::{Hasher, HasherChip},
+        MemoryConfig,
+    },
     system::memory::offline_checker::{
         MemoryBridge, MemoryBus, MemoryReadAuxCols, MemoryReadOrImmediateAuxCols,
         MemoryWriteAuxCols, AUX_LEN,
@@ -84,10 +88,15 @@ pub type TimestampedEquipartition<F, const N: usize> =
 /// If a key is not present in the map, then the block is uninitialized (and therefore zero).
 pub type Equipartition<F, const N: usize> = BTreeMap<(F, usize), [F; N]>;
 
-#[derive(Debug, Getters)]
+#[derive(Derivative, Getters)]
+#[derivative(Debug)]
 pub struct MemoryController<F> {
     pub memory_bus: MemoryBus,
     pub interface_chip: MemoryInterface<F>,
+    /// Hasher of the memory Merkle tree set by an extension, see [Self::set_memory_hasher].
+    /// `None` for the Poseidon2 hasher of the system.
+    #[derivative(Debug = "ignore")]
+    pub(crate) memory_hasher: Option<Box<dyn HasherChip<CHUNK, F>>>,
 
     #[getset(get = "pub")]
     pub(crate) mem_config: MemoryConfig,
@@ -235,6 +244,7 @@ impl<F: PrimeField32> MemoryController<F> {
                     range_checker.clone(),
                 ),
             },
+            memory_hasher: None,
             memory: Memory::new(&Equipartition::<_, 1>::new()),
             access_adapters: AccessAdapterInventory::new(
                 range_checker.clone(),
@@ -270,13 +280,19 @@ impl<F: PrimeField32> MemoryController<F> {
                 merkle_bus,
                 compression_bus,
             ),
-            merkle_chip: MemoryMerkleChip::new(memory_dims, merkle_bus, compression_bus),
+            merkle_chip: MemoryMerkleChip::new(
+                memory_dims,
+                merkle_bus,
+                compression_bus,
+                mem_config.merkle_arity,
+            ),
             initial_memory,
         };
         Self {
             memory_bus,
             mem_config,
             interface_chip,
+            memory_hasher: None,
             memory,
             access_adapters: AccessAdapterInventory::new(
                 range_checker.clone(),
@@ -333,6 +349,47 @@ impl<F: PrimeField32> MemoryController<F> {
         }
     }
 
+    /// Sets the hasher of the memory Merkle tree, for a [MemoryMerkleHasher] other than Poseidon2.
+    /// The extension setting it adds the chip receiving the compressions on the direct compression
+    /// bus, which the hasher records its compressions for. It is used instead of the system's
+    /// Poseidon2 chip to finalize persistent memory.
+    ///
+    /// [MemoryMerkleHasher]: crate::arch::MemoryMerkleHasher
+    pub fn set_memory_hasher(&mut self, hasher: Box<dyn HasherChip<CHUNK, F>>) {
+        assert!(
+            self.continuation_enabled(),
+            "a memory hasher needs persistent memory"
+        );
+        self.memory_hasher = Some(hasher);
+    }
+
+    /// Returns the Merkle root of the initial memory if persistent. This is the initial root the
+    /// memory Merkle AIR exposes as a public value.
+    pub fn initial_memory_root(&self, hasher: &impl Hasher<CHUNK, F>) -> Option<[F; CHUNK]> {
+        match &self.interface_chip {
+            MemoryInterface::Volatile { .. } => None,
+            MemoryInterface::Persistent {
+                merkle_chip,
+                initial_memory,
+                ..
+            } => Some(
+                MemoryNode::tree_from_memory(
+                    merkle_chip.air.memory_dimensions,
+                    initial_memory,
+                    hasher,
+                )
+                .hash(),
+            ),
+        }
+    }
+
+    /// Stop logging accesses for the access adapters, for execution that is never proven. Touched
+    /// addresses are still tracked, since they give the boundary and Merkle trace heights and the
+    /// final memory.
+    pub fn skip_access_adapter_records(&mut self) {
+        self.access_adapters.skip_records();
+    }
+
     pub fn memory_bridge(&self) -> MemoryBridge {
         MemoryBridge::new(
             self.memory_bus,
@@ -574,6 +631,11 @@ impl<F: PrimeField32> MemoryController<F> {
         num_airs
     }
 
+    /// Return the number of access adapter AIRs, which are the last AIRs of the memory controller.
+    pub fn num_access_adapters(&self) -> usize {
+        self.access_adapters.num_access_adapters()
+    }
+
     pub fn air_names(&self) -> Vec<String> {
         let mut air_names = vec!["Boundary".to_string()];
         if self.continuation_enabled() {
@@ -1,27 +1,36 @@
-use std::{borrow::Borrow, iter};
+use std::{array, borrow::Borrow};
 
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
     p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir},
     p3_field::{AbstractField, Field},
     p3_matrix::Matrix,
+    p3_util::log2_strict_usize,
     rap::{BaseAirWithPublicValues, PartitionedBaseAir},
 };
 
 use super::{DirectCompressionBus, MemoryMerkleBus};
 use crate::system::memory::merkle::{MemoryDimensions, MemoryMerkleCols, MemoryMerklePvs};
 
+/// Expands each touched node of the memory Merkle tree into its `arity` children. The tree of
+/// arity `2^k` has the same hashes as the binary tree, but only every `k`-th level of it has
+/// nodes, and a row compresses the `arity - 1` nodes of the binary subtree between a parent and
+/// its children. A higher arity needs fewer rows and Merkle bus interactions where neighbouring
+/// leaves are touched, at the cost of wider rows.
 #[derive(Clone, Debug)]
 pub struct MemoryMerkleAir<const CHUNK: usize> {
     pub memory_dimensions: MemoryDimensions,
     pub merkle_bus: MemoryMerkleBus,
     pub compression_bus: DirectCompressionBus,
+    /// Number of children of a node: 2, 4 or 8. The address space and address heights of
+    /// `memory_dimensions` are multiples of its logarithm.
+    pub arity: usize,
 }
 
 impl<const CHUNK: usize, F: Field> PartitionedBaseAir<F> for MemoryMerkleAir<CHUNK> {}
 impl<const CHUNK: usize, F: Field> BaseAir<F> for MemoryMerkleAir<CHUNK> {
     fn width(&self) -> usize {
-        MemoryMerkleCols::<F, CHUNK>::width()
+        self.hashes_offset() + (2 * self.arity - 1) * CHUNK + self.arity
     }
 }
 impl<const CHUNK: usize, F: Field> BaseAirWithPublicValues<F> for MemoryMerkleAir<CHUNK> {
@@ -35,9 +44,11 @@ impl<const CHUNK: usize, AB: InteractionBuilder + AirBuilderWithPublicValues> Ai
 {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        let (local, next) = (main.row_slice(0), main.row_slice(1));
-        let local: &MemoryMerkleCols<_, CHUNK> = (*local).borrow();
-        let next: &MemoryMerkleCols<_, CHUNK> = (*next).borrow();
+        let (local_slice, next_slice) = (main.row_slice(0), main.row_slice(1));
+        let (local_row, next_row): (&[AB::Var], &[AB::Var]) = (&local_slice, &next_slice);
+        let local: &MemoryMerkleCols<_> = local_row[..self.hashes_offset()].borrow();
+        let next: &MemoryMerkleCols<_> = next_row[..self.hashes_offset()].borrow();
+        let log_arity = self.log_arity();
 
         // `expand_direction` should be -1, 0, 1
         builder.assert_eq(
@@ -45,22 +56,22 @@ impl<const CHUNK: usize, AB: InteractionBuilder + AirBuilderWithPublicValues> Ai
             local.expand_direction * local.expand_direction * local.expand_direction,
         );
 
-        builder.assert_bool(local.left_direction_different);
-        builder.assert_bool(local.right_direction_different);
-
-        // if `expand_direction` != -1, then `*_direction_different` should be 0
-        builder
-            .when_ne(local.expand_direction, AB::F::NEG_ONE)
-            .assert_zero(local.left_direction_different);
-        builder
-            .when_ne(local.expand_direction, AB::F::NEG_ONE)
-            .assert_zero(local.right_direction_different);
+        for child in 0..self.arity {
+            let direction_different = self.direction_different(local_row, child);
+            builder.assert_bool(direction_different);
+            // if `expand_direction` != -1, then `direction_different` should be 0
+            builder
+                .when_ne(local.expand_direction, AB::F::NEG_ONE)
+                .assert_zero(direction_different);
+        }
 
         // rows should be sorted in descending order
         // independently by `parent_height`, `height_section`, `is_root`
-        builder
-            .when_transition()
-            .assert_bool(local.parent_height - next.parent_height);
+        // and `parent_height` decreases by the height of a row, `log_arity`, at a time
+        let height_decrease: AB::Expr = local.parent_height - next.parent_height;
+        builder.when_transition().assert_zero(
+            height_decrease.clone() * (height_decrease - AB::Expr::from_canonical_usize(log_arity)),
+        );
         builder
             .when_transition()
             .assert_bool(local.height_section - next.height_section);
@@ -76,12 +87,13 @@ impl<const CHUNK: usize, AB: InteractionBuilder + AirBuilderWithPublicValues> Ai
         // row with least height should have `height_section` = 0, `is_root` = 0
         builder.when_last_row().assert_zero(local.height_section);
         builder.when_last_row().assert_zero(local.is_root);
-        // `height_section` changes from 0 to 1 only when `parent_height` changes from `address_height` to `address_height` + 1
+        // `height_section` changes from 0 to 1 only when `parent_height` changes from
+        // `address_height` to `address_height` + `log_arity`
         builder
             .when_transition()
             .when_ne(
                 local.parent_height,
-                AB::F::from_canonical_usize(self.memory_dimensions.address_height + 1),
+                AB::F::from_canonical_usize(self.memory_dimensions.address_height + log_arity),
             )
             .assert_eq(local.height_section, next.height_section);
         builder
@@ -109,25 +121,46 @@ impl<const CHUNK: usize, AB: InteractionBuilder + AirBuilderWithPublicValues> Ai
             initial_root,
             final_root,
         } = builder.public_values().borrow();
+        let local_parent_hash = self.hash(local_row, 0);
+        let next_parent_hash = self.hash(next_row, 0);
         for i in 0..CHUNK {
             builder
                 .when_first_row()
-                .assert_eq(local.parent_hash[i], initial_root[i]);
+                .assert_eq(local_parent_hash[i], initial_root[i]);
             builder
                 .when_first_row()
-                .assert_eq(next.parent_hash[i], final_root[i]);
+                .assert_eq(next_parent_hash[i], final_root[i]);
         }
 
-        self.eval_interactions(builder, local);
+        self.eval_interactions(builder, local_row);
     }
 }
 
 impl<const CHUNK: usize> MemoryMerkleAir<CHUNK> {
-    pub fn eval_interactions<AB: InteractionBuilder>(
-        &self,
-        builder: &mut AB,
-        local: &MemoryMerkleCols<AB::Var, CHUNK>,
-    ) {
+    pub fn log_arity(&self) -> usize {
+        log2_strict_usize(self.arity)
+    }
+
+    /// Offset of the hashes in a row, after the [MemoryMerkleCols].
+    pub fn hashes_offset(&self) -> usize {
+        MemoryMerkleCols::<u8>::width()
+    }
+
+    /// Hash of the node of a row's subtree with the given 0-indexed heap index, so that the
+    /// parent is node 0 and the children are the nodes `arity - 1..2 * arity - 1`.
+    pub fn hash<T: Copy>(&self, row: &[T], node: usize) -> [T; CHUNK] {
+        let offset = self.hashes_offset() + node * CHUNK;
+        array::from_fn(|i| row[offset + i])
+    }
+
+    pub fn direction_different<T: Copy>(&self, row: &[T], child: usize) -> T {
+        row[self.hashes_offset() + (2 * self.arity - 1) * CHUNK + child]
+    }
+
+    pub fn eval_interactions<AB: InteractionBuilder>(&self, builder: &mut AB, row: &[AB::Var]) {
+        let local: &MemoryMerkleCols<_> = row[..self.hashes_offset()].borrow();
+        let arity = AB::Expr::from_canonical_usize(self.arity);
+
         // interaction does not occur for first two rows;
         // for those, parent hash value comes from public values
         builder.push_send(
@@ -139,47 +172,47 @@ impl<const CHUNK: usize> MemoryMerkleAir<CHUNK> {
                 local.parent_address_label.into(),
             ]
             .into_iter()
-            .chain(local.parent_hash.into_iter().map(Into::into)),
+            .chain(self.hash(row, 0).into_iter().map(Into::into)),
             // count can probably be made degree 1 if necessary
             (AB::Expr::ONE - local.is_root) * local.expand_direction,
         );
 
-        builder.push_receive(
-            self.merkle_bus.0,
-            [
-                local.expand_direction + (local.left_direction_different * AB::F::TWO),
-                local.parent_height - AB::F::ONE,
-                local.parent_as_label * (AB::Expr::ONE + local.height_section),
-                local.parent_address_label * (AB::Expr::TWO - local.height_section),
-            ]
-            .into_iter()
-            .chain(local.left_child_hash.into_iter().map(Into::into)),
-            local.expand_direction.into(),
-        );
-
-        builder.push_receive(
-            self.merkle_bus.0,
-            [
-                local.expand_direction + (local.right_direction_different * AB::F::TWO),
-                local.parent_height - AB::F::ONE,
-                (local.parent_as_label * (AB::Expr::ONE + local.height_section))
-                    + local.height_section,
-                (local.parent_address_label * (AB::Expr::TWO - local.height_section))
-                    + (AB::Expr::ONE - local.height_section),
-            ]
-            .into_iter()
-            .chain(local.right_child_hash.into_iter().map(Into::into)),
-            local.expand_direction.into(),
-        );
+        // the labels of the children are `arity * label + child` in the expanded section
+        let as_label_base = local.parent_as_label
+            * (AB::Expr::ONE + (arity.clone() - AB::Expr::ONE) * local.height_section);
+        let address_label_base = local.parent_address_label
+            * (arity.clone() - (arity - AB::Expr::ONE) * local.height_section);
+        for child in 0..self.arity {
+            let child_idx = AB::Expr::from_canonical_usize(child);
+            builder.push_receive(
+                self.merkle_bus.0,
+                [
+                    local.expand_direction + self.direction_different(row, child) * AB::F::TWO,
+                    local.parent_height - AB::F::from_canonical_usize(self.log_arity()),
+                    as_label_base.clone() + child_idx.clone() * local.height_section,
+                    address_label_base.clone() + child_idx * (AB::Expr::ONE - local.height_section),
+                ]
+                .into_iter()
+                .chain(
+                    self.hash(row, self.arity - 1 + child)
+                        .into_iter()
+                        .map(Into::into),
+                ),
+                local.expand_direction.into(),
+            );
+        }
 
-        let compress_fields = iter::empty()
-            .chain(local.left_child_hash)
-            .chain(local.right_child_hash)
-            .chain(local.parent_hash);
-        builder.push_send(
-            self.compression_bus.0,
-            compress_fields,
-            local.expand_direction * local.expand_direction,
-        );
+        for node in 0..self.arity - 1 {
+            let compress_fields = self
+                .hash(row, 2 * node + 1)
+                .into_iter()
+                .chain(self.hash(row, 2 * node + 2))
+                .chain(self.hash(row, node));
+            builder.push_send(
+                self.compression_bus.0,
+                compress_fields,
+                local.expand_direction * local.expand_direction,
+            );
+        }
     }
 }
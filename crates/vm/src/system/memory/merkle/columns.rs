@@ -1,8 +1,14 @@
 use openvm_circuit_primitives_derive::AlignedBorrow;
 
+/// The leading columns of a row of the [MemoryMerkleAir](super::MemoryMerkleAir), which expands
+/// a parent node into its `arity` children. They are followed by the `2 * arity - 1` hashes of the
+/// binary subtree between the parent and its children, in heap order: the parent hash first and
+/// the child hashes last. After the hashes there is one `direction_different` flag per child,
+/// which indicates whether `expand_direction` is different from origin for that child. The flags
+/// must be 0 when `expand_direction` != -1.
 #[derive(Debug, AlignedBorrow)]
 #[repr(C)]
-pub struct MemoryMerkleCols<T, const CHUNK: usize> {
+pub struct MemoryMerkleCols<T> {
     // `expand_direction` =  1 corresponds to initial memory state
     // `expand_direction` = -1 corresponds to final memory state
     // `expand_direction` =  0 corresponds to irrelevant row (all interactions multiplicity 0)
@@ -16,15 +22,6 @@ pub struct MemoryMerkleCols<T, const CHUNK: usize> {
 
     pub parent_as_label: T,
     pub parent_address_label: T,
-
-    pub parent_hash: [T; CHUNK],
-    pub left_child_hash: [T; CHUNK],
-    pub right_child_hash: [T; CHUNK],
-
-    // indicate whether `expand_direction` is different from origin
-    // when `expand_direction` != -1, must be 0
-    pub left_direction_different: T,
-    pub right_direction_different: T,
 }
 
 #[derive(Debug, Clone, Copy, AlignedBorrow)]
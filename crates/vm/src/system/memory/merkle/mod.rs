@@ -24,20 +24,29 @@ pub struct MemoryMerkleChip<const CHUNK: usize, F> {
 }
 #[derive(Debug)]
 struct FinalState<const CHUNK: usize, F> {
-    rows: Vec<MemoryMerkleCols<F, CHUNK>>,
+    rows: Vec<Vec<F>>,
     init_root: [F; CHUNK],
     final_root: [F; CHUNK],
 }
 
 impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
     /// `compression_bus` is the bus for direct (no-memory involved) interactions to call the cryptographic compression function.
+    /// `arity` is the number of children of a node, see [MemoryMerkleAir].
     pub fn new(
         memory_dimensions: MemoryDimensions,
         merkle_bus: MemoryMerkleBus,
         compression_bus: DirectCompressionBus,
+        arity: usize,
     ) -> Self {
+        assert!(
+            matches!(arity, 2 | 4 | 8),
+            "arity must be 2, 4 or 8, got {arity}"
+        );
+        let log_arity = arity.trailing_zeros() as usize;
         assert!(memory_dimensions.as_height > 0);
         assert!(memory_dimensions.address_height > 0);
+        assert_eq!(memory_dimensions.as_height % log_arity, 0);
+        assert_eq!(memory_dimensions.address_height % log_arity, 0);
         let mut touched_nodes = FxHashSet::default();
         touched_nodes.insert((memory_dimensions.overall_height(), 0, 0));
         Self {
@@ -45,6 +54,7 @@ impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
                 memory_dimensions,
                 merkle_bus,
                 compression_bus,
+                arity,
             },
             touched_nodes,
             num_touched_nonleaves: 1,
@@ -62,10 +72,11 @@ impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
             if height != 0 {
                 self.num_touched_nonleaves += 1;
             }
+            let (arity, log_arity) = (self.air.arity, self.air.log_arity());
             if height >= self.air.memory_dimensions.address_height {
-                self.touch_node(height + 1, as_label / 2, address_label);
+                self.touch_node(height + log_arity, as_label / arity, address_label);
             } else {
-                self.touch_node(height + 1, as_label, address_label / 2);
+                self.touch_node(height + log_arity, as_label, address_label / arity);
             }
         }
     }
@@ -1,12 +1,13 @@
 use std::{
     array,
-    borrow::BorrowMut,
+    borrow::Borrow,
     collections::{BTreeMap, BTreeSet, HashSet},
     sync::Arc,
 };
 
 use openvm_stark_backend::{
     interaction::InteractionType,
+    p3_air::BaseAir,
     p3_field::{AbstractField, PrimeField32},
     p3_matrix::dense::RowMajorMatrix,
     prover::types::AirProofInput,
@@ -39,6 +40,7 @@ const COMPRESSION_BUS: DirectCompressionBus = DirectCompressionBus(POSEIDON2_DIR
 
 fn test<const CHUNK: usize>(
     memory_dimensions: MemoryDimensions,
+    arity: usize,
     initial_memory: &Equipartition<BabyBear, CHUNK>,
     touched_labels: BTreeSet<(BabyBear, usize)>,
     final_memory: &Equipartition<BabyBear, CHUNK>,
@@ -73,7 +75,7 @@ fn test<const CHUNK: usize>(
         MemoryNode::tree_from_memory(memory_dimensions, final_memory, &hash_test_chip);
 
     let mut chip =
-        MemoryMerkleChip::<CHUNK, _>::new(memory_dimensions, merkle_bus, COMPRESSION_BUS);
+        MemoryMerkleChip::<CHUNK, _>::new(memory_dimensions, merkle_bus, COMPRESSION_BUS, arity);
     for &(address_space, label) in touched_labels.iter() {
         for i in 0..CHUNK {
             chip.touch_address(
@@ -162,6 +164,7 @@ fn test<const CHUNK: usize>(
 }
 
 fn random_test<const CHUNK: usize>(
+    arity: usize,
     height: usize,
     max_value: usize,
     mut num_initial_addresses: usize,
@@ -206,10 +209,11 @@ fn random_test<const CHUNK: usize>(
 
     test::<CHUNK>(
         MemoryDimensions {
-            as_height: 1,
+            as_height: arity.trailing_zeros() as usize,
             address_height: height,
             as_offset: 1,
         },
+        arity,
         &initial_memory,
         touched_labels,
         &final_memory,
@@ -218,17 +222,32 @@ fn random_test<const CHUNK: usize>(
 
 #[test]
 fn expand_test_0() {
-    random_test::<DEFAULT_CHUNK>(2, 3000, 2, 3);
+    random_test::<DEFAULT_CHUNK>(2, 2, 3000, 2, 3);
 }
 
 #[test]
 fn expand_test_1() {
-    random_test::<DEFAULT_CHUNK>(10, 3000, 400, 30);
+    random_test::<DEFAULT_CHUNK>(2, 10, 3000, 400, 30);
 }
 
 #[test]
 fn expand_test_2() {
-    random_test::<DEFAULT_CHUNK>(3, 3000, 3, 2);
+    random_test::<DEFAULT_CHUNK>(2, 3, 3000, 3, 2);
+}
+
+#[test]
+fn expand_test_arity_4() {
+    random_test::<DEFAULT_CHUNK>(4, 10, 3000, 400, 30);
+}
+
+#[test]
+fn expand_test_arity_8() {
+    random_test::<DEFAULT_CHUNK>(8, 9, 3000, 400, 30);
+}
+
+#[test]
+fn expand_test_arity_8_sparse() {
+    random_test::<DEFAULT_CHUNK>(8, 6, 3000, 3, 2);
 }
 
 #[test]
@@ -251,6 +270,7 @@ fn expand_test_no_accesses() {
         memory_dimensions,
         MemoryMerkleBus(MEMORY_MERKLE_BUS),
         COMPRESSION_BUS,
+        2,
     );
 
     chip.finalize(&tree, &memory, &mut hash_test_chip);
@@ -283,17 +303,19 @@ fn expand_test_negative() {
         memory_dimensions,
         MemoryMerkleBus(MEMORY_MERKLE_BUS),
         COMPRESSION_BUS,
+        2,
     );
 
     chip.finalize(&tree, &memory, &mut hash_test_chip);
+    let air = chip.air.clone();
     let mut chip_api = chip.generate_air_proof_input();
     {
         let trace = chip_api.raw.common_main.as_mut().unwrap();
+        let direction_offset = BaseAir::<BabyBear>::width(&air) - air.arity;
         for row in trace.rows_mut() {
-            let row: &mut MemoryMerkleCols<_, DEFAULT_CHUNK> = row.borrow_mut();
-            if row.expand_direction == BabyBear::NEG_ONE {
-                row.left_direction_different = BabyBear::ZERO;
-                row.right_direction_different = BabyBear::ZERO;
+            let cols: &MemoryMerkleCols<_> = row[..air.hashes_offset()].borrow();
+            if cols.expand_direction == BabyBear::NEG_ONE {
+                row[direction_offset..].fill(BabyBear::ZERO);
             }
         }
     }
@@ -1,7 +1,12 @@
-use std::{borrow::BorrowMut, cmp::Reverse, sync::Arc};
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Reverse,
+    sync::Arc,
+};
 
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
     p3_field::{AbstractField, PrimeField32},
     p3_matrix::dense::RowMajorMatrix,
     prover::types::AirProofInput,
@@ -13,8 +18,7 @@ use rustc_hash::FxHashSet;
 use crate::{
     arch::hasher::HasherChip,
     system::memory::{
-        manager::dimensions::MemoryDimensions,
-        merkle::{FinalState, MemoryMerkleChip, MemoryMerkleCols},
+        merkle::{FinalState, MemoryMerkleAir, MemoryMerkleChip, MemoryMerkleCols},
         tree::MemoryNode::{self, NonLeaf},
         Equipartition,
     },
@@ -32,19 +36,19 @@ impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
         // shouldn't be a leaf because
         // trace generation will expect an interaction from MemoryInterfaceChip in that case
         if self.touched_nodes.len() == 1 {
-            self.touch_node(1, 0, 0);
+            self.touch_node(self.air.log_arity(), 0, 0);
         }
 
         let mut rows = vec![];
         let mut tree_helper = TreeHelper {
-            memory_dimensions: self.air.memory_dimensions,
+            air: &self.air,
             final_memory,
             touched_nodes: &self.touched_nodes,
             trace_rows: &mut rows,
         };
         let final_tree = tree_helper.recur(
             self.air.memory_dimensions.overall_height(),
-            initial_tree,
+            &Arc::new(initial_tree.clone()),
             0,
             0,
             hasher,
@@ -79,9 +83,12 @@ where
         // important that this sort be stable,
         // because we need the initial root to be first and the final root to be second
         // TODO: do we only need find all height == 0 instead of sorting?
-        rows.sort_by_key(|row| Reverse(row.parent_height));
+        rows.sort_by_key(|row| {
+            let cols: &MemoryMerkleCols<_> = row[..air.hashes_offset()].borrow();
+            Reverse(cols.parent_height.as_canonical_u32())
+        });
 
-        let width = MemoryMerkleCols::<Val<SC>, CHUNK>::width();
+        let width = BaseAir::<Val<SC>>::width(air.as_ref());
         let mut height = rows.len().next_power_of_two();
         if let Some(mut oh) = self.overridden_height {
             oh = oh.next_power_of_two();
@@ -94,7 +101,7 @@ where
         let mut trace = Val::<SC>::zero_vec(width * height);
 
         for (trace_row, row) in trace.chunks_exact_mut(width).zip(rows) {
-            *trace_row.borrow_mut() = row;
+            trace_row.copy_from_slice(&row);
         }
 
         let trace = RowMajorMatrix::new(trace, width);
@@ -112,137 +119,151 @@ impl<const CHUNK: usize, F: PrimeField32> ChipUsageGetter for MemoryMerkleChip<C
     }
 
     fn trace_width(&self) -> usize {
-        MemoryMerkleCols::<F, CHUNK>::width()
+        BaseAir::<F>::width(&self.air)
     }
 }
 
 struct TreeHelper<'a, const CHUNK: usize, F: PrimeField32> {
-    memory_dimensions: MemoryDimensions,
+    air: &'a MemoryMerkleAir<CHUNK>,
     final_memory: &'a Equipartition<F, CHUNK>,
     touched_nodes: &'a FxHashSet<(usize, usize, usize)>,
-    trace_rows: &'a mut Vec<MemoryMerkleCols<F, CHUNK>>,
+    trace_rows: &'a mut Vec<Vec<F>>,
 }
 
 impl<const CHUNK: usize, F: PrimeField32> TreeHelper<'_, CHUNK, F> {
     fn recur(
         &mut self,
         height: usize,
-        initial_node: &MemoryNode<CHUNK, F>,
+        initial_node: &Arc<MemoryNode<CHUNK, F>>,
         as_label: usize,
         address_label: usize,
         hasher: &mut impl HasherChip<CHUNK, F>,
-    ) -> MemoryNode<CHUNK, F> {
+    ) -> Arc<MemoryNode<CHUNK, F>> {
         if height == 0 {
             let address_space =
-                F::from_canonical_usize(as_label + self.memory_dimensions.as_offset);
+                F::from_canonical_usize(as_label + self.air.memory_dimensions.as_offset);
             let leaf_values = *self
                 .final_memory
                 .get(&(address_space, address_label))
                 .unwrap_or(&[F::ZERO; CHUNK]);
-            MemoryNode::new_leaf(hasher.hash(&leaf_values))
-        } else if let NonLeaf {
-            left: initial_left_node,
-            right: initial_right_node,
-            ..
-        } = initial_node.clone()
-        {
-            // Tell the hasher about this hash.
-            hasher.compress_and_record(&initial_left_node.hash(), &initial_right_node.hash());
-
-            let is_as_section = height > self.memory_dimensions.address_height;
-
-            let (left_as_label, right_as_label) = if is_as_section {
-                (2 * as_label, 2 * as_label + 1)
-            } else {
-                (as_label, as_label)
-            };
-            let (left_address_label, right_address_label) = if is_as_section {
-                (address_label, address_label)
-            } else {
-                (2 * address_label, 2 * address_label + 1)
-            };
-
-            let left_is_final =
-                !self
-                    .touched_nodes
-                    .contains(&(height - 1, left_as_label, left_address_label));
+            return Arc::new(MemoryNode::new_leaf(hasher.hash(&leaf_values)));
+        }
+        let (arity, log_arity) = (self.air.arity, self.air.log_arity());
+        let initial_subtree = subtree(initial_node, arity);
+        // Tell the hasher about the hashes of the row.
+        for node in &initial_subtree[..arity - 1] {
+            if let NonLeaf { left, right, .. } = node.as_ref() {
+                hasher.compress_and_record(&left.hash(), &right.hash());
+            }
+        }
 
-            let final_left_node = if left_is_final {
-                initial_left_node
+        let is_as_section = height > self.air.memory_dimensions.address_height;
+        let mut direction_changes = Vec::with_capacity(arity);
+        let mut final_children = Vec::with_capacity(arity);
+        for (child, initial_child) in initial_subtree[arity - 1..].iter().enumerate() {
+            let (child_as_label, child_address_label) = if is_as_section {
+                (arity * as_label + child, address_label)
             } else {
-                Arc::new(self.recur(
-                    height - 1,
-                    &initial_left_node,
-                    left_as_label,
-                    left_address_label,
-                    hasher,
-                ))
+                (as_label, arity * address_label + child)
             };
-
-            let right_is_final =
-                !self
-                    .touched_nodes
-                    .contains(&(height - 1, right_as_label, right_address_label));
-
-            let final_right_node = if right_is_final {
-                initial_right_node
+            let is_final = !self.touched_nodes.contains(&(
+                height - log_arity,
+                child_as_label,
+                child_address_label,
+            ));
+            direction_changes.push(is_final);
+            final_children.push(if is_final {
+                initial_child.clone()
             } else {
-                Arc::new(self.recur(
-                    height - 1,
-                    &initial_right_node,
-                    right_as_label,
-                    right_address_label,
+                self.recur(
+                    height - log_arity,
+                    initial_child,
+                    child_as_label,
+                    child_address_label,
                     hasher,
-                ))
-            };
-
-            let final_node = MemoryNode::new_nonleaf(final_left_node, final_right_node, hasher);
-            self.add_trace_row(height, as_label, address_label, initial_node, None);
-            self.add_trace_row(
-                height,
-                as_label,
-                address_label,
-                &final_node,
-                Some([left_is_final, right_is_final]),
-            );
-            final_node
-        } else {
-            panic!("Leaf {:?} found at nonzero height {}", initial_node, height);
+                )
+            });
         }
+
+        let final_node = merge(&final_children, hasher);
+        self.add_trace_row(height, as_label, address_label, &initial_subtree, None);
+        self.add_trace_row(
+            height,
+            as_label,
+            address_label,
+            &subtree(&final_node, arity),
+            Some(&direction_changes),
+        );
+        final_node
     }
 
-    /// Expects `node` to be NonLeaf
+    /// Expects `subtree` to be the nodes of a row in heap order.
     fn add_trace_row(
         &mut self,
         parent_height: usize,
         as_label: usize,
         address_label: usize,
-        node: &MemoryNode<CHUNK, F>,
-        direction_changes: Option<[bool; 2]>,
+        subtree: &[Arc<MemoryNode<CHUNK, F>>],
+        direction_changes: Option<&[bool]>,
     ) {
-        let [left_direction_change, right_direction_change] =
-            direction_changes.unwrap_or([false; 2]);
-        let cols = if let NonLeaf { hash, left, right } = node {
-            MemoryMerkleCols {
-                expand_direction: if direction_changes.is_none() {
-                    F::ONE
-                } else {
-                    F::NEG_ONE
-                },
-                height_section: F::from_bool(parent_height > self.memory_dimensions.address_height),
-                parent_height: F::from_canonical_usize(parent_height),
-                is_root: F::from_bool(parent_height == self.memory_dimensions.overall_height()),
-                parent_as_label: F::from_canonical_usize(as_label),
-                parent_address_label: F::from_canonical_usize(address_label),
-                parent_hash: *hash,
-                left_child_hash: left.hash(),
-                right_child_hash: right.hash(),
-                left_direction_different: F::from_bool(left_direction_change),
-                right_direction_different: F::from_bool(right_direction_change),
+        let mut row = vec![F::ZERO; BaseAir::<F>::width(self.air)];
+        let (cols, rest) = row.split_at_mut(self.air.hashes_offset());
+        let cols: &mut MemoryMerkleCols<F> = cols.borrow_mut();
+        *cols = MemoryMerkleCols {
+            expand_direction: if direction_changes.is_none() {
+                F::ONE
+            } else {
+                F::NEG_ONE
+            },
+            height_section: F::from_bool(parent_height > self.air.memory_dimensions.address_height),
+            parent_height: F::from_canonical_usize(parent_height),
+            is_root: F::from_bool(parent_height == self.air.memory_dimensions.overall_height()),
+            parent_as_label: F::from_canonical_usize(as_label),
+            parent_address_label: F::from_canonical_usize(address_label),
+        };
+        let (hashes, direction_different) = rest.split_at_mut(subtree.len() * CHUNK);
+        for (hash, node) in hashes.chunks_exact_mut(CHUNK).zip(subtree) {
+            hash.copy_from_slice(&node.hash());
+        }
+        if let Some(direction_changes) = direction_changes {
+            for (flag, &changed) in direction_different.iter_mut().zip(direction_changes) {
+                *flag = F::from_bool(changed);
             }
+        }
+        self.trace_rows.push(row);
+    }
+}
+
+/// Returns the `2 * arity - 1` nodes of the binary subtree of depth `log2(arity)` rooted at
+/// `node`, in heap order.
+fn subtree<const CHUNK: usize, F: PrimeField32>(
+    node: &Arc<MemoryNode<CHUNK, F>>,
+    arity: usize,
+) -> Vec<Arc<MemoryNode<CHUNK, F>>> {
+    let mut nodes = Vec::with_capacity(2 * arity - 1);
+    nodes.push(node.clone());
+    for i in 0..arity - 1 {
+        if let NonLeaf { left, right, .. } = nodes[i].as_ref() {
+            let (left, right) = (left.clone(), right.clone());
+            nodes.push(left);
+            nodes.push(right);
         } else {
-            panic!("trace_rows expects node = {:?} to be NonLeaf", node);
-        };
-        self.trace_rows.push(cols);
+            panic!("Leaf {:?} found above the children of a row", nodes[i]);
+        }
+    }
+    nodes
+}
+
+/// Hashes `children` into the root of the binary subtree with these leaves, recording the
+/// compressions with `hasher`.
+fn merge<const CHUNK: usize, F: PrimeField32>(
+    children: &[Arc<MemoryNode<CHUNK, F>>],
+    hasher: &mut impl HasherChip<CHUNK, F>,
+) -> Arc<MemoryNode<CHUNK, F>> {
+    if let [child] = children {
+        return child.clone();
     }
+    let (left, right) = children.split_at(children.len() / 2);
+    let (left, right) = (merge(left, hasher), merge(right, hasher));
+    Arc::new(MemoryNode::new_nonleaf(left, right, hasher))
 }
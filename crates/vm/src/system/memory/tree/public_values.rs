@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use openvm_stark_backend::{p3_field::PrimeField32, p3_util::log2_strict_usize};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
     arch::hasher::Hasher,
@@ -10,6 +11,20 @@ use crate::{
 
 pub const PUBLIC_VALUES_ADDRESS_SPACE_OFFSET: usize = 2;
 
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum UserPublicValuesProofError {
+    #[error("expected {expected} public values, got {actual}")]
+    NumPublicValuesMismatch { expected: usize, actual: usize },
+    #[error("public values do not match their commitment")]
+    PublicValuesCommitMismatch,
+    #[error("expected a merkle proof of length {expected}, got {actual}")]
+    ProofLengthMismatch { expected: usize, actual: usize },
+    #[error("merkle proof does not lead to the public values address space")]
+    PathMismatch,
+    #[error("public values commitment is not in the final memory")]
+    FinalMemoryRootMismatch,
+}
+
 /// Merkle proof for user public values in the memory state.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound(
@@ -18,7 +33,7 @@ pub const PUBLIC_VALUES_ADDRESS_SPACE_OFFSET: usize = 2;
 ))]
 pub struct UserPublicValuesProof<const CHUNK: usize, F> {
     /// Proof of the path from the root of public values to the memory root in the format of (`bit`, `hash`)
-    /// `bit`: If `bit` is true, public values are in the left child, otherwise in the right child.
+    /// `bit`: If `bit` is true, public values are in the right child, otherwise in the left child.
     /// `hash`: Hash of the sibling node.
     pub proof: Vec<(bool, [F; CHUNK])>,
     /// Raw public values. Its length should be a power of two * CHUNK.
@@ -54,6 +69,56 @@ impl<const CHUNK: usize, F: PrimeField32> UserPublicValuesProof<CHUNK, F> {
             public_values_commit,
         }
     }
+
+    /// Verifies that the public values are the ones at the start of the public values address
+    /// space of the memory with root `final_memory_root`, which should be the final memory root
+    /// of the last segment.
+    pub fn verify(
+        &self,
+        memory_dimensions: MemoryDimensions,
+        num_public_values: usize,
+        hasher: &impl Hasher<CHUNK, F>,
+        final_memory_root: [F; CHUNK],
+    ) -> Result<(), UserPublicValuesProofError> {
+        if self.public_values.len() != num_public_values {
+            return Err(UserPublicValuesProofError::NumPublicValuesMismatch {
+                expected: num_public_values,
+                actual: self.public_values.len(),
+            });
+        }
+        if hasher.merkle_root(&self.public_values) != self.public_values_commit {
+            return Err(UserPublicValuesProofError::PublicValuesCommitMismatch);
+        }
+
+        let pv_height = log2_strict_usize(num_public_values / CHUNK);
+        let proof_len = memory_dimensions.overall_height() - pv_height;
+        if self.proof.len() != proof_len {
+            return Err(UserPublicValuesProofError::ProofLengthMismatch {
+                expected: proof_len,
+                actual: self.proof.len(),
+            });
+        }
+        let pv_as = F::from_canonical_usize(
+            PUBLIC_VALUES_ADDRESS_SPACE_OFFSET + memory_dimensions.as_offset,
+        );
+        let idx_prefix = memory_dimensions.label_to_index((pv_as, 0)) >> pv_height;
+
+        let mut curr_root = self.public_values_commit;
+        for (i, (is_right, sibling_hash)) in self.proof.iter().enumerate() {
+            if *is_right != (idx_prefix & (1 << i) != 0) {
+                return Err(UserPublicValuesProofError::PathMismatch);
+            }
+            curr_root = if *is_right {
+                hasher.compress(sibling_hash, &curr_root)
+            } else {
+                hasher.compress(&curr_root, sibling_hash)
+            };
+        }
+        if curr_root != final_memory_root {
+            return Err(UserPublicValuesProofError::FinalMemoryRootMismatch);
+        }
+        Ok(())
+    }
 }
 
 fn compute_merkle_proof_to_user_public_values_root<const CHUNK: usize, F: PrimeField32>(
@@ -145,7 +210,9 @@ mod tests {
     use openvm_stark_backend::p3_field::AbstractField;
     use openvm_stark_sdk::p3_baby_bear::BabyBear;
 
-    use super::{UserPublicValuesProof, PUBLIC_VALUES_ADDRESS_SPACE_OFFSET};
+    use super::{
+        UserPublicValuesProof, UserPublicValuesProofError, PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+    };
     use crate::{
         arch::{
             hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
@@ -191,5 +258,78 @@ mod tests {
             }
         }
         assert_eq!(curr_root, final_memory_root.hash());
+        assert_eq!(
+            pv_proof.verify(
+                memory_dimensions,
+                num_public_values,
+                &hasher,
+                final_memory_root.hash()
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_public_value_tampered() {
+        let mut vm_config = SystemConfig::default();
+        vm_config.memory_config.as_height = 4;
+        vm_config.memory_config.pointer_max_bits = 5;
+        let memory_dimensions = vm_config.memory_config.memory_dimensions();
+        let pv_as = F::from_canonical_usize(
+            PUBLIC_VALUES_ADDRESS_SPACE_OFFSET + memory_dimensions.as_offset,
+        );
+        let num_public_values = 16;
+        let memory: MemoryImage<F> = [((pv_as, F::from_canonical_u32(15)), F::ONE)]
+            .into_iter()
+            .collect();
+        let final_memory = memory_image_to_equipartition(memory);
+        let hasher = vm_poseidon2_hasher();
+        let pv_proof = UserPublicValuesProof::<{ CHUNK }, F>::compute(
+            memory_dimensions,
+            num_public_values,
+            &hasher,
+            &final_memory,
+        );
+        let final_memory_root =
+            MemoryNode::tree_from_memory(memory_dimensions, &final_memory, &hasher).hash();
+        let verify = |proof: &UserPublicValuesProof<{ CHUNK }, F>| {
+            proof.verify(
+                memory_dimensions,
+                num_public_values,
+                &hasher,
+                final_memory_root,
+            )
+        };
+
+        // Changing a public value breaks the commitment.
+        let mut tampered = pv_proof.clone();
+        tampered.public_values[15] = F::TWO;
+        assert_eq!(
+            verify(&tampered),
+            Err(UserPublicValuesProofError::PublicValuesCommitMismatch)
+        );
+
+        // Recomputing the commitment does not help: it is not in the final memory.
+        tampered.public_values_commit = hasher.merkle_root(&tampered.public_values);
+        assert_eq!(
+            verify(&tampered),
+            Err(UserPublicValuesProofError::FinalMemoryRootMismatch)
+        );
+
+        // A proof of a different address space is rejected.
+        let mut tampered = pv_proof.clone();
+        let last = tampered.proof.len() - 1;
+        tampered.proof[last].0 = !tampered.proof[last].0;
+        assert_eq!(
+            verify(&tampered),
+            Err(UserPublicValuesProofError::PathMismatch)
+        );
+
+        let mut tampered = pv_proof;
+        tampered.proof.pop();
+        assert!(matches!(
+            verify(&tampered),
+            Err(UserPublicValuesProofError::ProofLengthMismatch { .. })
+        ));
     }
 }
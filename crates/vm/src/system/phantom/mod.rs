@@ -87,6 +87,9 @@ impl<AB: AirBuilder + InteractionBuilder> Air<AB> for PhantomAir {
 pub struct PhantomChip<F> {
     pub air: PhantomAir,
     pub rows: Vec<PhantomCols<F>>,
+    /// Number of rows not kept in `rows`, see [InstructionExecutor::skip_records]. `None` while
+    /// rows are kept.
+    num_skipped_rows: Option<usize>,
     memory: MemoryControllerRef<F>,
     streams: OnceLock<Arc<Mutex<Streams<F>>>>,
     phantom_executors: FxHashMap<PhantomDiscriminant, Box<dyn PhantomSubExecutor<F>>>,
@@ -105,6 +108,7 @@ impl<F> PhantomChip<F> {
                 phantom_opcode: VmOpcode::from_usize(offset + SystemOpcode::PHANTOM.as_usize()),
             },
             rows: vec![],
+            num_skipped_rows: None,
             memory: memory_controller,
             streams: OnceLock::new(),
             phantom_executors: FxHashMap::default(),
@@ -169,12 +173,15 @@ impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
                 })?;
         }
 
-        self.rows.push(PhantomCols {
-            pc: F::from_canonical_u32(from_state.pc),
-            operands: [a, b, c],
-            timestamp: F::from_canonical_u32(from_state.timestamp),
-            is_valid: F::ONE,
-        });
+        match &mut self.num_skipped_rows {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.rows.push(PhantomCols {
+                pc: F::from_canonical_u32(from_state.pc),
+                operands: [a, b, c],
+                timestamp: F::from_canonical_u32(from_state.timestamp),
+                is_valid: F::ONE,
+            }),
+        }
         RefCell::borrow_mut(&self.memory).increment_timestamp();
         Ok(ExecutionState::new(
             from_state.pc + DEFAULT_PC_STEP,
@@ -185,6 +192,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
     fn get_opcode_name(&self, _: usize) -> String {
         format!("{:?}", SystemOpcode::PHANTOM)
     }
+
+    fn skip_records(&mut self) {
+        self.num_skipped_rows.get_or_insert(0);
+    }
 }
 
 impl<F: PrimeField32> ChipUsageGetter for PhantomChip<F> {
@@ -192,7 +203,7 @@ impl<F: PrimeField32> ChipUsageGetter for PhantomChip<F> {
         get_air_name(&self.air)
     }
     fn current_trace_height(&self) -> usize {
-        self.rows.len()
+        self.rows.len() + self.num_skipped_rows.unwrap_or(0)
     }
     fn trace_width(&self) -> usize {
         PhantomCols::<F>::width()
@@ -211,6 +222,10 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        assert!(
+            self.num_skipped_rows.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
         let correct_height = self.rows.len().next_power_of_two();
         let width = PhantomCols::<Val<SC>>::width();
         let mut rows = Val::<SC>::zero_vec(width * correct_height);
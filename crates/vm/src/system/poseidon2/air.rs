@@ -58,6 +58,8 @@ impl<AB: InteractionBuilder> Air<AB> for Poseidon2VmAir<AB::F> {
         builder.assert_bool(cols.io.is_opcode);
         builder.assert_bool(cols.io.is_compress_opcode);
         builder.assert_bool(cols.io.is_compress_direct);
+        builder.assert_bool(cols.io.is_absorb_opcode);
+        builder.assert_bool(cols.io.is_merkle_opcode);
 
         // Both opcode and compress_direct cannot be true
         builder.assert_zero(cols.io.is_opcode * cols.io.is_compress_direct);
@@ -65,13 +67,56 @@ impl<AB: InteractionBuilder> Air<AB> for Poseidon2VmAir<AB::F> {
         builder
             .when(cols.io.is_compress_opcode)
             .assert_one(cols.io.is_opcode);
-        let is_permute_opcode = cols.io.is_opcode - cols.io.is_compress_opcode;
+        builder
+            .when(cols.io.is_absorb_opcode)
+            .assert_one(cols.io.is_opcode);
+        builder
+            .when(cols.io.is_merkle_opcode)
+            .assert_one(cols.io.is_opcode);
+        builder.assert_zero(cols.io.is_compress_opcode * cols.io.is_absorb_opcode);
+        builder.assert_zero(cols.io.is_compress_opcode * cols.io.is_merkle_opcode);
+        builder.assert_zero(cols.io.is_absorb_opcode * cols.io.is_merkle_opcode);
+        let is_permute_opcode = cols.io.is_opcode
+            - cols.io.is_compress_opcode
+            - cols.io.is_absorb_opcode
+            - cols.io.is_merkle_opcode;
+        // Both permute and absorb write the whole state
+        let is_full_output =
+            cols.io.is_opcode - cols.io.is_compress_opcode - cols.io.is_merkle_opcode;
+        // Only compress and Merkle path levels read [c]_d
+        let reads_c = cols.io.is_compress_opcode + cols.io.is_merkle_opcode;
+
+        // except for a Merkle path level, [b]_d is the lhs_ptr and [c]_d is the rhs_ptr
+        builder
+            .when(cols.io.is_opcode - cols.io.is_merkle_opcode)
+            .assert_eq(cols.aux.lhs_ptr, cols.aux.b_value);
+        builder
+            .when(cols.io.is_compress_opcode)
+            .assert_eq(cols.aux.rhs_ptr, cols.aux.c_value);
 
         // if permute instruction, the rhs_ptr should be contiguous with lhs_ptr
-        builder.when(is_permute_opcode.clone()).assert_eq(
+        builder.when(is_permute_opcode).assert_eq(
             cols.aux.rhs_ptr,
             cols.aux.lhs_ptr + AB::F::from_canonical_usize(CHUNK),
         );
+        // if absorb instruction, the rhs is the capacity half of the state at dst_ptr
+        builder.when(cols.io.is_absorb_opcode).assert_eq(
+            cols.aux.rhs_ptr,
+            cols.aux.dst_ptr + AB::F::from_canonical_usize(CHUNK),
+        );
+        // if Merkle path level, [c]_d is the index bit, which puts the sibling at [b]_d on the
+        // left of the node at dst_ptr when set
+        let bit = cols.aux.c_value;
+        let mut when_merkle = builder.when(cols.io.is_merkle_opcode);
+        when_merkle.assert_bool(bit);
+        when_merkle.assert_eq(
+            cols.aux.lhs_ptr,
+            cols.aux.dst_ptr + bit * (cols.aux.b_value - cols.aux.dst_ptr),
+        );
+        when_merkle.assert_eq(
+            cols.aux.rhs_ptr,
+            cols.aux.dst_ptr + cols.aux.b_value - cols.aux.lhs_ptr,
+        );
 
         // Memory access constraints
         let timestamp = cols.io.timestamp;
@@ -82,16 +127,12 @@ impl<AB: InteractionBuilder> Air<AB> for Poseidon2VmAir<AB::F> {
         };
 
         // read addresses when is_opcode:
-        // dst <- [a]_d, lhs <- [b]_d
-        // Only when opcode is COMPRESS is rhs <- [c]_d read
+        // dst <- [a]_d, b_value <- [b]_d
+        // Only when opcode is COMPRESS or MERKLE_STEP is [c]_d read
         for (io_addr, aux_addr, count, mem_aux) in izip!(
             [cols.io.a, cols.io.b, cols.io.c],
-            [cols.aux.dst_ptr, cols.aux.lhs_ptr, cols.aux.rhs_ptr],
-            [
-                cols.io.is_opcode,
-                cols.io.is_opcode,
-                cols.io.is_compress_opcode
-            ],
+            [cols.aux.dst_ptr, cols.aux.b_value, cols.aux.c_value],
+            [cols.io.is_opcode.into(), cols.io.is_opcode.into(), reads_c],
             &cols.aux.ptr_aux_cols,
         ) {
             self.memory_bridge
@@ -146,7 +187,7 @@ impl<AB: InteractionBuilder> Air<AB> for Poseidon2VmAir<AB::F> {
                 timestamp_pp(),
                 &output2_aux_cols,
             )
-            .eval(builder, is_permute_opcode);
+            .eval(builder, is_full_output);
 
         self.eval_interactions(
             builder,
@@ -5,7 +5,10 @@ use openvm_stark_backend::{
 };
 
 use super::{air::Poseidon2VmAir, columns::Poseidon2VmIoCols, WIDTH};
-use crate::arch::{instructions::Poseidon2Opcode::PERM_POS2, ExecutionState};
+use crate::arch::{
+    instructions::Poseidon2Opcode::{ABSORB_POS2, MERKLE_STEP_POS2, PERM_POS2},
+    ExecutionState,
+};
 
 impl<F: Field> Poseidon2VmAir<F> {
     /// Receives instructions from the Core on the designated `POSEIDON2_BUS` (opcodes) or `POSEIDON2_DIRECT_BUS` (direct), and sends both read and write requests to the memory chip.
@@ -20,7 +23,12 @@ impl<F: Field> Poseidon2VmAir<F> {
         internal_io: Poseidon2IoCols<WIDTH, AB::Var>,
         timestamp_delta: AB::Expr,
     ) {
-        let opcode = AB::Expr::from_canonical_usize(PERM_POS2 as usize) + io.is_compress_opcode;
+        let opcode = AB::Expr::from_canonical_usize(PERM_POS2 as usize)
+            + io.is_compress_opcode
+            + io.is_absorb_opcode
+                * AB::F::from_canonical_usize(ABSORB_POS2 as usize - PERM_POS2 as usize)
+            + io.is_merkle_opcode
+                * AB::F::from_canonical_usize(MERKLE_STEP_POS2 as usize - PERM_POS2 as usize);
 
         self.execution_bridge
             .execute_and_increment_pc(
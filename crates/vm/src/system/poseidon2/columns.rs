@@ -22,7 +22,9 @@ pub struct Poseidon2VmCols<T> {
 /// * `clk`: the clock cycle (NOT timestamp)
 /// * `a`, `b`, `c`: addresses
 /// * `d`, `e`: address spaces
-/// * `cmp`: boolean for compression vs. permutation
+/// * `is_compress_opcode`: boolean for compression vs. permutation
+/// * `is_absorb_opcode`: boolean for absorbing into a sponge state
+/// * `is_merkle_opcode`: boolean for one level of a Merkle path
 #[derive(Clone, Copy, Debug)]
 pub struct Poseidon2VmIoCols<T> {
     pub is_opcode: T,
@@ -35,16 +37,22 @@ pub struct Poseidon2VmIoCols<T> {
     pub c: T,
     pub d: T,
     pub e: T,
+    pub is_absorb_opcode: T,
+    pub is_merkle_opcode: T,
 }
 
 /// Auxiliary columns for Poseidon2Chip.
 /// * `addresses`: addresses where inputs/outputs for Poseidon2 are located
+/// * `b_value`, `c_value`: values read from `[b]_d` and `[c]_d`, which are the input pointers
+///   except for a Merkle path level, where they are the sibling pointer and the index bit
 /// * `internal`: auxiliary columns used by Poseidon2Air for interpreting opcode, evaluating indicators, inverse, and explicit computations.
 #[derive(Clone, Debug)]
 pub struct Poseidon2VmAuxCols<T> {
     pub dst_ptr: T,
     pub lhs_ptr: T,
     pub rhs_ptr: T,
+    pub b_value: T,
+    pub c_value: T,
     pub internal: Poseidon2Cols<WIDTH, T>,
     pub ptr_aux_cols: [MemoryReadAuxCols<T, 1>; 3],
     pub input_aux_cols: [MemoryReadAuxCols<T, CHUNK>; 2],
@@ -88,7 +96,7 @@ impl<F: Field> Poseidon2VmCols<F> {
 
 impl<T: Clone> Poseidon2VmIoCols<T> {
     pub fn get_width() -> usize {
-        10
+        12
     }
 
     pub fn flatten(&self) -> Vec<T> {
@@ -103,6 +111,8 @@ impl<T: Clone> Poseidon2VmIoCols<T> {
             self.d.clone(),
             self.e.clone(),
             self.is_compress_opcode.clone(),
+            self.is_absorb_opcode.clone(),
+            self.is_merkle_opcode.clone(),
         ]
     }
 
@@ -118,6 +128,8 @@ impl<T: Clone> Poseidon2VmIoCols<T> {
             d: slice[7].clone(),
             e: slice[8].clone(),
             is_compress_opcode: slice[9].clone(),
+            is_absorb_opcode: slice[10].clone(),
+            is_merkle_opcode: slice[11].clone(),
         }
     }
 }
@@ -134,6 +146,8 @@ impl<F: Field> Poseidon2VmIoCols<F> {
             d: F::ONE,
             e: F::ONE,
             is_compress_opcode: F::ZERO,
+            is_absorb_opcode: F::ZERO,
+            is_merkle_opcode: F::ZERO,
         }
     }
 
@@ -149,13 +163,15 @@ impl<F: Field> Poseidon2VmIoCols<F> {
             d: F::ONE,
             e: F::ONE,
             is_compress_opcode: F::ZERO,
+            is_absorb_opcode: F::ZERO,
+            is_merkle_opcode: F::ZERO,
         }
     }
 }
 
 impl<T: Clone> Poseidon2VmAuxCols<T> {
     pub fn width(air: &Poseidon2VmAir<T>) -> usize {
-        3 + Poseidon2Cols::<WIDTH, T>::width(&air.inner)
+        5 + Poseidon2Cols::<WIDTH, T>::width(&air.inner)
             + 3 * MemoryReadAuxCols::<T, 1>::width()
             + 2 * MemoryReadAuxCols::<T, CHUNK>::width()
             + 2 * MemoryWriteAuxCols::<T, CHUNK>::width()
@@ -166,6 +182,8 @@ impl<T: Clone> Poseidon2VmAuxCols<T> {
             self.dst_ptr.clone(),
             self.lhs_ptr.clone(),
             self.rhs_ptr.clone(),
+            self.b_value.clone(),
+            self.c_value.clone(),
         ];
         result.extend(self.internal.flatten());
         result.extend(
@@ -190,8 +208,10 @@ impl<T: Clone> Poseidon2VmAuxCols<T> {
         let dst = slc[0].clone();
         let lhs = slc[1].clone();
         let rhs = slc[2].clone();
+        let b_value = slc[3].clone();
+        let c_value = slc[4].clone();
 
-        let mut start = 3;
+        let mut start = 5;
         let mut end = start + Poseidon2Cols::<WIDTH, T>::width(&air.inner);
         let internal = Poseidon2Cols::from_slice(&slc[start..end], &air.inner);
 
@@ -215,6 +235,8 @@ impl<T: Clone> Poseidon2VmAuxCols<T> {
             dst_ptr: dst,
             lhs_ptr: lhs,
             rhs_ptr: rhs,
+            b_value,
+            c_value,
             internal,
             ptr_aux_cols,
             input_aux_cols,
@@ -229,6 +251,8 @@ impl<F: Field> Poseidon2VmAuxCols<F> {
             dst_ptr: F::default(),
             lhs_ptr: F::default(),
             rhs_ptr: F::default(),
+            b_value: F::default(),
+            c_value: F::default(),
             internal: Poseidon2Cols::blank_row(&air.inner),
             ptr_aux_cols: array::from_fn(|_| MemoryReadAuxCols::disabled()),
             input_aux_cols: array::from_fn(|_| MemoryReadAuxCols::disabled()),
@@ -1,4 +1,5 @@
-//! Chip to handle **native kernel** instructions for Poseidon2 `compress` and `permute`.
+//! Chip to handle **native kernel** instructions for Poseidon2 `compress`, `permute`, `absorb`
+//! and Merkle path levels.
 //! This chip is put in [intrinsics](crate::intrinsics) for organizational convenience, but
 //! it is used as a system chip for persistent memory and as a native kernel chip for aggregation.
 //!
@@ -6,7 +7,9 @@
 //! is a cryptographic hash. `permute` is a cryptographic permutation, which can be made
 //! into a hash by applying a sponge construction. `compress` can be used as a hash in the
 //! internal leaves of a Merkle tree but **not** as the leaf hash because `compress` does not
-//! add any padding.
+//! add any padding. `absorb` is one step of such a sponge, overwriting the rate half of a
+//! state in memory with a chunk and permuting it in place. A Merkle path level is a `compress`
+//! of a node with its sibling, in the order given by an index bit, written back to the node.
 use std::array;
 
 use columns::*;
@@ -53,6 +56,9 @@ pub struct Poseidon2Chip<F: PrimeField32> {
     pub memory_controller: MemoryControllerRef<F>,
 
     records: Vec<Poseidon2Record<F>>,
+    /// Number of records not kept in `records`, see [InstructionExecutor::skip_records]. `None`
+    /// while records are kept.
+    num_skipped_records: Option<usize>,
 
     offset: usize,
 }
@@ -122,11 +128,19 @@ impl<F: PrimeField32> Poseidon2Chip<F> {
         Self {
             air,
             records: vec![],
+            num_skipped_records: None,
             memory_controller,
             offset,
         }
     }
 
+    fn push_record(&mut self, record: Poseidon2Record<F>) {
+        match &mut self.num_skipped_records {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.records.push(record),
+        }
+    }
+
     fn record_to_cols(
         aux_cols_factory: &MemoryAuxColsFactory<F>,
         record: Poseidon2Record<F>,
@@ -137,8 +151,9 @@ impl<F: PrimeField32> Poseidon2Chip<F> {
                 from_state,
                 internal_cols,
                 dst_ptr_read,
-                lhs_ptr_read,
-                rhs_ptr_read,
+                b_read,
+                c_read,
+                lhs_ptr,
                 rhs_ptr,
                 lhs_read,
                 rhs_read,
@@ -146,14 +161,14 @@ impl<F: PrimeField32> Poseidon2Chip<F> {
                 output2_write,
             } => {
                 let dst_ptr = dst_ptr_read.value();
-                let lhs_ptr = lhs_ptr_read.value();
+                let b_value = b_read.value();
+                let c_value = c_read.map_or(F::ZERO, |read| read.value());
 
-                let ptr_aux_cols =
-                    [Some(dst_ptr_read), Some(lhs_ptr_read), rhs_ptr_read].map(|maybe_read| {
-                        maybe_read.map_or_else(MemoryReadAuxCols::disabled, |read| {
-                            aux_cols_factory.make_read_aux_cols(read)
-                        })
-                    });
+                let ptr_aux_cols = [Some(dst_ptr_read), Some(b_read), c_read].map(|maybe_read| {
+                    maybe_read.map_or_else(MemoryReadAuxCols::disabled, |read| {
+                        aux_cols_factory.make_read_aux_cols(read)
+                    })
+                });
 
                 let input_aux_cols =
                     [lhs_read, rhs_read].map(|read| aux_cols_factory.make_read_aux_cols(read));
@@ -178,11 +193,19 @@ impl<F: PrimeField32> Poseidon2Chip<F> {
                         is_compress_opcode: F::from_bool(
                             instruction.opcode == VmOpcode::from_usize(COMP_POS2 as usize),
                         ),
+                        is_absorb_opcode: F::from_bool(
+                            instruction.opcode == VmOpcode::from_usize(ABSORB_POS2 as usize),
+                        ),
+                        is_merkle_opcode: F::from_bool(
+                            instruction.opcode == VmOpcode::from_usize(MERKLE_STEP_POS2 as usize),
+                        ),
                     },
                     aux: Poseidon2VmAuxCols {
                         dst_ptr,
                         lhs_ptr,
                         rhs_ptr,
+                        b_value,
+                        c_value,
                         internal: internal_cols,
                         ptr_aux_cols,
                         input_aux_cols,
@@ -202,11 +225,15 @@ impl<F: PrimeField32> Poseidon2Chip<F> {
                     d: F::ZERO,
                     e: F::ZERO,
                     is_compress_opcode: F::ZERO,
+                    is_absorb_opcode: F::ZERO,
+                    is_merkle_opcode: F::ZERO,
                 },
                 aux: Poseidon2VmAuxCols {
                     dst_ptr: F::ZERO,
                     lhs_ptr: F::ZERO,
                     rhs_ptr: F::ZERO,
+                    b_value: F::ZERO,
+                    c_value: F::ZERO,
                     internal: inner_cols,
                     ptr_aux_cols: array::from_fn(|_| MemoryReadAuxCols::disabled()),
                     input_aux_cols: array::from_fn(|_| MemoryReadAuxCols::disabled()),
@@ -224,14 +251,15 @@ enum Poseidon2Record<F> {
         from_state: ExecutionState<u32>,
         internal_cols: Poseidon2Cols<WIDTH, F>,
         dst_ptr_read: MemoryReadRecord<F, 1>,
-        lhs_ptr_read: MemoryReadRecord<F, 1>,
-        // None for permute (since rhs_ptr is computed from lhs_ptr).
-        rhs_ptr_read: Option<MemoryReadRecord<F, 1>>,
+        b_read: MemoryReadRecord<F, 1>,
+        // None for permute and absorb (since rhs_ptr is computed from lhs_ptr or dst_ptr).
+        c_read: Option<MemoryReadRecord<F, 1>>,
+        lhs_ptr: F,
         rhs_ptr: F,
         lhs_read: MemoryReadRecord<F, CHUNK>,
         rhs_read: MemoryReadRecord<F, CHUNK>,
         output1_write: MemoryWriteRecord<F, CHUNK>,
-        // None for compress (since output is of size CHUNK).
+        // None for compress and Merkle path levels (since output is of size CHUNK).
         output2_write: Option<MemoryWriteRecord<F, CHUNK>>,
     },
     DirectCompress {
@@ -244,7 +272,7 @@ impl<F: PrimeField32> InstructionExecutor<F> for Poseidon2Chip<F> {
     /// the given instruction using the subair, storing it in `rows`. Then, writes output to memory,
     /// truncating if the instruction is a compression.
     ///
-    /// Used for both compression and permutation.
+    /// Used for compression, permutation, absorption and Merkle path levels.
     fn execute(
         &mut self,
         instruction: Instruction<F>,
@@ -265,7 +293,6 @@ impl<F: PrimeField32> InstructionExecutor<F> for Poseidon2Chip<F> {
 
         let local_opcode = Poseidon2Opcode::from_usize(local_opcode_idx);
 
-        assert!(matches!(local_opcode, COMP_POS2 | PERM_POS2));
         debug_assert_eq!(WIDTH, CHUNK * 2);
 
         let chunk_f = F::from_canonical_usize(CHUNK);
@@ -273,17 +300,30 @@ impl<F: PrimeField32> InstructionExecutor<F> for Poseidon2Chip<F> {
         let dst_ptr_read = memory_controller.read_cell(d, a);
         let dst_ptr = dst_ptr_read.value();
 
-        let lhs_ptr_read = memory_controller.read_cell(d, b);
-        let lhs_ptr = lhs_ptr_read.value();
+        let b_read = memory_controller.read_cell(d, b);
+        let b_value = b_read.value();
 
-        let (rhs_ptr, rhs_ptr_read) = match local_opcode {
+        let (lhs_ptr, rhs_ptr, c_read) = match local_opcode {
             COMP_POS2 => {
-                let rhs_ptr_read = memory_controller.read_cell(d, c);
-                (rhs_ptr_read.value(), Some(rhs_ptr_read))
+                let c_read = memory_controller.read_cell(d, c);
+                (b_value, c_read.value(), Some(c_read))
             }
             PERM_POS2 => {
                 memory_controller.increment_timestamp();
-                (lhs_ptr + chunk_f, None)
+                (b_value, b_value + chunk_f, None)
+            }
+            ABSORB_POS2 => {
+                memory_controller.increment_timestamp();
+                (b_value, dst_ptr + chunk_f, None)
+            }
+            MERKLE_STEP_POS2 => {
+                let c_read = memory_controller.read_cell(d, c);
+                let (lhs_ptr, rhs_ptr) = match c_read.value() {
+                    bit if bit == F::ZERO => (dst_ptr, b_value),
+                    bit if bit == F::ONE => (b_value, dst_ptr),
+                    _ => return Err(ExecutionError::Fail { pc: from_state.pc }),
+                };
+                (lhs_ptr, rhs_ptr, Some(c_read))
             }
         };
 
@@ -305,29 +345,34 @@ impl<F: PrimeField32> InstructionExecutor<F> for Poseidon2Chip<F> {
 
         let output1_write = memory_controller.write(e, dst_ptr, output1);
         let output2_write = match local_opcode {
-            COMP_POS2 => {
+            COMP_POS2 | MERKLE_STEP_POS2 => {
                 memory_controller.increment_timestamp();
                 None
             }
-            PERM_POS2 => Some(memory_controller.write(e, dst_ptr + chunk_f, output2)),
+            PERM_POS2 | ABSORB_POS2 => Some(memory_controller.write(e, dst_ptr + chunk_f, output2)),
         };
 
-        self.records.push(Poseidon2Record::FromInstruction {
-            instruction: Instruction {
-                opcode: VmOpcode::from_usize(local_opcode as usize),
-                ..instruction
-            },
-            from_state,
-            internal_cols,
-            dst_ptr_read,
-            lhs_ptr_read,
-            rhs_ptr_read,
-            rhs_ptr,
-            lhs_read,
-            rhs_read,
-            output1_write,
-            output2_write,
-        });
+        // `memory_controller` borrows `self`, so this can't call `Self::push_record`.
+        match &mut self.num_skipped_records {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.records.push(Poseidon2Record::FromInstruction {
+                instruction: Instruction {
+                    opcode: VmOpcode::from_usize(local_opcode as usize),
+                    ..instruction
+                },
+                from_state,
+                internal_cols,
+                dst_ptr_read,
+                b_read,
+                c_read,
+                lhs_ptr,
+                rhs_ptr,
+                lhs_read,
+                rhs_read,
+                output1_write,
+                output2_write,
+            }),
+        }
 
         Ok(ExecutionState {
             pc: from_state.pc + DEFAULT_PC_STEP,
@@ -339,6 +384,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for Poseidon2Chip<F> {
         let local_opcode = Poseidon2Opcode::from_usize(opcode - self.offset);
         format!("{local_opcode:?}")
     }
+
+    fn skip_records(&mut self) {
+        self.num_skipped_records.get_or_insert(0);
+    }
 }
 impl<F: PrimeField32> Hasher<CHUNK, F> for Poseidon2Chip<F> {
     fn compress(&self, lhs: &[F; CHUNK], rhs: &[F; CHUNK]) -> [F; CHUNK] {
@@ -364,8 +413,7 @@ impl<F: PrimeField32> HasherChip<CHUNK, F> for Poseidon2Chip<F> {
         let inner_cols = self.air.inner.generate_trace_row(input_state);
         let output = array::from_fn(|i| inner_cols.io.output[i]);
 
-        self.records
-            .push(Poseidon2Record::DirectCompress { inner_cols });
+        self.push_record(Poseidon2Record::DirectCompress { inner_cols });
 
         output
     }
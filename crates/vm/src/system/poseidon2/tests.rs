@@ -34,11 +34,10 @@ fn random_instructions(num_ops: usize) -> Vec<Instruction<BabyBear>> {
             let [a, b, c] =
                 std::array::from_fn(|_| BabyBear::from_canonical_usize(gen_pointer(&mut rng, 1)));
             Instruction {
-                opcode: if rng.gen_bool(0.5) {
-                    VmOpcode::from_usize(PERM_POS2 as usize)
-                } else {
-                    VmOpcode::from_usize(COMP_POS2 as usize)
-                },
+                opcode: VmOpcode::from_usize(
+                    [PERM_POS2, COMP_POS2, ABSORB_POS2, MERKLE_STEP_POS2][rng.gen_range(0..4)]
+                        as usize,
+                ),
                 a,
                 b,
                 c,
@@ -79,7 +78,16 @@ fn tester_with_random_poseidon2_ops(num_ops: usize) -> VmChipTester<BabyBearBlak
         .map(|elem| elem.as_canonical_u64() as usize);
 
         let dst = gen_pointer(&mut rng, CHUNK);
-        let lhs = gen_pointer(&mut rng, CHUNK);
+        let mut lhs = gen_pointer(&mut rng, CHUNK);
+        // The absorbed chunk must not overlap the capacity half of the state.
+        while opcode == ABSORB_POS2 && lhs == dst + CHUNK {
+            lhs = gen_pointer(&mut rng, CHUNK);
+        }
+        // The sibling of a Merkle path level must not overlap the node.
+        while opcode == MERKLE_STEP_POS2 && lhs == dst {
+            lhs = gen_pointer(&mut rng, CHUNK);
+        }
+        let bit = rng.gen_range(0..2);
         let rhs = gen_pointer(&mut rng, CHUNK);
 
         let data: [_; WIDTH] =
@@ -92,6 +100,9 @@ fn tester_with_random_poseidon2_ops(num_ops: usize) -> VmChipTester<BabyBearBlak
         if opcode == COMP_POS2 {
             tester.write_cell(d, c, BabyBear::from_canonical_usize(rhs));
         }
+        if opcode == MERKLE_STEP_POS2 {
+            tester.write_cell(d, c, BabyBear::from_canonical_usize(bit));
+        }
 
         match opcode {
             COMP_POS2 => {
@@ -103,17 +114,35 @@ fn tester_with_random_poseidon2_ops(num_ops: usize) -> VmChipTester<BabyBearBlak
             PERM_POS2 => {
                 tester.write(e, lhs, data);
             }
+            ABSORB_POS2 => {
+                let data_left: [_; CHUNK] = std::array::from_fn(|i| data[i]);
+                let data_right: [_; CHUNK] = std::array::from_fn(|i| data[CHUNK + i]);
+                tester.write(e, dst + CHUNK, data_right);
+                tester.write(e, lhs, data_left);
+            }
+            MERKLE_STEP_POS2 => {
+                // The node is at dst and its sibling at lhs, ordered by the index bit.
+                let data_left: [_; CHUNK] = std::array::from_fn(|i| data[i]);
+                let data_right: [_; CHUNK] = std::array::from_fn(|i| data[CHUNK + i]);
+                let (node, sibling) = if bit == 0 {
+                    (data_left, data_right)
+                } else {
+                    (data_right, data_left)
+                };
+                tester.write(e, dst, node);
+                tester.write(e, lhs, sibling);
+            }
         }
 
         tester.execute(&mut chip, instruction);
 
         match opcode {
-            COMP_POS2 => {
+            COMP_POS2 | MERKLE_STEP_POS2 => {
                 let expected: [_; CHUNK] = std::array::from_fn(|i| hash[i]);
                 let actual = tester.read::<CHUNK>(e, dst);
                 assert_eq!(expected, actual);
             }
-            PERM_POS2 => {
+            PERM_POS2 | ABSORB_POS2 => {
                 let actual = tester.read::<WIDTH>(e, dst);
                 assert_eq!(hash, actual);
             }
@@ -150,8 +179,8 @@ fn poseidon2_negative_test() {
         let trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
         let original_trace = trace.clone();
 
-        // avoid pranking IO cols or dst,lhs,rhs
-        let start_prank_col = Poseidon2VmIoCols::<u8>::get_width() + 3;
+        // avoid pranking IO cols or dst,lhs,rhs,b_value,c_value
+        let start_prank_col = Poseidon2VmIoCols::<u8>::get_width() + 5;
         let end_prank_col = start_prank_col + Poseidon2Air::<16, BabyBear>::default().get_width();
         let width = rng.gen_range(start_prank_col..end_prank_col);
         let height = rng.gen_range(0..num_ops);
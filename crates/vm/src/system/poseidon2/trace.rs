@@ -29,8 +29,13 @@ where
             air,
             memory_controller,
             records,
+            num_skipped_records,
             offset: _,
         } = self;
+        assert!(
+            num_skipped_records.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
 
         let row_len = records.len();
         let correct_len = next_power_of_two_or_zero(row_len);
@@ -66,7 +71,7 @@ impl<F: PrimeField32> ChipUsageGetter for Poseidon2Chip<F> {
         get_air_name(&self.air)
     }
     fn current_trace_height(&self) -> usize {
-        self.records.len()
+        self.records.len() + self.num_skipped_records.unwrap_or(0)
     }
     fn trace_width(&self) -> usize {
         self.air.width()
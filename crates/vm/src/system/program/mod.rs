@@ -55,8 +55,8 @@ impl<F: PrimeField64> ProgramChip<F> {
     fn get_pc_index(&self, pc: u32) -> Result<usize, ExecutionError> {
         let step = self.program.step;
         let pc_base = self.program.pc_base;
-        let pc_index = ((pc - pc_base) / step) as usize;
-        if !(0..self.true_program_length).contains(&pc_index) {
+        let offset = pc.wrapping_sub(pc_base);
+        if pc < pc_base || offset / step >= self.true_program_length as u32 {
             return Err(ExecutionError::PcOutOfBounds {
                 pc,
                 step,
@@ -64,7 +64,25 @@ impl<F: PrimeField64> ProgramChip<F> {
                 program_len: self.true_program_length,
             });
         }
-        Ok(pc_index)
+        if offset % step != 0 {
+            return Err(ExecutionError::PcMisaligned { pc, step, pc_base });
+        }
+        Ok((offset / step) as usize)
+    }
+
+    /// Checks that the instruction at `from_pc` may continue at `to_pc`. Instructions are only
+    /// fetched from the program region, never from memory, so a jump into data, e.g. through a
+    /// corrupted return address pointing to the heap, is reported with the instruction which
+    /// jumped.
+    pub fn check_jump(&self, from_pc: u32, to_pc: u32) -> Result<(), ExecutionError> {
+        self.get_pc_index(to_pc)
+            .map(|_| ())
+            .map_err(|_| ExecutionError::JumpOutsideProgram {
+                from_pc,
+                to_pc,
+                pc_base: self.program.pc_base,
+                pc_end: self.program.pc_base + self.true_program_length as u32 * self.program.step,
+            })
     }
 
     pub fn get_instruction(
@@ -10,6 +10,7 @@ use openvm_native_compiler::{
 };
 use openvm_rv32im_transpiler::BranchEqualOpcode::*;
 use openvm_stark_backend::{
+    config::StarkGenericConfig,
     p3_field::AbstractField,
     p3_matrix::{dense::RowMajorMatrix, Matrix},
     prover::types::AirProofInput,
@@ -18,6 +19,7 @@ use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
         baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig,
+        fri_params::standard_fri_params_with_100_bits_conjectured_security,
     },
     dummy_airs::interaction::dummy_interaction_air::DummyInteractionAir,
     engine::StarkFriEngine,
@@ -27,8 +29,11 @@ use serde::{de::DeserializeOwned, Serialize};
 use static_assertions::assert_impl_all;
 
 use crate::{
-    arch::{instructions::SystemOpcode::*, READ_INSTRUCTION_BUS},
-    system::program::{trace::VmCommittedExe, ProgramBus, ProgramChip},
+    arch::{instructions::SystemOpcode::*, ExecutionError, READ_INSTRUCTION_BUS},
+    system::program::{
+        trace::{compute_program_digest, VmCommittedExe},
+        ProgramBus, ProgramChip,
+    },
 };
 
 assert_impl_all!(VmCommittedExe<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
@@ -211,3 +216,70 @@ fn test_program_negative() {
     ])
     .expect("Verification failed");
 }
+
+#[test]
+fn test_program_digest_is_independent_of_pcs() {
+    let instructions = vec![
+        Instruction::large_from_isize(VmOpcode::with_default_offset(STOREW), 5, 0, 0, 0, 1, 0, 1),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let commit = |log_blowup| {
+        let engine = BabyBearPoseidon2Engine::new(
+            standard_fri_params_with_100_bits_conjectured_security(log_blowup),
+        );
+        VmCommittedExe::<BabyBearPoseidon2Config>::commit(
+            program.clone().into(),
+            engine.config.pcs(),
+        )
+    };
+    let (exe_1, exe_2) = (commit(1), commit(3));
+    assert_ne!(exe_1.get_program_commit(), exe_2.get_program_commit());
+    assert_eq!(exe_1.get_program_digest(), exe_2.get_program_digest());
+
+    let mut other_instructions = instructions;
+    other_instructions[0].g = BabyBear::ONE;
+    assert_ne!(
+        exe_1.get_program_digest(),
+        compute_program_digest(&Program::from_instructions(&other_instructions))
+    );
+}
+
+#[test]
+fn test_program_pc_checks() {
+    let pc_base = 0x1000;
+    let instructions = vec![
+        Instruction::large_from_isize(VmOpcode::with_default_offset(STOREW), 5, 0, 0, 0, 1, 0, 1),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ];
+    let program =
+        Program::<BabyBear>::new_without_debug_infos(&instructions, DEFAULT_PC_STEP, pc_base, 0);
+    let mut chip = ProgramChip::new_with_program(program, ProgramBus(READ_INSTRUCTION_BUS));
+
+    assert!(chip.get_instruction(pc_base + DEFAULT_PC_STEP).is_ok());
+    assert!(matches!(
+        chip.get_instruction(pc_base - DEFAULT_PC_STEP),
+        Err(ExecutionError::PcOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        chip.get_instruction(pc_base + 1),
+        Err(ExecutionError::PcMisaligned { .. })
+    ));
+    // The padding to a power of two is not part of the program.
+    assert!(matches!(
+        chip.get_instruction(pc_base + 3 * DEFAULT_PC_STEP),
+        Err(ExecutionError::PcOutOfBounds { .. })
+    ));
+
+    assert!(chip
+        .check_jump(pc_base, pc_base + 2 * DEFAULT_PC_STEP)
+        .is_ok());
+    for to_pc in [0, pc_base + 2, pc_base + 3 * DEFAULT_PC_STEP, 0x20_0000] {
+        assert!(matches!(
+            chip.check_jump(pc_base, to_pc),
+            Err(ExecutionError::JumpOutsideProgram { from_pc, .. }) if from_pc == pc_base
+        ));
+    }
+}
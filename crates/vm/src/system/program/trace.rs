@@ -6,7 +6,7 @@ use openvm_instructions::{exe::VmExe, program::Program, SystemOpcode, VmOpcode};
 use openvm_stark_backend::{
     config::{Com, Domain, StarkGenericConfig, Val},
     p3_commit::PolynomialSpace,
-    p3_field::{Field, PrimeField64},
+    p3_field::{AbstractField, Field, PrimeField32, PrimeField64},
     p3_matrix::dense::RowMajorMatrix,
     p3_maybe_rayon::prelude::*,
     prover::{
@@ -17,6 +17,10 @@ use openvm_stark_backend::{
 use serde::{Deserialize, Serialize};
 
 use super::{Instruction, ProgramChip, ProgramExecutionCols, EXIT_CODE_FAIL};
+use crate::{
+    arch::hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+    system::memory::CHUNK,
+};
 
 #[derive(Serialize, Deserialize, Derivative)]
 #[serde(bound(
@@ -48,6 +52,69 @@ where
     pub fn get_program_commit(&self) -> Com<SC> {
         self.committed_program.prover_data.commit.clone()
     }
+
+    /// See [compute_program_digest].
+    pub fn get_program_digest(&self) -> [Val<SC>; CHUNK]
+    where
+        Val<SC>: PrimeField32,
+    {
+        compute_program_digest(&self.exe.program)
+    }
+}
+
+/// Poseidon2 digest of the instructions of a program. Unlike the program commitment, it does not
+/// depend on the PCS the program is committed with, so a program has the same digest in every
+/// VM configuration.
+///
+/// Each instruction is hashed as `compress([pc, opcode, a, b, c, d, e, f], [g, 0, ..])`, the
+/// instruction hashes are merkleized after padding with zero digests to a power of two, and the
+/// root is compressed with `[num_instructions, pc_base, step, 0, ..]`.
+pub fn compute_program_digest<F: PrimeField32>(program: &Program<F>) -> [F; CHUNK] {
+    let hasher = vm_poseidon2_hasher();
+    let instructions = program.enumerate_by_pc();
+    let mut layer: Vec<[F; CHUNK]> = instructions
+        .iter()
+        .map(|(pc, instruction, _)| {
+            let Instruction {
+                opcode,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+                g,
+            } = instruction;
+            let mut rhs = [F::ZERO; CHUNK];
+            rhs[0] = *g;
+            hasher.compress(
+                &[
+                    F::from_canonical_u32(*pc),
+                    opcode.to_field(),
+                    *a,
+                    *b,
+                    *c,
+                    *d,
+                    *e,
+                    *f,
+                ],
+                &rhs,
+            )
+        })
+        .collect();
+    layer.resize(layer.len().next_power_of_two(), [F::ZERO; CHUNK]);
+    while layer.len() > 1 {
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| hasher.compress(&pair[0], &pair[1]))
+            .collect();
+    }
+
+    let mut header = [F::ZERO; CHUNK];
+    header[0] = F::from_canonical_usize(instructions.len());
+    header[1] = F::from_canonical_u32(program.pc_base);
+    header[2] = F::from_canonical_u32(program.step);
+    hasher.compress(&layer[0], &header)
 }
 
 impl<F: PrimeField64> ProgramChip<F> {
@@ -0,0 +1,362 @@
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools;
+use openvm_stark_backend::{
+    air_builders::symbolic::{
+        symbolic_expression::SymbolicExpression,
+        symbolic_variable::{Entry, SymbolicVariable},
+    },
+    config::{StarkGenericConfig, Val},
+    keygen::types::MultiStarkProvingKey,
+    p3_field::{AbstractField, Field},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::ProofInput,
+};
+
+/// Describes the first AIR constraint that does not evaluate to zero on a trace.
+#[derive(Clone, Debug)]
+pub struct ConstraintViolation<F> {
+    pub air_id: usize,
+    pub air_name: String,
+    /// Index of the constraint in the symbolic constraints of the AIR's verifying key.
+    pub constraint_idx: usize,
+    /// Pretty-printed symbolic expression of the constraint.
+    pub expression: String,
+    pub row: usize,
+    pub height: usize,
+    /// Values of all trace cells and public values referenced by the constraint, in the
+    /// order they first appear in the expression.
+    pub referenced_values: Vec<(String, F)>,
+}
+
+impl<F: Field> Display for ConstraintViolation<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "constraint {} of AIR {} ({}) failed on row {} of {}",
+            self.constraint_idx, self.air_id, self.air_name, self.row, self.height
+        )?;
+        writeln!(f, "  constraint: {} == 0", self.expression)?;
+        for (name, value) in &self.referenced_values {
+            writeln!(f, "  {name} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A constraint that [check_constraints] could not evaluate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedConstraint {
+    pub air_id: usize,
+    pub air_name: String,
+    /// Index of the constraint in the symbolic constraints of the AIR's verifying key.
+    pub constraint_idx: usize,
+    /// Pretty-printed symbolic expression of the constraint.
+    pub expression: String,
+}
+
+impl Display for SkippedConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} of AIR {} ({}) depends on challenges and was not checked: {} == 0",
+            self.constraint_idx, self.air_id, self.air_name, self.expression
+        )
+    }
+}
+
+/// Evaluates every AIR constraint on every row of the traces in `proof_input` and returns the
+/// first constraint that does not vanish, with enough context to locate the bug.
+///
+/// This is a debugging aid and does not replace the prover's own checks. Constraints that depend
+/// on the after-challenge phase (permutation columns, challenges, exposed values), such as the
+/// LogUp constraints of AIRs with interactions, cannot be evaluated without the prover's
+/// randomness. They are returned instead of being checked; use the interaction debugger of the
+/// backend for bus balancing issues.
+pub fn check_constraints<SC: StarkGenericConfig>(
+    pk: &MultiStarkProvingKey<SC>,
+    proof_input: &ProofInput<SC>,
+) -> Result<Vec<SkippedConstraint>, ConstraintViolation<Val<SC>>> {
+    let mut skipped = vec![];
+    for (air_id, input) in &proof_input.per_air {
+        let air_pk = &pk.per_air[*air_id];
+        let preprocessed = air_pk.preprocessed_data.as_ref().map(|data| &*data.trace);
+        let partitioned_main: Vec<&RowMajorMatrix<Val<SC>>> = input
+            .raw
+            .cached_mains
+            .iter()
+            .map(|trace| trace.as_ref())
+            .chain(input.raw.common_main.as_ref())
+            .collect();
+        let trace = TraceView {
+            preprocessed,
+            partitioned_main: &partitioned_main,
+            public_values: &input.raw.public_values,
+        };
+        let height = trace.height();
+        if height == 0 {
+            continue;
+        }
+        for (constraint_idx, constraint) in air_pk
+            .vk
+            .symbolic_constraints
+            .constraints
+            .iter()
+            .enumerate()
+        {
+            if depends_on_challenges(constraint) {
+                skipped.push(SkippedConstraint {
+                    air_id: *air_id,
+                    air_name: air_pk.air_name.clone(),
+                    constraint_idx,
+                    expression: ExprDisplay(constraint).to_string(),
+                });
+                continue;
+            }
+            for row in 0..height {
+                if trace.eval(constraint, row) != Val::<SC>::ZERO {
+                    let mut vars = vec![];
+                    collect_variables(constraint, &mut vars);
+                    let referenced_values = vars
+                        .into_iter()
+                        .unique_by(|var| (var.entry, var.index))
+                        .map(|var| (variable_name(&var), trace.variable(&var, row)))
+                        .collect();
+                    return Err(ConstraintViolation {
+                        air_id: *air_id,
+                        air_name: air_pk.air_name.clone(),
+                        constraint_idx,
+                        expression: ExprDisplay(constraint).to_string(),
+                        row,
+                        height,
+                        referenced_values,
+                    });
+                }
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+struct TraceView<'a, F> {
+    preprocessed: Option<&'a RowMajorMatrix<F>>,
+    partitioned_main: &'a [&'a RowMajorMatrix<F>],
+    public_values: &'a [F],
+}
+
+impl<F: Field> TraceView<'_, F> {
+    fn height(&self) -> usize {
+        self.partitioned_main
+            .first()
+            .copied()
+            .or(self.preprocessed)
+            .map(|trace| trace.height())
+            .unwrap_or(0)
+    }
+
+    fn variable(&self, var: &SymbolicVariable<F>, row: usize) -> F {
+        let height = self.height();
+        let row_at = |offset: usize| (row + offset) % height;
+        match var.entry {
+            Entry::Preprocessed { offset } => self
+                .preprocessed
+                .expect("constraint references a preprocessed trace that does not exist")
+                .get(row_at(offset), var.index),
+            Entry::Main { part_index, offset } => {
+                self.partitioned_main[part_index].get(row_at(offset), var.index)
+            }
+            Entry::Public => self.public_values[var.index],
+            Entry::Permutation { .. } | Entry::Challenge | Entry::Exposed => {
+                unreachable!("after-challenge entries are filtered out")
+            }
+        }
+    }
+
+    fn eval(&self, expr: &SymbolicExpression<F>, row: usize) -> F {
+        match expr {
+            SymbolicExpression::Variable(var) => self.variable(var, row),
+            SymbolicExpression::IsFirstRow => F::from_bool(row == 0),
+            SymbolicExpression::IsLastRow => F::from_bool(row == self.height() - 1),
+            SymbolicExpression::IsTransition => F::from_bool(row != self.height() - 1),
+            SymbolicExpression::Constant(c) => *c,
+            SymbolicExpression::Add { x, y, .. } => self.eval(x, row) + self.eval(y, row),
+            SymbolicExpression::Sub { x, y, .. } => self.eval(x, row) - self.eval(y, row),
+            SymbolicExpression::Neg { x, .. } => -self.eval(x, row),
+            SymbolicExpression::Mul { x, y, .. } => self.eval(x, row) * self.eval(y, row),
+        }
+    }
+}
+
+fn depends_on_challenges<F>(expr: &SymbolicExpression<F>) -> bool {
+    match expr {
+        SymbolicExpression::Variable(var) => matches!(
+            var.entry,
+            Entry::Permutation { .. } | Entry::Challenge | Entry::Exposed
+        ),
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => {
+            depends_on_challenges(x) || depends_on_challenges(y)
+        }
+        SymbolicExpression::Neg { x, .. } => depends_on_challenges(x),
+        _ => false,
+    }
+}
+
+fn collect_variables<F: Copy>(expr: &SymbolicExpression<F>, vars: &mut Vec<SymbolicVariable<F>>) {
+    match expr {
+        SymbolicExpression::Variable(var) => vars.push(*var),
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => {
+            collect_variables(x, vars);
+            collect_variables(y, vars);
+        }
+        SymbolicExpression::Neg { x, .. } => collect_variables(x, vars),
+        _ => {}
+    }
+}
+
+fn variable_name<F>(var: &SymbolicVariable<F>) -> String {
+    let rotation = |offset: usize| if offset == 0 { "local" } else { "next" };
+    match var.entry {
+        Entry::Preprocessed { offset } => {
+            format!("preprocessed.{}[{}]", rotation(offset), var.index)
+        }
+        Entry::Main { part_index, offset } => {
+            format!("main[{part_index}].{}[{}]", rotation(offset), var.index)
+        }
+        Entry::Permutation { offset } => format!("perm.{}[{}]", rotation(offset), var.index),
+        Entry::Public => format!("public[{}]", var.index),
+        Entry::Challenge => format!("challenge[{}]", var.index),
+        Entry::Exposed => format!("exposed[{}]", var.index),
+    }
+}
+
+/// Infix pretty-printer for [SymbolicExpression].
+pub struct ExprDisplay<'a, F>(pub &'a SymbolicExpression<F>);
+
+impl<F: Field> Display for ExprDisplay<'_, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            SymbolicExpression::Variable(var) => write!(f, "{}", variable_name(var)),
+            SymbolicExpression::IsFirstRow => write!(f, "is_first_row"),
+            SymbolicExpression::IsLastRow => write!(f, "is_last_row"),
+            SymbolicExpression::IsTransition => write!(f, "is_transition"),
+            SymbolicExpression::Constant(c) => write!(f, "{c}"),
+            SymbolicExpression::Add { x, y, .. } => {
+                write!(f, "({} + {})", ExprDisplay(&**x), ExprDisplay(&**y))
+            }
+            SymbolicExpression::Sub { x, y, .. } => {
+                write!(f, "({} - {})", ExprDisplay(&**x), ExprDisplay(&**y))
+            }
+            SymbolicExpression::Neg { x, .. } => write!(f, "-{}", ExprDisplay(&**x)),
+            SymbolicExpression::Mul { x, y, .. } => {
+                write!(f, "{} * {}", ExprDisplay(&**x), ExprDisplay(&**y))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use openvm_stark_backend::{
+        engine::StarkEngine,
+        interaction::InteractionBuilder,
+        p3_air::{Air, AirBuilder, BaseAir},
+        prover::types::AirProofInput,
+        rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+    };
+    use openvm_stark_sdk::{
+        config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
+        engine::StarkFriEngine,
+        p3_baby_bear::BabyBear,
+        utils::to_field_vec,
+    };
+
+    use super::*;
+
+    /// Counts the rows whose first column is set in its second column.
+    #[derive(Clone, Copy)]
+    struct CounterAir {
+        with_interaction: bool,
+    }
+
+    impl<F: Field> BaseAirWithPublicValues<F> for CounterAir {}
+    impl<F: Field> PartitionedBaseAir<F> for CounterAir {}
+    impl<F: Field> BaseAir<F> for CounterAir {
+        fn width(&self) -> usize {
+            2
+        }
+    }
+
+    impl<AB: InteractionBuilder> Air<AB> for CounterAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let (local, next) = (main.row_slice(0), main.row_slice(1));
+            // constraint 0
+            builder.assert_bool(local[0]);
+            // constraint 1
+            builder
+                .when_transition()
+                .assert_eq(next[1], local[1] + local[0]);
+            if self.with_interaction {
+                builder.push_send(0, [local[1]], local[0]);
+            }
+        }
+    }
+
+    fn check_counter(
+        air: CounterAir,
+        tamper: impl FnOnce(&mut RowMajorMatrix<BabyBear>),
+    ) -> Result<Vec<SkippedConstraint>, ConstraintViolation<BabyBear>> {
+        let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+        let mut keygen_builder = engine.keygen_builder();
+        let air_id = keygen_builder.add_air(Arc::new(air));
+        let pk = keygen_builder.generate_pk();
+
+        let mut trace = RowMajorMatrix::new(to_field_vec((0..8).flat_map(|i| [1, i]).collect()), 2);
+        tamper(&mut trace);
+        let proof_input = ProofInput {
+            per_air: vec![(air_id, AirProofInput::simple_no_pis(Arc::new(air), trace))],
+        };
+        check_constraints(&pk, &proof_input)
+    }
+
+    #[test]
+    fn test_check_constraints_reports_tampered_row() {
+        let air = CounterAir {
+            with_interaction: false,
+        };
+        assert_eq!(check_counter(air, |_| {}).unwrap(), vec![]);
+
+        let violation =
+            check_counter(air, |trace| trace.values[2 * 5] = BabyBear::TWO).unwrap_err();
+        assert_eq!(violation.air_name, "CounterAir");
+        assert_eq!((violation.constraint_idx, violation.row), (0, 5));
+        assert_eq!(violation.height, 8);
+        assert_eq!(
+            violation.referenced_values,
+            vec![("main[0].local[0]".to_string(), BabyBear::TWO)]
+        );
+
+        let violation =
+            check_counter(air, |trace| trace.values[2 * 3 + 1] = BabyBear::ZERO).unwrap_err();
+        assert_eq!((violation.constraint_idx, violation.row), (1, 2));
+    }
+
+    #[test]
+    fn test_check_constraints_reports_skipped() {
+        let air = CounterAir {
+            with_interaction: true,
+        };
+        let skipped = check_counter(air, |_| {}).unwrap();
+        // The LogUp constraints of the interaction come after the two constraints of the AIR.
+        assert!(!skipped.is_empty());
+        assert!(skipped
+            .iter()
+            .all(|c| c.air_name == "CounterAir" && c.constraint_idx >= 2));
+    }
+}
@@ -48,8 +48,24 @@ where
     setup_tracing();
     let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
     let vm = VirtualMachine::new(engine, config);
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
     let mut result = vm.execute_and_generate(exe, input).unwrap();
+    if std::env::var("OPENVM_DEBUG_CONSTRAINTS").is_ok() {
+        match vm.debug_constraints(&pk, &result) {
+            Ok(skipped) => {
+                if !skipped.is_empty() {
+                    tracing::warn!(
+                        "{} constraints depend on challenges and were not checked",
+                        skipped.len()
+                    );
+                }
+                for constraint in skipped {
+                    tracing::debug!("{constraint}");
+                }
+            }
+            Err(violation) => panic!("{violation}"),
+        }
+    }
     let final_memory = result.final_memory.take();
     let proofs = vm.prove(&pk, result);
 
@@ -75,7 +91,7 @@ where
     setup_tracing();
     let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
     let vm = VirtualMachine::new(engine, config);
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
     let mut result = vm.execute_and_generate(exe, input).unwrap();
     let connector_pvs = &result.per_segment.last().unwrap().per_air[CONNECTOR_AIR_ID]
         .1
@@ -3,16 +3,19 @@ use std::{collections::BTreeMap, sync::Arc};
 use derive_more::derive::From;
 use openvm_circuit::{
     arch::{
+        check_bus_consistency,
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
-        ChipId, ExitCode, MemoryConfig, SingleSegmentVmExecutor, SystemConfig, SystemExecutor,
-        SystemPeriphery, SystemTraceHeights, VirtualMachine, VmChipComplex, VmComplexTraceHeights,
-        VmConfig, VmInventoryError, VmInventoryTraceHeights,
+        BusLintError, BusLintRules, ChipId, ExecutionError, ExitCode, MemoryConfig,
+        SingleSegmentVmExecutor, SystemConfig, SystemExecutor, SystemPeriphery, SystemTraceHeights,
+        VirtualMachine, VmChipComplex, VmComplexTraceHeights, VmConfig, VmConfigError, VmExecutor,
+        VmExecutorResult, VmInventoryError, VmInventoryTraceHeights,
     },
     derive::{AnyEnum, InstructionExecutor, VmConfig},
     system::{
         memory::{
-            tree::public_values::UserPublicValuesProof, MemoryTraceHeights,
-            VolatileMemoryTraceHeights, CHUNK,
+            memory_image_to_equipartition,
+            tree::{public_values::UserPublicValuesProof, MemoryNode},
+            MemoryTraceHeights, VolatileMemoryTraceHeights, CHUNK,
         },
         program::trace::VmCommittedExe,
     },
@@ -20,6 +23,7 @@ use openvm_circuit::{
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{
+    asm::{parse_asm, program_to_asm, OpcodeNames},
     exe::VmExe,
     instruction::Instruction,
     program::{Program, DEFAULT_PC_STEP},
@@ -39,11 +43,13 @@ use openvm_native_compiler::{
     FieldArithmeticOpcode::*, FieldExtensionOpcode::*, NativeBranchEqualOpcode, NativeJalOpcode::*,
     NativeLoadStoreOpcode::*, NativePhantom,
 };
+use openvm_rv32im_circuit::Rv32ImConfig;
 use openvm_rv32im_transpiler::BranchEqualOpcode::*;
 use openvm_stark_backend::{
     config::StarkGenericConfig,
     engine::StarkEngine,
     p3_field::{AbstractField, PrimeField32},
+    Chip,
 };
 use openvm_stark_sdk::{
     config::{
@@ -51,6 +57,7 @@ use openvm_stark_sdk::{
         fri_params::standard_fri_params_with_100_bits_conjectured_security,
         setup_tracing, FriParameters,
     },
+    dummy_airs::interaction::dummy_interaction_air::DummyInteractionAir,
     engine::StarkFriEngine,
     p3_baby_bear::BabyBear,
     utils::create_seeded_rng,
@@ -93,7 +100,7 @@ fn air_test_with_compress_poseidon2(
     };
     let vm = VirtualMachine::new(engine, config);
 
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
     let result = vm.execute_and_generate(program, vec![]).unwrap();
     let proofs = vm.prove(&pk, result);
     for proof in proofs {
@@ -145,6 +152,85 @@ fn test_vm_1() {
     air_test(NativeConfig::default(), program);
 }
 
+#[test]
+fn test_vm_opcode_map() {
+    let opcode_map = VmConfig::<BabyBear>::opcode_map(&NativeConfig::default()).unwrap();
+    assert!(opcode_map.windows(2).all(|w| w[0].opcode < w[1].opcode));
+
+    let storew = VmOpcode::with_default_offset(STOREW).as_usize();
+    let entry = opcode_map
+        .iter()
+        .find(|entry| entry.opcode == storew)
+        .expect("STOREW should be in the opcode map");
+    assert_eq!(entry.name, "STOREW");
+}
+
+#[test]
+fn test_vm_check_program() {
+    let config = NativeConfig::default();
+    let storew = Instruction::from_isize(VmOpcode::with_default_offset(STOREW), 6, 0, 0, 0, 1);
+    let keccak = Instruction::from_isize(VmOpcode::with_default_offset(KECCAK256), 0, 0, 0, 1, 2);
+    let terminate =
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0);
+
+    let program = Program::from_instructions(&[storew.clone(), terminate.clone()]);
+    VmConfig::<BabyBear>::check_program(&config, &program).unwrap();
+
+    let program = Program::from_instructions(&[storew, keccak, terminate]);
+    match VmConfig::<BabyBear>::check_program(&config, &program) {
+        Err(VmConfigError::UnsupportedOpcodes(opcodes)) => assert_eq!(
+            opcodes,
+            vec![VmOpcode::with_default_offset(KECCAK256).as_usize()]
+        ),
+        result => panic!("expected unsupported opcodes, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_vm_asm_round_trip() {
+    let opcode_map = VmConfig::<BabyBear>::opcode_map(&NativeConfig::default()).unwrap();
+    let names = OpcodeNames::new(
+        opcode_map
+            .into_iter()
+            .map(|entry| (entry.opcode, entry.name)),
+    );
+    let instructions = vec![
+        Instruction::from_isize(VmOpcode::with_default_offset(STOREW), 6, 0, 0, 0, 1),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BEQ)),
+            0,
+            0,
+            3 * DEFAULT_PC_STEP as isize,
+            1,
+            0,
+        ),
+        Instruction::large_from_isize(VmOpcode::with_default_offset(SUB), 0, 0, 1, 1, 1, 0, 0),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(JAL),
+            2,
+            -2 * DEFAULT_PC_STEP as isize,
+            0,
+            1,
+            0,
+        ),
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::DebugPanic as u16),
+            BabyBear::ZERO,
+            BabyBear::ZERO,
+            0,
+        ),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let asm = program_to_asm(&program, &names);
+    assert!(asm.contains("STOREW 6 0 0 0 1 0 0"));
+    assert!(asm.contains(" -8 "));
+    let parsed = parse_asm::<BabyBear>(&asm, &names).unwrap();
+    assert_eq!(parsed.instructions(), program.instructions());
+    assert_eq!(program_to_asm(&parsed, &names), asm);
+}
+
 #[test]
 fn test_vm_override_executor_height() {
     let fri_params = FriParameters::standard_fast();
@@ -245,7 +331,7 @@ fn test_vm_1_optional_air() {
     let engine =
         BabyBearPoseidon2Engine::new(standard_fri_params_with_100_bits_conjectured_security(3));
     let vm = VirtualMachine::new(engine, config);
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
     let num_airs = pk.per_air.len();
 
     {
@@ -290,7 +376,7 @@ fn test_vm_public_values() {
     let engine =
         BabyBearPoseidon2Engine::new(standard_fri_params_with_100_bits_conjectured_security(3));
     let vm = VirtualMachine::new(engine, config.clone());
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
 
     {
         let instructions = vec![
@@ -369,6 +455,79 @@ fn test_vm_initial_memory() {
     air_test(config, exe);
 }
 
+#[test]
+fn test_vm_initial_memory_root() {
+    // Program that fails if mem[(1, 7)] != 101. Its exe has no initial memory.
+    let program = Program::from_instructions(&[
+        Instruction::<BabyBear>::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BEQ)),
+            7,
+            101,
+            2 * DEFAULT_PC_STEP as isize,
+            1,
+            0,
+        ),
+        Instruction::<BabyBear>::from_isize(
+            VmOpcode::with_default_offset(PHANTOM),
+            0,
+            0,
+            SysPhantom::DebugPanic as isize,
+            0,
+            0,
+        ),
+        Instruction::<BabyBear>::from_isize(
+            VmOpcode::with_default_offset(TERMINATE),
+            0,
+            0,
+            0,
+            0,
+            0,
+        ),
+    ]);
+    let config = NativeConfig::aggregation(0, 3).with_continuations();
+    let memory_dimensions = config.system.memory_config.memory_dimensions();
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen().unwrap();
+
+    let initial_memory = memory_image_to_equipartition(
+        [(
+            (BabyBear::ONE, BabyBear::from_canonical_u32(7)),
+            BabyBear::from_canonical_u32(101),
+        )]
+        .into_iter()
+        .collect(),
+    );
+    let hasher = vm_poseidon2_hasher();
+    let initial_root =
+        MemoryNode::tree_from_memory(memory_dimensions, &initial_memory, &hasher).hash();
+
+    let result = vm.execute_and_generate_from_memory(
+        program.clone(),
+        vec![],
+        initial_memory.clone(),
+        hasher.compress(&initial_root, &initial_root),
+    );
+    assert!(matches!(
+        result,
+        Err(ExecutionError::InitialMemoryRootMismatch)
+    ));
+
+    let result = vm
+        .execute_and_generate_from_memory(program, vec![], initial_memory, initial_root)
+        .unwrap();
+    let merkle_air_proof_input = &result.per_segment[0]
+        .per_air
+        .iter()
+        .find(|(_, info)| info.air.name() == "MemoryMerkleAir<8>")
+        .unwrap()
+        .1;
+    assert_eq!(merkle_air_proof_input.raw.public_values[..8], initial_root);
+    let proofs = vm.prove(&pk, result);
+    vm.verify(&pk.get_vk(), proofs)
+        .expect("Verification failed");
+}
+
 #[test]
 fn test_vm_1_persistent() {
     let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
@@ -379,7 +538,7 @@ fn test_vm_1_persistent() {
     .with_continuations();
 
     let vm = VirtualMachine::new(engine, config);
-    let pk = vm.keygen();
+    let pk = vm.keygen().unwrap();
 
     let n = 6;
     let instructions = vec![
@@ -434,6 +593,53 @@ fn test_vm_1_persistent() {
         .expect("Verification failed");
 }
 
+#[test]
+fn test_vm_execute_and_generate_streaming() {
+    let n = 10000;
+    // [0]_1 <- [0]_1 + 1 until [0]_1 == n
+    let program = Program::from_instructions(&[
+        Instruction::large_from_isize(VmOpcode::with_default_offset(ADD), 0, 0, 1, 1, 1, 0, 0),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BNE)),
+            n,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            0,
+            1,
+        ),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ]);
+    let config = NativeConfig {
+        system: SystemConfig::new(3, MemoryConfig::default(), 0).with_max_segment_len(n as usize),
+        native: Default::default(),
+    }
+    .with_continuations();
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen().unwrap();
+    let committed_exe = vm.commit_exe(program);
+
+    let mut per_segment = vec![];
+    let final_memory = vm
+        .execute_and_generate_streaming(committed_exe, vec![], |seg_idx, proof_input| {
+            assert_eq!(seg_idx, per_segment.len());
+            per_segment.push(proof_input);
+        })
+        .unwrap();
+    assert!(per_segment.len() >= 2);
+    assert!(final_memory.is_some());
+
+    let proofs = vm.prove(
+        &pk,
+        VmExecutorResult {
+            per_segment,
+            final_memory,
+        },
+    );
+    vm.verify(&pk.get_vk(), proofs)
+        .expect("Verification failed");
+}
+
 #[test]
 fn test_vm_continuations() {
     let n = 200000;
@@ -506,6 +712,60 @@ fn test_vm_continuations() {
     assert_eq!(pv_proof.public_values[0], expected_output);
 }
 
+#[test]
+fn test_vm_run_fast() {
+    let n = 1000;
+    // [0]_1 counts up to n, then the program terminates.
+    let program = Program::from_instructions(&[
+        // [0]_1 <- 0
+        Instruction::from_isize(VmOpcode::with_default_offset(ADD), 0, 0, 0, 1, 0),
+        // [0]_1 <- [0]_1 + 1
+        Instruction::large_from_isize(VmOpcode::with_default_offset(ADD), 0, 0, 1, 1, 1, 0, 0),
+        // if [0]_1 != n, pc <- pc - 1
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BNE)),
+            n,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            0,
+            1,
+        ),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(TERMINATE),
+            0,
+            0,
+            ExitCode::Success as isize,
+            0,
+            0,
+        ),
+    ]);
+
+    let config = NativeConfig {
+        system: SystemConfig::new(3, MemoryConfig::default(), 0).with_max_segment_len(500),
+        native: Default::default(),
+    };
+    let result = VmExecutor::new(config.clone())
+        .run_fast(program.clone(), vec![])
+        .unwrap();
+    assert_eq!(result.cycles, 1 + 2 * n as u64);
+    assert_eq!(result.num_segments, 1);
+    assert_eq!(result.exit_code, ExitCode::Success as u32);
+
+    let executor = VmExecutor::new(config.with_continuations());
+    let result = executor.run_fast(program.clone(), vec![]).unwrap();
+    assert_eq!(result.cycles, 1 + 2 * n as u64);
+    assert!(result.num_segments > 1);
+    assert!(result.final_memory.is_some());
+
+    let segment_heights: Vec<_> = executor
+        .execute_segments(program, vec![])
+        .unwrap()
+        .iter()
+        .map(|segment| segment.current_trace_heights())
+        .collect();
+    assert_eq!(result.summary.segment_heights, segment_heights);
+}
+
 #[test]
 fn test_vm_without_field_arithmetic() {
     /*
@@ -801,6 +1061,44 @@ fn test_vm_hint() {
     air_test_with_min_segments(config, program, input_stream, 1);
 }
 
+#[test]
+fn test_vm_replay_hints() {
+    let program = Program::from_instructions(&[
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(PHANTOM),
+            0,
+            0,
+            NativePhantom::HintInput as isize,
+            0,
+            0,
+        ),
+        // [0]_2 <- length of the input
+        Instruction::from_isize(VmOpcode::with_default_offset(SHINTW), 0, 0, 0, 1, 2),
+        // [1]_2 <- input
+        Instruction::from_isize(VmOpcode::with_default_offset(SHINTW), 0, 1, 0, 1, 2),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ]);
+
+    type F = BabyBear;
+
+    let config = NativeConfig::default();
+    let executor = VmExecutor::new(config.clone());
+    let bundle = executor
+        .record_hints(program.clone(), vec![vec![F::from_canonical_u32(7)]])
+        .unwrap();
+    assert_eq!(bundle.hints, vec![F::ONE, F::from_canonical_u32(7)]);
+
+    // Hints are read from the bundle, not from the hint stream filled by the phantom.
+    let mut truncated = bundle.clone();
+    truncated.hints.pop();
+    assert!(matches!(
+        executor.run_fast(program.clone(), truncated),
+        Err(ExecutionError::HintOutOfBounds { .. })
+    ));
+
+    air_test_with_min_segments(config, program, bundle, 1);
+}
+
 #[test]
 fn test_vm_compress_poseidon2_as2() {
     let mut rng = create_seeded_rng();
@@ -1044,3 +1342,119 @@ fn test_vm_keccak_non_full_round() {
 
     air_test(NativeKeccakConfig::default(), program);
 }
+
+fn assert_buses_consistent<VC>(config: VC)
+where
+    VC: VmConfig<BabyBear>,
+    VC::Executor: Chip<BabyBearPoseidon2Config>,
+    VC::Periphery: Chip<BabyBearPoseidon2Config>,
+{
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    VirtualMachine::new(engine, config).keygen().unwrap();
+}
+
+#[test]
+fn test_vm_bus_consistency() {
+    assert_buses_consistent(Rv32ImConfig::default());
+    assert_buses_consistent(NativeConfig::default());
+    assert_buses_consistent(NativeConfig::new(
+        SystemConfig::default(),
+        Native { mul_add: true },
+    ));
+    assert_buses_consistent(NativeConfig::aggregation(0, 3));
+    assert_buses_consistent(NativeConfig::aggregation(0, 3).with_continuations());
+}
+
+#[test]
+fn test_vm_bus_consistency_mismatch() {
+    const BUS: usize = 0;
+    const UNRECEIVED_BUS: usize = 1;
+    const UNUSED_BUS: usize = 2;
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let mut keygen_builder = engine.keygen_builder();
+    keygen_builder.add_air(Arc::new(DummyInteractionAir::new(2, true, BUS)));
+    keygen_builder.add_air(Arc::new(DummyInteractionAir::new(1, false, BUS)));
+    keygen_builder.add_air(Arc::new(DummyInteractionAir::new(1, true, UNRECEIVED_BUS)));
+    keygen_builder.add_air(Arc::new(DummyInteractionAir::new(1, false, UNUSED_BUS)));
+    let pk = keygen_builder.generate_pk();
+
+    let errors = check_bus_consistency(&pk, &BusLintRules::default()).unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert!(matches!(
+        &errors[0],
+        BusLintError::ArityMismatch {
+            bus: BUS,
+            arity: 2,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &errors[1],
+        BusLintError::UnsentArity {
+            bus: BUS,
+            arity: 1,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &errors[2],
+        BusLintError::Unreceived {
+            bus: UNRECEIVED_BUS,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &errors[3],
+        BusLintError::Unsent {
+            bus: UNUSED_BUS,
+            ..
+        }
+    ));
+
+    // A lookup bus may have receives without sends, but not sends without receives.
+    let rules = BusLintRules {
+        lookup_buses: vec![BUS, UNUSED_BUS],
+        ..Default::default()
+    };
+    assert_eq!(
+        check_bus_consistency(&pk, &rules).unwrap_err(),
+        vec![errors[0].clone(), errors[2].clone()]
+    );
+
+    // On a signed bus, each message length must be used by two AIRs unless the AIR is optional.
+    let rules = BusLintRules {
+        signed_buses: vec![BUS],
+        ..Default::default()
+    };
+    let signed_errors = check_bus_consistency(&pk, &rules).unwrap_err();
+    assert_eq!(signed_errors.len(), 4);
+    assert!(matches!(
+        &signed_errors[0],
+        BusLintError::UnpairedArity {
+            bus: BUS,
+            arity: 1,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &signed_errors[1],
+        BusLintError::UnpairedArity {
+            bus: BUS,
+            arity: 2,
+            ..
+        }
+    ));
+    let rules = BusLintRules {
+        signed_buses: vec![BUS],
+        optional_air_ids: vec![0],
+        ..Default::default()
+    };
+    assert_eq!(
+        check_bus_consistency(&pk, &rules).unwrap_err(),
+        vec![
+            signed_errors[0].clone(),
+            errors[2].clone(),
+            errors[3].clone()
+        ]
+    );
+}
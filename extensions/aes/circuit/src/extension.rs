@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_aes_transpiler::Rv32AesOpcode;
+use openvm_circuit::{
+    arch::{
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
+        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::*;
+use openvm_rv32_adapters::Rv32HeapAdapterChip;
+use openvm_rv32im_circuit::{
+    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+    Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct AesRv32Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub aes: Aes,
+}
+
+impl Default for AesRv32Config {
+    fn default() -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            aes: Aes,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Aes;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum AesExecutor<F: PrimeField32> {
+    AesRound(Rv32AesRoundChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum AesPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    SboxLookup(Arc<AesSboxLookupChip>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for Aes {
+    type Executor = AesExecutor<F>;
+    type Periphery = AesPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+        let sbox_lu_chip = builder.find_or_add_periphery_chip(&mut inventory, |builder| {
+            Arc::new(AesSboxLookupChip::new(AesSboxLookupBus::new(
+                builder.new_lookup_bus_idx(),
+            )))
+        });
+
+        let round_chip = Rv32AesRoundChip::new(
+            Rv32HeapAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            AesRoundCoreChip::new(
+                bitwise_lu_chip,
+                sbox_lu_chip,
+                Rv32AesOpcode::default_offset(),
+            ),
+            memory_controller,
+        );
+        inventory.add_executor(
+            round_chip,
+            Rv32AesOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
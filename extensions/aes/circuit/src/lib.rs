@@ -0,0 +1,21 @@
+//! AES encryption rounds for RV32 guests. Each instruction performs one round on a 16-byte state
+//! in memory; the guest library builds block encryption and modes of operation on top.
+use openvm_aes_guest::BLOCK_SIZE;
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+use openvm_rv32_adapters::{Rv32HeapAdapterAir, Rv32HeapAdapterChip};
+
+mod round;
+pub use round::*;
+mod sbox_lookup;
+pub use sbox_lookup::*;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32AesRoundAir =
+    VmAirWrapper<Rv32HeapAdapterAir<2, BLOCK_SIZE, BLOCK_SIZE>, AesRoundCoreAir>;
+pub type Rv32AesRoundChip<F> =
+    VmChipWrapper<F, Rv32HeapAdapterChip<F, 2, BLOCK_SIZE, BLOCK_SIZE>, AesRoundCoreChip>;
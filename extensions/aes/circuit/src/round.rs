@@ -0,0 +1,261 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_aes_guest::{shift_rows_src, BLOCK_SIZE};
+use openvm_aes_transpiler::Rv32AesOpcode;
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{AesSboxLookupBus, AesSboxLookupChip};
+
+/// Number of XORs in the MixColumns sum `2 a0 ^ 3 a1 ^ a2 ^ a3` of an output byte, computed as
+/// `((((2 a0 ^ 2 a1) ^ a1) ^ a2) ^ a3)`.
+const MIX_XORS: usize = 4;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct AesRoundCoreCols<T> {
+    pub state: [T; BLOCK_SIZE],
+    pub round_key: [T; BLOCK_SIZE],
+    /// `S(state[i])`
+    pub sbox: [T; BLOCK_SIZE],
+    /// `2 * S(state[i])` in `GF(2^8)`
+    pub sbox_x2: [T; BLOCK_SIZE],
+    /// Partial MixColumns sums of each output byte. Constrained to zero in the last round, which
+    /// skips MixColumns, and on padding rows.
+    pub mix: [[T; MIX_XORS]; BLOCK_SIZE],
+    pub output: [T; BLOCK_SIZE],
+
+    pub opcode_enc_round_flag: T,
+    pub opcode_enc_last_round_flag: T,
+}
+
+/// One AES encryption round on a state read from memory. SubBytes uses the S-box table, ShiftRows
+/// is a permutation of columns, and the XORs of MixColumns and AddRoundKey use the bitwise lookup.
+#[derive(Copy, Clone, Debug)]
+pub struct AesRoundCoreAir {
+    pub bitwise_bus: BitwiseOperationLookupBus,
+    pub sbox_bus: AesSboxLookupBus,
+    pub offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for AesRoundCoreAir {
+    fn width(&self) -> usize {
+        AesRoundCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for AesRoundCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for AesRoundCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; BLOCK_SIZE]; 2]>,
+    I::Writes: From<[[AB::Expr; BLOCK_SIZE]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &AesRoundCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_enc_round_flag, cols.opcode_enc_last_round_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        for i in 0..BLOCK_SIZE {
+            self.sbox_bus.send(
+                builder,
+                [cols.state[i], cols.sbox[i], cols.sbox_x2[i]],
+                is_valid.clone(),
+            );
+        }
+
+        for i in 0..BLOCK_SIZE {
+            let (row, col) = (i % 4, i / 4);
+            // The k-th byte of the column of byte i after ShiftRows, starting from row `row`.
+            let src = |k: usize| shift_rows_src((row + k) % 4 + 4 * col);
+            let mix = &cols.mix[i];
+            self.bitwise_bus
+                .send_xor(cols.sbox_x2[src(0)], cols.sbox_x2[src(1)], mix[0])
+                .eval(builder, cols.opcode_enc_round_flag);
+            for k in 1..MIX_XORS {
+                self.bitwise_bus
+                    .send_xor(mix[k - 1], cols.sbox[src(k)], mix[k])
+                    .eval(builder, cols.opcode_enc_round_flag);
+            }
+            for &m in mix {
+                builder
+                    .when(not::<AB::Expr>(cols.opcode_enc_round_flag.into()))
+                    .assert_zero(m);
+            }
+            let before_key = cols.opcode_enc_round_flag * mix[MIX_XORS - 1]
+                + cols.opcode_enc_last_round_flag * cols.sbox[src(0)];
+            self.bitwise_bus
+                .send_xor(before_key, cols.round_key[i], cols.output[i])
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32AesOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.state.map(Into::into), cols.round_key.map(Into::into)].into(),
+            writes: [cols.output.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AesRoundCoreRecord {
+    pub opcode: Rv32AesOpcode,
+    pub state: [u8; BLOCK_SIZE],
+    pub round_key: [u8; BLOCK_SIZE],
+    pub sbox: [u8; BLOCK_SIZE],
+    pub sbox_x2: [u8; BLOCK_SIZE],
+    pub mix: [[u8; MIX_XORS]; BLOCK_SIZE],
+    pub output: [u8; BLOCK_SIZE],
+}
+
+#[derive(Debug)]
+pub struct AesRoundCoreChip {
+    pub air: AesRoundCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub sbox_lookup_chip: Arc<AesSboxLookupChip>,
+}
+
+impl AesRoundCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        sbox_lookup_chip: Arc<AesSboxLookupChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: AesRoundCoreAir {
+                bitwise_bus: bitwise_lookup_chip.bus(),
+                sbox_bus: sbox_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            sbox_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for AesRoundCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; BLOCK_SIZE]; 2]>,
+    I::Writes: From<[[F; BLOCK_SIZE]; 1]>,
+{
+    type Record = AesRoundCoreRecord;
+    type Air = AesRoundCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32AesOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; BLOCK_SIZE]; 2] = reads.into();
+        let [state, round_key] = data.map(|block| block.map(|x| x.as_canonical_u32() as u8));
+
+        let lookups = state.map(|x| self.sbox_lookup_chip.request(x));
+        let sbox = lookups.map(|(s, _)| s);
+        let sbox_x2 = lookups.map(|(_, s2)| s2);
+
+        let xor = |x: u8, y: u8| self.bitwise_lookup_chip.request_xor(x as u32, y as u32) as u8;
+        let mut mix = [[0u8; MIX_XORS]; BLOCK_SIZE];
+        let output = array::from_fn(|i| {
+            let (row, col) = (i % 4, i / 4);
+            let src = |k: usize| shift_rows_src((row + k) % 4 + 4 * col);
+            let before_key = match local_opcode {
+                Rv32AesOpcode::ENC_ROUND => {
+                    mix[i][0] = xor(sbox_x2[src(0)], sbox_x2[src(1)]);
+                    for k in 1..MIX_XORS {
+                        mix[i][k] = xor(mix[i][k - 1], sbox[src(k)]);
+                    }
+                    mix[i][MIX_XORS - 1]
+                }
+                Rv32AesOpcode::ENC_LAST_ROUND => sbox[src(0)],
+            };
+            xor(before_key, round_key[i])
+        });
+
+        let output_context = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [output.map(F::from_canonical_u8)].into(),
+        };
+        let record = AesRoundCoreRecord {
+            opcode: local_opcode,
+            state,
+            round_key,
+            sbox,
+            sbox_x2,
+            mix,
+            output,
+        };
+
+        Ok((output_context, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", Rv32AesOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut AesRoundCoreCols<_> = row_slice.borrow_mut();
+        row_slice.state = record.state.map(F::from_canonical_u8);
+        row_slice.round_key = record.round_key.map(F::from_canonical_u8);
+        row_slice.sbox = record.sbox.map(F::from_canonical_u8);
+        row_slice.sbox_x2 = record.sbox_x2.map(F::from_canonical_u8);
+        row_slice.mix = record.mix.map(|mix| mix.map(F::from_canonical_u8));
+        row_slice.output = record.output.map(F::from_canonical_u8);
+        row_slice.opcode_enc_round_flag = F::from_bool(record.opcode == Rv32AesOpcode::ENC_ROUND);
+        row_slice.opcode_enc_last_round_flag =
+            F::from_bool(record.opcode == Rv32AesOpcode::ENC_LAST_ROUND);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
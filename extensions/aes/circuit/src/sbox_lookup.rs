@@ -0,0 +1,185 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    mem::size_of,
+    sync::{atomic::AtomicU32, Arc},
+};
+
+use openvm_aes_guest::{xtime, SBOX};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::{InteractionBuilder, InteractionType},
+    p3_air::{Air, BaseAir, PairBuilder},
+    p3_field::Field,
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+/// Bus of the AES S-box table. Messages are `[x, S(x), 2 * S(x)]`, where the product is in
+/// `GF(2^8)`, so that MixColumns needs no further lookups for its doublings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AesSboxLookupBus {
+    pub index: usize,
+}
+
+impl AesSboxLookupBus {
+    pub const fn new(index: usize) -> Self {
+        Self { index }
+    }
+
+    pub fn send<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        message: [impl Into<AB::Expr>; 3],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_interaction(self.index, message, count, InteractionType::Send);
+    }
+
+    pub fn receive<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        message: [impl Into<AB::Expr>; 3],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_interaction(self.index, message, count, InteractionType::Receive);
+    }
+}
+
+#[derive(Default, AlignedBorrow, Copy, Clone)]
+#[repr(C)]
+pub struct AesSboxLookupCols<T> {
+    pub mult: T,
+}
+
+#[derive(Default, AlignedBorrow, Copy, Clone)]
+#[repr(C)]
+pub struct AesSboxLookupPreprocessedCols<T> {
+    pub x: T,
+    pub sbox: T,
+    pub sbox_x2: T,
+}
+
+pub const NUM_AES_SBOX_LOOKUP_COLS: usize = size_of::<AesSboxLookupCols<u8>>();
+pub const NUM_AES_SBOX_LOOKUP_PREPROCESSED_COLS: usize =
+    size_of::<AesSboxLookupPreprocessedCols<u8>>();
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct AesSboxLookupAir {
+    pub bus: AesSboxLookupBus,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for AesSboxLookupAir {}
+impl<F: Field> PartitionedBaseAir<F> for AesSboxLookupAir {}
+impl<F: Field> BaseAir<F> for AesSboxLookupAir {
+    fn width(&self) -> usize {
+        NUM_AES_SBOX_LOOKUP_COLS
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let rows: Vec<F> = SBOX
+            .iter()
+            .enumerate()
+            .flat_map(|(x, &s)| {
+                [
+                    F::from_canonical_usize(x),
+                    F::from_canonical_u8(s),
+                    F::from_canonical_u8(xtime(s)),
+                ]
+            })
+            .collect();
+        Some(RowMajorMatrix::new(
+            rows,
+            NUM_AES_SBOX_LOOKUP_PREPROCESSED_COLS,
+        ))
+    }
+}
+
+impl<AB: InteractionBuilder + PairBuilder> Air<AB> for AesSboxLookupAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let prep_local = preprocessed.row_slice(0);
+        let prep_local: &AesSboxLookupPreprocessedCols<AB::Var> = (*prep_local).borrow();
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &AesSboxLookupCols<AB::Var> = (*local).borrow();
+
+        self.bus.receive(
+            builder,
+            [prep_local.x, prep_local.sbox, prep_local.sbox_x2],
+            local.mult,
+        );
+    }
+}
+
+/// Lookup table of the AES S-box, with one row per byte.
+#[derive(Debug)]
+pub struct AesSboxLookupChip {
+    pub air: AesSboxLookupAir,
+    count: Vec<AtomicU32>,
+}
+
+impl AesSboxLookupChip {
+    pub fn new(bus: AesSboxLookupBus) -> Self {
+        Self {
+            air: AesSboxLookupAir::new(bus),
+            count: (0..SBOX.len()).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    pub fn bus(&self) -> AesSboxLookupBus {
+        self.air.bus
+    }
+
+    /// Records a lookup of `x` and returns `(S(x), 2 * S(x))`.
+    pub fn request(&self, x: u8) -> (u8, u8) {
+        self.count[x as usize].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let s = SBOX[x as usize];
+        (s, xtime(s))
+    }
+
+    pub fn clear(&self) {
+        for count in &self.count {
+            count.store(0, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    pub fn generate_trace<F: Field>(&self) -> RowMajorMatrix<F> {
+        let mut rows = F::zero_vec(self.count.len() * NUM_AES_SBOX_LOOKUP_COLS);
+        for (n, row) in rows.chunks_mut(NUM_AES_SBOX_LOOKUP_COLS).enumerate() {
+            let cols: &mut AesSboxLookupCols<F> = row.borrow_mut();
+            cols.mult =
+                F::from_canonical_u32(self.count[n].load(std::sync::atomic::Ordering::SeqCst));
+        }
+        RowMajorMatrix::new(rows, NUM_AES_SBOX_LOOKUP_COLS)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for AesSboxLookupChip {
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let trace = self.generate_trace::<Val<SC>>();
+        AirProofInput::simple_no_pis(Arc::new(self.air), trace)
+    }
+}
+
+impl ChipUsageGetter for AesSboxLookupChip {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+    fn constant_trace_height(&self) -> Option<usize> {
+        Some(SBOX.len())
+    }
+    fn current_trace_height(&self) -> usize {
+        SBOX.len()
+    }
+    fn trace_width(&self) -> usize {
+        NUM_AES_SBOX_LOOKUP_COLS
+    }
+}
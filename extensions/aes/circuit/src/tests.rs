@@ -0,0 +1,176 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use hex_literal::hex;
+use openvm_aes_guest::{Block, BLOCK_SIZE};
+use openvm_aes_transpiler::Rv32AesOpcode;
+use openvm_circuit::arch::{
+    testing::{Tamper, VmChipTestBuilder},
+    VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_rv32_adapters::{rv32_write_heap_default, Rv32HeapAdapterChip};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+use super::{
+    AesRoundCoreChip, AesRoundCoreCols, AesSboxLookupBus, AesSboxLookupChip, Rv32AesRoundChip,
+};
+
+type F = BabyBear;
+
+const PLAINTEXT: [u8; 16] = hex!("00112233445566778899aabbccddeeff");
+
+#[test]
+fn test_aes_fips197_vectors() {
+    let key_128: [u8; 16] = core::array::from_fn(|i| i as u8);
+    let key_256: [u8; 32] = core::array::from_fn(|i| i as u8);
+    assert_eq!(
+        openvm_aes_guest::Aes::new_128(&key_128).encrypt_block(&PLAINTEXT),
+        hex!("69c4e0d86a7b0430d8cdb78070b4c55a")
+    );
+    assert_eq!(
+        openvm_aes_guest::Aes::new_256(&key_256).encrypt_block(&PLAINTEXT),
+        hex!("8ea2b7ca516745bfeafc49904b496089")
+    );
+
+    // CTR mode is its own inverse.
+    let aes = openvm_aes_guest::Aes::new_128(&key_128);
+    let mut data = *b"counter mode over a partial block";
+    aes.apply_ctr(&PLAINTEXT, &mut data);
+    assert_ne!(&data, b"counter mode over a partial block");
+    aes.apply_ctr(&PLAINTEXT, &mut data);
+    assert_eq!(&data, b"counter mode over a partial block");
+}
+
+/// Encrypts the FIPS 197 AES-128 example with the round chip, one instruction per round.
+fn run_aes_rounds() -> (
+    VmChipTestBuilder<F>,
+    Rv32AesRoundChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    Arc<AesSboxLookupChip>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let sbox_chip = Arc::new(AesSboxLookupChip::new(AesSboxLookupBus::new(
+        BITWISE_OP_LOOKUP_BUS + 1,
+    )));
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = Rv32AesRoundChip::new(
+        Rv32HeapAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        AesRoundCoreChip::new(
+            bitwise_chip.clone(),
+            sbox_chip.clone(),
+            Rv32AesOpcode::default_offset(),
+        ),
+        tester.memory_controller(),
+    );
+
+    let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+    let aes = openvm_aes_guest::Aes::new_128(&key);
+    let round_keys = aes.round_keys();
+    let mut state: [u8; BLOCK_SIZE] = core::array::from_fn(|i| PLAINTEXT[i] ^ round_keys[0].0[i]);
+    let num_rounds = round_keys.len() - 1;
+    for (round, Block(round_key)) in round_keys.iter().enumerate().skip(1) {
+        let opcode = if round == num_rounds {
+            Rv32AesOpcode::ENC_LAST_ROUND
+        } else {
+            Rv32AesOpcode::ENC_ROUND
+        };
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![state.map(F::from_canonical_u8)],
+            vec![round_key.map(F::from_canonical_u8)],
+            opcode.with_default_offset(),
+        );
+        let rd_ptr = instruction.a.as_canonical_u32() as usize;
+        tester.execute(&mut chip, instruction);
+
+        let output_ptr = u32::from_le_bytes(
+            tester
+                .read::<4>(RV32_REGISTER_AS as usize, rd_ptr)
+                .map(|x| x.as_canonical_u32() as u8),
+        );
+        state = tester
+            .read::<BLOCK_SIZE>(RV32_MEMORY_AS as usize, output_ptr as usize)
+            .map(|x| x.as_canonical_u32() as u8);
+    }
+    assert_eq!(state, hex!("69c4e0d86a7b0430d8cdb78070b4c55a"));
+    (tester, chip, bitwise_chip, sbox_chip)
+}
+
+#[test]
+fn test_aes_round_chip() {
+    let (tester, chip, bitwise_chip, sbox_chip) = run_aes_rounds();
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(bitwise_chip)
+        .load(sbox_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Tampers with the trace of the rounds and asserts that it is rejected with `expected_error`.
+/// Row 0 is the first round, which includes MixColumns, and row 9 is the last round, which skips
+/// it.
+fn run_aes_round_negative_test(
+    tamper: impl Fn(&AesRoundCoreCols<usize>, usize) -> Tamper<F>,
+    expected_error: VerificationError,
+) {
+    let (tester, chip, bitwise_chip, sbox_chip) = run_aes_rounds();
+    let indices = (0..AesRoundCoreCols::<F>::width()).collect::<Vec<_>>();
+    let cols: &AesRoundCoreCols<usize> = indices[..].borrow();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let tamper = tamper(cols, adapter_width);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .load(sbox_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn test_aes_round_wrong_sbox_negative() {
+    run_aes_round_negative_test(
+        |cols, adapter_width| Tamper::off_by_one(0, adapter_width + cols.sbox[5]),
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn test_aes_round_wrong_mix_columns_negative() {
+    run_aes_round_negative_test(
+        |cols, adapter_width| Tamper::off_by_one(0, adapter_width + cols.mix[2][1]),
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn test_aes_last_round_nonzero_mix_negative() {
+    run_aes_round_negative_test(
+        |cols, adapter_width| Tamper::off_by_one(9, adapter_width + cols.mix[2][1]),
+        VerificationError::OodEvaluationMismatch,
+    );
+}
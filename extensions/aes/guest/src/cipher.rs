@@ -0,0 +1,96 @@
+use crate::{enc_last_round, enc_round, Block, BLOCK_SIZE, SBOX};
+
+/// Round constants of the key schedule.
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Number of rounds of AES-256, the largest supported key size.
+const MAX_ROUNDS: usize = 14;
+
+/// AES encryption with an expanded key. Blocks are encrypted with the round instructions in the
+/// zkVM, while the key schedule is computed in software once per key.
+///
+/// Only encryption is provided. It is enough to check ciphertexts and to run the CTR mode in
+/// both directions.
+#[derive(Clone, Debug)]
+pub struct Aes {
+    round_keys: [Block; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl Aes {
+    /// AES-128.
+    pub fn new_128(key: &[u8; 16]) -> Self {
+        Self::new(key)
+    }
+
+    /// AES-256.
+    pub fn new_256(key: &[u8; 32]) -> Self {
+        Self::new(key)
+    }
+
+    /// Key expansion from FIPS 197, section 5.2.
+    fn new(key: &[u8]) -> Self {
+        let nk = key.len() / 4;
+        let rounds = nk + 6;
+        let mut words = [[0u8; 4]; 4 * (MAX_ROUNDS + 1)];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(chunk);
+        }
+        for i in nk..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| SBOX[b as usize]);
+                temp[0] ^= RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(|b| SBOX[b as usize]);
+            }
+            words[i] = core::array::from_fn(|j| words[i - nk][j] ^ temp[j]);
+        }
+        let round_keys =
+            core::array::from_fn(|r| Block(core::array::from_fn(|i| words[4 * r + i / 4][i % 4])));
+        Self { round_keys, rounds }
+    }
+
+    /// The expanded key: the whitening key followed by one key per round.
+    pub fn round_keys(&self) -> &[Block] {
+        &self.round_keys[..=self.rounds]
+    }
+
+    /// Encrypts a single block.
+    pub fn encrypt_block(&self, block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+        let mut state = Block(core::array::from_fn(|i| block[i] ^ self.round_keys[0].0[i]));
+        for round_key in &self.round_keys[1..self.rounds] {
+            state = enc_round(&state, round_key);
+        }
+        enc_last_round(&state, &self.round_keys[self.rounds]).0
+    }
+
+    /// Encrypts `data` in place in ECB mode.
+    ///
+    /// Panics if the length of `data` is not a multiple of the block size.
+    pub fn encrypt_ecb(&self, data: &mut [u8]) {
+        assert_eq!(
+            data.len() % BLOCK_SIZE,
+            0,
+            "ECB input must be a whole number of blocks"
+        );
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut [u8; BLOCK_SIZE] = chunk.try_into().unwrap();
+            *block = self.encrypt_block(block);
+        }
+    }
+
+    /// Encrypts or decrypts `data` in place in CTR mode, starting from the counter block `iv`.
+    /// The whole block is incremented as a 128-bit big-endian integer, as in NIST SP 800-38A.
+    pub fn apply_ctr(&self, iv: &[u8; BLOCK_SIZE], data: &mut [u8]) {
+        let mut counter = u128::from_be_bytes(*iv);
+        for chunk in data.chunks_mut(BLOCK_SIZE) {
+            let keystream = self.encrypt_block(&counter.to_be_bytes());
+            for (byte, key_byte) in chunk.iter_mut().zip(keystream) {
+                *byte ^= key_byte;
+            }
+            counter = counter.wrapping_add(1);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+#![no_std]
+
+#[cfg(target_os = "zkvm")]
+use core::mem::MaybeUninit;
+
+use strum_macros::FromRepr;
+
+mod cipher;
+pub use cipher::*;
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+pub const FUNCT3: u8 = 0b111;
+
+/// The AES round instructions. Both take pointers to the state and the round key in `rs1` and
+/// `rs2`, and write the new state to the pointer in `rd`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum AesBaseFunct7 {
+    /// `MixColumns(ShiftRows(SubBytes(state))) ^ round_key`
+    EncRound = 0,
+    /// `ShiftRows(SubBytes(state)) ^ round_key`, the last round of an encryption.
+    EncLastRound,
+}
+
+/// Issues the round instruction `$funct7` with a fresh output block.
+#[cfg(target_os = "zkvm")]
+macro_rules! round_insn {
+    ($funct7:ident, $state:expr, $round_key:expr) => {{
+        let mut uninit = MaybeUninit::<Block>::uninit();
+        openvm_platform::custom_insn_r!(
+            OPCODE,
+            FUNCT3,
+            AesBaseFunct7::$funct7 as u8,
+            uninit.as_mut_ptr(),
+            $state as *const Block,
+            $round_key as *const Block
+        );
+        unsafe { uninit.assume_init() }
+    }};
+}
+
+/// Size of an AES block in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// An AES block or round key. The state is stored column by column, so byte `r + 4 * c` is in
+/// row `r` and column `c`, as in FIPS 197.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C, align(16))]
+pub struct Block(pub [u8; BLOCK_SIZE]);
+
+/// The AES S-box.
+pub const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Multiplication by `x` in `GF(2^8) = GF(2)[x]/(x^8 + x^4 + x^3 + x + 1)`.
+pub const fn xtime(a: u8) -> u8 {
+    (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Index in the state of the byte that ShiftRows moves to index `i`.
+pub const fn shift_rows_src(i: usize) -> usize {
+    let (row, col) = (i % 4, i / 4);
+    row + 4 * ((col + row) % 4)
+}
+
+/// One full encryption round: SubBytes, ShiftRows, MixColumns and AddRoundKey.
+#[inline(always)]
+pub fn enc_round(state: &Block, round_key: &Block) -> Block {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let shifted = sub_shift(state);
+        Block(core::array::from_fn(|i| {
+            let (row, col) = (i % 4, i / 4);
+            let a = |k: usize| shifted[(row + k) % 4 + 4 * col];
+            xtime(a(0)) ^ xtime(a(1)) ^ a(1) ^ a(2) ^ a(3) ^ round_key.0[i]
+        }))
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        round_insn!(EncRound, state, round_key)
+    }
+}
+
+/// The last encryption round, which has no MixColumns.
+#[inline(always)]
+pub fn enc_last_round(state: &Block, round_key: &Block) -> Block {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let shifted = sub_shift(state);
+        Block(core::array::from_fn(|i| shifted[i] ^ round_key.0[i]))
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        round_insn!(EncLastRound, state, round_key)
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+fn sub_shift(state: &Block) -> [u8; BLOCK_SIZE] {
+    core::array::from_fn(|i| SBOX[state.0[shift_rows_src(i)] as usize])
+}
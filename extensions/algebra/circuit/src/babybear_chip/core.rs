@@ -0,0 +1,297 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_algebra_transpiler::BabyBearOpcode;
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use super::BABYBEAR_NUM_BYTES;
+use crate::babybear_ext4_chip::BABYBEAR_MODULUS;
+
+/// Most significant byte of `p - 1 = 0x78000000`, the largest canonical element.
+const MAX_TOP_BYTE: u32 = (BABYBEAR_MODULUS - 1) >> 24;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct BabyBearArithCoreCols<T> {
+    pub a: [T; BABYBEAR_NUM_BYTES],
+    pub b: [T; BABYBEAR_NUM_BYTES],
+    pub c: [T; BABYBEAR_NUM_BYTES],
+    /// Inverse of `c` for DIV, zero otherwise.
+    pub c_inv: T,
+    /// Whether `a = p - 1`, the only canonical value with top byte `0x78`.
+    pub a_is_max: T,
+
+    pub opcode_add_flag: T,
+    pub opcode_sub_flag: T,
+    pub opcode_mul_flag: T,
+    pub opcode_div_flag: T,
+}
+
+/// Arithmetic on BabyBear elements stored as little-endian `u32`s. The AIR must be over BabyBear:
+/// operands are composed from their bytes in the trace field, which reduces them modulo `p`, and
+/// the output is constrained to be the canonical representative.
+#[derive(Copy, Clone, Debug)]
+pub struct BabyBearArithCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for BabyBearArithCoreAir {
+    fn width(&self) -> usize {
+        BabyBearArithCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for BabyBearArithCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for BabyBearArithCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; BABYBEAR_NUM_BYTES]; 2]>,
+    I::Writes: From<[[AB::Expr; BABYBEAR_NUM_BYTES]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &BabyBearArithCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_add_flag,
+            cols.opcode_sub_flag,
+            cols.opcode_mul_flag,
+            cols.opcode_div_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let compose = |bytes: &[AB::Var; BABYBEAR_NUM_BYTES]| {
+            bytes.iter().rev().fold(AB::Expr::ZERO, |acc, &byte| {
+                acc * AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) + byte
+            })
+        };
+        let a = compose(&cols.a);
+        let b = compose(&cols.b);
+        let c = compose(&cols.c);
+
+        builder
+            .when(cols.opcode_add_flag)
+            .assert_eq(a.clone(), b.clone() + c.clone());
+        builder
+            .when(cols.opcode_sub_flag)
+            .assert_eq(a.clone(), b.clone() - c.clone());
+        builder
+            .when(cols.opcode_mul_flag)
+            .assert_eq(a.clone(), b.clone() * c.clone());
+        builder
+            .when(cols.opcode_div_flag)
+            .assert_one(c * cols.c_inv);
+        builder
+            .when(cols.opcode_div_flag)
+            .assert_eq(a, b * cols.c_inv);
+
+        // The output is canonical when its bytes are in range and either its top byte is at most
+        // 0x77, or it is exactly 0x78000000.
+        builder.assert_bool(cols.a_is_max);
+        builder
+            .when(cols.a_is_max)
+            .assert_eq(cols.a[3], AB::Expr::from_canonical_u32(MAX_TOP_BYTE));
+        builder
+            .when(cols.a_is_max)
+            .assert_zero(cols.a[0] + cols.a[1] + cols.a[2]);
+        self.bus
+            .send_range(cols.a[0], cols.a[1])
+            .eval(builder, is_valid.clone());
+        self.bus
+            .send_range(cols.a[2], cols.a[3])
+            .eval(builder, is_valid.clone());
+        self.bus
+            .send_range(
+                AB::Expr::from_canonical_u32(MAX_TOP_BYTE - 1) + cols.a_is_max - cols.a[3],
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_valid.clone());
+
+        let expected_opcode = flags.iter().zip(BabyBearOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BabyBearArithCoreRecord<T> {
+    pub opcode: BabyBearOpcode,
+    pub a: [T; BABYBEAR_NUM_BYTES],
+    pub b: [T; BABYBEAR_NUM_BYTES],
+    pub c: [T; BABYBEAR_NUM_BYTES],
+    pub c_inv: T,
+    pub a_is_max: bool,
+}
+
+#[derive(Debug)]
+pub struct BabyBearArithCoreChip {
+    pub air: BabyBearArithCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl BabyBearArithCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: BabyBearArithCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for BabyBearArithCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; BABYBEAR_NUM_BYTES]; 2]>,
+    I::Writes: From<[[F; BABYBEAR_NUM_BYTES]; 1]>,
+{
+    type Record = BabyBearArithCoreRecord<F>;
+    type Air = BabyBearArithCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = BabyBearOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; BABYBEAR_NUM_BYTES]; 2] = reads.into();
+        let b = u32::from_le_bytes(data[0].map(|x| x.as_canonical_u32() as u8));
+        let c = u32::from_le_bytes(data[1].map(|x| x.as_canonical_u32() as u8));
+        let a =
+            run_babybear_arith(local_opcode, b, c).ok_or(ExecutionError::Fail { pc: from_pc })?;
+        let a_bytes = a.to_le_bytes().map(u32::from);
+        let a_is_max = a == BABYBEAR_MODULUS - 1;
+
+        self.bitwise_lookup_chip
+            .request_range(a_bytes[0], a_bytes[1]);
+        self.bitwise_lookup_chip
+            .request_range(a_bytes[2], a_bytes[3]);
+        self.bitwise_lookup_chip
+            .request_range(MAX_TOP_BYTE - 1 + a_is_max as u32 - a_bytes[3], 0);
+
+        let c_inv = if local_opcode == BabyBearOpcode::DIV {
+            F::from_canonical_u32(c % BABYBEAR_MODULUS).inverse()
+        } else {
+            F::ZERO
+        };
+        let a = a_bytes.map(F::from_canonical_u32);
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [a].into(),
+        };
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+            c_inv,
+            a_is_max,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "BabyBear{:?}",
+            BabyBearOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut BabyBearArithCoreCols<_> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.c_inv = record.c_inv;
+        row_slice.a_is_max = F::from_bool(record.a_is_max);
+        row_slice.opcode_add_flag = F::from_bool(record.opcode == BabyBearOpcode::ADD);
+        row_slice.opcode_sub_flag = F::from_bool(record.opcode == BabyBearOpcode::SUB);
+        row_slice.opcode_mul_flag = F::from_bool(record.opcode == BabyBearOpcode::MUL);
+        row_slice.opcode_div_flag = F::from_bool(record.opcode == BabyBearOpcode::DIV);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Returns the canonical result of `b op c` modulo the BabyBear prime, or `None` on division by
+/// zero. The operands may be any `u32` and are reduced first.
+pub fn run_babybear_arith(opcode: BabyBearOpcode, b: u32, c: u32) -> Option<u32> {
+    let p = BABYBEAR_MODULUS as u64;
+    let (b, c) = (b as u64 % p, c as u64 % p);
+    let a = match opcode {
+        BabyBearOpcode::ADD => (b + c) % p,
+        BabyBearOpcode::SUB => (b + p - c) % p,
+        BabyBearOpcode::MUL => b * c % p,
+        BabyBearOpcode::DIV => {
+            if c == 0 {
+                return None;
+            }
+            // Fermat inversion: c^(p - 2).
+            let (mut base, mut exp, mut c_inv) = (c, p - 2, 1);
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    c_inv = c_inv * base % p;
+                }
+                base = base * base % p;
+                exp >>= 1;
+            }
+            b * c_inv % p
+        }
+    };
+    Some(a as u32)
+}
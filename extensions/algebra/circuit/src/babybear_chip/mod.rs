@@ -0,0 +1,21 @@
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+use openvm_rv32_adapters::{Rv32HeapAdapterAir, Rv32HeapAdapterChip};
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+/// A BabyBear element is stored in memory as 4 little-endian bytes.
+pub const BABYBEAR_NUM_BYTES: usize = 4;
+
+pub type BabyBearArithAir = VmAirWrapper<
+    Rv32HeapAdapterAir<2, BABYBEAR_NUM_BYTES, BABYBEAR_NUM_BYTES>,
+    BabyBearArithCoreAir,
+>;
+pub type BabyBearArithChip<F> = VmChipWrapper<
+    F,
+    Rv32HeapAdapterChip<F, 2, BABYBEAR_NUM_BYTES, BABYBEAR_NUM_BYTES>,
+    BabyBearArithCoreChip,
+>;
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use openvm_algebra_transpiler::BabyBearOpcode;
+use openvm_circuit::arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{riscv::RV32_CELL_BITS, UsizeOpcode};
+use openvm_rv32_adapters::{rv32_write_heap_default, Rv32HeapAdapterChip};
+use openvm_stark_backend::p3_field::{AbstractField, Field, PrimeField32};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use super::{run_babybear_arith, BabyBearArithChip, BabyBearArithCoreChip, BABYBEAR_NUM_BYTES};
+use crate::babybear_ext4_chip::BABYBEAR_MODULUS;
+
+type F = BabyBear;
+
+fn to_bytes(x: u32) -> [F; BABYBEAR_NUM_BYTES] {
+    x.to_le_bytes().map(F::from_canonical_u8)
+}
+
+#[test]
+fn test_run_babybear_arith() {
+    let mut rng = create_seeded_rng();
+    for _ in 0..100 {
+        let b = rng.gen::<u32>();
+        let c = rng.gen_range(1..BABYBEAR_MODULUS);
+        let (fb, fc) = (F::from_wrapped_u32(b), F::from_canonical_u32(c));
+        for (opcode, expected) in [
+            (BabyBearOpcode::ADD, fb + fc),
+            (BabyBearOpcode::SUB, fb - fc),
+            (BabyBearOpcode::MUL, fb * fc),
+            (BabyBearOpcode::DIV, fb * fc.inverse()),
+        ] {
+            assert_eq!(
+                run_babybear_arith(opcode, b, c),
+                Some(expected.as_canonical_u32())
+            );
+        }
+    }
+    assert_eq!(
+        run_babybear_arith(BabyBearOpcode::SUB, 0, 1),
+        Some(BABYBEAR_MODULUS - 1)
+    );
+    assert_eq!(run_babybear_arith(BabyBearOpcode::DIV, 1, 0), None);
+    assert_eq!(
+        run_babybear_arith(BabyBearOpcode::DIV, 1, BABYBEAR_MODULUS),
+        None
+    );
+}
+
+#[test]
+fn test_babybear_arith_chip() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = BabyBearArithChip::new(
+        Rv32HeapAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        BabyBearArithCoreChip::new(bitwise_chip.clone(), BabyBearOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    for opcode in BabyBearOpcode::iter() {
+        // Include non-canonical inputs and the largest canonical output.
+        let inputs = [
+            (rng.gen::<u32>(), rng.gen_range(1..BABYBEAR_MODULUS)),
+            (u32::MAX, BABYBEAR_MODULUS + 1),
+            (0, 1),
+        ];
+        for (b, c) in inputs {
+            let instruction = rv32_write_heap_default(
+                &mut tester,
+                vec![to_bytes(b)],
+                vec![to_bytes(c)],
+                opcode.with_default_offset(),
+            );
+            tester.execute(&mut chip, instruction);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
@@ -0,0 +1,75 @@
+use std::{array, cell::RefCell, rc::Rc};
+
+use openvm_algebra_transpiler::BabyBearExt4Opcode;
+use openvm_circuit::{arch::VmChipWrapper, system::memory::MemoryControllerRef};
+use openvm_circuit_derive::InstructionExecutor;
+use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_mod_circuit_builder::{
+    ExprBuilder, ExprBuilderConfig, FieldExpr, FieldExpressionCoreChip, FieldVariable,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::BabyBearExt4AdapterChip;
+
+// Input: BabyBearExt4 * 2
+// Output: BabyBearExt4
+#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
+pub struct BabyBearExt4AddSubChip<F: PrimeField32>(
+    pub VmChipWrapper<F, BabyBearExt4AdapterChip<F>, FieldExpressionCoreChip>,
+);
+
+impl<F: PrimeField32> BabyBearExt4AddSubChip<F> {
+    pub fn new(
+        adapter: BabyBearExt4AdapterChip<F>,
+        memory_controller: MemoryControllerRef<F>,
+        config: ExprBuilderConfig,
+        offset: usize,
+    ) -> Self {
+        let (expr, is_add_flag, is_sub_flag) =
+            babybear_ext4_addsub_expr(config, memory_controller.borrow().range_checker.bus());
+        let core = FieldExpressionCoreChip::new(
+            expr,
+            offset,
+            vec![
+                BabyBearExt4Opcode::ADD as usize,
+                BabyBearExt4Opcode::SUB as usize,
+                BabyBearExt4Opcode::SETUP_ADDSUB as usize,
+            ],
+            vec![is_add_flag, is_sub_flag],
+            memory_controller.borrow().range_checker.clone(),
+            "BabyBearExt4AddSub",
+            false,
+        );
+        Self(VmChipWrapper::new(adapter, core, memory_controller))
+    }
+}
+
+pub fn babybear_ext4_addsub_expr(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> (FieldExpr, usize, usize) {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let mut x: [FieldVariable; 4] = array::from_fn(|_| ExprBuilder::new_input(builder.clone()));
+    let mut y: [FieldVariable; 4] = array::from_fn(|_| ExprBuilder::new_input(builder.clone()));
+    let add: [FieldVariable; 4] = array::from_fn(|i| &mut x[i] + &mut y[i]);
+    let sub: [FieldVariable; 4] = array::from_fn(|i| &mut x[i] - &mut y[i]);
+
+    let is_add_flag = builder.borrow_mut().new_flag();
+    let is_sub_flag = builder.borrow_mut().new_flag();
+    for i in 0..4 {
+        let diff = FieldVariable::select(is_sub_flag, &sub[i], &x[i]);
+        let mut z = FieldVariable::select(is_add_flag, &add[i], &diff);
+        z.save_output();
+    }
+
+    let builder = builder.borrow().clone();
+    (
+        FieldExpr::new(builder, range_bus, true),
+        is_add_flag,
+        is_sub_flag,
+    )
+}
@@ -0,0 +1,32 @@
+use num_bigint_dig::BigUint;
+use openvm_mod_circuit_builder::ExprBuilderConfig;
+use openvm_rv32_adapters::Rv32VecHeapAdapterChip;
+
+mod addsub;
+pub use addsub::*;
+
+mod muldiv;
+pub use muldiv::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The BabyBear prime `15 * 2^27 + 1`.
+pub const BABYBEAR_MODULUS: u32 = 0x78000001;
+/// Non-residue of the binomial extension `F_p[t]/(t^4 - W)`.
+pub const BABYBEAR_EXT4_W: isize = 11;
+/// Each coefficient is a BabyBear element stored as 4 little-endian bytes.
+pub const BABYBEAR_NUM_LIMBS: usize = 4;
+/// An extension element is read and written as a single 16-byte block.
+pub const BABYBEAR_EXT4_BLOCK_SIZE: usize = 4 * BABYBEAR_NUM_LIMBS;
+
+pub type BabyBearExt4AdapterChip<F> =
+    Rv32VecHeapAdapterChip<F, 2, 1, 1, BABYBEAR_EXT4_BLOCK_SIZE, BABYBEAR_EXT4_BLOCK_SIZE>;
+
+pub fn babybear_ext4_expr_config() -> ExprBuilderConfig {
+    ExprBuilderConfig {
+        modulus: BigUint::from(BABYBEAR_MODULUS),
+        num_limbs: BABYBEAR_NUM_LIMBS,
+        limb_bits: 8,
+    }
+}
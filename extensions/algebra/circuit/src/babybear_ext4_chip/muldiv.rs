@@ -0,0 +1,257 @@
+use std::{array, cell::RefCell, rc::Rc};
+
+use openvm_algebra_transpiler::BabyBearExt4Opcode;
+use openvm_circuit::{
+    arch::{
+        AdapterRuntimeContext, DynAdapterInterface, DynArray, ExecutionError, Result,
+        VmAdapterInterface, VmChipWrapper, VmCoreChip,
+    },
+    system::memory::MemoryControllerRef,
+};
+use openvm_circuit_derive::InstructionExecutor;
+use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::instruction::Instruction;
+use openvm_mod_circuit_builder::{
+    ExprBuilder, ExprBuilderConfig, FieldExpr, FieldExpressionCoreAir, FieldExpressionCoreChip,
+    FieldExpressionRecord, FieldVariable, SymbolicExpr,
+};
+use openvm_stark_backend::{p3_field::PrimeField32, p3_matrix::dense::RowMajorMatrix};
+
+use super::{
+    BabyBearExt4AdapterChip, BABYBEAR_EXT4_BLOCK_SIZE, BABYBEAR_EXT4_W, BABYBEAR_MODULUS,
+    BABYBEAR_NUM_LIMBS,
+};
+
+// Input: BabyBearExt4 * 2
+// Output: BabyBearExt4
+// DIV by zero is an execution error, and the AIR requires the divisor to be invertible.
+#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
+pub struct BabyBearExt4MulDivChip<F: PrimeField32>(
+    pub VmChipWrapper<F, BabyBearExt4AdapterChip<F>, BabyBearExt4MulDivCoreChip>,
+);
+
+impl<F: PrimeField32> BabyBearExt4MulDivChip<F> {
+    pub fn new(
+        adapter: BabyBearExt4AdapterChip<F>,
+        memory_controller: MemoryControllerRef<F>,
+        config: ExprBuilderConfig,
+        offset: usize,
+    ) -> Self {
+        let (expr, is_mul_flag, is_div_flag) =
+            babybear_ext4_muldiv_expr(config, memory_controller.borrow().range_checker.bus());
+        let core = FieldExpressionCoreChip::new(
+            expr,
+            offset,
+            vec![
+                BabyBearExt4Opcode::MUL as usize,
+                BabyBearExt4Opcode::DIV as usize,
+                BabyBearExt4Opcode::SETUP_MULDIV as usize,
+            ],
+            vec![is_mul_flag, is_div_flag],
+            memory_controller.borrow().range_checker.clone(),
+            "BabyBearExt4MulDiv",
+            false,
+        );
+        Self(VmChipWrapper::new(
+            adapter,
+            BabyBearExt4MulDivCoreChip(core),
+            memory_controller,
+        ))
+    }
+}
+
+/// The field expression core of [BabyBearExt4MulDivChip]. A DIV by zero fails with
+/// [ExecutionError::Fail] instead of panicking while computing the inverse.
+pub struct BabyBearExt4MulDivCoreChip(pub FieldExpressionCoreChip);
+
+impl BabyBearExt4MulDivCoreChip {
+    pub fn expr(&self) -> &FieldExpr {
+        self.0.expr()
+    }
+}
+
+impl<F: PrimeField32, I> VmCoreChip<F, I> for BabyBearExt4MulDivCoreChip
+where
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<DynArray<F>> + From<DynArray<F>>,
+    AdapterRuntimeContext<F, I>: From<AdapterRuntimeContext<F, DynAdapterInterface<F>>>,
+{
+    type Record = FieldExpressionRecord;
+    type Air = FieldExpressionCoreAir;
+
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let reads: DynArray<F> = reads.into();
+        let local_opcode = instruction.opcode.local_opcode_idx(self.0.air.offset);
+        // The divisor is the second input, four coefficients of BABYBEAR_NUM_LIMBS bytes each.
+        let divisor_is_zero = reads.0[BABYBEAR_EXT4_BLOCK_SIZE..]
+            .chunks_exact(BABYBEAR_NUM_LIMBS)
+            .all(|coeff| {
+                let coeff = coeff.iter().rev().fold(0u64, |acc, byte| {
+                    (acc << 8) + byte.as_canonical_u32() as u64
+                });
+                coeff % BABYBEAR_MODULUS as u64 == 0
+            });
+        if local_opcode == BabyBearExt4Opcode::DIV as usize && divisor_is_zero {
+            return Err(ExecutionError::Fail { pc: from_pc });
+        }
+        VmCoreChip::<F, I>::execute_instruction(&self.0, instruction, from_pc, reads.into())
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        VmCoreChip::<F, I>::get_opcode_name(&self.0, opcode)
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        VmCoreChip::<F, I>::generate_trace_row(&self.0, row_slice, record)
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.0.air
+    }
+
+    fn finalize(&self, trace: &mut RowMajorMatrix<F>, num_records: usize) {
+        VmCoreChip::<F, I>::finalize(&self.0, trace, num_records)
+    }
+}
+
+pub fn babybear_ext4_muldiv_expr(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> (FieldExpr, usize, usize) {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let x: [FieldVariable; 4] = array::from_fn(|_| ExprBuilder::new_input(builder.clone()));
+    let mut y: [FieldVariable; 4] = array::from_fn(|_| ExprBuilder::new_input(builder.clone()));
+    let is_mul_flag = builder.borrow_mut().new_flag();
+    let is_div_flag = builder.borrow_mut().new_flag();
+    let (z_idx, mut z): (Vec<usize>, Vec<FieldVariable>) = (0..4)
+        .map(|_| {
+            let (idx, var) = builder.borrow_mut().new_var();
+            (idx, FieldVariable::from_var(builder.clone(), var))
+        })
+        .unzip();
+
+    // For MUL the constraint is x * y - z = 0, for DIV and SETUP it is z * y - x = 0. SETUP has
+    // x.c0 = p = 0 and y = 0, so it holds for any z.
+    let mut lvar: [FieldVariable; 4] =
+        array::from_fn(|i| FieldVariable::select(is_mul_flag, &x[i], &z[i]));
+    let rvar: [FieldVariable; 4] =
+        array::from_fn(|i| FieldVariable::select(is_mul_flag, &z[i], &x[i]));
+    let product = ext4_mul(&mut lvar, &mut y);
+    for ((&idx, product), rvar) in z_idx.iter().zip(product).zip(rvar) {
+        let constraint = product - rvar;
+        builder.borrow_mut().set_constraint(idx, constraint.expr);
+    }
+
+    // With x = y = 0, z * y - x = 0 holds for any z, so DIV also requires y to be invertible:
+    // w satisfies y * w = 1 for DIV and w = 0 otherwise.
+    let w_vars: [(usize, SymbolicExpr); 4] = array::from_fn(|_| builder.borrow_mut().new_var());
+    let mut w: [FieldVariable; 4] =
+        array::from_fn(|i| FieldVariable::from_var(builder.clone(), w_vars[i].1.clone()));
+    let mut yw = ext4_mul(&mut y, &mut w);
+    for (k, (&(idx, _), yw)) in w_vars.iter().zip(&mut yw).enumerate() {
+        let target = if k == 0 { yw.int_add(-1) } else { yw.clone() };
+        let constraint = FieldVariable::select(is_div_flag, &target, &w[k]);
+        builder.borrow_mut().set_constraint(idx, constraint.expr);
+    }
+
+    // Compute expression has to be done manually at the SymbolicExpr level.
+    // Otherwise it saves the quotient and introduces new variables.
+    let x_expr: [SymbolicExpr; 4] = array::from_fn(|i| x[i].expr.clone());
+    let y_expr: [SymbolicExpr; 4] = array::from_fn(|i| y[i].expr.clone());
+    let compute_mul = ext4_mul_expr(&x_expr, &y_expr);
+    let y_inv = ext4_inverse_expr(&y_expr);
+    let compute_div = ext4_mul_expr(&x_expr, &y_inv);
+    for (i, (mul, div)) in compute_mul.into_iter().zip(compute_div).enumerate() {
+        let compute = SymbolicExpr::Select(
+            is_mul_flag,
+            Box::new(mul),
+            Box::new(SymbolicExpr::Select(
+                is_div_flag,
+                Box::new(div),
+                Box::new(x_expr[i].clone()),
+            )),
+        );
+        builder.borrow_mut().set_compute(z_idx[i], compute);
+    }
+    for ((&(idx, _), inv), y) in w_vars.iter().zip(y_inv).zip(&y_expr) {
+        let zero = SymbolicExpr::IntMul(Box::new(y.clone()), 0);
+        builder.borrow_mut().set_compute(
+            idx,
+            SymbolicExpr::Select(is_div_flag, Box::new(inv), Box::new(zero)),
+        );
+    }
+    for z in &mut z {
+        z.save_output();
+    }
+
+    let builder = builder.borrow().clone();
+    (
+        FieldExpr::new(builder, range_bus, true),
+        is_mul_flag,
+        is_div_flag,
+    )
+}
+
+/// Product in `F_p[t]/(t^4 - W)`: coefficient `k` collects `a_i b_j` with `i + j = k`, plus `W`
+/// times the terms with `i + j = k + 4`.
+fn ext4_mul(a: &mut [FieldVariable; 4], b: &mut [FieldVariable; 4]) -> [FieldVariable; 4] {
+    array::from_fn(|k| {
+        let mut low = &mut a[0] * &mut b[k];
+        for i in 1..=k {
+            low = low + (&mut a[i] * &mut b[k - i]);
+        }
+        if k == 3 {
+            return low;
+        }
+        let mut high = &mut a[k + 1] * &mut b[3];
+        for i in k + 2..4 {
+            high = high + (&mut a[i] * &mut b[k + 4 - i]);
+        }
+        low + high.int_mul(BABYBEAR_EXT4_W)
+    })
+}
+
+fn ext4_mul_expr(a: &[SymbolicExpr; 4], b: &[SymbolicExpr; 4]) -> [SymbolicExpr; 4] {
+    array::from_fn(|k| {
+        let mut low = &a[0] * &b[k];
+        for i in 1..=k {
+            low = low + &a[i] * &b[k - i];
+        }
+        if k == 3 {
+            return low;
+        }
+        let mut high = &a[k + 1] * &b[3];
+        for i in k + 2..4 {
+            high = high + &a[i] * &b[k + 4 - i];
+        }
+        low + SymbolicExpr::IntMul(Box::new(high), BABYBEAR_EXT4_W)
+    })
+}
+
+/// Inverse through the norm: with `a'(t) = a(-t)`, `a * a' = n0 + n1 t^2` and
+/// `(n0 + n1 t^2)(n0 - n1 t^2) = n0^2 - W n1^2` is in the base field, so
+/// `a^-1 = a' * (q0 + q2 t^2)` with `q0 = n0 / N` and `q2 = -n1 / N`.
+fn ext4_inverse_expr(a: &[SymbolicExpr; 4]) -> [SymbolicExpr; 4] {
+    let w = |e: SymbolicExpr| SymbolicExpr::IntMul(Box::new(e), BABYBEAR_EXT4_W);
+    let neg = |e: SymbolicExpr| SymbolicExpr::IntMul(Box::new(e), -1);
+    let n0 = &a[0] * &a[0] + w(&a[2] * &a[2]) - SymbolicExpr::IntMul(Box::new(w(&a[1] * &a[3])), 2);
+    let n1 = SymbolicExpr::IntMul(Box::new(&a[0] * &a[2]), 2) - &a[1] * &a[1] - w(&a[3] * &a[3]);
+    let norm = &n0 * &n0 - w(&n1 * &n1);
+    let q0 = &n0 / &norm;
+    let q2 = neg(n1) / &norm;
+    [
+        &a[0] * &q0 + w(&a[2] * &q2),
+        neg(&a[1] * &q0 + w(&a[3] * &q2)),
+        &a[0] * &q2 + &a[2] * &q0,
+        neg(&a[1] * &q2 + &a[3] * &q0),
+    ]
+}
@@ -0,0 +1,324 @@
+use std::sync::Arc;
+
+use num_bigint_dig::BigUint;
+use openvm_algebra_transpiler::BabyBearExt4Opcode;
+use openvm_circuit::arch::{
+    testing::{Tamper, VmChipTestBuilder},
+    ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_mod_circuit_builder::test_utils::field_expr_col_indices;
+use openvm_rv32_adapters::rv32_write_heap_default;
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{
+        extension::BinomialExtensionField, AbstractExtensionField, AbstractField, Field,
+        PrimeField32,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    babybear_ext4_expr_config, BabyBearExt4AdapterChip, BabyBearExt4AddSubChip,
+    BabyBearExt4MulDivChip, BABYBEAR_EXT4_BLOCK_SIZE, BABYBEAR_MODULUS,
+};
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+fn coeffs(x: EF) -> [u32; 4] {
+    let slice: &[F] = x.as_base_slice();
+    std::array::from_fn(|i| slice[i].as_canonical_u32())
+}
+
+fn to_bytes(coeffs: [u32; 4]) -> [F; BABYBEAR_EXT4_BLOCK_SIZE] {
+    std::array::from_fn(|i| F::from_canonical_u8(coeffs[i / 4].to_le_bytes()[i % 4]))
+}
+
+fn random_ext4(rng: &mut StdRng) -> EF {
+    EF::from_base_fn(|_| F::from_canonical_u32(rng.gen_range(0..BABYBEAR_MODULUS)))
+}
+
+fn new_adapter(
+    tester: &VmChipTestBuilder<F>,
+    bitwise_chip: &Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) -> BabyBearExt4AdapterChip<F> {
+    BabyBearExt4AdapterChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    )
+}
+
+fn new_bitwise_chip() -> Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>> {
+    Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ))
+}
+
+fn new_addsub_chip(
+    tester: &VmChipTestBuilder<F>,
+    bitwise_chip: &Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) -> BabyBearExt4AddSubChip<F> {
+    BabyBearExt4AddSubChip::new(
+        new_adapter(tester, bitwise_chip),
+        tester.memory_controller(),
+        babybear_ext4_expr_config(),
+        BabyBearExt4Opcode::default_offset(),
+    )
+}
+
+fn new_muldiv_chip(
+    tester: &VmChipTestBuilder<F>,
+    bitwise_chip: &Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) -> BabyBearExt4MulDivChip<F> {
+    BabyBearExt4MulDivChip::new(
+        new_adapter(tester, bitwise_chip),
+        tester.memory_controller(),
+        babybear_ext4_expr_config(),
+        BabyBearExt4Opcode::default_offset(),
+    )
+}
+
+/// Executes `opcode` on `x` and `y` and returns the result read back from memory.
+fn execute_ext4<E: InstructionExecutor<F>>(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut E,
+    opcode: BabyBearExt4Opcode,
+    x: [u32; 4],
+    y: [u32; 4],
+) -> [u32; 4] {
+    let instruction = rv32_write_heap_default(
+        tester,
+        vec![to_bytes(x)],
+        vec![to_bytes(y)],
+        opcode.with_default_offset(),
+    );
+    let rd_ptr = instruction.a.as_canonical_u32() as usize;
+    tester.execute(chip, instruction);
+
+    let output_ptr = u32::from_le_bytes(
+        tester
+            .read::<4>(RV32_REGISTER_AS as usize, rd_ptr)
+            .map(|x| x.as_canonical_u32() as u8),
+    );
+    let bytes = tester
+        .read::<BABYBEAR_EXT4_BLOCK_SIZE>(RV32_MEMORY_AS as usize, output_ptr as usize)
+        .map(|x| x.as_canonical_u32() as u8);
+    std::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+#[test]
+fn test_babybear_ext4_addsub() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = new_bitwise_chip();
+    let mut chip = new_addsub_chip(&tester, &bitwise_chip);
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let (x, y) = (random_ext4(&mut rng), random_ext4(&mut rng));
+    let inputs: Vec<BigUint> = coeffs(x)
+        .into_iter()
+        .chain(coeffs(y))
+        .map(BigUint::from)
+        .collect();
+    for (flags, expected) in [(vec![true, false], x + y), (vec![false, true], x - y)] {
+        let result = chip
+            .0
+            .core
+            .expr()
+            .execute_with_output(inputs.clone(), flags);
+        let expected: Vec<BigUint> = coeffs(expected).into_iter().map(BigUint::from).collect();
+        assert_eq!(result, expected);
+    }
+
+    execute_ext4(
+        &mut tester,
+        &mut chip,
+        BabyBearExt4Opcode::SETUP_ADDSUB,
+        [BABYBEAR_MODULUS, 0, 0, 0],
+        [0; 4],
+    );
+    for (opcode, expected) in [
+        (BabyBearExt4Opcode::ADD, x + y),
+        (BabyBearExt4Opcode::SUB, x - y),
+    ] {
+        let result = execute_ext4(&mut tester, &mut chip, opcode, coeffs(x), coeffs(y));
+        assert_eq!(result, coeffs(expected));
+    }
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_babybear_ext4_muldiv() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = new_bitwise_chip();
+    let mut chip = new_muldiv_chip(&tester, &bitwise_chip);
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let x = random_ext4(&mut rng);
+    let y = EF::from_base_fn(|_| F::from_canonical_u32(rng.gen_range(1..BABYBEAR_MODULUS)));
+    let inputs: Vec<BigUint> = coeffs(x)
+        .into_iter()
+        .chain(coeffs(y))
+        .map(BigUint::from)
+        .collect();
+    for (flags, expected) in [
+        (vec![true, false], x * y),
+        (vec![false, true], x * y.inverse()),
+    ] {
+        let result = chip
+            .0
+            .core
+            .expr()
+            .execute_with_output(inputs.clone(), flags);
+        let expected: Vec<BigUint> = coeffs(expected).into_iter().map(BigUint::from).collect();
+        assert_eq!(result, expected);
+    }
+
+    execute_ext4(
+        &mut tester,
+        &mut chip,
+        BabyBearExt4Opcode::SETUP_MULDIV,
+        [BABYBEAR_MODULUS, 0, 0, 0],
+        [0; 4],
+    );
+    for (opcode, expected) in [
+        (BabyBearExt4Opcode::MUL, x * y),
+        (BabyBearExt4Opcode::DIV, x * y.inverse()),
+    ] {
+        let result = execute_ext4(&mut tester, &mut chip, opcode, coeffs(x), coeffs(y));
+        assert_eq!(result, coeffs(expected));
+    }
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Random operations on both chips, checked against the Plonky3 extension field.
+#[test]
+fn test_babybear_ext4_random_ops() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = new_bitwise_chip();
+    let mut addsub_chip = new_addsub_chip(&tester, &bitwise_chip);
+    let mut muldiv_chip = new_muldiv_chip(&tester, &bitwise_chip);
+    execute_ext4(
+        &mut tester,
+        &mut addsub_chip,
+        BabyBearExt4Opcode::SETUP_ADDSUB,
+        [BABYBEAR_MODULUS, 0, 0, 0],
+        [0; 4],
+    );
+    execute_ext4(
+        &mut tester,
+        &mut muldiv_chip,
+        BabyBearExt4Opcode::SETUP_MULDIV,
+        [BABYBEAR_MODULUS, 0, 0, 0],
+        [0; 4],
+    );
+
+    let mut rng = create_seeded_rng();
+    for _ in 0..50 {
+        let x = random_ext4(&mut rng);
+        let mut y = random_ext4(&mut rng);
+        // Also cover the base field, where only the first coefficient is nonzero.
+        if rng.gen_bool(0.25) {
+            y = EF::from_base(y.as_base_slice()[0]);
+        }
+        let (opcode, expected) = match rng.gen_range(0..4) {
+            0 => (BabyBearExt4Opcode::ADD, x + y),
+            1 => (BabyBearExt4Opcode::SUB, x - y),
+            2 => (BabyBearExt4Opcode::MUL, x * y),
+            _ if y.is_zero() => (BabyBearExt4Opcode::MUL, x * y),
+            _ => (BabyBearExt4Opcode::DIV, x * y.inverse()),
+        };
+        let result = match opcode {
+            BabyBearExt4Opcode::ADD | BabyBearExt4Opcode::SUB => {
+                execute_ext4(&mut tester, &mut addsub_chip, opcode, coeffs(x), coeffs(y))
+            }
+            _ => execute_ext4(&mut tester, &mut muldiv_chip, opcode, coeffs(x), coeffs(y)),
+        };
+        assert_eq!(result, coeffs(expected), "{opcode:?} of {x} and {y}");
+    }
+    let tester = tester
+        .build()
+        .load(addsub_chip)
+        .load(muldiv_chip)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_babybear_ext4_div_by_zero() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = new_bitwise_chip();
+    let mut chip = new_muldiv_chip(&tester, &bitwise_chip);
+
+    // A coefficient equal to the modulus is zero too.
+    for y in [[0; 4], [BABYBEAR_MODULUS, 0, BABYBEAR_MODULUS, 0]] {
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![to_bytes([1, 2, 3, 4])],
+            vec![to_bytes(y)],
+            BabyBearExt4Opcode::DIV.with_default_offset(),
+        );
+        let from_state = ExecutionState {
+            pc: 0,
+            timestamp: tester.memory_controller().borrow().timestamp(),
+        };
+        assert!(matches!(
+            chip.execute(instruction, from_state),
+            Err(ExecutionError::Fail { pc: 0 })
+        ));
+    }
+}
+
+/// The AIR rejects a zero divisor even when the executor is bypassed: 0 / 0 satisfies
+/// `z * y = x`, but not `y * w = 1`.
+#[test]
+fn test_babybear_ext4_div_by_zero_negative() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = new_bitwise_chip();
+    let mut chip = new_muldiv_chip(&tester, &bitwise_chip);
+    execute_ext4(
+        &mut tester,
+        &mut chip,
+        BabyBearExt4Opcode::SETUP_MULDIV,
+        [BABYBEAR_MODULUS, 0, 0, 0],
+        [0; 4],
+    );
+    let result = execute_ext4(
+        &mut tester,
+        &mut chip,
+        BabyBearExt4Opcode::DIV,
+        [0; 4],
+        [1, 0, 0, 0],
+    );
+    assert_eq!(result, [0; 4]);
+
+    // Row 1 is the DIV. The divisor 1 has a single nonzero limb.
+    let cols = field_expr_col_indices(chip.0.core.expr());
+    let adapter_width = BaseAir::<F>::width(chip.0.adapter.air());
+    let tamper = Tamper::Set {
+        row: 1,
+        col: adapter_width + cols.inputs[4][0],
+        value: F::ZERO,
+    };
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_algebra_transpiler::BabyBearExt4Opcode;
+use openvm_circuit::{
+    arch::{SystemPort, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError},
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::{UsizeOpcode, VmOpcode};
+use openvm_rv32_adapters::Rv32VecHeapAdapterChip;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::babybear_ext4_chip::{
+    babybear_ext4_expr_config, BabyBearExt4AddSubChip, BabyBearExt4MulDivChip,
+};
+
+/// Arithmetic in the degree-4 binomial extension of BabyBear for RV32 guests.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct BabyBearExt4Extension;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, AnyEnum, From)]
+pub enum BabyBearExt4ExtensionExecutor<F: PrimeField32> {
+    AddSub(BabyBearExt4AddSubChip<F>),
+    MulDiv(BabyBearExt4MulDivChip<F>),
+}
+
+#[derive(ChipUsageGetter, Chip, AnyEnum, From)]
+pub enum BabyBearExt4ExtensionPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for BabyBearExt4Extension {
+    type Executor = BabyBearExt4ExtensionExecutor<F>;
+    type Periphery = BabyBearExt4ExtensionPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+
+        let offset = BabyBearExt4Opcode::default_offset();
+        let config = babybear_ext4_expr_config();
+        let adapter_chip = Rv32VecHeapAdapterChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            bitwise_lu_chip,
+        );
+
+        let addsub_chip = BabyBearExt4AddSubChip::new(
+            adapter_chip.clone(),
+            memory_controller.clone(),
+            config.clone(),
+            offset,
+        );
+        inventory.add_executor(
+            BabyBearExt4ExtensionExecutor::AddSub(addsub_chip),
+            [
+                BabyBearExt4Opcode::ADD,
+                BabyBearExt4Opcode::SUB,
+                BabyBearExt4Opcode::SETUP_ADDSUB,
+            ]
+            .map(VmOpcode::with_default_offset),
+        )?;
+        let muldiv_chip =
+            BabyBearExt4MulDivChip::new(adapter_chip, memory_controller, config, offset);
+        inventory.add_executor(
+            BabyBearExt4ExtensionExecutor::MulDiv(muldiv_chip),
+            [
+                BabyBearExt4Opcode::MUL,
+                BabyBearExt4Opcode::DIV,
+                BabyBearExt4Opcode::SETUP_MULDIV,
+            ]
+            .map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_algebra_transpiler::BabyBearOpcode;
+use openvm_circuit::{
+    arch::{SystemPort, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError},
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::{UsizeOpcode, VmOpcode};
+use openvm_rv32_adapters::Rv32HeapAdapterChip;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    babybear_chip::{BabyBearArithChip, BabyBearArithCoreChip},
+    babybear_ext4_chip::BABYBEAR_MODULUS,
+};
+
+/// BabyBear field arithmetic for RV32 guests. The VM must be over BabyBear.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct BabyBearExtension;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, AnyEnum, From)]
+pub enum BabyBearExtensionExecutor<F: PrimeField32> {
+    Arith(BabyBearArithChip<F>),
+}
+
+#[derive(ChipUsageGetter, Chip, AnyEnum, From)]
+pub enum BabyBearExtensionPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for BabyBearExtension {
+    type Executor = BabyBearExtensionExecutor<F>;
+    type Periphery = BabyBearExtensionPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        assert_eq!(
+            F::ORDER_U32,
+            BABYBEAR_MODULUS,
+            "the BabyBear extension requires a VM over BabyBear"
+        );
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+
+        let arith_chip = BabyBearArithChip::new(
+            Rv32HeapAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            BabyBearArithCoreChip::new(bitwise_lu_chip, BabyBearOpcode::default_offset()),
+            memory_controller,
+        );
+        inventory.add_executor(
+            arith_chip,
+            BabyBearOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
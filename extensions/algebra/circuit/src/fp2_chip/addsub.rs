@@ -86,18 +86,26 @@ mod tests {
     use halo2curves_axiom::{bn256::Fq2, ff::Field};
     use itertools::Itertools;
     use openvm_algebra_transpiler::Fp2Opcode;
-    use openvm_circuit::arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
+    use openvm_circuit::arch::{
+        testing::{Tamper, VmChipTestBuilder},
+        VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+    };
     use openvm_circuit_primitives::bitwise_op_lookup::{
         BitwiseOperationLookupBus, BitwiseOperationLookupChip,
     };
     use openvm_instructions::{riscv::RV32_CELL_BITS, UsizeOpcode};
     use openvm_mod_circuit_builder::{
-        test_utils::{biguint_to_limbs, bn254_fq2_to_biguint_vec, bn254_fq_to_biguint},
-        ExprBuilderConfig,
+        test_utils::{
+            biguint_to_limbs, bn254_fq2_to_biguint_vec, bn254_fq_to_biguint, field_expr_col_indices,
+        },
+        ExprBuilderConfig, FieldExprCols,
     };
     use openvm_pairing_guest::bn254::BN254_MODULUS;
     use openvm_rv32_adapters::{rv32_write_heap_default, Rv32VecHeapAdapterChip};
-    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_backend::{
+        p3_air::BaseAir, p3_field::AbstractField, utils::disable_debug_builder,
+        verifier::VerificationError,
+    };
     use openvm_stark_sdk::p3_baby_bear::BabyBear;
     use rand::{rngs::StdRng, SeedableRng};
 
@@ -107,8 +115,14 @@ mod tests {
     const LIMB_BITS: usize = 8;
     type F = BabyBear;
 
-    #[test]
-    fn test_fp2_addsub() {
+    /// Runs the setup, then an ADD and a SUB of random BN254 Fp2 elements, checking the
+    /// expression against halo2curves. Row 0 of the trace is the setup, row 1 the ADD and row 2
+    /// the SUB.
+    fn run_fp2_addsub() -> (
+        VmChipTestBuilder<F>,
+        Fp2AddSubChip<F, 2, NUM_LIMBS>,
+        Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    ) {
         let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
         let config = ExprBuilderConfig {
             modulus: BN254_MODULUS.clone(),
@@ -171,6 +185,17 @@ mod tests {
                     .map(BabyBear::from_canonical_u32)
             })
             .collect_vec();
+        let modulus_limbs = biguint_to_limbs::<NUM_LIMBS>(BN254_MODULUS.clone(), LIMB_BITS)
+            .map(BabyBear::from_canonical_u32);
+        let one_limbs = [BabyBear::ONE; NUM_LIMBS];
+        let setup_instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![modulus_limbs, one_limbs], // inputs[0] = prime, others doesn't matter
+            vec![one_limbs, one_limbs],
+            chip.0.core.air.offset + Fp2Opcode::SETUP_ADDSUB as usize,
+        );
+        tester.execute(&mut chip, setup_instruction);
+
         let instruction1 = rv32_write_heap_default(
             &mut tester,
             x_limbs.clone(),
@@ -185,7 +210,43 @@ mod tests {
         );
         tester.execute(&mut chip, instruction1);
         tester.execute(&mut chip, instruction2);
+        (tester, chip, bitwise_chip)
+    }
+
+    #[test]
+    fn test_fp2_addsub() {
+        let (tester, chip, bitwise_chip) = run_fp2_addsub();
         let tester = tester.build().load(chip).load(bitwise_chip).finalize();
         tester.simple_test().expect("Verification failed");
     }
+
+    /// Tampers with the ADD row. An off-by-one carry or quotient breaks the polynomial identity
+    /// of the expression, so the error is `OodEvaluationMismatch`.
+    fn run_fp2_add_negative_test(tamper: impl Fn(&FieldExprCols<usize>, usize) -> Tamper<F>) {
+        let (tester, chip, bitwise_chip) = run_fp2_addsub();
+        let cols = field_expr_col_indices(chip.0.core.expr());
+        let adapter_width = BaseAir::<F>::width(chip.0.adapter.air());
+        let tamper = tamper(&cols, adapter_width);
+        disable_debug_builder();
+        let tester = tester
+            .build()
+            .load_and_tamper(chip, &[tamper])
+            .load(bitwise_chip)
+            .finalize();
+        tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+    }
+
+    #[test]
+    fn test_fp2_add_wrong_carry_negative() {
+        run_fp2_add_negative_test(|cols, adapter_width| {
+            Tamper::off_by_one(1, adapter_width + cols.carry_limbs[1][0])
+        });
+    }
+
+    #[test]
+    fn test_fp2_add_wrong_quotient_negative() {
+        run_fp2_add_negative_test(|cols, adapter_width| {
+            Tamper::off_by_one(1, adapter_width + cols.q_limbs[0][0])
+        });
+    }
 }
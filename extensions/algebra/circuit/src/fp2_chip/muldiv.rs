@@ -215,6 +215,17 @@ mod tests {
                     .map(BabyBear::from_canonical_u32)
             })
             .collect_vec();
+        let modulus_limbs = biguint_to_limbs::<NUM_LIMBS>(BN254_MODULUS.clone(), LIMB_BITS)
+            .map(BabyBear::from_canonical_u32);
+        let one_limbs = [BabyBear::ONE; NUM_LIMBS];
+        let setup_instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![modulus_limbs, one_limbs], // inputs[0] = prime, others doesn't matter
+            vec![one_limbs, one_limbs],
+            chip.0.core.air.offset + Fp2Opcode::SETUP_MULDIV as usize,
+        );
+        tester.execute(&mut chip, setup_instruction);
+
         let instruction1 = rv32_write_heap_default(
             &mut tester,
             x_limbs.clone(),
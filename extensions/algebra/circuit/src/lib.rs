@@ -1,3 +1,5 @@
+pub mod babybear_chip;
+pub mod babybear_ext4_chip;
 pub mod fp2_chip;
 pub mod modular_chip;
 
@@ -7,5 +9,9 @@ mod modular_extension;
 pub use modular_extension::*;
 mod fp2_extension;
 pub use fp2_extension::*;
+mod babybear_extension;
+pub use babybear_extension::*;
+mod babybear_ext4_extension;
+pub use babybear_ext4_extension::*;
 mod config;
 pub use config::*;
@@ -2,6 +2,8 @@ mod addsub;
 pub use addsub::*;
 mod is_eq;
 pub use is_eq::*;
+mod muladd;
+pub use muladd::*;
 mod muldiv;
 pub use muldiv::*;
 use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
@@ -0,0 +1,40 @@
+use std::{cell::RefCell, rc::Rc};
+
+use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
+use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+use openvm_mod_circuit_builder::{
+    field_expression_chip, ExprBuilder, ExprBuilderConfig, FieldExpr,
+};
+
+/// `r = a * b + c`, as a single field expression so that the fused operation takes one row.
+pub fn modular_muladd_expr(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> FieldExpr {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let a = ExprBuilder::new_input(builder.clone());
+    let b = ExprBuilder::new_input(builder.clone());
+    let c = ExprBuilder::new_input(builder.clone());
+    let mut r = a * b + c;
+    r.save_output();
+
+    let builder = builder.borrow().clone();
+    FieldExpr::new(builder, range_bus, true)
+}
+
+field_expression_chip! {
+    /// Each prime field element will be represented as `BLOCKS * BLOCK_SIZE` cells in memory,
+    /// and the three inputs are read through the three reads heap adapter.
+    pub struct ModularMulAddChip {
+        num_reads: 3,
+        expr: modular_muladd_expr(),
+        opcodes: [
+            Rv32ModularArithmeticOpcode::MULADD,
+            Rv32ModularArithmeticOpcode::SETUP_MULADD,
+        ],
+        name: "ModularMulAdd",
+    }
+}
@@ -4,8 +4,8 @@ use itertools::Itertools;
 use num_bigint_dig::BigUint;
 use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
 use openvm_circuit::arch::{
-    AdapterAirContext, AdapterRuntimeContext, DynAdapterInterface, DynArray, MinimalInstruction,
-    Result, VmAdapterInterface, VmCoreAir, VmCoreChip,
+    AdapterAirContext, AdapterRuntimeContext, DynAdapterInterface, DynArray, ExecutionError,
+    MinimalInstruction, Result, VmAdapterInterface, VmCoreAir, VmCoreChip,
 };
 use openvm_circuit_primitives::{
     var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
@@ -165,7 +165,7 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let num_limbs = self.air.expr.canonical_num_limbs();
@@ -188,6 +188,14 @@ where
         let y_biguint = limbs_to_biguint(&y, limb_bits);
 
         let local_opcode = Rv32ModularArithmeticOpcode::from_usize(local_opcode_idx);
+        if local_opcode == Rv32ModularArithmeticOpcode::SETUP_MULDIV
+            && x_biguint != self.air.expr.prime
+        {
+            return Err(ExecutionError::SetupModulusMismatch {
+                pc: from_pc,
+                opcode,
+            });
+        }
         let is_mul_flag = match local_opcode {
             Rv32ModularArithmeticOpcode::MUL => true,
             Rv32ModularArithmeticOpcode::DIV | Rv32ModularArithmeticOpcode::SETUP_MULDIV => false,
@@ -1,10 +1,13 @@
 use std::{array::from_fn, sync::Arc};
 
 use num_bigint_dig::BigUint;
-use num_traits::Zero;
+use num_traits::{One, Zero};
 use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
 use openvm_circuit::arch::{
-    instructions::UsizeOpcode, testing::VmChipTestBuilder, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
+    instructions::UsizeOpcode,
+    testing::{Tamper, VmChipTestBuilder},
+    ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip, VmChipWrapper,
+    BITWISE_OP_LOOKUP_BUS,
 };
 use openvm_circuit_primitives::{
     bigint::utils::{
@@ -14,20 +17,29 @@ use openvm_circuit_primitives::{
 };
 use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, VmOpcode};
 use openvm_mod_circuit_builder::{
-    test_utils::{biguint_to_limbs, generate_field_element},
-    ExprBuilderConfig,
+    test_utils::{
+        biguint_to_limbs, field_element_strategy, field_expr_col_indices, generate_field_element,
+    },
+    ExprBuilderConfig, FieldExprCols,
 };
 use openvm_pairing_guest::bls12_381::BLS12_381_MODULUS;
 use openvm_rv32_adapters::{
-    rv32_write_heap_default, write_ptr_reg, Rv32IsEqualModAdapterChip, Rv32VecHeapAdapterChip,
+    rv32_read_heap_default, rv32_write_heap_default, rv32_write_heap_three_reads_default,
+    write_ptr_reg, Rv32IsEqualModAdapterChip, Rv32VecHeapAdapterChip, Rv32VecHeapAdapterCols,
+    SetupGuardCols,
 };
 use openvm_rv32im_circuit::adapters::RV32_REGISTER_NUM_LIMBS;
-use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_backend::{
+    p3_air::BaseAir, p3_field::AbstractField, utils::disable_debug_builder,
+    verifier::VerificationError,
+};
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use proptest::{prelude::*, sample::select};
 use rand::Rng;
 
 use super::{
-    ModularAddSubCoreChip, ModularIsEqualChip, ModularIsEqualCoreChip, ModularMulDivCoreChip,
+    ModularAddSubChip, ModularAddSubCoreChip, ModularIsEqualChip, ModularIsEqualCoreChip,
+    ModularMulAddChip, ModularMulDivCoreChip,
 };
 
 const NUM_LIMBS: usize = 32;
@@ -167,6 +179,259 @@ fn test_addsub(opcode_offset: usize, modulus: BigUint) {
     tester.simple_test().expect("Verification failed");
 }
 
+/// Runs the setup and one ADD on secp256k1 coordinates, then tampers with the ADD row, which is
+/// row 1. An off-by-one carry or quotient breaks the polynomial identity of the expression.
+fn run_addsub_negative_test(tamper: impl Fn(&FieldExprCols<usize>, usize) -> Tamper<F>) {
+    let modulus = secp256k1_coord_prime();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let core = ModularAddSubCoreChip::new(
+        config,
+        tester.memory_controller().borrow().range_checker.clone(),
+        Rv32ModularArithmeticOpcode::default_offset(),
+    );
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = VmChipWrapper::new(adapter, core, tester.memory_controller());
+
+    let mut rng = create_seeded_rng();
+    let mut random_limbs = || {
+        generate_field_element::<NUM_LIMBS, LIMB_BITS>(&modulus, &mut rng)
+            .map(F::from_canonical_u32)
+    };
+    let ops = [
+        (
+            ADD_LOCAL + 2,
+            biguint_to_limbs::<NUM_LIMBS>(modulus.clone(), LIMB_BITS).map(F::from_canonical_u32),
+            [F::ZERO; NUM_LIMBS],
+        ),
+        (ADD_LOCAL, random_limbs(), random_limbs()),
+    ];
+    for (op, a, b) in ops {
+        let instruction =
+            rv32_write_heap_default(&mut tester, vec![a], vec![b], chip.core.air.offset + op);
+        tester.execute(&mut chip, instruction);
+    }
+
+    let cols = field_expr_col_indices(&chip.core.air.expr);
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let tamper = tamper(&cols, adapter_width);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn test_addsub_wrong_carry_negative() {
+    run_addsub_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(1, adapter_width + cols.carry_limbs[0][0])
+    });
+}
+
+#[test]
+fn test_addsub_wrong_quotient_negative() {
+    run_addsub_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(1, adapter_width + cols.q_limbs[0][0])
+    });
+}
+
+/// Returns an ADD/SUB chip for secp256k1 coordinates whose adapter requires the setup.
+fn new_guarded_addsub_chip(
+    tester: &mut VmChipTestBuilder<F>,
+    bitwise_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) -> ModularAddSubChip<F, 1, BLOCK_SIZE> {
+    let offset = Rv32ModularArithmeticOpcode::default_offset();
+    let config = ExprBuilderConfig {
+        modulus: secp256k1_coord_prime(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let core = ModularAddSubCoreChip::new(
+        config,
+        tester.memory_controller().borrow().range_checker.clone(),
+        offset,
+    );
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip,
+    )
+    .with_setup_guard(offset + Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize);
+    VmChipWrapper::new(adapter, core, tester.memory_controller())
+}
+
+/// Runs the setup, if `with_setup`, and then an ADD on the guarded chip. Returns the result of
+/// the ADD.
+fn execute_guarded_addsub(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut ModularAddSubChip<F, 1, BLOCK_SIZE>,
+    with_setup: bool,
+) -> Result<ExecutionState<u32>, ExecutionError> {
+    let modulus = secp256k1_coord_prime();
+    let to_limbs =
+        |x: BigUint| biguint_to_limbs::<NUM_LIMBS>(x, LIMB_BITS).map(F::from_canonical_u32);
+    let offset = Rv32ModularArithmeticOpcode::default_offset();
+    if with_setup {
+        let setup = rv32_write_heap_default(
+            tester,
+            vec![to_limbs(modulus.clone())],
+            vec![to_limbs(BigUint::zero())],
+            offset + Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize,
+        );
+        tester.execute(chip, setup);
+    }
+    let instruction = rv32_write_heap_default(
+        tester,
+        vec![to_limbs(BigUint::from(3u32))],
+        vec![to_limbs(modulus - 1u32)],
+        offset + ADD_LOCAL,
+    );
+    let from_state = ExecutionState {
+        pc: 0,
+        timestamp: tester.memory_controller().borrow().timestamp(),
+    };
+    chip.execute(instruction, from_state)
+}
+
+#[test]
+fn test_addsub_setup_guard() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+    let mut chip = new_guarded_addsub_chip(&mut tester, bitwise_chip.clone());
+    execute_guarded_addsub(&mut tester, &mut chip, true).unwrap();
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_addsub_without_setup_fails() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+    let mut chip = new_guarded_addsub_chip(&mut tester, bitwise_chip);
+    assert!(matches!(
+        execute_guarded_addsub(&mut tester, &mut chip, false),
+        Err(ExecutionError::SetupNotDone { pc: 0, .. })
+    ));
+}
+
+/// The ADD row claims that the setup flag was unset, as if the guest skipped the setup.
+#[test]
+fn test_addsub_without_setup_negative() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+    let mut chip = new_guarded_addsub_chip(&mut tester, bitwise_chip.clone());
+    execute_guarded_addsub(&mut tester, &mut chip, true).unwrap();
+
+    let indices = (0..SetupGuardCols::<F>::width()).collect::<Vec<_>>();
+    let guard_cols: &SetupGuardCols<usize> = std::borrow::Borrow::borrow(&indices[..]);
+    let guard_offset = Rv32VecHeapAdapterCols::<F, 2, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::width();
+    let tamper = Tamper::Set {
+        row: 1,
+        col: guard_offset + guard_cols.flag_aux.prev_data[0],
+        value: F::ZERO,
+    };
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+/// Accumulates with `rd == rs1`, so that the first input is read as the previous data of the
+/// write.
+#[test]
+fn test_addsub_in_place() {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let modulus = secp256k1_coord_prime();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let offset = Rv32ModularArithmeticOpcode::default_offset();
+    let core = ModularAddSubCoreChip::new(
+        config,
+        tester.memory_controller().borrow().range_checker.clone(),
+        offset,
+    );
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = VmChipWrapper::new(adapter, core, tester.memory_controller());
+    let mut rng = create_seeded_rng();
+    let to_limbs = |x: &BigUint| {
+        vec![biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(F::from_canonical_u32)]
+    };
+
+    let setup = rv32_write_heap_default(
+        &mut tester,
+        to_limbs(&modulus),
+        to_limbs(&BigUint::zero()),
+        offset + Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize,
+    );
+    tester.execute(&mut chip, setup);
+
+    let (acc_reg, acc_ptr, b_reg, b_ptr) = (0, 0, 4, 128);
+    write_ptr_reg(&mut tester, 1, acc_reg, acc_ptr);
+    write_ptr_reg(&mut tester, 1, b_reg, b_ptr);
+    let mut acc = BigUint::zero();
+    tester.write(2, acc_ptr as usize, to_limbs(&acc)[0]);
+    for i in 0..20 {
+        let b = BigUint::new((0..NUM_LIMBS / 4).map(|_| rng.gen()).collect()) % &modulus;
+        tester.write(2, b_ptr as usize, to_limbs(&b)[0]);
+        let op = if i % 3 == 2 {
+            acc = (&acc + &modulus - &b) % &modulus;
+            Rv32ModularArithmeticOpcode::SUB
+        } else {
+            acc = (&acc + &b) % &modulus;
+            Rv32ModularArithmeticOpcode::ADD
+        };
+        let instruction = Instruction::from_usize(
+            VmOpcode::from_usize(offset + op as usize),
+            [acc_reg, acc_reg, b_reg, 1, 2],
+        );
+        tester.execute(&mut chip, instruction);
+        assert_eq!(
+            tester.read::<NUM_LIMBS>(2, acc_ptr as usize),
+            to_limbs(&acc)[0]
+        );
+    }
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
 #[test]
 fn test_coord_muldiv() {
     let opcode_offset = 0;
@@ -296,6 +561,71 @@ fn test_muldiv(opcode_offset: usize, modulus: BigUint) {
     tester.simple_test().expect("Verification failed");
 }
 
+#[test]
+fn test_coord_muladd() {
+    test_muladd(secp256k1_coord_prime());
+}
+
+#[test]
+fn test_scalar_muladd() {
+    test_muladd(secp256k1_scalar_prime());
+}
+
+fn test_muladd(modulus: BigUint) {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 3, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let offset = Rv32ModularArithmeticOpcode::default_offset();
+    let mut chip = ModularMulAddChip::new(adapter, tester.memory_controller(), config, offset);
+    let mut rng = create_seeded_rng();
+
+    let to_limbs = |x: &BigUint| {
+        vec![biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(F::from_canonical_u32)]
+    };
+    let setup = rv32_write_heap_three_reads_default(
+        &mut tester,
+        to_limbs(&modulus),
+        to_limbs(&BigUint::zero()),
+        to_limbs(&BigUint::zero()),
+        offset + Rv32ModularArithmeticOpcode::SETUP_MULADD as usize,
+    );
+    tester.execute(&mut chip, setup);
+
+    for _ in 0..20 {
+        let [a, b, c]: [BigUint; 3] =
+            from_fn(|_| BigUint::new((0..NUM_LIMBS / 4).map(|_| rng.gen()).collect()) % &modulus);
+        let instruction = rv32_write_heap_three_reads_default(
+            &mut tester,
+            to_limbs(&a),
+            to_limbs(&b),
+            to_limbs(&c),
+            offset + Rv32ModularArithmeticOpcode::MULADD as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+
+        let expected = (&a * &b + &c) % &modulus;
+        assert_eq!(
+            rv32_read_heap_default::<NUM_LIMBS>(&mut tester, &instruction, 1),
+            to_limbs(&expected)
+        );
+    }
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
 fn test_is_equal<const NUM_LANES: usize, const LANE_SIZE: usize, const TOTAL_LIMBS: usize>(
     opcode_offset: usize,
     modulus: BigUint,
@@ -377,3 +707,128 @@ fn test_modular_is_equal_1x32() {
 fn test_modular_is_equal_3x16() {
     test_is_equal::<3, 16, 48>(17, BLS12_381_MODULUS.clone(), 100);
 }
+
+/// Runs `ops` of (opcode, a, b) through an add/sub and a mul/div chip after their setups,
+/// and checks the outputs against the BigUint reference before proving.
+fn run_modular_prop_test(
+    modulus: BigUint,
+    ops: Vec<(Rv32ModularArithmeticOpcode, BigUint, BigUint)>,
+) {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let range_checker = tester.memory_controller().borrow().range_checker.clone();
+    let offset = Rv32ModularArithmeticOpcode::default_offset();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let new_adapter = |tester: &VmChipTestBuilder<F>| {
+        Rv32VecHeapAdapterChip::<F, 2, 1, 1, BLOCK_SIZE, BLOCK_SIZE>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        )
+    };
+    let mut addsub_chip = VmChipWrapper::new(
+        new_adapter(&tester),
+        ModularAddSubCoreChip::new(config.clone(), range_checker.clone(), offset),
+        tester.memory_controller(),
+    );
+    let mut muldiv_chip = VmChipWrapper::new(
+        new_adapter(&tester),
+        ModularMulDivCoreChip::new(config, range_checker, offset),
+        tester.memory_controller(),
+    );
+
+    let to_limbs = |x: &BigUint| {
+        vec![biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)]
+    };
+    let setup_instruction = |tester: &mut VmChipTestBuilder<F>, op: Rv32ModularArithmeticOpcode| {
+        rv32_write_heap_default(
+            tester,
+            to_limbs(&modulus),
+            to_limbs(&BigUint::zero()),
+            offset + op as usize,
+        )
+    };
+    let instruction = setup_instruction(&mut tester, Rv32ModularArithmeticOpcode::SETUP_ADDSUB);
+    tester.execute(&mut addsub_chip, instruction);
+    let instruction = setup_instruction(&mut tester, Rv32ModularArithmeticOpcode::SETUP_MULDIV);
+    tester.execute(&mut muldiv_chip, instruction);
+
+    for (op, a, b) in ops {
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            to_limbs(&a),
+            to_limbs(&b),
+            offset + op as usize,
+        );
+        let expected = match op {
+            Rv32ModularArithmeticOpcode::ADD => (&a + &b) % &modulus,
+            Rv32ModularArithmeticOpcode::SUB => (&a + &modulus - &b % &modulus) % &modulus,
+            Rv32ModularArithmeticOpcode::MUL => (&a * &b) % &modulus,
+            Rv32ModularArithmeticOpcode::DIV => {
+                (&a * big_uint_mod_inverse(&(&b % &modulus), &modulus)) % &modulus
+            }
+            _ => unreachable!(),
+        };
+        if op < Rv32ModularArithmeticOpcode::MUL {
+            tester.execute(&mut addsub_chip, instruction.clone());
+        } else {
+            tester.execute(&mut muldiv_chip, instruction.clone());
+        }
+        let output = rv32_read_heap_default::<NUM_LIMBS>(&mut tester, &instruction, 1);
+        assert_eq!(output, to_limbs(&expected), "{op:?} on {a} and {b}");
+    }
+
+    let tester = tester
+        .build()
+        .load(addsub_chip)
+        .load(muldiv_chip)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+fn modular_op_strategy(
+    modulus: BigUint,
+) -> impl Strategy<Value = (Rv32ModularArithmeticOpcode, BigUint, BigUint)> {
+    let ops = vec![
+        Rv32ModularArithmeticOpcode::ADD,
+        Rv32ModularArithmeticOpcode::SUB,
+        Rv32ModularArithmeticOpcode::MUL,
+        Rv32ModularArithmeticOpcode::DIV,
+    ];
+    let element = field_element_strategy(modulus.clone(), NUM_LIMBS, LIMB_BITS);
+    (select(ops), element.clone(), element).prop_map(move |(op, a, b)| {
+        // Division by zero is undefined, divide by one instead.
+        if op == Rv32ModularArithmeticOpcode::DIV && (&b % &modulus).is_zero() {
+            (op, a, BigUint::one())
+        } else {
+            (op, a, b)
+        }
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn prop_modular_coord_matches_reference(
+        ops in prop::collection::vec(modular_op_strategy(secp256k1_coord_prime()), 1..8)
+    ) {
+        run_modular_prop_test(secp256k1_coord_prime(), ops);
+    }
+
+    #[test]
+    fn prop_modular_scalar_matches_reference(
+        ops in prop::collection::vec(modular_op_strategy(secp256k1_scalar_prime()), 1..8)
+    ) {
+        run_modular_prop_test(secp256k1_scalar_prime(), ops);
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use derive_more::derive::From;
 use num_bigint_dig::BigUint;
+use openvm_algebra_guest::MODULAR_MULADD_MAX_MODULI;
 use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
 use openvm_circuit::{
     self,
@@ -9,9 +10,7 @@ use openvm_circuit::{
     system::phantom::PhantomChip,
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
-use openvm_circuit_primitives::bitwise_op_lookup::{
-    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
-};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{UsizeOpcode, VmOpcode};
 use openvm_mod_circuit_builder::ExprBuilderConfig;
@@ -23,7 +22,7 @@ use strum::EnumCount;
 
 use crate::modular_chip::{
     ModularAddSubChip, ModularAddSubCoreChip, ModularIsEqualChip, ModularIsEqualCoreChip,
-    ModularMulDivChip, ModularMulDivCoreChip,
+    ModularMulAddChip, ModularMulDivChip, ModularMulDivCoreChip,
 };
 
 #[serde_as]
@@ -39,10 +38,12 @@ pub enum ModularExtensionExecutor<F: PrimeField32> {
     ModularAddSubRv32_32(ModularAddSubChip<F, 1, 32>),
     ModularMulDivRv32_32(ModularMulDivChip<F, 1, 32>),
     ModularIsEqualRv32_32(ModularIsEqualChip<F, 1, 32, 32>),
+    ModularMulAddRv32_32(ModularMulAddChip<F, 1, 32>),
     // 48 limbs prime
     ModularAddSubRv32_48(ModularAddSubChip<F, 3, 16>),
     ModularMulDivRv32_48(ModularMulDivChip<F, 3, 16>),
     ModularIsEqualRv32_48(ModularIsEqualChip<F, 3, 16, 48>),
+    ModularMulAddRv32_48(ModularMulAddChip<F, 3, 16>),
 }
 
 #[derive(ChipUsageGetter, Chip, AnyEnum, From)]
@@ -67,17 +68,7 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
             memory_controller,
         } = builder.system_port();
         let range_checker = builder.system_base().range_checker_chip.clone();
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
-        {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
 
         let addsub_opcodes = (Rv32ModularArithmeticOpcode::ADD as usize)
             ..=(Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize);
@@ -85,6 +76,8 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
             ..=(Rv32ModularArithmeticOpcode::SETUP_MULDIV as usize);
         let iseq_opcodes = (Rv32ModularArithmeticOpcode::IS_EQ as usize)
             ..=(Rv32ModularArithmeticOpcode::SETUP_ISEQ as usize);
+        let muladd_opcodes = (Rv32ModularArithmeticOpcode::MULADD as usize)
+            ..=(Rv32ModularArithmeticOpcode::SETUP_MULADD as usize);
 
         for (i, modulus) in self.supported_modulus.iter().enumerate() {
             // determine the number of bytes needed to represent a prime field element
@@ -117,7 +110,9 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
 
             if bytes <= 32 {
                 let addsub_chip = ModularAddSubChip::new(
-                    adapter_chip_32.clone(),
+                    adapter_chip_32.clone().with_setup_guard(
+                        Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize + class_offset,
+                    ),
                     ModularAddSubCoreChip::new(
                         config32.clone(),
                         range_checker.clone(),
@@ -132,7 +127,9 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
                         .map(|x| VmOpcode::from_usize(x + class_offset)),
                 )?;
                 let muldiv_chip = ModularMulDivChip::new(
-                    adapter_chip_32.clone(),
+                    adapter_chip_32.clone().with_setup_guard(
+                        Rv32ModularArithmeticOpcode::SETUP_MULDIV as usize + class_offset,
+                    ),
                     ModularMulDivCoreChip::new(
                         config32.clone(),
                         range_checker.clone(),
@@ -166,9 +163,31 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
                         .clone()
                         .map(|x| VmOpcode::from_usize(x + class_offset)),
                 )?;
+                // The guest can only encode the fused multiply-add for the first moduli.
+                if i < MODULAR_MULADD_MAX_MODULI {
+                    let muladd_chip = ModularMulAddChip::new(
+                        Rv32VecHeapAdapterChip::new(
+                            execution_bus,
+                            program_bus,
+                            memory_controller.clone(),
+                            bitwise_lu_chip.clone(),
+                        ),
+                        memory_controller.clone(),
+                        config32.clone(),
+                        class_offset,
+                    );
+                    inventory.add_executor(
+                        ModularExtensionExecutor::ModularMulAddRv32_32(muladd_chip),
+                        muladd_opcodes
+                            .clone()
+                            .map(|x| VmOpcode::from_usize(x + class_offset)),
+                    )?;
+                }
             } else if bytes <= 48 {
                 let addsub_chip = ModularAddSubChip::new(
-                    adapter_chip_48.clone(),
+                    adapter_chip_48.clone().with_setup_guard(
+                        Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize + class_offset,
+                    ),
                     ModularAddSubCoreChip::new(
                         config48.clone(),
                         range_checker.clone(),
@@ -183,7 +202,9 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
                         .map(|x| VmOpcode::from_usize(x + class_offset)),
                 )?;
                 let muldiv_chip = ModularMulDivChip::new(
-                    adapter_chip_48.clone(),
+                    adapter_chip_48.clone().with_setup_guard(
+                        Rv32ModularArithmeticOpcode::SETUP_MULDIV as usize + class_offset,
+                    ),
                     ModularMulDivCoreChip::new(
                         config48.clone(),
                         range_checker.clone(),
@@ -217,6 +238,26 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
                         .clone()
                         .map(|x| VmOpcode::from_usize(x + class_offset)),
                 )?;
+                // The guest can only encode the fused multiply-add for the first moduli.
+                if i < MODULAR_MULADD_MAX_MODULI {
+                    let muladd_chip = ModularMulAddChip::new(
+                        Rv32VecHeapAdapterChip::new(
+                            execution_bus,
+                            program_bus,
+                            memory_controller.clone(),
+                            bitwise_lu_chip.clone(),
+                        ),
+                        memory_controller.clone(),
+                        config48.clone(),
+                        class_offset,
+                    );
+                    inventory.add_executor(
+                        ModularExtensionExecutor::ModularMulAddRv32_48(muladd_chip),
+                        muladd_opcodes
+                            .clone()
+                            .map(|x| VmOpcode::from_usize(x + class_offset)),
+                    )?;
+                }
             } else {
                 panic!("Modulus too large");
             }
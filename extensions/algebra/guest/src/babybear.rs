@@ -0,0 +1,205 @@
+#[cfg(target_os = "zkvm")]
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{field::Field, DivAssignUnsafe, DivUnsafe};
+
+/// The BabyBear prime `15 * 2^27 + 1`.
+pub const BABYBEAR_MODULUS: u32 = 0x78000001;
+
+/// Issues the BabyBear intrinsic `$funct7` with a fresh output and pointers to both operands.
+#[cfg(target_os = "zkvm")]
+macro_rules! babybear_insn {
+    ($funct7:ident, $a:expr, $b:expr) => {{
+        let mut uninit = MaybeUninit::<Self>::uninit();
+        openvm_platform::custom_insn_r!(
+            crate::OPCODE,
+            crate::BABYBEAR_FUNCT3,
+            crate::BabyBearBaseFunct7::$funct7 as usize,
+            uninit.as_mut_ptr(),
+            $a as *const Self,
+            $b as *const Self
+        );
+        unsafe { uninit.assume_init() }
+    }};
+}
+
+/// Element of the BabyBear field, stored as its canonical `u32` representative. In the zkVM,
+/// arithmetic uses the BabyBear intrinsics, which always write canonical results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C, align(4))]
+pub struct BabyBear(u32);
+
+impl BabyBear {
+    /// Creates an element from any `u32`, reducing it modulo the BabyBear prime.
+    pub const fn new(value: u32) -> Self {
+        Self(value % BABYBEAR_MODULUS)
+    }
+
+    /// The canonical representative, in `[0, p)`.
+    pub const fn as_canonical_u32(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns the multiplicative inverse. Panics on the host, and fails execution in the zkVM,
+    /// if `self` is zero.
+    pub fn inverse(&self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        assert_ne!(self.0, 0, "zero has no inverse");
+        Self::ONE.div_impl(self)
+    }
+
+    #[inline(always)]
+    fn add_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(add_mod(self.0, other.0))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            babybear_insn!(Add, self, other)
+        }
+    }
+
+    #[inline(always)]
+    fn sub_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(sub_mod(self.0, other.0))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            babybear_insn!(Sub, self, other)
+        }
+    }
+
+    #[inline(always)]
+    fn mul_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(mul_mod(self.0, other.0))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            babybear_insn!(Mul, self, other)
+        }
+    }
+
+    /// Computes `self / other`. The result is undefined if `other` is zero.
+    #[inline(always)]
+    fn div_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(mul_mod(self.0, pow_mod(other.0, BABYBEAR_MODULUS - 2)))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            babybear_insn!(Div, self, other)
+        }
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+pub(crate) fn add_mod(a: u32, b: u32) -> u32 {
+    ((a as u64 + b as u64) % BABYBEAR_MODULUS as u64) as u32
+}
+
+#[cfg(not(target_os = "zkvm"))]
+pub(crate) fn sub_mod(a: u32, b: u32) -> u32 {
+    ((a as u64 + BABYBEAR_MODULUS as u64 - b as u64) % BABYBEAR_MODULUS as u64) as u32
+}
+
+#[cfg(not(target_os = "zkvm"))]
+pub(crate) fn mul_mod(a: u32, b: u32) -> u32 {
+    ((a as u64 * b as u64) % BABYBEAR_MODULUS as u64) as u32
+}
+
+#[cfg(not(target_os = "zkvm"))]
+pub(crate) fn pow_mod(mut base: u32, mut exp: u32) -> u32 {
+    let mut acc = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base);
+        }
+        base = mul_mod(base, base);
+        exp >>= 1;
+    }
+    acc
+}
+
+macro_rules! impl_binop {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $impl:ident) => {
+        impl $trait for BabyBear {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(self, other: Self) -> Self {
+                self.$impl(&other)
+            }
+        }
+
+        impl<'a> $trait<&'a BabyBear> for BabyBear {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(self, other: &'a Self) -> Self {
+                self.$impl(other)
+            }
+        }
+
+        impl<'a> $trait<&'a BabyBear> for &'a BabyBear {
+            type Output = BabyBear;
+            #[inline(always)]
+            fn $method(self, other: &'a BabyBear) -> BabyBear {
+                self.$impl(other)
+            }
+        }
+
+        impl $assign_trait for BabyBear {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: Self) {
+                *self = self.$impl(&other);
+            }
+        }
+
+        impl<'a> $assign_trait<&'a BabyBear> for BabyBear {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: &'a Self) {
+                *self = self.$impl(other);
+            }
+        }
+    };
+}
+
+impl_binop!(Add, add, AddAssign, add_assign, add_impl);
+impl_binop!(Sub, sub, SubAssign, sub_assign, sub_impl);
+impl_binop!(Mul, mul, MulAssign, mul_assign, mul_impl);
+impl_binop!(
+    DivUnsafe,
+    div_unsafe,
+    DivAssignUnsafe,
+    div_assign_unsafe,
+    div_impl
+);
+
+impl Neg for BabyBear {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self::ZERO.sub_impl(&self)
+    }
+}
+
+impl Field for BabyBear {
+    type SelfRef<'a> = &'a Self;
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+
+    fn double_assign(&mut self) {
+        *self = self.add_impl(self);
+    }
+
+    fn square_assign(&mut self) {
+        *self = self.mul_impl(self);
+    }
+}
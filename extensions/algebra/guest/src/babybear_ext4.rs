@@ -0,0 +1,247 @@
+#[cfg(target_os = "zkvm")]
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_os = "zkvm"))]
+use crate::babybear::{add_mod, mul_mod, pow_mod, sub_mod};
+use crate::{field::Field, DivAssignUnsafe, DivUnsafe, BABYBEAR_MODULUS};
+
+/// The extension is `F_p[t]/(t^4 - W)`, the same as Plonky3's quartic BabyBear extension.
+pub const BABYBEAR_EXT4_W: u32 = 11;
+
+/// Issues the extension intrinsic `$funct7` with a fresh output and pointers to both operands.
+#[cfg(target_os = "zkvm")]
+macro_rules! ext4_insn {
+    ($funct7:ident, $a:expr, $b:expr) => {{
+        let mut uninit = MaybeUninit::<Self>::uninit();
+        openvm_platform::custom_insn_r!(
+            crate::OPCODE,
+            crate::BABYBEAR_EXT4_FUNCT3,
+            crate::BabyBearExt4BaseFunct7::$funct7 as usize,
+            uninit.as_mut_ptr(),
+            $a as *const Self,
+            $b as *const Self
+        );
+        unsafe { uninit.assume_init() }
+    }};
+}
+
+/// Element `c[0] + c[1] t + c[2] t^2 + c[3] t^3` of the degree-4 binomial extension of BabyBear,
+/// with canonical coefficients. In the zkVM, arithmetic uses the BabyBear extension intrinsics;
+/// [`BabyBearExt4::setup`] must be called once before any operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C, align(16))]
+pub struct BabyBearExt4([u32; 4]);
+
+impl BabyBearExt4 {
+    /// Creates an element from its coefficients, reducing them modulo the BabyBear prime.
+    pub const fn new(coeffs: [u32; 4]) -> Self {
+        Self([
+            coeffs[0] % BABYBEAR_MODULUS,
+            coeffs[1] % BABYBEAR_MODULUS,
+            coeffs[2] % BABYBEAR_MODULUS,
+            coeffs[3] % BABYBEAR_MODULUS,
+        ])
+    }
+
+    /// Embeds a base field element.
+    pub const fn from_base(c: u32) -> Self {
+        Self::new([c, 0, 0, 0])
+    }
+
+    /// Canonical coefficients, lowest degree first.
+    pub const fn coeffs(&self) -> [u32; 4] {
+        self.0
+    }
+
+    /// Checks that the extension chips are configured for BabyBear. Must be called before the
+    /// first operation in the zkVM; does nothing on the host.
+    pub fn setup() {
+        #[cfg(target_os = "zkvm")]
+        {
+            let modulus = Self([BABYBEAR_MODULUS, 0, 0, 0]);
+            let mut uninit = MaybeUninit::<Self>::uninit();
+            // `rs2` selects the chip: x0 for SETUP_ADDSUB, x1 for SETUP_MULDIV.
+            openvm_platform::custom_insn_r!(
+                crate::OPCODE,
+                crate::BABYBEAR_EXT4_FUNCT3,
+                crate::BabyBearExt4BaseFunct7::Setup as usize,
+                uninit.as_mut_ptr(),
+                &modulus as *const Self,
+                "x0"
+            );
+            openvm_platform::custom_insn_r!(
+                crate::OPCODE,
+                crate::BABYBEAR_EXT4_FUNCT3,
+                crate::BabyBearExt4BaseFunct7::Setup as usize,
+                uninit.as_mut_ptr(),
+                &modulus as *const Self,
+                "x1"
+            );
+        }
+    }
+
+    /// Computes `self / other`. Dividing by zero fails execution in the guest, like the base field
+    /// DIV. On the host the inverse of zero is zero.
+    #[inline(always)]
+    fn div_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            self.mul_impl(&other.inverse())
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            ext4_insn!(Div, self, other)
+        }
+    }
+
+    #[inline(always)]
+    fn add_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(core::array::from_fn(|i| add_mod(self.0[i], other.0[i])))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            ext4_insn!(Add, self, other)
+        }
+    }
+
+    #[inline(always)]
+    fn sub_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            Self(core::array::from_fn(|i| sub_mod(self.0[i], other.0[i])))
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            ext4_insn!(Sub, self, other)
+        }
+    }
+
+    #[inline(always)]
+    fn mul_impl(&self, other: &Self) -> Self {
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            let (a, b) = (&self.0, &other.0);
+            let mut coeffs = [0u32; 4];
+            for (i, &a_i) in a.iter().enumerate() {
+                for (j, &b_j) in b.iter().enumerate() {
+                    let mut term = mul_mod(a_i, b_j);
+                    if i + j >= 4 {
+                        term = mul_mod(term, BABYBEAR_EXT4_W);
+                    }
+                    coeffs[(i + j) % 4] = add_mod(coeffs[(i + j) % 4], term);
+                }
+            }
+            Self(coeffs)
+        }
+        #[cfg(target_os = "zkvm")]
+        {
+            ext4_insn!(Mul, self, other)
+        }
+    }
+
+    /// Inverse through the norm: with `a'(t) = a(-t)`, `a * a' = n0 + n1 t^2` lies in the
+    /// quadratic subfield and `(n0 + n1 t^2)(n0 - n1 t^2) = n0^2 - W n1^2` in the base field.
+    #[cfg(not(target_os = "zkvm"))]
+    fn inverse(&self) -> Self {
+        let [a0, a1, a2, a3] = self.0;
+        let w = BABYBEAR_EXT4_W;
+        let n0 = sub_mod(
+            add_mod(mul_mod(a0, a0), mul_mod(w, mul_mod(a2, a2))),
+            mul_mod(2 * w, mul_mod(a1, a3)),
+        );
+        let n1 = sub_mod(
+            mul_mod(2, mul_mod(a0, a2)),
+            add_mod(mul_mod(a1, a1), mul_mod(w, mul_mod(a3, a3))),
+        );
+        let norm = sub_mod(mul_mod(n0, n0), mul_mod(w, mul_mod(n1, n1)));
+        let norm_inv = pow_mod(norm, BABYBEAR_MODULUS - 2);
+        let conj = Self([a0, sub_mod(0, a1), a2, sub_mod(0, a3)]);
+        let quad_inv = Self([
+            mul_mod(n0, norm_inv),
+            0,
+            sub_mod(0, mul_mod(n1, norm_inv)),
+            0,
+        ]);
+        conj.mul_impl(&quad_inv)
+    }
+}
+
+macro_rules! impl_binop {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $impl:ident) => {
+        impl $trait for BabyBearExt4 {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(self, other: Self) -> Self {
+                self.$impl(&other)
+            }
+        }
+
+        impl<'a> $trait<&'a BabyBearExt4> for BabyBearExt4 {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(self, other: &'a Self) -> Self {
+                self.$impl(other)
+            }
+        }
+
+        impl<'a> $trait<&'a BabyBearExt4> for &'a BabyBearExt4 {
+            type Output = BabyBearExt4;
+            #[inline(always)]
+            fn $method(self, other: &'a BabyBearExt4) -> BabyBearExt4 {
+                self.$impl(other)
+            }
+        }
+
+        impl $assign_trait for BabyBearExt4 {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: Self) {
+                *self = self.$impl(&other);
+            }
+        }
+
+        impl<'a> $assign_trait<&'a BabyBearExt4> for BabyBearExt4 {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: &'a Self) {
+                *self = self.$impl(other);
+            }
+        }
+    };
+}
+
+impl_binop!(Add, add, AddAssign, add_assign, add_impl);
+impl_binop!(Sub, sub, SubAssign, sub_assign, sub_impl);
+impl_binop!(Mul, mul, MulAssign, mul_assign, mul_impl);
+impl_binop!(
+    DivUnsafe,
+    div_unsafe,
+    DivAssignUnsafe,
+    div_assign_unsafe,
+    div_impl
+);
+
+impl Neg for BabyBearExt4 {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self::ZERO.sub_impl(&self)
+    }
+}
+
+impl Field for BabyBearExt4 {
+    type SelfRef<'a> = &'a Self;
+    const ZERO: Self = Self([0; 4]);
+    const ONE: Self = Self([1, 0, 0, 0]);
+
+    fn double_assign(&mut self) {
+        *self = self.add_impl(self);
+    }
+
+    fn square_assign(&mut self) {
+        *self = self.mul_impl(self);
+    }
+}
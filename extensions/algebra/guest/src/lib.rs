@@ -5,6 +5,12 @@ extern crate self as openvm_algebra_guest;
 pub const OPCODE: u8 = 0x2b;
 pub const MODULAR_ARITHMETIC_FUNCT3: u8 = 0b000;
 pub const COMPLEX_EXT_FIELD_FUNCT3: u8 = 0b010;
+/// The fused `a * b + c` is an R4-type instruction, with `funct2 = mod_idx`.
+pub const MODULAR_MULADD_FUNCT3: u8 = 0b100;
+/// Only the first moduli can use the fused multiply-add, since `funct2` has two bits.
+pub const MODULAR_MULADD_MAX_MODULI: usize = 4;
+pub const BABYBEAR_EXT4_FUNCT3: u8 = 0b101;
+pub const BABYBEAR_FUNCT3: u8 = 0b110;
 
 /// Modular arithmetic is configurable.
 /// The funct7 field equals `mod_idx * MODULAR_ARITHMETIC_MAX_KINDS + base_funct7`.
@@ -39,6 +45,27 @@ impl ComplexExtFieldBaseFunct7 {
     pub const COMPLEX_EXT_FIELD_MAX_KINDS: u8 = 8;
 }
 
+/// The BabyBear quartic extension has a single instance, so funct7 is the base funct7.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum BabyBearExt4BaseFunct7 {
+    Add = 0,
+    Sub,
+    Mul,
+    Div,
+    Setup,
+}
+
+/// BabyBear arithmetic needs no setup, since the chip is specific to the BabyBear prime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum BabyBearBaseFunct7 {
+    Add = 0,
+    Sub,
+    Mul,
+    Div,
+}
+
 /// Modular arithmetic traits for use with OpenVM intrinsics.
 extern crate alloc;
 
@@ -68,6 +95,17 @@ mod halo2curves;
 mod exp_bytes;
 pub use exp_bytes::*;
 
+/// The BabyBear field
+mod babybear;
+pub use babybear::*;
+
+/// Degree-4 extension of the BabyBear field
+mod babybear_ext4;
+pub use babybear_ext4::*;
+
+/// Verification of BabyBear STARK proofs
+pub mod stark;
+
 /// Division operation that is undefined behavior when the denominator is not invertible.
 pub trait DivUnsafe<Rhs = Self>: Sized {
     /// Output type of `div_unsafe`.
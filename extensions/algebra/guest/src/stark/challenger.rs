@@ -0,0 +1,85 @@
+use super::{
+    poseidon2::{Poseidon2Params, POSEIDON2_WIDTH},
+    Digest, DIGEST_SIZE,
+};
+use crate::{BabyBear, BabyBearExt4};
+
+/// Fiat-Shamir transcript of the BabyBear STARK configuration, a duplex sponge over the Poseidon2
+/// permutation with rate [DIGEST_SIZE], in overwrite mode.
+///
+/// Reference: `p3_challenger::DuplexChallenger`
+#[derive(Clone, Debug)]
+pub struct DuplexChallenger<'a> {
+    perm: &'a Poseidon2Params,
+    sponge_state: [BabyBear; POSEIDON2_WIDTH],
+    nb_inputs: usize,
+    nb_outputs: usize,
+}
+
+impl<'a> DuplexChallenger<'a> {
+    pub fn new(perm: &'a Poseidon2Params) -> Self {
+        Self {
+            perm,
+            sponge_state: [BabyBear::default(); POSEIDON2_WIDTH],
+            nb_inputs: 0,
+            nb_outputs: 0,
+        }
+    }
+
+    fn duplexing(&mut self) {
+        self.nb_inputs = 0;
+        self.perm.permute_mut(&mut self.sponge_state);
+        self.nb_outputs = DIGEST_SIZE;
+    }
+
+    pub fn observe(&mut self, value: BabyBear) {
+        self.nb_outputs = 0;
+        self.sponge_state[self.nb_inputs] = value;
+        self.nb_inputs += 1;
+        if self.nb_inputs == DIGEST_SIZE {
+            self.duplexing();
+        }
+    }
+
+    pub fn observe_slice(&mut self, values: &[BabyBear]) {
+        for &value in values {
+            self.observe(value);
+        }
+    }
+
+    pub fn observe_digest(&mut self, digest: &Digest) {
+        self.observe_slice(digest);
+    }
+
+    pub fn observe_ext(&mut self, value: BabyBearExt4) {
+        for c in value.coeffs() {
+            self.observe(BabyBear::new(c));
+        }
+    }
+
+    pub fn sample(&mut self) -> BabyBear {
+        if self.nb_inputs != 0 || self.nb_outputs == 0 {
+            self.duplexing();
+        }
+        self.nb_outputs -= 1;
+        self.sponge_state[self.nb_outputs]
+    }
+
+    pub fn sample_ext(&mut self) -> BabyBearExt4 {
+        let coeffs = [(); 4].map(|_| self.sample().as_canonical_u32());
+        BabyBearExt4::new(coeffs)
+    }
+
+    /// Samples the `bits` low bits of a field element.
+    pub fn sample_bits(&mut self, bits: usize) -> usize {
+        assert!(bits < usize::BITS as usize, "cannot sample {bits} bits");
+        self.sample().as_canonical_u32() as usize & ((1 << bits) - 1)
+    }
+
+    /// Observes the proof of work `witness` and returns whether the next sample has its `bits` low
+    /// bits equal to zero.
+    pub fn check_witness(&mut self, bits: usize, witness: BabyBear) -> bool {
+        self.observe(witness);
+        self.sample_bits(bits) == 0
+    }
+}
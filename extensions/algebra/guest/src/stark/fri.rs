@@ -0,0 +1,308 @@
+use alloc::{vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+use super::{challenger::DuplexChallenger, mmcs::verify_batch, poseidon2::Poseidon2Params, Digest};
+use crate::{field::Field, BabyBear, BabyBearExt4, DivUnsafe};
+
+/// Multiplicative generator of the BabyBear field, the shift of the evaluation cosets.
+pub const BABYBEAR_GENERATOR: u32 = 31;
+
+/// Largest `k` such that BabyBear has a multiplicative subgroup of order `2^k`.
+pub const BABYBEAR_TWO_ADICITY: usize = 27;
+
+/// Generator of the subgroup of order `2^27`, `31^15`.
+const BABYBEAR_TWO_ADIC_GENERATOR: u32 = 0x1a427a41;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriParameters {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+/// Opening of the rows of a batch of matrices at a query index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchOpening {
+    pub opened_values: Vec<Vec<BabyBear>>,
+    pub opening_proof: Vec<Digest>,
+}
+
+/// Opening of the sibling of the folded evaluation in a commit phase round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitPhaseProofStep {
+    pub sibling_value: BabyBearExt4,
+    pub opening_proof: Vec<Digest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryProof {
+    /// One opening per round of committed matrices.
+    pub input_proof: Vec<BatchOpening>,
+    pub commit_phase_openings: Vec<CommitPhaseProofStep>,
+}
+
+/// Proof of the two-adic FRI PCS, with the fields of `p3_fri::FriProof`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriProof {
+    pub commit_phase_commits: Vec<Digest>,
+    pub query_proofs: Vec<QueryProof>,
+    pub final_poly: BabyBearExt4,
+    pub pow_witness: BabyBear,
+}
+
+/// A committed matrix and its claimed evaluations.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcsMatrix {
+    /// Log size of the trace domain of the matrix, before the blowup.
+    pub log_n: usize,
+    /// Points at which the matrix is opened, each with the evaluations of every column.
+    pub points: Vec<(BabyBearExt4, Vec<BabyBearExt4>)>,
+}
+
+/// A commitment to a batch of matrices, in the order in which they were committed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcsRound {
+    pub commit: Digest,
+    pub mats: Vec<PcsMatrix>,
+}
+
+/// Reference: `p3_fri::FriError`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FriError {
+    InvalidProofShape,
+    InputMmcsError,
+    CommitPhaseMmcsError,
+    FinalPolyMismatch,
+    InvalidPowWitness,
+}
+
+/// Verifies that the matrices of `rounds` evaluate to the claimed values, continuing the
+/// transcript of `challenger`.
+///
+/// Reference: `p3_fri::TwoAdicFriPcs::verify`
+pub fn verify_two_adic_pcs(
+    perm: &Poseidon2Params,
+    config: &FriParameters,
+    rounds: &[PcsRound],
+    proof: &FriProof,
+    challenger: &mut DuplexChallenger<'_>,
+) -> Result<(), FriError> {
+    let alpha = challenger.sample_ext();
+    let betas: Vec<BabyBearExt4> = proof
+        .commit_phase_commits
+        .iter()
+        .map(|commit| {
+            challenger.observe_digest(commit);
+            challenger.sample_ext()
+        })
+        .collect();
+    challenger.observe_ext(proof.final_poly);
+
+    if proof.query_proofs.len() != config.num_queries {
+        return Err(FriError::InvalidProofShape);
+    }
+    if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
+        return Err(FriError::InvalidPowWitness);
+    }
+
+    let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
+    if log_max_height > BABYBEAR_TWO_ADICITY {
+        return Err(FriError::InvalidProofShape);
+    }
+    let query_indices: Vec<usize> = (0..config.num_queries)
+        .map(|_| challenger.sample_bits(log_max_height))
+        .collect();
+
+    for (query_proof, &index) in proof.query_proofs.iter().zip(&query_indices) {
+        let reduced_openings = reduced_openings(
+            perm,
+            config,
+            rounds,
+            &query_proof.input_proof,
+            alpha,
+            index,
+            log_max_height,
+        )?;
+        let folded_eval = verify_query(
+            perm,
+            &proof.commit_phase_commits,
+            &query_proof.commit_phase_openings,
+            &betas,
+            &reduced_openings,
+            index,
+            log_max_height,
+        )?;
+        if folded_eval != proof.final_poly {
+            return Err(FriError::FinalPolyMismatch);
+        }
+    }
+    Ok(())
+}
+
+/// Checks the openings of the committed matrices at `index` and combines their quotients by the
+/// opening points with powers of `alpha`, one sum per log height.
+fn reduced_openings(
+    perm: &Poseidon2Params,
+    config: &FriParameters,
+    rounds: &[PcsRound],
+    input_proof: &[BatchOpening],
+    alpha: BabyBearExt4,
+    index: usize,
+    log_max_height: usize,
+) -> Result<Vec<BabyBearExt4>, FriError> {
+    if input_proof.len() != rounds.len() {
+        return Err(FriError::InvalidProofShape);
+    }
+    let mut ro = vec![BabyBearExt4::ZERO; log_max_height + 1];
+    let mut alpha_pow = vec![BabyBearExt4::ONE; log_max_height + 1];
+    for (round, batch_opening) in rounds.iter().zip(input_proof) {
+        let log_heights: Vec<usize> = round
+            .mats
+            .iter()
+            .map(|mat| mat.log_n + config.log_blowup)
+            .collect();
+        let log_batch_max_height = log_heights.iter().copied().max().unwrap_or(0);
+        if log_batch_max_height > log_max_height
+            || batch_opening.opened_values.len() != round.mats.len()
+        {
+            return Err(FriError::InvalidProofShape);
+        }
+        let heights: Vec<usize> = log_heights.iter().map(|&h| 1 << h).collect();
+        if !verify_batch(
+            perm,
+            &round.commit,
+            &heights,
+            index >> (log_max_height - log_batch_max_height),
+            &batch_opening.opened_values,
+            &batch_opening.opening_proof,
+        ) {
+            return Err(FriError::InputMmcsError);
+        }
+
+        for ((mat, mat_opening), &log_height) in round
+            .mats
+            .iter()
+            .zip(&batch_opening.opened_values)
+            .zip(&log_heights)
+        {
+            let rev_reduced_index =
+                reverse_bits_len(index >> (log_max_height - log_height), log_height);
+            let x = embed(
+                exp(two_adic_generator(log_height), rev_reduced_index as u64)
+                    * BabyBear::new(BABYBEAR_GENERATOR),
+            );
+            for (z, ps_at_z) in &mat.points {
+                if ps_at_z.len() != mat_opening.len() {
+                    return Err(FriError::InvalidProofShape);
+                }
+                let z_minus_x_inv = BabyBearExt4::ONE.div_unsafe(&(*z - x));
+                for (&p_at_x, p_at_z) in mat_opening.iter().zip(ps_at_z) {
+                    let quotient = (*p_at_z - embed(p_at_x)) * z_minus_x_inv;
+                    ro[log_height] += alpha_pow[log_height] * quotient;
+                    alpha_pow[log_height] *= alpha;
+                }
+            }
+        }
+    }
+    Ok(ro)
+}
+
+/// Folds the reduced openings of a query down to the final polynomial.
+///
+/// Reference: `p3_fri::verifier::verify_query`
+fn verify_query(
+    perm: &Poseidon2Params,
+    commit_phase_commits: &[Digest],
+    commit_phase_openings: &[CommitPhaseProofStep],
+    betas: &[BabyBearExt4],
+    reduced_openings: &[BabyBearExt4],
+    mut index: usize,
+    log_max_height: usize,
+) -> Result<BabyBearExt4, FriError> {
+    if commit_phase_openings.len() != commit_phase_commits.len() {
+        return Err(FriError::InvalidProofShape);
+    }
+    let mut folded_eval = BabyBearExt4::ZERO;
+    let mut x = exp(
+        two_adic_generator(log_max_height),
+        reverse_bits_len(index, log_max_height) as u64,
+    );
+
+    for (i, ((commit, step), beta)) in commit_phase_commits
+        .iter()
+        .zip(commit_phase_openings)
+        .zip(betas)
+        .enumerate()
+    {
+        let log_folded_height = log_max_height - i - 1;
+        folded_eval += reduced_openings[log_folded_height + 1];
+
+        let index_sibling = index ^ 1;
+        let index_pair = index >> 1;
+        let mut evals = [folded_eval; 2];
+        evals[index_sibling % 2] = step.sibling_value;
+        let row: Vec<BabyBear> = evals
+            .iter()
+            .flat_map(|eval| eval.coeffs().map(BabyBear::new))
+            .collect();
+        if !verify_batch(
+            perm,
+            commit,
+            &[1 << log_folded_height],
+            index_pair,
+            &[row],
+            &step.opening_proof,
+        ) {
+            return Err(FriError::CommitPhaseMmcsError);
+        }
+
+        // The sibling of `x` is `-x`.
+        let mut xs = [embed(x); 2];
+        xs[index_sibling % 2] = -xs[index_sibling % 2];
+        folded_eval =
+            evals[0] + (*beta - xs[0]) * (evals[1] - evals[0]).div_unsafe(&(xs[1] - xs[0]));
+
+        index = index_pair;
+        x.square_assign();
+    }
+    Ok(folded_eval)
+}
+
+/// Generator of the subgroup of order `2^log_n`.
+pub fn two_adic_generator(log_n: usize) -> BabyBear {
+    assert!(
+        log_n <= BABYBEAR_TWO_ADICITY,
+        "no subgroup of order 2^{log_n}"
+    );
+    let mut g = BabyBear::new(BABYBEAR_TWO_ADIC_GENERATOR);
+    for _ in log_n..BABYBEAR_TWO_ADICITY {
+        g.square_assign();
+    }
+    g
+}
+
+fn exp(mut base: BabyBear, mut power: u64) -> BabyBear {
+    let mut acc = BabyBear::ONE;
+    while power > 0 {
+        if power & 1 == 1 {
+            acc *= base;
+        }
+        base.square_assign();
+        power >>= 1;
+    }
+    acc
+}
+
+/// Reverses the `bits` low bits of `x`.
+fn reverse_bits_len(x: usize, bits: usize) -> usize {
+    if bits == 0 {
+        0
+    } else {
+        x.reverse_bits() >> (usize::BITS as usize - bits)
+    }
+}
+
+fn embed(x: BabyBear) -> BabyBearExt4 {
+    BabyBearExt4::from_base(x.as_canonical_u32())
+}
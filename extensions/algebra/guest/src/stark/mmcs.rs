@@ -0,0 +1,110 @@
+use alloc::vec::Vec;
+use core::{cmp::Reverse, iter::Peekable};
+
+use super::{
+    poseidon2::{Poseidon2Params, POSEIDON2_WIDTH},
+    Digest, DIGEST_SIZE,
+};
+use crate::BabyBear;
+
+/// Hashes `values` with the padding-free sponge of the Merkle trees, absorbing [DIGEST_SIZE]
+/// elements at a time in overwrite mode.
+///
+/// Reference: `p3_symmetric::PaddingFreeSponge`
+pub fn hash<'a>(perm: &Poseidon2Params, values: impl IntoIterator<Item = &'a BabyBear>) -> Digest {
+    let mut state = [BabyBear::default(); POSEIDON2_WIDTH];
+    let mut absorbed = 0;
+    for &value in values {
+        state[absorbed] = value;
+        absorbed += 1;
+        if absorbed == DIGEST_SIZE {
+            perm.permute_mut(&mut state);
+            absorbed = 0;
+        }
+    }
+    if absorbed != 0 {
+        perm.permute_mut(&mut state);
+    }
+    truncate(&state)
+}
+
+/// Compresses two nodes of a Merkle tree into their parent.
+///
+/// Reference: `p3_symmetric::TruncatedPermutation`
+pub fn compress(perm: &Poseidon2Params, left: &Digest, right: &Digest) -> Digest {
+    let mut state = [BabyBear::default(); POSEIDON2_WIDTH];
+    state[..DIGEST_SIZE].copy_from_slice(left);
+    state[DIGEST_SIZE..].copy_from_slice(right);
+    perm.permute_mut(&mut state);
+    truncate(&state)
+}
+
+fn truncate(state: &[BabyBear; POSEIDON2_WIDTH]) -> Digest {
+    let mut digest = [BabyBear::default(); DIGEST_SIZE];
+    digest.copy_from_slice(&state[..DIGEST_SIZE]);
+    digest
+}
+
+/// Returns whether `opened_values` are the rows at `index` of matrices of the given `heights`
+/// committed to by `commit`, with the authentication path `proof`. The rows and heights are in
+/// the order of the matrices when committed. A matrix whose height is smaller than the tallest
+/// one is opened at the row `index` shifted right by the difference of their log heights.
+///
+/// Reference: `p3_merkle_tree::MerkleTreeMmcs::verify_batch`
+pub fn verify_batch(
+    perm: &Poseidon2Params,
+    commit: &Digest,
+    heights: &[usize],
+    mut index: usize,
+    opened_values: &[Vec<BabyBear>],
+    proof: &[Digest],
+) -> bool {
+    if heights.is_empty() || heights.len() != opened_values.len() {
+        return false;
+    }
+    // Matrices of the same padded height are hashed together, tallest first.
+    let mut order: Vec<usize> = (0..heights.len()).collect();
+    order.sort_by_key(|&i| Reverse(heights[i].next_power_of_two()));
+    let mut order = order.into_iter().peekable();
+
+    let mut curr_height_padded = heights[*order.peek().unwrap()].next_power_of_two();
+    if proof.len() != curr_height_padded.trailing_zeros() as usize {
+        return false;
+    }
+    let mut root = hash_rows(perm, heights, opened_values, &mut order, curr_height_padded);
+    for sibling in proof {
+        root = if index & 1 == 0 {
+            compress(perm, &root, sibling)
+        } else {
+            compress(perm, sibling, &root)
+        };
+        index >>= 1;
+        curr_height_padded >>= 1;
+
+        if order
+            .peek()
+            .is_some_and(|&i| heights[i].next_power_of_two() == curr_height_padded)
+        {
+            let next_height_openings_digest =
+                hash_rows(perm, heights, opened_values, &mut order, curr_height_padded);
+            root = compress(perm, &root, &next_height_openings_digest);
+        }
+    }
+    order.next().is_none() && root == *commit
+}
+
+/// Hashes together the rows of the next matrices in `order` whose padded height is
+/// `height_padded`.
+fn hash_rows(
+    perm: &Poseidon2Params,
+    heights: &[usize],
+    opened_values: &[Vec<BabyBear>],
+    order: &mut Peekable<alloc::vec::IntoIter<usize>>,
+    height_padded: usize,
+) -> Digest {
+    let mut rows = Vec::new();
+    while let Some(i) = order.next_if(|&i| heights[i].next_power_of_two() == height_padded) {
+        rows.push(opened_values[i].as_slice());
+    }
+    hash(perm, rows.into_iter().flatten())
+}
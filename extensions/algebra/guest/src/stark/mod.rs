@@ -0,0 +1,34 @@
+//! Building blocks for verifying OpenVM STARK proofs in guest programs, over the BabyBear
+//! configuration of the VM (`BabyBearPoseidon2Config`): the Poseidon2 permutation, the duplex
+//! challenger of the transcript, batch openings of Merkle tree commitments, and the two-adic FRI
+//! polynomial commitment scheme.
+//!
+//! The arithmetic uses the `babybear` and `babybear_ext4` extensions, so [BabyBearExt4::setup]
+//! must be called before verifying in the zkVM. The permutation is computed in software from
+//! these instructions, since the Poseidon2 chip only reads native field cells.
+//!
+//! These mirror the native recursion verifier, which is the reference for the transcript. They
+//! do not check the constraints of the AIRs: a STARK verifier observes the commitments of a
+//! proof, evaluates the constraints of every AIR at the out-of-domain point and compares them
+//! with the opened quotient, and then calls [fri::verify_two_adic_pcs] for the openings.
+//!
+//! [BabyBearExt4::setup]: crate::BabyBearExt4::setup
+
+use crate::BabyBear;
+
+pub mod challenger;
+pub mod fri;
+pub mod mmcs;
+pub mod poseidon2;
+
+#[cfg(all(test, not(target_os = "zkvm")))]
+mod tests;
+
+pub use challenger::DuplexChallenger;
+pub use poseidon2::Poseidon2Params;
+
+/// Number of BabyBear elements in a digest.
+pub const DIGEST_SIZE: usize = 8;
+
+/// Digest of the Merkle trees and of the transcript.
+pub type Digest = [BabyBear; DIGEST_SIZE];
@@ -0,0 +1,96 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{field::Field, BabyBear};
+
+/// Width of the Poseidon2 permutation of the BabyBear STARK configuration.
+pub const POSEIDON2_WIDTH: usize = 16;
+
+/// The 4x4 block of the external linear layer used by Plonky3's `Poseidon2BabyBear`.
+pub const MDS_MAT_4: [[u32; 4]; 4] = [[2, 3, 1, 1], [1, 2, 3, 1], [1, 1, 2, 3], [3, 1, 1, 2]];
+
+/// The 4x4 block of the external linear layer used by the Horizen Labs implementation.
+pub const HL_MDS_MAT_4: [[u32; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// Parameters of a width-16 Poseidon2 permutation over BabyBear with the S-box `x^7`, in the
+/// format of `openvm_poseidon2_air::Poseidon2Config`, from which they can be copied.
+///
+/// The permutation decides which proofs are accepted, so the parameters must be fixed by the
+/// program, not read from its input.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poseidon2Params {
+    /// Round constants of the external rounds, half of which come before the internal rounds.
+    pub external_constants: Vec<[BabyBear; POSEIDON2_WIDTH]>,
+    /// Round constants of the internal rounds.
+    pub internal_constants: Vec<BabyBear>,
+    pub ext_mds_matrix: [[u32; 4]; 4],
+    /// The diagonal of the internal matrix minus the identity.
+    pub int_diag_m1_matrix: [BabyBear; POSEIDON2_WIDTH],
+    pub reduction_factor: BabyBear,
+}
+
+impl Poseidon2Params {
+    /// Applies the permutation to `state`.
+    pub fn permute_mut(&self, state: &mut [BabyBear; POSEIDON2_WIDTH]) {
+        let rounds_f_beginning = self.external_constants.len() / 2;
+        self.ext_lin_layer(state);
+        for constants in &self.external_constants[..rounds_f_beginning] {
+            for (s, c) in state.iter_mut().zip(constants) {
+                *s = sbox(*s + c);
+            }
+            self.ext_lin_layer(state);
+        }
+        for constant in &self.internal_constants {
+            state[0] = sbox(state[0] + constant);
+            self.int_lin_layer(state);
+        }
+        for constants in &self.external_constants[rounds_f_beginning..] {
+            for (s, c) in state.iter_mut().zip(constants) {
+                *s = sbox(*s + c);
+            }
+            self.ext_lin_layer(state);
+        }
+    }
+
+    pub fn permute(&self, mut state: [BabyBear; POSEIDON2_WIDTH]) -> [BabyBear; POSEIDON2_WIDTH] {
+        self.permute_mut(&mut state);
+        state
+    }
+
+    fn ext_lin_layer(&self, state: &mut [BabyBear; POSEIDON2_WIDTH]) {
+        let mut new_state = [BabyBear::ZERO; POSEIDON2_WIDTH];
+        for i in (0..POSEIDON2_WIDTH).step_by(4) {
+            for (index1, row) in self.ext_mds_matrix.iter().enumerate() {
+                for (index2, &entry) in row.iter().enumerate() {
+                    new_state[i + index1] += BabyBear::new(entry) * state[i + index2];
+                }
+            }
+        }
+
+        let mut sums = [BabyBear::ZERO; 4];
+        for (i, s) in new_state.iter().enumerate() {
+            sums[i % 4] += s;
+        }
+        for (i, s) in new_state.iter_mut().enumerate() {
+            *s += sums[i % 4];
+        }
+        *state = new_state;
+    }
+
+    fn int_lin_layer(&self, state: &mut [BabyBear; POSEIDON2_WIDTH]) {
+        let mut sum = BabyBear::ZERO;
+        for s in state.iter() {
+            sum += s;
+        }
+        for (s, diag_m1) in state.iter_mut().zip(&self.int_diag_m1_matrix) {
+            *s = (sum + *diag_m1 * *s) * self.reduction_factor;
+        }
+    }
+}
+
+fn sbox(x: BabyBear) -> BabyBear {
+    let x2 = x * x;
+    let x3 = x2 * x;
+    x3 * x3 * x
+}
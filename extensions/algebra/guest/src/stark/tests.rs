@@ -0,0 +1,214 @@
+use alloc::{vec, vec::Vec};
+
+use openvm_poseidon2_air::{p3_symmetric::Permutation, poseidon2::Poseidon2Config};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_challenger::{CanObserve, CanSample, CanSampleBits, FieldChallenger},
+    p3_commit::Pcs,
+    p3_field::{extension::BinomialExtensionField, AbstractExtensionField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::{default_engine, default_perm, BabyBearPoseidon2Config},
+    p3_baby_bear::BabyBear as P3BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use super::{
+    fri::{
+        verify_two_adic_pcs, BatchOpening, CommitPhaseProofStep, FriError, FriParameters, FriProof,
+        PcsMatrix, PcsRound, QueryProof,
+    },
+    DuplexChallenger, Poseidon2Params,
+};
+use crate::{field::Field, BabyBear, BabyBearExt4};
+
+type SC = BabyBearPoseidon2Config;
+type F = Val<SC>;
+type EF = BinomialExtensionField<P3BabyBear, 4>;
+type Challenger = <SC as StarkGenericConfig>::Challenger;
+type ScPcs = <SC as StarkGenericConfig>::Pcs;
+
+fn felt(x: F) -> BabyBear {
+    BabyBear::new(x.as_canonical_u32())
+}
+
+fn ext(x: EF) -> BabyBearExt4 {
+    let coeffs: &[F] = x.as_base_slice();
+    BabyBearExt4::new(core::array::from_fn(|i| coeffs[i].as_canonical_u32()))
+}
+
+fn digest(x: impl Into<[F; 8]>) -> [BabyBear; 8] {
+    x.into().map(felt)
+}
+
+/// The permutation of the default engine, which the native Poseidon2 chip also computes.
+fn poseidon2_params() -> Poseidon2Params {
+    let config = Poseidon2Config::<16, P3BabyBear>::default();
+    Poseidon2Params {
+        external_constants: config
+            .external_constants
+            .into_iter()
+            .map(|round| round.map(felt))
+            .collect(),
+        internal_constants: config.internal_constants.into_iter().map(felt).collect(),
+        ext_mds_matrix: config.ext_mds_matrix,
+        int_diag_m1_matrix: config.int_diag_m1_matrix.map(felt),
+        reduction_factor: felt(config.reduction_factor),
+    }
+}
+
+#[test]
+fn test_poseidon2_permute() {
+    let mut rng = create_seeded_rng();
+    let params = poseidon2_params();
+    let perm = default_perm();
+    for _ in 0..10 {
+        let state: [F; 16] = rng.gen();
+        assert_eq!(
+            params.permute(state.map(felt)),
+            perm.permute(state).map(felt)
+        );
+    }
+}
+
+#[test]
+fn test_duplex_challenger() {
+    let mut rng = create_seeded_rng();
+    let params = poseidon2_params();
+    let mut expected = Challenger::new(default_perm());
+    let mut challenger = DuplexChallenger::new(&params);
+    for num_observed in [0, 3, 8, 13, 1] {
+        let values: Vec<F> = (0..num_observed).map(|_| rng.gen()).collect();
+        expected.observe_slice(&values);
+        challenger.observe_slice(&values.iter().copied().map(felt).collect::<Vec<_>>());
+        assert_eq!(challenger.sample(), felt(expected.sample()));
+        assert_eq!(challenger.sample_ext(), ext(expected.sample_ext_element()));
+        assert_eq!(challenger.sample_bits(10), expected.sample_bits(10));
+    }
+}
+
+/// Commits to matrices of the given log heights, opens them at a random point and returns the
+/// claims and the proof in the form of the guest verifier.
+fn prove_pcs(log_degrees: &[usize]) -> (FriParameters, PcsRound, FriProof) {
+    let mut rng = create_seeded_rng();
+    let engine = default_engine();
+    let pcs = engine.config.pcs();
+
+    let mut domains_and_polys: Vec<_> = log_degrees
+        .iter()
+        .map(|&d| {
+            (
+                <ScPcs as Pcs<EF, Challenger>>::natural_domain_for_degree(pcs, 1 << d),
+                RowMajorMatrix::<F>::rand(&mut rng, 1 << d, 5),
+            )
+        })
+        .collect();
+    domains_and_polys.sort_by_key(|(domain, _)| core::cmp::Reverse(domain.log_n));
+    let (commit, data) = <ScPcs as Pcs<EF, Challenger>>::commit(pcs, domains_and_polys.clone());
+    let mut challenger = Challenger::new(engine.perm.clone());
+    challenger.observe(commit);
+    let zeta: EF = challenger.sample_ext_element();
+    let points = domains_and_polys.iter().map(|_| vec![zeta]).collect();
+    let (opening, proof) = pcs.open(vec![(&data, points)], &mut challenger);
+
+    let round = PcsRound {
+        commit: digest(commit),
+        mats: domains_and_polys
+            .iter()
+            .zip(&opening[0])
+            .map(|((domain, _), mat_openings)| PcsMatrix {
+                log_n: domain.log_n,
+                points: vec![(
+                    ext(zeta),
+                    mat_openings[0].iter().copied().map(ext).collect(),
+                )],
+            })
+            .collect(),
+    };
+    let proof = FriProof {
+        commit_phase_commits: proof.commit_phase_commits.into_iter().map(digest).collect(),
+        query_proofs: proof
+            .query_proofs
+            .into_iter()
+            .map(|query_proof| QueryProof {
+                input_proof: query_proof
+                    .input_proof
+                    .into_iter()
+                    .map(|batch_opening| BatchOpening {
+                        opened_values: batch_opening
+                            .opened_values
+                            .into_iter()
+                            .map(|row| row.into_iter().map(felt).collect())
+                            .collect(),
+                        opening_proof: batch_opening
+                            .opening_proof
+                            .into_iter()
+                            .map(digest)
+                            .collect(),
+                    })
+                    .collect(),
+                commit_phase_openings: query_proof
+                    .commit_phase_openings
+                    .into_iter()
+                    .map(|step| CommitPhaseProofStep {
+                        sibling_value: ext(step.sibling_value),
+                        opening_proof: step.opening_proof.into_iter().map(digest).collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        final_poly: ext(proof.final_poly),
+        pow_witness: felt(proof.pow_witness),
+    };
+    let fri_params = FriParameters {
+        log_blowup: engine.fri_params.log_blowup,
+        num_queries: engine.fri_params.num_queries,
+        proof_of_work_bits: engine.fri_params.proof_of_work_bits,
+    };
+    (fri_params, round, proof)
+}
+
+fn verify_pcs(
+    params: &Poseidon2Params,
+    fri_params: &FriParameters,
+    round: PcsRound,
+    proof: &FriProof,
+) -> Result<(), FriError> {
+    let mut challenger = DuplexChallenger::new(params);
+    challenger.observe_digest(&round.commit);
+    challenger.sample_ext();
+    verify_two_adic_pcs(params, fri_params, &[round], proof, &mut challenger)
+}
+
+#[test]
+fn test_two_adic_fri_pcs() {
+    let params = poseidon2_params();
+    for log_degrees in [&[8][..], &[9, 6, 6, 3]] {
+        let (fri_params, round, proof) = prove_pcs(log_degrees);
+        assert_eq!(verify_pcs(&params, &fri_params, round, &proof), Ok(()));
+    }
+}
+
+#[test]
+fn test_two_adic_fri_pcs_wrong_claim_fail() {
+    let params = poseidon2_params();
+    let (fri_params, mut round, proof) = prove_pcs(&[8, 5]);
+    round.mats[1].points[0].1[2] += BabyBearExt4::ONE;
+    assert_eq!(
+        verify_pcs(&params, &fri_params, round, &proof),
+        Err(FriError::FinalPolyMismatch)
+    );
+}
+
+#[test]
+fn test_two_adic_fri_pcs_wrong_opening_fail() {
+    let params = poseidon2_params();
+    let (fri_params, round, mut proof) = prove_pcs(&[8, 5]);
+    proof.query_proofs[0].input_proof[0].opened_values[0][1] += BabyBear::ONE;
+    assert_eq!(
+        verify_pcs(&params, &fri_params, round, &proof),
+        Err(FriError::InputMmcsError)
+    );
+}
@@ -14,6 +14,10 @@ use syn::{
 
 static MOD_IDX: AtomicUsize = AtomicUsize::new(0);
 
+/// Mirrors `openvm_algebra_guest::MODULAR_MULADD_MAX_MODULI`, which `moduli_init!` asserts at
+/// compile time: the modulus index must fit into the two bit `funct2` of the fused multiply-add.
+const MODULAR_MULADD_MAX_MODULI: usize = 4;
+
 /// This macro generates the code to setup the modulus for a given prime. Also it places the moduli into a special static variable to be later extracted from the ELF and used by the VM.
 /// Usage:
 /// ```
@@ -98,6 +102,11 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
         create_extern_func!(mul_extern_func);
         create_extern_func!(div_extern_func);
         create_extern_func!(is_eq_extern_func);
+        create_extern_func!(add_assign_extern_func);
+        create_extern_func!(sub_assign_extern_func);
+        create_extern_func!(mul_assign_extern_func);
+        create_extern_func!(div_assign_extern_func);
+        create_extern_func!(mul_add_extern_func);
 
         let block_size = proc_macro::Literal::usize_unsuffixed(block_size);
         let block_size = syn::Lit::new(block_size.to_string().parse::<_>().unwrap());
@@ -115,6 +124,12 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                 fn #mul_extern_func(rd: usize, rs1: usize, rs2: usize);
                 fn #div_extern_func(rd: usize, rs1: usize, rs2: usize);
                 fn #is_eq_extern_func(rs1: usize, rs2: usize) -> bool;
+                // In place: `rd` and `rs1` are the same register.
+                fn #add_assign_extern_func(rd: usize, rs2: usize);
+                fn #sub_assign_extern_func(rd: usize, rs2: usize);
+                fn #mul_assign_extern_func(rd: usize, rs2: usize);
+                fn #div_assign_extern_func(rd: usize, rs2: usize);
+                fn #mul_add_extern_func(rd: usize, rs1: usize, rs2: usize, rs3: usize);
             }
 
             impl #struct_name {
@@ -140,9 +155,8 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                     #[cfg(target_os = "zkvm")]
                     {
                         unsafe {
-                            #add_extern_func(
+                            #add_assign_extern_func(
                                 self as *mut Self as usize,
-                                self as *const Self as usize,
                                 other as *const Self as usize,
                             );
                         }
@@ -161,9 +175,8 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                     #[cfg(target_os = "zkvm")]
                     {
                         unsafe {
-                            #sub_extern_func(
+                            #sub_assign_extern_func(
                                 self as *mut Self as usize,
-                                self as *const Self as usize,
                                 other as *const Self as usize,
                             );
                         }
@@ -181,9 +194,8 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                     #[cfg(target_os = "zkvm")]
                     {
                         unsafe {
-                            #mul_extern_func(
+                            #mul_assign_extern_func(
                                 self as *mut Self as usize,
-                                self as *const Self as usize,
                                 other as *const Self as usize,
                             );
                         }
@@ -201,9 +213,8 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                     #[cfg(target_os = "zkvm")]
                     {
                         unsafe {
-                            #div_extern_func(
+                            #div_assign_extern_func(
                                 self as *mut Self as usize,
-                                self as *const Self as usize,
                                 other as *const Self as usize,
                             );
                         }
@@ -304,6 +315,32 @@ pub fn moduli_declare(input: TokenStream) -> TokenStream {
                     }
                 }
 
+                /// Returns `self * other + addend`. For the first moduli this is a single
+                /// `MULADD` instruction, otherwise a multiplication followed by an addition.
+                #[inline(always)]
+                pub fn mul_add(&self, other: &Self, addend: &Self) -> Self {
+                    #[cfg(not(target_os = "zkvm"))]
+                    {
+                        Self::from_biguint(
+                            (self.as_biguint() * other.as_biguint() + addend.as_biguint())
+                                % Self::modulus_biguint(),
+                        )
+                    }
+                    #[cfg(target_os = "zkvm")]
+                    {
+                        let mut uninit: core::mem::MaybeUninit<#struct_name> = core::mem::MaybeUninit::uninit();
+                        unsafe {
+                            #mul_add_extern_func(
+                                uninit.as_mut_ptr() as usize,
+                                self as *const #struct_name as usize,
+                                other as *const #struct_name as usize,
+                                addend as *const #struct_name as usize,
+                            );
+                        }
+                        unsafe { uninit.assume_init() }
+                    }
+                }
+
                 #[inline(always)]
                 fn eq_impl(&self, other: &Self) -> bool {
                     #[cfg(not(target_os = "zkvm"))]
@@ -788,6 +825,27 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
                     )
                 }
             });
+
+            let assign_func_name = syn::Ident::new(
+                &format!("{}_assign_extern_func_{}", op_type, modulus_hex),
+                span.into(),
+            );
+            externs.push(quote::quote_spanned! { span.into() =>
+                #[no_mangle]
+                extern "C" fn #assign_func_name(rd: usize, rs2: usize) {
+                    // rd is also rs1, so that the chip executes the operation in place
+                    unsafe {
+                        core::arch::asm!(
+                            ".insn r {opcode}, {funct3}, {funct7}, {rd}, {rd}, {rs2}",
+                            opcode = const ::openvm_algebra_guest::OPCODE,
+                            funct3 = const ::openvm_algebra_guest::MODULAR_ARITHMETIC_FUNCT3 as usize,
+                            funct7 = const ::openvm_algebra_guest::ModArithBaseFunct7::#local_opcode as usize + #mod_idx * (::openvm_algebra_guest::ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize),
+                            rd = in(reg) rd,
+                            rs2 = in(reg) rs2
+                        );
+                    }
+                }
+            });
         }
 
         let is_eq_extern_func =
@@ -811,6 +869,64 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
             }
         });
 
+        let mul_add_extern_func =
+            syn::Ident::new(&format!("mul_add_extern_func_{}", modulus_hex), span.into());
+        if mod_idx < MODULAR_MULADD_MAX_MODULI {
+            externs.push(quote::quote_spanned! { span.into() =>
+                #[no_mangle]
+                extern "C" fn #mul_add_extern_func(rd: usize, rs1: usize, rs2: usize, rs3: usize) {
+                    // funct2 holds the modulus index
+                    openvm_platform::custom_insn_r4!(
+                        ::openvm_algebra_guest::OPCODE,
+                        ::openvm_algebra_guest::MODULAR_MULADD_FUNCT3 as usize,
+                        #mod_idx,
+                        rd,
+                        rs1,
+                        rs2,
+                        rs3
+                    )
+                }
+            });
+        } else {
+            let mul_extern_func =
+                syn::Ident::new(&format!("mul_extern_func_{}", modulus_hex), span.into());
+            let add_extern_func =
+                syn::Ident::new(&format!("add_extern_func_{}", modulus_hex), span.into());
+            let block_size = proc_macro::Literal::usize_unsuffixed(block_size);
+            let block_size = syn::Lit::new(block_size.to_string().parse::<_>().unwrap());
+            externs.push(quote::quote_spanned! { span.into() =>
+                #[no_mangle]
+                extern "C" fn #mul_add_extern_func(rd: usize, rs1: usize, rs2: usize, rs3: usize) {
+                    // The modulus index does not fit into funct2, so there is no fused instruction.
+                    // The product goes through a temporary because `rd` may be the same as `rs3`.
+                    #[repr(C, align(#block_size))]
+                    struct Product([u8; #limbs]);
+                    let mut product: core::mem::MaybeUninit<Product> = core::mem::MaybeUninit::uninit();
+                    let product_ptr = product.as_mut_ptr() as usize;
+                    #mul_extern_func(product_ptr, rs1, rs2);
+                    #add_extern_func(rd, product_ptr, rs3);
+                }
+            });
+        }
+
+        // Only the moduli with a fused multiply-add chip need its setup.
+        let setup_muladd = if mod_idx < MODULAR_MULADD_MAX_MODULI {
+            quote::quote_spanned! { span.into() =>
+                openvm_platform::custom_insn_r!(
+                    ::openvm_algebra_guest::OPCODE,
+                    ::openvm_algebra_guest::MODULAR_ARITHMETIC_FUNCT3,
+                    ::openvm_algebra_guest::ModArithBaseFunct7::SetupMod as usize
+                        + #mod_idx
+                            * (::openvm_algebra_guest::ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize),
+                    uninit.as_mut_ptr(),
+                    remaining.as_ptr(),
+                    "x3" // will be parsed as 3 and therefore transpiled to SETUP_MULADD
+                );
+            }
+        } else {
+            quote::quote_spanned! { span.into() => }
+        };
+
         setup_all_moduli.push(quote::quote_spanned! { span.into() =>
             #setup_function();
         });
@@ -830,7 +946,7 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
                     let remaining = &#serialized_name[ptr..];
 
                     // We are going to use the numeric representation of the `rs2` register to distinguish the chip to setup.
-                    // The transpiler will transform this instruction, based on whether `rs2` is `x0`, `x1` or `x2`, into a `SETUP_ADDSUB`, `SETUP_MULDIV` or `SETUP_ISEQ` instruction.
+                    // The transpiler will transform this instruction, based on whether `rs2` is `x0`, `x1`, `x2` or `x3`, into a `SETUP_ADDSUB`, `SETUP_MULDIV`, `SETUP_ISEQ` or `SETUP_MULADD` instruction.
                     let mut uninit: core::mem::MaybeUninit<[u8; #limbs]> = core::mem::MaybeUninit::uninit();
                     openvm_platform::custom_insn_r!(
                         ::openvm_algebra_guest::OPCODE,
@@ -866,15 +982,18 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
                         );
                         // rd = inout(reg) is necessary because this instruction will write to `rd` register
                     }
+                    #setup_muladd
                 }
             }
         });
     }
 
+    let muladd_max_moduli = MODULAR_MULADD_MAX_MODULI;
     let total_limbs_cnt = two_modular_limbs_flattened_list.len();
     let cnt_limbs_list_len = limb_list_borders.len();
     TokenStream::from(quote::quote_spanned! { span.into() =>
         #(#openvm_section)*
+        const _: () = assert!(::openvm_algebra_guest::MODULAR_MULADD_MAX_MODULI == #muladd_max_moduli);
         #[cfg(target_os = "zkvm")]
         mod openvm_intrinsics_ffi {
             #(#externs)*
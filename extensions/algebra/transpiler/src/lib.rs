@@ -1,13 +1,17 @@
 use openvm_algebra_guest::{
-    ComplexExtFieldBaseFunct7, ModArithBaseFunct7, COMPLEX_EXT_FIELD_FUNCT3,
-    MODULAR_ARITHMETIC_FUNCT3, OPCODE,
+    BabyBearBaseFunct7, BabyBearExt4BaseFunct7, ComplexExtFieldBaseFunct7, ModArithBaseFunct7,
+    BABYBEAR_EXT4_FUNCT3, BABYBEAR_FUNCT3, COMPLEX_EXT_FIELD_FUNCT3, MODULAR_ARITHMETIC_FUNCT3,
+    MODULAR_MULADD_FUNCT3, OPCODE,
 };
 use openvm_instructions::{
     instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode, VmOpcode,
 };
 use openvm_instructions_derive::UsizeOpcode;
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension};
+use openvm_transpiler::{
+    util::{from_r4_type, from_r_type, R4Type},
+    TranspilerExtension,
+};
 use rrs_lib::instruction_formats::RType;
 use strum::{EnumCount, EnumIter, FromRepr};
 
@@ -26,6 +30,8 @@ pub enum Rv32ModularArithmeticOpcode {
     SETUP_MULDIV,
     IS_EQ,
     SETUP_ISEQ,
+    MULADD,
+    SETUP_MULADD,
 }
 
 #[derive(
@@ -43,12 +49,50 @@ pub enum Fp2Opcode {
     SETUP_MULDIV,
 }
 
+/// Degree-4 binomial extension of BabyBear, `F_p[t]/(t^4 - 11)`. Operands are four canonical
+/// BabyBear elements, each stored as 4 little-endian bytes.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x800]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum BabyBearExt4Opcode {
+    ADD,
+    SUB,
+    SETUP_ADDSUB,
+    MUL,
+    DIV,
+    SETUP_MULDIV,
+}
+
+/// BabyBear field arithmetic. Operands and results are BabyBear elements stored as 4 little-endian
+/// bytes; results are canonical. Inversion is `DIV` with numerator one.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x810]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum BabyBearOpcode {
+    ADD,
+    SUB,
+    MUL,
+    DIV,
+}
+
 #[derive(Default)]
 pub struct ModularTranspilerExtension;
 
 #[derive(Default)]
 pub struct Fp2TranspilerExtension;
 
+#[derive(Default)]
+pub struct BabyBearExt4TranspilerExtension;
+
+#[derive(Default)]
+pub struct BabyBearTranspilerExtension;
+
 impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
         if instruction_stream.is_empty() {
@@ -61,6 +105,12 @@ impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
         if opcode != OPCODE {
             return None;
         }
+        if funct3 == MODULAR_MULADD_FUNCT3 {
+            let dec_insn = R4Type::new(instruction_u32);
+            let mod_idx_shift = dec_insn.funct2 as usize * Rv32ModularArithmeticOpcode::COUNT;
+            let global_opcode = Rv32ModularArithmeticOpcode::MULADD.with_default_offset();
+            return Some((from_r4_type(global_opcode + mod_idx_shift, 2, &dec_insn), 1));
+        }
         if funct3 != MODULAR_ARITHMETIC_FUNCT3 {
             return None;
         }
@@ -70,8 +120,8 @@ impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
             let base_funct7 =
                 (dec_insn.funct7 as u8) % ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS;
             assert!(
-                Rv32ModularArithmeticOpcode::COUNT
-                    <= ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS as usize
+                (ModArithBaseFunct7::SetupMod as u8)
+                    < ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS
             );
             let mod_idx_shift = ((dec_insn.funct7 as u8)
                 / ModArithBaseFunct7::MODULAR_ARITHMETIC_MAX_KINDS)
@@ -82,6 +132,7 @@ impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
                     0 => Rv32ModularArithmeticOpcode::SETUP_ADDSUB,
                     1 => Rv32ModularArithmeticOpcode::SETUP_MULDIV,
                     2 => Rv32ModularArithmeticOpcode::SETUP_ISEQ,
+                    3 => Rv32ModularArithmeticOpcode::SETUP_MULADD,
                     _ => panic!("invalid opcode"),
                 };
                 Some(Instruction::new(
@@ -193,3 +244,77 @@ impl<F: PrimeField32> TranspilerExtension<F> for Fp2TranspilerExtension {
         instruction.map(|instruction| (instruction, 1))
     }
 }
+
+impl<F: PrimeField32> TranspilerExtension<F> for BabyBearExt4TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if opcode != OPCODE || funct3 != BABYBEAR_EXT4_FUNCT3 {
+            return None;
+        }
+
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match BabyBearExt4BaseFunct7::from_repr(dec_insn.funct7 as u8)? {
+            BabyBearExt4BaseFunct7::Add => BabyBearExt4Opcode::ADD,
+            BabyBearExt4BaseFunct7::Sub => BabyBearExt4Opcode::SUB,
+            BabyBearExt4BaseFunct7::Mul => BabyBearExt4Opcode::MUL,
+            BabyBearExt4BaseFunct7::Div => BabyBearExt4Opcode::DIV,
+            BabyBearExt4BaseFunct7::Setup => {
+                let local_opcode = match dec_insn.rs2 {
+                    0 => BabyBearExt4Opcode::SETUP_ADDSUB,
+                    1 => BabyBearExt4Opcode::SETUP_MULDIV,
+                    _ => return None,
+                };
+                return Some((
+                    Instruction::new(
+                        VmOpcode::with_default_offset(local_opcode),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        F::ZERO, // rs2 = 0
+                        F::ONE,  // d_as = 1
+                        F::TWO,  // e_as = 2
+                        F::ZERO,
+                        F::ZERO,
+                    ),
+                    1,
+                ));
+            }
+        };
+        Some((
+            from_r_type(local_opcode.with_default_offset(), 2, &dec_insn),
+            1,
+        ))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for BabyBearTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if opcode != OPCODE || funct3 != BABYBEAR_FUNCT3 {
+            return None;
+        }
+
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match BabyBearBaseFunct7::from_repr(dec_insn.funct7 as u8)? {
+            BabyBearBaseFunct7::Add => BabyBearOpcode::ADD,
+            BabyBearBaseFunct7::Sub => BabyBearOpcode::SUB,
+            BabyBearBaseFunct7::Mul => BabyBearOpcode::MUL,
+            BabyBearBaseFunct7::Div => BabyBearOpcode::DIV,
+        };
+        Some((
+            from_r_type(local_opcode.with_default_offset(), 2, &dec_insn),
+            1,
+        ))
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use derive_more::derive::From;
 use openvm_bigint_transpiler::{
     Rv32BaseAlu256Opcode, Rv32BranchEqual256Opcode, Rv32BranchLessThan256Opcode,
-    Rv32LessThan256Opcode, Rv32Mul256Opcode, Rv32Shift256Opcode,
+    Rv32LessThan256Opcode, Rv32ModReduce256Opcode, Rv32Mul256Opcode, Rv32Shift256Opcode,
 };
 use openvm_circuit::{
     arch::{
@@ -14,7 +14,7 @@ use openvm_circuit::{
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
 use openvm_circuit_primitives::{
-    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    bitwise_op_lookup::BitwiseOperationLookupChip,
     range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
@@ -25,6 +25,7 @@ use openvm_rv32im_circuit::{
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 
 use crate::*;
 
@@ -80,12 +81,13 @@ pub enum Int256Executor<F: PrimeField32> {
     BranchLessThan256(Rv32BranchLessThan256Chip<F>),
     Multiplication256(Rv32Multiplication256Chip<F>),
     Shift256(Rv32Shift256Chip<F>),
+    ModReduce256(Rv32ModReduce256Chip<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Int256Periphery<F: PrimeField32> {
     BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
-    /// Only needed for multiplication extension
+    /// Only needed for multiplication and modular reduction
     RangeTupleChecker(Arc<RangeTupleCheckerChip<2>>),
     Phantom(PhantomChip<F>),
 }
@@ -105,17 +107,7 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
             memory_controller,
         } = builder.system_port();
         let range_checker_chip = builder.system_base().range_checker_chip.clone();
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
-        {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
 
         let range_tuple_chip = if let Some(chip) = builder
             .find_chip::<Arc<RangeTupleCheckerChip<2>>>()
@@ -126,8 +118,10 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
             }) {
             chip.clone()
         } else {
-            let range_tuple_bus =
-                RangeTupleCheckerBus::new(builder.new_bus_idx(), self.range_tuple_checker_sizes);
+            let range_tuple_bus = RangeTupleCheckerBus::new(
+                builder.new_lookup_bus_idx(),
+                self.range_tuple_checker_sizes,
+            );
             let chip = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
             inventory.add_periphery_chip(chip.clone());
             chip
@@ -209,7 +203,10 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
                 memory_controller.clone(),
                 bitwise_lu_chip.clone(),
             ),
-            MultiplicationCoreChip::new(range_tuple_chip, Rv32Mul256Opcode::default_offset()),
+            MultiplicationCoreChip::new(
+                range_tuple_chip.clone(),
+                Rv32Mul256Opcode::default_offset(),
+            ),
             memory_controller.clone(),
         );
         inventory.add_executor(
@@ -217,6 +214,25 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
             Rv32Mul256Opcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        let mod_reduce_chip = Rv32ModReduce256Chip::new(
+            Rv32VecHeapTwoReadsAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            ModReduceCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_chip.clone(),
+                Rv32ModReduce256Opcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            mod_reduce_chip,
+            Rv32ModReduce256Opcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
         let shift_chip = Rv32Shift256Chip::new(
             Rv32HeapAdapterChip::new(
                 execution_bus,
@@ -1,5 +1,7 @@
 use openvm_circuit::{self, arch::VmChipWrapper};
-use openvm_rv32_adapters::{Rv32HeapAdapterChip, Rv32HeapBranchAdapterChip};
+use openvm_rv32_adapters::{
+    Rv32HeapAdapterChip, Rv32HeapBranchAdapterChip, Rv32VecHeapTwoReadsAdapterChip,
+};
 use openvm_rv32im_circuit::{
     adapters::{INT256_NUM_LIMBS, RV32_CELL_BITS},
     BaseAluCoreChip, BranchEqualCoreChip, BranchLessThanCoreChip, LessThanCoreChip,
@@ -8,6 +10,8 @@ use openvm_rv32im_circuit::{
 
 mod extension;
 pub use extension::*;
+mod mod_reduce;
+pub use mod_reduce::*;
 
 #[cfg(test)]
 mod tests;
@@ -47,3 +51,10 @@ pub type Rv32BranchLessThan256Chip<F> = VmChipWrapper<
     Rv32HeapBranchAdapterChip<F, 2, INT256_NUM_LIMBS>,
     BranchLessThanCoreChip<INT256_NUM_LIMBS, RV32_CELL_BITS>,
 >;
+
+/// Reduces a 512-bit value, read as two blocks, modulo a 256-bit modulus.
+pub type Rv32ModReduce256Chip<F> = VmChipWrapper<
+    F,
+    Rv32VecHeapTwoReadsAdapterChip<F, 2, 1, 1, INT256_NUM_LIMBS, INT256_NUM_LIMBS>,
+    ModReduceCoreChip<INT256_NUM_LIMBS, RV32_CELL_BITS>,
+>;
@@ -0,0 +1,323 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use num_bigint::BigUint;
+use openvm_bigint_transpiler::Rv32ModReduce256Opcode;
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct ModReduceCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    // a = q * n + r for some 0 <= r < n, where a and q have 2 * NUM_LIMBS limbs, low half first.
+    pub a: [[T; NUM_LIMBS]; 2],
+    pub n: [T; NUM_LIMBS],
+    pub q: [[T; NUM_LIMBS]; 2],
+    pub r: [T; NUM_LIMBS],
+
+    // Auxiliary columns to constrain that r < n. lt_marker marks the most significant limb where
+    // r and n differ, and lt_diff = n[i] - r[i] at that limb.
+    pub lt_marker: [T; NUM_LIMBS],
+    pub lt_diff: T,
+
+    pub is_valid: T,
+}
+
+/// Reduces a `2 * NUM_LIMBS` limb value modulo a `NUM_LIMBS` limb modulus which is read at
+/// runtime. The quotient is a hint, and the chip checks `a = q * n + r` with carries together
+/// with `r < n`. The modulus must be non-zero.
+#[derive(Copy, Clone, Debug)]
+pub struct ModReduceCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_tuple_bus: RangeTupleCheckerBus<2>,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for ModReduceCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        ModReduceCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for ModReduceCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for ModReduceCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<([[AB::Expr; NUM_LIMBS]; 2], [[AB::Expr; NUM_LIMBS]; 1])>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ModReduceCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        builder.assert_bool(cols.is_valid);
+
+        let a = cols.a.concat();
+        let q = cols.q.concat();
+        let n = &cols.n;
+        let r = &cols.r;
+
+        // Constrain that q * n + r - a = 0 limb by limb. There is one carry for each of the
+        // 3 * NUM_LIMBS limbs of q and r, which are range checked in pairs, and the last carry
+        // must be zero.
+        let num_carries = 3 * NUM_LIMBS;
+        let carry_divide = AB::F::from_canonical_u32(1 << LIMB_BITS).inverse();
+        let mut carry: Vec<AB::Expr> = Vec::with_capacity(num_carries);
+        for i in 0..num_carries {
+            let mut expected_limb = if i == 0 {
+                AB::Expr::ZERO
+            } else {
+                carry[i - 1].clone()
+            };
+            for k in i.saturating_sub(2 * NUM_LIMBS - 1)..=i.min(NUM_LIMBS - 1) {
+                expected_limb += n[k] * q[i - k];
+            }
+            if i < NUM_LIMBS {
+                expected_limb += AB::Expr::from(r[i]);
+            }
+            if i < 2 * NUM_LIMBS {
+                expected_limb -= AB::Expr::from(a[i]);
+            }
+            carry.push(expected_limb * carry_divide);
+        }
+        builder.assert_zero(carry[num_carries - 1].clone());
+
+        for (limb, carry) in q.iter().chain(r.iter()).zip(carry.iter()) {
+            self.range_tuple_bus
+                .send(vec![(*limb).into(), carry.clone()])
+                .eval(builder, cols.is_valid);
+        }
+
+        // Constrain that r < n.
+        let marker = &cols.lt_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+        for i in (0..NUM_LIMBS).rev() {
+            let diff = n[i] - r[i];
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.lt_diff, diff);
+        }
+        builder.when(cols.is_valid).assert_one(prefix_sum);
+        self.bitwise_lookup_bus
+            .send_range(cols.lt_diff - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, cols.is_valid);
+
+        let expected_opcode = AB::Expr::from_canonical_usize(
+            Rv32ModReduce256Opcode::MOD_REDUCE as usize + self.offset,
+        );
+
+        let reads: ([[AB::Expr; NUM_LIMBS]; 2], [[AB::Expr; NUM_LIMBS]; 1]) =
+            (cols.a.map(|x| x.map(Into::into)), [cols.n.map(Into::into)]);
+        AdapterAirContext {
+            to_pc: None,
+            reads: reads.into(),
+            writes: [cols.r.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ModReduceCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: ModReduceCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+    pub range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> ModReduceCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+        offset: usize,
+    ) -> Self {
+        // The RangeTupleChecker is used to range check (q[i], carry[i]) and (r[i], carry[i])
+        // pairs. Each limb of q * n is the sum of at most NUM_LIMBS products of two limbs, so
+        // the carries are less than NUM_LIMBS * 2^LIMB_BITS. BitwiseOperationLookup is used to
+        // range check lt_diff.
+        debug_assert!(
+            range_tuple_chip.sizes()[0] == 1 << LIMB_BITS,
+            "First element of RangeTupleChecker must have size {}",
+            1 << LIMB_BITS
+        );
+        debug_assert!(
+            range_tuple_chip.sizes()[1] >= (1 << LIMB_BITS) * NUM_LIMBS as u32,
+            "Second element of RangeTupleChecker must have size of at least {}",
+            (1 << LIMB_BITS) * NUM_LIMBS as u32
+        );
+
+        Self {
+            air: ModReduceCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_tuple_bus: *range_tuple_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_tuple_chip,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModReduceCoreRecord<T, const NUM_LIMBS: usize> {
+    pub a: [[T; NUM_LIMBS]; 2],
+    pub n: [T; NUM_LIMBS],
+    pub q: [[T; NUM_LIMBS]; 2],
+    pub r: [T; NUM_LIMBS],
+    pub lt_diff_val: T,
+    pub lt_diff_idx: usize,
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for ModReduceCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<([[F; NUM_LIMBS]; 2], [[F; NUM_LIMBS]; 1])>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = ModReduceCoreRecord<F, NUM_LIMBS>;
+    type Air = ModReduceCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        _instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let (a_data, [n_data]) = reads.into();
+        let a = a_data.map(|x| x.map(|y| y.as_canonical_u32()));
+        let n = n_data.map(|x| x.as_canonical_u32());
+        if n.iter().all(|&x| x == 0) {
+            return Err(ExecutionError::Fail { pc: from_pc });
+        }
+
+        let (q, r, carries) = run_mod_reduce::<NUM_LIMBS, LIMB_BITS>(&a, &n);
+        for (&limb, &carry) in q.iter().flatten().chain(r.iter()).zip(carries.iter()) {
+            self.range_tuple_chip.add_count(&[limb, carry]);
+        }
+
+        let lt_diff_idx = (0..NUM_LIMBS).rev().find(|&i| n[i] != r[i]).unwrap();
+        let lt_diff_val = n[lt_diff_idx] - r[lt_diff_idx];
+        self.bitwise_lookup_chip.request_range(lt_diff_val - 1, 0);
+
+        let output = AdapterRuntimeContext::without_pc([r.map(F::from_canonical_u32)]);
+        let record = ModReduceCoreRecord {
+            a: a_data,
+            n: n_data,
+            q: q.map(|x| x.map(F::from_canonical_u32)),
+            r: r.map(F::from_canonical_u32),
+            lt_diff_val: F::from_canonical_u32(lt_diff_val),
+            lt_diff_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32ModReduce256Opcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ModReduceCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.n = record.n;
+        row_slice.q = record.q;
+        row_slice.r = record.r;
+        row_slice.lt_marker = array::from_fn(|i| F::from_bool(i == record.lt_diff_idx));
+        row_slice.lt_diff = record.lt_diff_val;
+        row_slice.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (q, r, carries) such that a = q * n + r with r < n. The carries are those of
+// q * n + r - a, limb by limb. n must be non-zero.
+#[allow(clippy::type_complexity)]
+pub(super) fn run_mod_reduce<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    a: &[[u32; NUM_LIMBS]; 2],
+    n: &[u32; NUM_LIMBS],
+) -> ([[u32; NUM_LIMBS]; 2], [u32; NUM_LIMBS], Vec<u32>) {
+    let a_big = limbs_to_biguint::<LIMB_BITS>(&a.concat());
+    let n_big = limbs_to_biguint::<LIMB_BITS>(n);
+    let q_limbs = biguint_to_limbs::<LIMB_BITS>(&(&a_big / &n_big), 2 * NUM_LIMBS);
+    let r_limbs = biguint_to_limbs::<LIMB_BITS>(&(&a_big % &n_big), NUM_LIMBS);
+    let q = array::from_fn(|i| array::from_fn(|j| q_limbs[i * NUM_LIMBS + j]));
+    let r = array::from_fn(|i| r_limbs[i]);
+
+    let a = a.concat();
+    let num_carries = 3 * NUM_LIMBS;
+    let mut carries = Vec::with_capacity(num_carries);
+    let mut carry = 0u64;
+    for i in 0..num_carries {
+        let mut limb = carry;
+        for k in i.saturating_sub(2 * NUM_LIMBS - 1)..=i.min(NUM_LIMBS - 1) {
+            limb += n[k] as u64 * q_limbs[i - k] as u64;
+        }
+        if i < NUM_LIMBS {
+            limb += r_limbs[i] as u64;
+        }
+        if i < 2 * NUM_LIMBS {
+            // The low limbs of q * n + r equal those of a, so this never underflows.
+            limb -= a[i] as u64;
+        }
+        carry = limb >> LIMB_BITS;
+        carries.push(carry as u32);
+    }
+    debug_assert_eq!(carry, 0);
+    (q, r, carries)
+}
+
+fn limbs_to_biguint<const LIMB_BITS: usize>(x: &[u32]) -> BigUint {
+    x.iter()
+        .rev()
+        .fold(BigUint::default(), |acc, &limb| (acc << LIMB_BITS) + limb)
+}
+
+fn biguint_to_limbs<const LIMB_BITS: usize>(x: &BigUint, num_limbs: usize) -> Vec<u32> {
+    let mask = BigUint::from((1u32 << LIMB_BITS) - 1);
+    (0..num_limbs)
+        .map(|i| {
+            let limb = (x >> (i * LIMB_BITS)) & &mask;
+            limb.iter_u32_digits().next().unwrap_or(0)
+        })
+        .collect()
+}
@@ -1,9 +1,12 @@
-use std::sync::Arc;
+use std::{array, borrow::Borrow, sync::Arc};
 
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use openvm_bigint_transpiler::Rv32ModReduce256Opcode;
 use openvm_circuit::{
     arch::{
-        testing::VmChipTestBuilder, InstructionExecutor, BITWISE_OP_LOOKUP_BUS,
-        RANGE_TUPLE_CHECKER_BUS,
+        testing::{Tamper, VmChipTestBuilder},
+        InstructionExecutor, VmAdapterChip, BITWISE_OP_LOOKUP_BUS, RANGE_TUPLE_CHECKER_BUS,
     },
     utils::generate_long_number,
 };
@@ -13,8 +16,8 @@ use openvm_circuit_primitives::{
 };
 use openvm_instructions::{program::PC_BITS, riscv::RV32_CELL_BITS, UsizeOpcode};
 use openvm_rv32_adapters::{
-    rv32_heap_branch_default, rv32_write_heap_default, Rv32HeapAdapterChip,
-    Rv32HeapBranchAdapterChip,
+    rv32_heap_branch_default, rv32_read_heap_default, rv32_write_heap_default, Rv32HeapAdapterChip,
+    Rv32HeapBranchAdapterChip, Rv32VecHeapTwoReadsAdapterChip,
 };
 use openvm_rv32im_circuit::{
     adapters::{INT256_NUM_LIMBS, RV_B_TYPE_IMM_BITS},
@@ -24,12 +27,19 @@ use openvm_rv32im_circuit::{
 use openvm_rv32im_transpiler::{
     BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, LessThanOpcode, MulOpcode, ShiftOpcode,
 };
-use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use proptest::{prelude::*, sample::select};
 use rand::Rng;
 
 use super::{
-    Rv32BaseAlu256Chip, Rv32BranchEqual256Chip, Rv32BranchLessThan256Chip, Rv32LessThan256Chip,
+    ModReduceCoreChip, ModReduceCoreCols, Rv32BaseAlu256Chip, Rv32BranchEqual256Chip,
+    Rv32BranchLessThan256Chip, Rv32LessThan256Chip, Rv32ModReduce256Chip,
     Rv32Multiplication256Chip, Rv32Shift256Chip,
 };
 
@@ -212,6 +222,116 @@ fn mul_256_rand_test() {
     run_mul_256_rand_test(24);
 }
 
+/// Executes `num_ops` random MOD_REDUCE instructions, half of them with small moduli.
+#[allow(clippy::type_complexity)]
+fn execute_mod_reduce_256_rand(
+    num_ops: usize,
+) -> (
+    VmChipTestBuilder<F>,
+    Rv32ModReduce256Chip<F>,
+    Arc<RangeTupleCheckerChip<2>>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) {
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [
+            1 << RV32_CELL_BITS,
+            (INT256_NUM_LIMBS * (1 << RV32_CELL_BITS)) as u32,
+        ],
+    );
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32ModReduce256Chip::<F>::new(
+        Rv32VecHeapTwoReadsAdapterChip::<F, 2, 1, 1, INT256_NUM_LIMBS, INT256_NUM_LIMBS>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        ModReduceCoreChip::new(bitwise_chip.clone(), range_tuple_checker.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    let mut rng = create_seeded_rng();
+    for i in 0..num_ops {
+        let a_lo = generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let a_hi = generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let mut n = generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        // Also cover small moduli, which have large quotients.
+        if i % 2 == 1 {
+            let len = rng.gen_range(1..INT256_NUM_LIMBS);
+            n[len..].fill(0);
+        }
+        if n.iter().all(|&x| x == 0) {
+            n[0] = 1;
+        }
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![
+                a_lo.map(F::from_canonical_u32),
+                a_hi.map(F::from_canonical_u32),
+            ],
+            vec![n.map(F::from_canonical_u32)],
+            Rv32ModReduce256Opcode::MOD_REDUCE as usize,
+        );
+        tester.execute(&mut chip, instruction);
+    }
+    (tester, chip, range_tuple_checker, bitwise_chip)
+}
+
+#[test]
+fn mod_reduce_256_rand_test() {
+    let (tester, chip, range_tuple_checker, bitwise_chip) = execute_mod_reduce_256_rand(24);
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(range_tuple_checker)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Tampers with the first MOD_REDUCE row and asserts that it is rejected.
+fn run_mod_reduce_256_negative_test(
+    tamper: impl Fn(&ModReduceCoreCols<usize, INT256_NUM_LIMBS, RV32_CELL_BITS>, usize) -> Tamper<F>,
+) {
+    let (tester, chip, range_tuple_checker, bitwise_chip) = execute_mod_reduce_256_rand(1);
+    let indices =
+        (0..ModReduceCoreCols::<F, INT256_NUM_LIMBS, RV32_CELL_BITS>::width()).collect::<Vec<_>>();
+    let cols: &ModReduceCoreCols<usize, INT256_NUM_LIMBS, RV32_CELL_BITS> = indices[..].borrow();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let tamper = tamper(cols, adapter_width);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(range_tuple_checker)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn mod_reduce_256_wrong_quotient_negative_test() {
+    // q * n + r no longer equals a, so the last carry of the product check is not zero.
+    run_mod_reduce_256_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(0, adapter_width + cols.q[0][0])
+    });
+}
+
+#[test]
+fn mod_reduce_256_wrong_lt_diff_negative_test() {
+    // lt_diff must equal n - r at the marked limb.
+    run_mod_reduce_256_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(0, adapter_width + cols.lt_diff)
+    });
+}
+
 fn run_shift_256_rand_test(opcode: ShiftOpcode, num_ops: usize) {
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -365,3 +485,162 @@ fn blt_256_bge_rand_test() {
 fn blt_256_bgeu_rand_test() {
     run_blt_256_rand_test(BranchLessThanOpcode::BGEU, 24);
 }
+
+fn u256_to_limbs(x: &BigUint) -> [F; INT256_NUM_LIMBS] {
+    let bytes = x.to_bytes_le();
+    assert!(bytes.len() <= INT256_NUM_LIMBS);
+    array::from_fn(|i| F::from_canonical_u8(bytes.get(i).copied().unwrap_or(0)))
+}
+
+/// A proptest strategy for 256-bit values: one of `edges` or 0, 1 and 2^256 - 1 half of the time,
+/// a random value otherwise.
+fn u256_strategy(edges: Vec<BigUint>) -> BoxedStrategy<BigUint> {
+    let max = (BigUint::one() << 256usize) - 1u32;
+    let edges = [vec![BigUint::zero(), BigUint::one(), max], edges].concat();
+    prop_oneof![
+        select(edges),
+        any::<[u8; INT256_NUM_LIMBS]>().prop_map(|bytes| BigUint::from_bytes_le(&bytes)),
+    ]
+    .boxed()
+}
+
+/// Runs `ops` of (opcode, b, c) through a 256-bit ALU chip and checks the outputs against the
+/// BigUint reference, wrapping at 2^256, before proving.
+fn run_alu_256_prop_test(ops: Vec<(BaseAluOpcode, BigUint, BigUint)>) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32BaseAlu256Chip::<F>::new(
+        Rv32HeapAdapterChip::<F, 2, INT256_NUM_LIMBS, INT256_NUM_LIMBS>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        BaseAluCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    let modulus = BigUint::one() << 256usize;
+    for (opcode, b, c) in ops {
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![u256_to_limbs(&b)],
+            vec![u256_to_limbs(&c)],
+            opcode as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+        let expected = match opcode {
+            BaseAluOpcode::ADD => (&b + &c) % &modulus,
+            BaseAluOpcode::SUB => (&b + &modulus - &c) % &modulus,
+            BaseAluOpcode::XOR => &b ^ &c,
+            BaseAluOpcode::OR => &b | &c,
+            BaseAluOpcode::AND => &b & &c,
+        };
+        let output = rv32_read_heap_default::<INT256_NUM_LIMBS>(&mut tester, &instruction, 1);
+        assert_eq!(
+            output,
+            vec![u256_to_limbs(&expected)],
+            "{opcode:?} on {b} and {c}"
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Runs `ops` of (a, n) through a MOD_REDUCE chip and checks the outputs against the BigUint
+/// reference before proving.
+fn run_mod_reduce_256_prop_test(ops: Vec<(BigUint, BigUint)>) {
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [
+            1 << RV32_CELL_BITS,
+            (INT256_NUM_LIMBS * (1 << RV32_CELL_BITS)) as u32,
+        ],
+    );
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32ModReduce256Chip::<F>::new(
+        Rv32VecHeapTwoReadsAdapterChip::<F, 2, 1, 1, INT256_NUM_LIMBS, INT256_NUM_LIMBS>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        ModReduceCoreChip::new(bitwise_chip.clone(), range_tuple_checker.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for (a, n) in ops {
+        let a_lo = &a % (BigUint::one() << 256usize);
+        let a_hi = &a >> 256usize;
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![u256_to_limbs(&a_lo), u256_to_limbs(&a_hi)],
+            vec![u256_to_limbs(&n)],
+            Rv32ModReduce256Opcode::MOD_REDUCE as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+        let output = rv32_read_heap_default::<INT256_NUM_LIMBS>(&mut tester, &instruction, 1);
+        assert_eq!(output, vec![u256_to_limbs(&(&a % &n))], "{a} mod {n}");
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(range_tuple_checker)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+fn alu_256_op_strategy() -> impl Strategy<Value = (BaseAluOpcode, BigUint, BigUint)> {
+    let opcodes = vec![
+        BaseAluOpcode::ADD,
+        BaseAluOpcode::SUB,
+        BaseAluOpcode::XOR,
+        BaseAluOpcode::OR,
+        BaseAluOpcode::AND,
+    ];
+    (
+        select(opcodes),
+        u256_strategy(vec![]),
+        u256_strategy(vec![]),
+    )
+}
+
+/// A nonzero modulus n, and a 512-bit a whose halves are random or on the edges around n.
+fn mod_reduce_256_op_strategy() -> impl Strategy<Value = (BigUint, BigUint)> {
+    u256_strategy(vec![])
+        .prop_filter("n != 0", |n| !n.is_zero())
+        .prop_flat_map(|n| {
+            let edges = vec![&n - 1u32, n.clone()];
+            (u256_strategy(edges.clone()), u256_strategy(edges), Just(n))
+        })
+        .prop_map(|(a_lo, a_hi, n)| ((a_hi << 256usize) + a_lo, n))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn prop_alu_256_matches_reference(
+        ops in prop::collection::vec(alu_256_op_strategy(), 1..8)
+    ) {
+        run_alu_256_prop_test(ops);
+    }
+
+    #[test]
+    fn prop_mod_reduce_256_matches_reference(
+        ops in prop::collection::vec(mod_reduce_256_op_strategy(), 1..8)
+    ) {
+        run_mod_reduce_256_prop_test(ops);
+    }
+}
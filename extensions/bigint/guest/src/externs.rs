@@ -40,6 +40,18 @@ unsafe extern "C" fn zkvm_u256_wrapping_mul_impl(result: *mut u8, a: *const u8,
     );
 }
 
+#[no_mangle]
+unsafe extern "C" fn zkvm_u512_mod_reduce_impl(result: *mut u8, a: *const u8, modulus: *const u8) {
+    custom_insn_r!(
+        OPCODE,
+        INT256_FUNCT3,
+        Int256Funct7::ModReduce as u8,
+        result as *mut u8,
+        a as *const u8,
+        modulus as *const u8
+    );
+}
+
 #[no_mangle]
 unsafe extern "C" fn zkvm_u256_bitxor_impl(result: *mut u8, a: *const u8, b: *const u8) {
     custom_insn_r!(
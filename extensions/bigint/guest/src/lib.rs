@@ -31,6 +31,7 @@ pub enum Int256Funct7 {
     Slt,
     Sltu,
     Mul,
+    ModReduce,
 }
 
 #[cfg(all(feature = "export-intrinsics", target_os = "zkvm"))]
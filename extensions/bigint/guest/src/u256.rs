@@ -75,6 +75,30 @@ impl U256 {
     pub fn as_le_bytes(&self) -> &[u8; 32] {
         &self.limbs
     }
+
+    /// Reduces the 512-bit value `value[0] + value[1] * 2^256` modulo `modulus`, which must be
+    /// non-zero.
+    #[inline(always)]
+    pub fn reduce_wide(value: &[U256; 2], modulus: &U256) -> U256 {
+        #[cfg(target_os = "zkvm")]
+        {
+            let mut uninit: MaybeUninit<U256> = MaybeUninit::uninit();
+            custom_insn_r!(
+                OPCODE,
+                INT256_FUNCT3,
+                Int256Funct7::ModReduce as u8,
+                uninit.as_mut_ptr(),
+                value as *const [U256; 2],
+                modulus as *const U256
+            );
+            unsafe { uninit.assume_init() }
+        }
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            let value = (value[1].as_biguint() << 256usize) + value[0].as_biguint();
+            U256::from_biguint(&(value % modulus.as_biguint()))
+        }
+    }
 }
 
 impl_bin_op!(
@@ -10,7 +10,7 @@ use openvm_rv32im_transpiler::{
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{util::from_r_type, TranspilerExtension};
 use rrs_lib::instruction_formats::{BType, RType};
-use strum::IntoEnumIterator;
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 // =================================================================================================
 // Intrinsics: 256-bit Integers
@@ -76,6 +76,16 @@ impl Rv32Mul256Opcode {
     }
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x460]
+#[allow(non_camel_case_types)]
+#[repr(usize)]
+pub enum Rv32ModReduce256Opcode {
+    MOD_REDUCE,
+}
+
 #[derive(Default)]
 pub struct Int256TranspilerExtension;
 
@@ -132,6 +142,9 @@ impl<F: PrimeField32> TranspilerExtension<F> for Int256TranspilerExtension {
                     Some(Int256Funct7::Mul) => {
                         MulOpcode::MUL as usize + Rv32Mul256Opcode::default_offset()
                     }
+                    Some(Int256Funct7::ModReduce) => {
+                        Rv32ModReduce256Opcode::MOD_REDUCE.with_default_offset()
+                    }
                     _ => unimplemented!(),
                 };
                 Some(from_r_type(global_opcode, 2, &dec_insn))
@@ -0,0 +1,26 @@
+//! The ChaCha quarter round for RV32 guests. Each instruction transforms four words of the
+//! ChaCha state in memory; the guest library builds the ChaCha20 block function on top.
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+use openvm_rv32_adapters::{Rv32HeapAdapterAir, Rv32HeapAdapterChip};
+
+mod quarter_round;
+pub use quarter_round::*;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of bytes of the four words read and written by a quarter round.
+pub const QUARTER_STATE_SIZE: usize = 16;
+
+pub type Rv32ChaChaQuarterRoundAir = VmAirWrapper<
+    Rv32HeapAdapterAir<1, QUARTER_STATE_SIZE, QUARTER_STATE_SIZE>,
+    ChaChaQuarterRoundCoreAir,
+>;
+pub type Rv32ChaChaQuarterRoundChip<F> = VmChipWrapper<
+    F,
+    Rv32HeapAdapterChip<F, 1, QUARTER_STATE_SIZE, QUARTER_STATE_SIZE>,
+    ChaChaQuarterRoundCoreChip,
+>;
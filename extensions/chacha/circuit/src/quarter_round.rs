@@ -0,0 +1,335 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_chacha_transpiler::Rv32ChaChaOpcode;
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    UsizeOpcode,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+use crate::QUARTER_STATE_SIZE;
+
+const WORD_SIZE: usize = RV32_REGISTER_NUM_LIMBS;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct ChaChaQuarterRoundCoreCols<T> {
+    /// The little-endian words `a, b, c, d`.
+    pub input: [T; QUARTER_STATE_SIZE],
+
+    /// `a1 = a + b`
+    pub a1: [T; WORD_SIZE],
+    /// `d ^ a1`, rotated left by 16 to give `d1`.
+    pub d1_xor: [T; WORD_SIZE],
+    /// `c1 = c + d1`
+    pub c1: [T; WORD_SIZE],
+    /// `b ^ c1`, rotated left by 12 to give `b1`.
+    pub b1_xor: [T; WORD_SIZE],
+    /// Low nibbles of `b1_xor`.
+    pub b1_xor_lo: [T; WORD_SIZE],
+    /// High nibbles of `b1_xor`.
+    pub b1_xor_hi: [T; WORD_SIZE],
+
+    /// `a2 = a1 + b1`, the output `a`.
+    pub a2: [T; WORD_SIZE],
+    /// `d1 ^ a2`, rotated left by 8 to give the output `d`.
+    pub d2_xor: [T; WORD_SIZE],
+    /// `c2 = c1 + d2`, the output `c`.
+    pub c2: [T; WORD_SIZE],
+    /// `b1 ^ c2`, rotated left by 7 to give the output `b`.
+    pub b2_xor: [T; WORD_SIZE],
+    /// Lowest bits of `b2_xor`.
+    pub b2_xor_bit: [T; WORD_SIZE],
+    /// `b2_xor` without its lowest bits.
+    pub b2_xor_half: [T; WORD_SIZE],
+
+    pub is_valid: T,
+}
+
+/// The ChaCha quarter round on four words read from memory, in a single row. Additions are
+/// constrained bytewise with boolean carries; XORs and the range checks of the nibbles and bits
+/// split off for the rotations by 12 and 7 use the bitwise lookup. Rotations by 16 and 8 are
+/// permutations of bytes.
+#[derive(Copy, Clone, Debug)]
+pub struct ChaChaQuarterRoundCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for ChaChaQuarterRoundCoreAir {
+    fn width(&self) -> usize {
+        ChaChaQuarterRoundCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for ChaChaQuarterRoundCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for ChaChaQuarterRoundCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; QUARTER_STATE_SIZE]; 1]>,
+    I::Writes: From<[[AB::Expr; QUARTER_STATE_SIZE]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ChaChaQuarterRoundCoreCols<_> = local_core.borrow();
+        builder.assert_bool(cols.is_valid);
+
+        let word = |i: usize| -> [AB::Expr; WORD_SIZE] {
+            array::from_fn(|j| cols.input[WORD_SIZE * i + j].into())
+        };
+        let [a, b, c, d] = [0, 1, 2, 3].map(word);
+        let vars = |x: &[AB::Var; WORD_SIZE]| x.map(Into::<AB::Expr>::into);
+
+        let d1: [AB::Expr; WORD_SIZE] = array::from_fn(|i| cols.d1_xor[(i + 2) % 4].into());
+        let b1: [AB::Expr; WORD_SIZE] = array::from_fn(|i| {
+            cols.b1_xor_lo[(i + 3) % 4] * AB::Expr::from_canonical_u32(16)
+                + cols.b1_xor_hi[(i + 2) % 4]
+        });
+        let d2: [AB::Expr; WORD_SIZE] = array::from_fn(|i| cols.d2_xor[(i + 3) % 4].into());
+        let b2: [AB::Expr; WORD_SIZE] = array::from_fn(|i| {
+            cols.b2_xor_bit[i] * AB::Expr::from_canonical_u32(128) + cols.b2_xor_half[(i + 3) % 4]
+        });
+
+        // The sums are bytes because they are operands of XOR lookups.
+        eval_add(builder, &a, &b, &cols.a1);
+        eval_add(builder, &c, &d1, &cols.c1);
+        eval_add(builder, &vars(&cols.a1), &b1, &cols.a2);
+        eval_add(builder, &vars(&cols.c1), &d2, &cols.c2);
+
+        for i in 0..WORD_SIZE {
+            self.bus
+                .send_xor(d[i].clone(), cols.a1[i], cols.d1_xor[i])
+                .eval(builder, cols.is_valid);
+            self.bus
+                .send_xor(b[i].clone(), cols.c1[i], cols.b1_xor[i])
+                .eval(builder, cols.is_valid);
+            self.bus
+                .send_xor(d1[i].clone(), cols.a2[i], cols.d2_xor[i])
+                .eval(builder, cols.is_valid);
+            self.bus
+                .send_xor(b1[i].clone(), cols.c2[i], cols.b2_xor[i])
+                .eval(builder, cols.is_valid);
+
+            // 16 * lo and hi are bytes and sum to a byte, so lo and hi are nibbles.
+            builder.assert_eq(
+                cols.b1_xor[i],
+                cols.b1_xor_hi[i] * AB::Expr::from_canonical_u32(16) + cols.b1_xor_lo[i],
+            );
+            self.bus
+                .send_range(
+                    cols.b1_xor_lo[i] * AB::Expr::from_canonical_u32(16),
+                    cols.b1_xor_hi[i],
+                )
+                .eval(builder, cols.is_valid);
+
+            builder.assert_bool(cols.b2_xor_bit[i]);
+            builder.assert_eq(
+                cols.b2_xor[i],
+                cols.b2_xor_half[i] * AB::Expr::TWO + cols.b2_xor_bit[i],
+            );
+        }
+        for i in (0..WORD_SIZE).step_by(2) {
+            self.bus
+                .send_range(cols.b2_xor_half[i], cols.b2_xor_half[i + 1])
+                .eval(builder, cols.is_valid);
+        }
+
+        let output = [vars(&cols.a2), b2, vars(&cols.c2), d2].concat();
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.input.map(Into::into)].into(),
+            writes: [output.try_into().unwrap_or_else(|_| unreachable!())].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: AB::Expr::from_canonical_usize(
+                    Rv32ChaChaOpcode::QUARTER_ROUND as usize + self.offset,
+                ),
+            }
+            .into(),
+        }
+    }
+}
+
+/// Constrains the little-endian bytes `z` to be `x + y` modulo `2^32`, given that all are bytes.
+fn eval_add<AB: AirBuilder>(
+    builder: &mut AB,
+    x: &[AB::Expr; WORD_SIZE],
+    y: &[AB::Expr; WORD_SIZE],
+    z: &[AB::Var; WORD_SIZE],
+) {
+    let carry_divide = AB::F::from_canonical_u32(1 << RV32_CELL_BITS).inverse();
+    let mut carry = AB::Expr::ZERO;
+    for i in 0..WORD_SIZE {
+        carry = AB::Expr::from(carry_divide) * (x[i].clone() + y[i].clone() + carry - z[i]);
+        builder.assert_bool(carry.clone());
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChaChaQuarterRoundCoreRecord {
+    pub input: [u8; QUARTER_STATE_SIZE],
+    pub a1: u32,
+    pub d1_xor: u32,
+    pub c1: u32,
+    pub b1_xor: u32,
+    pub a2: u32,
+    pub d2_xor: u32,
+    pub c2: u32,
+    pub b2_xor: u32,
+}
+
+#[derive(Debug)]
+pub struct ChaChaQuarterRoundCoreChip {
+    pub air: ChaChaQuarterRoundCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl ChaChaQuarterRoundCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: ChaChaQuarterRoundCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for ChaChaQuarterRoundCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; QUARTER_STATE_SIZE]; 1]>,
+    I::Writes: From<[[F; QUARTER_STATE_SIZE]; 1]>,
+{
+    type Record = ChaChaQuarterRoundCoreRecord;
+    type Air = ChaChaQuarterRoundCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        _instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let [data]: [[F; QUARTER_STATE_SIZE]; 1] = reads.into();
+        let input = data.map(|x| x.as_canonical_u32() as u8);
+        let [a, b, c, d] = array::from_fn(|i| {
+            u32::from_le_bytes(
+                input[WORD_SIZE * i..WORD_SIZE * (i + 1)]
+                    .try_into()
+                    .unwrap(),
+            )
+        });
+
+        let a1 = a.wrapping_add(b);
+        let d1_xor = d ^ a1;
+        let d1 = d1_xor.rotate_left(16);
+        let c1 = c.wrapping_add(d1);
+        let b1_xor = b ^ c1;
+        let b1 = b1_xor.rotate_left(12);
+        let a2 = a1.wrapping_add(b1);
+        let d2_xor = d1 ^ a2;
+        let d2 = d2_xor.rotate_left(8);
+        let c2 = c1.wrapping_add(d2);
+        let b2_xor = b1 ^ c2;
+        let b2 = b2_xor.rotate_left(7);
+
+        for (x, y) in [(d, a1), (b, c1), (d1, a2), (b1, c2)] {
+            for (x, y) in x.to_le_bytes().into_iter().zip(y.to_le_bytes()) {
+                self.bitwise_lookup_chip.request_xor(x as u32, y as u32);
+            }
+        }
+        for byte in b1_xor.to_le_bytes() {
+            self.bitwise_lookup_chip
+                .request_range(16 * (byte as u32 & 0xf), byte as u32 >> 4);
+        }
+        let halves = b2_xor.to_le_bytes().map(|byte| byte as u32 >> 1);
+        for pair in halves.chunks_exact(2) {
+            self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+        }
+
+        let output: [u8; QUARTER_STATE_SIZE] = [a2, b2, c2, d2]
+            .map(u32::to_le_bytes)
+            .concat()
+            .try_into()
+            .unwrap();
+        let output_context = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [output.map(F::from_canonical_u8)].into(),
+        };
+        let record = ChaChaQuarterRoundCoreRecord {
+            input,
+            a1,
+            d1_xor,
+            c1,
+            b1_xor,
+            a2,
+            d2_xor,
+            c2,
+            b2_xor,
+        };
+
+        Ok((output_context, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32ChaChaOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ChaChaQuarterRoundCoreCols<_> = row_slice.borrow_mut();
+        let bytes = |x: u32| x.to_le_bytes();
+        let to_field = |x: [u8; WORD_SIZE]| x.map(F::from_canonical_u8);
+        row_slice.input = record.input.map(F::from_canonical_u8);
+        row_slice.a1 = to_field(bytes(record.a1));
+        row_slice.d1_xor = to_field(bytes(record.d1_xor));
+        row_slice.c1 = to_field(bytes(record.c1));
+        row_slice.b1_xor = to_field(bytes(record.b1_xor));
+        row_slice.b1_xor_lo = to_field(bytes(record.b1_xor).map(|x| x & 0xf));
+        row_slice.b1_xor_hi = to_field(bytes(record.b1_xor).map(|x| x >> 4));
+        row_slice.a2 = to_field(bytes(record.a2));
+        row_slice.d2_xor = to_field(bytes(record.d2_xor));
+        row_slice.c2 = to_field(bytes(record.c2));
+        row_slice.b2_xor = to_field(bytes(record.b2_xor));
+        row_slice.b2_xor_bit = to_field(bytes(record.b2_xor).map(|x| x & 1));
+        row_slice.b2_xor_half = to_field(bytes(record.b2_xor).map(|x| x >> 1));
+        row_slice.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,184 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use hex_literal::hex;
+use openvm_chacha_guest::{
+    chacha20_apply_keystream, chacha20_block, chacha20poly1305_open_in_place,
+    chacha20poly1305_seal_in_place, poly1305, quarter_round,
+};
+use openvm_chacha_transpiler::Rv32ChaChaOpcode;
+use openvm_circuit::arch::{
+    testing::{Tamper, VmChipTestBuilder},
+    VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_rv32_adapters::{rv32_write_heap_default, Rv32HeapAdapterChip};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{
+    ChaChaQuarterRoundCoreChip, ChaChaQuarterRoundCoreCols, Rv32ChaChaQuarterRoundChip,
+    QUARTER_STATE_SIZE,
+};
+
+type F = BabyBear;
+
+const QUARTER_ROUND_INPUT: [u32; 4] = [0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567];
+const QUARTER_ROUND_OUTPUT: [u32; 4] = [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb];
+
+const SUNSCREEN: &[u8; 114] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+#[test]
+fn test_chacha20_rfc8439_vectors() {
+    // Sections 2.1.1, 2.3.2 and 2.4.2 of RFC 8439.
+    assert_eq!(quarter_round(QUARTER_ROUND_INPUT), QUARTER_ROUND_OUTPUT);
+
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let block = chacha20_block(&key, 1, &hex!("000000090000004a00000000"));
+    assert_eq!(block[..16], hex!("10f1e7e4d13b5915500fdd1fa32071c4"));
+    assert_eq!(block[48..], hex!("b5129cd1de164eb9cbd083e8a2503c4e"));
+
+    let nonce = hex!("000000000000004a00000000");
+    let mut data = *SUNSCREEN;
+    chacha20_apply_keystream(&key, &nonce, 1, &mut data);
+    assert_eq!(data[..16], hex!("6e2e359a2568f98041ba0728dd0d6981"));
+    assert_eq!(data[data.len() - 2..], hex!("874d"));
+}
+
+#[test]
+fn test_poly1305_rfc8439_vectors() {
+    // Sections 2.5.2 and 2.8.2 of RFC 8439.
+    let key = hex!("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+    assert_eq!(
+        poly1305(&key, b"Cryptographic Forum Research Group"),
+        hex!("a8061dc1305136c6c22b8baf0c0127a9")
+    );
+
+    let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce = hex!("070000004041424344454647");
+    let aad = hex!("50515253c0c1c2c3c4c5c6c7");
+    let plaintext = *SUNSCREEN;
+    let mut data = plaintext;
+    let tag = chacha20poly1305_seal_in_place(&key, &nonce, &aad, &mut data);
+    assert_eq!(data[..16], hex!("d31a8d34648e60db7b86afbc53ef7ec2"));
+    assert_eq!(tag, hex!("1ae10b594f09e26a7e902ecbd0600691"));
+
+    let mut forged = tag;
+    forged[0] ^= 1;
+    assert!(!chacha20poly1305_open_in_place(
+        &key, &nonce, &aad, &mut data, &forged
+    ));
+    assert!(chacha20poly1305_open_in_place(
+        &key, &nonce, &aad, &mut data, &tag
+    ));
+    assert_eq!(data, plaintext);
+}
+
+/// Runs the quarter round chip on the RFC 8439 example, all ones and random words.
+fn run_quarter_rounds() -> (
+    VmChipTestBuilder<F>,
+    Rv32ChaChaQuarterRoundChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = Rv32ChaChaQuarterRoundChip::new(
+        Rv32HeapAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        ChaChaQuarterRoundCoreChip::new(bitwise_chip.clone(), Rv32ChaChaOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    let inputs = [QUARTER_ROUND_INPUT, [u32::MAX; 4]]
+        .into_iter()
+        .chain((0..10).map(|_| rng.gen::<[u32; 4]>()));
+    for words in inputs {
+        let bytes: [u8; QUARTER_STATE_SIZE] =
+            words.map(u32::to_le_bytes).concat().try_into().unwrap();
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![bytes.map(F::from_canonical_u8)],
+            vec![],
+            Rv32ChaChaOpcode::QUARTER_ROUND.with_default_offset(),
+        );
+        let rd_ptr = instruction.a.as_canonical_u32() as usize;
+        tester.execute(&mut chip, instruction);
+
+        let output_ptr = u32::from_le_bytes(
+            tester
+                .read::<4>(RV32_REGISTER_AS as usize, rd_ptr)
+                .map(|x| x.as_canonical_u32() as u8),
+        );
+        let output = tester
+            .read::<QUARTER_STATE_SIZE>(RV32_MEMORY_AS as usize, output_ptr as usize)
+            .map(|x| x.as_canonical_u32() as u8);
+        let expected: [u8; QUARTER_STATE_SIZE] = quarter_round(words)
+            .map(u32::to_le_bytes)
+            .concat()
+            .try_into()
+            .unwrap();
+        assert_eq!(output, expected);
+    }
+    (tester, chip, bitwise_chip)
+}
+
+#[test]
+fn test_chacha_quarter_round_chip() {
+    let (tester, chip, bitwise_chip) = run_quarter_rounds();
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Tampers with the trace of the RFC 8439 example and asserts that it is rejected. A wrong sum
+/// breaks the carry constraints of the addition, so the error is `OodEvaluationMismatch` even
+/// though the tampered limb is also sent to the XOR lookup.
+fn run_quarter_round_negative_test(
+    tamper: impl Fn(&ChaChaQuarterRoundCoreCols<usize>, usize) -> Tamper<F>,
+) {
+    let (tester, chip, bitwise_chip) = run_quarter_rounds();
+    let indices = (0..ChaChaQuarterRoundCoreCols::<F>::width()).collect::<Vec<_>>();
+    let cols: &ChaChaQuarterRoundCoreCols<usize> = indices[..].borrow();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let tamper = tamper(cols, adapter_width);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn test_chacha_quarter_round_wrong_sum_negative() {
+    run_quarter_round_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(0, adapter_width + cols.c1[1])
+    });
+}
+
+#[test]
+fn test_chacha_quarter_round_wrong_output_negative() {
+    run_quarter_round_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(0, adapter_width + cols.a2[3])
+    });
+}
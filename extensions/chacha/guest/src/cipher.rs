@@ -0,0 +1,80 @@
+use crate::quarter_round;
+
+/// Size of a ChaCha20 key in bytes.
+pub const KEY_SIZE: usize = 32;
+/// Size of a ChaCha20 nonce in bytes, as in RFC 8439.
+pub const NONCE_SIZE: usize = 12;
+/// Size of a ChaCha20 keystream block in bytes.
+pub const CHACHA_BLOCK_SIZE: usize = 64;
+
+/// The constant words `"expand 32-byte k"` at the start of the state.
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// Indices of the quarter rounds of a double round: four columns, then four diagonals.
+const DOUBLE_ROUND: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The ChaCha20 block function of RFC 8439: 20 rounds over the state built from `key`, the block
+/// `counter` and `nonce`, with the input added back in. Each quarter round is one instruction in
+/// the zkVM.
+pub fn chacha20_block(
+    key: &[u8; KEY_SIZE],
+    counter: u32,
+    nonce: &[u8; NONCE_SIZE],
+) -> [u8; CHACHA_BLOCK_SIZE] {
+    let mut input = [0u32; 16];
+    input[..4].copy_from_slice(&CONSTANTS);
+    for (word, bytes) in input[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    input[12] = counter;
+    for (word, bytes) in input[13..].iter_mut().zip(nonce.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut state = input;
+    for _ in 0..10 {
+        for indices in DOUBLE_ROUND {
+            let words = quarter_round(indices.map(|i| state[i]));
+            for (i, word) in indices.into_iter().zip(words) {
+                state[i] = word;
+            }
+        }
+    }
+
+    let mut output = [0u8; CHACHA_BLOCK_SIZE];
+    for ((bytes, word), input) in output.chunks_exact_mut(4).zip(state).zip(input) {
+        bytes.copy_from_slice(&word.wrapping_add(input).to_le_bytes());
+    }
+    output
+}
+
+/// XORs `data` in place with the ChaCha20 keystream starting at block `counter`. Encryption and
+/// decryption are the same operation.
+///
+/// Panics if the block counter overflows.
+pub fn chacha20_apply_keystream(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    counter: u32,
+    data: &mut [u8],
+) {
+    for (i, chunk) in data.chunks_mut(CHACHA_BLOCK_SIZE).enumerate() {
+        let counter = u32::try_from(i)
+            .ok()
+            .and_then(|i| counter.checked_add(i))
+            .expect("ChaCha20 block counter overflow");
+        let keystream = chacha20_block(key, counter, nonce);
+        for (byte, k) in chunk.iter_mut().zip(keystream) {
+            *byte ^= k;
+        }
+    }
+}
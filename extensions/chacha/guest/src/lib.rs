@@ -0,0 +1,63 @@
+#![no_std]
+
+#[cfg(target_os = "zkvm")]
+use core::mem::MaybeUninit;
+
+use strum_macros::FromRepr;
+
+mod cipher;
+pub use cipher::*;
+#[cfg(feature = "poly1305")]
+mod poly1305;
+#[cfg(feature = "poly1305")]
+pub use poly1305::*;
+
+/// This is custom-1 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x2b;
+pub const FUNCT3: u8 = 0b111;
+
+/// The ChaCha instructions. They take a pointer to their input in `rs1` and write their output to
+/// the pointer in `rd`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum ChaChaBaseFunct7 {
+    /// The ChaCha quarter round on four little-endian words `[a, b, c, d]`.
+    QuarterRound = 0,
+}
+
+/// Four words of the ChaCha state, aligned for the quarter round instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C, align(16))]
+struct QuarterState([u32; 4]);
+
+/// The ChaCha quarter round of RFC 8439 on `[a, b, c, d]`.
+#[inline(always)]
+pub fn quarter_round(words: [u32; 4]) -> [u32; 4] {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let [mut a, mut b, mut c, mut d] = words;
+        a = a.wrapping_add(b);
+        d = (d ^ a).rotate_left(16);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_left(12);
+        a = a.wrapping_add(b);
+        d = (d ^ a).rotate_left(8);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_left(7);
+        [a, b, c, d]
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        let input = QuarterState(words);
+        let mut output = MaybeUninit::<QuarterState>::uninit();
+        openvm_platform::custom_insn_r!(
+            OPCODE,
+            FUNCT3,
+            ChaChaBaseFunct7::QuarterRound as u8,
+            output.as_mut_ptr(),
+            &input as *const QuarterState,
+            "x0"
+        );
+        unsafe { output.assume_init() }.0
+    }
+}
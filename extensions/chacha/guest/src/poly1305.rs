@@ -0,0 +1,156 @@
+use openvm_algebra_guest::IntMod;
+
+use crate::{chacha20_apply_keystream, chacha20_block, KEY_SIZE, NONCE_SIZE};
+
+/// Size of a Poly1305 key in bytes: the multiplier `r` followed by the final addend `s`.
+pub const POLY1305_KEY_SIZE: usize = 32;
+/// Size of a Poly1305 tag, and of the message blocks it accumulates, in bytes.
+pub const POLY1305_BLOCK_SIZE: usize = 16;
+
+// The field of Poly1305, modulo `2^130 - 5`.
+openvm_algebra_moduli_setup::moduli_declare! {
+    Poly1305Field { modulus = "0x3fffffffffffffffffffffffffffffffb" },
+}
+
+/// Incremental Poly1305 of RFC 8439. The accumulator is an element of [Poly1305Field], so each
+/// block costs one modular addition and one modular multiplication.
+///
+/// The guest must include the Poly1305 modulus `"0x3fffffffffffffffffffffffffffffffb"` in its
+/// `moduli_init!` and set it up before use, and the VM must support it in the modular arithmetic
+/// extension.
+pub struct Poly1305 {
+    r: Poly1305Field,
+    s: u128,
+    acc: Poly1305Field,
+    buffer: [u8; POLY1305_BLOCK_SIZE],
+    buffered: usize,
+}
+
+impl Poly1305 {
+    /// Creates an accumulator from a one-time key. `r` is clamped as the RFC requires.
+    pub fn new(key: &[u8; POLY1305_KEY_SIZE]) -> Self {
+        let (r, s) = key.split_at(POLY1305_BLOCK_SIZE);
+        let r = u128::from_le_bytes(r.try_into().unwrap()) & 0x0ffffffc0ffffffc0ffffffc0fffffff;
+        Self {
+            r: field_from_le_bytes(&r.to_le_bytes(), false),
+            s: u128::from_le_bytes(s.try_into().unwrap()),
+            acc: Poly1305Field::ZERO,
+            buffer: [0; POLY1305_BLOCK_SIZE],
+            buffered: 0,
+        }
+    }
+
+    /// Absorbs `data`. A message may be split across calls at any byte.
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffered > 0 {
+            let take = data.len().min(POLY1305_BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < POLY1305_BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffered = 0;
+        }
+        let mut blocks = data.chunks_exact(POLY1305_BLOCK_SIZE);
+        for block in &mut blocks {
+            self.process_block(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Returns the tag `(acc + s) mod 2^128` of the absorbed message.
+    pub fn finalize(mut self) -> [u8; POLY1305_BLOCK_SIZE] {
+        if self.buffered > 0 {
+            let block = self.buffer;
+            self.process_block(&block[..self.buffered]);
+        }
+        // Only the low 128 bits are used, so the accumulator must be the canonical residue.
+        self.acc.assert_unique();
+        let acc = u128::from_le_bytes(
+            self.acc.as_le_bytes()[..POLY1305_BLOCK_SIZE]
+                .try_into()
+                .unwrap(),
+        );
+        acc.wrapping_add(self.s).to_le_bytes()
+    }
+
+    /// `acc = (acc + block + 2^(8 * len)) * r`
+    fn process_block(&mut self, block: &[u8]) {
+        self.acc += field_from_le_bytes(block, true);
+        self.acc *= &self.r;
+    }
+}
+
+/// Reads at most 16 little-endian bytes into [Poly1305Field], appending a one byte if `pad` is set.
+fn field_from_le_bytes(bytes: &[u8], pad: bool) -> Poly1305Field {
+    let mut limbs = [0u8; Poly1305Field::NUM_LIMBS];
+    limbs[..bytes.len()].copy_from_slice(bytes);
+    if pad {
+        limbs[bytes.len()] = 1;
+    }
+    Poly1305Field::from_le_bytes(&limbs)
+}
+
+/// Computes the Poly1305 tag of `message` under the one-time `key`.
+pub fn poly1305(key: &[u8; POLY1305_KEY_SIZE], message: &[u8]) -> [u8; POLY1305_BLOCK_SIZE] {
+    let mut mac = Poly1305::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// Encrypts `buffer` in place with the ChaCha20-Poly1305 AEAD of RFC 8439 and returns the tag over
+/// `aad` and the ciphertext.
+pub fn chacha20poly1305_seal_in_place(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    buffer: &mut [u8],
+) -> [u8; POLY1305_BLOCK_SIZE] {
+    chacha20_apply_keystream(key, nonce, 1, buffer);
+    aead_tag(key, nonce, aad, buffer)
+}
+
+/// Checks `tag` over `aad` and the ciphertext in `buffer`, then decrypts `buffer` in place.
+/// Returns `false`, leaving `buffer` unchanged, if the tag does not match.
+pub fn chacha20poly1305_open_in_place(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8; POLY1305_BLOCK_SIZE],
+) -> bool {
+    let expected = aead_tag(key, nonce, aad, buffer);
+    if expected
+        .iter()
+        .zip(tag)
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        != 0
+    {
+        return false;
+    }
+    chacha20_apply_keystream(key, nonce, 1, buffer);
+    true
+}
+
+fn aead_tag(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> [u8; POLY1305_BLOCK_SIZE] {
+    let block = chacha20_block(key, 0, nonce);
+    let mut mac = Poly1305::new(block[..POLY1305_KEY_SIZE].try_into().unwrap());
+    let zeros = [0u8; POLY1305_BLOCK_SIZE];
+    for data in [aad, ciphertext] {
+        mac.update(data);
+        mac.update(&zeros[..data.len().wrapping_neg() % POLY1305_BLOCK_SIZE]);
+    }
+    mac.update(&(aad.len() as u64).to_le_bytes());
+    mac.update(&(ciphertext.len() as u64).to_le_bytes());
+    mac.finalize()
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        SystemPort, VmChipWrapper, VmCoreChip, VmExtension, VmInventory, VmInventoryBuilder,
+        VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::riscv::RV32_CELL_BITS;
+use openvm_rv32_adapters::Rv32HeapAdapterChip;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Builds the core of a user-defined chip.
+pub trait CustomCoreBuilder<
+    F: PrimeField32,
+    const NUM_READS: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+>
+{
+    type Core: VmCoreChip<F, CustomChipInterface<F, NUM_READS, READ_SIZE, WRITE_SIZE>>
+        + Send
+        + Sync
+        + 'static;
+
+    /// Builds the core. `opcode_offset` is the global opcode of the local opcode 0 of the schema.
+    /// The core may use the shared `bitwise_lookup_chip`, the range checker of the
+    /// [SystemBase](openvm_circuit::arch::SystemBase) and buses allocated from the `builder`.
+    fn build(
+        &self,
+        opcode_offset: usize,
+        builder: &mut VmInventoryBuilder<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    ) -> Self::Core;
+}
+
+/// Extension adding one user-defined chip, whose instructions are described by `schema`.
+///
+/// To use it in a [VmConfig](openvm_circuit::arch::VmConfig), give the extension type an alias
+/// `XExtension` and alias `XExtensionExecutor<F>` to the matching [CustomChipExecutor] and
+/// `XExtensionPeriphery<F>` to [CustomChipPeriphery].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomChipExtension<
+    B,
+    const NUM_READS: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+> {
+    pub schema: CustomOpcodeSchema,
+    pub core: B,
+}
+
+impl<B, const NUM_READS: usize, const READ_SIZE: usize, const WRITE_SIZE: usize>
+    CustomChipExtension<B, NUM_READS, READ_SIZE, WRITE_SIZE>
+{
+    pub fn new(schema: CustomOpcodeSchema, core: B) -> Self {
+        assert!(
+            NUM_READS <= 2,
+            "the heap adapter reads at most two pointers"
+        );
+        Self { schema, core }
+    }
+
+    /// The transpiler extension for the instructions of the schema.
+    pub fn transpiler_extension(&self) -> CustomTranspilerExtension {
+        CustomTranspilerExtension::new(self.schema)
+    }
+}
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+#[chip(
+    where = "CustomChip<F, C, NUM_READS, READ_SIZE, WRITE_SIZE>: openvm_stark_backend::Chip<SC>"
+)]
+pub enum CustomChipExecutor<
+    F: PrimeField32,
+    C: VmCoreChip<F, CustomChipInterface<F, NUM_READS, READ_SIZE, WRITE_SIZE>> + Send + Sync + 'static,
+    const NUM_READS: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+> {
+    Custom(CustomChip<F, C, NUM_READS, READ_SIZE, WRITE_SIZE>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum CustomChipPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F, B, const NUM_READS: usize, const READ_SIZE: usize, const WRITE_SIZE: usize> VmExtension<F>
+    for CustomChipExtension<B, NUM_READS, READ_SIZE, WRITE_SIZE>
+where
+    F: PrimeField32,
+    B: CustomCoreBuilder<F, NUM_READS, READ_SIZE, WRITE_SIZE>,
+{
+    type Executor = CustomChipExecutor<F, B::Core, NUM_READS, READ_SIZE, WRITE_SIZE>;
+    type Periphery = CustomChipPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+        let core = self
+            .core
+            .build(self.schema.opcode_offset, builder, bitwise_lu_chip.clone());
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+
+        let chip = VmChipWrapper::new(
+            Rv32HeapAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip,
+            ),
+            core,
+            memory_controller,
+        );
+        inventory.add_executor(chip, self.schema.global_opcodes())?;
+
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,31 @@
+//! A template for user-defined chips. The user supplies the core of the chip, that is its AIR,
+//! trace generation and execution, as a [VmCoreChip](openvm_circuit::arch::VmCoreChip), together
+//! with a [CustomOpcodeSchema]. [CustomChipExtension] wraps the core in the heap adapter, which
+//! connects it to the execution and memory buses, and registers the opcodes of the schema. The
+//! matching transpiler extension is [CustomChipExtension::transpiler_extension].
+use openvm_circuit::arch::{
+    BasicAdapterInterface, MinimalInstruction, VmAirWrapper, VmChipWrapper,
+};
+pub use openvm_custom_chip_transpiler::{CustomOpcodeSchema, CustomTranspilerExtension};
+use openvm_rv32_adapters::{Rv32HeapAdapterAir, Rv32HeapAdapterChip};
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The interface between the heap adapter and a user-defined core. The core receives
+/// `NUM_READS` blocks of `READ_SIZE` bytes, read from the heap at the pointers in `rs1` and
+/// `rs2`, and returns one block of `WRITE_SIZE` bytes, written to the heap at the pointer in `rd`.
+pub type CustomChipInterface<
+    T,
+    const NUM_READS: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+> = BasicAdapterInterface<T, MinimalInstruction<T>, NUM_READS, 1, READ_SIZE, WRITE_SIZE>;
+
+pub type CustomAir<A, const NUM_READS: usize, const READ_SIZE: usize, const WRITE_SIZE: usize> =
+    VmAirWrapper<Rv32HeapAdapterAir<NUM_READS, READ_SIZE, WRITE_SIZE>, A>;
+pub type CustomChip<F, C, const NUM_READS: usize, const READ_SIZE: usize, const WRITE_SIZE: usize> =
+    VmChipWrapper<F, Rv32HeapAdapterChip<F, NUM_READS, READ_SIZE, WRITE_SIZE>, C>;
@@ -0,0 +1,225 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    testing::VmChipTestBuilder, AdapterAirContext, AdapterRuntimeContext, MinimalInstruction,
+    Result, VmAdapterInterface, VmChipWrapper, VmCoreAir, VmCoreChip, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS};
+use openvm_rv32_adapters::{rv32_read_heap_default, rv32_write_heap_default, Rv32HeapAdapterChip};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use openvm_transpiler::TranspilerExtension;
+use rand::Rng;
+
+use super::{CustomChip, CustomOpcodeSchema, CustomTranspilerExtension};
+
+type F = BabyBear;
+
+const BLOCK_SIZE: usize = 32;
+const OPCODE_OFFSET: usize = 0x360;
+
+/// An example core with two opcodes: local opcode 0 reverses the bytes of a block, and local
+/// opcode 1 copies it.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+struct ReverseCoreCols<T> {
+    input: [T; BLOCK_SIZE],
+    output: [T; BLOCK_SIZE],
+    is_reverse: T,
+    is_copy: T,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ReverseCoreAir {
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for ReverseCoreAir {
+    fn width(&self) -> usize {
+        ReverseCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for ReverseCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for ReverseCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; BLOCK_SIZE]; 1]>,
+    I::Writes: From<[[AB::Expr; BLOCK_SIZE]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ReverseCoreCols<_> = local_core.borrow();
+        builder.assert_bool(cols.is_reverse);
+        builder.assert_bool(cols.is_copy);
+        let is_valid = cols.is_reverse + cols.is_copy;
+        builder.assert_bool(is_valid.clone());
+
+        for i in 0..BLOCK_SIZE {
+            builder.assert_eq(
+                cols.output[i],
+                cols.is_reverse * cols.input[BLOCK_SIZE - 1 - i] + cols.is_copy * cols.input[i],
+            );
+        }
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.input.map(Into::into)].into(),
+            writes: [cols.output.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: AB::Expr::from_canonical_usize(self.offset) + cols.is_copy,
+            }
+            .into(),
+        }
+    }
+}
+
+struct ReverseCoreChip {
+    air: ReverseCoreAir,
+}
+
+struct ReverseCoreRecord<T> {
+    input: [T; BLOCK_SIZE],
+    is_copy: bool,
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for ReverseCoreChip
+where
+    I::Reads: Into<[[F; BLOCK_SIZE]; 1]>,
+    I::Writes: From<[[F; BLOCK_SIZE]; 1]>,
+{
+    type Record = ReverseCoreRecord<F>;
+    type Air = ReverseCoreAir;
+
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let is_copy = instruction.opcode.local_opcode_idx(self.air.offset) == 1;
+        let [input] = reads.into();
+        let output = if is_copy {
+            input
+        } else {
+            array::from_fn(|i| input[BLOCK_SIZE - 1 - i])
+        };
+        Ok((
+            AdapterRuntimeContext::without_pc([output]),
+            ReverseCoreRecord { input, is_copy },
+        ))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        ["REVERSE", "COPY"][opcode - self.air.offset].to_string()
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ReverseCoreCols<_> = row_slice.borrow_mut();
+        row_slice.input = record.input;
+        row_slice.output = if record.is_copy {
+            record.input
+        } else {
+            array::from_fn(|i| record.input[BLOCK_SIZE - 1 - i])
+        };
+        row_slice.is_reverse = F::from_bool(!record.is_copy);
+        row_slice.is_copy = F::from_bool(record.is_copy);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+#[test]
+fn test_custom_chip() {
+    let mut rng = create_seeded_rng();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip: CustomChip<F, ReverseCoreChip, 1, BLOCK_SIZE, BLOCK_SIZE> = VmChipWrapper::new(
+        Rv32HeapAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        ReverseCoreChip {
+            air: ReverseCoreAir {
+                offset: OPCODE_OFFSET,
+            },
+        },
+        tester.memory_controller(),
+    );
+
+    for _ in 0..20 {
+        let local_opcode = rng.gen_range(0..2);
+        let input: [u8; BLOCK_SIZE] = array::from_fn(|_| rng.gen());
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![input.map(F::from_canonical_u8)],
+            vec![],
+            OPCODE_OFFSET + local_opcode,
+        );
+        tester.execute(&mut chip, instruction.clone());
+
+        let mut expected = input;
+        if local_opcode == 0 {
+            expected.reverse();
+        }
+        let output = rv32_read_heap_default::<BLOCK_SIZE>(&mut tester, &instruction, 1);
+        assert_eq!(output, vec![expected.map(F::from_canonical_u8)]);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_custom_transpiler_extension() {
+    let schema = CustomOpcodeSchema::new(0x7b, 0b010, 2, OPCODE_OFFSET);
+    let extension = CustomTranspilerExtension::new(schema);
+    // An R-type instruction with rd = x10, rs1 = x11 and rs2 = x12.
+    let encode = |opcode: u32, funct3: u32, funct7: u32| {
+        (funct7 << 25) | (12 << 20) | (11 << 15) | (funct3 << 12) | (10 << 7) | opcode
+    };
+
+    let (instruction, len) =
+        TranspilerExtension::<F>::process_custom(&extension, &[encode(0x7b, 0b010, 1)]).unwrap();
+    assert_eq!(len, 1);
+    assert_eq!(instruction.opcode.as_usize(), OPCODE_OFFSET + 1);
+    assert_eq!(instruction.a, F::from_canonical_usize(4 * 10));
+    assert_eq!(instruction.e, F::TWO);
+
+    // Local opcodes beyond the schema, and other opcodes or funct3 values, are left to the other
+    // extensions.
+    for word in [
+        encode(0x7b, 0b010, 2),
+        encode(0x5b, 0b010, 0),
+        encode(0x7b, 0b011, 0),
+    ] {
+        assert!(TranspilerExtension::<F>::process_custom(&extension, &[word]).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Transpiler extension for a user-defined chip, driven by a [CustomOpcodeSchema].
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use serde::{Deserialize, Serialize};
+
+/// The RISC-V major opcodes reserved for custom extensions: custom-0 to custom-3.
+pub const CUSTOM_OPCODES: [u8; 4] = [0x0b, 0x2b, 0x5b, 0x7b];
+
+/// Number of distinct `funct7` values, and so the maximum number of opcodes of a schema.
+pub const MAX_NUM_OPCODES: usize = 1 << 7;
+
+/// Describes the instructions of a user-defined chip.
+///
+/// The guest emits R-type instructions with the major opcode `opcode` and the given `funct3`. The
+/// `funct7` field selects one of `num_opcodes` local opcodes, which are transpiled to the global
+/// opcodes `opcode_offset..opcode_offset + num_opcodes`. Registers `rd`, `rs1` and `rs2` hold
+/// pointers into the heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomOpcodeSchema {
+    pub opcode: u8,
+    pub funct3: u8,
+    pub num_opcodes: usize,
+    pub opcode_offset: usize,
+}
+
+impl CustomOpcodeSchema {
+    /// Panics if `opcode` is not one of [CUSTOM_OPCODES], `funct3` does not fit in three bits, or
+    /// `num_opcodes` is zero or more than [MAX_NUM_OPCODES].
+    pub fn new(opcode: u8, funct3: u8, num_opcodes: usize, opcode_offset: usize) -> Self {
+        assert!(
+            CUSTOM_OPCODES.contains(&opcode),
+            "opcode {opcode:#x} is not a custom RISC-V opcode"
+        );
+        assert!(funct3 < 8, "funct3 {funct3:#b} does not fit in three bits");
+        assert!(
+            (1..=MAX_NUM_OPCODES).contains(&num_opcodes),
+            "a schema has between 1 and {MAX_NUM_OPCODES} opcodes, not {num_opcodes}"
+        );
+        Self {
+            opcode,
+            funct3,
+            num_opcodes,
+            opcode_offset,
+        }
+    }
+
+    /// Returns the local opcode selected by `funct7`, if the schema has it.
+    pub fn local_opcode(&self, funct7: u8) -> Option<usize> {
+        let local_opcode = funct7 as usize;
+        (local_opcode < self.num_opcodes).then_some(local_opcode)
+    }
+
+    /// The global opcodes of the schema, in order of local opcode.
+    pub fn global_opcodes(&self) -> impl Iterator<Item = VmOpcode> {
+        (self.opcode_offset..self.opcode_offset + self.num_opcodes).map(VmOpcode::from_usize)
+    }
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct CustomTranspilerExtension {
+    pub schema: CustomOpcodeSchema,
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for CustomTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (self.schema.opcode, self.schema.funct3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = self.schema.local_opcode(dec_insn.funct7 as u8)?;
+        let instruction = from_r_type(self.schema.opcode_offset + local_opcode, 2, &dec_insn);
+        Some((instruction, 1))
+    }
+}
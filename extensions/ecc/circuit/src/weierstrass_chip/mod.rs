@@ -8,85 +8,33 @@ pub use double::*;
 mod tests;
 
 use num_bigint_dig::BigUint;
-use openvm_circuit::{arch::VmChipWrapper, system::memory::MemoryControllerRef};
-use openvm_circuit_derive::InstructionExecutor;
-use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_ecc_transpiler::Rv32WeierstrassOpcode;
-use openvm_mod_circuit_builder::{ExprBuilderConfig, FieldExpressionCoreChip};
-use openvm_rv32_adapters::Rv32VecHeapAdapterChip;
-use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_mod_circuit_builder::field_expression_chip;
 
-/// BLOCK_SIZE: how many cells do we read at a time, must be a power of 2.
-/// BLOCKS: how many blocks do we need to represent one input or output
-/// For example, for bls12_381, BLOCK_SIZE = 16, each element has 3 blocks and with two elements per input AffinePoint, BLOCKS = 6.
-/// For secp256k1, BLOCK_SIZE = 32, BLOCKS = 2.
-#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
-pub struct EcAddNeChip<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>(
-    VmChipWrapper<
-        F,
-        Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
-        FieldExpressionCoreChip,
-    >,
-);
-
-impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
-    EcAddNeChip<F, BLOCKS, BLOCK_SIZE>
-{
-    pub fn new(
-        adapter: Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
-        memory_controller: MemoryControllerRef<F>,
-        config: ExprBuilderConfig,
-        offset: usize,
-    ) -> Self {
-        let expr = ec_add_ne_expr(config, memory_controller.borrow().range_checker.bus());
-        let core = FieldExpressionCoreChip::new(
-            expr,
-            offset,
-            vec![
-                Rv32WeierstrassOpcode::EC_ADD_NE as usize,
-                Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
-            ],
-            vec![],
-            memory_controller.borrow().range_checker.clone(),
-            "EcAddNe",
-            false,
-        );
-        Self(VmChipWrapper::new(adapter, core, memory_controller))
+field_expression_chip! {
+    /// BLOCK_SIZE: how many cells do we read at a time, must be a power of 2.
+    /// BLOCKS: how many blocks do we need to represent one input or output
+    /// For example, for bls12_381, BLOCK_SIZE = 16, each element has 3 blocks and with two elements per input AffinePoint, BLOCKS = 6.
+    /// For secp256k1, BLOCK_SIZE = 32, BLOCKS = 2.
+    pub struct EcAddNeChip {
+        num_reads: 2,
+        expr: ec_add_ne_expr(),
+        opcodes: [
+            Rv32WeierstrassOpcode::EC_ADD_NE,
+            Rv32WeierstrassOpcode::SETUP_EC_ADD_NE,
+        ],
+        name: "EcAddNe",
     }
 }
 
-#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
-pub struct EcDoubleChip<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>(
-    VmChipWrapper<
-        F,
-        Rv32VecHeapAdapterChip<F, 1, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
-        FieldExpressionCoreChip,
-    >,
-);
-
-impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
-    EcDoubleChip<F, BLOCKS, BLOCK_SIZE>
-{
-    pub fn new(
-        adapter: Rv32VecHeapAdapterChip<F, 1, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
-        memory_controller: MemoryControllerRef<F>,
-        config: ExprBuilderConfig,
-        offset: usize,
-        a: BigUint,
-    ) -> Self {
-        let expr = ec_double_expr(config, memory_controller.borrow().range_checker.bus(), a);
-        let core = FieldExpressionCoreChip::new(
-            expr,
-            offset,
-            vec![
-                Rv32WeierstrassOpcode::EC_DOUBLE as usize,
-                Rv32WeierstrassOpcode::SETUP_EC_DOUBLE as usize,
-            ],
-            vec![],
-            memory_controller.borrow().range_checker.clone(),
-            "EcDouble",
-            false,
-        );
-        Self(VmChipWrapper::new(adapter, core, memory_controller))
+field_expression_chip! {
+    pub struct EcDoubleChip {
+        num_reads: 1,
+        expr: ec_double_expr(a: BigUint),
+        opcodes: [
+            Rv32WeierstrassOpcode::EC_DOUBLE,
+            Rv32WeierstrassOpcode::SETUP_EC_DOUBLE,
+        ],
+        name: "EcDouble",
     }
 }
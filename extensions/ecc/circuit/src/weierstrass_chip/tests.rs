@@ -1,20 +1,30 @@
 use std::{str::FromStr, sync::Arc};
 
 use num_bigint_dig::BigUint;
-use num_traits::{FromPrimitive, Num, Zero};
-use openvm_circuit::arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
+use num_traits::{FromPrimitive, Num, One, Zero};
+use openvm_circuit::arch::{
+    testing::{Tamper, VmChipTestBuilder},
+    VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+};
 use openvm_circuit_primitives::{
-    bigint::utils::{secp256k1_coord_prime, secp256r1_coord_prime},
+    bigint::utils::{big_uint_mod_inverse, secp256k1_coord_prime, secp256r1_coord_prime},
     bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
 };
 use openvm_ecc_transpiler::Rv32WeierstrassOpcode;
 use openvm_instructions::{riscv::RV32_CELL_BITS, UsizeOpcode};
 use openvm_mod_circuit_builder::{
-    test_utils::biguint_to_limbs, ExprBuilderConfig, FieldExpressionCoreChip,
+    test_utils::{biguint_to_limbs, field_element_strategy, field_expr_col_indices},
+    ExprBuilderConfig, FieldExprCols, FieldExpressionCoreChip,
+};
+use openvm_rv32_adapters::{
+    rv32_read_heap_default, rv32_write_heap_default, Rv32VecHeapAdapterChip,
+};
+use openvm_stark_backend::{
+    p3_air::BaseAir, p3_field::AbstractField, utils::disable_debug_builder,
+    verifier::VerificationError,
 };
-use openvm_rv32_adapters::{rv32_write_heap_default, Rv32VecHeapAdapterChip};
-use openvm_stark_backend::p3_field::AbstractField;
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
+use proptest::prelude::*;
 
 use super::{EcAddNeChip, EcDoubleChip};
 
@@ -302,3 +312,261 @@ fn test_p256_double() {
 
     tester.simple_test().expect("Verification failed");
 }
+
+fn run_add_ne_negative_test(tamper: impl Fn(&FieldExprCols<usize>, usize) -> Tamper<F>) {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: secp256k1_coord_prime(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 2, 2, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = EcAddNeChip::new(
+        adapter,
+        tester.memory_controller(),
+        config,
+        Rv32WeierstrassOpcode::default_offset(),
+    );
+
+    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(&chip.0.core).try_into().unwrap();
+    let one_limbs = [BabyBear::ONE; NUM_LIMBS];
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        vec![prime_limbs, one_limbs],
+        vec![one_limbs, one_limbs],
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, setup_instruction);
+
+    let [p1, p2] = [&SampleEcPoints[0], &SampleEcPoints[1]].map(|(x, y)| {
+        [x, y].map(|c| {
+            biguint_to_limbs::<NUM_LIMBS>(c.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)
+        })
+    });
+    let instruction = rv32_write_heap_default(
+        &mut tester,
+        p1.to_vec(),
+        p2.to_vec(),
+        chip.0.core.air.offset + Rv32WeierstrassOpcode::EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, instruction);
+
+    // Row 0 is the setup, row 1 is the addition.
+    let cols = field_expr_col_indices(chip.0.core.expr());
+    let adapter_width = BaseAir::<F>::width(chip.0.adapter.air());
+    let tamper = tamper(&cols, adapter_width);
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[tamper])
+        .load(bitwise_chip)
+        .finalize();
+    // Each tamper changes a value in the polynomial identity of the expression. The tampered
+    // cells are also range checked, but the constraints are checked first.
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn test_add_ne_wrong_carry_negative() {
+    run_add_ne_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(1, adapter_width + cols.carry_limbs[0][0])
+    });
+}
+
+#[test]
+fn test_add_ne_wrong_quotient_negative() {
+    run_add_ne_negative_test(|cols, adapter_width| {
+        Tamper::off_by_one(1, adapter_width + cols.q_limbs[1][0])
+    });
+}
+
+#[test]
+fn test_add_ne_skipped_range_check_negative() {
+    // x3 limbs are range checked to LIMB_BITS bits.
+    run_add_ne_negative_test(|cols, adapter_width| {
+        Tamper::skipped_range_check(1, adapter_width + cols.vars[1][0], LIMB_BITS)
+    });
+}
+
+fn reduce_all<const N: usize>(xs: &[BigUint; N], p: &BigUint) -> [BigUint; N] {
+    xs.clone().map(|x| x % p)
+}
+
+/// Reference for EC_ADD_NE, the inputs are (x1, y1, x2, y2) with x1 != x2 mod p.
+fn ec_add_ne_reference(p: &BigUint, points: &[BigUint; 4]) -> [BigUint; 2] {
+    let [x1, y1, x2, y2] = reduce_all(points, p);
+    let lambda = (&y2 + p - &y1) * big_uint_mod_inverse(&((&x2 + p - &x1) % p), p) % p;
+    let x3 = (&lambda * &lambda + p + p - &x1 - &x2) % p;
+    let y3 = (&lambda * ((&x1 + p - &x3) % p) + p - &y1) % p;
+    [x3, y3]
+}
+
+/// Reference for EC_DOUBLE on a curve with a = 0, the inputs are (x, y) with y != 0 mod p.
+fn ec_double_reference(p: &BigUint, point: &[BigUint; 2]) -> [BigUint; 2] {
+    let [x, y] = reduce_all(point, p);
+    let three = BigUint::from_u32(3).unwrap();
+    let lambda = three * &x * &x % p
+        * big_uint_mod_inverse(&(BigUint::from_u32(2).unwrap() * &y % p), p)
+        % p;
+    let x3 = (&lambda * &lambda + p + p - &x - &x) % p;
+    let y3 = (&lambda * ((&x + p - &x3) % p) + p - &y) % p;
+    [x3, y3]
+}
+
+fn to_limbs_vec(xs: &[BigUint]) -> Vec<[BabyBear; NUM_LIMBS]> {
+    xs.iter()
+        .map(|x| {
+            biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)
+        })
+        .collect()
+}
+
+/// Runs `ops` through an EC_ADD_NE chip after its setup, checking the outputs against the
+/// BigUint reference before proving.
+fn run_add_ne_prop_test(ops: Vec<[BigUint; 4]>) {
+    let modulus = secp256k1_coord_prime();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 2, 2, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = EcAddNeChip::new(
+        adapter,
+        tester.memory_controller(),
+        config,
+        Rv32WeierstrassOpcode::default_offset(),
+    );
+    let offset = chip.0.core.air.offset;
+
+    // inputs[0] = prime, x2 = 1 keeps the setup's lambda well defined.
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        to_limbs_vec(&[modulus.clone(), BigUint::zero()]),
+        to_limbs_vec(&[BigUint::one(), BigUint::zero()]),
+        offset + Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+    );
+    tester.execute(&mut chip, setup_instruction);
+
+    for points in ops {
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            to_limbs_vec(&points[..2]),
+            to_limbs_vec(&points[2..]),
+            offset + Rv32WeierstrassOpcode::EC_ADD_NE as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+        let output = rv32_read_heap_default::<NUM_LIMBS>(&mut tester, &instruction, 2);
+        let expected = ec_add_ne_reference(&modulus, &points);
+        assert_eq!(output, to_limbs_vec(&expected), "EC_ADD_NE on {points:?}");
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Runs `ops` through an EC_DOUBLE chip after its setup, checking the outputs against the
+/// BigUint reference before proving.
+fn run_double_prop_test(ops: Vec<[BigUint; 2]>) {
+    let modulus = secp256k1_coord_prime();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: modulus.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let adapter = Rv32VecHeapAdapterChip::<F, 1, 2, 2, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = EcDoubleChip::new(
+        adapter,
+        tester.memory_controller(),
+        config,
+        Rv32WeierstrassOpcode::default_offset(),
+        BigUint::zero(),
+    );
+    let offset = chip.0.core.air.offset;
+
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        to_limbs_vec(&[modulus.clone(), BigUint::one()]),
+        vec![],
+        offset + Rv32WeierstrassOpcode::SETUP_EC_DOUBLE as usize,
+    );
+    tester.execute(&mut chip, setup_instruction);
+
+    for point in ops {
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            to_limbs_vec(&point),
+            vec![],
+            offset + Rv32WeierstrassOpcode::EC_DOUBLE as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+        let output = rv32_read_heap_default::<NUM_LIMBS>(&mut tester, &instruction, 2);
+        let expected = ec_double_reference(&modulus, &point);
+        assert_eq!(output, to_limbs_vec(&expected), "EC_DOUBLE on {point:?}");
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+fn coord_strategy() -> BoxedStrategy<BigUint> {
+    field_element_strategy(secp256k1_coord_prime(), NUM_LIMBS, LIMB_BITS)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn prop_add_ne_matches_reference(
+        ops in prop::collection::vec(
+            [coord_strategy(), coord_strategy(), coord_strategy(), coord_strategy()]
+                .prop_filter("x1 != x2 mod p", |[x1, _, x2, _]| {
+                    let p = secp256k1_coord_prime();
+                    x1 % &p != x2 % &p
+                }),
+            1..6,
+        )
+    ) {
+        run_add_ne_prop_test(ops);
+    }
+
+    #[test]
+    fn prop_double_matches_reference(
+        ops in prop::collection::vec(
+            [coord_strategy(), coord_strategy()]
+                .prop_filter("y != 0 mod p", |[_, y]| !(y % secp256k1_coord_prime()).is_zero()),
+            1..6,
+        )
+    ) {
+        run_double_prop_test(ops);
+    }
+}
@@ -0,0 +1,73 @@
+use ecdsa::RecoveryId;
+use openvm_algebra_guest::IntMod;
+use openvm_keccak256_guest::keccak256;
+
+use super::SECP256K1_ORDER_BE;
+use crate::{ecdsa::VerifyingKey, weierstrass::WeierstrassPoint, Group};
+
+/// Length of the input of the `ecrecover` precompile: hash, `v`, `r` and `s`.
+pub const ECRECOVER_INPUT_LEN: usize = 128;
+
+/// Recovers the Ethereum address that signed `msg_hash`.
+///
+/// `sig` is `r || s` in big-endian bytes and `recid` is the parity of the `y` coordinate of the
+/// signature's nonce point, i.e. `v - 27`. Returns `None` if `recid` is not 0 or 1, if `r` or `s`
+/// is not in `[1, n)`, or if the recovered key is the identity. High `s` values are accepted, as
+/// by the EVM precompile.
+///
+/// The nonce point is decompressed with a hint, and a hint cannot prove that `r` is not the
+/// x-coordinate of a curve point, so such signatures make the guest panic instead of returning
+/// `None`.
+///
+/// The guest must set up the secp256k1 moduli and curve with `moduli_init!` and `sw_init!`.
+pub fn ecrecover(msg_hash: &[u8; 32], sig: &[u8; 64], recid: u8) -> Option<[u8; 20]> {
+    if recid > 1 {
+        return None;
+    }
+    let (r, s) = sig.split_at(32);
+    if !is_valid_scalar(r) || !is_valid_scalar(s) {
+        return None;
+    }
+    let recid = RecoveryId::from_byte(recid)?;
+    let key = VerifyingKey::<k256::Secp256k1>::recover_from_prehash_noverify(msg_hash, sig, recid);
+    let point = key.as_affine();
+    if point.is_identity() {
+        return None;
+    }
+
+    let mut encoded = [0u8; 64];
+    encoded[..32].copy_from_slice(&point.x().to_be_bytes());
+    encoded[32..].copy_from_slice(&point.y().to_be_bytes());
+    let hash = keccak256(&encoded);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Some(address)
+}
+
+/// The EVM `ecrecover` precompile at address `0x01`.
+///
+/// `input` is `hash || v || r || s` with 32-byte big-endian words, right-padded with zeros to
+/// [ECRECOVER_INPUT_LEN] bytes. Returns the signer's address left-padded to 32 bytes, or `None`
+/// when the precompile returns empty output.
+pub fn ecrecover_precompile(input: &[u8]) -> Option<[u8; 32]> {
+    let mut padded = [0u8; ECRECOVER_INPUT_LEN];
+    let len = input.len().min(ECRECOVER_INPUT_LEN);
+    padded[..len].copy_from_slice(&input[..len]);
+
+    // `v` must be a 32-byte big-endian integer equal to 27 or 28.
+    if padded[32..63].iter().any(|&b| b != 0) || !matches!(padded[63], 27 | 28) {
+        return None;
+    }
+    let msg_hash: &[u8; 32] = padded[..32].try_into().unwrap();
+    let sig: &[u8; 64] = padded[64..].try_into().unwrap();
+    let address = ecrecover(msg_hash, sig, padded[63] - 27)?;
+
+    let mut output = [0u8; 32];
+    output[12..].copy_from_slice(&address);
+    Some(output)
+}
+
+/// Whether the big-endian `scalar` is in `[1, n)`.
+fn is_valid_scalar(scalar: &[u8]) -> bool {
+    scalar.iter().any(|&b| b != 0) && scalar < SECP256K1_ORDER_BE.as_slice()
+}
@@ -10,6 +10,15 @@ use openvm_algebra_guest::IntMod;
 use super::group::{CyclicGroup, Group};
 use crate::weierstrass::{CachedMulTable, IntrinsicCurve};
 
+#[cfg(feature = "ecrecover")]
+mod ecrecover;
+#[cfg(feature = "ecrecover")]
+pub use ecrecover::*;
+#[cfg(feature = "schnorr")]
+mod schnorr;
+#[cfg(feature = "schnorr")]
+pub use schnorr::*;
+
 #[cfg(not(target_os = "zkvm"))]
 lazy_static! {
     pub static ref SECP256K1_MODULUS: BigUint = BigUint::from_bytes_be(&hex!(
@@ -20,6 +29,11 @@ lazy_static! {
     ));
 }
 
+/// Order of secp256k1 in big-endian bytes.
+#[cfg(any(feature = "ecrecover", feature = "schnorr"))]
+const SECP256K1_ORDER_BE: [u8; 32] =
+    hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+
 pub const SECP256K1_NUM_LIMBS: usize = 32;
 pub const SECP256K1_LIMB_BITS: usize = 8;
 pub const SECP256K1_BLOCK_SIZE: usize = 32;
@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+
+use hex_literal::hex;
+use openvm_algebra_guest::{IntMod, Reduce};
+use sha2::{Digest, Sha256};
+
+use super::{Secp256k1Coord, Secp256k1Point, Secp256k1Scalar, SECP256K1_ORDER_BE};
+use crate::{
+    weierstrass::{IntrinsicCurve, WeierstrassPoint},
+    CyclicGroup, Group,
+};
+
+/// Coordinate modulus of secp256k1 in big-endian bytes.
+const SECP256K1_MODULUS_BE: [u8; 32] =
+    hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+
+/// A BIP-340 signature to verify in a batch: an x-only public key, a message of any length and
+/// the 64-byte signature `r || s`.
+#[derive(Clone, Copy, Debug)]
+pub struct SchnorrBatchItem<'a> {
+    pub pubkey: &'a [u8; 32],
+    pub msg: &'a [u8],
+    pub sig: &'a [u8; 64],
+}
+
+/// The BIP-340 tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`, where `data` is the
+/// concatenation of `chunks`. SHA-256 runs in software.
+pub fn tagged_hash(tag: &[u8], chunks: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// Verifies a BIP-340 Schnorr signature `sig = r || s` of `msg` under the x-only `pubkey`.
+///
+/// Returns `false` if `pubkey` or `r` is not below the field modulus, if `s` is not below the
+/// group order, or if the signature does not verify. The public key is decompressed with a hint,
+/// which cannot prove that `pubkey` is not the x-coordinate of a curve point, so such keys make
+/// the guest panic instead of returning `false`.
+///
+/// The guest must set up the secp256k1 moduli and curve with `moduli_init!` and `sw_init!`.
+pub fn verify_schnorr(pubkey: &[u8; 32], msg: &[u8], sig: &[u8; 64]) -> bool {
+    let (r, s) = sig.split_at(32);
+    if !is_below(pubkey, &SECP256K1_MODULUS_BE)
+        || !is_below(r, &SECP256K1_MODULUS_BE)
+        || !is_below(s, &SECP256K1_ORDER_BE)
+    {
+        return false;
+    }
+    let point = lift_x(pubkey);
+    let s = Secp256k1Scalar::from_be_bytes(s);
+    let e = challenge(r, pubkey, msg);
+
+    // R = s * G - e * P
+    let nonce_point =
+        <::k256::Secp256k1 as IntrinsicCurve>::msm(&[s, -e], &[Secp256k1Point::GENERATOR, point]);
+    if nonce_point.is_identity() || !has_even_y(&nonce_point) {
+        return false;
+    }
+    nonce_point.x() == &Secp256k1Coord::from_be_bytes(r)
+}
+
+/// Verifies several BIP-340 signatures at once with the batch equation
+/// `(sum a_i s_i) G - sum a_i R_i - sum (a_i e_i) P_i = 0`, computed as a single MSM.
+///
+/// The coefficients are `a_1 = 1` and, for `i > 1`, the tagged hash `"BIP0340/batch"` of a seed
+/// and `i`, where the seed is the SHA-256 of all public keys, messages and signatures. This
+/// replaces the random coefficients of BIP-340, since a guest has no source of randomness that
+/// the prover does not control.
+///
+/// Returns `true` for an empty batch, and `false` if any input is out of range as in
+/// [verify_schnorr] or if the batch does not verify. Like [verify_schnorr], the guest panics if
+/// a public key or an `r` is not the x-coordinate of a curve point.
+pub fn verify_schnorr_batch(items: &[SchnorrBatchItem]) -> bool {
+    let mut seed = Sha256::new();
+    for item in items {
+        let (r, s) = item.sig.split_at(32);
+        if !is_below(item.pubkey, &SECP256K1_MODULUS_BE)
+            || !is_below(r, &SECP256K1_MODULUS_BE)
+            || !is_below(s, &SECP256K1_ORDER_BE)
+        {
+            return false;
+        }
+        seed.update(item.pubkey);
+        seed.update((item.msg.len() as u64).to_le_bytes());
+        seed.update(item.msg);
+        seed.update(item.sig);
+    }
+    let seed: [u8; 32] = seed.finalize().into();
+
+    let mut sum_s = Secp256k1Scalar::ZERO;
+    let mut coeffs = Vec::with_capacity(2 * items.len() + 1);
+    let mut bases = Vec::with_capacity(2 * items.len() + 1);
+    for (i, item) in items.iter().enumerate() {
+        let (r, s) = item.sig.split_at(32);
+        let a = if i == 0 {
+            Secp256k1Scalar::ONE
+        } else {
+            Secp256k1Scalar::reduce_be_bytes(&tagged_hash(
+                b"BIP0340/batch",
+                &[&seed, &(i as u32).to_le_bytes()],
+            ))
+        };
+        let e = challenge(r, item.pubkey, item.msg);
+        sum_s += &a * &Secp256k1Scalar::from_be_bytes(s);
+        coeffs.push(-(&a * &e));
+        bases.push(lift_x(item.pubkey));
+        coeffs.push(-a);
+        bases.push(lift_x(r.try_into().unwrap()));
+    }
+    coeffs.push(sum_s);
+    bases.push(Secp256k1Point::GENERATOR);
+
+    <::k256::Secp256k1 as IntrinsicCurve>::msm(&coeffs, &bases).is_identity()
+}
+
+/// `e = int(tagged_hash("BIP0340/challenge", r || P.x || msg)) mod n`
+fn challenge(r: &[u8], pubkey: &[u8; 32], msg: &[u8]) -> Secp256k1Scalar {
+    Secp256k1Scalar::reduce_be_bytes(&tagged_hash(b"BIP0340/challenge", &[r, pubkey, msg]))
+}
+
+/// The point with x-coordinate `x` and an even y-coordinate. `x` must be below the modulus.
+fn lift_x(x: &[u8; 32]) -> Secp256k1Point {
+    Secp256k1Point::decompress(Secp256k1Coord::from_be_bytes(x), &0)
+}
+
+fn has_even_y(point: &Secp256k1Point) -> bool {
+    // The parity is only meaningful for the canonical representative.
+    point.y().assert_unique();
+    point.y().as_le_bytes()[0] & 1 == 0
+}
+
+/// Whether the big-endian `bytes` are less than the big-endian `bound` of the same length.
+fn is_below(bytes: &[u8], bound: &[u8; 32]) -> bool {
+    bytes < bound.as_slice()
+}
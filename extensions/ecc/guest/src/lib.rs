@@ -43,3 +43,16 @@ pub enum SwBaseFunct7 {
 impl SwBaseFunct7 {
     pub const SHORT_WEIERSTRASS_MAX_KINDS: u8 = 8;
 }
+
+/// Entry that `sw_init!` places in the `.openvm` ELF section for each curve, so that the
+/// transpiler can check the curves of the guest against the VM config. The layout matches the
+/// modulus entries of `moduli_init!`: kind `2`, curve index, little-endian length of the
+/// coordinate modulus, and the coordinate modulus.
+#[doc(hidden)]
+#[repr(C)]
+pub struct SerializedCurve<Repr> {
+    pub kind: u8,
+    pub curve_idx: u8,
+    pub modulus_len: [u8; 4],
+    pub modulus: Repr,
+}
@@ -436,6 +436,7 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
     let mut externs = Vec::new();
     let mut setups = Vec::new();
     let mut setup_all_curves = Vec::new();
+    let mut openvm_section = Vec::new();
 
     let span = proc_macro::Span::call_site();
 
@@ -497,6 +498,25 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
             }
         });
 
+        let serialized_name =
+            syn::Ident::new(&format!("OPENVM_SERIALIZED_CURVE_{}", ec_idx), span.into());
+        let serialized_idx = ec_idx as u8;
+        openvm_section.push(quote::quote_spanned! { span.into() =>
+            #[cfg(target_os = "zkvm")]
+            #[link_section = ".openvm"]
+            #[no_mangle]
+            #[used]
+            static #serialized_name: ::openvm_ecc_guest::SerializedCurve<
+                <#item as openvm_algebra_guest::IntMod>::Repr,
+            > = ::openvm_ecc_guest::SerializedCurve {
+                kind: 2, // 2 for "curve"
+                curve_idx: #serialized_idx,
+                modulus_len: (<#item as openvm_algebra_guest::IntMod>::NUM_LIMBS as u32)
+                    .to_le_bytes(),
+                modulus: <#item as openvm_algebra_guest::IntMod>::MODULUS,
+            };
+        });
+
         let setup_function = syn::Ident::new(&format!("setup_sw_{}", str_path), span.into());
         setups.push(quote::quote_spanned! { span.into() =>
             #[allow(non_snake_case)]
@@ -548,6 +568,7 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
 
             #(#externs)*
         }
+        #(#openvm_section)*
         #(#setups)*
         pub fn setup_all_curves() {
             #(#setup_all_curves)*
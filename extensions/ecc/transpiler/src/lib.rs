@@ -1,10 +1,12 @@
 use openvm_ecc_guest::{SwBaseFunct7, OPCODE, SW_FUNCT3};
 use openvm_instructions::{
-    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, PhantomDiscriminant, UsizeOpcode,
-    VmOpcode,
+    builder::InstructionBuilder,
+    instruction::Instruction,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_NUM_LIMBS},
+    PhantomDiscriminant, UsizeOpcode, VmOpcode,
 };
 use openvm_instructions_derive::UsizeOpcode;
-use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_stark_backend::p3_field::{Field, PrimeField32};
 use openvm_transpiler::{util::from_r_type, TranspilerExtension};
 use rrs_lib::instruction_formats::RType;
 use strum::{EnumCount, EnumIter, FromRepr};
@@ -28,6 +30,74 @@ pub enum EccPhantom {
     HintDecompress = 0x40,
 }
 
+/// Builders for the short Weierstrass curve instructions, with the same operands as the
+/// transpiled instructions. `curve_idx` is the index of the curve in the extension config.
+/// The registers hold pointers to the points in memory.
+pub trait EccInstructionBuilder<F> {
+    /// Writes `[rs1] + [rs2]` to `[rd]`, for points with distinct x-coordinates.
+    fn ec_add_ne(rd: usize, rs1: usize, rs2: usize, curve_idx: usize) -> Instruction<F>;
+    /// Writes `2 * [rs1]` to `[rd]`.
+    fn ec_double(rd: usize, rs1: usize, curve_idx: usize) -> Instruction<F>;
+    /// Checks the curve parameters at `[rs1]` (and `[rs2]` for addition) before the first
+    /// addition or doubling on curve `curve_idx`.
+    fn ec_setup(
+        opcode: Rv32WeierstrassOpcode,
+        rd: usize,
+        rs1: usize,
+        rs2: usize,
+        curve_idx: usize,
+    ) -> Instruction<F>;
+}
+
+fn sw_opcode<F: Field>(local_opcode: Rv32WeierstrassOpcode, curve_idx: usize) -> VmOpcode {
+    InstructionBuilder::<F>::indexed_opcode(local_opcode, curve_idx, Rv32WeierstrassOpcode::COUNT)
+}
+
+impl<F: Field> EccInstructionBuilder<F> for InstructionBuilder<F> {
+    fn ec_add_ne(rd: usize, rs1: usize, rs2: usize, curve_idx: usize) -> Instruction<F> {
+        InstructionBuilder::r_type(
+            sw_opcode::<F>(Rv32WeierstrassOpcode::EC_ADD_NE, curve_idx),
+            rd,
+            rs1,
+            rs2,
+            RV32_MEMORY_AS,
+        )
+    }
+
+    fn ec_double(rd: usize, rs1: usize, curve_idx: usize) -> Instruction<F> {
+        InstructionBuilder::r_type(
+            sw_opcode::<F>(Rv32WeierstrassOpcode::EC_DOUBLE, curve_idx),
+            rd,
+            rs1,
+            0,
+            RV32_MEMORY_AS,
+        )
+    }
+
+    fn ec_setup(
+        opcode: Rv32WeierstrassOpcode,
+        rd: usize,
+        rs1: usize,
+        rs2: usize,
+        curve_idx: usize,
+    ) -> Instruction<F> {
+        assert!(
+            matches!(
+                opcode,
+                Rv32WeierstrassOpcode::SETUP_EC_ADD_NE | Rv32WeierstrassOpcode::SETUP_EC_DOUBLE
+            ),
+            "{opcode:?} is not a setup opcode"
+        );
+        InstructionBuilder::r_type(
+            sw_opcode::<F>(opcode, curve_idx),
+            rd,
+            rs1,
+            rs2,
+            RV32_MEMORY_AS,
+        )
+    }
+}
+
 #[derive(Default)]
 pub struct EccTranspilerExtension;
 
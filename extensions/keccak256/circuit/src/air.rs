@@ -16,28 +16,30 @@ use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
 use openvm_keccak256_transpiler::Rv32KeccakOpcode;
 use openvm_rv32im_circuit::adapters::abstract_compose;
 use openvm_stark_backend::{
-    air_builders::sub::SubAirBuilder,
     interaction::InteractionBuilder,
     p3_air::{Air, AirBuilder, BaseAir},
     p3_field::AbstractField,
     p3_matrix::Matrix,
     rap::{BaseAirWithPublicValues, PartitionedBaseAir},
 };
-use p3_keccak_air::{KeccakAir, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, U64_LIMBS};
 
 use super::{
     columns::{KeccakVmCols, NUM_KECCAK_VM_COLS},
-    KECCAK_ABSORB_READS, KECCAK_DIGEST_BYTES, KECCAK_DIGEST_WRITES, KECCAK_RATE_BYTES,
+    KeccakfBus, KECCAK_ABSORB_READS, KECCAK_DIGEST_BYTES, KECCAK_DIGEST_WRITES, KECCAK_RATE_BYTES,
     KECCAK_RATE_U16S, KECCAK_REGISTER_READS, KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE,
-    NUM_ABSORB_ROUNDS,
 };
 
+/// Sponge AIR of the keccak256 hasher. Each row absorbs one [KECCAK_RATE_BYTES] block and sends
+/// the keccak-f permutation of the absorbed state to the [KeccakfAir](super::KeccakfAir) on the
+/// [KeccakfBus], so the per-block columns are not repeated on the rounds of the permutation.
 #[derive(Clone, Copy, Debug, derive_new::new)]
 pub struct KeccakVmAir {
     pub execution_bridge: ExecutionBridge,
     pub memory_bridge: MemoryBridge,
     /// Bus to send 8-bit XOR requests to.
     pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Bus to send the keccak-f permutation of each block to.
+    pub keccakf_bus: KeccakfBus,
     /// Maximum number of bits allowed for an address pointer
     pub ptr_max_bits: usize,
     pub(super) offset: usize,
@@ -58,27 +60,19 @@ impl<AB: InteractionBuilder> Air<AB> for KeccakVmAir {
         let local: &KeccakVmCols<AB::Var> = (*local).borrow();
         let next: &KeccakVmCols<AB::Var> = (*next).borrow();
 
+        builder.assert_bool(local.instruction.is_enabled);
         builder.assert_bool(local.sponge.is_new_start);
-        builder.assert_eq(
-            local.sponge.is_new_start,
-            local.sponge.is_new_start * local.is_first_round(),
-        );
-        builder.assert_eq(
-            local.instruction.is_enabled_first_round,
-            local.instruction.is_enabled * local.is_first_round(),
-        );
         // Not strictly necessary:
         builder
             .when_first_row()
             .assert_one(local.sponge.is_new_start);
 
-        self.eval_keccak_f(builder);
         self.constrain_padding(builder, local, next);
-        self.constrain_consistency_across_rounds(builder, local, next);
 
         let mem = &local.mem_oc;
         // Interactions:
         self.constrain_absorb(builder, local, next);
+        self.eval_keccak_f(builder, local);
         let start_read_timestamp = self.eval_instruction(builder, local, &mem.register_aux);
         let start_write_timestamp =
             self.constrain_input_read(builder, local, start_read_timestamp, &mem.absorb_reads);
@@ -94,30 +88,19 @@ impl<AB: InteractionBuilder> Air<AB> for KeccakVmAir {
 }
 
 impl KeccakVmAir {
-    /// Evaluate the keccak-f permutation constraints.
-    ///
-    /// WARNING: The keccak-f AIR columns **must** be the first columns in the main AIR.
+    /// Sends the keccak-f permutation of `preimage` into `postimage` to the keccak-f AIR.
     #[inline]
-    pub fn eval_keccak_f<AB: AirBuilder>(&self, builder: &mut AB) {
-        let keccak_f_air = KeccakAir {};
-        let mut sub_builder =
-            SubAirBuilder::<AB, KeccakAir, AB::Var>::new(builder, 0..NUM_KECCAK_PERM_COLS);
-        keccak_f_air.eval(&mut sub_builder);
-    }
-
-    /// Many columns are expected to be the same between rounds and only change per-block.
-    pub fn constrain_consistency_across_rounds<AB: AirBuilder>(
+    pub fn eval_keccak_f<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
         local: &KeccakVmCols<AB::Var>,
-        next: &KeccakVmCols<AB::Var>,
     ) {
-        let mut transition_builder = builder.when_transition();
-        let mut round_builder = transition_builder.when(not(local.is_last_round()));
-        // Instruction columns
-        local
-            .instruction
-            .assert_eq(&mut round_builder, next.instruction);
+        self.keccakf_bus.send(
+            builder,
+            local.sponge.preimage,
+            local.sponge.postimage,
+            local.instruction.is_enabled,
+        );
     }
 
     pub fn constrain_block_transition<AB: AirBuilder>(
@@ -131,7 +114,7 @@ impl KeccakVmAir {
         // (this means it's not receiving a new opcode or starting a dummy block)
         // then we want _parts_ of opcode instruction to stay the same
         // between blocks.
-        let mut block_transition = builder.when(local.is_last_round() * not(next.is_new_start()));
+        let mut block_transition = builder.when(not::<AB::Expr>(next.is_new_start()));
         block_transition.assert_eq(local.instruction.is_enabled, next.instruction.is_enabled);
         // dst is only going to be used for writes in the last input block
         assert_array_eq(
@@ -167,8 +150,8 @@ impl KeccakVmAir {
     /// See Section 5.1 of https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
     /// Note this is the ONLY difference between Keccak and SHA-3
     ///
-    /// Constrains padding constraints and length between rounds and
-    /// between blocks. Padding logic is tied to constraints on `is_new_start`.
+    /// Constrains padding constraints and length between blocks. Padding logic is tied to
+    /// constraints on `is_new_start`.
     pub fn constrain_padding<AB: AirBuilder>(
         &self,
         builder: &mut AB,
@@ -189,17 +172,6 @@ impl KeccakVmAir {
                 .when(is_padding_byte[i - 1])
                 .assert_one(is_padding_byte[i]);
         }
-        // is_padding_byte must stay the same on all rounds in a block
-        // we use next instead of local.step_flags.last() because the last row of the trace overall may not
-        // end on a last round
-        let is_last_round = next.inner.step_flags[0];
-        let is_not_last_round = not(is_last_round);
-        for i in 0..KECCAK_RATE_BYTES {
-            builder.when(is_not_last_round.clone()).assert_eq(
-                local.sponge.is_padding_byte[i],
-                next.sponge.is_padding_byte[i],
-            );
-        }
 
         let num_padding_bytes = local
             .sponge
@@ -208,7 +180,7 @@ impl KeccakVmAir {
             .fold(AB::Expr::ZERO, |a, &b| a + b);
 
         // If final rate block of input, then last byte must be padding
-        let is_final_block = is_padding_byte[KECCAK_RATE_BYTES - 1];
+        let is_final_block = local.is_final_block();
 
         // is_padding_byte must be consistent with remaining_len
         builder.when(is_final_block).assert_eq(
@@ -217,27 +189,17 @@ impl KeccakVmAir {
         );
         // If this block is not final, when transitioning to next block, remaining len
         // must decrease by `KECCAK_RATE_BYTES`.
-        builder
-            .when(is_last_round)
-            .when(not(is_final_block))
-            .assert_eq(
-                remaining_len - AB::F::from_canonical_usize(KECCAK_RATE_BYTES),
-                next.remaining_len(),
-            );
+        builder.when(not::<AB::Expr>(is_final_block)).assert_eq(
+            remaining_len - AB::F::from_canonical_usize(KECCAK_RATE_BYTES),
+            next.remaining_len(),
+        );
         // To enforce that is_padding_byte must be set appropriately for an input, we require
         // the block before a new start to have padding
-        builder
-            .when(is_last_round)
-            .when(next.is_new_start())
-            .assert_one(is_final_block);
+        builder.when(next.is_new_start()).assert_one(is_final_block);
         // Make sure there are not repeated padding blocks
-        builder
-            .when(is_last_round)
-            .when(is_final_block)
-            .assert_one(next.is_new_start());
+        builder.when(is_final_block).assert_one(next.is_new_start());
         // The chain above enforces that for an input, the remaining length must decrease by RATE
         // block-by-block until it reaches a final block with padding.
-
         // ====== Constrain the block_bytes are padded according to is_padding_byte =====
 
         // If the first padding byte is at the end of the block, then the block has a
@@ -287,17 +249,17 @@ impl KeccakVmAir {
     }
 
     /// Constrain state transition between keccak-f permutations is valid absorb of input bytes.
-    /// The end-state in last round is given by `a_prime_prime_prime()` in `u16` limbs.
+    /// The end-state of a block is given by `postimage` in `u16` limbs.
     /// The pre-state is given by `preimage` also in `u16` limbs.
     /// The input `block_bytes` will be given as **bytes**.
     ///
-    /// We will XOR `block_bytes` with `a_prime_prime_prime()` and constrain to be `next.preimage`.
+    /// We will XOR `next.block_bytes` with `postimage` and constrain to be `next.preimage`.
     /// This will be done using 8-bit XOR lookup in a separate AIR via interactions.
     /// This will require decomposing `u16` into bytes.
     /// Note that the XOR lookup automatically range checks its inputs to be bytes.
     ///
-    /// We use the following trick to keep `u16` limbs and avoid changing
-    /// the `keccak-f` AIR itself:
+    /// We use the following trick to keep `u16` limbs, which are the limbs of the
+    /// `keccak-f` AIR:
     /// if we already have a 16-bit limb `x` and we also provide a 8-bit limb
     /// `hi = x >> 8`, assuming `x` and `hi` have been range checked,
     /// we can use the expression `lo = x - hi * 256` for the low byte.
@@ -311,32 +273,14 @@ impl KeccakVmAir {
         local: &KeccakVmCols<AB::Var>,
         next: &KeccakVmCols<AB::Var>,
     ) {
-        let updated_state_bytes = (0..NUM_ABSORB_ROUNDS).flat_map(|i| {
-            let y = i / 5;
-            let x = i % 5;
-            (0..U64_LIMBS).flat_map(move |limb| {
-                let state_limb = local.postimage(y, x, limb);
-                let hi = local.sponge.state_hi[i * U64_LIMBS + limb];
-                let lo = state_limb - hi * AB::F::from_canonical_u64(1 << 8);
-                // Conversion from bytes to u64 is little-endian
-                [lo, hi.into()]
-            })
-        });
-
-        let post_absorb_state_bytes = (0..NUM_ABSORB_ROUNDS).flat_map(|i| {
-            let y = i / 5;
-            let x = i % 5;
-            (0..U64_LIMBS).flat_map(move |limb| {
-                let state_limb = next.inner.preimage[y][x][limb];
-                let hi = next.sponge.state_hi[i * U64_LIMBS + limb];
-                let lo = state_limb - hi * AB::F::from_canonical_u64(1 << 8);
-                [lo, hi.into()]
-            })
-        });
+        let updated_state_bytes =
+            rate_bytes::<AB>(&local.sponge.postimage, &local.sponge.postimage_hi);
+        let post_absorb_state_bytes =
+            rate_bytes::<AB>(&next.sponge.preimage, &next.sponge.preimage_hi);
 
-        // We xor on last round of each block, even if it is a final block,
+        // We xor on each block, even if it is a final block,
         // because we use xor to range check the output bytes (= updated_state_bytes)
-        let is_final_block = *local.sponge.is_padding_byte.last().unwrap();
+        let is_final_block = local.is_final_block();
         for (input, prev, post) in izip!(
             next.sponge.block_bytes,
             updated_state_bytes,
@@ -355,23 +299,12 @@ impl KeccakVmAir {
                     prev.clone(),
                     select(is_final_block, prev, post),
                 )
-                .eval(
-                    builder,
-                    local.is_last_round() * local.instruction.is_enabled,
-                );
+                .eval(builder, local.instruction.is_enabled);
         }
 
         // We separately constrain that when(local.is_new_start), the preimage (u16s) equals the block bytes
-        let local_preimage_bytes = (0..NUM_ABSORB_ROUNDS).flat_map(|i| {
-            let y = i / 5;
-            let x = i % 5;
-            (0..U64_LIMBS).flat_map(move |limb| {
-                let state_limb = local.inner.preimage[y][x][limb];
-                let hi = local.sponge.state_hi[i * U64_LIMBS + limb];
-                let lo = state_limb - hi * AB::F::from_canonical_u64(1 << 8);
-                [lo, hi.into()]
-            })
-        });
+        let local_preimage_bytes =
+            rate_bytes::<AB>(&local.sponge.preimage, &local.sponge.preimage_hi);
         let mut when_is_new_start =
             builder.when(local.is_new_start() * local.instruction.is_enabled);
         for (preimage_byte, block_byte) in zip(local_preimage_bytes, local.sponge.block_bytes) {
@@ -380,18 +313,15 @@ impl KeccakVmAir {
 
         // constrain transition on the state outside rate
         let mut reset_builder = builder.when(local.is_new_start());
-        for i in KECCAK_RATE_U16S..KECCAK_WIDTH_U16S {
-            let y = i / U64_LIMBS / 5;
-            let x = (i / U64_LIMBS) % 5;
-            let limb = i % U64_LIMBS;
-            reset_builder.assert_zero(local.inner.preimage[y][x][limb]);
+        for &limb in &local.sponge.preimage[KECCAK_RATE_U16S..] {
+            reset_builder.assert_zero(limb);
         }
-        let mut absorb_builder = builder.when(local.is_last_round() * not(next.is_new_start()));
-        for i in KECCAK_RATE_U16S..KECCAK_WIDTH_U16S {
-            let y = i / U64_LIMBS / 5;
-            let x = (i / U64_LIMBS) % 5;
-            let limb = i % U64_LIMBS;
-            absorb_builder.assert_eq(local.postimage(y, x, limb), next.inner.preimage[y][x][limb]);
+        let mut absorb_builder = builder.when(not::<AB::Expr>(next.is_new_start()));
+        for (&post, &pre) in zip(
+            &local.sponge.postimage[KECCAK_RATE_U16S..],
+            &next.sponge.preimage[KECCAK_RATE_U16S..],
+        ) {
+            absorb_builder.assert_eq(post, pre);
         }
     }
 
@@ -518,8 +448,7 @@ impl KeccakVmAir {
     ) -> AB::Expr {
         let partial_block = &local.mem_oc.partial_block;
         // Only read input from memory when it is an opcode-related row
-        // and only on the first round of block
-        let is_input = local.instruction.is_enabled_first_round;
+        let is_input = local.instruction.is_enabled;
 
         let mut timestamp = start_read_timestamp;
         // read `state` into `word[src + ...]_e`
@@ -580,26 +509,13 @@ impl KeccakVmAir {
     ) {
         let instruction = local.instruction;
 
-        let is_final_block = *local.sponge.is_padding_byte.last().unwrap();
-        // since keccak-f AIR has this column, we might as well use it
-        builder.assert_eq(
-            local.inner.export,
-            instruction.is_enabled * is_final_block * local.is_last_round(),
-        );
+        // The digest is written after the final block
+        let is_digest = instruction.is_enabled * local.is_final_block();
         // See `constrain_absorb` on how we derive the postimage bytes from u16 limbs
         // **SAFETY:** we always XOR the final state with 0 in `constrain_absorb`,
         // so the output bytes **are** range checked.
-        let updated_state_bytes = (0..NUM_ABSORB_ROUNDS).flat_map(|i| {
-            let y = i / 5;
-            let x = i % 5;
-            (0..U64_LIMBS).flat_map(move |limb| {
-                let state_limb = local.postimage(y, x, limb);
-                let hi = local.sponge.state_hi[i * U64_LIMBS + limb];
-                let lo = state_limb - hi * AB::F::from_canonical_u64(1 << 8);
-                // Conversion from bytes to u64 is little-endian
-                [lo, hi.into()]
-            })
-        });
+        let updated_state_bytes =
+            rate_bytes::<AB>(&local.sponge.postimage, &local.sponge.postimage_hi);
         let dst = abstract_compose::<AB::Expr, _>(instruction.dst);
         for (i, digest_bytes) in updated_state_bytes
             .take(KECCAK_DIGEST_BYTES)
@@ -619,7 +535,7 @@ impl KeccakVmAir {
                     timestamp,
                     &mem_aux[i],
                 )
-                .eval(builder, local.inner.export)
+                .eval(builder, is_digest.clone())
         }
     }
 
@@ -635,3 +551,16 @@ impl KeccakVmAir {
             )
     }
 }
+
+/// Little-endian bytes of the rate part of a state given as `u16` limbs, with the most
+/// significant byte of each limb in `hi`.
+fn rate_bytes<'a, AB: AirBuilder>(
+    limbs: &'a [AB::Var; KECCAK_WIDTH_U16S],
+    hi: &'a [AB::Var; KECCAK_RATE_U16S],
+) -> impl Iterator<Item = AB::Expr> + 'a {
+    zip(limbs, hi).flat_map(|(&limb, &hi)| {
+        let lo = limb - hi * AB::F::from_canonical_u64(1 << 8);
+        // Conversion from bytes to u16 is little-endian
+        [lo, hi.into()]
+    })
+}
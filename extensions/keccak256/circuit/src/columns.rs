@@ -1,22 +1,20 @@
 use core::mem::size_of;
 
 use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
-use openvm_circuit_primitives::utils::assert_array_eq;
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
-use openvm_stark_backend::p3_air::AirBuilder;
-use p3_keccak_air::KeccakCols as KeccakPermCols;
 
 use super::{
     KECCAK_ABSORB_READS, KECCAK_DIGEST_WRITES, KECCAK_RATE_BYTES, KECCAK_RATE_U16S,
-    KECCAK_REGISTER_READS, KECCAK_WORD_SIZE,
+    KECCAK_REGISTER_READS, KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE,
 };
 
+/// Columns of one row of the [KeccakVmAir](super::KeccakVmAir), which absorbs one
+/// [KECCAK_RATE_BYTES] block of input. The keccak-f permutation of the block is proven by the
+/// [KeccakfAir](super::KeccakfAir).
 #[repr(C)]
 #[derive(Debug, AlignedBorrow)]
 pub struct KeccakVmCols<T> {
-    /// Columns for keccak-f permutation
-    pub inner: KeccakPermCols<T>,
     /// Columns for sponge and padding
     pub sponge: KeccakSpongeCols<T>,
     /// Columns for instruction interface and register access
@@ -36,9 +34,6 @@ pub struct KeccakInstructionCols<T> {
     /// True for all rows that are part of opcode execution.
     /// False on dummy rows only used to pad the height.
     pub is_enabled: T,
-    /// Is enabled and first round of block. Used to lower constraint degree.
-    /// is_enabled * inner.step_flags[0]
-    pub is_enabled_first_round: T,
     /// The starting timestamp to use for memory access in this row.
     /// A single row will do multiple memory accesses.
     pub start_timestamp: T,
@@ -72,9 +67,8 @@ pub struct KeccakInstructionCols<T> {
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
 pub struct KeccakSpongeCols<T> {
-    /// Only used on first row of a round to determine whether the state
-    /// prior to absorb should be reset to all 0s.
-    /// Constrained to be zero if not first round.
+    /// Whether the state prior to absorb should be reset to all 0s, i.e. whether this is the
+    /// first block of an input.
     pub is_new_start: T,
 
     /// Whether the current byte is a padding byte.
@@ -86,11 +80,17 @@ pub struct KeccakSpongeCols<T> {
     /// bytes.
     pub block_bytes: [T; KECCAK_RATE_BYTES],
 
-    /// For each of the first [KECCAK_RATE_U16S] `u16` limbs in the state,
-    /// the most significant byte of the limb.
-    /// Here `state` is the postimage state if last round and the preimage
-    /// state if first round. It can be junk if not first or last round.
-    pub state_hi: [T; KECCAK_RATE_U16S],
+    /// State after absorbing `block_bytes`, as `u16` limbs. This is the input of the keccak-f
+    /// permutation of the block.
+    pub preimage: [T; KECCAK_WIDTH_U16S],
+    /// For each of the first [KECCAK_RATE_U16S] `u16` limbs of `preimage`, the most significant
+    /// byte of the limb.
+    pub preimage_hi: [T; KECCAK_RATE_U16S],
+    /// State after the keccak-f permutation of the block, as `u16` limbs.
+    pub postimage: [T; KECCAK_WIDTH_U16S],
+    /// For each of the first [KECCAK_RATE_U16S] `u16` limbs of `postimage`, the most significant
+    /// byte of the limb.
+    pub postimage_hi: [T; KECCAK_RATE_U16S],
 }
 
 #[repr(C)]
@@ -117,35 +117,8 @@ impl<T: Copy> KeccakVmCols<T> {
         self.sponge.is_new_start
     }
 
-    pub fn postimage(&self, y: usize, x: usize, limb: usize) -> T {
-        self.inner.a_prime_prime_prime(y, x, limb)
-    }
-
-    pub fn is_first_round(&self) -> T {
-        *self.inner.step_flags.first().unwrap()
-    }
-
-    pub fn is_last_round(&self) -> T {
-        *self.inner.step_flags.last().unwrap()
-    }
-}
-
-impl<T: Copy> KeccakInstructionCols<T> {
-    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
-    where
-        T: Into<AB::Expr>,
-    {
-        builder.assert_eq(self.is_enabled, other.is_enabled);
-        builder.assert_eq(self.start_timestamp, other.start_timestamp);
-        builder.assert_eq(self.dst_ptr, other.dst_ptr);
-        builder.assert_eq(self.src_ptr, other.src_ptr);
-        builder.assert_eq(self.len_ptr, other.len_ptr);
-        builder.assert_eq(self.e, other.e);
-        assert_array_eq(builder, self.dst, other.dst);
-        assert_array_eq(builder, self.src_limbs, other.src_limbs);
-        builder.assert_eq(self.src, other.src);
-        assert_array_eq(builder, self.len_limbs, other.len_limbs);
-        builder.assert_eq(self.remaining_len, other.remaining_len);
+    pub fn is_final_block(&self) -> T {
+        *self.sponge.is_padding_byte.last().unwrap()
     }
 }
 
@@ -0,0 +1,317 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::{Arc, Mutex},
+};
+
+use openvm_circuit::{
+    arch::hasher::{Hasher, HasherChip},
+    system::memory::{merkle::DirectCompressionBus, CHUNK},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+use super::{utils::keccak_f, KeccakfBus, KeccakfChip, KECCAK_RATE_BYTES, KECCAK_WIDTH_U16S};
+
+/// Number of field elements of the two children compressed together.
+const NUM_INPUTS: usize = 2 * CHUNK;
+/// Index of the `u16` limb of the keccak-f state with the first padding byte, `0x01`.
+const PAD_START_LIMB: usize = NUM_INPUTS * 4 / 2;
+/// Index of the `u16` limb of the keccak-f state with the last padding byte, `0x80`.
+const PAD_END_LIMB: usize = KECCAK_RATE_BYTES / 2 - 1;
+
+/// Keccak-256 compression of the memory Merkle tree, see
+/// [MemoryMerkleHasher::Keccak](openvm_circuit::arch::MemoryMerkleHasher::Keccak). The two
+/// children are `2 * CHUNK` field elements of which the little-endian bytes of the canonical
+/// values are hashed, and the `i`-th element of the parent is the `i`-th little-endian `u32` word
+/// of the digest reduced modulo the field characteristic.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeccakMerkleHasher;
+
+impl<F: PrimeField32> Hasher<CHUNK, F> for KeccakMerkleHasher {
+    fn compress(&self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK] {
+        let input = array::from_fn(|i| {
+            if i < CHUNK {
+                left[i].as_canonical_u32()
+            } else {
+                right[i - CHUNK].as_canonical_u32()
+            }
+        });
+        compress_words(&input).map(|word| F::from_canonical_u32(word % F::ORDER_U32))
+    }
+}
+
+/// The keccak-f state absorbing the padded single block of `input`.
+fn padded_state(input: &[u32; NUM_INPUTS]) -> [u64; 25] {
+    let mut state = [0; 25];
+    for (lane, words) in state.iter_mut().zip(input.chunks_exact(2)) {
+        *lane = words[0] as u64 | (words[1] as u64) << 32;
+    }
+    state[PAD_START_LIMB / 4] = 0x01;
+    state[PAD_END_LIMB / 4] = 0x80 << 56;
+    state
+}
+
+/// The first `CHUNK` little-endian `u32` words of the Keccak-256 digest of `input`.
+fn compress_words(input: &[u32; NUM_INPUTS]) -> [u32; CHUNK] {
+    let postimage = keccak_f(padded_state(input));
+    array::from_fn(|i| (postimage[i / 2] >> (32 * (i % 2))) as u32)
+}
+
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct KeccakCompressionCols<T> {
+    pub is_valid: T,
+    /// The two children, `left || right`.
+    pub input: [T; NUM_INPUTS],
+    /// Little-endian bytes of the canonical values of `input`.
+    pub input_bytes: [[T; 4]; NUM_INPUTS],
+    /// Whether the most significant byte of an input is that of the largest field element, in
+    /// which case the other bytes are zero.
+    pub input_is_max: [T; NUM_INPUTS],
+    /// The keccak-f state after the permutation of the padded input, as `u16` limbs.
+    pub postimage: [T; KECCAK_WIDTH_U16S],
+}
+
+/// Receives the compressions of the memory Merkle tree on the [DirectCompressionBus] and proves
+/// them with one keccak-f permutation each, sent on the [KeccakfBus].
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakCompressionAir {
+    pub compression_bus: DirectCompressionBus,
+    pub keccakf_bus: KeccakfBus,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Most significant byte of the largest field element, whose other bytes must be zero.
+    pub max_top_byte: u32,
+}
+
+impl KeccakCompressionAir {
+    /// Only supports fields whose largest element is a multiple of `2^24`, such as BabyBear.
+    pub fn new(
+        compression_bus: DirectCompressionBus,
+        keccakf_bus: KeccakfBus,
+        bitwise_lookup_bus: BitwiseOperationLookupBus,
+        modulus: u32,
+    ) -> Self {
+        assert_eq!(
+            (modulus - 1) % (1 << 24),
+            0,
+            "the largest field element must be a multiple of 2^24"
+        );
+        Self {
+            compression_bus,
+            keccakf_bus,
+            bitwise_lookup_bus,
+            max_top_byte: (modulus - 1) >> 24,
+        }
+    }
+}
+
+impl<F> BaseAirWithPublicValues<F> for KeccakCompressionAir {}
+impl<F> PartitionedBaseAir<F> for KeccakCompressionAir {}
+impl<F> BaseAir<F> for KeccakCompressionAir {
+    fn width(&self) -> usize {
+        KeccakCompressionCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for KeccakCompressionAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &KeccakCompressionCols<AB::Var> = (*local).borrow();
+        builder.assert_bool(local.is_valid);
+
+        // The bytes are those of the canonical value: if the most significant byte is not that of
+        // the largest element, it is smaller.
+        let byte_base = AB::F::from_canonical_u32(1 << 8);
+        let mut range_checks: Vec<AB::Expr> = Vec::with_capacity(5 * NUM_INPUTS);
+        for ((&input, bytes), &is_max) in local
+            .input
+            .iter()
+            .zip(&local.input_bytes)
+            .zip(&local.input_is_max)
+        {
+            let value = bytes
+                .iter()
+                .rev()
+                .fold(AB::Expr::ZERO, |acc, &byte| acc * byte_base + byte);
+            builder.assert_eq(input, value);
+            builder.assert_bool(is_max);
+            builder
+                .when(is_max)
+                .assert_eq(bytes[3], AB::F::from_canonical_u32(self.max_top_byte));
+            for &byte in &bytes[..3] {
+                builder.when(is_max).assert_zero(byte);
+            }
+            range_checks.extend(bytes.iter().map(|&byte| byte.into()));
+            range_checks
+                .push(AB::Expr::from_canonical_u32(self.max_top_byte - 1) + is_max - bytes[3]);
+        }
+        for pair in range_checks.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0].clone(), pair[1].clone())
+                .eval(builder, local.is_valid);
+        }
+
+        let preimage: [AB::Expr; KECCAK_WIDTH_U16S] = array::from_fn(|limb| {
+            if limb < PAD_START_LIMB {
+                let bytes = &local.input_bytes[limb / 2];
+                let low = 2 * (limb % 2);
+                bytes[low] + bytes[low + 1] * byte_base
+            } else if limb == PAD_START_LIMB {
+                AB::Expr::ONE
+            } else if limb == PAD_END_LIMB {
+                AB::Expr::from_canonical_u32(0x80 << 8)
+            } else {
+                AB::Expr::ZERO
+            }
+        });
+        self.keccakf_bus
+            .send(builder, preimage, local.postimage, local.is_valid);
+
+        let limb_base = AB::F::from_canonical_u32(1 << 16);
+        let parent = array::from_fn::<_, CHUNK, _>(|i| {
+            local.postimage[2 * i] + local.postimage[2 * i + 1] * limb_base
+        });
+        builder.push_receive(
+            self.compression_bus.0,
+            local.input.into_iter().map(Into::into).chain(parent),
+            local.is_valid,
+        );
+    }
+}
+
+/// Proves the compressions of the memory Merkle tree with the Keccak-256 hasher. Its records are
+/// made by a [KeccakCompressionRecorder], which the memory controller finalizes persistent memory
+/// with.
+#[derive(Debug)]
+pub struct KeccakCompressionChip {
+    pub air: KeccakCompressionAir,
+    /// Canonical values of the inputs of the compressions.
+    records: Mutex<Vec<[u32; NUM_INPUTS]>>,
+    keccakf_chip: Arc<KeccakfChip>,
+    bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+impl KeccakCompressionChip {
+    pub fn new(
+        compression_bus: DirectCompressionBus,
+        keccakf_chip: Arc<KeccakfChip>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        modulus: u32,
+    ) -> Self {
+        Self {
+            air: KeccakCompressionAir::new(
+                compression_bus,
+                keccakf_chip.bus(),
+                bitwise_lookup_chip.bus(),
+                modulus,
+            ),
+            records: Mutex::new(Vec::new()),
+            keccakf_chip,
+            bitwise_lookup_chip,
+        }
+    }
+
+    fn record(&self, input: [u32; NUM_INPUTS]) {
+        let mut range_checks = Vec::with_capacity(5 * NUM_INPUTS);
+        for value in input {
+            let bytes = value.to_le_bytes().map(u32::from);
+            let is_max = bytes[3] == self.air.max_top_byte;
+            range_checks.extend(bytes);
+            range_checks.push(self.air.max_top_byte - 1 + is_max as u32 - bytes[3]);
+        }
+        for pair in range_checks.chunks_exact(2) {
+            self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+        }
+        self.keccakf_chip.request_permutation(padded_state(&input));
+        self.records.lock().unwrap().push(input);
+    }
+}
+
+/// The [KeccakMerkleHasher] recording its compressions in a [KeccakCompressionChip].
+pub struct KeccakCompressionRecorder(pub Arc<KeccakCompressionChip>);
+
+impl<F: PrimeField32> Hasher<CHUNK, F> for KeccakCompressionRecorder {
+    fn compress(&self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK] {
+        KeccakMerkleHasher.compress(left, right)
+    }
+}
+
+impl<F: PrimeField32> HasherChip<CHUNK, F> for KeccakCompressionRecorder {
+    fn compress_and_record(&mut self, left: &[F; CHUNK], right: &[F; CHUNK]) -> [F; CHUNK] {
+        let input = array::from_fn(|i| {
+            if i < CHUNK {
+                left[i].as_canonical_u32()
+            } else {
+                right[i - CHUNK].as_canonical_u32()
+            }
+        });
+        self.0.record(input);
+        KeccakMerkleHasher.compress(left, right)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for KeccakCompressionChip
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let width = self.trace_width();
+        let records = self.records.into_inner().unwrap();
+        let height = records.len().next_power_of_two();
+        let mut values = Val::<SC>::zero_vec(height * width);
+        values
+            .par_chunks_mut(width)
+            .zip(records.into_par_iter())
+            .for_each(|(row, input)| {
+                let cols: &mut KeccakCompressionCols<Val<SC>> = row.borrow_mut();
+                cols.is_valid = Val::<SC>::ONE;
+                for (i, value) in input.into_iter().enumerate() {
+                    let bytes = value.to_le_bytes();
+                    cols.input[i] = Val::<SC>::from_canonical_u32(value);
+                    cols.input_bytes[i] = bytes.map(Val::<SC>::from_canonical_u8);
+                    cols.input_is_max[i] =
+                        Val::<SC>::from_bool(bytes[3] as u32 == self.air.max_top_byte);
+                }
+                let postimage = keccak_f(padded_state(&input));
+                for (limb, cell) in cols.postimage.iter_mut().enumerate() {
+                    let bits = (postimage[limb / 4] >> (16 * (limb % 4))) as u16;
+                    *cell = Val::<SC>::from_canonical_u16(bits);
+                }
+            });
+        AirProofInput::simple_no_pis(air, RowMajorMatrix::new(values, width))
+    }
+}
+
+impl ChipUsageGetter for KeccakCompressionChip {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    fn trace_width(&self) -> usize {
+        KeccakCompressionCols::<u8>::width()
+    }
+}
@@ -1,13 +1,12 @@
 use derive_more::derive::From;
 use openvm_circuit::{
     arch::{
-        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
-        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+        MemoryMerkleHasher, SystemConfig, SystemExecutor, SystemPeriphery, SystemPort,
+        VmChipComplex, VmConfig, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
     },
     system::phantom::PhantomChip,
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
-use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupBus;
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::*;
 use openvm_rv32im_circuit::{
@@ -57,6 +56,8 @@ pub enum Keccak256Executor<F: PrimeField32> {
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Keccak256Periphery<F: PrimeField32> {
     BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    Keccakf(Arc<KeccakfChip>),
+    KeccakCompression(Arc<KeccakCompressionChip>),
     Phantom(PhantomChip<F>),
 }
 
@@ -74,23 +75,39 @@ impl<F: PrimeField32> VmExtension<F> for Keccak256 {
             program_bus,
             memory_controller,
         } = builder.system_port();
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+        let keccakf_chip = Arc::new(KeccakfChip::new(KeccakfBus(builder.new_bus_idx())));
+        inventory.add_periphery_chip(keccakf_chip.clone());
+
+        let system_config = builder.system_config();
+        if system_config.continuation_enabled
+            && system_config.memory_config.merkle_hasher == MemoryMerkleHasher::Keccak
         {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
+            // Added after the keccak-f chip so that it is generated first, dropping its reference
+            // to the keccak-f chip.
+            let compression_bus = memory_controller
+                .borrow()
+                .interface_chip
+                .compression_bus()
+                .expect("persistent memory has a compression bus");
+            let compression_chip = Arc::new(KeccakCompressionChip::new(
+                compression_bus,
+                keccakf_chip.clone(),
+                bitwise_lu_chip.clone(),
+                F::ORDER_U32,
+            ));
+            inventory.add_periphery_chip(compression_chip.clone());
+            memory_controller
+                .borrow_mut()
+                .set_memory_hasher(Box::new(KeccakCompressionRecorder(compression_chip)));
+        }
 
         let keccak_chip = KeccakVmChip::new(
             execution_bus,
             program_bus,
             memory_controller,
             bitwise_lu_chip,
+            keccakf_chip,
             Rv32KeccakOpcode::default_offset(),
         );
         inventory.add_executor(
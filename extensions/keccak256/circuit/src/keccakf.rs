@@ -0,0 +1,187 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::{Arc, Mutex},
+};
+
+use openvm_circuit_primitives::utils::not;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+use p3_keccak_air::{generate_trace_rows, KeccakAir, KeccakCols, NUM_KECCAK_COLS, NUM_ROUNDS};
+
+use super::KECCAK_WIDTH_U16S;
+
+/// Bus of the keccak-f permutations of the absorbed blocks. A permutation of `preimage` into
+/// `postimage` has the fields `preimage || postimage`, each as [KECCAK_WIDTH_U16S] `u16` limbs
+/// where the limbs of the `u64` lane `x + 5 * y` start at index `4 * (x + 5 * y)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeccakfBus(pub usize);
+
+impl KeccakfBus {
+    pub fn send<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        preimage: [impl Into<AB::Expr>; KECCAK_WIDTH_U16S],
+        postimage: [impl Into<AB::Expr>; KECCAK_WIDTH_U16S],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_send(
+            self.0,
+            preimage
+                .into_iter()
+                .map(Into::into)
+                .chain(postimage.into_iter().map(Into::into)),
+            count,
+        );
+    }
+
+    pub fn receive<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        preimage: [impl Into<AB::Expr>; KECCAK_WIDTH_U16S],
+        postimage: [impl Into<AB::Expr>; KECCAK_WIDTH_U16S],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_receive(
+            self.0,
+            preimage
+                .into_iter()
+                .map(Into::into)
+                .chain(postimage.into_iter().map(Into::into)),
+            count,
+        );
+    }
+}
+
+/// The keccak-f AIR of Plonky3, which does one permutation every [NUM_ROUNDS] rows, receiving
+/// the permutations marked as exported on the [KeccakfBus].
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct KeccakfAir {
+    pub bus: KeccakfBus,
+}
+
+impl<F> BaseAirWithPublicValues<F> for KeccakfAir {}
+impl<F> PartitionedBaseAir<F> for KeccakfAir {}
+impl<F> BaseAir<F> for KeccakfAir {
+    fn width(&self) -> usize {
+        NUM_KECCAK_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for KeccakfAir {
+    fn eval(&self, builder: &mut AB) {
+        KeccakAir {}.eval(builder);
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &KeccakCols<AB::Var> = (*local).borrow();
+
+        // The postimage is only in `a_prime_prime_prime` on the last round.
+        let is_last_round = local.step_flags[NUM_ROUNDS - 1];
+        builder.assert_bool(local.export);
+        builder
+            .when(not::<AB::Expr>(is_last_round))
+            .assert_zero(local.export);
+
+        let lane = |i: usize| (i / 4 / 5, (i / 4) % 5, i % 4);
+        self.bus.receive(
+            builder,
+            std::array::from_fn::<_, KECCAK_WIDTH_U16S, _>(|i| {
+                let (y, x, limb) = lane(i);
+                local.preimage[y][x][limb]
+            }),
+            std::array::from_fn::<_, KECCAK_WIDTH_U16S, _>(|i| {
+                let (y, x, limb) = lane(i);
+                local.a_prime_prime_prime(y, x, limb)
+            }),
+            local.export,
+        );
+    }
+}
+
+/// Proves the keccak-f permutations requested by the [KeccakVmChip](super::KeccakVmChip), one
+/// per absorbed block.
+#[derive(Debug)]
+pub struct KeccakfChip {
+    pub air: KeccakfAir,
+    records: Mutex<KeccakfRecords>,
+}
+
+#[derive(Debug, Default)]
+struct KeccakfRecords {
+    preimages: Vec<[u64; 25]>,
+    /// Number of permutations counted towards the trace height but not kept in `preimages`.
+    num_skipped: usize,
+}
+
+impl KeccakfChip {
+    pub fn new(bus: KeccakfBus) -> Self {
+        Self {
+            air: KeccakfAir::new(bus),
+            records: Mutex::new(KeccakfRecords::default()),
+        }
+    }
+
+    pub fn bus(&self) -> KeccakfBus {
+        self.air.bus
+    }
+
+    /// Requests the permutation of `preimage`, which must be sent once on the bus.
+    pub fn request_permutation(&self, preimage: [u64; 25]) {
+        self.records.lock().unwrap().preimages.push(preimage);
+    }
+
+    /// Counts `num_permutations` permutations towards the trace height without keeping them.
+    pub fn skip_permutations(&self, num_permutations: usize) {
+        self.records.lock().unwrap().num_skipped += num_permutations;
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for KeccakfChip
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let records = self.records.into_inner().unwrap();
+        assert_eq!(
+            records.num_skipped, 0,
+            "cannot generate the trace of a chip which skipped its records"
+        );
+        let num_permutations = records.preimages.len();
+        let mut trace: RowMajorMatrix<Val<SC>> = generate_trace_rows(records.preimages);
+        for permutation in 0..num_permutations {
+            let last_row: &mut KeccakCols<Val<SC>> = trace
+                .row_mut((permutation + 1) * NUM_ROUNDS - 1)
+                .borrow_mut();
+            last_row.export = Val::<SC>::ONE;
+        }
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+impl ChipUsageGetter for KeccakfChip {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        let records = self.records.lock().unwrap();
+        (records.preimages.len() + records.num_skipped) * NUM_ROUNDS
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_KECCAK_COLS
+    }
+}
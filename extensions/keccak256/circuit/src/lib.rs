@@ -4,14 +4,18 @@ use std::{array::from_fn, cmp::min, sync::Arc};
 
 use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
 use openvm_stark_backend::p3_field::PrimeField32;
-use tiny_keccak::{Hasher, Keccak};
-use utils::num_keccak_f;
+use utils::{keccak_f, num_keccak_f};
 
 pub mod air;
 pub mod columns;
+mod compression;
+mod keccakf;
 pub mod trace;
 pub mod utils;
 
+pub use compression::*;
+pub use keccakf::*;
+
 mod extension;
 pub use extension::*;
 
@@ -68,8 +72,13 @@ pub struct KeccakVmChip<F: PrimeField32> {
     pub air: KeccakVmAir,
     /// IO and memory data necessary for each opcode call
     pub records: Vec<KeccakRecord<F>>,
+    /// Number of input blocks of the records not kept in `records`, see
+    /// [InstructionExecutor::skip_records]. `None` while records are kept.
+    num_skipped_blocks: Option<usize>,
     pub memory_controller: MemoryControllerRef<F>,
     pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+    /// Proves the keccak-f permutation of each absorbed block.
+    pub keccakf_chip: Arc<KeccakfChip>,
 
     offset: usize,
 }
@@ -95,6 +104,10 @@ pub struct KeccakInputBlock<F> {
     pub remaining_len: usize,
     pub src: usize,
     pub is_new_start: bool,
+    /// State after absorbing `padded_bytes`.
+    pub preimage: [u64; 25],
+    /// State after the keccak-f permutation of `preimage`.
+    pub postimage: [u64; 25],
 }
 
 impl<F: PrimeField32> KeccakVmChip<F> {
@@ -103,6 +116,7 @@ impl<F: PrimeField32> KeccakVmChip<F> {
         program_bus: ProgramBus,
         memory_controller: MemoryControllerRef<F>,
         bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        keccakf_chip: Arc<KeccakfChip>,
         offset: usize,
     ) -> Self {
         let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
@@ -112,12 +126,15 @@ impl<F: PrimeField32> KeccakVmChip<F> {
                 ExecutionBridge::new(execution_bus, program_bus),
                 memory_bridge,
                 bitwise_lookup_chip.bus(),
+                keccakf_chip.bus(),
                 ptr_max_bits,
                 offset,
             ),
             memory_controller,
             bitwise_lookup_chip,
+            keccakf_chip,
             records: Vec::new(),
+            num_skipped_blocks: None,
             offset,
         }
     }
@@ -157,7 +174,7 @@ impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
         let mut remaining_len = len as usize;
         let num_blocks = num_keccak_f(remaining_len);
         let mut input_blocks = Vec::with_capacity(num_blocks);
-        let mut hasher = Keccak::v256();
+        let mut state = [0u64; 25];
         let mut src = src as usize;
 
         for block_idx in 0..num_blocks {
@@ -187,34 +204,39 @@ impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
                 }
             }
 
-            let mut block = KeccakInputBlock {
+            if block_idx == num_blocks - 1 {
+                // handle padding here since it is convenient
+                debug_assert!(remaining_len < KECCAK_RATE_BYTES);
+                if remaining_len == KECCAK_RATE_BYTES - 1 {
+                    bytes[remaining_len] = 0b1000_0001;
+                } else {
+                    bytes[remaining_len] = 0x01;
+                    bytes[KECCAK_RATE_BYTES - 1] = 0x80;
+                }
+            }
+            // absorb, where u64 <-> bytes conversion is little-endian
+            for (s, lane_bytes) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+                *s ^= u64::from_le_bytes(lane_bytes.try_into().unwrap());
+            }
+            let preimage = state;
+            state = keccak_f(state);
+
+            input_blocks.push(KeccakInputBlock {
                 reads,
                 partial_read_idx,
                 padded_bytes: bytes,
                 remaining_len,
                 src,
                 is_new_start: block_idx == 0,
-            };
+                preimage,
+                postimage: state,
+            });
             if block_idx != num_blocks - 1 {
                 src += KECCAK_RATE_BYTES;
                 remaining_len -= KECCAK_RATE_BYTES;
-                hasher.update(&block.padded_bytes);
-            } else {
-                // handle padding here since it is convenient
-                debug_assert!(remaining_len < KECCAK_RATE_BYTES);
-                hasher.update(&block.padded_bytes[..remaining_len]);
-
-                if remaining_len == KECCAK_RATE_BYTES - 1 {
-                    block.padded_bytes[remaining_len] = 0b1000_0001;
-                } else {
-                    block.padded_bytes[remaining_len] = 0x01;
-                    block.padded_bytes[KECCAK_RATE_BYTES - 1] = 0x80;
-                }
             }
-            input_blocks.push(block);
         }
-        let mut output = [0u8; 32];
-        hasher.finalize(&mut output);
+        let output: [u8; KECCAK_DIGEST_BYTES] = from_fn(|i| state[i / 8].to_le_bytes()[i % 8]);
         let dst = dst as usize;
         let digest_writes: [_; KECCAK_DIGEST_WRITES] = from_fn(|i| {
             memory.write::<KECCAK_WORD_SIZE>(
@@ -235,7 +257,18 @@ impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
         };
 
         // Add the events to chip state for later trace generation usage
-        self.records.push(record);
+        match &mut self.num_skipped_blocks {
+            Some(num_skipped) => {
+                *num_skipped += num_blocks;
+                self.keccakf_chip.skip_permutations(num_blocks);
+            }
+            None => {
+                for block in &record.input_blocks {
+                    self.keccakf_chip.request_permutation(block.preimage);
+                }
+                self.records.push(record);
+            }
+        }
 
         // NOTE: Check this is consistent with KeccakVmAir::timestamp_change (we don't use it to avoid
         // unnecessary conversions here)
@@ -253,6 +286,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for KeccakVmChip<F> {
     fn get_opcode_name(&self, _: usize) -> String {
         "KECCAK256".to_string()
     }
+
+    fn skip_records(&mut self) {
+        self.num_skipped_blocks.get_or_insert(0);
+    }
 }
 
 impl<F: PrimeField32> Default for KeccakInputBlock<F> {
@@ -268,6 +305,8 @@ impl<F: PrimeField32> Default for KeccakInputBlock<F> {
             is_new_start: true,
             reads: Vec::new(),
             src: 0,
+            preimage: [0; 25],
+            postimage: [0; 25],
         }
     }
 }
@@ -6,21 +6,18 @@ use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     p3_air::BaseAir,
     p3_field::{AbstractField, PrimeField32},
-    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_matrix::dense::RowMajorMatrix,
     p3_maybe_rayon::prelude::*,
     prover::types::AirProofInput,
     rap::{get_air_name, AnyRap},
     Chip, ChipUsageGetter,
 };
-use p3_keccak_air::{
-    generate_trace_rows, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, NUM_ROUNDS, U64_LIMBS,
-};
-use tiny_keccak::keccakf;
+use p3_keccak_air::U64_LIMBS;
 
 use super::{
     columns::{KeccakInstructionCols, KeccakVmCols},
     KeccakVmChip, KECCAK_ABSORB_READS, KECCAK_DIGEST_WRITES, KECCAK_RATE_BYTES, KECCAK_RATE_U16S,
-    KECCAK_REGISTER_READS, KECCAK_WORD_SIZE, NUM_ABSORB_ROUNDS,
+    KECCAK_REGISTER_READS, KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE,
 };
 
 impl<SC: StarkGenericConfig> Chip<SC> for KeccakVmChip<Val<SC>>
@@ -32,19 +29,18 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        assert!(
+            self.num_skipped_blocks.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
         let air = self.air();
         let trace_width = self.trace_width();
         let records = self.records;
         let total_num_blocks: usize = records.iter().map(|r| r.input_blocks.len()).sum();
-        let mut states = Vec::with_capacity(total_num_blocks);
         let mut instruction_blocks = Vec::with_capacity(total_num_blocks);
 
         #[derive(Clone)]
-        struct StateDiff<F> {
-            /// hi-byte of pre-state
-            pre_hi: [u8; KECCAK_RATE_U16S],
-            /// hi-byte of post-state
-            post_hi: [u8; KECCAK_RATE_U16S],
+        struct BlockAccesses<F> {
             /// if first block
             register_reads:
                 Option<[MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>; KECCAK_REGISTER_READS]>,
@@ -52,21 +48,16 @@ where
             digest_writes: Option<[MemoryWriteRecord<F, KECCAK_WORD_SIZE>; KECCAK_DIGEST_WRITES]>,
         }
 
-        impl<F> Default for StateDiff<F> {
+        impl<F> Default for BlockAccesses<F> {
             fn default() -> Self {
                 Self {
-                    pre_hi: [0; KECCAK_RATE_U16S],
-                    post_hi: [0; KECCAK_RATE_U16S],
                     register_reads: None,
                     digest_writes: None,
                 }
             }
         }
 
-        // prepare the states
-        let mut state: [u64; 25];
         for record in records {
-            state = [0u64; 25];
             let src_limbs: [_; RV32_REGISTER_NUM_LIMBS - 1] =
                 from_fn(|i| record.src_read.data[i + 1]);
             let len_limbs: [_; RV32_REGISTER_NUM_LIMBS - 1] =
@@ -74,7 +65,6 @@ where
             let mut instruction = KeccakInstructionCols {
                 pc: record.pc,
                 is_enabled: Val::<SC>::ONE,
-                is_enabled_first_round: Val::<SC>::ZERO,
                 start_timestamp: Val::<SC>::from_canonical_u32(record.start_timestamp()),
                 dst_ptr: record.dst_read.pointer,
                 src_ptr: record.src_read.pointer,
@@ -89,44 +79,33 @@ where
                 ),
             };
             let num_blocks = record.input_blocks.len();
+            let mut prev_postimage = None;
             for (idx, block) in record.input_blocks.into_iter().enumerate() {
-                // absorb
-                for (bytes, s) in block.padded_bytes.chunks_exact(8).zip(state.iter_mut()) {
-                    // u64 <-> bytes conversion is little-endian
-                    for (i, &byte) in bytes.iter().enumerate() {
-                        let s_byte = (*s >> (i * 8)) as u8;
-                        // Update bitwise lookup (i.e. xor) chip state: order matters!
-                        if idx != 0 {
-                            self.bitwise_lookup_chip
-                                .request_xor(byte as u32, s_byte as u32);
-                        }
-                        *s ^= (byte as u64) << (i * 8);
+                // Update bitwise lookup (i.e. xor) chip state: order matters!
+                if let Some(prev_postimage) = prev_postimage {
+                    for (&byte, s_byte) in
+                        block.padded_bytes.iter().zip(state_bytes(prev_postimage))
+                    {
+                        self.bitwise_lookup_chip
+                            .request_xor(byte as u32, s_byte as u32);
                     }
                 }
-                let pre_hi: [u8; KECCAK_RATE_U16S] =
-                    from_fn(|i| (state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8);
-                states.push(state);
-                keccakf(&mut state);
-                let post_hi: [u8; KECCAK_RATE_U16S] =
-                    from_fn(|i| (state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8);
+                prev_postimage = Some(block.postimage);
                 // Range check the final state
                 if idx == num_blocks - 1 {
-                    for s in state.into_iter().take(NUM_ABSORB_ROUNDS) {
-                        for s_byte in s.to_le_bytes() {
-                            self.bitwise_lookup_chip.request_xor(0, s_byte as u32);
-                        }
+                    for s_byte in state_bytes(block.postimage).take(KECCAK_RATE_BYTES) {
+                        self.bitwise_lookup_chip.request_xor(0, s_byte as u32);
                     }
                 }
-                let register_reads =
-                    (idx == 0).then_some([record.dst_read, record.src_read, record.len_read]);
-                let digest_writes = (idx == num_blocks - 1).then_some(record.digest_writes);
-                let diff = StateDiff {
-                    pre_hi,
-                    post_hi,
-                    register_reads,
-                    digest_writes,
+                let accesses = BlockAccesses {
+                    register_reads: (idx == 0).then_some([
+                        record.dst_read,
+                        record.src_read,
+                        record.len_read,
+                    ]),
+                    digest_writes: (idx == num_blocks - 1).then_some(record.digest_writes),
                 };
-                instruction_blocks.push((instruction, diff, block));
+                instruction_blocks.push((instruction, accesses, block));
                 instruction.remaining_len -= Val::<SC>::from_canonical_usize(KECCAK_RATE_BYTES);
                 instruction.src += Val::<SC>::from_canonical_usize(KECCAK_RATE_BYTES);
                 instruction.start_timestamp +=
@@ -134,61 +113,41 @@ where
             }
         }
 
-        let p3_keccak_trace: RowMajorMatrix<Val<SC>> = generate_trace_rows(states);
-        let num_rows = p3_keccak_trace.height();
-        // Every `NUM_ROUNDS` rows corresponds to one input block
-        let num_blocks = num_rows.div_ceil(NUM_ROUNDS);
-        // Resize with dummy `is_enabled = 0`
-        instruction_blocks.resize(num_blocks, Default::default());
+        // One row per block, with dummy `is_enabled = 0` blocks for padding
+        let num_rows = total_num_blocks.next_power_of_two();
+        instruction_blocks.resize(num_rows, Default::default());
 
         let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
 
-        // Use unsafe alignment so we can parallely write to the matrix
         let mut trace =
             RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
         let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
 
         trace
             .values
-            .par_chunks_mut(trace_width * NUM_ROUNDS)
-            .zip(
-                p3_keccak_trace
-                    .values
-                    .par_chunks(NUM_KECCAK_PERM_COLS * NUM_ROUNDS),
-            )
+            .par_chunks_mut(trace_width)
             .zip(instruction_blocks.into_par_iter())
-            .for_each(|((rows, p3_keccak_mat), (instruction, diff, block))| {
-                let height = rows.len() / trace_width;
-                let partial_read_data = if let Some(partial_read_idx) = block.partial_read_idx {
-                    block.reads[partial_read_idx].data
-                } else {
-                    [Val::<SC>::ZERO; KECCAK_WORD_SIZE]
-                };
-                for (row, p3_keccak_row) in rows
-                    .chunks_exact_mut(trace_width)
-                    .zip(p3_keccak_mat.chunks_exact(NUM_KECCAK_PERM_COLS))
-                {
-                    // Safety: `KeccakPermCols` **must** be the first field in `KeccakVmCols`
-                    row[..NUM_KECCAK_PERM_COLS].copy_from_slice(p3_keccak_row);
-                    let row_mut: &mut KeccakVmCols<Val<SC>> = row.borrow_mut();
-                    row_mut.instruction = instruction;
-
-                    row_mut.sponge.block_bytes =
-                        block.padded_bytes.map(Val::<SC>::from_canonical_u8);
-                    row_mut
-                        .mem_oc
+            .for_each(|(row, (instruction, accesses, block))| {
+                let row: &mut KeccakVmCols<Val<SC>> = row.borrow_mut();
+                row.instruction = instruction;
+
+                row.sponge.is_new_start = Val::<SC>::from_bool(block.is_new_start);
+                for (i, is_padding) in row.sponge.is_padding_byte.iter_mut().enumerate() {
+                    *is_padding = Val::<SC>::from_bool(i >= block.remaining_len);
+                }
+                row.sponge.block_bytes = block.padded_bytes.map(Val::<SC>::from_canonical_u8);
+                row.sponge.preimage = state_limbs(block.preimage);
+                row.sponge.preimage_hi = state_hi(block.preimage);
+                row.sponge.postimage = state_limbs(block.postimage);
+                row.sponge.postimage_hi = state_hi(block.postimage);
+
+                if let Some(partial_read_idx) = block.partial_read_idx {
+                    row.mem_oc
                         .partial_block
-                        .copy_from_slice(&partial_read_data[1..]);
-                    for (i, is_padding) in row_mut.sponge.is_padding_byte.iter_mut().enumerate() {
-                        *is_padding = Val::<SC>::from_bool(i >= block.remaining_len);
-                    }
+                        .copy_from_slice(&block.reads[partial_read_idx].data[1..]);
                 }
-                let first_row: &mut KeccakVmCols<Val<SC>> = rows[..trace_width].borrow_mut();
-                first_row.sponge.is_new_start = Val::<SC>::from_bool(block.is_new_start);
-                first_row.sponge.state_hi = diff.pre_hi.map(Val::<SC>::from_canonical_u8);
-                first_row.instruction.is_enabled_first_round = first_row.instruction.is_enabled;
                 // Make memory access aux columns. Any aux column not explicitly defined defaults to all 0s
-                if let Some(register_reads) = diff.register_reads {
+                if let Some(register_reads) = accesses.register_reads {
                     let need_range_check = [
                         &register_reads[0], // dst
                         &register_reads[1], // src
@@ -204,25 +163,16 @@ where
                     }
                     for (i, record) in register_reads.into_iter().enumerate() {
                         // TODO[jpw] make_read_aux_cols should directly write into slice
-                        first_row.mem_oc.register_aux[i] =
-                            aux_cols_factory.make_read_aux_cols(record);
+                        row.mem_oc.register_aux[i] = aux_cols_factory.make_read_aux_cols(record);
                     }
                 }
                 for (i, record) in block.reads.into_iter().enumerate() {
                     // TODO[jpw] make_read_aux_cols should directly write into slice
-                    first_row.mem_oc.absorb_reads[i] = aux_cols_factory.make_read_aux_cols(record);
+                    row.mem_oc.absorb_reads[i] = aux_cols_factory.make_read_aux_cols(record);
                 }
-
-                let last_row: &mut KeccakVmCols<Val<SC>> =
-                    rows[(height - 1) * trace_width..].borrow_mut();
-                last_row.sponge.state_hi = diff.post_hi.map(Val::<SC>::from_canonical_u8);
-                last_row.inner.export = instruction.is_enabled
-                    * Val::<SC>::from_bool(block.remaining_len < KECCAK_RATE_BYTES);
-                if let Some(digest_writes) = diff.digest_writes {
+                if let Some(digest_writes) = accesses.digest_writes {
                     for (i, record) in digest_writes.into_iter().enumerate() {
-                        // TODO: these aux columns are only used for the last row - can we share them with aux reads in first row?
-                        last_row.mem_oc.digest_writes[i] =
-                            aux_cols_factory.make_write_aux_cols(record);
+                        row.mem_oc.digest_writes[i] = aux_cols_factory.make_write_aux_cols(record);
                     }
                 }
             });
@@ -231,13 +181,31 @@ where
     }
 }
 
+/// Little-endian bytes of a keccak state.
+fn state_bytes(state: [u64; 25]) -> impl Iterator<Item = u8> {
+    state.into_iter().flat_map(u64::to_le_bytes)
+}
+
+/// `u16` limbs of a keccak state, little-endian within each lane.
+fn state_limbs<F: PrimeField32>(state: [u64; 25]) -> [F; KECCAK_WIDTH_U16S] {
+    from_fn(|i| F::from_canonical_u16((state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16)) as u16))
+}
+
+/// Most significant byte of each rate `u16` limb of a keccak state.
+fn state_hi<F: PrimeField32>(state: [u64; 25]) -> [F; KECCAK_RATE_U16S] {
+    from_fn(|i| F::from_canonical_u8((state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8))
+}
+
 impl<F: PrimeField32> ChipUsageGetter for KeccakVmChip<F> {
     fn air_name(&self) -> String {
         get_air_name(&self.air)
     }
     fn current_trace_height(&self) -> usize {
-        let num_blocks: usize = self.records.iter().map(|r| r.input_blocks.len()).sum();
-        num_blocks * NUM_ROUNDS
+        self.records
+            .iter()
+            .map(|r| r.input_blocks.len())
+            .sum::<usize>()
+            + self.num_skipped_blocks.unwrap_or(0)
     }
 
     fn trace_width(&self) -> usize {
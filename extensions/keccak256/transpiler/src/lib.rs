@@ -1,7 +1,10 @@
-use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions::{
+    builder::InstructionBuilder, instruction::Instruction, riscv::RV32_MEMORY_AS, UsizeOpcode,
+    VmOpcode,
+};
 use openvm_instructions_derive::UsizeOpcode;
 use openvm_keccak256_guest::{FUNCT3, OPCODE};
-use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_stark_backend::p3_field::{Field, PrimeField32};
 use openvm_transpiler::{util::from_r_type, TranspilerExtension};
 use rrs_lib::instruction_formats::RType;
 use strum::{EnumCount, EnumIter, FromRepr};
@@ -15,6 +18,25 @@ pub enum Rv32KeccakOpcode {
     KECCAK256,
 }
 
+/// Builder for the keccak256 instruction, with the same operands as the transpiled instruction.
+pub trait Keccak256InstructionBuilder<F> {
+    /// Hashes `len = [rs2]` bytes of memory starting at `[rs1]` and writes the 32-byte digest to
+    /// memory starting at `[rd]`.
+    fn keccak256(rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+}
+
+impl<F: Field> Keccak256InstructionBuilder<F> for InstructionBuilder<F> {
+    fn keccak256(rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        InstructionBuilder::r_type(
+            VmOpcode::with_default_offset(Rv32KeccakOpcode::KECCAK256),
+            rd,
+            rs1,
+            rs2,
+            RV32_MEMORY_AS,
+        )
+    }
+}
+
 #[derive(Default)]
 pub struct Keccak256TranspilerExtension;
 
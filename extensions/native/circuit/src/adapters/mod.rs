@@ -5,6 +5,8 @@ pub mod convert_adapter;
 // 0 reads, 1 write, jump support
 pub mod jal_native_adapter;
 pub mod loadstore_native_adapter;
+// 3 reads, 1 write, read size = write size = N, no imm support, read/write to address space d
+pub mod native_mul_add_adapter;
 // 2 reads, 1 write, read size = write size = N, no imm support, read/write to address space d
 pub mod native_vectorized_adapter;
 // 2 reads and 1 write from/to heap memory
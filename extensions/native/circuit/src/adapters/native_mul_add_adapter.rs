@@ -0,0 +1,238 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cell::RefCell,
+    marker::PhantomData,
+};
+
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, program::DEFAULT_PC_STEP};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+/// Reads `b`, `c` and `e` and writes `a`, all in address space `d`.
+#[derive(Debug)]
+pub struct NativeMulAddAdapterChip<F: Field, const N: usize> {
+    pub air: NativeMulAddAdapterAir<N>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32, const N: usize> NativeMulAddAdapterChip<F, N> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+    ) -> Self {
+        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: NativeMulAddAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NativeMulAddReadRecord<F: Field, const N: usize> {
+    pub b: MemoryReadRecord<F, N>,
+    pub c: MemoryReadRecord<F, N>,
+    pub e: MemoryReadRecord<F, N>,
+}
+
+#[derive(Debug)]
+pub struct NativeMulAddWriteRecord<F: Field, const N: usize> {
+    pub from_state: ExecutionState<u32>,
+    pub a: MemoryWriteRecord<F, N>,
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct NativeMulAddAdapterCols<T, const N: usize> {
+    pub from_state: ExecutionState<T>,
+    pub address_space: T,
+    pub a_pointer: T,
+    pub b_pointer: T,
+    pub c_pointer: T,
+    pub e_pointer: T,
+    pub reads_aux: [MemoryReadAuxCols<T, N>; 3],
+    pub writes_aux: [MemoryWriteAuxCols<T, N>; 1],
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct NativeMulAddAdapterAir<const N: usize> {
+    pub(super) execution_bridge: ExecutionBridge,
+    pub(super) memory_bridge: MemoryBridge,
+}
+
+impl<F: Field, const N: usize> BaseAir<F> for NativeMulAddAdapterAir<N> {
+    fn width(&self) -> usize {
+        NativeMulAddAdapterCols::<F, N>::width()
+    }
+}
+
+impl<AB: InteractionBuilder, const N: usize> VmAdapterAir<AB> for NativeMulAddAdapterAir<N> {
+    type Interface = BasicAdapterInterface<AB::Expr, MinimalInstruction<AB::Expr>, 3, 1, N, N>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let cols: &NativeMulAddAdapterCols<_, N> = local.borrow();
+        let timestamp = cols.from_state.timestamp;
+        let mut timestamp_delta = 0usize;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let read_pointers = [cols.b_pointer, cols.c_pointer, cols.e_pointer];
+        for ((pointer, data), aux) in read_pointers
+            .into_iter()
+            .zip(ctx.reads)
+            .zip(&cols.reads_aux)
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(cols.address_space, pointer),
+                    data,
+                    timestamp_pp(),
+                    aux,
+                )
+                .eval(builder, ctx.instruction.is_valid.clone());
+        }
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(cols.address_space, cols.a_pointer),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &cols.writes_aux[0],
+            )
+            .eval(builder, ctx.instruction.is_valid.clone());
+
+        self.execution_bridge
+            .execute_and_increment_or_set_pc(
+                ctx.instruction.opcode,
+                [
+                    cols.a_pointer,
+                    cols.b_pointer,
+                    cols.c_pointer,
+                    cols.address_space,
+                    cols.e_pointer,
+                ],
+                cols.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+                (DEFAULT_PC_STEP, ctx.to_pc),
+            )
+            .eval(builder, ctx.instruction.is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &NativeMulAddAdapterCols<_, N> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32, const N: usize> VmAdapterChip<F> for NativeMulAddAdapterChip<F, N> {
+    type ReadRecord = NativeMulAddReadRecord<F, N>;
+    type WriteRecord = NativeMulAddWriteRecord<F, N>;
+    type Air = NativeMulAddAdapterAir<N>;
+    type Interface = BasicAdapterInterface<F, MinimalInstruction<F>, 3, 1, N, N>;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, e, .. } = *instruction;
+
+        let b_val = memory.read::<N>(d, b);
+        let c_val = memory.read::<N>(d, c);
+        let e_val = memory.read::<N>(d, e);
+
+        Ok((
+            [b_val.data, c_val.data, e_val.data],
+            Self::ReadRecord {
+                b: b_val,
+                c: c_val,
+                e: e_val,
+            },
+        ))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, .. } = *instruction;
+        let a_val = memory.write(d, a, output.writes[0]);
+
+        Ok((
+            ExecutionState {
+                pc: output.to_pc.unwrap_or(from_state.pc + DEFAULT_PC_STEP),
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                a: a_val,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let row_slice: &mut NativeMulAddAdapterCols<_, N> = row_slice.borrow_mut();
+
+        row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
+        row_slice.address_space = write_record.a.address_space;
+        row_slice.a_pointer = write_record.a.pointer;
+        row_slice.b_pointer = read_record.b.pointer;
+        row_slice.c_pointer = read_record.c.pointer;
+        row_slice.e_pointer = read_record.e.pointer;
+
+        row_slice.reads_aux = [
+            aux_cols_factory.make_read_aux_cols(read_record.b),
+            aux_cols_factory.make_read_aux_cols(read_record.c),
+            aux_cols_factory.make_read_aux_cols(read_record.e),
+        ];
+        row_slice.writes_aux = [aux_cols_factory.make_write_aux_cols(write_record.a)];
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -2,6 +2,7 @@ use branch_native_adapter::BranchNativeAdapterChip;
 use derive_more::derive::From;
 use jal_native_adapter::JalNativeAdapterChip;
 use loadstore_native_adapter::NativeLoadStoreAdapterChip;
+use native_mul_add_adapter::NativeMulAddAdapterChip;
 use native_vectorized_adapter::NativeVectorizedAdapterChip;
 use openvm_circuit::{
     arch::{
@@ -17,7 +18,7 @@ use openvm_instructions::{
     program::DEFAULT_PC_STEP, PhantomDiscriminant, Poseidon2Opcode, UsizeOpcode, VmOpcode,
 };
 use openvm_native_compiler::{
-    FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
+    FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, MulAddOpcode, NativeBranchEqualOpcode,
     NativeJalOpcode, NativeLoadStoreOpcode, NativePhantom,
 };
 use openvm_poseidon2_air::poseidon2::air::SBOX_DEGREE;
@@ -68,7 +69,13 @@ impl NativeConfig {
 }
 
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
-pub struct Native;
+pub struct Native {
+    /// If set, adds the multiply-add chips for the instructions emitted with
+    /// `CompilerOptions::fuse_mul_add`. Off by default so existing configs keep the same set of
+    /// AIRs.
+    #[serde(default)]
+    pub mul_add: bool,
+}
 
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
 pub enum NativeExecutor<F: PrimeField32> {
@@ -77,8 +84,11 @@ pub enum NativeExecutor<F: PrimeField32> {
     Jal(NativeJalChip<F>),
     FieldArithmetic(FieldArithmeticChip<F>),
     FieldExtension(FieldExtensionChip<F>),
+    FieldMulAdd(FieldMulAddChip<F>),
+    ExtensionMulAdd(ExtensionMulAddChip<F>),
     Poseidon2(Poseidon2Chip<F>),
     FriReducedOpening(FriReducedOpeningChip<F>),
+    FriFold(FriFoldChip<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
@@ -166,6 +176,28 @@ impl<F: PrimeField32> VmExtension<F> for Native {
             FieldExtensionOpcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        if self.mul_add {
+            let field_mul_add_chip = FieldMulAddChip::new(
+                NativeMulAddAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                MulAddCoreChip::new(MulAddOpcode::default_offset()),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                field_mul_add_chip,
+                [VmOpcode::with_default_offset(MulAddOpcode::MULADD)],
+            )?;
+
+            let extension_mul_add_chip = ExtensionMulAddChip::new(
+                NativeMulAddAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                MulAddCoreChip::new(MulAddOpcode::default_offset()),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                extension_mul_add_chip,
+                [VmOpcode::with_default_offset(MulAddOpcode::BBE4MULADD)],
+            )?;
+        }
+
         let fri_reduced_opening_chip = FriReducedOpeningChip::new(
             memory_controller.clone(),
             execution_bus,
@@ -174,7 +206,20 @@ impl<F: PrimeField32> VmExtension<F> for Native {
         );
         inventory.add_executor(
             fri_reduced_opening_chip,
-            FriOpcode::iter().map(VmOpcode::with_default_offset),
+            [VmOpcode::with_default_offset(
+                FriOpcode::FRI_REDUCED_OPENING,
+            )],
+        )?;
+
+        let fri_fold_chip = FriFoldChip::new(
+            memory_controller.clone(),
+            execution_bus,
+            program_bus,
+            FriOpcode::default_offset(),
+        );
+        inventory.add_executor(
+            fri_fold_chip,
+            [VmOpcode::with_default_offset(FriOpcode::FRI_FOLD)],
         )?;
 
         let poseidon2_chip = Poseidon2Chip::from_poseidon2_config(
@@ -186,7 +231,8 @@ impl<F: PrimeField32> VmExtension<F> for Native {
             execution_bus,
             program_bus,
             memory_controller.clone(),
-            builder.new_bus_idx(),
+            // Nothing sends direct compressions to this chip.
+            builder.new_lookup_bus_idx(),
             Poseidon2Opcode::default_offset(),
         );
         inventory.add_executor(
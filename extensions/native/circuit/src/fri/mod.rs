@@ -314,6 +314,9 @@ pub struct FriReducedOpeningChip<F: Field> {
     memory: MemoryControllerRef<F>,
     air: FriReducedOpeningAir,
     records: Vec<FriReducedOpeningRecord<F>>,
+    /// Whether records are dropped, see [InstructionExecutor::skip_records]. `height` still counts
+    /// them.
+    skip_records: bool,
     height: usize,
 }
 
@@ -332,6 +335,7 @@ impl<F: PrimeField32> FriReducedOpeningChip<F> {
         Self {
             memory,
             records: vec![],
+            skip_records: false,
             air,
             height: 0,
         }
@@ -399,19 +403,21 @@ impl<F: PrimeField32> InstructionExecutor<F> for FriReducedOpeningChip<F> {
         debug_assert_eq!(alpha_pow_write.prev_data, alpha_pow_original);
         let result_write = memory.write(addr_space, result_ptr, result);
 
-        self.records.push(FriReducedOpeningRecord {
-            pc: F::from_canonical_u32(from_state.pc),
-            start_timestamp: F::from_canonical_u32(from_state.timestamp),
-            instruction,
-            alpha_read,
-            length_read,
-            a_ptr_read,
-            b_ptr_read,
-            a_reads,
-            b_reads,
-            alpha_pow_write,
-            result_write,
-        });
+        if !self.skip_records {
+            self.records.push(FriReducedOpeningRecord {
+                pc: F::from_canonical_u32(from_state.pc),
+                start_timestamp: F::from_canonical_u32(from_state.timestamp),
+                instruction,
+                alpha_read,
+                length_read,
+                a_ptr_read,
+                b_ptr_read,
+                a_reads,
+                b_reads,
+                alpha_pow_write,
+                result_write,
+            });
+        }
 
         self.height += length;
 
@@ -425,6 +431,10 @@ impl<F: PrimeField32> InstructionExecutor<F> for FriReducedOpeningChip<F> {
         assert_eq!(opcode, (FRI_REDUCED_OPENING as usize) + self.air.offset);
         String::from("FRI_REDUCED_OPENING")
     }
+
+    fn skip_records(&mut self) {
+        self.skip_records = true;
+    }
 }
 
 impl<F: Field> ChipUsageGetter for FriReducedOpeningChip<F> {
@@ -535,6 +545,10 @@ impl<F: PrimeField32> FriReducedOpeningChip<F> {
     }
 
     fn generate_trace(self) -> RowMajorMatrix<F> {
+        assert!(
+            !self.skip_records,
+            "cannot generate the trace of a chip which skipped its records"
+        );
         let width = self.trace_width();
         let height = next_power_of_two_or_zero(self.height);
         let mut flat_trace = F::zero_vec(width * height);
@@ -0,0 +1,379 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cell::RefCell,
+    sync::Arc,
+};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryControllerRef, MemoryReadRecord,
+            MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, program::DEFAULT_PC_STEP};
+use openvm_native_compiler::FriOpcode::FRI_FOLD;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+use super::field_extension::{FieldExtension, EXT_DEG};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of memory accesses of a single fold: four reads and one write.
+const NUM_ACCESSES: usize = 5;
+
+/// One row per fold. The fold of the evaluations `eval_0` at `x` and `eval_1` at `-x` with
+/// challenge `beta` is `result = eval_0 + (beta - x) * (eval_1 - eval_0) / (-2 * x)`, which is
+/// constrained without the division as
+/// `2 * x * result - x * eval_0 - x * eval_1 + beta * eval_1 - beta * eval_0 = 0`.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct FriFoldCols<T> {
+    pub enabled: T,
+
+    pub pc: T,
+    pub start_timestamp: T,
+
+    pub result_ptr: T,
+    pub eval_0_ptr: T,
+    pub eval_1_ptr: T,
+    pub addr_space: T,
+    pub beta_ptr: T,
+    pub x_ptr: T,
+
+    pub eval_0_aux: MemoryReadAuxCols<T, EXT_DEG>,
+    pub eval_1_aux: MemoryReadAuxCols<T, EXT_DEG>,
+    pub beta_aux: MemoryReadAuxCols<T, EXT_DEG>,
+    pub x_aux: MemoryReadAuxCols<T, EXT_DEG>,
+    pub result_aux: MemoryWriteAuxCols<T, EXT_DEG>,
+
+    pub eval_0: [T; EXT_DEG],
+    pub eval_1: [T; EXT_DEG],
+    pub beta: [T; EXT_DEG],
+    pub x: [T; EXT_DEG],
+    pub result: [T; EXT_DEG],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct FriFoldAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for FriFoldAir {
+    fn width(&self) -> usize {
+        FriFoldCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for FriFoldAir {}
+impl<F: Field> PartitionedBaseAir<F> for FriFoldAir {}
+
+impl<AB: InteractionBuilder> Air<AB> for FriFoldAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FriFoldCols<AB::Var> = (*local).borrow();
+
+        let &FriFoldCols {
+            enabled,
+            pc,
+            start_timestamp,
+            result_ptr,
+            eval_0_ptr,
+            eval_1_ptr,
+            addr_space,
+            beta_ptr,
+            x_ptr,
+            eval_0_aux,
+            eval_1_aux,
+            beta_aux,
+            x_aux,
+            result_aux,
+            eval_0,
+            eval_1,
+            beta,
+            x,
+            result,
+        } = local;
+
+        builder.assert_bool(enabled);
+
+        let x_times_result: [AB::Expr; EXT_DEG] = FieldExtension::multiply(x, result);
+        let x_times_eval_0: [AB::Expr; EXT_DEG] = FieldExtension::multiply(x, eval_0);
+        let x_times_eval_1: [AB::Expr; EXT_DEG] = FieldExtension::multiply(x, eval_1);
+        let beta_times_eval_0: [AB::Expr; EXT_DEG] = FieldExtension::multiply(beta, eval_0);
+        let beta_times_eval_1: [AB::Expr; EXT_DEG] = FieldExtension::multiply(beta, eval_1);
+        for i in 0..EXT_DEG {
+            builder.when(enabled).assert_zero(
+                x_times_result[i].clone() * AB::F::TWO
+                    - x_times_eval_0[i].clone()
+                    - x_times_eval_1[i].clone()
+                    + beta_times_eval_1[i].clone()
+                    - beta_times_eval_0[i].clone(),
+            );
+        }
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::F::from_canonical_usize((FRI_FOLD as usize) + self.offset),
+                [
+                    result_ptr, eval_0_ptr, eval_1_ptr, addr_space, beta_ptr, x_ptr,
+                ],
+                ExecutionState::new(pc, start_timestamp),
+                AB::F::from_canonical_usize(NUM_ACCESSES),
+            )
+            .eval(builder, enabled);
+
+        let reads = [
+            (eval_0_ptr, eval_0, &eval_0_aux),
+            (eval_1_ptr, eval_1, &eval_1_aux),
+            (beta_ptr, beta, &beta_aux),
+            (x_ptr, x, &x_aux),
+        ];
+        for (i, (ptr, data, aux)) in reads.into_iter().enumerate() {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(addr_space, ptr),
+                    data,
+                    start_timestamp + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, enabled);
+        }
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(addr_space, result_ptr),
+                result,
+                start_timestamp + AB::F::from_canonical_usize(NUM_ACCESSES - 1),
+                &result_aux,
+            )
+            .eval(builder, enabled);
+    }
+}
+
+pub struct FriFoldRecord<F: Field> {
+    pub pc: F,
+    pub start_timestamp: F,
+    pub instruction: Instruction<F>,
+    pub eval_0_read: MemoryReadRecord<F, EXT_DEG>,
+    pub eval_1_read: MemoryReadRecord<F, EXT_DEG>,
+    pub beta_read: MemoryReadRecord<F, EXT_DEG>,
+    pub x_read: MemoryReadRecord<F, EXT_DEG>,
+    pub result_write: MemoryWriteRecord<F, EXT_DEG>,
+}
+
+pub struct FriFoldChip<F: Field> {
+    memory: MemoryControllerRef<F>,
+    air: FriFoldAir,
+    records: Vec<FriFoldRecord<F>>,
+    /// Number of records not kept in `records`, see [InstructionExecutor::skip_records]. `None`
+    /// while records are kept.
+    num_skipped_records: Option<usize>,
+}
+
+impl<F: PrimeField32> FriFoldChip<F> {
+    pub fn new(
+        memory: MemoryControllerRef<F>,
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        offset: usize,
+    ) -> Self {
+        let air = FriFoldAir {
+            execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+            memory_bridge: RefCell::borrow(&memory).memory_bridge(),
+            offset,
+        };
+        Self {
+            memory,
+            records: vec![],
+            num_skipped_records: None,
+            air,
+        }
+    }
+}
+
+/// Folds the evaluations `eval_0` at `x` and `eval_1` at `-x` with challenge `beta`.
+pub(super) fn fri_fold<F: Field>(
+    eval_0: [F; EXT_DEG],
+    eval_1: [F; EXT_DEG],
+    beta: [F; EXT_DEG],
+    x: [F; EXT_DEG],
+) -> [F; EXT_DEG] {
+    let minus_two_x = x.map(|x| -(x + x));
+    FieldExtension::add(
+        eval_0,
+        FieldExtension::divide(
+            FieldExtension::multiply(
+                FieldExtension::subtract(beta, x),
+                FieldExtension::subtract(eval_1, eval_0),
+            ),
+            minus_two_x,
+        ),
+    )
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for FriFoldChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            a: result_ptr,
+            b: eval_0_ptr,
+            c: eval_1_ptr,
+            d: addr_space,
+            e: beta_ptr,
+            f: x_ptr,
+            ..
+        } = instruction;
+
+        let mut memory = RefCell::borrow_mut(&self.memory);
+
+        let eval_0_read = memory.read(addr_space, eval_0_ptr);
+        let eval_1_read = memory.read(addr_space, eval_1_ptr);
+        let beta_read = memory.read(addr_space, beta_ptr);
+        let x_read = memory.read(addr_space, x_ptr);
+
+        let result = fri_fold(
+            eval_0_read.data,
+            eval_1_read.data,
+            beta_read.data,
+            x_read.data,
+        );
+        let result_write = memory.write(addr_space, result_ptr, result);
+
+        match &mut self.num_skipped_records {
+            Some(num_skipped) => *num_skipped += 1,
+            None => self.records.push(FriFoldRecord {
+                pc: F::from_canonical_u32(from_state.pc),
+                start_timestamp: F::from_canonical_u32(from_state.timestamp),
+                instruction,
+                eval_0_read,
+                eval_1_read,
+                beta_read,
+                x_read,
+                result_write,
+            }),
+        }
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: memory.timestamp(),
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        assert_eq!(opcode, (FRI_FOLD as usize) + self.air.offset);
+        String::from("FRI_FOLD")
+    }
+
+    fn skip_records(&mut self) {
+        self.num_skipped_records.get_or_insert(0);
+    }
+}
+
+impl<F: Field> ChipUsageGetter for FriFoldChip<F> {
+    fn air_name(&self) -> String {
+        "FriFoldAir".to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() + self.num_skipped_records.unwrap_or(0)
+    }
+
+    fn trace_width(&self) -> usize {
+        FriFoldCols::<F>::width()
+    }
+}
+
+impl<F: PrimeField32> FriFoldChip<F> {
+    fn record_to_row(
+        record: &FriFoldRecord<F>,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+        row: &mut FriFoldCols<F>,
+    ) {
+        let Instruction {
+            a: result_ptr,
+            b: eval_0_ptr,
+            c: eval_1_ptr,
+            d: addr_space,
+            e: beta_ptr,
+            f: x_ptr,
+            ..
+        } = record.instruction;
+
+        *row = FriFoldCols {
+            enabled: F::ONE,
+            pc: record.pc,
+            start_timestamp: record.start_timestamp,
+            result_ptr,
+            eval_0_ptr,
+            eval_1_ptr,
+            addr_space,
+            beta_ptr,
+            x_ptr,
+            eval_0_aux: aux_cols_factory.make_read_aux_cols(record.eval_0_read),
+            eval_1_aux: aux_cols_factory.make_read_aux_cols(record.eval_1_read),
+            beta_aux: aux_cols_factory.make_read_aux_cols(record.beta_read),
+            x_aux: aux_cols_factory.make_read_aux_cols(record.x_read),
+            result_aux: aux_cols_factory.make_write_aux_cols(record.result_write),
+            eval_0: record.eval_0_read.data,
+            eval_1: record.eval_1_read.data,
+            beta: record.beta_read.data,
+            x: record.x_read.data,
+            result: record.result_write.data,
+        };
+    }
+
+    fn generate_trace(self) -> RowMajorMatrix<F> {
+        assert!(
+            self.num_skipped_records.is_none(),
+            "cannot generate the trace of a chip which skipped its records"
+        );
+        let width = self.trace_width();
+        let height = next_power_of_two_or_zero(self.records.len());
+        let mut flat_trace = F::zero_vec(width * height);
+        let aux_cols_factory = RefCell::borrow(&self.memory).aux_cols_factory();
+
+        flat_trace
+            .par_chunks_mut(width)
+            .zip(self.records.par_iter())
+            .for_each(|(row, record)| {
+                Self::record_to_row(record, &aux_cols_factory, row.borrow_mut());
+            });
+
+        RowMajorMatrix::new(flat_trace, width)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for FriFoldChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput::simple_no_pis(self.air(), self.generate_trace())
+    }
+}
@@ -0,0 +1,100 @@
+use openvm_circuit::arch::testing::{memory::gen_pointer, VmChipTestBuilder};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_native_compiler::FriOpcode::{self, FRI_FOLD};
+use openvm_stark_backend::{
+    p3_field::AbstractField, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{fri_fold, FriFoldChip, FriFoldCols, EXT_DEG};
+
+#[test]
+fn fri_fold_air_test() {
+    let num_ops = 3; // non-power-of-2 to also test padding
+    let elem_range = || 1..=100;
+    let address_space_range = || 1usize..=2;
+
+    let offset = FriOpcode::default_offset();
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = FriFoldChip::new(
+        tester.memory_controller(),
+        tester.execution_bus(),
+        tester.program_bus(),
+        offset,
+    );
+
+    let mut rng = create_seeded_rng();
+
+    macro_rules! gen_ext {
+        () => {
+            std::array::from_fn::<_, EXT_DEG, _>(|_| {
+                BabyBear::from_canonical_u32(rng.gen_range(elem_range()))
+            })
+        };
+    }
+
+    for _ in 0..num_ops {
+        let eval_0 = gen_ext!();
+        let eval_1 = gen_ext!();
+        let beta = gen_ext!();
+        let x = gen_ext!();
+        let result = fri_fold(eval_0, eval_1, beta, x);
+
+        // The fold is the line through (x, eval_0) and (-x, eval_1), evaluated at beta.
+        assert_eq!(fri_fold(eval_0, eval_1, x, x), eval_0);
+        assert_eq!(fri_fold(eval_0, eval_1, x.map(|x| -x), x), eval_1);
+
+        let result_pointer = gen_pointer(&mut rng, 4);
+        let eval_0_pointer = gen_pointer(&mut rng, 4);
+        let eval_1_pointer = gen_pointer(&mut rng, 4);
+        let beta_pointer = gen_pointer(&mut rng, 4);
+        let x_pointer = gen_pointer(&mut rng, 4);
+
+        let address_space = rng.gen_range(address_space_range());
+
+        tester.write(address_space, eval_0_pointer, eval_0);
+        tester.write(address_space, eval_1_pointer, eval_1);
+        tester.write(address_space, beta_pointer, beta);
+        tester.write(address_space, x_pointer, x);
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::from_usize(FRI_FOLD as usize + offset),
+                [
+                    result_pointer,
+                    eval_0_pointer,
+                    eval_1_pointer,
+                    address_space,
+                    beta_pointer,
+                    x_pointer,
+                ],
+            ),
+        );
+        assert_eq!(result, tester.read(address_space, result_pointer));
+    }
+
+    let mut tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+
+    disable_debug_builder();
+    // negative test pranking each value
+    for height in 0..num_ops {
+        let trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+        let old_trace = trace.clone();
+        for width in 0..FriFoldCols::<BabyBear>::width() {
+            let prank_value = BabyBear::from_canonical_u32(rng.gen_range(1..=100));
+            trace.row_mut(height)[width] = prank_value;
+        }
+
+        assert_eq!(
+            tester.simple_test().err(),
+            Some(VerificationError::OodEvaluationMismatch),
+            "Expected constraint to fail"
+        );
+
+        tester.air_proof_inputs[2].raw.common_main = Some(old_trace);
+    }
+}
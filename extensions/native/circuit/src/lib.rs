@@ -5,16 +5,20 @@ mod castf;
 mod field_arithmetic;
 mod field_extension;
 mod fri;
+mod fri_fold;
 mod jal;
 mod loadstore;
+mod mul_add;
 
 pub use branch_eq::*;
 pub use castf::*;
 pub use field_arithmetic::*;
 pub use field_extension::*;
 pub use fri::*;
+pub use fri_fold::*;
 pub use jal::*;
 pub use loadstore::*;
+pub use mul_add::*;
 
 mod extension;
 pub use extension::*;
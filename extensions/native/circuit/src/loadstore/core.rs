@@ -1,5 +1,4 @@
 use std::{
-    array,
     borrow::{Borrow, BorrowMut},
     sync::{Arc, OnceLock},
 };
@@ -154,10 +153,9 @@ where
 
         let data_write = if local_opcode == NativeLoadStoreOpcode::SHINTW {
             let mut streams = self.streams.get().unwrap().lock();
-            if streams.hint_stream.len() < NUM_CELLS {
-                return Err(ExecutionError::HintOutOfBounds { pc: from_pc });
-            }
-            array::from_fn(|_| streams.hint_stream.pop_front().unwrap())
+            streams
+                .pop_hints()
+                .ok_or(ExecutionError::HintOutOfBounds { pc: from_pc })?
         } else {
             [data_read; NUM_CELLS]
         };
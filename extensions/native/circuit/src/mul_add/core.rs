@@ -0,0 +1,195 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    ops::{Add, Mul},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_native_compiler::MulAddOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+use crate::{FieldExtension, EXT_DEG};
+
+/// Columns of `a = b * c + e`, where the operands are base field elements for `N = 1` and
+/// extension field elements for `N = EXT_DEG`.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct MulAddCoreCols<T, const N: usize> {
+    pub a: [T; N],
+    pub b: [T; N],
+    pub c: [T; N],
+    pub e: [T; N],
+
+    pub is_valid: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MulAddCoreAir<const N: usize> {
+    offset: usize,
+}
+
+impl<const N: usize> MulAddCoreAir<N> {
+    fn opcode(&self) -> usize {
+        let local_opcode = match N {
+            1 => MulAddOpcode::MULADD,
+            EXT_DEG => MulAddOpcode::BBE4MULADD,
+            _ => unreachable!("multiply-add is only supported for N = 1 and N = {EXT_DEG}"),
+        };
+        self.offset + local_opcode.as_usize()
+    }
+}
+
+impl<F: Field, const N: usize> BaseAir<F> for MulAddCoreAir<N> {
+    fn width(&self) -> usize {
+        MulAddCoreCols::<F, N>::width()
+    }
+}
+
+impl<F: Field, const N: usize> BaseAirWithPublicValues<F> for MulAddCoreAir<N> {}
+
+impl<AB, I, const N: usize> VmCoreAir<AB, I> for MulAddCoreAir<N>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; N]; 3]>,
+    I::Writes: From<[[AB::Expr; N]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &MulAddCoreCols<_, N> = local_core.borrow();
+        builder.assert_bool(cols.is_valid);
+
+        let product: [AB::Expr; N] = MulAdd::multiply(cols.b, cols.c);
+        for ((a, product), e) in cols.a.into_iter().zip(product).zip(cols.e) {
+            builder.assert_eq(a, product + e);
+        }
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [
+                cols.b.map(Into::into),
+                cols.c.map(Into::into),
+                cols.e.map(Into::into),
+            ]
+            .into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: AB::Expr::from_canonical_usize(self.opcode()),
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MulAddRecord<F, const N: usize> {
+    pub a: [F; N],
+    pub b: [F; N],
+    pub c: [F; N],
+    pub e: [F; N],
+}
+
+#[derive(Debug)]
+pub struct MulAddCoreChip<const N: usize> {
+    pub air: MulAddCoreAir<N>,
+}
+
+impl<const N: usize> MulAddCoreChip<N> {
+    pub fn new(offset: usize) -> Self {
+        Self {
+            air: MulAddCoreAir { offset },
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const N: usize> VmCoreChip<F, I>
+    for MulAddCoreChip<N>
+where
+    I::Reads: Into<[[F; N]; 3]>,
+    I::Writes: From<[[F; N]; 1]>,
+{
+    type Record = MulAddRecord<F, N>;
+    type Air = MulAddCoreAir<N>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        _instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let [b, c, e] = reads.into();
+        let a = MulAdd::solve(b, c, e);
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [a].into(),
+        };
+
+        Ok((output, Self::Record { a, b, c, e }))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", MulAddOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let MulAddRecord { a, b, c, e } = record;
+        let cols: &mut MulAddCoreCols<_, N> = row_slice.borrow_mut();
+        cols.a = a;
+        cols.b = b;
+        cols.c = c;
+        cols.e = e;
+        cols.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub struct MulAdd;
+impl MulAdd {
+    pub(super) fn solve<F: Field, const N: usize>(b: [F; N], c: [F; N], e: [F; N]) -> [F; N] {
+        let product: [F; N] = Self::multiply(b, c);
+        array::from_fn(|i| product[i] + e[i])
+    }
+
+    /// Multiplies base field elements for `N = 1` and extension field elements for
+    /// `N = EXT_DEG`.
+    pub(crate) fn multiply<V, E, const N: usize>(x: [V; N], y: [V; N]) -> [E; N]
+    where
+        E: AbstractField,
+        V: Copy,
+        V: Mul<V, Output = E>,
+        E: Mul<V, Output = E>,
+        V: Add<V, Output = E>,
+        E: Add<V, Output = E>,
+    {
+        match N {
+            1 => array::from_fn(|_| x[0] * y[0]),
+            EXT_DEG => {
+                let product =
+                    FieldExtension::multiply(array::from_fn(|i| x[i]), array::from_fn(|i| y[i]));
+                array::from_fn(|i| product[i].clone())
+            }
+            _ => unreachable!("multiply-add is only supported for N = 1 and N = {EXT_DEG}"),
+        }
+    }
+}
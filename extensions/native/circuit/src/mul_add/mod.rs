@@ -0,0 +1,19 @@
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+
+use super::{
+    adapters::native_mul_add_adapter::{NativeMulAddAdapterAir, NativeMulAddAdapterChip},
+    EXT_DEG,
+};
+
+#[cfg(test)]
+mod tests;
+
+mod core;
+pub use core::*;
+
+pub type FieldMulAddAir = VmAirWrapper<NativeMulAddAdapterAir<1>, MulAddCoreAir<1>>;
+pub type FieldMulAddChip<F> = VmChipWrapper<F, NativeMulAddAdapterChip<F, 1>, MulAddCoreChip<1>>;
+
+pub type ExtensionMulAddAir = VmAirWrapper<NativeMulAddAdapterAir<EXT_DEG>, MulAddCoreAir<EXT_DEG>>;
+pub type ExtensionMulAddChip<F> =
+    VmChipWrapper<F, NativeMulAddAdapterChip<F, EXT_DEG>, MulAddCoreChip<EXT_DEG>>;
@@ -0,0 +1,114 @@
+use std::array;
+
+use openvm_circuit::arch::{
+    testing::{
+        memory::{gen_address_space, gen_pointer},
+        VmChipTestBuilder,
+    },
+    VmChipWrapper,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_native_compiler::MulAddOpcode;
+use openvm_stark_backend::{
+    p3_field::{extension::BinomialExtensionField, AbstractExtensionField, AbstractField},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{
+    super::adapters::native_mul_add_adapter::NativeMulAddAdapterChip, MulAdd, MulAddCoreChip,
+};
+use crate::EXT_DEG;
+
+type F = BabyBear;
+
+fn run_mul_add_air_test<const N: usize>(opcode: MulAddOpcode) {
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = VmChipWrapper::new(
+        NativeMulAddAdapterChip::<F, N>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        MulAddCoreChip::<N>::new(0),
+        tester.memory_controller(),
+    );
+    let trace_width = chip.trace_width();
+
+    let mut rng = create_seeded_rng();
+    let num_ops: usize = 7; // test padding with dummy row
+
+    for _ in 0..num_ops {
+        let address_space = gen_address_space(&mut rng);
+        // Consecutive operands, so that they do not overlap.
+        let base_address = gen_pointer(&mut rng, 4 * N);
+        let [b_address, c_address, e_address, a_address] = array::from_fn(|i| base_address + i * N);
+
+        let b: [F; N] = array::from_fn(|_| rng.gen());
+        let c: [F; N] = array::from_fn(|_| rng.gen());
+        let e: [F; N] = array::from_fn(|_| rng.gen());
+        tester.write(address_space, b_address, b);
+        tester.write(address_space, c_address, c);
+        tester.write(address_space, e_address, e);
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::from_usize(opcode.as_usize()),
+                [a_address, b_address, c_address, address_space, e_address],
+            ),
+        );
+        assert_eq!(
+            MulAdd::solve(b, c, e),
+            tester.read::<N>(address_space, a_address)
+        );
+    }
+
+    // positive test
+    let mut tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+
+    disable_debug_builder();
+    // negative test pranking each IO value
+    for height in [0, num_ops - 1] {
+        let mul_add_trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+        let original_trace = mul_add_trace.clone();
+        for width in 0..trace_width {
+            let prank_value = F::from_canonical_u32(rng.gen_range(1..=100));
+            mul_add_trace.row_mut(height)[width] = prank_value;
+        }
+
+        // Run a test after pranking each row
+        assert_eq!(
+            tester.simple_test().err(),
+            Some(VerificationError::OodEvaluationMismatch),
+            "Expected constraint to fail"
+        );
+        tester.air_proof_inputs[2].raw.common_main = Some(original_trace);
+    }
+}
+
+#[test]
+fn field_mul_add_air_test() {
+    run_mul_add_air_test::<1>(MulAddOpcode::MULADD);
+}
+
+#[test]
+fn extension_mul_add_air_test() {
+    run_mul_add_air_test::<EXT_DEG>(MulAddOpcode::BBE4MULADD);
+}
+
+#[test]
+fn extension_mul_add_consistency_test() {
+    type EF = BinomialExtensionField<F, EXT_DEG>;
+
+    let mut rng = create_seeded_rng();
+    for _ in 0..100 {
+        let [b, c, e]: [[F; EXT_DEG]; 3] = array::from_fn(|_| array::from_fn(|_| rng.gen()));
+        let expected = EF::from_base_slice(&b) * EF::from_base_slice(&c) + EF::from_base_slice(&e);
+        assert_eq!(MulAdd::solve(b, c, e), expected.as_base_slice());
+    }
+}
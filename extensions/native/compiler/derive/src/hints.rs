@@ -33,6 +33,16 @@ pub fn create_new_struct_and_impl_hintable(ast: &ItemStruct) -> Result<TokenStre
         let params = &ast.generics.params;
         quote! { < C: openvm_native_compiler::prelude::Config, #params >}
     };
+    // Fields may only be hintable for a specific config (e.g. `InnerVal`), so bound each field
+    // type instead of assuming it is hintable for every `C`.
+    let where_clause = {
+        let predicates = where_clause.map(|clause| clause.predicates.to_token_stream());
+        quote! {
+            where
+                #(#field_types: openvm_native_recursion::hints::Hintable<C>,)*
+                #predicates
+        }
+    };
     let input_struct_tokens: Vec<_> = field_names
         .iter()
         .zip(field_types.iter())
@@ -63,8 +73,8 @@ pub fn create_new_struct_and_impl_hintable(ast: &ItemStruct) -> Result<TokenStre
         .collect();
 
     Ok(quote! {
-        #[derive(openvm_native_compiler_derive::DslVariable, Debug, Clone)]
-        pub struct #name_var_ident #impl_generics  {
+        #[derive(openvm_native_compiler::prelude::DslVariable, Debug, Clone)]
+        pub struct #name_var_ident #impl_generics #where_clause {
             #(#input_struct_tokens)*
         }
 
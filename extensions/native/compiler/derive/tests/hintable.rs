@@ -1,7 +1,10 @@
-use openvm_native_compiler::prelude::*;
-use openvm_native_compiler_derive::Hintable;
-use openvm_native_recursion::{hints::InnerVal, types::InnerConfig};
-use openvm_stark_backend::p3_field::AbstractField;
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{asm::AsmBuilder, prelude::*};
+use openvm_native_recursion::{
+    hints::{Hintable, InnerChallenge, InnerVal, WitnessStream},
+    types::InnerConfig,
+};
+use openvm_stark_backend::p3_field::{AbstractExtensionField, AbstractField};
 
 #[derive(Hintable)]
 struct TestStruct {
@@ -10,10 +13,18 @@ struct TestStruct {
     c: usize,
 }
 
+#[derive(Hintable)]
+struct MixedStruct {
+    height: usize,
+    value: InnerVal,
+    challenge: InnerChallenge,
+    values: Vec<InnerVal>,
+}
+
 #[test]
 fn test_macro() {
     let x = TestStruct { a: 1, b: 2, c: 3 };
-    let stream = openvm_native_recursion::hints::Hintable::<InnerConfig>::write(&x);
+    let stream = Hintable::<InnerConfig>::write(&x);
     assert_eq!(
         stream,
         [1, 2, 3]
@@ -21,3 +32,40 @@ fn test_macro() {
             .to_vec()
     );
 }
+
+#[test]
+fn test_hintable_round_trip() {
+    let x = MixedStruct {
+        height: 7,
+        value: InnerVal::from_canonical_u32(11),
+        challenge: InnerChallenge::from_base_slice(&[1, 2, 3, 4].map(InnerVal::from_canonical_u32)),
+        values: [5, 6, 7].map(InnerVal::from_canonical_u32).to_vec(),
+    };
+    let stream = WitnessStream::<InnerConfig>::new()
+        .write(&x)
+        .write(&x.height)
+        .into_inner();
+
+    let mut builder = AsmBuilder::<InnerVal, InnerChallenge>::default();
+    builder.flags.debug = true;
+    let var = MixedStruct::read(&mut builder);
+    let height = usize::read(&mut builder);
+
+    builder.assert_var_eq(var.height, InnerVal::from_canonical_usize(x.height));
+    builder.assert_var_eq(height, InnerVal::from_canonical_usize(x.height));
+    let value: Felt<_> = builder.constant(x.value);
+    builder.assert_felt_eq(var.value, value);
+    let challenge: Ext<_, _> = builder.constant(x.challenge);
+    builder.assert_ext_eq(var.challenge, challenge);
+    let len: Var<_> = builder.constant(InnerVal::from_canonical_usize(x.values.len()));
+    builder.assert_var_eq(var.values.len(), len);
+    for (i, &val) in x.values.iter().enumerate() {
+        let actual = builder.get(&var.values, i);
+        let expected: Felt<_> = builder.constant(val);
+        builder.assert_felt_eq(actual, expected);
+    }
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, stream);
+}
@@ -4,6 +4,10 @@ use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32, TwoAdicField}
 use super::{config::AsmConfig, AsmCompiler};
 use crate::{
     conversion::{convert_program, CompilerOptions},
+    ir::{
+        display_ir, optimize, FuseMulAdd, IntrinsicRegistry, IrPass, LoopUnrolling, StackSlotReuse,
+        StrengthReduction,
+    },
     prelude::Builder,
 };
 
@@ -16,7 +20,42 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmBu
     }
 
     pub fn compile_isa_with_options(self, options: CompilerOptions) -> Program<F> {
-        let mut compiler = AsmCompiler::new(options.word_size);
+        self.compile_isa_with_intrinsics(options, IntrinsicRegistry::default())
+    }
+
+    /// Compiles the program, resolving its [DslIr::Intrinsic](crate::ir::DslIr::Intrinsic)
+    /// instructions with `intrinsics`.
+    pub fn compile_isa_with_intrinsics(
+        mut self,
+        options: CompilerOptions,
+        intrinsics: IntrinsicRegistry,
+    ) -> Program<F> {
+        if options.optimize_ir {
+            optimize(&mut self.operations);
+        }
+        if options.max_unroll_iterations > 0 {
+            LoopUnrolling {
+                max_iterations: options.max_unroll_iterations,
+            }
+            .run(&mut self.operations);
+            // Unrolled loop variables are immediates which can be folded into the bodies.
+            if options.optimize_ir {
+                optimize(&mut self.operations);
+            }
+        }
+        if options.strength_reduction {
+            StrengthReduction.run(&mut self.operations);
+        }
+        if options.fuse_mul_add {
+            FuseMulAdd.run(&mut self.operations);
+        }
+        if options.trace_ir {
+            tracing::debug!("DSL IR:\n{}", display_ir(&self.operations));
+        }
+        if options.reuse_stack_slots {
+            StackSlotReuse.run(&mut self.operations);
+        }
+        let mut compiler = AsmCompiler::new(options.word_size).with_intrinsics(intrinsics);
         compiler.build(self.operations);
         let asm_code = compiler.code();
         convert_program(asm_code, options)
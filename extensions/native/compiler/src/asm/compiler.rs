@@ -7,7 +7,10 @@ use openvm_stark_backend::p3_field::{ExtensionField, Field, PrimeField32, TwoAdi
 use super::{config::AsmConfig, AssemblyCode, BasicBlock, IndexTriple, ValueOrConst};
 use crate::{
     asm::AsmInstruction,
-    ir::{Array, DslIr, Ext, Felt, Ptr, RVar, Usize, Var},
+    ir::{
+        Array, DslIr, Ext, Felt, FunctionId, IntrinsicOperand, IntrinsicRegistry, Ptr, RVar, Usize,
+        Var,
+    },
     prelude::{MemIndex, TracedVec},
 };
 
@@ -34,8 +37,18 @@ pub struct AsmCompiler<F, EF> {
     break_counter: usize,
     contains_break: BTreeSet<F>,
     function_labels: BTreeMap<String, F>,
+    functions: BTreeMap<FunctionId, FunctionLabels<F>>,
     trap_label: F,
     word_size: usize,
+    intrinsics: IntrinsicRegistry,
+}
+
+/// The blocks of a function defined with [DslIr::FnDef].
+struct FunctionLabels<F> {
+    /// Block returning to the call site whose index is in the return site variable.
+    dispatch: F,
+    entry: F,
+    num_calls: usize,
 }
 
 impl<F> Var<F> {
@@ -78,12 +91,20 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
             break_label_map: BTreeMap::new(),
             contains_break: BTreeSet::new(),
             function_labels: BTreeMap::new(),
+            functions: BTreeMap::new(),
             break_counter: 0,
             trap_label: F::ONE,
             word_size,
+            intrinsics: IntrinsicRegistry::default(),
         }
     }
 
+    /// Sets the registry resolving the intrinsics of the program.
+    pub fn with_intrinsics(mut self, intrinsics: IntrinsicRegistry) -> Self {
+        self.intrinsics = intrinsics;
+        self
+    }
+
     /// Creates a new break label.
     pub fn new_break_label(&mut self) -> F {
         let label = self.break_counter;
@@ -285,6 +306,18 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                 DslIr::MulEFI(dst, lhs, rhs) => {
                     self.mul_ext_felti(dst, lhs, rhs, debug_info);
                 }
+                DslIr::MulAddF(dst, lhs, rhs, addend) => {
+                    self.push(
+                        AsmInstruction::MulAddF(dst.fp(), lhs.fp(), rhs.fp(), addend.fp()),
+                        debug_info,
+                    );
+                }
+                DslIr::MulAddE(dst, lhs, rhs, addend) => {
+                    self.push(
+                        AsmInstruction::MulAddE(dst.fp(), lhs.fp(), rhs.fp(), addend.fp()),
+                        debug_info,
+                    );
+                }
                 DslIr::CastFV(dst, src) => {
                     self.push(
                         AsmInstruction::AddFI(dst.fp(), src.fp(), F::ZERO),
@@ -385,6 +418,58 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                     let loop_compiler = LoopCompiler { compiler: self };
                     loop_compiler.compile(move |builder| builder.build(block), debug_info);
                 }
+                DslIr::FnDef(id, body) => {
+                    // The body is only entered by a call, so the code before it jumps over it.
+                    let before = self.block_label();
+                    // The branches to the call sites are added to the dispatch block as the calls
+                    // are compiled, and the next block traps if none of them is taken.
+                    self.basic_block();
+                    let dispatch = self.block_label();
+                    self.basic_block();
+                    self.push(AsmInstruction::j(self.trap_label), debug_info.clone());
+                    self.basic_block();
+                    let entry = self.block_label();
+                    self.function_labels.insert(format!("fn{}", id.0), entry);
+                    self.functions.insert(
+                        id,
+                        FunctionLabels {
+                            dispatch,
+                            entry,
+                            num_calls: 0,
+                        },
+                    );
+                    let break_label = self.break_label.take();
+                    self.build(body);
+                    self.break_label = break_label;
+                    self.basic_block();
+                    let after = self.block_label();
+                    self.push_to_block(before, AsmInstruction::j(after), debug_info);
+                }
+                DslIr::Call(id, return_site) => {
+                    let function = self
+                        .functions
+                        .get_mut(&id)
+                        .unwrap_or_else(|| panic!("function {id:?} is not defined"));
+                    let (dispatch, entry) = (function.dispatch, function.entry);
+                    let call = F::from_canonical_usize(function.num_calls);
+                    function.num_calls += 1;
+                    self.push(
+                        AsmInstruction::ImmF(return_site.fp(), call),
+                        debug_info.clone(),
+                    );
+                    self.push(AsmInstruction::j(entry), debug_info.clone());
+                    self.basic_block();
+                    let return_label = self.block_label();
+                    self.push_to_block(
+                        dispatch,
+                        AsmInstruction::BeqI(return_label, return_site.fp(), call),
+                        debug_info,
+                    );
+                }
+                DslIr::Ret(id, _) => {
+                    let dispatch = self.functions[&id].dispatch;
+                    self.push(AsmInstruction::j(dispatch), debug_info);
+                }
                 DslIr::AssertEqV(lhs, rhs) => {
                     // If lhs != rhs, execute TRAP
                     self.assert(lhs.fp(), ValueOrConst::Val(rhs.fp()), false, debug_info)
@@ -516,6 +601,20 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                         _ => unimplemented!(),
                     }
                 }
+                DslIr::Poseidon2AbsorbBabyBear(state, chunk) => match (state, chunk) {
+                    (Array::Dyn(state, _), Array::Dyn(chunk, _)) => self.push(
+                        AsmInstruction::Poseidon2Absorb(state.fp(), chunk.fp()),
+                        debug_info,
+                    ),
+                    _ => unimplemented!(),
+                },
+                DslIr::Poseidon2MerkleStepBabyBear(node, sibling, bit) => match (node, sibling) {
+                    (Array::Dyn(node, _), Array::Dyn(sibling, _)) => self.push(
+                        AsmInstruction::Poseidon2MerkleStep(node.fp(), sibling.fp(), bit.fp()),
+                        debug_info,
+                    ),
+                    _ => unimplemented!(),
+                },
                 DslIr::Error() => self.push(AsmInstruction::j(self.trap_label), debug_info),
                 DslIr::PrintF(dst) => {
                     self.push(AsmInstruction::PrintF(dst.fp()), debug_info);
@@ -581,6 +680,42 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                         debug_info,
                     );
                 }
+                DslIr::FriFold(result, eval_0, eval_1, beta, x) => {
+                    self.push(
+                        AsmInstruction::FriFold(
+                            result.fp(),
+                            eval_0.fp(),
+                            eval_1.fp(),
+                            beta.fp(),
+                            x.fp(),
+                        ),
+                        debug_info,
+                    );
+                }
+                DslIr::Intrinsic(id, outputs, inputs) => {
+                    let def = self
+                        .intrinsics
+                        .get(id)
+                        .unwrap_or_else(|| panic!("intrinsic {id:?} is not registered"));
+                    assert_eq!(
+                        (outputs.len(), inputs.len()),
+                        (def.num_outputs, def.num_inputs),
+                        "wrong number of outputs and inputs of intrinsic {}",
+                        def.name
+                    );
+                    let operands = outputs
+                        .iter()
+                        .chain(&inputs)
+                        .map(|operand| match operand {
+                            IntrinsicOperand::Var(var) => var.fp(),
+                            IntrinsicOperand::Felt(felt) => felt.fp(),
+                            IntrinsicOperand::Ext(ext) => ext.fp(),
+                            IntrinsicOperand::Ptr(ptr) => ptr.fp(),
+                        })
+                        .collect();
+                    let opcode = def.opcode;
+                    self.push(AsmInstruction::Intrinsic(opcode, operands), debug_info);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -1,6 +1,7 @@
 use alloc::{collections::BTreeMap, format};
 use core::fmt;
 
+use openvm_instructions::VmOpcode;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32};
 
 use super::A0;
@@ -67,6 +68,12 @@ pub enum AsmInstruction<F, EF> {
     /// Divide extension, dst = lhs / rhs.
     DivE(i32, i32, i32),
 
+    /// Multiply-add, dst = lhs * rhs + addend.
+    MulAddF(i32, i32, i32, i32),
+
+    /// Multiply-add extension, dst = lhs * rhs + addend.
+    MulAddE(i32, i32, i32, i32),
+
     /// Jump.
     Jump(i32, F),
 
@@ -110,10 +117,27 @@ pub enum AsmInstruction<F, EF> {
     /// Perform 2-to-1 cryptographic compression using Poseidon2.
     /// (a, b, c) are memory pointers to (dst, lhs, rhs)
     Poseidon2Compress(i32, i32, i32),
+    /// Absorb a chunk into a Poseidon2 sponge state in place, replacing the state with the
+    /// permutation of the chunk followed by the capacity half of the state.
+    /// (a, b) are memory pointers to (state, chunk)
+    Poseidon2Absorb(i32, i32),
+    /// Replace a Merkle tree node in place by the compression of the node and its sibling,
+    /// with the sibling on the left if the index bit is set.
+    /// (a, b, c) are memory pointers to (node, sibling, bit)
+    Poseidon2MerkleStep(i32, i32, i32),
 
     /// (a, b, res, len, alpha, alpha_pow)
     FriReducedOpening(i32, i32, i32, i32, i32, i32),
 
+    /// (res, eval_0, eval_1, beta, x)
+    FriFold(i32, i32, i32, i32, i32),
+
+    /// Intrinsic(opcode, operands).
+    ///
+    /// Instruction of a VM intrinsic, see [IntrinsicDef](crate::ir::IntrinsicDef) for the fields
+    /// of its operands.
+    Intrinsic(VmOpcode, Vec<i32>),
+
     /// Print a variable.
     PrintV(i32),
 
@@ -225,6 +249,20 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
             AsmInstruction::DivE(dst, lhs, rhs) => {
                 write!(f, "ediv  ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
             }
+            AsmInstruction::MulAddF(dst, lhs, rhs, addend) => {
+                write!(
+                    f,
+                    "madd  ({})fp, ({})fp, ({})fp, ({})fp",
+                    dst, lhs, rhs, addend
+                )
+            }
+            AsmInstruction::MulAddE(dst, lhs, rhs, addend) => {
+                write!(
+                    f,
+                    "emadd ({})fp, ({})fp, ({})fp, ({})fp",
+                    dst, lhs, rhs, addend
+                )
+            }
             AsmInstruction::Jump(dst, label) => {
                 write!(
                     f,
@@ -311,6 +349,16 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
             AsmInstruction::Poseidon2Permute(dst, lhs) => {
                 write!(f, "poseidon2_permute ({})fp, ({})fp", dst, lhs)
             }
+            AsmInstruction::Poseidon2Absorb(state, chunk) => {
+                write!(f, "poseidon2_absorb ({})fp, ({})fp", state, chunk)
+            }
+            AsmInstruction::Poseidon2MerkleStep(node, sibling, bit) => {
+                write!(
+                    f,
+                    "poseidon2_merkle_step ({})fp, ({})fp, ({})fp",
+                    node, sibling, bit
+                )
+            }
             AsmInstruction::Poseidon2Compress(result, src1, src2) => {
                 write!(
                     f,
@@ -318,6 +366,13 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
                     result, src1, src2
                 )
             }
+            AsmInstruction::Intrinsic(opcode, operands) => {
+                write!(f, "intrinsic {}", opcode)?;
+                for operand in operands {
+                    write!(f, ", ({})fp", operand)?;
+                }
+                Ok(())
+            }
             AsmInstruction::PrintF(dst) => {
                 write!(f, "print_f ({})fp", dst)
             }
@@ -347,6 +402,13 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
                     a, b, res, len, alpha, alpha_pow
                 )
             }
+            AsmInstruction::FriFold(res, eval_0, eval_1, beta, x) => {
+                write!(
+                    f,
+                    "fri_fold ({})fp, ({})fp, ({})fp, ({})fp, ({})fp",
+                    res, eval_0, eval_1, beta, x
+                )
+            }
         }
     }
 }
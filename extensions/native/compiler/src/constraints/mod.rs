@@ -158,6 +158,13 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                     opcode: ConstraintOpcode::SubV,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
                 }),
+                DslIr::SubVIN(a, b, c) => {
+                    let tmp = self.alloc_v(&mut constraints, b);
+                    constraints.push(Constraint {
+                        opcode: ConstraintOpcode::SubV,
+                        args: vec![vec![a.id()], vec![tmp], vec![c.id()]],
+                    });
+                }
                 DslIr::SubF(a, b, c) => constraints.push(Constraint {
                     opcode: ConstraintOpcode::SubF,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
@@ -170,6 +177,13 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                     opcode: ConstraintOpcode::SubEF,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
                 }),
+                DslIr::SubEFI(a, b, c) => {
+                    let tmp = self.alloc_f(&mut constraints, c);
+                    constraints.push(Constraint {
+                        opcode: ConstraintOpcode::SubEF,
+                        args: vec![vec![a.id()], vec![b.id()], vec![tmp]],
+                    });
+                }
                 DslIr::SubEI(a, b, c) => {
                     let tmp = self.alloc_e(&mut constraints, c);
                     constraints.push(Constraint {
@@ -199,6 +213,13 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                     opcode: ConstraintOpcode::MulF,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
                 }),
+                DslIr::MulFI(a, b, c) => {
+                    let tmp = self.alloc_f(&mut constraints, c);
+                    constraints.push(Constraint {
+                        opcode: ConstraintOpcode::MulF,
+                        args: vec![vec![a.id()], vec![b.id()], vec![tmp]],
+                    });
+                }
                 DslIr::MulE(a, b, c) => constraints.push(Constraint {
                     opcode: ConstraintOpcode::MulE,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
@@ -214,6 +235,13 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                     opcode: ConstraintOpcode::MulEF,
                     args: vec![vec![a.id()], vec![b.id()], vec![c.id()]],
                 }),
+                DslIr::MulEFI(a, b, c) => {
+                    let tmp = self.alloc_f(&mut constraints, c);
+                    constraints.push(Constraint {
+                        opcode: ConstraintOpcode::MulEF,
+                        args: vec![vec![a.id()], vec![b.id()], vec![tmp]],
+                    });
+                }
                 DslIr::DivFIN(a, b, c) => {
                     let tmp = self.alloc_f(&mut constraints, b.inverse());
                     constraints.push(Constraint {
@@ -357,6 +385,15 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                         vec![a[3].id()],
                     ],
                 }),
+                DslIr::CastFV(a, b) => constraints.push(Constraint {
+                    opcode: ConstraintOpcode::CastFV,
+                    args: vec![vec![a.id()], vec![b.id()]],
+                }),
+                DslIr::CircuitPublish(a, index) => constraints.push(Constraint {
+                    opcode: ConstraintOpcode::Publish,
+                    args: vec![vec![a.id()], vec![index.to_string()]],
+                }),
+                DslIr::CycleTrackerStart(_) | DslIr::CycleTrackerEnd(_) => {}
                 _ => panic!("unsupported {:?}", instruction),
             };
         }
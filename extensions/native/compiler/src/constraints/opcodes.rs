@@ -43,4 +43,8 @@ pub enum ConstraintOpcode {
     CommitVkeyHash,
     CommitCommitedValuesDigest,
     CircuitFelts2Ext,
+    /// Reduces a felt to its canonical value and assigns it to a var.
+    CastFV,
+    /// Exposes a var as the public input at the given index.
+    Publish,
 }
@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, fmt};
+
+use openvm_instructions::{
+    program::Program, Poseidon2Opcode, PublishOpcode, SystemOpcode, UsizeOpcode,
+};
+use openvm_rv32im_transpiler::BranchEqualOpcode;
+use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+use strum::EnumCount;
+
+use super::CompilerOptions;
+use crate::{
+    CastfOpcode, FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, MulAddOpcode,
+    NativeBranchEqualOpcode, NativeJalOpcode, NativeLoadStoreOpcode,
+};
+
+/// Static estimate of the number of instructions a compiled program executes.
+#[derive(Clone, Debug, Default)]
+pub struct CycleEstimate {
+    /// Basic blocks in program order.
+    pub blocks: Vec<BlockEstimate>,
+    /// Loops in program order, with the trip count used for the estimate.
+    pub loops: Vec<LoopEstimate>,
+    /// Estimated number of executed instructions per chip.
+    pub chips: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockEstimate {
+    pub start_pc: u32,
+    pub num_instructions: usize,
+    /// Number of times the block is estimated to run.
+    pub executions: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoopEstimate {
+    /// Target of the backward branch closing the loop. Trip counts are keyed by this pc.
+    pub header_pc: u32,
+    /// Pc of the backward branch.
+    pub end_pc: u32,
+    pub iterations: u64,
+}
+
+impl CycleEstimate {
+    pub fn total_cycles(&self) -> u64 {
+        self.blocks
+            .iter()
+            .map(|block| block.num_instructions as u64 * block.executions)
+            .sum()
+    }
+}
+
+impl fmt::Display for CycleEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total cycles: {}", self.total_cycles())?;
+        for LoopEstimate {
+            header_pc,
+            end_pc,
+            iterations,
+        } in &self.loops
+        {
+            writeln!(f, "loop {header_pc}..={end_pc}: {iterations} iterations")?;
+        }
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "block {}: {} instructions x {}",
+                block.start_pc, block.num_instructions, block.executions
+            )?;
+        }
+        for (chip, cycles) in &self.chips {
+            writeln!(f, "{chip}: {cycles}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimates the instructions executed by a program compiled from the DSL, without running it.
+///
+/// Every backward branch closes a loop. A loop runs `loop_bounds[header_pc]` times, or
+/// [`CompilerOptions::estimated_loop_iterations`] times if it has no entry. Both sides of a
+/// conditional branch are assumed to run, so the estimate is an upper bound for straight-line
+/// code with `if`s.
+pub fn estimate_cycles<F: PrimeField32>(
+    program: &Program<F>,
+    options: &CompilerOptions,
+    loop_bounds: &BTreeMap<u32, u64>,
+) -> CycleEstimate {
+    let instructions = program.enumerate_by_pc();
+
+    let mut leaders = vec![];
+    let mut loops: BTreeMap<u32, u32> = BTreeMap::new();
+    if let Some((pc, _, _)) = instructions.first() {
+        leaders.push(*pc);
+    }
+    for (pc, instruction, _) in &instructions {
+        let offset = match branch_class(instruction.opcode.as_usize()) {
+            Some(BranchClass::Jal) => instruction.b,
+            Some(BranchClass::BranchEqual) => instruction.c,
+            None => continue,
+        };
+        let target = (F::from_canonical_u32(*pc) + offset).as_canonical_u32();
+        leaders.push(target);
+        leaders.push(pc + program.step);
+        if target <= *pc {
+            let end = loops.entry(target).or_default();
+            *end = (*end).max(*pc);
+        }
+    }
+    leaders.sort_unstable();
+    leaders.dedup();
+
+    let loops = loops
+        .into_iter()
+        .map(|(header_pc, end_pc)| LoopEstimate {
+            header_pc,
+            end_pc,
+            iterations: loop_bounds
+                .get(&header_pc)
+                .copied()
+                .unwrap_or(options.estimated_loop_iterations as u64),
+        })
+        .collect::<Vec<_>>();
+    let executions = |pc: u32| {
+        loops
+            .iter()
+            .filter(|l| (l.header_pc..=l.end_pc).contains(&pc))
+            .map(|l| l.iterations)
+            .product::<u64>()
+    };
+
+    let mut blocks: Vec<BlockEstimate> = vec![];
+    let mut chips = BTreeMap::new();
+    for (pc, instruction, _) in &instructions {
+        if blocks.is_empty() || leaders.binary_search(pc).is_ok() {
+            blocks.push(BlockEstimate {
+                start_pc: *pc,
+                num_instructions: 0,
+                executions: executions(*pc),
+            });
+        }
+        let block = blocks.last_mut().unwrap();
+        block.num_instructions += 1;
+        *chips
+            .entry(chip_name(instruction.opcode.as_usize()))
+            .or_default() += block.executions;
+    }
+
+    CycleEstimate {
+        blocks,
+        loops,
+        chips,
+    }
+}
+
+enum BranchClass {
+    Jal,
+    BranchEqual,
+}
+
+fn branch_class(opcode: usize) -> Option<BranchClass> {
+    if in_class(
+        opcode,
+        NativeJalOpcode::default_offset(),
+        NativeJalOpcode::COUNT,
+    ) {
+        Some(BranchClass::Jal)
+    } else if in_class(
+        opcode,
+        NativeBranchEqualOpcode::default_offset(),
+        BranchEqualOpcode::COUNT,
+    ) {
+        Some(BranchClass::BranchEqual)
+    } else {
+        None
+    }
+}
+
+fn chip_name(opcode: usize) -> &'static str {
+    let classes = [
+        (
+            "System",
+            SystemOpcode::default_offset(),
+            SystemOpcode::COUNT,
+        ),
+        (
+            "LoadStore",
+            NativeLoadStoreOpcode::default_offset(),
+            NativeLoadStoreOpcode::COUNT,
+        ),
+        (
+            "BranchEqual",
+            NativeBranchEqualOpcode::default_offset(),
+            BranchEqualOpcode::COUNT,
+        ),
+        (
+            "Jal",
+            NativeJalOpcode::default_offset(),
+            NativeJalOpcode::COUNT,
+        ),
+        (
+            "Publish",
+            PublishOpcode::default_offset(),
+            PublishOpcode::COUNT,
+        ),
+        ("Castf", CastfOpcode::default_offset(), CastfOpcode::COUNT),
+        (
+            "FieldArithmetic",
+            FieldArithmeticOpcode::default_offset(),
+            FieldArithmeticOpcode::COUNT,
+        ),
+        (
+            "FieldExtension",
+            FieldExtensionOpcode::default_offset(),
+            FieldExtensionOpcode::COUNT,
+        ),
+        (
+            "Poseidon2",
+            Poseidon2Opcode::default_offset(),
+            Poseidon2Opcode::COUNT,
+        ),
+        ("Fri", FriOpcode::default_offset(), FriOpcode::COUNT),
+        (
+            "MulAdd",
+            MulAddOpcode::default_offset(),
+            MulAddOpcode::COUNT,
+        ),
+    ];
+    classes
+        .into_iter()
+        .find(|&(_, offset, count)| in_class(opcode, offset, count))
+        .map_or("Unknown", |(name, _, _)| name)
+}
+
+fn in_class(opcode: usize, offset: usize, count: usize) -> bool {
+    (offset..offset + count).contains(&opcode)
+}
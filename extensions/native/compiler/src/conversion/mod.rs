@@ -9,13 +9,20 @@ use openvm_rv32im_transpiler::BranchEqualOpcode;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32, PrimeField64};
 use serde::{Deserialize, Serialize};
 
+mod estimate;
+pub use estimate::*;
+
 use crate::{
     asm::{AsmInstruction, AssemblyCode},
-    FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
+    ir::IntrinsicDef,
+    FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, MulAddOpcode, NativeBranchEqualOpcode,
     NativeJalOpcode, NativeLoadStoreOpcode, NativePhantom,
 };
 
+/// Missing fields deserialize to their [Default] values, so options serialized before a field was
+/// added still load.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CompilerOptions {
     // The compiler will ensure that the heap pointer is aligned to be a multiple of `word_size`.
     pub word_size: usize,
@@ -23,6 +30,30 @@ pub struct CompilerOptions {
     pub enable_cycle_tracker: bool,
     pub field_arithmetic_enabled: bool,
     pub field_extension_enabled: bool,
+    /// Runs constant folding, common subexpression elimination and dead code elimination on the
+    /// DSL program before compiling it. Disabled by default because it changes the compiled
+    /// program, and with it the commitment of existing programs.
+    pub optimize_ir: bool,
+    /// Lets variables with disjoint live ranges share a stack slot. Disabled by default for the
+    /// same reason as `optimize_ir`; leave it off to give every variable its own address when
+    /// debugging memory accesses of a program.
+    pub reuse_stack_slots: bool,
+    /// Logs the DSL IR at `debug` level before compiling it, with the builder call site of every
+    /// instruction.
+    pub trace_ir: bool,
+    /// Unrolls `For` loops with constant bounds and at most this many iterations. Unrolling
+    /// trades program size for the loop counter update and branch of every iteration.
+    pub max_unroll_iterations: usize,
+    /// Replaces multiplications of extension elements by small constants with extension
+    /// additions, see `StrengthReduction`. Requires `field_extension_enabled`.
+    pub strength_reduction: bool,
+    /// Fuses a multiplication followed by an addition of its result into one multiply-add
+    /// instruction. Requires a VM with the multiply-add chips, see `Native::mul_add` of the native
+    /// circuit.
+    pub fuse_mul_add: bool,
+    /// Trip count assumed by [`estimate_cycles`] for loops without a known bound. Does not
+    /// affect compilation.
+    pub estimated_loop_iterations: usize,
 }
 
 impl Default for CompilerOptions {
@@ -33,6 +64,13 @@ impl Default for CompilerOptions {
             enable_cycle_tracker: false,
             field_arithmetic_enabled: true,
             field_extension_enabled: true,
+            optimize_ir: false,
+            reuse_stack_slots: false,
+            trace_ir: false,
+            max_unroll_iterations: 0,
+            strength_reduction: false,
+            fuse_mul_add: false,
+            estimated_loop_iterations: 1,
         }
     }
 }
@@ -46,6 +84,18 @@ impl CompilerOptions {
         self.enable_cycle_tracker = true;
         self
     }
+    pub fn with_ir_optimization(mut self) -> Self {
+        self.optimize_ir = true;
+        self
+    }
+    pub fn with_stack_slot_reuse(mut self) -> Self {
+        self.reuse_stack_slots = true;
+        self
+    }
+    pub fn with_loop_unrolling(mut self, max_iterations: usize) -> Self {
+        self.max_unroll_iterations = max_iterations;
+        self
+    }
 }
 
 fn inst<F: PrimeField64>(opcode: VmOpcode, a: F, b: F, c: F, d: AS, e: AS) -> Instruction<F> {
@@ -602,6 +652,26 @@ fn convert_instruction<F: PrimeField32, EF: ExtensionField<F>>(
                 )
             }
         }
+        AsmInstruction::MulAddF(dst, lhs, rhs, addend) => vec![Instruction {
+            opcode: options.opcode_with_offset(MulAddOpcode::MULADD),
+            a: i32_f(dst),
+            b: i32_f(lhs),
+            c: i32_f(rhs),
+            d: AS::Memory.to_field(),
+            e: i32_f(addend),
+            f: F::ZERO,
+            g: F::ZERO,
+        }],
+        AsmInstruction::MulAddE(dst, lhs, rhs, addend) => vec![Instruction {
+            opcode: options.opcode_with_offset(MulAddOpcode::BBE4MULADD),
+            a: i32_f(dst),
+            b: i32_f(lhs),
+            c: i32_f(rhs),
+            d: AS::Memory.to_field(),
+            e: i32_f(addend),
+            f: F::ZERO,
+            g: F::ZERO,
+        }],
         AsmInstruction::Poseidon2Compress(dst, src1, src2) => vec![inst(
             options.opcode_with_offset(Poseidon2Opcode::COMP_POS2),
             i32_f(dst),
@@ -618,6 +688,22 @@ fn convert_instruction<F: PrimeField32, EF: ExtensionField<F>>(
             AS::Memory,
             AS::Memory,
         )],
+        AsmInstruction::Poseidon2Absorb(state, chunk) => vec![inst(
+            options.opcode_with_offset(Poseidon2Opcode::ABSORB_POS2),
+            i32_f(state),
+            i32_f(chunk),
+            F::ZERO,
+            AS::Memory,
+            AS::Memory,
+        )],
+        AsmInstruction::Poseidon2MerkleStep(node, sibling, bit) => vec![inst(
+            options.opcode_with_offset(Poseidon2Opcode::MERKLE_STEP_POS2),
+            i32_f(node),
+            i32_f(sibling),
+            i32_f(bit),
+            AS::Memory,
+            AS::Memory,
+        )],
         AsmInstruction::CycleTrackerStart() => {
             if options.enable_cycle_tracker {
                 vec![Instruction::debug(PhantomDiscriminant(SysPhantom::CtStart as u16))]
@@ -651,6 +737,38 @@ fn convert_instruction<F: PrimeField32, EF: ExtensionField<F>>(
             f: i32_f(alpha),
             g: i32_f(alpha_pow),
         }],
+        AsmInstruction::FriFold(res, eval_0, eval_1, beta, x) => vec![Instruction {
+            opcode: options.opcode_with_offset(FriOpcode::FRI_FOLD),
+            a: i32_f(res),
+            b: i32_f(eval_0),
+            c: i32_f(eval_1),
+            d: AS::Memory.to_field(),
+            e: i32_f(beta),
+            f: i32_f(x),
+            g: F::ZERO,
+        }],
+        AsmInstruction::Intrinsic(opcode, operands) => {
+            let mut fields = [F::ZERO; IntrinsicDef::MAX_OPERANDS];
+            for (field, &operand) in fields.iter_mut().zip(&operands) {
+                *field = i32_f(operand);
+            }
+            let [a, b, c, e, f, g] = fields;
+            let e = if operands.len() <= 3 {
+                AS::Memory.to_field()
+            } else {
+                e
+            };
+            vec![Instruction {
+                opcode,
+                a,
+                b,
+                c,
+                d: AS::Memory.to_field(),
+                e,
+                f,
+                g,
+            }]
+        }
     };
 
     let debug_infos = vec![debug_info; instructions.len()];
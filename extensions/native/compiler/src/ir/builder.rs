@@ -109,6 +109,7 @@ pub struct Builder<C: Config> {
     pub(crate) var_count: u32,
     pub(crate) felt_count: u32,
     pub(crate) ext_count: u32,
+    pub(crate) fn_count: u32,
     pub operations: TracedVec<DslIr<C>>,
     pub(crate) nb_public_values: Option<Var<C::N>>,
     pub(crate) witness_var_count: u32,
@@ -125,6 +126,7 @@ impl<C: Config> Builder<C> {
             var_count: self.var_count,
             felt_count: self.felt_count,
             ext_count: self.ext_count,
+            fn_count: self.fn_count,
             // Witness counts are only used when the target is a gnark circuit.  And sub-builders are
             // not used when the target is a gnark circuit, so it's fine to set the witness counts to 0.
             witness_var_count: 0,
@@ -318,6 +320,37 @@ impl<C: Config> Builder<C> {
         }
     }
 
+    /// Evaluates the arm whose key equals `value`, or `default` if no key matches.
+    ///
+    /// The native kernel has no indirect jumps, so this compiles to a chain of equality branches
+    /// rather than a jump table. Keys are tested in order.
+    pub fn switch<V: Into<SymbolicVar<C::N>>>(
+        &mut self,
+        value: V,
+        arms: &mut [(C::N, &mut dyn FnMut(&mut Builder<C>))],
+        default: &mut dyn FnMut(&mut Builder<C>),
+    ) {
+        let value: Var<C::N> = self.eval(value.into());
+        self.switch_impl(value, arms, default);
+    }
+
+    fn switch_impl(
+        &mut self,
+        value: Var<C::N>,
+        arms: &mut [(C::N, &mut dyn FnMut(&mut Builder<C>))],
+        default: &mut dyn FnMut(&mut Builder<C>),
+    ) {
+        match arms.split_first_mut() {
+            None => default(self),
+            Some(((key, arm), rest)) => {
+                self.if_eq(value, *key).then_or_else(
+                    |builder| arm(builder),
+                    |builder| builder.switch_impl(value, rest, default),
+                );
+            }
+        }
+    }
+
     /// Evaluate a block of operations if two expressions are equal.
     pub fn if_eq<LhsExpr: Into<SymbolicVar<C::N>>, RhsExpr: Into<SymbolicVar<C::N>>>(
         &mut self,
@@ -408,19 +441,30 @@ impl<C: Config> Builder<C> {
 
     pub fn hint_var(&mut self) -> Var<C::N> {
         let arr = self.hint_vars();
+        self.assert_single_hint(&arr);
         self.get(&arr, RVar::zero())
     }
 
     pub fn hint_felt(&mut self) -> Felt<C::F> {
         let arr = self.hint_felts();
+        self.assert_single_hint(&arr);
         self.get(&arr, RVar::zero())
     }
 
     pub fn hint_ext(&mut self) -> Ext<C::F, C::EF> {
         let arr = self.hint_exts();
+        self.assert_single_hint(&arr);
         self.get(&arr, RVar::zero())
     }
 
+    /// In debug mode, asserts that a hinted vector which is read as a single value has exactly
+    /// one element. This catches reads which are out of order with the writes on the host.
+    fn assert_single_hint<V: MemVariable<C>>(&mut self, arr: &Array<C, V>) {
+        if self.flags.debug {
+            self.assert_var_eq(arr.len(), C::N::ONE);
+        }
+    }
+
     /// Hint a vector of variables.
     ///
     /// Writes the next element of the witness stream into memory and returns it.
@@ -6,8 +6,8 @@ use openvm_stark_backend::p3_field::AbstractField;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    Builder, Config, FromConstant, MemIndex, MemVariable, Ptr, RVar, Ref, SymbolicVar, Usize, Var,
-    Variable,
+    Builder, CanSelect, Config, FromConstant, MemIndex, MemVariable, Ptr, RVar, Ref, SymbolicVar,
+    Usize, Var, Variable,
 };
 
 /// A logical array.
@@ -189,7 +189,7 @@ impl<C: Config> Builder<C> {
                         panic!("Cannot get an uninitialized element in a fixed slice");
                     }
                 } else {
-                    panic!("Cannot index into a fixed slice with a variable size")
+                    panic!("Cannot index into a fixed slice with a variable size, use `select_index` instead")
                 }
             }
             Array::Dyn(ptr, len) => {
@@ -209,6 +209,62 @@ impl<C: Config> Builder<C> {
         }
     }
 
+    /// Gets the element of `slice` at `index`. Unlike [Builder::get], an [Array::Fixed] can be
+    /// indexed by a variable in static mode: the element is picked by a tree of selects over the
+    /// bits of `index`, which costs `O(len)` constraints. `index` is constrained to be in bounds.
+    pub fn select_index<V, I>(&mut self, slice: &Array<C, V>, index: I) -> V
+    where
+        V: MemVariable<C> + CanSelect<C>,
+        I: Into<RVar<C::N>>,
+    {
+        let index = index.into();
+        let (Array::Fixed(values), RVar::Val(index)) = (slice, index) else {
+            return self.get(slice, index);
+        };
+        assert!(
+            self.flags.static_only,
+            "Fixed slices can only be indexed with a variable in static mode"
+        );
+        let mut layer = values
+            .borrow()
+            .iter()
+            .map(|v| {
+                v.clone()
+                    .expect("Cannot get an uninitialized element in a fixed slice")
+            })
+            .collect_vec();
+        let len = layer.len();
+        assert!(len > 0, "Cannot index into an empty fixed slice");
+
+        if len == 1 || !len.is_power_of_two() {
+            // The bit decomposition only bounds `index` by the next power of two, and does not
+            // bound it at all for a single element.
+            let prod: Var<_> = self.eval(C::N::ONE);
+            for i in 0..len {
+                self.assign(&prod, prod * (index - C::N::from_canonical_usize(i)));
+            }
+            self.assert_var_eq(prod, C::N::ZERO);
+        }
+        let num_bits = len.next_power_of_two().trailing_zeros() as usize;
+        let bits = self.num2bits_v_circuit(index, num_bits);
+        for bit in bits {
+            // An unpaired last element is only reachable when `bit` is 0.
+            layer = layer
+                .into_iter()
+                .chunks(2)
+                .into_iter()
+                .map(|mut pair| {
+                    let even = pair.next().unwrap();
+                    match pair.next() {
+                        Some(odd) => V::select(self, bit, odd, even),
+                        None => even,
+                    }
+                })
+                .collect();
+        }
+        layer.pop().unwrap()
+    }
+
     /// Returns a pointer to the array at the specified `index` within the given `slice`.
     pub fn get_ptr<V: MemVariable<C>, I: Into<RVar<C::N>>>(
         &mut self,
@@ -322,7 +378,7 @@ impl<C: Config> Builder<C> {
                     let idx = index.value();
                     v.borrow_mut()[idx] = Some(value);
                 } else {
-                    panic!("Cannot index into a fixed slice with a variable size")
+                    panic!("Cannot index into a fixed slice with a variable size, use `select_index` instead")
                 }
             }
             Array::Dyn(ptr, _) => {
@@ -18,4 +18,19 @@ impl<C: Config> Builder<C> {
         ));
         result
     }
+
+    /// Folds the evaluations `eval_0` at `x` and `eval_1` at `-x` of a polynomial into the
+    /// evaluation at `x^2` of its fold with challenge `beta`, as in one round of a FRI query.
+    pub fn fri_fold(
+        &mut self,
+        eval_0: Ext<C::F, C::EF>,
+        eval_1: Ext<C::F, C::EF>,
+        beta: Ext<C::F, C::EF>,
+        x: Ext<C::F, C::EF>,
+    ) -> Ext<C::F, C::EF> {
+        let result = self.uninit();
+        self.operations
+            .push(crate::ir::DslIr::FriFold(result, eval_0, eval_1, beta, x));
+        result
+    }
 }
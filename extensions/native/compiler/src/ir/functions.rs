@@ -0,0 +1,94 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use super::{passes::slot_counts, Builder, Config, DslIr, Var, Variable};
+
+/// Identifier of a function defined with [Builder::fn_def].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FunctionId(pub u32);
+
+/// A function of the DSL, taking an `I` and returning an `O`.
+///
+/// Functions use static frames: the parameter, the result and the locals of a function are
+/// variables with stack slots of their own, which every call shares. A call therefore only
+/// assigns the parameter, jumps to the body and copies the result out. Since a function can only
+/// call the functions defined before it, there is no recursion and no frame is ever live twice.
+///
+/// The native ISA has no indirect jump, so a call stores the index of its call site in
+/// `return_site` and the body returns through a dispatch over the call sites.
+#[derive(Debug, Clone)]
+pub struct DslFunction<C: Config, I, O> {
+    id: FunctionId,
+    return_site: Var<C::N>,
+    input: I,
+    output: O,
+    _marker: PhantomData<C>,
+}
+
+impl<C: Config, I, O> DslFunction<C, I, O> {
+    pub fn id(&self) -> FunctionId {
+        self.id
+    }
+}
+
+impl<C: Config> Builder<C> {
+    /// Defines a function whose body is built by `f` from the parameter.
+    ///
+    /// Functions are only supported by the ASM backend, and must be defined at the top level of
+    /// the program, outside of any loop or branch.
+    pub fn fn_def<I: Variable<C>, O: Variable<C>>(
+        &mut self,
+        f: impl FnOnce(&mut Builder<C>, I) -> O,
+    ) -> DslFunction<C, I, O> {
+        assert!(
+            !self.is_sub_builder && !self.flags.static_only,
+            "functions must be defined at the top level of a program with control flow"
+        );
+        // The ids of the variables of a nested block are reused after the block, although such a
+        // variable may still be read there. The frame of the function must not overlap any of
+        // them.
+        let (var_count, felt_count, ext_count) = slot_counts(&mut self.operations);
+        self.var_count = self.var_count.max(var_count);
+        self.felt_count = self.felt_count.max(felt_count);
+        self.ext_count = self.ext_count.max(ext_count);
+
+        let id = FunctionId(self.fn_count);
+        self.fn_count += 1;
+        let return_site = self.uninit();
+        let input: I = self.uninit();
+        let output: O = self.uninit();
+
+        let mut body = self.create_sub_builder();
+        let result = f(&mut body, input.clone());
+        output.assign(result.into(), &mut body);
+        body.push(DslIr::Ret(id, return_site));
+        // Later variables must not share the slots of the frame.
+        self.var_count = body.var_count;
+        self.felt_count = body.felt_count;
+        self.ext_count = body.ext_count;
+
+        self.push(DslIr::FnDef(id, body.operations));
+        DslFunction {
+            id,
+            return_site,
+            input,
+            output,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Calls `function` on `input` and returns a copy of its result.
+    pub fn fn_call<I: Variable<C>, O: Variable<C>>(
+        &mut self,
+        function: &DslFunction<C, I, O>,
+        input: impl Into<I::Expression>,
+    ) -> O {
+        function.input.assign(input.into(), self);
+        self.push(DslIr::Call(function.id, function.return_site));
+        // The next call overwrites the result.
+        let output: O = self.uninit();
+        output.assign(function.output.clone().into(), self);
+        output
+    }
+}
@@ -1,6 +1,12 @@
+use std::fmt::Write;
+
+use openvm_instructions::instruction::call_site;
 use serde::{Deserialize, Serialize};
 
-use super::{Array, Config, Ext, Felt, MemIndex, Ptr, RVar, TracedVec, Var};
+use super::{
+    Array, Config, Ext, Felt, FunctionId, IntrinsicId, IntrinsicOperand, MemIndex, Ptr, RVar,
+    TracedVec, Var,
+};
 
 /// An intermeddiate instruction set for implementing programs.
 ///
@@ -77,6 +83,15 @@ pub enum DslIr<C: Config> {
     MulEFI(Ext<C::F, C::EF>, Ext<C::F, C::EF>, C::F),
     /// Multiplies an extension field element and a field element (ext = ext * felt).
     MulEF(Ext<C::F, C::EF>, Ext<C::F, C::EF>, Felt<C::F>),
+    /// Multiplies two field elements and adds a third (felt = felt * felt + felt).
+    MulAddF(Felt<C::F>, Felt<C::F>, Felt<C::F>, Felt<C::F>),
+    /// Multiplies two extension field elements and adds a third (ext = ext * ext + ext).
+    MulAddE(
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+    ),
 
     // Divisions.
     /// Divides two variables (var = var / var).
@@ -142,6 +157,13 @@ pub enum DslIr<C: Config> {
     IfNeI(Var<C::N>, C::N, TracedVec<DslIr<C>>, TracedVec<DslIr<C>>),
     /// Break out of a loop.
     Break,
+    /// Defines a function with the parameters (id, body). The body is only executed by a `Call`
+    /// and ends with a `Ret`. Should only be used when target is the native ISA.
+    FnDef(FunctionId, TracedVec<DslIr<C>>),
+    /// Calls a function with the parameters (id, return site variable).
+    Call(FunctionId, Var<C::N>),
+    /// Returns from a function to the call site held by the parameters (id, return site variable).
+    Ret(FunctionId, Var<C::N>),
 
     // Assertions.
     /// Assert that two variables are equal (var == var).
@@ -204,6 +226,12 @@ pub enum DslIr<C: Config> {
         Array<C, Felt<C::F>>,
         Array<C, Felt<C::F>>,
     ),
+    /// Absorbs a chunk of baby bear elements into a Poseidon2 sponge state in place
+    /// (state = p2_permute(chunk || state[HASH_RATE..])).
+    Poseidon2AbsorbBabyBear(Array<C, Felt<C::F>>, Array<C, Felt<C::F>>),
+    /// Replaces a Merkle tree node in place by its parent, given its sibling and index bit
+    /// (node = p2_compress(bit ? sibling || node : node || sibling)).
+    Poseidon2MerkleStepBabyBear(Array<C, Felt<C::F>>, Array<C, Felt<C::F>>, Var<C::N>),
     /// Permutes an array of Bn254 elements using Poseidon2 (output = p2_permute(array)). Should only
     /// be used when target is a gnark circuit.
     CircuitPoseidon2Permute([Var<C::N>; 3]),
@@ -277,6 +305,24 @@ pub enum DslIr<C: Config> {
         Array<C, Ext<C::F, C::EF>>,
         Ext<C::F, C::EF>,
     ),
+    /// FriFold(result, eval_0, eval_1, beta, x)
+    FriFold(
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+        Ext<C::F, C::EF>,
+    ),
+
+    // Intrinsics.
+    /// Calls a VM intrinsic, which writes the outputs and reads the inputs (id, outputs, inputs).
+    /// Its instruction is given by the [IntrinsicRegistry](super::IntrinsicRegistry) of the
+    /// compiler. Should only be used when target is the native ISA.
+    Intrinsic(
+        IntrinsicId,
+        Vec<IntrinsicOperand<C>>,
+        Vec<IntrinsicOperand<C>>,
+    ),
 
     // Debugging instructions.
     /// Executes less than (var = var < var).  This operation is NOT constrained.
@@ -294,3 +340,47 @@ impl<C: Config> Default for DslIr<C> {
         Self::Halt
     }
 }
+
+/// Formats a DSL program with one instruction per line and nested blocks indented. Each
+/// instruction is followed by the builder call site which emitted it, if the program was built
+/// with `RUST_BACKTRACE` set.
+pub fn display_ir<C: Config>(operations: &TracedVec<DslIr<C>>) -> String {
+    let mut out = String::new();
+    write_block(&mut out, operations, 0);
+    out
+}
+
+fn write_block<C: Config>(out: &mut String, operations: &TracedVec<DslIr<C>>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for (instr, trace) in operations.vec.iter().zip(&operations.traces) {
+        let (line, blocks) = match instr {
+            DslIr::For(start, end, step, loop_var, body) => (
+                format!("For({start:?}, {end:?}, {step:?}, {loop_var:?})"),
+                vec![body],
+            ),
+            DslIr::Loop(body) => ("Loop".to_string(), vec![body]),
+            DslIr::FnDef(id, body) => (format!("FnDef({id:?})"), vec![body]),
+            DslIr::IfEq(lhs, rhs, then_block, else_block)
+            | DslIr::IfNe(lhs, rhs, then_block, else_block) => (
+                format!("{instr}({lhs:?}, {rhs:?})"),
+                vec![then_block, else_block],
+            ),
+            DslIr::IfEqI(lhs, rhs, then_block, else_block)
+            | DslIr::IfNeI(lhs, rhs, then_block, else_block) => (
+                format!("{instr}({lhs:?}, {rhs:?})"),
+                vec![then_block, else_block],
+            ),
+            _ => (format!("{instr:?}"), vec![]),
+        };
+        match trace.as_ref().and_then(call_site) {
+            Some(call_site) => writeln!(out, "{indent}{line} @ {call_site}").unwrap(),
+            None => writeln!(out, "{indent}{line}").unwrap(),
+        }
+        for (i, block) in blocks.into_iter().enumerate() {
+            if i == 1 && !block.is_empty() {
+                writeln!(out, "{indent}Else").unwrap();
+            }
+            write_block(out, block, depth + 1);
+        }
+    }
+}
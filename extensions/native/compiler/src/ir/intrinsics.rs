@@ -0,0 +1,137 @@
+use alloc::collections::BTreeMap;
+
+use openvm_instructions::VmOpcode;
+use serde::{Deserialize, Serialize};
+
+use super::{Array, Builder, Config, DslIr, Ext, Felt, Ptr, Var};
+
+/// Identifier of a VM intrinsic called with [DslIr::Intrinsic]. The [IntrinsicRegistry] the
+/// program is compiled with maps it to the opcode of the instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct IntrinsicId(pub u32);
+
+/// Operand of an intrinsic, passed to the instruction as the address of its stack slot. A
+/// pointer operand lets the instruction read or write the memory it points to, as for the
+/// arrays of the Poseidon2 instructions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IntrinsicOperand<C: Config> {
+    Var(Var<C::N>),
+    Felt(Felt<C::F>),
+    Ext(Ext<C::F, C::EF>),
+    Ptr(Ptr<C::N>),
+}
+
+impl<C: Config> From<Var<C::N>> for IntrinsicOperand<C> {
+    fn from(var: Var<C::N>) -> Self {
+        Self::Var(var)
+    }
+}
+
+impl<C: Config> From<Felt<C::F>> for IntrinsicOperand<C> {
+    fn from(felt: Felt<C::F>) -> Self {
+        Self::Felt(felt)
+    }
+}
+
+impl<C: Config> From<Ext<C::F, C::EF>> for IntrinsicOperand<C> {
+    fn from(ext: Ext<C::F, C::EF>) -> Self {
+        Self::Ext(ext)
+    }
+}
+
+impl<C: Config> From<Ptr<C::N>> for IntrinsicOperand<C> {
+    fn from(ptr: Ptr<C::N>) -> Self {
+        Self::Ptr(ptr)
+    }
+}
+
+/// Passes the pointer of a dynamic array. Panics for a fixed array, which is not in memory.
+impl<C: Config, T> From<&Array<C, T>> for IntrinsicOperand<C> {
+    fn from(array: &Array<C, T>) -> Self {
+        Self::Ptr(array.ptr())
+    }
+}
+
+/// Instruction of an intrinsic in the native ISA.
+///
+/// The operands, outputs first, are passed in the fields `a`, `b`, `c`, `e`, `f` and `g` of the
+/// instruction in that order, and `d` is the native address space. With at most three operands,
+/// `e` is also the native address space. This is the layout of the Poseidon2 and FRI instructions
+/// of the native extension.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntrinsicDef {
+    pub name: String,
+    pub opcode: VmOpcode,
+    pub num_outputs: usize,
+    pub num_inputs: usize,
+}
+
+impl IntrinsicDef {
+    /// Maximum number of operands of an instruction.
+    pub const MAX_OPERANDS: usize = 6;
+
+    pub fn new(
+        name: impl Into<String>,
+        opcode: VmOpcode,
+        num_outputs: usize,
+        num_inputs: usize,
+    ) -> Self {
+        assert!(
+            num_outputs + num_inputs <= Self::MAX_OPERANDS,
+            "an instruction has at most {} operands",
+            Self::MAX_OPERANDS
+        );
+        Self {
+            name: name.into(),
+            opcode,
+            num_outputs,
+            num_inputs,
+        }
+    }
+}
+
+/// Maps the [IntrinsicId]s of a program to the instructions implementing them, so that the
+/// instructions of a VM extension are available to the DSL without a dedicated [DslIr] variant.
+/// The VM the program runs on must have the extension whose chip executes the opcode.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IntrinsicRegistry {
+    intrinsics: BTreeMap<IntrinsicId, IntrinsicDef>,
+}
+
+impl IntrinsicRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the intrinsic `id`. Panics if it is already registered.
+    pub fn register(&mut self, id: IntrinsicId, def: IntrinsicDef) {
+        if let Some(existing) = self.intrinsics.insert(id, def) {
+            panic!(
+                "intrinsic {id:?} is already registered as {}",
+                existing.name
+            );
+        }
+    }
+
+    pub fn with_intrinsic(mut self, id: IntrinsicId, def: IntrinsicDef) -> Self {
+        self.register(id, def);
+        self
+    }
+
+    pub fn get(&self, id: IntrinsicId) -> Option<&IntrinsicDef> {
+        self.intrinsics.get(&id)
+    }
+}
+
+impl<C: Config> Builder<C> {
+    /// Calls the intrinsic `id`, which writes `outputs` and reads `inputs`. The memory behind
+    /// pointer operands may be read or written by the instruction either way.
+    pub fn intrinsic(
+        &mut self,
+        id: IntrinsicId,
+        outputs: Vec<IntrinsicOperand<C>>,
+        inputs: Vec<IntrinsicOperand<C>>,
+    ) {
+        self.operations.push(DslIr::Intrinsic(id, outputs, inputs));
+    }
+}
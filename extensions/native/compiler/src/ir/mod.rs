@@ -1,8 +1,11 @@
 pub use builder::*;
 pub use collections::*;
+pub use functions::*;
 pub use instructions::*;
+pub use intrinsics::*;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField, TwoAdicField};
-pub use poseidon::{DIGEST_SIZE, PERMUTATION_WIDTH};
+pub use passes::*;
+pub use poseidon::{DIGEST_SIZE, HASH_RATE, PERMUTATION_WIDTH};
 pub use ptr::*;
 pub use ref_ptr::*;
 pub use select::*;
@@ -15,7 +18,10 @@ mod bits;
 mod builder;
 mod collections;
 mod fri;
+mod functions;
 mod instructions;
+mod intrinsics;
+mod passes;
 mod poseidon;
 mod ptr;
 mod ref_ptr;
@@ -0,0 +1,1349 @@
+//! Optimization passes over [DslIr].
+//!
+//! The DSL is not in SSA form: `builder.assign` and loop variables redefine a variable in place.
+//! All passes are therefore conservative. Facts about variables never flow into or out of a
+//! nested block (loop or branch body), and a variable is only replaced by another one if both of
+//! them are defined exactly once in the whole program.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    mem::{discriminant, take, Discriminant},
+};
+
+use openvm_stark_backend::p3_field::{AbstractExtensionField, Field};
+
+use super::{
+    Array, Config, DslIr, Ext, Felt, IntrinsicOperand, MemIndex, Ptr, RVar, TracedVec, Usize, Var,
+};
+
+/// A transformation of a DSL program which preserves its semantics.
+pub trait IrPass<C: Config> {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>);
+}
+
+/// Runs constant folding, common subexpression elimination and dead code elimination, in this
+/// order.
+pub fn optimize<C: Config>(operations: &mut TracedVec<DslIr<C>>) {
+    ConstantFolding.run(operations);
+    CommonSubexpressionElimination.run(operations);
+    DeadCodeElimination.run(operations);
+}
+
+/// Replaces arithmetic whose operands are known at compile time by immediates, and arithmetic
+/// with one known operand by its immediate form.
+///
+/// Divisions by a known zero are kept so that they still fail at runtime.
+pub struct ConstantFolding;
+
+/// Removes arithmetic which recomputes a value that is already held by another variable, and
+/// replaces all uses of its destination by that variable.
+pub struct CommonSubexpressionElimination;
+
+/// Removes arithmetic and immediates whose destination is never read.
+///
+/// Divisions are kept, since they fail at runtime on a zero divisor.
+pub struct DeadCodeElimination;
+
+/// Lets variables whose live ranges do not overlap share a stack slot, which shrinks the stack
+/// frame and the number of memory addresses the program touches.
+///
+/// Only the ASM backend supports this pass: `Array::Fixed` operands are shared between
+/// instructions and would be renamed more than once.
+pub struct StackSlotReuse;
+
+/// Replaces `For` loops with constant bounds and at most `max_iterations` iterations by copies
+/// of their body, each preceded by an immediate assignment of the loop variable.
+///
+/// Loops which may break are kept. Bounds held by variables are only constant after
+/// [ConstantFolding].
+pub struct LoopUnrolling {
+    pub max_iterations: usize,
+}
+
+/// Replaces multiplications of extension elements by small constants with additions.
+///
+/// A multiplication by an extension immediate compiles to `D` immediates and an extension
+/// multiplication, and one by a base field immediate to `D` base field multiplications, while an
+/// extension addition is a single instruction. Extension immediates in the base field are
+/// therefore multiplied as base field immediates, which are in turn replaced by the additions of
+/// a double-and-add chain if it is shorter. Base field multiplications are a single instruction
+/// and are kept.
+pub struct StrengthReduction;
+
+/// Fuses a multiplication followed by an addition of its result into one multiply-add, if the
+/// product is not read anywhere else.
+pub struct FuseMulAdd;
+
+impl<C: Config> IrPass<C> for ConstantFolding {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        Constants::default().fold_block(operations);
+    }
+}
+
+impl<C: Config> IrPass<C> for CommonSubexpressionElimination {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut def_counts = HashMap::new();
+        count_accesses(operations, Access::is_def, &mut def_counts);
+        let mut cse = Cse {
+            def_counts,
+            ..Default::default()
+        };
+        cse.eliminate_block(operations);
+        if !cse.renames.is_empty() {
+            rename_block(operations, &cse.renames);
+        }
+    }
+}
+
+impl<C: Config> IrPass<C> for DeadCodeElimination {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        // Removing an instruction may make the definitions of its operands dead.
+        loop {
+            let mut use_counts = HashMap::new();
+            count_accesses(operations, Access::is_use, &mut use_counts);
+            if remove_dead(operations, &use_counts) == 0 {
+                break;
+            }
+        }
+    }
+}
+
+impl<C: Config> IrPass<C> for StackSlotReuse {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut liveness = Liveness::default();
+        liveness.visit_block(operations, None);
+        let renames = liveness.allocate_slots();
+        rename_block(operations, &renames);
+    }
+}
+
+impl<C: Config> IrPass<C> for LoopUnrolling {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut unrolled = TracedVec::new();
+        for (mut instr, trace) in take(operations) {
+            for block in nested_blocks(&mut instr) {
+                self.run(block);
+            }
+            let DslIr::For(RVar::Const(start), RVar::Const(end), step, loop_var, body) = &instr
+            else {
+                unrolled.extend([(instr, trace)]);
+                continue;
+            };
+            let Some(num_iterations) =
+                trip_count(*start, *end, *step, self.max_iterations).filter(|_| !may_break(body))
+            else {
+                unrolled.extend([(instr, trace)]);
+                continue;
+            };
+            let mut value = *start;
+            for _ in 0..num_iterations {
+                unrolled.extend([(DslIr::ImmV(*loop_var, value), trace.clone())]);
+                unrolled.extend(body.clone());
+                value += *step;
+            }
+            // The loop variable holds the end of the range after the loop.
+            unrolled.extend([(DslIr::ImmV(*loop_var, *end), trace)]);
+        }
+        *operations = unrolled;
+    }
+}
+
+impl<C: Config> IrPass<C> for StrengthReduction {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut reduced = TracedVec::new();
+        for (mut instr, trace) in take(operations) {
+            for block in nested_blocks(&mut instr) {
+                self.run(block);
+            }
+            let instr = match instr {
+                DslIr::MulEI(dst, a, y) if y.as_base_slice()[1..].iter().all(Field::is_zero) => {
+                    DslIr::MulEFI(dst, a, y.as_base_slice()[0])
+                }
+                instr => instr,
+            };
+            match &instr {
+                DslIr::MulEFI(dst, a, y) => match addition_chain(*dst, *a, *y) {
+                    Some(chain) => reduced.extend(chain.into_iter().map(|i| (i, trace.clone()))),
+                    None => reduced.extend([(instr, trace)]),
+                },
+                _ => reduced.extend([(instr, trace)]),
+            }
+        }
+        *operations = reduced;
+    }
+}
+
+impl<C: Config> IrPass<C> for FuseMulAdd {
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut use_counts = HashMap::new();
+        count_accesses(operations, Access::is_use, &mut use_counts);
+        fuse_mul_add(operations, &use_counts);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Access {
+    Use,
+    Def,
+    /// Read and then overwritten by the same instruction.
+    UseDef,
+}
+
+impl Access {
+    fn is_use(self) -> bool {
+        self != Access::Def
+    }
+
+    fn is_def(self) -> bool {
+        self != Access::Use
+    }
+}
+
+/// A variable backed by a fixed stack location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Slot {
+    Var(u32),
+    Felt(u32),
+    Ext(u32),
+}
+
+type SlotVisitor<'a> = dyn FnMut(Access, &mut Slot) + 'a;
+
+trait HasSlots {
+    /// Calls `f` on every variable of `self`. Changes made by `f` are written back.
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>);
+}
+
+impl<N> HasSlots for Var<N> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        let mut slot = Slot::Var(self.0);
+        f(access, &mut slot);
+        if let Slot::Var(id) = slot {
+            self.0 = id;
+        }
+    }
+}
+
+impl<F> HasSlots for Felt<F> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        let mut slot = Slot::Felt(self.0);
+        f(access, &mut slot);
+        if let Slot::Felt(id) = slot {
+            self.0 = id;
+        }
+    }
+}
+
+impl<F, EF> HasSlots for Ext<F, EF> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        let mut slot = Slot::Ext(self.0);
+        f(access, &mut slot);
+        if let Slot::Ext(id) = slot {
+            self.0 = id;
+        }
+    }
+}
+
+impl<N> HasSlots for RVar<N> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        if let RVar::Val(var) = self {
+            var.visit_slots(access, f);
+        }
+    }
+}
+
+impl<N> HasSlots for Usize<N> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        if let Usize::Var(var) = self {
+            var.visit_slots(access, f);
+        }
+    }
+}
+
+impl<N> HasSlots for Ptr<N> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        self.address.visit_slots(access, f);
+    }
+}
+
+impl<N> HasSlots for MemIndex<N> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        self.index.visit_slots(access, f);
+    }
+}
+
+impl<T: HasSlots, const N: usize> HasSlots for [T; N] {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        for x in self {
+            x.visit_slots(access, f);
+        }
+    }
+}
+
+impl<T: HasSlots> HasSlots for Vec<T> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        for x in self {
+            x.visit_slots(access, f);
+        }
+    }
+}
+
+impl<C: Config> HasSlots for IntrinsicOperand<C> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        match self {
+            IntrinsicOperand::Var(var) => var.visit_slots(access, f),
+            IntrinsicOperand::Felt(felt) => felt.visit_slots(access, f),
+            IntrinsicOperand::Ext(ext) => ext.visit_slots(access, f),
+            // Only the memory behind the pointer is written.
+            IntrinsicOperand::Ptr(ptr) => ptr.visit_slots(Access::Use, f),
+        }
+    }
+}
+
+impl<C: Config, T: HasSlots> HasSlots for Array<C, T> {
+    fn visit_slots(&mut self, access: Access, f: &mut SlotVisitor<'_>) {
+        match self {
+            Array::Fixed(vec) => {
+                for x in vec.borrow_mut().iter_mut().flatten() {
+                    x.visit_slots(access, f);
+                }
+            }
+            // Only the heap memory behind the pointer is written.
+            Array::Dyn(ptr, len) => {
+                ptr.visit_slots(Access::Use, f);
+                len.visit_slots(Access::Use, f);
+            }
+        }
+    }
+}
+
+/// Visits the variables read and written by `instr`, excluding the ones of nested blocks.
+fn visit_operands<C: Config>(instr: &mut DslIr<C>, f: &mut SlotVisitor<'_>) {
+    use Access::{Def, Use, UseDef};
+    match instr {
+        DslIr::ImmV(dst, _) => dst.visit_slots(Def, f),
+        DslIr::ImmF(dst, _) => dst.visit_slots(Def, f),
+        DslIr::ImmE(dst, _) => dst.visit_slots(Def, f),
+        DslIr::AddV(dst, lhs, rhs)
+        | DslIr::SubV(dst, lhs, rhs)
+        | DslIr::MulV(dst, lhs, rhs)
+        | DslIr::LessThanV(dst, lhs, rhs)
+        | DslIr::LessThan(dst, lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddVI(dst, lhs, _)
+        | DslIr::SubVI(dst, lhs, _)
+        | DslIr::MulVI(dst, lhs, _)
+        | DslIr::LessThanVI(dst, lhs, _)
+        | DslIr::SubVIN(dst, _, lhs)
+        | DslIr::NegV(dst, lhs) => {
+            lhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddF(dst, lhs, rhs)
+        | DslIr::SubF(dst, lhs, rhs)
+        | DslIr::MulF(dst, lhs, rhs)
+        | DslIr::DivF(dst, lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddFI(dst, lhs, _)
+        | DslIr::SubFI(dst, lhs, _)
+        | DslIr::MulFI(dst, lhs, _)
+        | DslIr::DivFI(dst, lhs, _)
+        | DslIr::SubFIN(dst, _, lhs)
+        | DslIr::DivFIN(dst, _, lhs)
+        | DslIr::NegF(dst, lhs) => {
+            lhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddE(dst, lhs, rhs)
+        | DslIr::SubE(dst, lhs, rhs)
+        | DslIr::MulE(dst, lhs, rhs)
+        | DslIr::DivE(dst, lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddEI(dst, lhs, _)
+        | DslIr::SubEI(dst, lhs, _)
+        | DslIr::MulEI(dst, lhs, _)
+        | DslIr::DivEI(dst, lhs, _)
+        | DslIr::SubEIN(dst, _, lhs)
+        | DslIr::DivEIN(dst, _, lhs)
+        | DslIr::AddEFI(dst, lhs, _)
+        | DslIr::SubEFI(dst, lhs, _)
+        | DslIr::MulEFI(dst, lhs, _)
+        | DslIr::DivEFI(dst, lhs, _)
+        | DslIr::NegE(dst, lhs) => {
+            lhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddEF(dst, lhs, rhs)
+        | DslIr::SubEF(dst, lhs, rhs)
+        | DslIr::MulEF(dst, lhs, rhs)
+        | DslIr::DivEF(dst, lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::AddEFFI(dst, lhs, _) => {
+            lhs.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::MulAddF(dst, lhs, rhs, addend) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            addend.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::MulAddE(dst, lhs, rhs, addend) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+            addend.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::CastFV(dst, src) => {
+            src.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::UnsafeCastVF(dst, src) => {
+            src.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::For(start, end, _, loop_var, _) => {
+            start.visit_slots(Use, f);
+            end.visit_slots(Use, f);
+            loop_var.visit_slots(Def, f);
+        }
+        DslIr::IfEq(lhs, rhs, _, _) | DslIr::IfNe(lhs, rhs, _, _) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+        }
+        DslIr::IfEqI(lhs, _, _, _) | DslIr::IfNeI(lhs, _, _, _) => lhs.visit_slots(Use, f),
+        DslIr::AssertEqV(lhs, rhs) | DslIr::AssertNeV(lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+        }
+        DslIr::AssertEqF(lhs, rhs) | DslIr::AssertNeF(lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+        }
+        DslIr::AssertEqE(lhs, rhs) | DslIr::AssertNeE(lhs, rhs) => {
+            lhs.visit_slots(Use, f);
+            rhs.visit_slots(Use, f);
+        }
+        DslIr::AssertEqVI(lhs, _) | DslIr::AssertNeVI(lhs, _) => lhs.visit_slots(Use, f),
+        DslIr::AssertEqFI(lhs, _) | DslIr::AssertNeFI(lhs, _) => lhs.visit_slots(Use, f),
+        DslIr::AssertEqEI(lhs, _) | DslIr::AssertNeEI(lhs, _) => lhs.visit_slots(Use, f),
+        DslIr::Alloc(ptr, len, _) => {
+            len.visit_slots(Use, f);
+            ptr.visit_slots(Def, f);
+        }
+        DslIr::LoadV(dst, ptr, index) => {
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::LoadF(dst, ptr, index) => {
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::LoadE(dst, ptr, index) => {
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+            dst.visit_slots(Def, f);
+        }
+        DslIr::LoadHeapPtr(ptr) => ptr.visit_slots(Def, f),
+        DslIr::StoreV(src, ptr, index) => {
+            src.visit_slots(Use, f);
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+        }
+        DslIr::StoreF(src, ptr, index) => {
+            src.visit_slots(Use, f);
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+        }
+        DslIr::StoreE(src, ptr, index) => {
+            src.visit_slots(Use, f);
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+        }
+        DslIr::StoreHeapPtr(ptr) => ptr.visit_slots(Use, f),
+        DslIr::CircuitNum2BitsV(src, _, bits) => {
+            src.visit_slots(Use, f);
+            bits.visit_slots(Def, f);
+        }
+        DslIr::CircuitNum2BitsF(src, bits) => {
+            src.visit_slots(Use, f);
+            bits.visit_slots(Def, f);
+        }
+        DslIr::Poseidon2PermuteBabyBear(output, input) => {
+            input.visit_slots(Use, f);
+            output.visit_slots(Def, f);
+        }
+        DslIr::Poseidon2CompressBabyBear(output, left, right) => {
+            left.visit_slots(Use, f);
+            right.visit_slots(Use, f);
+            output.visit_slots(Def, f);
+        }
+        DslIr::Poseidon2AbsorbBabyBear(state, chunk) => {
+            chunk.visit_slots(Use, f);
+            state.visit_slots(UseDef, f);
+        }
+        DslIr::Poseidon2MerkleStepBabyBear(node, sibling, bit) => {
+            sibling.visit_slots(Use, f);
+            bit.visit_slots(Use, f);
+            node.visit_slots(UseDef, f);
+        }
+        DslIr::CircuitPoseidon2Permute(state) => state.visit_slots(UseDef, f),
+        DslIr::PrintV(src) => src.visit_slots(Use, f),
+        DslIr::PrintF(src) => src.visit_slots(Use, f),
+        DslIr::PrintE(src) => src.visit_slots(Use, f),
+        DslIr::HintBitsU(src) => src.visit_slots(Use, f),
+        DslIr::HintBitsV(src, _) => src.visit_slots(Use, f),
+        DslIr::HintBitsF(src, _) => src.visit_slots(Use, f),
+        DslIr::StoreHintWord(ptr, index) => {
+            ptr.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+        }
+        DslIr::WitnessVar(dst, _) => dst.visit_slots(Def, f),
+        DslIr::WitnessFelt(dst, _) => dst.visit_slots(Def, f),
+        DslIr::WitnessExt(dst, _) => dst.visit_slots(Def, f),
+        DslIr::Publish(value, index) => {
+            value.visit_slots(Use, f);
+            index.visit_slots(Use, f);
+        }
+        DslIr::CircuitCommitVkeyHash(src)
+        | DslIr::CircuitCommitCommitedValuesDigest(src)
+        | DslIr::CircuitPublish(src, _) => src.visit_slots(Use, f),
+        DslIr::CircuitSelectV(cond, a, b, out) => {
+            cond.visit_slots(Use, f);
+            a.visit_slots(Use, f);
+            b.visit_slots(Use, f);
+            out.visit_slots(Def, f);
+        }
+        DslIr::CircuitSelectF(cond, a, b, out) => {
+            cond.visit_slots(Use, f);
+            a.visit_slots(Use, f);
+            b.visit_slots(Use, f);
+            out.visit_slots(Def, f);
+        }
+        DslIr::CircuitSelectE(cond, a, b, out) => {
+            cond.visit_slots(Use, f);
+            a.visit_slots(Use, f);
+            b.visit_slots(Use, f);
+            out.visit_slots(Def, f);
+        }
+        DslIr::CircuitExt2Felt(felts, ext) => {
+            ext.visit_slots(Use, f);
+            felts.visit_slots(Def, f);
+        }
+        DslIr::CircuitFelts2Ext(felts, ext) => {
+            felts.visit_slots(Use, f);
+            ext.visit_slots(Def, f);
+        }
+        DslIr::FriReducedOpening(alpha, curr_alpha_pow, at_x_array, at_z_array, result) => {
+            alpha.visit_slots(Use, f);
+            // The chip writes the next power of alpha back.
+            curr_alpha_pow.visit_slots(UseDef, f);
+            at_x_array.visit_slots(Use, f);
+            at_z_array.visit_slots(Use, f);
+            result.visit_slots(Def, f);
+        }
+        DslIr::FriFold(result, eval_0, eval_1, beta, x) => {
+            eval_0.visit_slots(Use, f);
+            eval_1.visit_slots(Use, f);
+            beta.visit_slots(Use, f);
+            x.visit_slots(Use, f);
+            result.visit_slots(Def, f);
+        }
+        DslIr::Intrinsic(_, outputs, inputs) => {
+            inputs.visit_slots(Use, f);
+            outputs.visit_slots(Def, f);
+        }
+        DslIr::Call(_, return_site) => return_site.visit_slots(Def, f),
+        DslIr::Ret(_, return_site) => return_site.visit_slots(Use, f),
+        DslIr::Loop(_)
+        | DslIr::FnDef(..)
+        | DslIr::Break
+        | DslIr::Error()
+        | DslIr::HintInputVec()
+        | DslIr::Halt
+        | DslIr::CycleTrackerStart(_)
+        | DslIr::CycleTrackerEnd(_) => {}
+    }
+}
+
+/// Returns the nested blocks of a control flow instruction.
+fn nested_blocks<C: Config>(instr: &mut DslIr<C>) -> Vec<&mut TracedVec<DslIr<C>>> {
+    match instr {
+        DslIr::For(_, _, _, _, body) | DslIr::Loop(body) | DslIr::FnDef(_, body) => vec![body],
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => vec![then_block, else_block],
+        _ => vec![],
+    }
+}
+
+/// The extension additions computing `dst = a * y`, if they are fewer than the `D` base field
+/// multiplications of the product.
+fn addition_chain<C: Config>(
+    dst: Ext<C::F, C::EF>,
+    a: Ext<C::F, C::EF>,
+    y: C::F,
+) -> Option<Vec<DslIr<C>>> {
+    let d = <C::EF as AbstractExtensionField<C::F>>::D;
+    // Every factor from `2^D` on needs at least `D` doublings.
+    let k = (2..1usize << d).find(|&k| C::F::from_canonical_usize(k) == y)?;
+    // Double for every bit below the leading one, and add `a` for every set bit.
+    let mut chain = Vec::new();
+    for i in (0..k.ilog2()).rev() {
+        let acc = if chain.is_empty() { a } else { dst };
+        chain.push(DslIr::AddE(dst, acc, acc));
+        if (k >> i) & 1 == 1 {
+            // The first doubling has overwritten `a` if it is `dst`.
+            if dst == a {
+                return None;
+            }
+            chain.push(DslIr::AddE(dst, dst, a));
+        }
+    }
+    (chain.len() < d).then_some(chain)
+}
+
+fn is_control_flow<C: Config>(instr: &DslIr<C>) -> bool {
+    matches!(
+        instr,
+        DslIr::For(..)
+            | DslIr::Loop(_)
+            | DslIr::IfEq(..)
+            | DslIr::IfNe(..)
+            | DslIr::IfEqI(..)
+            | DslIr::IfNeI(..)
+            | DslIr::Break
+            | DslIr::FnDef(..)
+            | DslIr::Call(..)
+            | DslIr::Ret(..)
+    )
+}
+
+/// Returns whether `operations` contain a `Break` of the enclosing loop.
+fn may_break<C: Config>(operations: &TracedVec<DslIr<C>>) -> bool {
+    operations.vec.iter().any(|instr| match instr {
+        DslIr::Break => true,
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => {
+            may_break(then_block) || may_break(else_block)
+        }
+        _ => false,
+    })
+}
+
+/// Returns the number of iterations of a loop from `start` to `end`, if it is at most `max`.
+fn trip_count<N: Field>(start: N, end: N, step: N, max: usize) -> Option<usize> {
+    let mut value = start;
+    for num_iterations in 0..=max {
+        if value == end {
+            return Some(num_iterations);
+        }
+        value += step;
+    }
+    None
+}
+
+fn count_accesses<C: Config>(
+    operations: &mut TracedVec<DslIr<C>>,
+    filter: fn(Access) -> bool,
+    counts: &mut HashMap<Slot, usize>,
+) {
+    for instr in operations.vec.iter_mut() {
+        visit_operands(instr, &mut |access, slot| {
+            if filter(access) {
+                *counts.entry(*slot).or_default() += 1;
+            }
+        });
+        for block in nested_blocks(instr) {
+            count_accesses(block, filter, counts);
+        }
+    }
+}
+
+/// Returns the number of var, felt and ext ids used by `operations`, i.e. one more than the
+/// largest id of each kind.
+pub(crate) fn slot_counts<C: Config>(operations: &mut TracedVec<DslIr<C>>) -> (u32, u32, u32) {
+    let mut accesses = HashMap::new();
+    count_accesses(operations, |_| true, &mut accesses);
+    let mut counts = (0, 0, 0);
+    for slot in accesses.into_keys() {
+        let count = match slot {
+            Slot::Var(_) => &mut counts.0,
+            Slot::Felt(_) => &mut counts.1,
+            Slot::Ext(_) => &mut counts.2,
+        };
+        *count = (*count).max(slot.id() + 1);
+    }
+    counts
+}
+
+fn rename_block<C: Config>(operations: &mut TracedVec<DslIr<C>>, renames: &HashMap<Slot, Slot>) {
+    for instr in operations.vec.iter_mut() {
+        rename_operands(instr, renames);
+        for block in nested_blocks(instr) {
+            rename_block(block, renames);
+        }
+    }
+}
+
+fn rename_operands<C: Config>(instr: &mut DslIr<C>, renames: &HashMap<Slot, Slot>) {
+    visit_operands(instr, &mut |_, slot| {
+        if let Some(new_slot) = renames.get(slot) {
+            *slot = *new_slot;
+        }
+    });
+}
+
+/// Immediate operand of an arithmetic instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Imm<N, F, EF> {
+    None,
+    Var(N),
+    Felt(F),
+    Ext(EF),
+}
+
+/// A pure computation: the instruction kind, its variable operands and its immediate operand.
+type Expr<C> = (
+    Discriminant<DslIr<C>>,
+    Vec<Slot>,
+    Imm<<C as Config>::N, <C as Config>::F, <C as Config>::EF>,
+);
+
+/// Returns the destination and the computed expression of immediates and arithmetic
+/// instructions, and `None` for all other instructions.
+fn pure_expr<C: Config>(instr: &DslIr<C>) -> Option<(Slot, Expr<C>)> {
+    let op = discriminant(instr);
+    let v = |x: &Var<C::N>| Slot::Var(x.0);
+    let f = |x: &Felt<C::F>| Slot::Felt(x.0);
+    let e = |x: &Ext<C::F, C::EF>| Slot::Ext(x.0);
+    // Sorts the operands of commutative instructions.
+    let sorted = |a: Slot, b: Slot| if a <= b { vec![a, b] } else { vec![b, a] };
+    let (dst, args, imm) = match instr {
+        DslIr::ImmV(dst, x) => (v(dst), vec![], Imm::Var(*x)),
+        DslIr::ImmF(dst, x) => (f(dst), vec![], Imm::Felt(*x)),
+        DslIr::ImmE(dst, x) => (e(dst), vec![], Imm::Ext(*x)),
+        DslIr::AddV(dst, a, b) | DslIr::MulV(dst, a, b) => (v(dst), sorted(v(a), v(b)), Imm::None),
+        DslIr::SubV(dst, a, b) => (v(dst), vec![v(a), v(b)], Imm::None),
+        DslIr::AddVI(dst, a, x)
+        | DslIr::SubVI(dst, a, x)
+        | DslIr::SubVIN(dst, x, a)
+        | DslIr::MulVI(dst, a, x) => (v(dst), vec![v(a)], Imm::Var(*x)),
+        DslIr::NegV(dst, a) => (v(dst), vec![v(a)], Imm::None),
+        DslIr::AddF(dst, a, b) | DslIr::MulF(dst, a, b) => (f(dst), sorted(f(a), f(b)), Imm::None),
+        DslIr::SubF(dst, a, b) | DslIr::DivF(dst, a, b) => (f(dst), vec![f(a), f(b)], Imm::None),
+        DslIr::AddFI(dst, a, x)
+        | DslIr::SubFI(dst, a, x)
+        | DslIr::SubFIN(dst, x, a)
+        | DslIr::MulFI(dst, a, x)
+        | DslIr::DivFI(dst, a, x)
+        | DslIr::DivFIN(dst, x, a) => (f(dst), vec![f(a)], Imm::Felt(*x)),
+        DslIr::NegF(dst, a) => (f(dst), vec![f(a)], Imm::None),
+        DslIr::AddE(dst, a, b) | DslIr::MulE(dst, a, b) => (e(dst), sorted(e(a), e(b)), Imm::None),
+        DslIr::SubE(dst, a, b) | DslIr::DivE(dst, a, b) => (e(dst), vec![e(a), e(b)], Imm::None),
+        DslIr::AddEI(dst, a, x)
+        | DslIr::SubEI(dst, a, x)
+        | DslIr::SubEIN(dst, x, a)
+        | DslIr::MulEI(dst, a, x)
+        | DslIr::DivEI(dst, a, x)
+        | DslIr::DivEIN(dst, x, a) => (e(dst), vec![e(a)], Imm::Ext(*x)),
+        DslIr::AddEFI(dst, a, x)
+        | DslIr::SubEFI(dst, a, x)
+        | DslIr::MulEFI(dst, a, x)
+        | DslIr::DivEFI(dst, a, x) => (e(dst), vec![e(a)], Imm::Felt(*x)),
+        DslIr::AddEF(dst, a, b)
+        | DslIr::SubEF(dst, a, b)
+        | DslIr::MulEF(dst, a, b)
+        | DslIr::DivEF(dst, a, b) => (e(dst), vec![e(a), f(b)], Imm::None),
+        DslIr::AddEFFI(dst, a, x) => (e(dst), vec![f(a)], Imm::Ext(*x)),
+        DslIr::NegE(dst, a) => (e(dst), vec![e(a)], Imm::None),
+        DslIr::MulAddF(dst, a, b, c) => {
+            (f(dst), [sorted(f(a), f(b)), vec![f(c)]].concat(), Imm::None)
+        }
+        DslIr::MulAddE(dst, a, b, c) => {
+            (e(dst), [sorted(e(a), e(b)), vec![e(c)]].concat(), Imm::None)
+        }
+        _ => return None,
+    };
+    Some((dst, (op, args, imm)))
+}
+
+fn is_division<C: Config>(instr: &DslIr<C>) -> bool {
+    matches!(
+        instr,
+        DslIr::DivF(..)
+            | DslIr::DivFI(..)
+            | DslIr::DivFIN(..)
+            | DslIr::DivE(..)
+            | DslIr::DivEI(..)
+            | DslIr::DivEIN(..)
+            | DslIr::DivEFI(..)
+            | DslIr::DivEF(..)
+    )
+}
+
+/// Returns the number of removed instructions.
+fn remove_dead<C: Config>(
+    operations: &mut TracedVec<DslIr<C>>,
+    use_counts: &HashMap<Slot, usize>,
+) -> usize {
+    let mut num_removed = 0;
+    let mut live = TracedVec::new();
+    for (mut instr, trace) in take(operations) {
+        if !is_division(&instr) {
+            if let Some((dst, _)) = pure_expr(&instr) {
+                if !use_counts.contains_key(&dst) {
+                    num_removed += 1;
+                    continue;
+                }
+            }
+        }
+        for block in nested_blocks(&mut instr) {
+            num_removed += remove_dead(block, use_counts);
+        }
+        live.extend([(instr, trace)]);
+    }
+    *operations = live;
+    num_removed
+}
+
+fn fuse_mul_add<C: Config>(
+    operations: &mut TracedVec<DslIr<C>>,
+    use_counts: &HashMap<Slot, usize>,
+) {
+    // Returns the other operand of an addition which reads the product `prod` exactly once.
+    fn addend<T: Copy>(prod: u32, lhs: T, rhs: T, id: fn(T) -> u32) -> Option<T> {
+        match (id(lhs) == prod, id(rhs) == prod) {
+            (true, false) => Some(rhs),
+            (false, true) => Some(lhs),
+            _ => None,
+        }
+    }
+    let single_use = |slot: Slot| use_counts.get(&slot) == Some(&1);
+
+    let mut fused = TracedVec::new();
+    for (mut instr, trace) in take(operations) {
+        for block in nested_blocks(&mut instr) {
+            fuse_mul_add(block, use_counts);
+        }
+        let mul_add = match (fused.vec.last(), &instr) {
+            (Some(DslIr::MulF(prod, a, b)), DslIr::AddF(dst, lhs, rhs))
+                if single_use(Slot::Felt(prod.0)) =>
+            {
+                addend(prod.0, *lhs, *rhs, |x| x.0).map(|c| DslIr::MulAddF(*dst, *a, *b, c))
+            }
+            (Some(DslIr::MulE(prod, a, b)), DslIr::AddE(dst, lhs, rhs))
+                if single_use(Slot::Ext(prod.0)) =>
+            {
+                addend(prod.0, *lhs, *rhs, |x| x.0).map(|c| DslIr::MulAddE(*dst, *a, *b, c))
+            }
+            _ => None,
+        };
+        if let Some(mul_add) = mul_add {
+            fused.vec.pop();
+            fused.traces.pop();
+            fused.extend([(mul_add, trace)]);
+        } else {
+            fused.extend([(instr, trace)]);
+        }
+    }
+    *operations = fused;
+}
+
+/// Values of variables known at the current position of a block.
+struct Constants<C: Config> {
+    vars: HashMap<u32, C::N>,
+    felts: HashMap<u32, C::F>,
+    exts: HashMap<u32, C::EF>,
+}
+
+impl<C: Config> Default for Constants<C> {
+    fn default() -> Self {
+        Self {
+            vars: HashMap::new(),
+            felts: HashMap::new(),
+            exts: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Config> Constants<C> {
+    fn clear(&mut self) {
+        self.vars.clear();
+        self.felts.clear();
+        self.exts.clear();
+    }
+
+    fn fold_block(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        self.clear();
+        let mut folded = TracedVec::new();
+        for (instr, trace) in take(operations) {
+            let mut instr = self.fold(instr);
+            if is_control_flow(&instr) {
+                for block in nested_blocks(&mut instr) {
+                    self.fold_block(block);
+                }
+                self.clear();
+            }
+            match &mut instr {
+                DslIr::ImmV(dst, x) => {
+                    self.vars.insert(dst.0, *x);
+                }
+                DslIr::ImmF(dst, x) => {
+                    self.felts.insert(dst.0, *x);
+                }
+                DslIr::ImmE(dst, x) => {
+                    self.exts.insert(dst.0, *x);
+                }
+                instr => visit_operands(instr, &mut |access, slot| {
+                    if access.is_def() {
+                        match *slot {
+                            Slot::Var(id) => {
+                                self.vars.remove(&id);
+                            }
+                            Slot::Felt(id) => {
+                                self.felts.remove(&id);
+                            }
+                            Slot::Ext(id) => {
+                                self.exts.remove(&id);
+                            }
+                        }
+                    }
+                }),
+            }
+            folded.extend([(instr, trace)]);
+        }
+        *operations = folded;
+    }
+
+    fn fold(&self, instr: DslIr<C>) -> DslIr<C> {
+        let v = |x: &Var<C::N>| self.vars.get(&x.0).copied();
+        let f = |x: &Felt<C::F>| self.felts.get(&x.0).copied();
+        let e = |x: &Ext<C::F, C::EF>| self.exts.get(&x.0).copied();
+        let base = |x: C::F| C::EF::from_base(x);
+        match instr {
+            DslIr::AddV(dst, a, b) => match (v(&a), v(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmV(dst, x + y),
+                (None, Some(y)) => DslIr::AddVI(dst, a, y),
+                (Some(x), None) => DslIr::AddVI(dst, b, x),
+                (None, None) => DslIr::AddV(dst, a, b),
+            },
+            DslIr::AddVI(dst, a, y) if v(&a).is_some() => DslIr::ImmV(dst, v(&a).unwrap() + y),
+            DslIr::SubV(dst, a, b) => match (v(&a), v(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmV(dst, x - y),
+                (None, Some(y)) => DslIr::SubVI(dst, a, y),
+                (Some(x), None) => DslIr::SubVIN(dst, x, b),
+                (None, None) => DslIr::SubV(dst, a, b),
+            },
+            DslIr::SubVI(dst, a, y) if v(&a).is_some() => DslIr::ImmV(dst, v(&a).unwrap() - y),
+            DslIr::SubVIN(dst, x, b) if v(&b).is_some() => DslIr::ImmV(dst, x - v(&b).unwrap()),
+            DslIr::MulV(dst, a, b) => match (v(&a), v(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmV(dst, x * y),
+                (None, Some(y)) => DslIr::MulVI(dst, a, y),
+                (Some(x), None) => DslIr::MulVI(dst, b, x),
+                (None, None) => DslIr::MulV(dst, a, b),
+            },
+            DslIr::MulVI(dst, a, y) if v(&a).is_some() => DslIr::ImmV(dst, v(&a).unwrap() * y),
+            DslIr::NegV(dst, a) if v(&a).is_some() => DslIr::ImmV(dst, -v(&a).unwrap()),
+
+            DslIr::AddF(dst, a, b) => match (f(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmF(dst, x + y),
+                (None, Some(y)) => DslIr::AddFI(dst, a, y),
+                (Some(x), None) => DslIr::AddFI(dst, b, x),
+                (None, None) => DslIr::AddF(dst, a, b),
+            },
+            DslIr::AddFI(dst, a, y) if f(&a).is_some() => DslIr::ImmF(dst, f(&a).unwrap() + y),
+            DslIr::SubF(dst, a, b) => match (f(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmF(dst, x - y),
+                (None, Some(y)) => DslIr::SubFI(dst, a, y),
+                (Some(x), None) => DslIr::SubFIN(dst, x, b),
+                (None, None) => DslIr::SubF(dst, a, b),
+            },
+            DslIr::SubFI(dst, a, y) if f(&a).is_some() => DslIr::ImmF(dst, f(&a).unwrap() - y),
+            DslIr::SubFIN(dst, x, b) if f(&b).is_some() => DslIr::ImmF(dst, x - f(&b).unwrap()),
+            DslIr::MulF(dst, a, b) => match (f(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmF(dst, x * y),
+                (None, Some(y)) => DslIr::MulFI(dst, a, y),
+                (Some(x), None) => DslIr::MulFI(dst, b, x),
+                (None, None) => DslIr::MulF(dst, a, b),
+            },
+            DslIr::MulFI(dst, a, y) if f(&a).is_some() => DslIr::ImmF(dst, f(&a).unwrap() * y),
+            DslIr::DivF(dst, a, b) => match (f(&a), f(&b)) {
+                (Some(x), Some(y)) if !y.is_zero() => DslIr::ImmF(dst, x / y),
+                (None, Some(y)) if !y.is_zero() => DslIr::DivFI(dst, a, y),
+                (Some(x), None) => DslIr::DivFIN(dst, x, b),
+                _ => DslIr::DivF(dst, a, b),
+            },
+            DslIr::DivFI(dst, a, y) if f(&a).is_some() && !y.is_zero() => {
+                DslIr::ImmF(dst, f(&a).unwrap() / y)
+            }
+            DslIr::DivFIN(dst, x, b) if f(&b).is_some_and(|y| !y.is_zero()) => {
+                DslIr::ImmF(dst, x / f(&b).unwrap())
+            }
+            DslIr::NegF(dst, a) if f(&a).is_some() => DslIr::ImmF(dst, -f(&a).unwrap()),
+
+            DslIr::AddE(dst, a, b) => match (e(&a), e(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x + y),
+                (None, Some(y)) => DslIr::AddEI(dst, a, y),
+                (Some(x), None) => DslIr::AddEI(dst, b, x),
+                (None, None) => DslIr::AddE(dst, a, b),
+            },
+            DslIr::AddEI(dst, a, y) if e(&a).is_some() => DslIr::ImmE(dst, e(&a).unwrap() + y),
+            DslIr::SubE(dst, a, b) => match (e(&a), e(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x - y),
+                (None, Some(y)) => DslIr::SubEI(dst, a, y),
+                (Some(x), None) => DslIr::SubEIN(dst, x, b),
+                (None, None) => DslIr::SubE(dst, a, b),
+            },
+            DslIr::SubEI(dst, a, y) if e(&a).is_some() => DslIr::ImmE(dst, e(&a).unwrap() - y),
+            DslIr::SubEIN(dst, x, b) if e(&b).is_some() => DslIr::ImmE(dst, x - e(&b).unwrap()),
+            DslIr::MulE(dst, a, b) => match (e(&a), e(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x * y),
+                (None, Some(y)) => DslIr::MulEI(dst, a, y),
+                (Some(x), None) => DslIr::MulEI(dst, b, x),
+                (None, None) => DslIr::MulE(dst, a, b),
+            },
+            DslIr::MulEI(dst, a, y) if e(&a).is_some() => DslIr::ImmE(dst, e(&a).unwrap() * y),
+            DslIr::DivE(dst, a, b) => match (e(&a), e(&b)) {
+                (Some(x), Some(y)) if !y.is_zero() => DslIr::ImmE(dst, x / y),
+                (None, Some(y)) if !y.is_zero() => DslIr::DivEI(dst, a, y),
+                (Some(x), None) => DslIr::DivEIN(dst, x, b),
+                _ => DslIr::DivE(dst, a, b),
+            },
+            DslIr::DivEI(dst, a, y) if e(&a).is_some() && !y.is_zero() => {
+                DslIr::ImmE(dst, e(&a).unwrap() / y)
+            }
+            DslIr::DivEIN(dst, x, b) if e(&b).is_some_and(|y| !y.is_zero()) => {
+                DslIr::ImmE(dst, x / e(&b).unwrap())
+            }
+            DslIr::NegE(dst, a) if e(&a).is_some() => DslIr::ImmE(dst, -e(&a).unwrap()),
+
+            DslIr::AddEF(dst, a, b) => match (e(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x + base(y)),
+                (None, Some(y)) => DslIr::AddEFI(dst, a, y),
+                (Some(x), None) => DslIr::AddEFFI(dst, b, x),
+                (None, None) => DslIr::AddEF(dst, a, b),
+            },
+            DslIr::AddEFI(dst, a, y) if e(&a).is_some() => {
+                DslIr::ImmE(dst, e(&a).unwrap() + base(y))
+            }
+            DslIr::AddEFFI(dst, a, y) if f(&a).is_some() => {
+                DslIr::ImmE(dst, base(f(&a).unwrap()) + y)
+            }
+            DslIr::SubEF(dst, a, b) => match (e(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x - base(y)),
+                (None, Some(y)) => DslIr::SubEFI(dst, a, y),
+                _ => DslIr::SubEF(dst, a, b),
+            },
+            DslIr::SubEFI(dst, a, y) if e(&a).is_some() => {
+                DslIr::ImmE(dst, e(&a).unwrap() - base(y))
+            }
+            DslIr::MulEF(dst, a, b) => match (e(&a), f(&b)) {
+                (Some(x), Some(y)) => DslIr::ImmE(dst, x * base(y)),
+                (None, Some(y)) => DslIr::MulEFI(dst, a, y),
+                _ => DslIr::MulEF(dst, a, b),
+            },
+            DslIr::MulEFI(dst, a, y) if e(&a).is_some() => {
+                DslIr::ImmE(dst, e(&a).unwrap() * base(y))
+            }
+            DslIr::DivEF(dst, a, b) => match (e(&a), f(&b)) {
+                (Some(x), Some(y)) if !y.is_zero() => DslIr::ImmE(dst, x / base(y)),
+                (None, Some(y)) if !y.is_zero() => DslIr::DivEFI(dst, a, y),
+                _ => DslIr::DivEF(dst, a, b),
+            },
+            DslIr::DivEFI(dst, a, y) if e(&a).is_some() && !y.is_zero() => {
+                DslIr::ImmE(dst, e(&a).unwrap() / base(y))
+            }
+            DslIr::For(start, end, step, loop_var, mut body) => {
+                let fold_bound = |x: RVar<C::N>| match x {
+                    RVar::Val(var) => v(&var).map_or(x, RVar::Const),
+                    x => x,
+                };
+                // The end is read again after every iteration.
+                let mut body_defs = HashMap::new();
+                count_accesses(&mut body, Access::is_def, &mut body_defs);
+                let end = match end {
+                    RVar::Val(var) if body_defs.contains_key(&Slot::Var(var.0)) => end,
+                    end => fold_bound(end),
+                };
+                DslIr::For(fold_bound(start), end, step, loop_var, body)
+            }
+            instr => instr,
+        }
+    }
+}
+
+/// State of common subexpression elimination.
+struct Cse<C: Config> {
+    /// Number of definitions of each variable in the whole program.
+    def_counts: HashMap<Slot, usize>,
+    /// Variables whose definition was removed, mapped to the variable holding the same value.
+    renames: HashMap<Slot, Slot>,
+    /// Position of the current instruction, counting instructions of all blocks.
+    pos: usize,
+    /// Position of the last read of each variable.
+    last_use: HashMap<Slot, usize>,
+    /// Expressions computed in the current block, with the variable holding the result and the
+    /// position of its definition.
+    available: HashMap<Expr<C>, (Slot, usize)>,
+    /// Available expressions by operand, to forget them when the operand is redefined.
+    users: HashMap<Slot, Vec<Expr<C>>>,
+}
+
+impl<C: Config> Default for Cse<C> {
+    fn default() -> Self {
+        Self {
+            def_counts: HashMap::new(),
+            renames: HashMap::new(),
+            pos: 0,
+            last_use: HashMap::new(),
+            available: HashMap::new(),
+            users: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Config> Cse<C> {
+    fn clear(&mut self) {
+        self.available.clear();
+        self.users.clear();
+    }
+
+    fn invalidate(&mut self, slot: Slot) {
+        for expr in self.users.remove(&slot).unwrap_or_default() {
+            self.available.remove(&expr);
+        }
+    }
+
+    fn is_single_def(&self, slot: Slot) -> bool {
+        self.def_counts.get(&slot) == Some(&1)
+    }
+
+    fn eliminate_block(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        self.clear();
+        let mut kept = TracedVec::new();
+        for (mut instr, trace) in take(operations) {
+            self.pos += 1;
+            rename_operands(&mut instr, &self.renames);
+            if is_control_flow(&instr) {
+                for block in nested_blocks(&mut instr) {
+                    self.eliminate_block(block);
+                }
+                self.clear();
+                kept.extend([(instr, trace)]);
+                continue;
+            }
+
+            let expr = pure_expr(&instr);
+            if let Some((dst, expr)) = &expr {
+                if let Some(&(src, def_pos)) = self.available.get(expr) {
+                    // `src` and `dst` are defined once, next to each other, so they always hold
+                    // the same value unless `dst` is read in between.
+                    let read_in_between = self.last_use.get(dst).is_some_and(|&p| p >= def_pos);
+                    if self.is_single_def(*dst) && !expr.1.contains(dst) && !read_in_between {
+                        self.renames.insert(*dst, src);
+                        self.invalidate(*dst);
+                        continue;
+                    }
+                }
+            }
+
+            let mut defs = vec![];
+            visit_operands(&mut instr, &mut |access, slot| {
+                if access.is_use() {
+                    self.last_use.insert(*slot, self.pos);
+                }
+                if access.is_def() {
+                    defs.push(*slot);
+                }
+            });
+            for slot in defs {
+                self.invalidate(slot);
+            }
+            if let Some((dst, expr)) = expr {
+                if self.is_single_def(dst) && !expr.1.contains(&dst) {
+                    for &arg in &expr.1 {
+                        self.users.entry(arg).or_default().push(expr.clone());
+                    }
+                    self.available.insert(expr, (dst, self.pos));
+                }
+            }
+            kept.extend([(instr, trace)]);
+        }
+        *operations = kept;
+    }
+}
+
+/// A variable access recorded by [Liveness].
+struct SlotAccess {
+    slot: Slot,
+    pos: usize,
+    access: Access,
+    /// Innermost block containing the access.
+    block: usize,
+    /// Outermost loop or function body containing the access.
+    outer_loop: Option<usize>,
+}
+
+/// Live ranges of variables over the program positions, in the order the instructions appear in
+/// the source. Instructions of nested blocks get the positions between the start and the end of
+/// their control flow instruction.
+#[derive(Default)]
+struct Liveness {
+    pos: usize,
+    /// Position range of each block.
+    blocks: Vec<(usize, usize)>,
+    /// Position range of each loop which is not nested in another loop, and of each function
+    /// body.
+    outer_loops: Vec<(usize, usize)>,
+    accesses: Vec<SlotAccess>,
+}
+
+impl Liveness {
+    fn visit_block<C: Config>(
+        &mut self,
+        operations: &mut TracedVec<DslIr<C>>,
+        outer_loop: Option<usize>,
+    ) {
+        let block = self.blocks.len();
+        self.blocks.push((self.pos, self.pos));
+        for instr in operations.vec.iter_mut() {
+            self.pos += 1;
+            let start = self.pos;
+            let is_loop = matches!(instr, DslIr::For(..) | DslIr::Loop(_));
+            let outer_loop = match outer_loop {
+                None if is_loop => {
+                    self.outer_loops.push((start, start));
+                    Some(self.outer_loops.len() - 1)
+                }
+                // A function body runs at every call, so its variables are live everywhere.
+                None if matches!(instr, DslIr::FnDef(..)) => {
+                    self.outer_loops.push((0, usize::MAX));
+                    Some(self.outer_loops.len() - 1)
+                }
+                outer_loop => outer_loop,
+            };
+            let mut operands = vec![];
+            visit_operands(instr, &mut |access, slot| operands.push((access, *slot)));
+            self.record(&operands, start, block, outer_loop);
+            for nested in nested_blocks(instr) {
+                self.visit_block(nested, outer_loop);
+            }
+            if is_loop {
+                // The loop variable and the bounds are accessed on every iteration.
+                self.pos += 1;
+                self.record(&operands, self.pos, block, outer_loop);
+                if let Some(outer_loop) = outer_loop {
+                    let end = &mut self.outer_loops[outer_loop].1;
+                    *end = (*end).max(self.pos);
+                }
+            }
+        }
+        self.blocks[block].1 = self.pos;
+    }
+
+    fn record(
+        &mut self,
+        operands: &[(Access, Slot)],
+        pos: usize,
+        block: usize,
+        outer_loop: Option<usize>,
+    ) {
+        self.accesses
+            .extend(operands.iter().map(|&(access, slot)| SlotAccess {
+                slot,
+                pos,
+                access,
+                block,
+                outer_loop,
+            }));
+    }
+
+    /// Assigns slots to variables with a linear scan over their live ranges, and returns the new
+    /// slot of each variable.
+    fn allocate_slots(&self) -> HashMap<Slot, Slot> {
+        // Accesses are recorded in program order.
+        let mut ranges = HashMap::<Slot, (usize, usize)>::new();
+        let mut first_accesses = HashMap::<Slot, &SlotAccess>::new();
+        let mut last_accesses = HashMap::<Slot, usize>::new();
+        for access in &self.accesses {
+            first_accesses.entry(access.slot).or_insert(access);
+            last_accesses.insert(access.slot, access.pos);
+            let (start, end) = access
+                .outer_loop
+                .map_or((access.pos, access.pos), |l| self.outer_loops[l]);
+            let range = ranges.entry(access.slot).or_insert((start, end));
+            range.0 = range.0.min(start);
+            range.1 = range.1.max(end);
+        }
+        for (slot, first) in first_accesses {
+            // A variable may be read before it is written, e.g. if it is only assigned in one
+            // branch. Its slot must then keep its initial value, so it is never shared with an
+            // earlier variable.
+            let block_end = self.blocks[first.block].1;
+            if first.access.is_use() || last_accesses[&slot] > block_end {
+                ranges.get_mut(&slot).unwrap().0 = 0;
+            }
+        }
+
+        let mut renames = HashMap::new();
+        let kinds: [fn(u32) -> Slot; 3] = [Slot::Var, Slot::Felt, Slot::Ext];
+        for kind in kinds {
+            let mut kind_ranges: Vec<_> = ranges
+                .iter()
+                .filter(|(slot, _)| kind(slot.id()) == **slot)
+                .map(|(&slot, &(start, end))| (start, end, slot))
+                .collect();
+            kind_ranges.sort();
+            // New slots are taken from the used slots, in increasing order.
+            let mut ids: Vec<_> = kind_ranges.iter().map(|(_, _, slot)| slot.id()).collect();
+            ids.sort();
+
+            let mut free = BinaryHeap::new();
+            let mut active = BinaryHeap::new();
+            let mut num_used = 0;
+            for (start, end, slot) in kind_ranges {
+                while let Some(&Reverse((active_end, idx))) = active.peek() {
+                    if active_end >= start {
+                        break;
+                    }
+                    active.pop();
+                    free.push(Reverse(idx));
+                }
+                let idx = free.pop().map(|Reverse(idx)| idx).unwrap_or_else(|| {
+                    num_used += 1;
+                    num_used - 1
+                });
+                active.push(Reverse((end, idx)));
+                renames.insert(slot, kind(ids[idx]));
+            }
+        }
+        renames
+    }
+}
+
+impl Slot {
+    fn id(self) -> u32 {
+        match self {
+            Slot::Var(id) | Slot::Felt(id) | Slot::Ext(id) => id,
+        }
+    }
+}
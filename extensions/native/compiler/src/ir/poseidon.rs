@@ -1,6 +1,6 @@
 use openvm_stark_backend::p3_field::AbstractField;
 
-use super::{Array, Builder, Config, DslIr, Ext, Felt, Usize, Var};
+use super::{Array, Builder, Config, DslIr, Ext, Felt, RVar, Usize, Var};
 
 pub const DIGEST_SIZE: usize = 8;
 pub const HASH_RATE: usize = 8;
@@ -74,6 +74,39 @@ impl<C: Config> Builder<C> {
         ));
     }
 
+    /// Absorbs `chunk` into the sponge `state` in place: the first [HASH_RATE] elements of the
+    /// state are overwritten by the chunk, and the state is then permuted.
+    ///
+    /// Reference: [p3_symmetric::PaddingFreeSponge]
+    pub fn poseidon2_absorb_mut(
+        &mut self,
+        state: &Array<C, Felt<C::F>>,
+        chunk: &Array<C, Felt<C::F>>,
+    ) {
+        if let Array::Fixed(_) = state {
+            panic!("Poseidon2 absorption is not allowed on fixed arrays");
+        }
+        self.operations
+            .push(DslIr::Poseidon2AbsorbBabyBear(state.clone(), chunk.clone()));
+    }
+
+    /// Replaces the Merkle tree `node` in place by its parent, given its `sibling` and the bit of
+    /// the leaf index at this level, which is set when the node is a right child.
+    ///
+    /// Reference: [p3_symmetric::TruncatedPermutation]
+    pub fn poseidon2_merkle_step(
+        &mut self,
+        node: &Array<C, Felt<C::F>>,
+        sibling: &Array<C, Felt<C::F>>,
+        bit: Var<C::N>,
+    ) {
+        self.operations.push(DslIr::Poseidon2MerkleStepBabyBear(
+            node.clone(),
+            sibling.clone(),
+            bit,
+        ));
+    }
+
     /// Applies the Poseidon2 permutation to the given array.
     ///
     /// Reference: [p3_symmetric::PaddingFreeSponge]
@@ -95,23 +128,46 @@ impl<C: Config> Builder<C> {
                 builder
                     .if_eq(break_flag, C::N::ONE)
                     .then_may_break(|builder| builder.break_loop())?;
-                // Insert elements of the chunk.
-                builder
-                    .range(0, hash_rate)
-                    .may_break()
-                    .for_each(|j, builder| {
-                        let index = builder.eval_expr(i + j);
-                        let element = builder.get(array, index);
-                        builder.set_value(&state, j, element);
+                // Only the last chunk can be partial, in which case it ends before its last slot.
+                let is_partial: Var<_> = builder.eval(C::N::ZERO);
+                for j in 0..HASH_RATE - 1 {
+                    builder
+                        .if_eq(i + RVar::from(j), last_index.clone())
+                        .then(|builder| builder.assign(&is_partial, C::N::ONE));
+                }
+                builder.if_eq(is_partial, C::N::ZERO).then_or_else(
+                    |builder| match array {
+                        // Absorb a whole chunk straight from the array.
+                        Array::Dyn(..) => {
+                            let chunk = array.shift(builder, i);
+                            builder.poseidon2_absorb_mut(&state, &chunk);
+                        }
+                        Array::Fixed(_) => {
+                            for j in 0..HASH_RATE {
+                                let index = builder.eval_expr(i + RVar::from(j));
+                                let element = builder.get(array, index);
+                                builder.set_value(&state, j, element);
+                            }
+                            builder.poseidon2_permute_mut(&state);
+                        }
+                    },
+                    |builder| {
+                        // Insert elements of the partial chunk.
                         builder
-                            .if_eq(index, last_index.clone())
-                            .then_may_break(|builder| {
-                                builder.assign(&break_flag, C::N::ONE);
-                                builder.break_loop()
-                            })
-                    });
-
-                builder.poseidon2_permute_mut(&state);
+                            .range(0, hash_rate)
+                            .may_break()
+                            .for_each(|j, builder| {
+                                let index = builder.eval_expr(i + j);
+                                let element = builder.get(array, index);
+                                builder.set_value(&state, j, element);
+                                builder
+                                    .if_eq(index, last_index.clone())
+                                    .then_may_break(|builder| builder.break_loop())
+                            });
+                        builder.poseidon2_permute_mut(&state);
+                        builder.assign(&break_flag, C::N::ONE);
+                    },
+                );
                 Ok(())
             });
 
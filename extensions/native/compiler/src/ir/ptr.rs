@@ -54,6 +54,25 @@ impl<C: Config> Builder<C> {
         );
         self.push(DslIr::StoreHeapPtr(ptr));
     }
+
+    /// Frees the heap memory of `ptr` and of everything allocated after it, so that the next
+    /// allocations reuse it. The freed memory is not cleared.
+    pub fn dealloc(&mut self, ptr: Ptr<C::N>) {
+        self.store_heap_ptr(ptr);
+    }
+
+    /// Runs `f` and frees all heap memory allocated by it afterwards. Arrays allocated inside `f`
+    /// must not be accessed after it returns. In static mode there is no heap and `f` is just
+    /// run.
+    pub fn arena<R>(&mut self, f: impl FnOnce(&mut Builder<C>) -> R) -> R {
+        if self.flags.static_only {
+            return f(self);
+        }
+        let heap_ptr = self.load_heap_ptr();
+        let ret = f(self);
+        self.dealloc(heap_ptr);
+        ret
+    }
 }
 
 impl<C: Config> Variable<C> for Ptr<C::N> {
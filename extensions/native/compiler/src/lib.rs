@@ -90,6 +90,19 @@ pub enum FieldExtensionOpcode {
     BBE4DIV,
 }
 
+/// Opcodes for fused multiply-add, `a = b * c + e`.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x170]
+#[repr(usize)]
+pub enum MulAddOpcode {
+    /// Multiply-add of base field elements.
+    MULADD,
+    /// Multiply-add of extension field elements.
+    BBE4MULADD,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromRepr)]
 #[repr(u16)]
 pub enum NativePhantom {
@@ -112,4 +125,7 @@ pub enum FriOpcode {
     /// In FRI pcs opening verification, the reduced opening polynomial is computed one evaluation
     /// per column polynomial, per opening point
     FRI_REDUCED_OPENING,
+    /// Folds a pair of sibling evaluations at `x` and `-x` into the evaluation of the folded
+    /// polynomial at `x^2`, for one round of one FRI query
+    FRI_FOLD,
 }
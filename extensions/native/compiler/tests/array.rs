@@ -278,3 +278,36 @@ fn test_slice_assert_eq_neg() {
     let program = builder.compile_isa();
     execute_program(program, vec![]);
 }
+
+#[test]
+fn test_arena_reuses_heap() {
+    type F = BabyBear;
+    type EF = BinomialExtensionField<BabyBear, 4>;
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let ptrs: Vec<Var<_>> = (0..2)
+        .map(|_| {
+            builder.arena(|builder| {
+                let arr: Array<_, Var<_>> = builder.dyn_array(10);
+                builder.range(0, arr.len()).for_each(|i, builder| {
+                    builder.set(&arr, i, i);
+                });
+                let value = builder.get(&arr, 9);
+                builder.assert_var_eq(value, F::from_canonical_u32(9));
+                arr.ptr().address
+            })
+        })
+        .collect();
+    builder.assert_var_eq(ptrs[0], ptrs[1]);
+
+    let arr: Array<_, Var<_>> = builder.dyn_array(4);
+    builder.assert_var_eq(arr.ptr().address, ptrs[0]);
+    builder.dealloc(arr.ptr());
+    let arr: Array<_, Var<_>> = builder.dyn_array(4);
+    builder.assert_var_eq(arr.ptr().address, ptrs[0]);
+
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, vec![]);
+}
@@ -0,0 +1,59 @@
+use openvm_instructions::instruction::{call_site, frame_crate};
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    ir::{DslIr, Felt, Var},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+#[test]
+fn test_frame_crate() {
+    assert_eq!(
+        frame_crate("openvm_native_compiler::ir::builder::Builder<C>::eval"),
+        "openvm_native_compiler"
+    );
+    assert_eq!(
+        frame_crate("<openvm_native_compiler::ir::var::Felt<F> as core::ops::arith::Add>::add"),
+        "openvm_native_compiler"
+    );
+    assert_eq!(
+        frame_crate("<&mut alloc::vec::Vec<T> as core::iter::traits::collect::Extend<T>>::extend"),
+        "alloc"
+    );
+    assert_eq!(
+        frame_crate("openvm_native_recursion::fri::verify_two_adic_pcs::{{closure}}"),
+        "openvm_native_recursion"
+    );
+}
+
+#[test]
+fn test_call_site() {
+    // Backtraces are only recorded when RUST_BACKTRACE is set.
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let line = line!() + 1;
+    let x: Felt<_> = builder.eval(F::ONE);
+    let n: Var<_> = builder.eval(F::TWO);
+    builder.range(0, n).for_each(|_, builder| {
+        builder.assign(&x, x + x);
+    });
+
+    let site = |trace: &Option<_>| call_site(trace.as_ref().unwrap()).unwrap();
+    let operations = &builder.operations;
+    assert!(site(&operations.traces[0]).ends_with(&format!("call_site.rs:{line}")));
+    let (body, trace) = operations
+        .vec
+        .iter()
+        .zip(&operations.traces)
+        .find_map(|(instr, trace)| match instr {
+            DslIr::For(_, _, _, _, body) => Some((body, trace)),
+            _ => None,
+        })
+        .unwrap();
+    assert!(site(trace).ends_with(&format!("call_site.rs:{}", line + 2)));
+    // Instructions inside a loop body point into the closure, not into the loop helper.
+    assert!(site(&body.traces[0]).ends_with(&format!("call_site.rs:{}", line + 3)));
+}
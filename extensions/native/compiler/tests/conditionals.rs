@@ -115,3 +115,34 @@ fn test_compiler_conditionals_const() {
         "Constant conditionals should be optimized"
     );
 }
+
+#[test]
+fn test_compiler_switch() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    for (value, expected) in [(0, 10), (1, 11), (2, 12), (3, 0)] {
+        let value: Var<_> = builder.eval(F::from_canonical_u32(value));
+        let c: Var<_> = builder.eval(F::ZERO);
+        builder.switch(
+            value,
+            &mut [
+                (F::ZERO, &mut |builder| {
+                    builder.assign(&c, F::from_canonical_u32(10))
+                }),
+                (F::ONE, &mut |builder| {
+                    builder.assign(&c, F::from_canonical_u32(11))
+                }),
+                (F::TWO, &mut |builder| {
+                    builder.assign(&c, F::from_canonical_u32(12))
+                }),
+            ],
+            &mut |builder| builder.assign(&c, F::ZERO),
+        );
+        builder.assert_var_eq(c, F::from_canonical_u32(expected));
+    }
+
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, vec![]);
+}
@@ -1,5 +1,11 @@
+use std::collections::BTreeMap;
+
 use openvm_native_circuit::execute_program;
-use openvm_native_compiler::{asm::AsmBuilder, conversion::CompilerOptions, ir::Var};
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::{estimate_cycles, CompilerOptions},
+    ir::Var,
+};
 use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
 
@@ -50,3 +56,38 @@ fn test_cycle_tracker() {
     println!("{}", program);
     execute_program(program, vec![]);
 }
+
+#[test]
+fn test_estimate_cycles() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let n: Var<_> = builder.eval(F::from_canonical_u32(10));
+    let m: Var<_> = builder.eval(F::from_canonical_u32(20));
+    let total: Var<_> = builder.eval(F::ZERO);
+    builder.range(0, n).for_each(|_, builder| {
+        builder.range(0, m).for_each(|_, builder| {
+            builder.assign(&total, total + F::ONE);
+        });
+    });
+    builder.assert_var_eq(total, F::from_canonical_u32(200));
+    builder.halt();
+
+    let options = CompilerOptions::default();
+    let program = builder.compile_isa_with_options(options);
+
+    let estimate = estimate_cycles(&program, &options, &BTreeMap::new());
+    assert_eq!(estimate.loops.len(), 2);
+    assert_eq!(estimate.total_cycles(), program.len() as u64);
+
+    let (outer, inner) = (&estimate.loops[0], &estimate.loops[1]);
+    let loop_bounds = BTreeMap::from([(outer.header_pc, 10), (inner.header_pc, 20)]);
+    let estimate = estimate_cycles(&program, &options, &loop_bounds);
+    println!("{estimate}");
+    let inner_body = estimate
+        .blocks
+        .iter()
+        .find(|block| block.start_pc == inner.header_pc)
+        .unwrap();
+    assert_eq!(inner_body.executions, 200);
+
+    execute_program(program, vec![]);
+}
@@ -0,0 +1,71 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::CompilerOptions,
+    ir::{Felt, Var},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+fn build() -> AsmBuilder<F, EF> {
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    let square = builder.fn_def(|builder, x: Felt<F>| -> Felt<F> { builder.eval(x * x) });
+    // Calls `square` from its body.
+    let sum_of_squares = builder.fn_def(|builder, xs: [Felt<F>; 2]| -> Felt<F> {
+        let a = builder.fn_call(&square, xs[0]);
+        let b = builder.fn_call(&square, xs[1]);
+        builder.eval(a + b)
+    });
+
+    let nine = builder.fn_call(&square, F::from_canonical_u32(3));
+    builder.assert_felt_eq(nine, F::from_canonical_u32(9));
+
+    let three: Felt<_> = builder.eval(F::from_canonical_u32(3));
+    let four: Felt<_> = builder.eval(F::from_canonical_u32(4));
+    let twenty_five = builder.fn_call(&sum_of_squares, [three, four]);
+    builder.assert_felt_eq(twenty_five, F::from_canonical_u32(25));
+    // The result of the first call is a copy, so later calls do not change it.
+    builder.assert_felt_eq(nine, F::from_canonical_u32(9));
+
+    let n: Var<_> = builder.eval(F::from_canonical_u32(4));
+    let x: Felt<_> = builder.eval(F::ZERO);
+    let sum: Felt<_> = builder.eval(F::ZERO);
+    builder.range(0, n).for_each(|_, builder| {
+        builder.assign(&x, x + F::ONE);
+        let x_squared = builder.fn_call(&square, x);
+        builder.assign(&sum, sum + x_squared);
+    });
+    builder.assert_felt_eq(sum, F::from_canonical_u32(1 + 4 + 9 + 16));
+
+    builder.halt();
+    builder
+}
+
+#[test]
+fn test_compiler_functions() {
+    execute_program(build().compile_isa(), vec![]);
+}
+
+#[test]
+fn test_compiler_functions_optimized() {
+    let program = build().compile_isa_with_options(CompilerOptions {
+        optimize_ir: true,
+        reuse_stack_slots: true,
+        max_unroll_iterations: 16,
+        ..Default::default()
+    });
+    execute_program(program, vec![]);
+}
+
+#[test]
+#[should_panic(expected = "functions must be defined at the top level")]
+fn test_compiler_function_in_loop() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    builder.range(0, 2).for_each(|_, builder| {
+        builder.fn_def(|builder, x: Felt<F>| -> Felt<F> { builder.eval(x + x) });
+    });
+}
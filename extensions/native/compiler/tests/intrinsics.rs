@@ -0,0 +1,70 @@
+use openvm_instructions::{Poseidon2Opcode, VmOpcode};
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::CompilerOptions,
+    ir::{IntrinsicDef, IntrinsicId, IntrinsicRegistry, DIGEST_SIZE, PERMUTATION_WIDTH},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::{config::baby_bear_poseidon2::default_perm, p3_baby_bear::BabyBear};
+use p3_symmetric::Permutation;
+use rand::{thread_rng, Rng};
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+const POSEIDON2_COMPRESS: IntrinsicId = IntrinsicId(0);
+
+#[test]
+fn test_compiler_intrinsic_poseidon2_compress() {
+    let mut rng = thread_rng();
+    let intrinsics = IntrinsicRegistry::new().with_intrinsic(
+        POSEIDON2_COMPRESS,
+        IntrinsicDef::new(
+            "poseidon2_compress",
+            VmOpcode::with_default_offset(Poseidon2Opcode::COMP_POS2),
+            1,
+            2,
+        ),
+    );
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let left_vals: [F; DIGEST_SIZE] = rng.gen();
+    let right_vals: [F; DIGEST_SIZE] = rng.gen();
+    let mut input = [F::ZERO; PERMUTATION_WIDTH];
+    input[..DIGEST_SIZE].copy_from_slice(&left_vals);
+    input[DIGEST_SIZE..].copy_from_slice(&right_vals);
+    let expected_result = default_perm().permute(input);
+
+    let left = builder.dyn_array(DIGEST_SIZE);
+    let right = builder.dyn_array(DIGEST_SIZE);
+    for (i, (&left_val, &right_val)) in left_vals.iter().zip(&right_vals).enumerate() {
+        builder.set(&left, i, left_val);
+        builder.set(&right, i, right_val);
+    }
+    let result = builder.dyn_array(DIGEST_SIZE);
+    builder.intrinsic(
+        POSEIDON2_COMPRESS,
+        vec![(&result).into()],
+        vec![(&left).into(), (&right).into()],
+    );
+
+    for (i, val) in expected_result[..DIGEST_SIZE].iter().enumerate() {
+        let res = builder.get(&result, i);
+        builder.assert_felt_eq(res, *val);
+    }
+    builder.halt();
+
+    let program = builder.compile_isa_with_intrinsics(CompilerOptions::default(), intrinsics);
+    execute_program(program, vec![]);
+}
+
+#[test]
+#[should_panic(expected = "is not registered")]
+fn test_compiler_intrinsic_unregistered() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let result = builder.dyn_array(DIGEST_SIZE);
+    builder.intrinsic(POSEIDON2_COMPRESS, vec![(&result).into()], vec![]);
+    builder.halt();
+    builder.compile_isa();
+}
@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+
+use openvm_circuit::arch::{
+    instructions::program::Program, ExecutionError, SystemConfig, VmExecutor,
+};
+use openvm_native_circuit::{execute_program, Native, NativeConfig};
+use openvm_native_compiler::{
+    asm::{AsmBuilder, AsmConfig},
+    conversion::CompilerOptions,
+    ir::{
+        optimize, DslIr, Ext, ExtConst, Felt, IrPass, LoopUnrolling, RVar, StrengthReduction,
+        TracedVec, Var,
+    },
+};
+use openvm_stark_backend::p3_field::{
+    extension::BinomialExtensionField, AbstractField, PrimeField32,
+};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+/// Number of distinct stack addresses referenced by the operands of `program`.
+fn num_stack_slots(program: &Program<F>) -> usize {
+    // Mirrors `STACK_TOP` of the assembly compiler. Variables are stored below it.
+    const STACK_TOP: u32 = (1 << 24) - 64;
+    program
+        .instructions()
+        .iter()
+        .flat_map(|instr| [instr.a, instr.b, instr.c])
+        .map(|operand| operand.as_canonical_u32())
+        .filter(|addr| (STACK_TOP - (1 << 16)..STACK_TOP).contains(addr))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Number of `For` loops at the top level of `operations`.
+fn num_loops(operations: &TracedVec<DslIr<AsmConfig<F, EF>>>) -> usize {
+    operations
+        .vec
+        .iter()
+        .filter(|instr| matches!(instr, DslIr::For(..)))
+        .count()
+}
+
+fn build_program() -> AsmBuilder<F, EF> {
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    // Constant folding.
+    let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+    let b: Felt<_> = builder.eval(F::from_canonical_u32(5));
+    let c: Felt<_> = builder.eval(a * b + a);
+    builder.assert_felt_eq(c, F::from_canonical_u32(18));
+
+    // Common subexpressions and dead code.
+    let x = builder.hint_felt();
+    let y1: Felt<_> = builder.eval(x * x + x);
+    let y2: Felt<_> = builder.eval(x * x + x);
+    let _unused: Felt<_> = builder.eval(x * a + b);
+    builder.assert_felt_eq(y1, y2);
+    builder.assert_felt_eq(y1, F::from_canonical_u32(56));
+
+    // Variables redefined in a loop must not be merged.
+    let acc: Felt<_> = builder.eval(x);
+    builder.range(0, 3).for_each(|_, builder| {
+        builder.assign(&acc, acc * x);
+    });
+    let pow: Felt<_> = builder.eval(x * x * x * x);
+    builder.assert_felt_eq(acc, pow);
+    builder.assert_felt_eq(acc, F::from_canonical_u32(2401));
+
+    builder.halt();
+    builder
+}
+
+#[test]
+fn test_compiler_optimize_ir() {
+    let input = vec![vec![F::from_canonical_u32(7)]];
+
+    let program = build_program().compile_isa();
+    let optimized_program =
+        build_program().compile_isa_with_options(CompilerOptions::default().with_ir_optimization());
+    assert!(optimized_program.len() < program.len());
+
+    execute_program(program, input.clone());
+    execute_program(optimized_program, input);
+}
+
+#[test]
+fn test_compiler_stack_slot_reuse() {
+    let build = || {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let x = builder.hint_felt();
+        let acc: Felt<_> = builder.eval(F::ZERO);
+        builder.range(0, 10).for_each(|i, builder| {
+            let i_var: Var<_> = builder.eval(i);
+            let i_felt = builder.unsafe_cast_var_to_felt(i_var);
+            let square: Felt<_> = builder.eval(i_felt * i_felt);
+            let term: Felt<_> = builder.eval(square * x + i_felt);
+            builder.assign(&acc, acc + term);
+        });
+        // sum(7 * i^2 + i) for i in 0..10
+        builder.assert_felt_eq(acc, F::from_canonical_u32(2040));
+        let doubled: Felt<_> = builder.eval(acc + acc);
+        builder.assert_felt_eq(doubled, F::from_canonical_u32(4080));
+        builder.halt();
+        builder
+    };
+    let input = vec![vec![F::from_canonical_u32(7)]];
+
+    let reused =
+        build().compile_isa_with_options(CompilerOptions::default().with_stack_slot_reuse());
+    let program = build().compile_isa();
+    // The loop temporaries and the final sum share slots with dead variables.
+    assert!(
+        num_stack_slots(&reused) < num_stack_slots(&program),
+        "stack slot reuse did not reduce the {} stack slots",
+        num_stack_slots(&program)
+    );
+
+    execute_program(reused, input.clone());
+    execute_program(program, input);
+}
+
+#[test]
+fn test_compiler_loop_unrolling() {
+    let build = || {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let x = builder.hint_felt();
+        let acc: Felt<_> = builder.eval(F::ZERO);
+        // The bound is only known to be constant after constant folding.
+        let n: Var<_> = builder.eval(F::from_canonical_u32(4));
+        builder.range(0, n).for_each(|i, builder| {
+            let i_var: Var<_> = builder.eval(i);
+            let i_felt = builder.unsafe_cast_var_to_felt(i_var);
+            builder.assign(&acc, acc * F::TWO + i_felt * x);
+        });
+        builder.assert_felt_eq(acc, F::from_canonical_u32(77));
+
+        // Loops which may break are not unrolled.
+        let count: Var<_> = builder.eval(F::ZERO);
+        builder.range(0, 8).may_break().for_each(|i, builder| {
+            builder.assign(&count, count + F::ONE);
+            builder
+                .if_eq(i, RVar::from(2))
+                .then_may_break(|builder| builder.break_loop())
+        });
+        builder.assert_var_eq(count, F::from_canonical_u32(3));
+        builder.halt();
+        builder
+    };
+    let input = vec![vec![F::from_canonical_u32(7)]];
+
+    // The constant bound loop is unrolled, the loop which may break is kept.
+    let mut operations = build().operations;
+    assert_eq!(num_loops(&operations), 2);
+    optimize(&mut operations);
+    LoopUnrolling { max_iterations: 16 }.run(&mut operations);
+    assert_eq!(num_loops(&operations), 1);
+    let mut operations = build().operations;
+    optimize(&mut operations);
+    LoopUnrolling { max_iterations: 3 }.run(&mut operations);
+    assert_eq!(num_loops(&operations), 2);
+
+    let program = build().compile_isa_with_options(CompilerOptions {
+        optimize_ir: true,
+        max_unroll_iterations: 16,
+        ..Default::default()
+    });
+    execute_program(program, input);
+}
+
+#[test]
+fn test_compiler_strength_reduction() {
+    let build = || {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let x = builder.hint_felt();
+        let a: Ext<_, _> = builder.eval(x);
+        for k in [0, 1, 2, 3, 4, 5, 6, 8, 16] {
+            let expected = EF::from_canonical_u32(7 * k).cons();
+            let product: Ext<_, _> = builder.eval(a * F::from_canonical_u32(k));
+            builder.assert_ext_eq(product, expected.clone());
+            // Extension constants in the base field are multiplied as base field constants.
+            let product: Ext<_, _> = builder.eval(a * EF::from_canonical_u32(k));
+            builder.assert_ext_eq(product, expected);
+        }
+        let b: Ext<_, _> = builder.eval(a);
+        builder.assign(&b, b * F::from_canonical_u32(3));
+        builder.assign(&b, b * F::from_canonical_u32(4));
+        builder.assert_ext_eq(b, EF::from_canonical_u32(84).cons());
+        builder.halt();
+        builder
+    };
+    let count = |operations: &TracedVec<DslIr<AsmConfig<F, EF>>>| {
+        let (mut mul_ei, mut mul_efi, mut add_e) = (0, 0, 0);
+        for instr in &operations.vec {
+            match instr {
+                DslIr::MulEI(..) => mul_ei += 1,
+                DslIr::MulEFI(..) => mul_efi += 1,
+                DslIr::AddE(..) => add_e += 1,
+                _ => {}
+            }
+        }
+        (mul_ei, mul_efi, add_e)
+    };
+
+    let mut operations = build().operations;
+    assert_eq!(count(&operations), (9, 11, 0));
+    StrengthReduction.run(&mut operations);
+    // The additions for 2, 3, 4, 5, 6 and 8. 0, 1 and 16 stay multiplications by a base field
+    // constant, and so does tripling in place, which would add the overwritten input, while
+    // quadrupling in place only doubles.
+    let chains = 1 + 2 + 2 + 3 + 3 + 3;
+    assert_eq!(count(&operations), (0, 2 * 3 + 1, 2 * chains + 2));
+
+    let input = vec![vec![F::from_canonical_u32(7)]];
+    let reduced = build().compile_isa_with_options(CompilerOptions {
+        strength_reduction: true,
+        ..Default::default()
+    });
+    let program = build().compile_isa();
+    assert!(reduced.len() < program.len());
+    execute_program(reduced, input.clone());
+    execute_program(program, input);
+}
+
+#[test]
+fn test_compiler_fuse_mul_add() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let x = builder.hint_felt();
+    let y: Felt<_> = builder.eval(x * x + x);
+    builder.assert_felt_eq(y, F::from_canonical_u32(56));
+
+    let a: Ext<_, _> = builder.eval(x);
+    let b: Ext<_, _> = builder.eval(a * a + a);
+    let c: Ext<_, _> = builder.eval(a + b * a);
+    builder.assert_ext_eq(b, EF::from_canonical_u32(56).cons());
+    builder.assert_ext_eq(c, EF::from_canonical_u32(399).cons());
+    builder.halt();
+
+    let program = builder.compile_isa_with_options(CompilerOptions {
+        fuse_mul_add: true,
+        ..Default::default()
+    });
+    let config = NativeConfig::new(
+        SystemConfig::default().with_public_values(4),
+        Native { mul_add: true },
+    );
+    VmExecutor::<F, NativeConfig>::new(config.clone())
+        .execute(program.clone(), vec![vec![F::from_canonical_u32(7)]])
+        .unwrap();
+
+    // The default native extension does not have the multiply-add chips.
+    let mut config = config;
+    config.native = Native::default();
+    assert!(matches!(
+        VmExecutor::<F, NativeConfig>::new(config)
+            .execute(program, vec![vec![F::from_canonical_u32(7)]]),
+        Err(ExecutionError::DisabledOperation { .. })
+    ));
+}
+
+#[test]
+fn test_compiler_options_deserialize_without_new_fields() {
+    // Options serialized before the optimization passes were added.
+    let json = r#"{
+        "word_size": 4,
+        "compile_prints": false,
+        "enable_cycle_tracker": true,
+        "field_arithmetic_enabled": true,
+        "field_extension_enabled": false
+    }"#;
+    let options: CompilerOptions = serde_json::from_str(json).unwrap();
+    let default = CompilerOptions::default();
+    assert_eq!(options.word_size, 4);
+    assert!(!options.compile_prints);
+    assert!(options.enable_cycle_tracker);
+    assert!(!options.field_extension_enabled);
+    assert_eq!(options.optimize_ir, default.optimize_ir);
+    assert_eq!(options.reuse_stack_slots, default.reuse_stack_slots);
+    assert_eq!(options.trace_ir, default.trace_ir);
+    assert_eq!(options.max_unroll_iterations, default.max_unroll_iterations);
+    assert_eq!(options.strength_reduction, default.strength_reduction);
+    assert_eq!(options.fuse_mul_add, default.fuse_mul_add);
+    assert_eq!(
+        options.estimated_loop_iterations,
+        default.estimated_loop_iterations
+    );
+}
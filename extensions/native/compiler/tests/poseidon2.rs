@@ -1,12 +1,12 @@
 use openvm_native_circuit::execute_program;
 use openvm_native_compiler::{
     asm::AsmBuilder,
-    ir::{Array, Var, PERMUTATION_WIDTH},
+    ir::{Array, Var, DIGEST_SIZE, HASH_RATE, PERMUTATION_WIDTH},
     prelude::RVar,
 };
 use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
 use openvm_stark_sdk::{config::baby_bear_poseidon2::default_perm, p3_baby_bear::BabyBear};
-use p3_symmetric::Permutation;
+use p3_symmetric::{CryptographicHasher, PaddingFreeSponge, Permutation};
 use rand::{thread_rng, Rng};
 
 type F = BabyBear;
@@ -90,3 +90,70 @@ fn test_compiler_poseidon2_hash_1() {
     let program = builder.compile_isa();
     execute_program(program, vec![]);
 }
+
+#[test]
+fn test_compiler_poseidon2_hash_absorb() {
+    let mut rng = thread_rng();
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    // Whole chunks followed by a partial one, compared against the reference sponge.
+    let random_state_vals: [F; 3 * HASH_RATE + 3] = rng.gen();
+    let sponge =
+        PaddingFreeSponge::<_, PERMUTATION_WIDTH, HASH_RATE, DIGEST_SIZE>::new(default_perm());
+    let expected_result = sponge.hash_iter(random_state_vals);
+
+    let random_state = builder.dyn_array(random_state_vals.len());
+    for (i, val) in random_state_vals.iter().enumerate() {
+        builder.set(&random_state, i, *val);
+    }
+    let result = builder.poseidon2_hash(&random_state);
+    for (i, val) in expected_result.iter().enumerate() {
+        let res = builder.get(&result, i);
+        builder.assert_felt_eq(res, *val);
+    }
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, vec![]);
+}
+
+#[test]
+fn test_compiler_poseidon2_merkle_step() {
+    let mut rng = thread_rng();
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    let perm = default_perm();
+    for bit in 0..2 {
+        let node_vals: [F; DIGEST_SIZE] = rng.gen();
+        let sibling_vals: [F; DIGEST_SIZE] = rng.gen();
+        let (left, right) = if bit == 0 {
+            (node_vals, sibling_vals)
+        } else {
+            (sibling_vals, node_vals)
+        };
+        let mut input = [F::ZERO; PERMUTATION_WIDTH];
+        input[..DIGEST_SIZE].copy_from_slice(&left);
+        input[DIGEST_SIZE..].copy_from_slice(&right);
+        let expected_result = perm.permute(input);
+
+        let node = builder.dyn_array(DIGEST_SIZE);
+        let sibling = builder.dyn_array(DIGEST_SIZE);
+        for (i, (&node_val, &sibling_val)) in node_vals.iter().zip(&sibling_vals).enumerate() {
+            builder.set(&node, i, node_val);
+            builder.set(&sibling, i, sibling_val);
+        }
+        let bit: Var<_> = builder.eval(F::from_canonical_usize(bit));
+        builder.poseidon2_merkle_step(&node, &sibling, bit);
+
+        for (i, val) in expected_result[..DIGEST_SIZE].iter().enumerate() {
+            let res = builder.get(&node, i);
+            builder.assert_felt_eq(res, *val);
+        }
+    }
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, vec![]);
+}
@@ -1,7 +1,7 @@
 pub use domain::*;
 use openvm_native_compiler::{
     ir::{
-        Array, Builder, Config, Ext, ExtensionOperand, Felt, Ptr, RVar, SymbolicVar, Usize, Var,
+        Array, Builder, Config, Ext, ExtensionOperand, Felt, RVar, SymbolicVar, Usize, Var,
         DIGEST_SIZE,
     },
     prelude::MemVariable,
@@ -202,10 +202,16 @@ where
             let [xs_0, xs_1]: [Ext<_, _>; 2] =
                 cond_eval(builder, index_sibling_mod_2, x * two_adic_generator_one, x);
 
-            builder.assign(
-                &folded_eval,
-                eval_0 + (beta - xs_0) * (eval_1 - eval_0) / (xs_1 - xs_0),
-            );
+            if builder.flags.static_only {
+                builder.assign(
+                    &folded_eval,
+                    eval_0 + (beta - xs_0) * (eval_1 - eval_0) / (xs_1 - xs_0),
+                );
+            } else {
+                // `xs_1 = -xs_0`, which the dedicated chip relies on.
+                let folded = builder.fri_fold(eval_0, eval_1, beta, xs_0);
+                builder.assign(&folded_eval, folded);
+            }
 
             builder.assign(&x, x * x);
         });
@@ -274,31 +280,13 @@ pub fn verify_batch<C: Config>(
             opened_values,
         )
         .into_inner_digest();
-    let root_ptr = root.ptr();
 
     // For each sibling in the proof, reconstruct the root.
-    let left: Ptr<C::N> = builder.uninit();
-    let right: Ptr<C::N> = builder.uninit();
     builder.range(0, proof.len()).for_each(|i, builder| {
         let sibling = builder.get_ptr(&proof, i);
         let bit = builder.get(&index_bits, i);
 
-        builder.if_eq(bit, C::N::ONE).then_or_else(
-            |builder| {
-                builder.assign(&left, sibling);
-                builder.assign(&right, root_ptr);
-            },
-            |builder| {
-                builder.assign(&left, root_ptr);
-                builder.assign(&right, sibling);
-            },
-        );
-
-        builder.poseidon2_compress_x(
-            &Array::Dyn(root_ptr, Usize::from(0)),
-            &Array::Dyn(left, Usize::from(0)),
-            &Array::Dyn(right, Usize::from(0)),
-        );
+        builder.poseidon2_merkle_step(&root, &Array::Dyn(sibling, Usize::from(0)), bit);
         builder.assign(
             &current_height,
             current_height.clone() * (C::N::TWO.inverse()),
@@ -14,7 +14,7 @@ use super::{
 };
 use crate::{
     challenger::ChallengerVariable, commit::PcsVariable, digest::DigestVariable,
-    fri::types::FriProofVariable,
+    fri::types::FriProofVariable, utils::assert_var_at_most,
 };
 
 /// Notes:
@@ -51,6 +51,11 @@ pub fn verify_two_adic_pcs<C: Config>(
 
     let log_global_max_height =
         builder.eval_expr(proof.commit_phase_commits.len() + RVar::from(log_blowup));
+    if !builder.flags.static_only {
+        // The number of folding rounds comes from the proof, so the height can vary between
+        // proofs verified by the same program.
+        assert_var_at_most(builder, log_global_max_height, C::F::TWO_ADICITY);
+    }
 
     let reduced_openings: Array<_, Array<_, Ext<_, _>>> = builder.array(proof.query_proofs.len());
 
@@ -0,0 +1,64 @@
+//! Export of an outer verifier program to a gnark circuit, as an alternative to the Halo2 static
+//! verifier for chains where a Groth16 proof is cheaper to verify than a Halo2 KZG proof.
+//!
+//! The [Constraint]s are the instructions of a circuit over the BN254 scalar field in which felts
+//! and extension elements are emulated BabyBear values, and the [GnarkWitness] holds the values of
+//! its witness instructions. Both are serializable to JSON for a gnark program that builds the
+//! circuit and proves it with Groth16.
+
+use openvm_native_compiler::{
+    constraints::{Constraint, ConstraintCompiler},
+    ir::{DslIr, TracedVec, Witness},
+};
+use openvm_stark_backend::p3_field::{AbstractExtensionField, PrimeField};
+use serde::{Deserialize, Serialize};
+
+use crate::config::outer::OuterConfig;
+
+/// An outer verifier program compiled to gnark constraints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GnarkCircuit {
+    pub constraints: Vec<Constraint>,
+    /// Number of public inputs, each set by a `Publish` constraint.
+    pub num_public_values: usize,
+}
+
+impl GnarkCircuit {
+    pub fn compile(operations: TracedVec<DslIr<OuterConfig>>, num_public_values: usize) -> Self {
+        Self {
+            constraints: ConstraintCompiler::<OuterConfig>::default().emit(operations),
+            num_public_values,
+        }
+    }
+}
+
+/// A [Witness] of the outer config with every value in decimal, as the immediates of the
+/// constraints.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GnarkWitness {
+    pub vars: Vec<String>,
+    pub felts: Vec<String>,
+    pub exts: Vec<Vec<String>>,
+    pub vkey_hash: String,
+    pub committed_values_digest: String,
+}
+
+impl From<&Witness<OuterConfig>> for GnarkWitness {
+    fn from(witness: &Witness<OuterConfig>) -> Self {
+        Self {
+            vars: witness.vars.iter().map(decimal).collect(),
+            felts: witness.felts.iter().map(decimal).collect(),
+            exts: witness
+                .exts
+                .iter()
+                .map(|x| x.as_base_slice().iter().map(decimal).collect())
+                .collect(),
+            vkey_hash: decimal(&witness.vkey_hash),
+            committed_values_digest: decimal(&witness.commited_values_digest),
+        }
+    }
+}
+
+fn decimal<F: PrimeField>(x: &F) -> String {
+    x.as_canonical_biguint().to_string()
+}
@@ -2,7 +2,7 @@ use std::io::Write;
 
 use openvm_native_compiler::{
     constraints::halo2::compiler::convert_fr,
-    ir::{Builder, Witness},
+    ir::{Builder, ExtConst, Var, Witness},
 };
 use openvm_stark_backend::p3_field::{
     reduce_32 as reduce_32_gt, split_32 as split_32_gt, AbstractField,
@@ -21,12 +21,13 @@ use snark_verifier_sdk::{
 };
 
 use crate::{
-    config::outer::OuterConfig,
+    config::outer::{OuterChallenge, OuterConfig},
     halo2::{
         utils::gen_kzg_params, wrapper::Halo2WrapperProvingKey, CircuitBuilderStage::Prover,
         DslOperations, Halo2Prover, Halo2ProvingMetadata, Halo2ProvingPinning,
     },
     utils::{reduce_32, split_32},
+    witness::Witnessable,
 };
 
 mod multi_field32;
@@ -115,6 +116,33 @@ fn test_num2bits_v() {
     );
 }
 
+#[test]
+fn test_select_ext_array() {
+    let values: Vec<OuterChallenge> = (0..5)
+        .map(|i| OuterChallenge::from_canonical_u32(i * i + 1))
+        .collect();
+
+    let mut builder = Builder::<OuterConfig>::default();
+    builder.flags.static_only = true;
+    let array = values.read(&mut builder);
+    for (i, value) in values.iter().enumerate() {
+        let index: Var<_> = builder.eval(Bn254Fr::from_canonical_usize(i));
+        let result = builder.select_index(&array, index);
+        builder.assert_ext_eq(result, value.cons());
+    }
+
+    let mut witness = Witness::default();
+    values.write(&mut witness);
+    Halo2Prover::mock::<OuterConfig>(
+        10,
+        DslOperations {
+            operations: builder.operations,
+            num_public_values: 0,
+        },
+        witness,
+    );
+}
+
 #[test]
 fn test_reduce_32() {
     let value_1 = BabyBear::from_canonical_u32(1345237507);
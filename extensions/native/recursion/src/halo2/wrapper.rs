@@ -11,7 +11,7 @@ use snark_verifier_sdk::{
         },
         halo2_proofs::{plonk::keygen_pk2, poly::commitment::Params},
     },
-    CircuitExt, Snark, SHPLONK,
+    CircuitExt, Snark, LIMBS, SHPLONK,
 };
 
 use crate::halo2::{
@@ -41,6 +41,10 @@ pub struct Halo2WrapperProvingKey {
 
 const MIN_ROWS: usize = 20;
 
+/// Number of instances of the wrapper circuit holding the KZG accumulator: the limbs of the
+/// coordinates of two G1 points. They come before the instances of the wrapped snark.
+pub const NUM_ACCUMULATOR_INSTANCES: usize = 4 * LIMBS;
+
 impl Halo2WrapperProvingKey {
     /// Auto select k to let Wrapper circuit only have 1 advice column.
     pub fn keygen_auto_tune(reader: &impl Halo2ParamsReader, dummy_snark: Snark) -> Self {
@@ -127,8 +131,7 @@ impl Halo2WrapperProvingKey {
         );
         assert_eq!(
             self.pinning.metadata.num_pvs[0],
-            // 12 is the number of public values for the accumulator
-            snark_to_verify.instances[0].len() + 12
+            snark_to_verify.instances[0].len() + NUM_ACCUMULATOR_INSTANCES
         );
         generate_wrapper_circuit_object(Prover, k, snark_to_verify)
             .use_params(
@@ -64,6 +64,30 @@ pub trait Hintable<C: Config> {
     }
 }
 
+/// Host-side writer for the hint stream of a recursion program.
+///
+/// Values must be written in the same order as the program reads them with [Hintable::read].
+/// Compile the program with `builder.flags.debug` set to detect mismatched reads at runtime.
+#[derive(Clone, Debug, Default)]
+pub struct WitnessStream<C: Config> {
+    stream: Vec<Vec<C::N>>,
+}
+
+impl<C: Config> WitnessStream<C> {
+    pub fn new() -> Self {
+        Self { stream: Vec::new() }
+    }
+
+    pub fn write<T: Hintable<C>>(mut self, value: &T) -> Self {
+        self.stream.extend(value.write());
+        self
+    }
+
+    pub fn into_inner(self) -> Vec<Vec<C::N>> {
+        self.stream
+    }
+}
+
 impl<C: Config> Hintable<C> for usize {
     type HintVariable = Var<C::N>;
 
@@ -98,7 +122,8 @@ impl Hintable<InnerConfig> for InnerChallenge {
     }
 
     fn write(&self) -> Vec<Vec<<InnerConfig as Config>::N>> {
-        vec![self.as_base_slice().to_vec()]
+        // `hint_ext` reads the number of extension elements before their base field elements.
+        vec![*self].write()
     }
 }
 
@@ -475,9 +500,12 @@ mod test {
         asm::AsmBuilder,
         ir::{Ext, Felt, Var},
     };
-    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_backend::p3_field::{AbstractExtensionField, AbstractField};
 
-    use crate::hints::{Hintable, InnerChallenge, InnerVal};
+    use crate::{
+        hints::{Hintable, InnerChallenge, InnerVal, WitnessStream},
+        types::InnerConfig,
+    };
 
     #[test]
     fn test_var_array() {
@@ -553,4 +581,47 @@ mod test {
         let program = builder.compile_isa();
         execute_program(program, stream);
     }
+
+    #[test]
+    fn test_ext() {
+        let x = InnerChallenge::from_base_slice(&[1, 2, 3, 4].map(InnerVal::from_canonical_u32));
+        let stream = InnerChallenge::write(&x);
+        // `hint_ext` reads the number of extension elements before their base field elements.
+        assert_eq!(
+            stream,
+            vec![
+                vec![InnerVal::ONE],
+                [1, 2, 3, 4].map(InnerVal::from_canonical_u32).to_vec(),
+            ]
+        );
+
+        let mut builder = AsmBuilder::<InnerVal, InnerChallenge>::default();
+        builder.flags.debug = true;
+        let actual = InnerChallenge::read(&mut builder);
+        let expected: Ext<InnerVal, InnerChallenge> = builder.constant(x);
+        builder.assert_ext_eq(actual, expected);
+        builder.halt();
+
+        let program = builder.compile_isa();
+        execute_program(program, stream);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_witness_stream_out_of_order() {
+        let stream = WitnessStream::<InnerConfig>::new()
+            .write(&vec![InnerVal::ONE, InnerVal::TWO])
+            .write(&InnerVal::ONE)
+            .into_inner();
+
+        // Reading a single felt where the host wrote a vector fails under debug flags.
+        let mut builder = AsmBuilder::<InnerVal, InnerChallenge>::default();
+        builder.flags.debug = true;
+        InnerVal::read(&mut builder);
+        Vec::<InnerVal>::read(&mut builder);
+        builder.halt();
+
+        let program = builder.compile_isa();
+        execute_program(program, stream);
+    }
 }
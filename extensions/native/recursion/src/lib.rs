@@ -4,6 +4,7 @@ pub mod config;
 pub mod digest;
 mod folder;
 pub mod fri;
+pub mod gnark;
 mod helper;
 pub mod hints;
 mod outer_poseidon2;
@@ -4,7 +4,7 @@ use itertools::Itertools;
 use openvm_circuit::arch::instructions::program::Program;
 use openvm_native_compiler::{
     conversion::CompilerOptions,
-    ir::{Array, Builder, Config, Ext, ExtConst, Felt, SymbolicExt, Usize},
+    ir::{Array, Builder, Config, Ext, ExtConst, Felt, SymbolicExt, Usize, Var},
     prelude::RVar,
 };
 use openvm_stark_backend::{
@@ -32,7 +32,7 @@ use crate::{
     },
     hints::Hintable,
     types::{InnerConfig, MultiStarkVerificationAdvice, StarkVerificationAdvice},
-    utils::const_fri_config,
+    utils::{assert_var_at_most, const_fri_config},
     vars::{
         AdjacentOpenedValuesVariable, AirProofDataVariable, CommitmentsVariable, StarkProofVariable,
     },
@@ -49,6 +49,12 @@ pub struct VerifierProgram<C: Config> {
 
 impl VerifierProgram<InnerConfig> {
     /// Create a new instance of the program for the [BabyBearPoseidon2] config.
+    ///
+    /// The program is specialized to the verifying key: `constants` (widths, quotient degrees,
+    /// symbolic constraints and preprocessed commitments of every AIR) are embedded as constants.
+    /// The shape of the proof (which AIRs are present, their heights and order) is still read from
+    /// the input, so the program can verify any proof of the verifying key. Only the static
+    /// verifier (`builder.flags.static_only`) additionally fixes the proof shape.
     pub fn build(
         constants: MultiStarkVerificationAdvice<InnerConfig>,
         fri_params: &FriParameters,
@@ -115,15 +121,42 @@ where
         } else {
             // Recycle stack space after verifying
             let mut tmp_builder = builder.create_sub_builder();
-            // Recycle heap space after verifying by resetting the heap pointer.
-            let old_heap_ptr = tmp_builder.load_heap_ptr();
-            let mut challenger = CH::new(&mut tmp_builder);
-            Self::verify_raps(&mut tmp_builder, pcs, m_advice, &mut challenger, proof);
-            tmp_builder.store_heap_ptr(old_heap_ptr);
+            // Recycle heap space after verifying.
+            tmp_builder.arena(|tmp_builder| {
+                let mut challenger = CH::new(tmp_builder);
+                Self::verify_raps(tmp_builder, pcs, m_advice, &mut challenger, proof);
+            });
             builder.operations.extend(tmp_builder.operations);
         }
     }
 
+    /// Verifies `proof` against `m_advices[index]`, so proofs of different STARKs can be verified
+    /// by the same program. Fails if `index` is out of range. The caller is responsible for
+    /// committing `index`.
+    ///
+    /// Every advice is verified in its own branch, so this is not supported by the static
+    /// verifier.
+    pub fn verify_with_selected_advice<CH: ChallengerVariable<C>>(
+        builder: &mut Builder<C>,
+        pcs: &TwoAdicFriPcsVariable<C>,
+        m_advices: &[MultiStarkVerificationAdvice<C>],
+        index: Var<C::N>,
+        proof: &StarkProofVariable<C>,
+    ) {
+        assert!(
+            !builder.flags.static_only,
+            "the static verifier cannot select the advice at runtime"
+        );
+        let is_verified: Var<C::N> = builder.eval(C::N::ZERO);
+        for (i, m_advice) in m_advices.iter().enumerate() {
+            builder.if_eq(index, RVar::from(i)).then(|builder| {
+                Self::verify::<CH>(builder, pcs, m_advice, proof);
+                builder.assign(&is_verified, C::N::ONE);
+            });
+        }
+        builder.assert_var_eq(is_verified, C::N::ONE);
+    }
+
     /// Reference: [openvm_stark_backend::verifier::MultiTraceStarkVerifier::verify_raps].
     pub fn verify_raps(
         builder: &mut Builder<C>,
@@ -147,6 +180,8 @@ where
         } = proof;
 
         let num_airs = RVar::from(air_proofs.len());
+        // Quotient degrees never exceed the blowup, so every domain fits in the two-adic subgroup.
+        let max_log_degree = C::F::TWO_ADICITY - pcs.config.log_blowup;
         let num_challenges_to_sample = m_advice_var.num_challenges_to_sample(builder);
         // Currently only support 0 or 1 phase is supported.
         let num_phases = RVar::from(num_challenges_to_sample.len());
@@ -214,7 +249,12 @@ where
             let log_degree = if builder.flags.static_only {
                 builder.eval(C::F::from_canonical_usize(air_proof.log_degree.value()))
             } else {
-                builder.unsafe_cast_var_to_felt(air_proof.log_degree.get_var())
+                // Log degrees are read from each proof, so proofs verified by the same program
+                // may have different trace heights. Bound them by the largest domain the PCS
+                // supports.
+                let log_degree = air_proof.log_degree.get_var();
+                assert_var_at_most(builder, log_degree, max_log_degree);
+                builder.unsafe_cast_var_to_felt(log_degree)
             };
             challenger.observe(builder, log_degree);
         });
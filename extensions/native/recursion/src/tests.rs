@@ -264,3 +264,46 @@ fn test_optional_air() {
         assert!(unwind_res.is_err());
     }
 }
+
+#[test]
+fn test_gnark_circuit() {
+    use openvm_native_compiler::{
+        constraints::opcodes::ConstraintOpcode,
+        ir::{Builder, Felt, Var, Witness},
+    };
+    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_sdk::{p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr};
+
+    use crate::{
+        config::outer::OuterConfig,
+        gnark::{GnarkCircuit, GnarkWitness},
+    };
+
+    let mut builder = Builder::<OuterConfig>::default();
+    builder.flags.static_only = true;
+    builder.cycle_tracker_start("test");
+    let a: Var<_> = builder.witness_var();
+    let x: Felt<_> = builder.witness_felt();
+    let y: Felt<_> = builder.eval(x * BabyBear::from_canonical_u32(3));
+    let y = builder.cast_felt_to_var(y);
+    let z: Var<_> = builder.eval(Bn254Fr::from_canonical_u32(7) - a);
+    builder.static_commit_public_value(0, y);
+    builder.static_commit_public_value(1, z);
+    builder.cycle_tracker_end("test");
+
+    let circuit = GnarkCircuit::compile(builder.operations, 2);
+    let count = |f: fn(&ConstraintOpcode) -> bool| {
+        circuit.constraints.iter().filter(|c| f(&c.opcode)).count()
+    };
+    assert_eq!(count(|op| matches!(op, ConstraintOpcode::CastFV)), 1);
+    assert_eq!(count(|op| matches!(op, ConstraintOpcode::Publish)), 2);
+
+    let witness = Witness::<OuterConfig> {
+        vars: vec![Bn254Fr::from_canonical_u32(5)],
+        felts: vec![BabyBear::from_canonical_u32(2)],
+        ..Default::default()
+    };
+    let witness = GnarkWitness::from(&witness);
+    assert_eq!(witness.vars, ["5"]);
+    assert_eq!(witness.felts, ["2"]);
+}
@@ -1,4 +1,6 @@
-use openvm_native_compiler::ir::{Builder, CanSelect, Config, Felt, MemVariable, Var};
+use openvm_native_compiler::ir::{
+    Builder, CanSelect, Config, Felt, MemVariable, RVar, SymbolicVar, Var,
+};
 use openvm_stark_backend::{
     p3_commit::TwoAdicMultiplicativeCoset,
     p3_field::{AbstractField, TwoAdicField},
@@ -37,6 +39,20 @@ pub fn const_fri_config<C: Config>(
     }
 }
 
+/// Asserts `0 <= value <= max` in the dynamic verifier. Meant for small bounds like log degrees,
+/// since both `value` and `max - value` are decomposed into bits.
+pub fn assert_var_at_most<C: Config>(
+    builder: &mut Builder<C>,
+    value: impl Into<SymbolicVar<C::N>>,
+    max: usize,
+) {
+    let num_bits = usize::BITS - max.leading_zeros();
+    let value: Var<C::N> = builder.eval(value.into());
+    let slack: Var<C::N> = builder.eval(RVar::from(max) - value);
+    builder.num2bits_v(value, num_bits);
+    builder.num2bits_v(slack, num_bits);
+}
+
 /// Reference: https://github.com/Plonky3/Plonky3/blob/622375885320ac6bf3c338001760ed8f2230e3cb/field/src/helpers.rs#L136
 pub fn reduce_32<C: Config>(builder: &mut Builder<C>, vals: &[Felt<C::F>]) -> Var<C::N> {
     let mut power = C::N::ONE;
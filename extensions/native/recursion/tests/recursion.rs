@@ -1,15 +1,21 @@
 use openvm_circuit::arch::{instructions::program::Program, SystemConfig, VmExecutor};
 use openvm_native_circuit::{Native, NativeConfig};
-use openvm_native_compiler::{asm::AsmBuilder, ir::Felt};
-use openvm_native_recursion::testing_utils::inner::run_recursive_test;
+use openvm_native_compiler::{asm::AsmBuilder, conversion::CompilerOptions, ir::Felt};
+use openvm_native_recursion::testing_utils::inner::{
+    build_verification_program, run_recursive_test,
+};
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
     p3_field::{extension::BinomialExtensionField, AbstractField},
 };
 use openvm_stark_sdk::{
-    config::fri_params::standard_fri_params_with_100_bits_conjectured_security,
-    engine::ProofInputForTest, p3_baby_bear::BabyBear,
+    config::{
+        baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+        fri_params::standard_fri_params_with_100_bits_conjectured_security,
+    },
+    engine::{ProofInputForTest, StarkFriEngine},
+    p3_baby_bear::BabyBear,
 };
 
 fn fibonacci_program(a: u32, b: u32, n: u32) -> Program<BabyBear> {
@@ -47,7 +53,10 @@ where
     Domain<SC>: PolynomialSpace<Val = BabyBear>,
 {
     let fib_program = fibonacci_program(a, b, n);
-    let vm_config = NativeConfig::new(SystemConfig::default().with_public_values(3), Native);
+    let vm_config = NativeConfig::new(
+        SystemConfig::default().with_public_values(3),
+        Native::default(),
+    );
 
     let executor = VmExecutor::<BabyBear, NativeConfig>::new(vm_config);
 
@@ -68,6 +77,24 @@ fn test_fibonacci_program_verify() {
     );
 }
 
+#[test]
+fn test_verifier_program_with_different_trace_heights() {
+    let run_test_fast = |n| {
+        <BabyBearPoseidon2Engine as StarkFriEngine<BabyBearPoseidon2Config>>::run_test_fast(
+            fibonacci_program_test_proof_input(0, 1, n).per_air,
+        )
+        .unwrap()
+    };
+    // The verifier program is specialized to the vk, which doesn't depend on trace heights.
+    let (program, _) = build_verification_program(run_test_fast(32), CompilerOptions::default());
+    let (_, input_stream) =
+        build_verification_program(run_test_fast(1 << 10), CompilerOptions::default());
+
+    VmExecutor::<BabyBear, NativeConfig>::new(NativeConfig::aggregation(4, 7))
+        .execute(program, input_stream)
+        .unwrap();
+}
+
 #[cfg(feature = "static-verifier")]
 #[test]
 #[ignore = "slow"]
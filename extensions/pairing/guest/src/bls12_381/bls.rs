@@ -0,0 +1,199 @@
+//! BLS signatures over BLS12-381 in the minimal-pubkey-size variant: public keys are in G1 and
+//! signatures and message points are in G2, as in Ethereum consensus.
+//!
+//! Verification uses the pairing check of [`Bls12_381`], so it runs on the pairing intrinsics
+//! inside the VM. Messages are passed as points in G2; [`hash_to_field_fp2`] implements the
+//! hash-to-field step of the RFC 9380 hash-to-curve suite, while the simplified SWU map, the
+//! 3-isogeny and cofactor clearing are left to the caller.
+
+use alloc::vec::Vec;
+
+use hex_literal::hex;
+use openvm_algebra_guest::{DivUnsafe, Field, IntMod, Reduce};
+use openvm_ecc_guest::{weierstrass::IntrinsicCurve, AffinePoint, CyclicGroup, Group};
+
+use super::{Bls12_381, Fp, Fp2, G1Affine, Scalar};
+use crate::pairing::{PairingCheck, PairingCheckError};
+
+/// Order of the prime order subgroups G1 and G2, big endian.
+const SUBGROUP_ORDER_BE: [u8; 32] =
+    hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+
+/// The `b` coefficient of the twist `y^2 = x^3 + 4(1 + u)` that contains G2.
+const G2_CURVE_B: Fp2 = Fp2::new(Fp::from_const_u8(4), Fp::from_const_u8(4));
+
+/// Returns whether `p` is a point of G1 other than the identity. Points from [`G1Affine`] are on
+/// the curve but may lie outside the prime order subgroup, so public keys must pass this check
+/// before they are used.
+pub fn g1_subgroup_check(p: &G1Affine) -> bool {
+    if p.is_identity() {
+        return false;
+    }
+    // p is in the subgroup exactly when [r]p = 0, that is [r - 1]p = -p.
+    let r_minus_one = <Scalar as IntMod>::ZERO - <Scalar as IntMod>::ONE;
+    Bls12_381::msm(&[r_minus_one], &[p.clone()]) == -p.clone()
+}
+
+/// Returns whether `q` is on the twist and in G2, and is not the identity.
+///
+/// The check multiplies by the subgroup order with affine arithmetic over `Fp2`, which costs one
+/// `Fp2` inversion per step.
+pub fn g2_subgroup_check(q: &AffinePoint<Fp2>) -> bool {
+    if q.is_infinity() || !g2_is_on_curve(q) {
+        return false;
+    }
+    let mut acc = AffinePoint::new(<Fp2 as Field>::ZERO, <Fp2 as Field>::ZERO);
+    for byte in SUBGROUP_ORDER_BE {
+        for bit in (0..8).rev() {
+            acc = g2_add(&acc, &acc);
+            if (byte >> bit) & 1 == 1 {
+                acc = g2_add(&acc, q);
+            }
+        }
+    }
+    acc.is_infinity()
+}
+
+/// Verifies a signature on a single message point.
+pub fn verify(
+    pk: &G1Affine,
+    msg_point: &AffinePoint<Fp2>,
+    sig: &AffinePoint<Fp2>,
+) -> Result<(), PairingCheckError> {
+    aggregate_verify(
+        core::slice::from_ref(pk),
+        core::slice::from_ref(msg_point),
+        sig,
+    )
+}
+
+/// Verifies an aggregate signature on distinct messages, one per public key:
+/// `e(g1, sig) = prod_i e(pk_i, msg_i)`. Public keys and the signature are subgroup checked.
+pub fn aggregate_verify(
+    pks: &[G1Affine],
+    msg_points: &[AffinePoint<Fp2>],
+    sig: &AffinePoint<Fp2>,
+) -> Result<(), PairingCheckError> {
+    if pks.is_empty()
+        || pks.len() != msg_points.len()
+        || !pks.iter().all(g1_subgroup_check)
+        || !g2_subgroup_check(sig)
+    {
+        return Err(PairingCheckError);
+    }
+    let mut p: Vec<AffinePoint<Fp>> = pks
+        .iter()
+        .map(|pk| AffinePoint::new(pk.x.clone(), pk.y.clone()))
+        .collect();
+    let neg_g1 = G1Affine::NEG_GENERATOR;
+    p.push(AffinePoint::new(neg_g1.x, neg_g1.y));
+    let mut q = msg_points.to_vec();
+    q.push(sig.clone());
+    Bls12_381::pairing_check(&p, &q)
+}
+
+/// Verifies an aggregate signature of several public keys on the same message. The public keys
+/// are summed first, so only two pairings are computed.
+pub fn fast_aggregate_verify(
+    pks: &[G1Affine],
+    msg_point: &AffinePoint<Fp2>,
+    sig: &AffinePoint<Fp2>,
+) -> Result<(), PairingCheckError> {
+    if pks.is_empty() || !pks.iter().all(g1_subgroup_check) {
+        return Err(PairingCheckError);
+    }
+    let aggregate_pk = pks.iter().fold(G1Affine::IDENTITY, |acc, pk| acc + pk);
+    if aggregate_pk.is_identity() {
+        return Err(PairingCheckError);
+    }
+    aggregate_verify(&[aggregate_pk], core::slice::from_ref(msg_point), sig)
+}
+
+/// `expand_message_xmd` from RFC 9380, section 5.3.1, for a hash with 32-byte output and
+/// 64-byte blocks such as SHA-256. `hash` is called with the parts of each hash input in order.
+///
+/// Panics if `dst` is longer than 255 bytes or `len_in_bytes` is above 8160.
+pub fn expand_message_xmd(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+    hash: impl Fn(&[&[u8]]) -> [u8; 32],
+) -> Vec<u8> {
+    let ell = len_in_bytes.div_ceil(32);
+    assert!(
+        ell <= 255,
+        "requested {len_in_bytes} bytes, at most 8160 are supported"
+    );
+    assert!(
+        dst.len() <= 255,
+        "domain separation tag is longer than 255 bytes"
+    );
+    let dst_len = [dst.len() as u8];
+    let dst_prime: [&[u8]; 2] = [dst, &dst_len];
+
+    let b_0 = hash(&[
+        &[0u8; 64],
+        msg,
+        &(len_in_bytes as u16).to_be_bytes(),
+        &[0],
+        dst_prime[0],
+        dst_prime[1],
+    ]);
+    let mut b_i = hash(&[&b_0, &[1], dst_prime[0], dst_prime[1]]);
+    let mut uniform = Vec::with_capacity(ell * 32);
+    uniform.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut xored = b_0;
+        for (x, b) in xored.iter_mut().zip(b_i) {
+            *x ^= b;
+        }
+        b_i = hash(&[&xored, &[i as u8], dst_prime[0], dst_prime[1]]);
+        uniform.extend_from_slice(&b_i);
+    }
+    uniform.truncate(len_in_bytes);
+    uniform
+}
+
+/// `hash_to_field` from RFC 9380, section 5.2, for `Fp2` with `L = 64` and `expand_message_xmd`
+/// over `hash`. Returns the two field elements used by `hash_to_curve` for G2.
+pub fn hash_to_field_fp2(msg: &[u8], dst: &[u8], hash: impl Fn(&[&[u8]]) -> [u8; 32]) -> [Fp2; 2] {
+    const L: usize = 64;
+    let uniform = expand_message_xmd(msg, dst, 2 * 2 * L, hash);
+    let mut elements = uniform
+        .chunks_exact(L)
+        .map(Fp::reduce_be_bytes)
+        .collect::<Vec<_>>()
+        .into_iter();
+    core::array::from_fn(|_| {
+        let c0 = elements.next().unwrap();
+        let c1 = elements.next().unwrap();
+        Fp2::new(c0, c1)
+    })
+}
+
+fn g2_is_on_curve(q: &AffinePoint<Fp2>) -> bool {
+    let x_cubed = q.x.clone() * &q.x * &q.x;
+    q.y.clone() * &q.y == x_cubed + &G2_CURVE_B
+}
+
+/// Affine addition on the twist, with `(0, 0)` as the identity.
+fn g2_add(p: &AffinePoint<Fp2>, q: &AffinePoint<Fp2>) -> AffinePoint<Fp2> {
+    if p.is_infinity() {
+        return q.clone();
+    }
+    if q.is_infinity() {
+        return p.clone();
+    }
+    let lambda = if p.x == q.x {
+        if p.y != q.y || p.y == <Fp2 as Field>::ZERO {
+            return AffinePoint::new(<Fp2 as Field>::ZERO, <Fp2 as Field>::ZERO);
+        }
+        let x_squared = p.x.clone() * &p.x;
+        (x_squared.clone() + &x_squared + &x_squared).div_unsafe(&(p.y.clone() + &p.y))
+    } else {
+        (q.y.clone() - &p.y).div_unsafe(&(q.x.clone() - &p.x))
+    };
+    let x = lambda.clone() * &lambda - &p.x - &q.x;
+    let y = lambda * &(p.x.clone() - &x) - &p.y;
+    AffinePoint::new(x, y)
+}
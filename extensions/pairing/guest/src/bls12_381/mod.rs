@@ -8,6 +8,8 @@ mod fp12;
 mod fp2;
 mod pairing;
 
+pub mod bls;
+
 pub use fp12::*;
 pub use fp2::*;
 use hex_literal::hex;
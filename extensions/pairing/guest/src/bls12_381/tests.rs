@@ -8,7 +8,7 @@ use openvm_algebra_guest::{field::FieldExtension, IntMod};
 use openvm_ecc_guest::AffinePoint;
 use rand::{rngs::StdRng, SeedableRng};
 
-use super::{Fp, Fp12, Fp2};
+use super::{bls, Fp, Fp12, Fp2};
 use crate::{
     bls12_381::Bls12_381,
     pairing::{
@@ -269,3 +269,20 @@ fn test_bls12381_miller_loop_identity_2() {
     let compare_final = compare_miller.final_exponentiation();
     assert_eq!(final_f, compare_final);
 }
+
+#[test]
+fn test_bls12381_g2_subgroup_check() {
+    let mut rng = StdRng::seed_from_u64(12);
+    let h2c_q = G2Affine::random(&mut rng);
+    let q = AffinePoint {
+        x: convert_bls12381_halo2_fq2_to_fp2(h2c_q.x),
+        y: convert_bls12381_halo2_fq2_to_fp2(h2c_q.y),
+    };
+    assert!(bls::g2_subgroup_check(&q));
+
+    let off_curve = AffinePoint {
+        x: q.x.clone(),
+        y: q.y.clone() + &<Fp2 as openvm_algebra_guest::Field>::ONE,
+    };
+    assert!(!bls::g2_subgroup_check(&off_curve));
+}
@@ -29,8 +29,8 @@ use openvm_stark_backend::{
 };
 
 use super::{
-    vec_heap_generate_trace_row_impl, Rv32VecHeapAdapterAir, Rv32VecHeapAdapterCols,
-    Rv32VecHeapReadRecord, Rv32VecHeapWriteRecord,
+    is_in_place, vec_heap_generate_trace_row_impl, vec_heap_read_inputs, Rv32VecHeapAdapterAir,
+    Rv32VecHeapAdapterCols, Rv32VecHeapReadRecord, Rv32VecHeapWriteRecord,
 };
 
 /// This adapter reads from NUM_READS <= 2 pointers and writes to 1 pointer.
@@ -38,6 +38,8 @@ use super::{
 ///   are read from registers (address space 1).
 /// * Reads are from the addresses in `rs[0]` (and `rs[1]` if `R = 2`).
 /// * Writes are to the address in `rd`.
+/// * If `READ_SIZE == WRITE_SIZE` and `rd` and `rs[0]` are the same register, the first read is
+///   replaced by the previous data of the write, as in [Rv32VecHeapAdapterChip](crate::Rv32VecHeapAdapterChip).
 
 #[derive(Clone, Copy, Debug, derive_new::new)]
 pub struct Rv32HeapAdapterAir<
@@ -172,16 +174,18 @@ impl<F: PrimeField32, const NUM_READS: usize, const READ_SIZE: usize, const WRIT
         });
         let (rd_record, rd_val) = read_rv32_register(memory, d, a);
 
-        let read_records = rs_vals.map(|address| {
+        for address in rs_vals {
             debug_assert!(address < (1 << self.air.address_bits));
-            [memory.read::<READ_SIZE>(e, F::from_canonical_u32(address))]
-        });
+        }
+        let in_place = is_in_place::<F, NUM_READS, 1, 1, READ_SIZE, WRITE_SIZE>(instruction);
+        let read_records = vec_heap_read_inputs(memory, e, rs_vals, in_place);
         let read_data = read_records.map(|r| r[0].data);
 
         let record = Rv32VecHeapReadRecord {
             rs: rs_records,
             rd: rd_record,
             rd_val: F::from_canonical_u32(rd_val),
+            in_place,
             reads: read_records,
         };
 
@@ -211,7 +215,11 @@ impl<F: PrimeField32, const NUM_READS: usize, const READ_SIZE: usize, const WRIT
                 pc: from_state.pc + 4,
                 timestamp: memory.timestamp(),
             },
-            Self::WriteRecord { from_state, writes },
+            Self::WriteRecord {
+                from_state,
+                writes,
+                setup_flag: None,
+            },
         ))
     }
 
@@ -1,7 +1,7 @@
 use openvm_circuit::arch::testing::{memory::gen_pointer, VmChipTestBuilder};
 use openvm_instructions::{instruction::Instruction, VmOpcode};
 use openvm_rv32im_circuit::adapters::{RV32_REGISTER_NUM_LIMBS, RV_IS_TYPE_IMM_BITS};
-use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
 use rand::{rngs::StdRng, Rng};
 
@@ -45,6 +45,44 @@ pub fn rv32_write_heap_default<const NUM_LIMBS: usize>(
     )
 }
 
+/// Like [rv32_write_heap_default] for three inputs, with the third pointer register in operand
+/// `f` as expected by the three reads [Rv32VecHeapAdapterChip](crate::Rv32VecHeapAdapterChip).
+pub fn rv32_write_heap_three_reads_default<const NUM_LIMBS: usize>(
+    tester: &mut VmChipTestBuilder<BabyBear>,
+    addr1_writes: Vec<[BabyBear; NUM_LIMBS]>,
+    addr2_writes: Vec<[BabyBear; NUM_LIMBS]>,
+    addr3_writes: Vec<[BabyBear; NUM_LIMBS]>,
+    opcode_with_offset: usize,
+) -> Instruction<BabyBear> {
+    let (reg1, _) =
+        tester.write_heap_default::<NUM_LIMBS>(RV32_REGISTER_NUM_LIMBS, 128, addr1_writes);
+    let (reg2, _) =
+        tester.write_heap_default::<NUM_LIMBS>(RV32_REGISTER_NUM_LIMBS, 128, addr2_writes);
+    let (reg3, _) =
+        tester.write_heap_default::<NUM_LIMBS>(RV32_REGISTER_NUM_LIMBS, 128, addr3_writes);
+    let (rd, _) = tester.write_heap_pointer_default(RV32_REGISTER_NUM_LIMBS, 128);
+
+    Instruction::from_usize(
+        VmOpcode::from_usize(opcode_with_offset),
+        [rd, reg1, reg2, 1, 2, reg3],
+    )
+}
+
+/// Reads the `num_blocks` output blocks of a heap `instruction` made by [rv32_write_heap_default],
+/// after it was executed. The output pointer is in the register `a`.
+pub fn rv32_read_heap_default<const NUM_LIMBS: usize>(
+    tester: &mut VmChipTestBuilder<BabyBear>,
+    instruction: &Instruction<BabyBear>,
+    num_blocks: usize,
+) -> Vec<[BabyBear; NUM_LIMBS]> {
+    let ptr_bytes =
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, instruction.a.as_canonical_u32() as usize);
+    let ptr = u32::from_le_bytes(ptr_bytes.map(|x| x.as_canonical_u32() as u8)) as usize;
+    (0..num_blocks)
+        .map(|i| tester.read::<NUM_LIMBS>(2, ptr + i * NUM_LIMBS))
+        .collect()
+}
+
 pub fn rv32_write_heap_default_with_increment<const NUM_LIMBS: usize>(
     tester: &mut VmChipTestBuilder<BabyBear>,
     addr1_writes: Vec<[BabyBear; NUM_LIMBS]>,
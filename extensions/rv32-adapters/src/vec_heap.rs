@@ -10,8 +10,9 @@ use std::{
 use itertools::izip;
 use openvm_circuit::{
     arch::{
-        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
-        Result, VecHeapAdapterInterface, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
+        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionError,
+        ExecutionState, Result, VecHeapAdapterInterface, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
     },
     system::{
         memory::{
@@ -39,14 +40,53 @@ use openvm_stark_backend::{
     p3_field::{AbstractField, Field, PrimeField32},
 };
 
-/// This adapter reads from R (R <= 2) pointers and writes to 1 pointer.
+/// The maximum number of heap pointers read by [Rv32VecHeapAdapterChip].
+pub const MAX_VEC_HEAP_READS: usize = 3;
+
+/// The instruction operands holding the register pointers `rs[0..R]`: `b`, `c` and then `f`,
+/// since `d` and `e` are the register and heap address spaces.
+pub const RS_PTR_OPERANDS: [usize; MAX_VEC_HEAP_READS] = [1, 2, 5];
+
+/// The first pointer of the setup flags of [SetupGuard] in the register address space. The
+/// registers `x0..x31` are below it, so no RV32 instruction writes the flags.
+pub const SETUP_FLAG_PTR_OFFSET: u32 = 1 << 16;
+
+/// Requires the setup opcode of a chip to be executed before its other opcodes.
+///
+/// Every instruction of the chip writes `1` to a setup flag in the register address space, and
+/// every instruction other than the setup constrains the previous value of the flag to be `1`.
+/// Since the flag is in memory, a setup in an earlier segment still counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetupGuard {
+    /// The global setup opcode of the chip.
+    pub setup_opcode: usize,
+    /// The pointer of the setup flag in the register address space.
+    pub flag_ptr: u32,
+}
+
+impl SetupGuard {
+    /// The flag pointer is derived from the global setup opcode, which is unique per chip.
+    pub fn new(setup_opcode: usize) -> Self {
+        Self {
+            setup_opcode,
+            flag_ptr: SETUP_FLAG_PTR_OFFSET + (setup_opcode * RV32_REGISTER_NUM_LIMBS) as u32,
+        }
+    }
+}
+
+/// This adapter reads from R (R <= 3) pointers and writes to 1 pointer.
 /// * The data is read from the heap (address space 2), and the pointers
 ///   are read from registers (address space 1).
 /// * Reads take the form of `BLOCKS_PER_READ` consecutive reads of size
 ///   `READ_SIZE` from the heap, starting from the addresses in `rs[0]`
-///   (and `rs[1]` if `R = 2`).
+///   (and `rs[1]` if `R >= 2`, `rs[2]` if `R = 3`).
 /// * Writes take the form of `BLOCKS_PER_WRITE` consecutive writes of
 ///   size `WRITE_SIZE` to the heap, starting from the address in `rd`.
+/// * The register pointers are the instruction operands: `rd` is `a`, and
+///   `rs[0]`, `rs[1]`, `rs[2]` are `b`, `c`, `f` (see [RS_PTR_OPERANDS]).
+/// * If `rd` and `rs[0]` are the same register and the read and write layouts
+///   match, the instruction is executed in place: the first input is the
+///   previous data of the write, without a separate read of the heap.
 #[derive(Debug, Clone)]
 pub struct Rv32VecHeapAdapterChip<
     F: Field,
@@ -78,7 +118,7 @@ impl<
         memory_controller: MemoryControllerRef<F>,
         bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
     ) -> Self {
-        assert!(NUM_READS <= 2);
+        assert!(NUM_READS <= MAX_VEC_HEAP_READS);
         let memory_controller = RefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         let address_bits = memory_controller.mem_config().pointer_max_bits;
@@ -97,6 +137,18 @@ impl<
             _marker: PhantomData,
         }
     }
+
+    /// Enables a [SetupGuard] for the chip with the global `setup_opcode`.
+    pub fn with_setup_guard(mut self, setup_opcode: usize) -> Self {
+        let guard = SetupGuard::new(setup_opcode);
+        assert!(
+            (guard.flag_ptr as usize) < (1 << self.air.address_bits),
+            "setup flag pointer {} is out of bounds",
+            guard.flag_ptr
+        );
+        self.air.setup_guard = Some(guard);
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -113,6 +165,9 @@ pub struct Rv32VecHeapReadRecord<
 
     pub rd_val: F,
 
+    /// Whether the first input is the previous data of the write, see [is_in_place].
+    pub in_place: bool,
+    /// The records of the first input are placeholders if `in_place`.
     pub reads: [[MemoryReadRecord<F, READ_SIZE>; BLOCKS_PER_READ]; NUM_READS],
 }
 
@@ -122,6 +177,10 @@ pub struct Rv32VecHeapWriteRecord<F: Field, const BLOCKS_PER_WRITE: usize, const
     pub from_state: ExecutionState<u32>,
 
     pub writes: [MemoryWriteRecord<F, WRITE_SIZE>; BLOCKS_PER_WRITE],
+
+    /// Whether the instruction is the setup and the write of the setup flag, if the chip has a
+    /// [SetupGuard].
+    pub setup_flag: Option<(bool, MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>)>,
 }
 
 #[repr(C)]
@@ -138,6 +197,9 @@ pub struct Rv32VecHeapAdapterCols<
 
     pub rs_ptr: [T; NUM_READS],
     pub rd_ptr: T,
+    /// Whether `rs[0] == rd`, in which case the first input is the previous data of the write
+    /// instead of a separate heap read.
+    pub is_in_place: T,
 
     pub rs_val: [[T; RV32_REGISTER_NUM_LIMBS]; NUM_READS],
     pub rd_val: [T; RV32_REGISTER_NUM_LIMBS],
@@ -149,6 +211,14 @@ pub struct Rv32VecHeapAdapterCols<
     pub writes_aux: [MemoryWriteAuxCols<T, WRITE_SIZE>; BLOCKS_PER_WRITE],
 }
 
+/// The columns of a [SetupGuard], after the [Rv32VecHeapAdapterCols].
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct SetupGuardCols<T> {
+    pub is_setup: T,
+    pub flag_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug, derive_new::new)]
 pub struct Rv32VecHeapAdapterAir<
@@ -163,6 +233,8 @@ pub struct Rv32VecHeapAdapterAir<
     pub bus: BitwiseOperationLookupBus,
     /// The max number of bits for an address in memory
     address_bits: usize,
+    #[new(default)]
+    pub setup_guard: Option<SetupGuard>,
 }
 
 impl<
@@ -184,6 +256,7 @@ impl<
             READ_SIZE,
             WRITE_SIZE,
         >::width()
+            + self.setup_guard.map_or(0, |_| SetupGuardCols::<F>::width())
     }
 }
 
@@ -212,6 +285,14 @@ impl<
         local: &[AB::Var],
         ctx: AdapterAirContext<AB::Expr, Self::Interface>,
     ) {
+        let (local, local_guard) = local.split_at(Rv32VecHeapAdapterCols::<
+            AB::Var,
+            NUM_READS,
+            BLOCKS_PER_READ,
+            BLOCKS_PER_WRITE,
+            READ_SIZE,
+            WRITE_SIZE,
+        >::width());
         let cols: &Rv32VecHeapAdapterCols<
             _,
             NUM_READS,
@@ -243,6 +324,33 @@ impl<
                 .eval(builder, ctx.instruction.is_valid.clone());
         }
 
+        let is_valid = ctx.instruction.is_valid.clone();
+        match (ctx.reads.first(), cols.rs_ptr.first()) {
+            (Some(first_reads), Some(&rs1_ptr))
+                if in_place_supported::<
+                    NUM_READS,
+                    BLOCKS_PER_READ,
+                    BLOCKS_PER_WRITE,
+                    READ_SIZE,
+                    WRITE_SIZE,
+                >() =>
+            {
+                builder.assert_bool(cols.is_in_place);
+                builder.when(cols.is_in_place).assert_one(is_valid.clone());
+                builder
+                    .when(cols.is_in_place)
+                    .assert_eq(rs1_ptr, cols.rd_ptr);
+                // The first input is the data overwritten by the output. The memory argument
+                // constrains the previous data of a write like the data of a read.
+                for (read, aux) in zip(first_reads, &cols.writes_aux) {
+                    for (x, &prev) in zip(read, &aux.prev_data) {
+                        builder.when(cols.is_in_place).assert_eq(x.clone(), prev);
+                    }
+                }
+            }
+            _ => builder.assert_zero(cols.is_in_place),
+        }
+
         // We constrain the highest limbs of heap pointers to be less than 2^(addr_bits - (RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1))).
         // This ensures that no overflow occurs when computing memory pointers. Since the number of cells accessed with each address
         // will be small enough, and combined with the memory argument, it ensures that all the cells accessed in the memory are less than 2^addr_bits.
@@ -272,8 +380,15 @@ impl<
         let rs_val_f: [AB::Expr; NUM_READS] = cols.rs_val.map(abstract_compose);
 
         let e = AB::F::from_canonical_u32(RV32_MEMORY_AS);
-        // Reads from heap
-        for (address, reads, reads_aux) in izip!(rs_val_f, ctx.reads, &cols.reads_aux,) {
+        // Reads from heap. The timestamps of the first input are skipped when in place.
+        for (j, (address, reads, reads_aux)) in
+            izip!(rs_val_f, ctx.reads, &cols.reads_aux).enumerate()
+        {
+            let enabled = if j == 0 {
+                is_valid.clone() - cols.is_in_place
+            } else {
+                is_valid.clone()
+            };
             for (i, (read, aux)) in zip(reads, reads_aux).enumerate() {
                 self.memory_bridge
                     .read(
@@ -285,7 +400,7 @@ impl<
                         timestamp_pp(),
                         aux,
                     )
-                    .eval(builder, ctx.instruction.is_valid.clone());
+                    .eval(builder, enabled.clone());
             }
         }
 
@@ -304,22 +419,52 @@ impl<
                 .eval(builder, ctx.instruction.is_valid.clone());
         }
 
+        if let Some(guard) = self.setup_guard {
+            let guard_cols: &SetupGuardCols<AB::Var> = local_guard.borrow();
+            builder.assert_bool(guard_cols.is_setup);
+            builder.when(guard_cols.is_setup).assert_eq(
+                ctx.instruction.opcode.clone(),
+                AB::F::from_canonical_usize(guard.setup_opcode),
+            );
+            // Only the setup may find the flag unset.
+            builder
+                .when(ctx.instruction.is_valid.clone() - guard_cols.is_setup)
+                .assert_one(guard_cols.flag_aux.prev_data[0]);
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                        AB::F::from_canonical_u32(guard.flag_ptr),
+                    ),
+                    from_fn(|i| {
+                        if i == 0 {
+                            AB::Expr::ONE
+                        } else {
+                            AB::Expr::ZERO
+                        }
+                    }),
+                    timestamp_pp(),
+                    &guard_cols.flag_aux,
+                )
+                .eval(builder, ctx.instruction.is_valid.clone());
+        }
+
+        // Operands a, b, c, d, e, f; the unused rs pointers are zero.
+        let mut operands = [
+            cols.rd_ptr.into(),
+            AB::Expr::ZERO,
+            AB::Expr::ZERO,
+            AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+            e.into(),
+            AB::Expr::ZERO,
+        ];
+        for (&ptr, operand) in zip(&cols.rs_ptr, RS_PTR_OPERANDS) {
+            operands[operand] = ptr.into();
+        }
         self.execution_bridge
             .execute_and_increment_or_set_pc(
                 ctx.instruction.opcode,
-                [
-                    cols.rd_ptr.into(),
-                    cols.rs_ptr
-                        .first()
-                        .map(|&x| x.into())
-                        .unwrap_or(AB::Expr::ZERO),
-                    cols.rs_ptr
-                        .get(1)
-                        .map(|&x| x.into())
-                        .unwrap_or(AB::Expr::ZERO),
-                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
-                    e.into(),
-                ],
+                operands,
                 cols.from_state,
                 AB::F::from_canonical_usize(timestamp_delta),
                 (4, ctx.to_pc),
@@ -335,7 +480,15 @@ impl<
             BLOCKS_PER_WRITE,
             READ_SIZE,
             WRITE_SIZE,
-        > = local.borrow();
+        > = local[..Rv32VecHeapAdapterCols::<
+            AB::Var,
+            NUM_READS,
+            BLOCKS_PER_READ,
+            BLOCKS_PER_WRITE,
+            READ_SIZE,
+            WRITE_SIZE,
+        >::width()]
+            .borrow();
         cols.from_state.pc
     }
 }
@@ -378,7 +531,10 @@ impl<
         <Self::Interface as VmAdapterInterface<F>>::Reads,
         Self::ReadRecord,
     )> {
-        let Instruction { a, b, c, d, e, .. } = *instruction;
+        let Instruction {
+            a, b, c, d, e, f, ..
+        } = *instruction;
+        let operands = [a, b, c, d, e, f];
 
         debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
         debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
@@ -386,22 +542,23 @@ impl<
         // Read register values
         let mut rs_vals = [0; NUM_READS];
         let rs_records: [_; NUM_READS] = from_fn(|i| {
-            let addr = if i == 0 { b } else { c };
-            let (record, val) = read_rv32_register(memory, d, addr);
+            let (record, val) = read_rv32_register(memory, d, operands[RS_PTR_OPERANDS[i]]);
             rs_vals[i] = val;
             record
         });
         let (rd_record, rd_val) = read_rv32_register(memory, d, a);
 
         // Read memory values
-        let read_records = rs_vals.map(|address| {
+        for address in rs_vals {
             assert!(
                 address as usize + READ_SIZE * BLOCKS_PER_READ - 1 < (1 << self.air.address_bits)
             );
-            from_fn(|i| {
-                memory.read::<READ_SIZE>(e, F::from_canonical_u32(address + (i * READ_SIZE) as u32))
-            })
-        });
+        }
+        let in_place =
+            is_in_place::<F, NUM_READS, BLOCKS_PER_READ, BLOCKS_PER_WRITE, READ_SIZE, WRITE_SIZE>(
+                instruction,
+            );
+        let read_records = vec_heap_read_inputs(memory, e, rs_vals, in_place);
         let read_data = read_records.map(|r| r.map(|x| x.data));
         assert!(rd_val as usize + WRITE_SIZE * BLOCKS_PER_WRITE - 1 < (1 << self.air.address_bits));
 
@@ -409,6 +566,7 @@ impl<
             rs: rs_records,
             rd: rd_record,
             rd_val: F::from_canonical_u32(rd_val),
+            in_place,
             reads: read_records,
         };
 
@@ -435,12 +593,35 @@ impl<
             record
         });
 
+        let setup_flag = match self.air.setup_guard {
+            Some(guard) => {
+                let is_setup = instruction.opcode.as_usize() == guard.setup_opcode;
+                let record = memory.write(
+                    F::from_canonical_u32(RV32_REGISTER_AS),
+                    F::from_canonical_u32(guard.flag_ptr),
+                    from_fn(|i| if i == 0 { F::ONE } else { F::ZERO }),
+                );
+                if !is_setup && record.prev_data[0] != F::ONE {
+                    return Err(ExecutionError::SetupNotDone {
+                        pc: from_state.pc,
+                        opcode: instruction.opcode,
+                    });
+                }
+                Some((is_setup, record))
+            }
+            None => None,
+        };
+
         Ok((
             ExecutionState {
                 pc: from_state.pc + 4,
                 timestamp: memory.timestamp(),
             },
-            Self::WriteRecord { from_state, writes },
+            Self::WriteRecord {
+                from_state,
+                writes,
+                setup_flag,
+            },
         ))
     }
 
@@ -451,6 +632,14 @@ impl<
         write_record: Self::WriteRecord,
         aux_cols_factory: &MemoryAuxColsFactory<F>,
     ) {
+        let (row_slice, guard_slice) = row_slice.split_at_mut(Rv32VecHeapAdapterCols::<
+            F,
+            NUM_READS,
+            BLOCKS_PER_READ,
+            BLOCKS_PER_WRITE,
+            READ_SIZE,
+            WRITE_SIZE,
+        >::width());
         vec_heap_generate_trace_row_impl(
             row_slice,
             &read_record,
@@ -458,7 +647,12 @@ impl<
             aux_cols_factory,
             &self.bitwise_lookup_chip,
             self.air.address_bits,
-        )
+        );
+        if let Some((is_setup, record)) = write_record.setup_flag {
+            let guard_cols: &mut SetupGuardCols<F> = guard_slice.borrow_mut();
+            guard_cols.is_setup = F::from_bool(is_setup);
+            guard_cols.flag_aux = aux_cols_factory.make_write_aux_cols(record);
+        }
     }
 
     fn air(&self) -> &Self::Air {
@@ -493,6 +687,7 @@ pub(super) fn vec_heap_generate_trace_row_impl<
 
     row_slice.rd_ptr = read_record.rd.pointer;
     row_slice.rs_ptr = read_record.rs.map(|r| r.pointer);
+    row_slice.is_in_place = F::from_bool(read_record.in_place);
 
     row_slice.rd_val = read_record.rd.data;
     row_slice.rs_val = read_record.rs.map(|r| r.data);
@@ -501,9 +696,13 @@ pub(super) fn vec_heap_generate_trace_row_impl<
         .rs
         .map(|r| aux_cols_factory.make_read_aux_cols(r));
     row_slice.rd_read_aux = aux_cols_factory.make_read_aux_cols(read_record.rd);
-    row_slice.reads_aux = read_record
-        .reads
-        .map(|r| r.map(|x| aux_cols_factory.make_read_aux_cols(x)));
+    row_slice.reads_aux = from_fn(|j| {
+        if j == 0 && read_record.in_place {
+            from_fn(|_| MemoryReadAuxCols::disabled())
+        } else {
+            read_record.reads[j].map(|x| aux_cols_factory.make_read_aux_cols(x))
+        }
+    });
     row_slice.writes_aux = write_record
         .writes
         .map(|w| aux_cols_factory.make_write_aux_cols(w));
@@ -521,3 +720,67 @@ pub(super) fn vec_heap_generate_trace_row_impl<
         bitwise_lookup_chip.request_range(pair[0] << limb_shift_bits, pair[1] << limb_shift_bits);
     }
 }
+
+/// Whether the in-place mode is possible for these read and write layouts: the first input must
+/// have the same layout as the output.
+pub(super) fn in_place_supported<
+    const NUM_READS: usize,
+    const BLOCKS_PER_READ: usize,
+    const BLOCKS_PER_WRITE: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+>() -> bool {
+    NUM_READS > 0 && BLOCKS_PER_READ == BLOCKS_PER_WRITE && READ_SIZE == WRITE_SIZE
+}
+
+/// Whether `instruction` is executed in place, i.e. `rd` and `rs[0]` are the same register.
+pub fn is_in_place<
+    F: Field,
+    const NUM_READS: usize,
+    const BLOCKS_PER_READ: usize,
+    const BLOCKS_PER_WRITE: usize,
+    const READ_SIZE: usize,
+    const WRITE_SIZE: usize,
+>(
+    instruction: &Instruction<F>,
+) -> bool {
+    in_place_supported::<NUM_READS, BLOCKS_PER_READ, BLOCKS_PER_WRITE, READ_SIZE, WRITE_SIZE>()
+        && instruction.a == instruction.b
+}
+
+/// Reads the heap inputs at the addresses `rs_vals`. If `in_place`, the first input is read
+/// without a record, since it is the previous data of the write, but its timestamps are still
+/// used so that the timestamp change doesn't depend on the mode. Its records are placeholders.
+pub(super) fn vec_heap_read_inputs<
+    F: PrimeField32,
+    const NUM_READS: usize,
+    const BLOCKS_PER_READ: usize,
+    const READ_SIZE: usize,
+>(
+    memory: &mut MemoryController<F>,
+    e: F,
+    rs_vals: [u32; NUM_READS],
+    in_place: bool,
+) -> [[MemoryReadRecord<F, READ_SIZE>; BLOCKS_PER_READ]; NUM_READS] {
+    let mut j = 0;
+    rs_vals.map(|address| {
+        let first_in_place = j == 0 && in_place;
+        j += 1;
+        from_fn(|i| {
+            let pointer = F::from_canonical_u32(address + (i * READ_SIZE) as u32);
+            if first_in_place {
+                let timestamp = memory.timestamp();
+                memory.increment_timestamp();
+                MemoryReadRecord {
+                    address_space: e,
+                    pointer,
+                    timestamp,
+                    prev_timestamp: timestamp,
+                    data: memory.unsafe_read::<READ_SIZE>(e, pointer),
+                }
+            } else {
+                memory.read::<READ_SIZE>(e, pointer)
+            }
+        })
+    })
+}
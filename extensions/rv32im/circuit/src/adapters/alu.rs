@@ -29,6 +29,7 @@ use openvm_stark_backend::{
     interaction::InteractionBuilder,
     p3_air::{AirBuilder, BaseAir},
     p3_field::{AbstractField, Field, PrimeField32},
+    p3_matrix::Matrix,
 };
 
 use super::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
@@ -36,9 +37,15 @@ use super::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
 /// Reads instructions of the form OP a, b, c, d, e where [a:4]_d = [b:4]_d op [c:4]_e.
 /// Operand d can only be 1, and e can be either 1 (for register reads) or 0 (when c
 /// is an immediate).
+///
+/// With [Rv32BaseAluAdapterChip::with_block_execution], consecutive rows executing a straight-line
+/// block of instructions are chained together, so the block only interacts with the execution bus
+/// when it is entered and when it is left.
 #[derive(Debug)]
 pub struct Rv32BaseAluAdapterChip<F: Field> {
     pub air: Rv32BaseAluAdapterAir,
+    /// State after the last executed instruction, used to detect blocks in block execution mode.
+    last_to_state: Option<ExecutionState<u32>>,
     _marker: PhantomData<F>,
 }
 
@@ -54,10 +61,19 @@ impl<F: PrimeField32> Rv32BaseAluAdapterChip<F> {
             air: Rv32BaseAluAdapterAir {
                 execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
                 memory_bridge,
+                block_execution: false,
             },
+            last_to_state: None,
             _marker: PhantomData,
         }
     }
+
+    /// Chains each instruction with the previously executed one whenever it starts where the
+    /// previous one ended. This adds a trailing `continues_block` column to the adapter.
+    pub fn with_block_execution(mut self) -> Self {
+        self.air.block_execution = true;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -77,6 +93,8 @@ pub struct Rv32BaseAluWriteRecord<F: Field> {
     pub from_state: ExecutionState<u32>,
     /// Write to destination register
     pub rd: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    /// Whether the instruction continues the block of the previous row
+    pub continues_block: bool,
 }
 
 #[repr(C)]
@@ -98,11 +116,14 @@ pub struct Rv32BaseAluAdapterCols<T> {
 pub struct Rv32BaseAluAdapterAir {
     pub(super) execution_bridge: ExecutionBridge,
     pub(super) memory_bridge: MemoryBridge,
+    /// If set, [Rv32BaseAluAdapterCols] is followed by a `continues_block` column which is one
+    /// when the row continues the straight-line block of the previous row.
+    pub(super) block_execution: bool,
 }
 
 impl<F: Field> BaseAir<F> for Rv32BaseAluAdapterAir {
     fn width(&self) -> usize {
-        Rv32BaseAluAdapterCols::<F>::width()
+        Rv32BaseAluAdapterCols::<F>::width() + self.block_execution as usize
     }
 }
 
@@ -122,7 +143,9 @@ impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32BaseAluAdapterAir {
         local: &[AB::Var],
         ctx: AdapterAirContext<AB::Expr, Self::Interface>,
     ) {
-        let local: &Rv32BaseAluAdapterCols<_> = local.borrow();
+        let cols_width = Rv32BaseAluAdapterCols::<AB::Var>::width();
+        let continues_block = local.get(cols_width).copied();
+        let local: &Rv32BaseAluAdapterCols<_> = local[..cols_width].borrow();
         let timestamp = local.from_state.timestamp;
         let mut timestamp_delta: usize = 0;
         let mut timestamp_pp = || {
@@ -172,25 +195,59 @@ impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32BaseAluAdapterAir {
             )
             .eval(builder, ctx.instruction.is_valid.clone());
 
-        self.execution_bridge
-            .execute_and_increment_or_set_pc(
-                ctx.instruction.opcode,
-                [
-                    local.rd_ptr.into(),
-                    local.rs1_ptr.into(),
-                    local.rs2.into(),
-                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
-                    local.rs2_as.into(),
-                ],
-                local.from_state,
-                AB::F::from_canonical_usize(timestamp_delta),
-                (4, ctx.to_pc),
-            )
-            .eval(builder, ctx.instruction.is_valid);
+        let execution = self.execution_bridge.execute_and_increment_or_set_pc(
+            ctx.instruction.opcode,
+            [
+                local.rd_ptr.into(),
+                local.rs1_ptr.into(),
+                local.rs2.into(),
+                AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                local.rs2_as.into(),
+            ],
+            local.from_state,
+            AB::F::from_canonical_usize(timestamp_delta),
+            (4, ctx.to_pc),
+        );
+
+        let Some(continues_block) = continues_block else {
+            execution.eval(builder, ctx.instruction.is_valid);
+            return;
+        };
+
+        let main = builder.main();
+        let (next_from_state, next_continues_block) = {
+            let next = main.row_slice(1);
+            let next: &[AB::Var] = (*next).borrow();
+            let next_cols: &Rv32BaseAluAdapterCols<_> = next[..cols_width].borrow();
+            (next_cols.from_state, next[cols_width])
+        };
+
+        // Only valid rows can be chained, and the first row starts a block.
+        builder.assert_bool(continues_block);
+        builder
+            .when(continues_block)
+            .assert_one(ctx.instruction.is_valid.clone());
+        builder.when_first_row().assert_zero(continues_block);
+
+        // A row continuing the block starts where the previous valid row ended.
+        let to_state = execution.to_state().clone();
+        let mut when_chained = builder.when_transition();
+        let mut when_chained = when_chained.when(next_continues_block);
+        when_chained.assert_one(ctx.instruction.is_valid.clone());
+        when_chained.assert_eq(next_from_state.pc, to_state.pc);
+        when_chained.assert_eq(next_from_state.timestamp, to_state.timestamp);
+
+        execution.eval_in_block(
+            builder,
+            ctx.instruction.is_valid,
+            continues_block,
+            next_continues_block,
+        );
     }
 
     fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
-        let cols: &Rv32BaseAluAdapterCols<_> = local.borrow();
+        let cols: &Rv32BaseAluAdapterCols<_> =
+            local[..Rv32BaseAluAdapterCols::<AB::Var>::width()].borrow();
         cols.from_state.pc
     }
 }
@@ -265,12 +322,20 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32BaseAluAdapterChip<F> {
             timestamp_delta
         );
 
+        let to_state = ExecutionState {
+            pc: from_state.pc + 4,
+            timestamp: memory.timestamp(),
+        };
+        let continues_block = self.air.block_execution && self.last_to_state == Some(from_state);
+        self.last_to_state = Some(to_state);
+
         Ok((
-            ExecutionState {
-                pc: from_state.pc + 4,
-                timestamp: memory.timestamp(),
+            to_state,
+            Self::WriteRecord {
+                from_state,
+                rd,
+                continues_block,
             },
-            Self::WriteRecord { from_state, rd },
         ))
     }
 
@@ -281,6 +346,10 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32BaseAluAdapterChip<F> {
         write_record: Self::WriteRecord,
         aux_cols_factory: &MemoryAuxColsFactory<F>,
     ) {
+        let (row_slice, block_cols) = row_slice.split_at_mut(Rv32BaseAluAdapterCols::<F>::width());
+        if let Some(continues_block) = block_cols.first_mut() {
+            *continues_block = F::from_bool(write_record.continues_block);
+        }
         let row_slice: &mut Rv32BaseAluAdapterCols<_> = row_slice.borrow_mut();
         row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
         row_slice.rd_ptr = write_record.rd.pointer;
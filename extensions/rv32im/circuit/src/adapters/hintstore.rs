@@ -34,7 +34,10 @@ use openvm_stark_backend::{
 };
 
 use super::{compose, RV32_REGISTER_NUM_LIMBS};
-use crate::adapters::RV32_CELL_BITS;
+use crate::{
+    adapters::RV32_CELL_BITS,
+    hintstore::{HintDigestBus, Rv32HintDigestChip},
+};
 
 /// This chip reads rs1 and gets a intermediate memory pointer address with rs1 + imm.
 /// It writes to the memory at the intermediate pointer.
@@ -42,6 +45,7 @@ use crate::adapters::RV32_CELL_BITS;
 pub struct Rv32HintStoreAdapterChip<F: Field> {
     pub air: Rv32HintStoreAdapterAir,
     pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    hint_digest_chip: Option<Arc<Rv32HintDigestChip<F>>>,
     _marker: PhantomData<F>,
 }
 
@@ -60,11 +64,20 @@ impl<F: PrimeField32> Rv32HintStoreAdapterChip<F> {
                 memory_bridge,
                 range_bus: range_checker_chip.bus(),
                 pointer_max_bits: memory_controller.mem_config().pointer_max_bits,
+                hint_digest_bus: None,
             },
             range_checker_chip,
+            hint_digest_chip: None,
             _marker: PhantomData,
         }
     }
+
+    /// Absorbs every hint word written into the digest of `hint_digest_chip`.
+    pub fn with_hint_digest(mut self, hint_digest_chip: Arc<Rv32HintDigestChip<F>>) -> Self {
+        self.air.hint_digest_bus = Some(hint_digest_chip.bus());
+        self.hint_digest_chip = Some(hint_digest_chip);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -104,6 +117,8 @@ pub struct Rv32HintStoreAdapterAir {
     pub(super) execution_bridge: ExecutionBridge,
     pub range_bus: VariableRangeCheckerBus,
     pointer_max_bits: usize,
+    /// Bus to send `(timestamp, word)` of every hint word written on, if hints are bound.
+    pub hint_digest_bus: Option<HintDigestBus>,
 }
 
 impl<F: Field> BaseAir<F> for Rv32HintStoreAdapterAir {
@@ -198,6 +213,14 @@ impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32HintStoreAdapterAir {
             )
             .eval(builder, is_valid.clone());
 
+        if let Some(bus) = self.hint_digest_bus {
+            builder.push_send(
+                bus.0,
+                [timestamp.into()].into_iter().chain(ctx.writes[0].clone()),
+                is_valid.clone(),
+            );
+        }
+
         let to_pc = ctx
             .to_pc
             .unwrap_or(local_cols.from_state.pc + AB::F::from_canonical_u32(4));
@@ -292,6 +315,9 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32HintStoreAdapterChip<F> {
         let ptr = read_record.mem_ptr_limbs[0]
             + read_record.mem_ptr_limbs[1] * F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
         let write_record = memory.write(instruction.e, ptr, output.writes[0]);
+        if let Some(hint_digest_chip) = &self.hint_digest_chip {
+            hint_digest_chip.absorb(from_state.timestamp, output.writes[0]);
+        }
 
         Ok((
             ExecutionState {
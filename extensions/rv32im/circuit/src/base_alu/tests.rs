@@ -2,7 +2,7 @@ use std::{borrow::BorrowMut, sync::Arc};
 
 use openvm_circuit::{
     arch::{
-        testing::{TestAdapterChip, VmChipTestBuilder},
+        testing::{Tamper, TestAdapterChip, VmChipTestBuilder},
         ExecutionBridge, VmAdapterChip, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
     },
     utils::generate_long_number,
@@ -28,7 +28,9 @@ use rand::Rng;
 
 use super::{core::run_alu, BaseAluCoreChip, Rv32BaseAluChip};
 use crate::{
-    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    adapters::{
+        Rv32BaseAluAdapterChip, Rv32BaseAluAdapterCols, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+    },
     base_alu::BaseAluCoreCols,
     test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
 };
@@ -110,6 +112,84 @@ fn rv32_alu_and_rand_test() {
     run_rv32_alu_rand_test(BaseAluOpcode::AND, 100);
 }
 
+fn setup_rv32_alu_block_test(
+    num_ops: usize,
+    pc_step: u32,
+) -> (
+    VmChipTestBuilder<F>,
+    Rv32BaseAluChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32BaseAluChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+        .with_block_execution(),
+        BaseAluCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    // Registers are written up front so that the instructions run back to back.
+    let opcodes = [
+        BaseAluOpcode::ADD,
+        BaseAluOpcode::SUB,
+        BaseAluOpcode::XOR,
+        BaseAluOpcode::OR,
+        BaseAluOpcode::AND,
+    ];
+    let instructions: Vec<_> = (0..num_ops)
+        .map(|_| {
+            let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+            let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+            let opcode = opcodes[rng.gen_range(0..opcodes.len())];
+            rv32_rand_write_register_or_imm(&mut tester, b, c, None, opcode as usize, &mut rng).0
+        })
+        .collect();
+    let start_pc = 4 * rng.gen_range(0..1 << 20);
+    for (i, instruction) in instructions.into_iter().enumerate() {
+        tester.execute_with_pc(&mut chip, instruction, start_pc + pc_step * i as u32);
+    }
+    (tester, chip, bitwise_chip)
+}
+
+fn run_rv32_alu_block_rand_test(pc_step: u32, chained: bool) {
+    let num_ops = 50;
+    let (tester, chip, bitwise_chip) = setup_rv32_alu_block_test(num_ops, pc_step);
+    let continues_block_col = Rv32BaseAluAdapterCols::<F>::width();
+    let check_blocks = |trace: &mut DenseMatrix<BabyBear>| {
+        assert_eq!(trace.get(0, continues_block_col), F::ZERO);
+        for row in 1..num_ops {
+            assert_eq!(trace.get(row, continues_block_col), F::from_bool(chained));
+        }
+    };
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, check_blocks)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_alu_block_rand_test() {
+    run_rv32_alu_block_rand_test(4, true);
+}
+
+#[test]
+fn rv32_alu_block_jump_rand_test() {
+    // Instructions which do not follow each other start a new block each.
+    run_rv32_alu_block_rand_test(8, false);
+}
+
 //////////////////////////////////////////////////////////////////////////////////////
 // NEGATIVE TESTS
 //
@@ -320,3 +400,17 @@ fn run_and_sanity_test() {
         assert_eq!(z[i], result[i])
     }
 }
+
+#[test]
+fn rv32_alu_block_broken_chain_negative_test() {
+    let (tester, chip, bitwise_chip) = setup_rv32_alu_block_test(2, 4);
+    // The second instruction claims to run right after the first one, but from another pc.
+    let from_pc_col = 0;
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &[Tamper::off_by_one(1, from_pc_col)])
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
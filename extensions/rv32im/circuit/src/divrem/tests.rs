@@ -501,6 +501,136 @@ fn rv32_divrem_false_r_zero_flag_negative_test() {
     run_rv32_divrem_negative_test(false, b, c, &prank_vals, false);
 }
 
+//////////////////////////////////////////////////////////////////////////////////////
+// SPEC CONFORMANCE TESTS
+//
+// Compare against the RISC-V M extension spec, including division by zero (quotient
+// of all ones, remainder equal to the dividend) and signed overflow (quotient equal to
+// the dividend, zero remainder), on random operands biased towards the edge cases.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn rv32_divrem_spec(opcode: DivRemOpcode, x: u32, y: u32) -> u32 {
+    match opcode {
+        DivRemOpcode::DIV => match (x as i32, y as i32) {
+            (_, 0) => u32::MAX,
+            (i32::MIN, -1) => x,
+            (x, y) => (x / y) as u32,
+        },
+        DivRemOpcode::DIVU => x.checked_div(y).unwrap_or(u32::MAX),
+        DivRemOpcode::REM => match (x as i32, y as i32) {
+            (_, 0) => x,
+            (i32::MIN, -1) => 0,
+            (x, y) => (x % y) as u32,
+        },
+        DivRemOpcode::REMU => x.checked_rem(y).unwrap_or(x),
+    }
+}
+
+fn gen_divrem_spec_operand(rng: &mut StdRng) -> u32 {
+    const EDGE_CASES: [u32; 6] = [0, 1, u32::MAX, i32::MIN as u32, i32::MAX as u32, 2];
+    if rng.gen_bool(0.5) {
+        EDGE_CASES[rng.gen_range(0..EDGE_CASES.len())]
+    } else {
+        rng.gen()
+    }
+}
+
+#[test]
+fn run_divrem_spec_fuzz_test() {
+    let mut rng = create_seeded_rng();
+    for _ in 0..10000 {
+        let x = gen_divrem_spec_operand(&mut rng);
+        let y = gen_divrem_spec_operand(&mut rng);
+        let x_limbs = x.to_le_bytes().map(u32::from);
+        let y_limbs = y.to_le_bytes().map(u32::from);
+        for signed in [false, true] {
+            let (q, r, _, _, _, _) =
+                run_divrem::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(signed, &x_limbs, &y_limbs);
+            let (div, rem) = if signed {
+                (DivRemOpcode::DIV, DivRemOpcode::REM)
+            } else {
+                (DivRemOpcode::DIVU, DivRemOpcode::REMU)
+            };
+            assert_eq!(
+                rv32_divrem_spec(div, x, y).to_le_bytes().map(u32::from),
+                q,
+                "{div:?} {x:#x} {y:#x}"
+            );
+            assert_eq!(
+                rv32_divrem_spec(rem, x, y).to_le_bytes().map(u32::from),
+                r,
+                "{rem:?} {x:#x} {y:#x}"
+            );
+        }
+    }
+}
+
+#[test]
+fn rv32_divrem_spec_fuzz_test() {
+    const MAX_NUM_LIMBS: u32 = 32;
+    let mut rng = create_seeded_rng();
+
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [1 << RV32_CELL_BITS, MAX_NUM_LIMBS * (1 << RV32_CELL_BITS)],
+    );
+
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32DivRemChip::<F>::new(
+        Rv32MultAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        DivRemCoreChip::new(bitwise_chip.clone(), range_tuple_checker.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    let opcodes = [
+        DivRemOpcode::DIV,
+        DivRemOpcode::DIVU,
+        DivRemOpcode::REM,
+        DivRemOpcode::REMU,
+    ];
+    for _ in 0..200 {
+        let opcode = opcodes[rng.gen_range(0..opcodes.len())];
+        let x = gen_divrem_spec_operand(&mut rng);
+        let y = gen_divrem_spec_operand(&mut rng);
+
+        let rs1 = gen_pointer(&mut rng, 4);
+        let rs2 = gen_pointer(&mut rng, 4);
+        let rd = gen_pointer(&mut rng, 4);
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs1, x.to_le_bytes().map(F::from_canonical_u8));
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs2, y.to_le_bytes().map(F::from_canonical_u8));
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, rs2, 1, 0]),
+        );
+
+        assert_eq!(
+            rv32_divrem_spec(opcode, x, y)
+                .to_le_bytes()
+                .map(F::from_canonical_u8),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd),
+            "{opcode:?} {x:#x} {y:#x}"
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(bitwise_chip)
+        .load(range_tuple_checker)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
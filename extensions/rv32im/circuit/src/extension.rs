@@ -10,7 +10,7 @@ use openvm_circuit::{
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
 use openvm_circuit_primitives::{
-    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    bitwise_op_lookup::BitwiseOperationLookupChip,
     range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
@@ -135,12 +135,18 @@ pub struct Rv32Io;
 pub struct Rv32M {
     #[serde(default = "default_range_tuple_checker_sizes")]
     pub range_tuple_checker_sizes: [u32; 2],
+    /// If set, MUL and MULH/MULHSU/MULHU are handled by a single chip that shares the
+    /// partial product columns, instead of one chip each. Off by default so existing
+    /// configs keep the same set of AIRs.
+    #[serde(default)]
+    pub fused_mul: bool,
 }
 
 impl Default for Rv32M {
     fn default() -> Self {
         Self {
             range_tuple_checker_sizes: default_range_tuple_checker_sizes(),
+            fused_mul: false,
         }
     }
 }
@@ -172,6 +178,7 @@ pub enum Rv32IExecutor<F: PrimeField32> {
 pub enum Rv32MExecutor<F: PrimeField32> {
     Multiplication(Rv32MultiplicationChip<F>),
     MultiplicationHigh(Rv32MulHChip<F>),
+    MultiplicationFused(Rv32MulFusedChip<F>),
     DivRem(Rv32DivRemChip<F>),
 }
 
@@ -200,6 +207,7 @@ pub enum Rv32MPeriphery<F: PrimeField32> {
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Rv32IoPeriphery<F: PrimeField32> {
     BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    HintDigest(Arc<Rv32HintDigestChip<F>>),
     // We put this only to get the <F> generic to work
     Phantom(PhantomChip<F>),
 }
@@ -221,20 +229,20 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
             memory_controller,
         } = builder.system_port();
         let range_checker = builder.system_base().range_checker_chip.clone();
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
-        {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
+        let basic_block_execution = builder.system_config().basic_block_execution;
+        let alu_adapter = || {
+            let adapter =
+                Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone());
+            if basic_block_execution {
+                adapter.with_block_execution()
+            } else {
+                adapter
+            }
         };
 
         let base_alu_chip = Rv32BaseAluChip::new(
-            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            alu_adapter(),
             BaseAluCoreChip::new(bitwise_lu_chip.clone(), BaseAluOpcode::default_offset()),
             memory_controller.clone(),
         );
@@ -244,7 +252,7 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
         )?;
 
         let lt_chip = Rv32LessThanChip::new(
-            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            alu_adapter(),
             LessThanCoreChip::new(bitwise_lu_chip.clone(), LessThanOpcode::default_offset()),
             memory_controller.clone(),
         );
@@ -254,7 +262,7 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
         )?;
 
         let shift_chip = Rv32ShiftChip::new(
-            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            alu_adapter(),
             ShiftCoreChip::new(
                 bitwise_lu_chip.clone(),
                 range_checker.clone(),
@@ -391,17 +399,7 @@ impl<F: PrimeField32> VmExtension<F> for Rv32M {
             memory_controller,
         } = builder.system_port();
 
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
-        {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
 
         let range_tuple_checker = if let Some(chip) = builder
             .find_chip::<Arc<RangeTupleCheckerChip<2>>>()
@@ -412,36 +410,60 @@ impl<F: PrimeField32> VmExtension<F> for Rv32M {
             }) {
             chip.clone()
         } else {
-            let range_tuple_bus =
-                RangeTupleCheckerBus::new(builder.new_bus_idx(), self.range_tuple_checker_sizes);
+            let range_tuple_bus = RangeTupleCheckerBus::new(
+                builder.new_lookup_bus_idx(),
+                self.range_tuple_checker_sizes,
+            );
             let chip = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
             inventory.add_periphery_chip(chip.clone());
             chip
         };
 
-        let mul_chip = Rv32MultiplicationChip::new(
-            Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
-            MultiplicationCoreChip::new(range_tuple_checker.clone(), MulOpcode::default_offset()),
-            memory_controller.clone(),
-        );
-        inventory.add_executor(
-            mul_chip,
-            MulOpcode::iter().map(VmOpcode::with_default_offset),
-        )?;
-
-        let mul_h_chip = Rv32MulHChip::new(
-            Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
-            MulHCoreChip::new(
-                bitwise_lu_chip.clone(),
-                range_tuple_checker.clone(),
-                MulHOpcode::default_offset(),
-            ),
-            memory_controller.clone(),
-        );
-        inventory.add_executor(
-            mul_h_chip,
-            MulHOpcode::iter().map(VmOpcode::with_default_offset),
-        )?;
+        if self.fused_mul {
+            let mul_fused_chip = Rv32MulFusedChip::new(
+                Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                MulFusedCoreChip::new(
+                    bitwise_lu_chip.clone(),
+                    range_tuple_checker.clone(),
+                    MulOpcode::default_offset(),
+                    MulHOpcode::default_offset(),
+                ),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                mul_fused_chip,
+                MulOpcode::iter()
+                    .map(VmOpcode::with_default_offset)
+                    .chain(MulHOpcode::iter().map(VmOpcode::with_default_offset)),
+            )?;
+        } else {
+            let mul_chip = Rv32MultiplicationChip::new(
+                Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                MultiplicationCoreChip::new(
+                    range_tuple_checker.clone(),
+                    MulOpcode::default_offset(),
+                ),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                mul_chip,
+                MulOpcode::iter().map(VmOpcode::with_default_offset),
+            )?;
+
+            let mul_h_chip = Rv32MulHChip::new(
+                Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                MulHCoreChip::new(
+                    bitwise_lu_chip.clone(),
+                    range_tuple_checker.clone(),
+                    MulHOpcode::default_offset(),
+                ),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                mul_h_chip,
+                MulHOpcode::iter().map(VmOpcode::with_default_offset),
+            )?;
+        }
 
         let div_rem_chip = Rv32DivRemChip::new(
             Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
@@ -476,25 +498,28 @@ impl<F: PrimeField32> VmExtension<F> for Rv32Io {
             memory_controller,
         } = builder.system_port();
         let range_checker = builder.system_base().range_checker_chip.clone();
-        let bitwise_lu_chip = if let Some(chip) = builder
-            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
-            .first()
-        {
-            Arc::clone(chip)
-        } else {
-            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
-            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
-            inventory.add_periphery_chip(chip.clone());
-            chip
-        };
+        let bitwise_lu_chip = builder.shared_bitwise_lookup_chip(&mut inventory);
 
-        let mut hintstore_chip = Rv32HintStoreChip::new(
-            Rv32HintStoreAdapterChip::new(
-                execution_bus,
-                program_bus,
-                memory_controller.clone(),
+        let mut adapter = Rv32HintStoreAdapterChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            range_checker.clone(),
+        );
+        if builder.system_config().hint_digest {
+            let max_constraint_degree = builder.system_config().max_constraint_degree;
+            let clk_max_bits = builder.system_config().memory_config.clk_max_bits;
+            let hint_digest_chip = Arc::new(Rv32HintDigestChip::new(
+                HintDigestBus(builder.new_bus_idx()),
+                max_constraint_degree,
+                clk_max_bits,
                 range_checker.clone(),
-            ),
+            ));
+            inventory.add_periphery_chip(hint_digest_chip.clone());
+            adapter = adapter.with_hint_digest(hint_digest_chip);
+        }
+        let mut hintstore_chip = Rv32HintStoreChip::new(
+            adapter,
             Rv32HintStoreCoreChip::new(
                 bitwise_lu_chip.clone(),
                 Rv32HintStoreOpcode::default_offset(),
@@ -1,5 +1,4 @@
 use std::{
-    array,
     borrow::{Borrow, BorrowMut},
     sync::{Arc, OnceLock},
 };
@@ -134,11 +133,9 @@ where
         _reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let mut streams = self.streams.get().unwrap().lock();
-        if streams.hint_stream.len() < RV32_REGISTER_NUM_LIMBS {
-            return Err(ExecutionError::HintOutOfBounds { pc: from_pc });
-        }
-        let data: [F; RV32_REGISTER_NUM_LIMBS] =
-            array::from_fn(|_| streams.hint_stream.pop_front().unwrap());
+        let data: [F; RV32_REGISTER_NUM_LIMBS] = streams
+            .pop_hints()
+            .ok_or(ExecutionError::HintOutOfBounds { pc: from_pc })?;
         let write_data = data;
 
         let output = AdapterRuntimeContext::without_pc([write_data]);
@@ -0,0 +1,301 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{vm_poseidon2_config, POSEIDON2_WIDTH};
+use openvm_circuit_primitives::{
+    assert_less_than::{AssertLessThanIo, AssertLtSubAir, AssertLtWhenTransitionAir},
+    utils::implies,
+    var_range::VariableRangeCheckerChip,
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_poseidon2_air::poseidon2::{air::SBOX_DEGREE, Poseidon2Air, Poseidon2Cols};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+use parking_lot::Mutex;
+
+use crate::adapters::RV32_REGISTER_NUM_LIMBS;
+
+/// Number of field elements of the hint digest.
+pub const HINT_DIGEST_LEN: usize = POSEIDON2_WIDTH / 2;
+/// Number of limbs `timestamp` differences are decomposed into. This requires
+/// `clk_max_bits.div_ceil(decomp) == TIMESTAMP_AUX_LEN`, as for the memory offline checker.
+const TIMESTAMP_AUX_LEN: usize = 2;
+
+/// Bus of the words written by `HINT_STOREW`, sent as `(timestamp, word)` by the hint store
+/// adapter and received by the [Rv32HintDigestAir].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HintDigestBus(pub usize);
+
+/// Public values of the [Rv32HintDigestAir].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Rv32HintDigestPvs<T> {
+    /// Digest of all hint words consumed in the segment.
+    pub digest: [T; HINT_DIGEST_LEN],
+}
+
+/// Columns of a row, followed by the columns of the Poseidon2 permutation of
+/// `state || word || 0`, whose first half of the output is the state of the next row.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Rv32HintDigestCols<T> {
+    /// Boolean. `0` on padding rows, which are all at the bottom.
+    pub is_valid: T,
+    /// Timestamp at which `HINT_STOREW` consumed `word`.
+    pub timestamp: T,
+    pub word: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Digest before absorbing `word`.
+    pub state: [T; HINT_DIGEST_LEN],
+    pub timestamp_lt_aux: [T; TIMESTAMP_AUX_LEN],
+}
+
+/// Absorbs the hint words consumed by `HINT_STOREW`, in the order of their timestamps, into a
+/// running digest starting from zero. The digest after the last row is the public value.
+#[derive(Clone, Debug)]
+pub struct Rv32HintDigestAir<F> {
+    pub bus: HintDigestBus,
+    pub poseidon2: Poseidon2Air<POSEIDON2_WIDTH, F>,
+    timestamp_lt_air: AssertLtWhenTransitionAir,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32HintDigestAir<F> {
+    fn num_public_values(&self) -> usize {
+        Rv32HintDigestPvs::<F>::width()
+    }
+}
+impl<F: Field> PartitionedBaseAir<F> for Rv32HintDigestAir<F> {}
+impl<F: Field> BaseAir<F> for Rv32HintDigestAir<F> {
+    fn width(&self) -> usize {
+        Rv32HintDigestCols::<F>::width() + self.poseidon2.get_width()
+    }
+}
+
+impl<AB: InteractionBuilder + AirBuilderWithPublicValues> Air<AB> for Rv32HintDigestAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let num_cols = Rv32HintDigestCols::<AB::Var>::width();
+        let local_poseidon2 = Poseidon2Cols::<POSEIDON2_WIDTH, AB::Var>::from_slice(
+            &local[num_cols..],
+            &self.poseidon2,
+        );
+        let local: &Rv32HintDigestCols<AB::Var> = local[..num_cols].borrow();
+        let next: &Rv32HintDigestCols<AB::Var> = next[..num_cols].borrow();
+
+        self.poseidon2.eval_without_interactions(
+            builder,
+            local_poseidon2.io,
+            local_poseidon2.aux.into_expr::<AB>(),
+        );
+
+        builder.assert_bool(local.is_valid);
+        builder
+            .when_transition()
+            .assert_one(implies(next.is_valid, local.is_valid));
+
+        // The permutation input is `state || word || 0` on valid rows.
+        let input = local_poseidon2.io.input;
+        let (input_state, input_rest) = input.split_at(HINT_DIGEST_LEN);
+        let (input_word, input_zeros) = input_rest.split_at(RV32_REGISTER_NUM_LIMBS);
+        let mut when_valid = builder.when(local.is_valid);
+        for (&x, &state) in input_state.iter().zip(&local.state) {
+            when_valid.assert_eq(x, state);
+        }
+        for (&x, &limb) in input_word.iter().zip(&local.word) {
+            when_valid.assert_eq(x, limb);
+        }
+        for &x in input_zeros {
+            when_valid.assert_zero(x);
+        }
+
+        // Padding rows keep the digest.
+        let digest: [AB::Expr; HINT_DIGEST_LEN] = array::from_fn(|i| {
+            local.state[i] + local.is_valid * (local_poseidon2.io.output[i] - local.state[i])
+        });
+        for ((&state, &next_state), d) in local.state.iter().zip(&next.state).zip(&digest) {
+            builder.when_first_row().assert_zero(state);
+            builder.when_transition().assert_eq(next_state, d.clone());
+        }
+        let pvs: &Rv32HintDigestPvs<AB::PublicVar> = builder.public_values().borrow();
+        let pv_digest = pvs.digest;
+        for (d, pv) in digest.into_iter().zip(pv_digest) {
+            builder.when_last_row().assert_eq(d, pv);
+        }
+
+        // Words are absorbed in the order they were consumed.
+        self.timestamp_lt_air.eval(
+            builder,
+            (
+                AssertLessThanIo::new(local.timestamp, next.timestamp, next.is_valid),
+                &local.timestamp_lt_aux[..],
+            ),
+        );
+
+        builder.push_receive(
+            self.bus.0,
+            [local.timestamp.into()]
+                .into_iter()
+                .chain(local.word.map(Into::into)),
+            local.is_valid,
+        );
+    }
+}
+
+#[derive(Debug)]
+struct Rv32HintDigestRecord<F> {
+    timestamp: u32,
+    word: [F; RV32_REGISTER_NUM_LIMBS],
+    state: [F; HINT_DIGEST_LEN],
+    poseidon2: Poseidon2Cols<POSEIDON2_WIDTH, F>,
+}
+
+#[derive(Debug)]
+struct Rv32HintDigestState<F> {
+    digest: [F; HINT_DIGEST_LEN],
+    records: Vec<Rv32HintDigestRecord<F>>,
+}
+
+/// Keeps the running digest of the hint words consumed by `HINT_STOREW`, see
+/// [SystemConfig::hint_digest](openvm_circuit::arch::SystemConfig::hint_digest). Shared with the
+/// hint store adapter, which calls [Self::absorb].
+#[derive(Debug)]
+pub struct Rv32HintDigestChip<F: Field> {
+    pub air: Rv32HintDigestAir<F>,
+    range_checker: Arc<VariableRangeCheckerChip>,
+    state: Mutex<Rv32HintDigestState<F>>,
+}
+
+impl<F: PrimeField32> Rv32HintDigestChip<F> {
+    pub fn new(
+        bus: HintDigestBus,
+        max_constraint_degree: usize,
+        clk_max_bits: usize,
+        range_checker: Arc<VariableRangeCheckerChip>,
+    ) -> Self {
+        let timestamp_lt_air = AssertLtSubAir::new(range_checker.bus(), clk_max_bits);
+        assert_eq!(timestamp_lt_air.decomp_limbs, TIMESTAMP_AUX_LEN);
+        Self {
+            air: Rv32HintDigestAir {
+                bus,
+                poseidon2: Poseidon2Air::from_config(
+                    vm_poseidon2_config(),
+                    max_constraint_degree.min(SBOX_DEGREE),
+                    0,
+                ),
+                timestamp_lt_air: timestamp_lt_air.when_transition(),
+            },
+            range_checker,
+            state: Mutex::new(Rv32HintDigestState {
+                digest: [F::ZERO; HINT_DIGEST_LEN],
+                records: vec![],
+            }),
+        }
+    }
+
+    pub fn bus(&self) -> HintDigestBus {
+        self.air.bus
+    }
+
+    /// The digest of the hint words absorbed so far.
+    pub fn digest(&self) -> [F; HINT_DIGEST_LEN] {
+        self.state.lock().digest
+    }
+
+    /// Absorbs a hint word consumed at `timestamp`, which must be greater than the timestamps of
+    /// the words absorbed before.
+    pub fn absorb(&self, timestamp: u32, word: [F; RV32_REGISTER_NUM_LIMBS]) {
+        let mut state = self.state.lock();
+        let mut input = [F::ZERO; POSEIDON2_WIDTH];
+        input[..HINT_DIGEST_LEN].copy_from_slice(&state.digest);
+        input[HINT_DIGEST_LEN..HINT_DIGEST_LEN + RV32_REGISTER_NUM_LIMBS].copy_from_slice(&word);
+        let poseidon2 = self.air.poseidon2.generate_trace_row(input);
+        let digest = array::from_fn(|i| poseidon2.io.output[i]);
+        let record = Rv32HintDigestRecord {
+            timestamp,
+            word,
+            state: state.digest,
+            poseidon2,
+        };
+        state.digest = digest;
+        state.records.push(record);
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32HintDigestChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air.clone())
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let width = self.trace_width();
+        let height = self.current_trace_height().next_power_of_two();
+        let num_cols = Rv32HintDigestCols::<Val<SC>>::width();
+        let Rv32HintDigestState { digest, records } = self.state.into_inner();
+        let num_records = records.len();
+
+        let mut rows = Val::<SC>::zero_vec(height * width);
+        let blank_poseidon2 = Poseidon2Cols::blank_row(&self.air.poseidon2).flatten();
+        for (i, row) in rows.chunks_exact_mut(width).enumerate() {
+            let (row, poseidon2_row) = row.split_at_mut(num_cols);
+            let cols: &mut Rv32HintDigestCols<Val<SC>> = row.borrow_mut();
+            let Some(record) = records.get(i) else {
+                cols.state = digest;
+                poseidon2_row.copy_from_slice(&blank_poseidon2);
+                continue;
+            };
+            cols.is_valid = Val::<SC>::ONE;
+            cols.timestamp = Val::<SC>::from_canonical_u32(record.timestamp);
+            cols.word = record.word;
+            cols.state = record.state;
+            if let Some(next) = records.get(i + 1) {
+                self.air.timestamp_lt_air.0.generate_subrow(
+                    (&self.range_checker, record.timestamp, next.timestamp),
+                    &mut cols.timestamp_lt_aux,
+                );
+            }
+            poseidon2_row.copy_from_slice(&record.poseidon2.clone().flatten());
+        }
+        // The last row is range checked against the first one due to wraparound.
+        if num_records > 0 {
+            let cols: &mut Rv32HintDigestCols<Val<SC>> =
+                rows[width * (height - 1)..][..num_cols].borrow_mut();
+            self.air
+                .timestamp_lt_air
+                .0
+                .generate_subrow((&self.range_checker, 0, 1), &mut cols.timestamp_lt_aux);
+        }
+
+        let trace = RowMajorMatrix::new(rows, width);
+        AirProofInput::simple(Arc::new(self.air), trace, digest.to_vec())
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32HintDigestChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    /// Never empty, so that the digest is public even when no hint was consumed.
+    fn current_trace_height(&self) -> usize {
+        self.state.lock().records.len().max(1)
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -3,8 +3,11 @@ use openvm_circuit::arch::VmChipWrapper;
 use crate::adapters::Rv32HintStoreAdapterChip;
 
 mod core;
+mod digest;
 pub use core::*;
 
+pub use digest::*;
+
 #[cfg(test)]
 mod tests;
 
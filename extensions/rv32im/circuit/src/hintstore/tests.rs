@@ -2,6 +2,7 @@ use std::{array, borrow::BorrowMut, sync::Arc};
 
 use openvm_circuit::{
     arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
         testing::{memory::gen_pointer, VmChipTestBuilder},
         Streams, VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
     },
@@ -26,13 +27,14 @@ use openvm_stark_sdk::{config::setup_tracing, p3_baby_bear::BabyBear, utils::cre
 use parking_lot::Mutex;
 use rand::{rngs::StdRng, Rng};
 
-use super::{Rv32HintStoreChip, Rv32HintStoreCoreChip};
+use super::{HintDigestBus, Rv32HintDigestChip, Rv32HintStoreChip, Rv32HintStoreCoreChip};
 use crate::{
     adapters::{compose, Rv32HintStoreAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
-    hintstore::Rv32HintStoreCoreCols,
+    hintstore::{Rv32HintDigestCols, Rv32HintStoreCoreCols, HINT_DIGEST_LEN},
 };
 
 const IMM_BITS: usize = 16;
+const HINT_DIGEST_BUS: usize = 13;
 
 type F = BabyBear;
 
@@ -43,7 +45,7 @@ fn set_and_execute(
     opcode: Rv32HintStoreOpcode,
     rs1: Option<[u32; RV32_REGISTER_NUM_LIMBS]>,
     imm: Option<u32>,
-) {
+) -> [F; RV32_REGISTER_NUM_LIMBS] {
     let imm = imm.unwrap_or(rng.gen_range(0..(1 << IMM_BITS)));
     let imm_ext = u32_sign_extend::<IMM_BITS>(imm);
     let ptr_val = rng.gen_range(
@@ -87,6 +89,7 @@ fn set_and_execute(
 
     let write_data = read_data;
     assert_eq!(write_data, tester.read::<4>(2, ptr_val as usize));
+    write_data
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////
@@ -129,6 +132,108 @@ fn rand_hintstore_test() {
     tester.simple_test().expect("Verification failed");
 }
 
+/// Sets up a hint store chip which absorbs the words written into a digest, and executes
+/// `num_words` `HINT_STOREW`s.
+fn setup_hint_digest_test(
+    num_words: usize,
+) -> (
+    VmChipTestBuilder<F>,
+    Rv32HintStoreChip<F>,
+    Arc<Rv32HintDigestChip<F>>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    Vec<[F; RV32_REGISTER_NUM_LIMBS]>,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let (range_checker_chip, clk_max_bits) = {
+        let memory_controller = tester.memory_controller();
+        let memory_controller = memory_controller.borrow();
+        (
+            memory_controller.range_checker.clone(),
+            memory_controller.mem_config().clk_max_bits,
+        )
+    };
+    let digest_chip = Arc::new(Rv32HintDigestChip::new(
+        HintDigestBus(HINT_DIGEST_BUS),
+        3,
+        clk_max_bits,
+        range_checker_chip.clone(),
+    ));
+    let adapter = Rv32HintStoreAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip,
+    )
+    .with_hint_digest(digest_chip.clone());
+
+    let mut core =
+        Rv32HintStoreCoreChip::new(bitwise_chip.clone(), Rv32HintStoreOpcode::default_offset());
+    core.set_streams(Arc::new(Mutex::new(Streams::default())));
+    let mut chip = Rv32HintStoreChip::<F>::new(adapter, core, tester.memory_controller());
+
+    let words = (0..num_words)
+        .map(|_| set_and_execute(&mut tester, &mut chip, &mut rng, HINT_STOREW, None, None))
+        .collect();
+    (tester, chip, digest_chip, bitwise_chip, words)
+}
+
+#[test]
+fn rand_hint_digest_test() {
+    setup_tracing();
+    let (tester, chip, digest_chip, bitwise_chip, words) = setup_hint_digest_test(100);
+
+    let hasher = vm_poseidon2_hasher();
+    let expected = words
+        .iter()
+        .fold([F::ZERO; HINT_DIGEST_LEN], |digest, word| {
+            let mut rhs = [F::ZERO; HINT_DIGEST_LEN];
+            rhs[..RV32_REGISTER_NUM_LIMBS].copy_from_slice(word);
+            hasher.compress(&digest, &rhs)
+        });
+    assert_eq!(digest_chip.digest(), expected);
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(digest_chip)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn negative_hint_digest_order_test() {
+    let (tester, chip, digest_chip, bitwise_chip, _) = setup_hint_digest_test(4);
+
+    // Absorbing the same words in another order must not verify.
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let width = trace.width();
+        let mut rows = trace.values.clone();
+        let (first, rest) = rows.split_at_mut(width);
+        first.swap_with_slice(&mut rest[..width]);
+        let cols: &mut Rv32HintDigestCols<F> =
+            first[..Rv32HintDigestCols::<F>::width()].borrow_mut();
+        assert_eq!(cols.is_valid, F::ONE);
+        *trace = RowMajorMatrix::new(rows, width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load(chip)
+        .load_and_prank_trace(digest_chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
 //////////////////////////////////////////////////////////////////////////////////////
 // NEGATIVE TESTS
 //
@@ -12,6 +12,7 @@ mod less_than;
 mod load_sign_extend;
 mod loadstore;
 mod mul;
+mod mul_fused;
 mod mulh;
 mod shift;
 
@@ -27,6 +28,7 @@ pub use less_than::*;
 pub use load_sign_extend::*;
 pub use loadstore::*;
 pub use mul::*;
+pub use mul_fused::*;
 pub use mulh::*;
 pub use shift::*;
 
@@ -0,0 +1,288 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::{MulHOpcode, MulOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::mulh::{eval_mulh, run_mulh, MulHEvalCols};
+
+/// Handles MUL, MULH, MULHSU and MULHU in a single chip. Every row computes the full
+/// `2 * NUM_LIMBS` limb product of `b` and `c`, and MUL writes back the low half
+/// `a_mul` while the MULH variants write back the high half `a`.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct MulFusedCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub a_mul: [T; NUM_LIMBS],
+    pub b_ext: T,
+    pub c_ext: T,
+
+    pub opcode_mul_flag: T,
+    pub opcode_mulh_flag: T,
+    pub opcode_mulhsu_flag: T,
+    pub opcode_mulhu_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MulFusedCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_tuple_bus: RangeTupleCheckerBus<2>,
+    mul_offset: usize,
+    mulh_offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for MulFusedCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        MulFusedCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for MulFusedCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for MulFusedCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &MulFusedCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let mulh_flags = [
+            cols.opcode_mulh_flag,
+            cols.opcode_mulhsu_flag,
+            cols.opcode_mulhu_flag,
+        ];
+
+        builder.assert_bool(cols.opcode_mul_flag);
+        let is_valid =
+            mulh_flags
+                .iter()
+                .fold(cols.opcode_mul_flag.into(), |acc: AB::Expr, &flag| {
+                    builder.assert_bool(flag);
+                    acc + flag.into()
+                });
+        builder.assert_bool(is_valid.clone());
+
+        // MUL treats both operands as unsigned, its result is the low half regardless.
+        eval_mulh::<AB, NUM_LIMBS, LIMB_BITS>(
+            builder,
+            self.bitwise_lookup_bus,
+            self.range_tuple_bus,
+            MulHEvalCols {
+                a: &cols.a,
+                b: &cols.b,
+                c: &cols.c,
+                a_mul: &cols.a_mul,
+                b_ext: cols.b_ext,
+                c_ext: cols.c_ext,
+            },
+            is_valid.clone(),
+            cols.opcode_mul_flag + cols.opcode_mulhu_flag,
+            cols.opcode_mul_flag + cols.opcode_mulhu_flag + cols.opcode_mulhsu_flag,
+            cols.opcode_mulh_flag.into(),
+            cols.opcode_mulh_flag + cols.opcode_mulhsu_flag,
+        );
+
+        let expected_opcode = mulh_flags.iter().zip(MulHOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into()
+                    * AB::Expr::from_canonical_usize(self.mulh_offset + local_opcode as usize)
+            },
+        ) + cols.opcode_mul_flag
+            * AB::Expr::from_canonical_usize(self.mul_offset + MulOpcode::MUL as usize);
+
+        let not_mul = AB::Expr::ONE - cols.opcode_mul_flag;
+        let write_data: [AB::Expr; NUM_LIMBS] = std::array::from_fn(|i| {
+            cols.a_mul[i] * cols.opcode_mul_flag + cols.a[i] * not_mul.clone()
+        });
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [write_data].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MulFusedCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: MulFusedCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+    pub range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MulFusedCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+        mul_offset: usize,
+        mulh_offset: usize,
+    ) -> Self {
+        // Same lookup requirements as MulHCoreChip, since MUL rows also compute the
+        // full product.
+        debug_assert!(
+            range_tuple_chip.sizes()[0] == 1 << LIMB_BITS,
+            "First element of RangeTupleChecker must have size {}",
+            1 << LIMB_BITS
+        );
+        debug_assert!(
+            range_tuple_chip.sizes()[1] >= (1 << LIMB_BITS) * 2 * NUM_LIMBS as u32,
+            "Second element of RangeTupleChecker must have size of at least {}",
+            (1 << LIMB_BITS) * 2 * NUM_LIMBS as u32
+        );
+
+        Self {
+            air: MulFusedCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_tuple_bus: *range_tuple_chip.bus(),
+                mul_offset,
+                mulh_offset,
+            },
+            bitwise_lookup_chip,
+            range_tuple_chip,
+        }
+    }
+
+    /// Returns `None` for MUL, otherwise the MULH variant of the global `opcode`.
+    fn mulh_opcode(&self, opcode: usize) -> Option<MulHOpcode> {
+        if opcode == self.air.mul_offset + MulOpcode::MUL as usize {
+            None
+        } else {
+            Some(MulHOpcode::from_usize(opcode - self.air.mulh_offset))
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MulFusedCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    /// `None` if the instruction is MUL
+    pub opcode: Option<MulHOpcode>,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub a_mul: [T; NUM_LIMBS],
+    pub b_ext: T,
+    pub c_ext: T,
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for MulFusedCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = MulFusedCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = MulFusedCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let mulh_opcode = self.mulh_opcode(opcode.as_usize());
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        // MUL shares the unsigned product with MULHU, its output is the low half.
+        let (a, a_mul, carry, b_ext, c_ext) =
+            run_mulh::<NUM_LIMBS, LIMB_BITS>(mulh_opcode.unwrap_or(MulHOpcode::MULHU), &b, &c);
+
+        for i in 0..NUM_LIMBS {
+            self.range_tuple_chip.add_count(&[a_mul[i], carry[i]]);
+            self.range_tuple_chip
+                .add_count(&[a[i], carry[NUM_LIMBS + i]]);
+        }
+
+        if matches!(mulh_opcode, Some(MulHOpcode::MULH | MulHOpcode::MULHSU)) {
+            let b_sign_mask = if b_ext == 0 { 0 } else { 1 << (LIMB_BITS - 1) };
+            let c_sign_mask = if c_ext == 0 { 0 } else { 1 << (LIMB_BITS - 1) };
+            self.bitwise_lookup_chip.request_range(
+                (b[NUM_LIMBS - 1] - b_sign_mask) << 1,
+                (c[NUM_LIMBS - 1] - c_sign_mask)
+                    << ((mulh_opcode == Some(MulHOpcode::MULH)) as u32),
+            );
+        }
+
+        let output = if mulh_opcode.is_none() { a_mul } else { a };
+        let output = AdapterRuntimeContext::without_pc([output.map(F::from_canonical_u32)]);
+        let record = MulFusedCoreRecord {
+            opcode: mulh_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            a_mul: a_mul.map(F::from_canonical_u32),
+            b_ext: F::from_canonical_u32(b_ext),
+            c_ext: F::from_canonical_u32(c_ext),
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        match self.mulh_opcode(opcode) {
+            Some(mulh_opcode) => format!("{:?}", mulh_opcode),
+            None => format!("{:?}", MulOpcode::MUL),
+        }
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut MulFusedCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.a_mul = record.a_mul;
+        row_slice.b_ext = record.b_ext;
+        row_slice.c_ext = record.c_ext;
+        row_slice.opcode_mul_flag = F::from_bool(record.opcode.is_none());
+        row_slice.opcode_mulh_flag = F::from_bool(record.opcode == Some(MulHOpcode::MULH));
+        row_slice.opcode_mulhsu_flag = F::from_bool(record.opcode == Some(MulHOpcode::MULHSU));
+        row_slice.opcode_mulhu_flag = F::from_bool(record.opcode == Some(MulHOpcode::MULHU));
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,201 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, TestAdapterChip, VmChipTestBuilder},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
+        RANGE_TUPLE_CHECKER_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::{MulHOpcode, MulOpcode};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use crate::{
+    adapters::{Rv32MultAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    mul_fused::{MulFusedCoreChip, MulFusedCoreCols, Rv32MulFusedChip},
+    mulh::run_mulh,
+};
+
+type F = BabyBear;
+
+const MAX_NUM_LIMBS: u32 = 32;
+
+fn setup_lookup_chips() -> (
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    Arc<RangeTupleCheckerChip<2>>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [1 << RV32_CELL_BITS, MAX_NUM_LIMBS * (1 << RV32_CELL_BITS)],
+    );
+    (
+        Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+            bitwise_bus,
+        )),
+        Arc::new(RangeTupleCheckerChip::new(range_tuple_bus)),
+    )
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_mul_fused_rand_test() {
+    let mut rng = create_seeded_rng();
+    let (bitwise_chip, range_tuple_checker) = setup_lookup_chips();
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MulFusedChip::<F>::new(
+        Rv32MultAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        MulFusedCoreChip::new(
+            bitwise_chip.clone(),
+            range_tuple_checker.clone(),
+            MulOpcode::default_offset(),
+            MulHOpcode::default_offset(),
+        ),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..200 {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let rs1 = gen_pointer(&mut rng, 4);
+        let rs2 = gen_pointer(&mut rng, 4);
+        let rd = gen_pointer(&mut rng, 4);
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs1, b.map(F::from_canonical_u32));
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs2, c.map(F::from_canonical_u32));
+
+        let (opcode, expected) = match rng.gen_range(0..4) {
+            0 => (
+                VmOpcode::with_default_offset(MulOpcode::MUL),
+                run_mulh::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(MulHOpcode::MULHU, &b, &c).1,
+            ),
+            i => {
+                let mulh_opcode = MulHOpcode::from_usize(i - 1);
+                (
+                    VmOpcode::with_default_offset(mulh_opcode),
+                    run_mulh::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(mulh_opcode, &b, &c).0,
+                )
+            }
+        };
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(opcode, [rd, rs1, rs2, 1, 0]),
+        );
+        assert_eq!(
+            expected.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(bitwise_chip)
+        .load(range_tuple_checker)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the write part of the trace and check that the core chip throws the expected error.
+// A dummy adapter is used so memory interactions don't indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32MulFusedTestChip<F> =
+    VmChipWrapper<F, TestAdapterChip<F>, MulFusedCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>>;
+
+#[test]
+fn rv32_mul_fused_signed_ext_negative_test() {
+    // MUL must treat its operands as unsigned, so a row that sign extends b is rejected
+    // even though the low half it writes back is unchanged.
+    let b = [197, 85, 150, 232];
+    let c = [51, 109, 78, 142];
+    let (bitwise_chip, range_tuple_chip) = setup_lookup_chips();
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MulFusedTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[b.map(F::from_canonical_u32), c.map(F::from_canonical_u32)].concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        MulFusedCoreChip::new(
+            bitwise_chip.clone(),
+            range_tuple_chip.clone(),
+            MulOpcode::default_offset(),
+            MulHOpcode::default_offset(),
+        ),
+        tester.memory_controller(),
+    );
+
+    tester.execute(
+        &mut chip,
+        Instruction::from_usize(
+            VmOpcode::with_default_offset(MulOpcode::MUL),
+            [0, 0, 0, 1, 0],
+        ),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let (a, a_mul, carry, b_ext, c_ext) =
+        run_mulh::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(MulHOpcode::MULHSU, &b, &c);
+    assert_ne!(b_ext, 0);
+
+    range_tuple_chip.clear();
+    for i in 0..RV32_REGISTER_NUM_LIMBS {
+        range_tuple_chip.add_count(&[a_mul[i], carry[i]]);
+        range_tuple_chip.add_count(&[a[i], carry[RV32_REGISTER_NUM_LIMBS + i]]);
+    }
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut MulFusedCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a = a.map(F::from_canonical_u32);
+        cols.a_mul = a_mul.map(F::from_canonical_u32);
+        cols.b_ext = F::from_canonical_u32(b_ext);
+        cols.c_ext = F::from_canonical_u32(c_ext);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .load(range_tuple_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
@@ -86,75 +86,24 @@ where
         });
         builder.assert_bool(is_valid.clone());
 
-        let b = &cols.b;
-        let c = &cols.c;
-        let carry_divide = AB::F::from_canonical_u32(1 << LIMB_BITS).inverse();
-
-        // Note b * c = a << LIMB_BITS + a_mul, in order to constrain that a is correct we
-        // need to compute the carries generated by a_mul.
-        let a_mul = &cols.a_mul;
-        let mut carry_mul: [AB::Expr; NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
-
-        for i in 0..NUM_LIMBS {
-            let expected_limb = if i == 0 {
-                AB::Expr::ZERO
-            } else {
-                carry_mul[i - 1].clone()
-            } + (0..=i).fold(AB::Expr::ZERO, |ac, k| ac + (b[k] * c[i - k]));
-            carry_mul[i] = AB::Expr::from(carry_divide) * (expected_limb - a_mul[i]);
-        }
-
-        for (a_mul, carry_mul) in a_mul.iter().zip(carry_mul.iter()) {
-            self.range_tuple_bus
-                .send(vec![(*a_mul).into(), carry_mul.clone()])
-                .eval(builder, is_valid.clone());
-        }
-
-        // We can now constrain that a is correct using carry_mul[NUM_LIMBS - 1]
-        let a = &cols.a;
-        let mut carry: [AB::Expr; NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
-
-        for j in 0..NUM_LIMBS {
-            let expected_limb = if j == 0 {
-                carry_mul[NUM_LIMBS - 1].clone()
-            } else {
-                carry[j - 1].clone()
-            } + ((j + 1)..NUM_LIMBS)
-                .fold(AB::Expr::ZERO, |acc, k| acc + (b[k] * c[NUM_LIMBS + j - k]))
-                + (0..(j + 1)).fold(AB::Expr::ZERO, |acc, k| {
-                    acc + (b[k] * cols.c_ext) + (c[k] * cols.b_ext)
-                });
-            carry[j] = AB::Expr::from(carry_divide) * (expected_limb - a[j]);
-        }
-
-        for (a, carry) in a.iter().zip(carry.iter()) {
-            self.range_tuple_bus
-                .send(vec![(*a).into(), carry.clone()])
-                .eval(builder, is_valid.clone());
-        }
-
-        // Check that b_ext and c_ext are correct using bitwise lookup. We check
-        // both b and c when the opcode is MULH, and only b when MULHSU.
-        let sign_mask = AB::F::from_canonical_u32(1 << (LIMB_BITS - 1));
-        let ext_inv = AB::F::from_canonical_u32((1 << LIMB_BITS) - 1).inverse();
-        let b_sign = cols.b_ext * ext_inv;
-        let c_sign = cols.c_ext * ext_inv;
-
-        builder.assert_bool(b_sign.clone());
-        builder.assert_bool(c_sign.clone());
-        builder
-            .when(cols.opcode_mulhu_flag)
-            .assert_zero(b_sign.clone());
-        builder
-            .when(cols.opcode_mulhu_flag + cols.opcode_mulhsu_flag)
-            .assert_zero(c_sign.clone());
-
-        self.bitwise_lookup_bus
-            .send_range(
-                AB::Expr::from_canonical_u32(2) * (b[NUM_LIMBS - 1] - b_sign * sign_mask),
-                (cols.opcode_mulh_flag + AB::Expr::ONE) * (c[NUM_LIMBS - 1] - c_sign * sign_mask),
-            )
-            .eval(builder, cols.opcode_mulh_flag + cols.opcode_mulhsu_flag);
+        eval_mulh::<AB, NUM_LIMBS, LIMB_BITS>(
+            builder,
+            self.bitwise_lookup_bus,
+            self.range_tuple_bus,
+            MulHEvalCols {
+                a: &cols.a,
+                b: &cols.b,
+                c: &cols.c,
+                a_mul: &cols.a_mul,
+                b_ext: cols.b_ext,
+                c_ext: cols.c_ext,
+            },
+            is_valid.clone(),
+            cols.opcode_mulhu_flag.into(),
+            cols.opcode_mulhu_flag + cols.opcode_mulhsu_flag,
+            cols.opcode_mulh_flag.into(),
+            cols.opcode_mulh_flag + cols.opcode_mulhsu_flag,
+        );
 
         let expected_opcode = flags.iter().zip(MulHOpcode::iter()).fold(
             AB::Expr::ZERO,
@@ -176,6 +125,104 @@ where
     }
 }
 
+/// Borrowed view of the columns shared by every chip that computes a full
+/// `2 * NUM_LIMBS` product of `b` and `c`, where `a_mul` is the low half and `a`
+/// is the high half.
+pub(crate) struct MulHEvalCols<'a, T, const NUM_LIMBS: usize> {
+    pub a: &'a [T; NUM_LIMBS],
+    pub b: &'a [T; NUM_LIMBS],
+    pub c: &'a [T; NUM_LIMBS],
+    pub a_mul: &'a [T; NUM_LIMBS],
+    pub b_ext: T,
+    pub c_ext: T,
+}
+
+/// Constrains `b * c == (a << (NUM_LIMBS * LIMB_BITS)) + a_mul`, where `b` and `c` are
+/// extended by `b_ext` and `c_ext`. The extensions are forced to zero when `b_unsigned`
+/// and `c_unsigned` respectively are set, and are sign checked `sign_check_count` times.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn eval_mulh<AB: InteractionBuilder, const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    builder: &mut AB,
+    bitwise_lookup_bus: BitwiseOperationLookupBus,
+    range_tuple_bus: RangeTupleCheckerBus<2>,
+    cols: MulHEvalCols<AB::Var, NUM_LIMBS>,
+    is_valid: AB::Expr,
+    b_unsigned: AB::Expr,
+    c_unsigned: AB::Expr,
+    c_shift: AB::Expr,
+    sign_check_count: AB::Expr,
+) {
+    let MulHEvalCols {
+        a,
+        b,
+        c,
+        a_mul,
+        b_ext,
+        c_ext,
+    } = cols;
+    let carry_divide = AB::F::from_canonical_u32(1 << LIMB_BITS).inverse();
+
+    // Note b * c = a << LIMB_BITS + a_mul, in order to constrain that a is correct we
+    // need to compute the carries generated by a_mul.
+    let mut carry_mul: [AB::Expr; NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
+
+    for i in 0..NUM_LIMBS {
+        let expected_limb = if i == 0 {
+            AB::Expr::ZERO
+        } else {
+            carry_mul[i - 1].clone()
+        } + (0..=i).fold(AB::Expr::ZERO, |ac, k| ac + (b[k] * c[i - k]));
+        carry_mul[i] = AB::Expr::from(carry_divide) * (expected_limb - a_mul[i]);
+    }
+
+    for (a_mul, carry_mul) in a_mul.iter().zip(carry_mul.iter()) {
+        range_tuple_bus
+            .send(vec![(*a_mul).into(), carry_mul.clone()])
+            .eval(builder, is_valid.clone());
+    }
+
+    // We can now constrain that a is correct using carry_mul[NUM_LIMBS - 1]
+    let mut carry: [AB::Expr; NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
+
+    for j in 0..NUM_LIMBS {
+        let expected_limb = if j == 0 {
+            carry_mul[NUM_LIMBS - 1].clone()
+        } else {
+            carry[j - 1].clone()
+        } + ((j + 1)..NUM_LIMBS)
+            .fold(AB::Expr::ZERO, |acc, k| acc + (b[k] * c[NUM_LIMBS + j - k]))
+            + (0..(j + 1)).fold(AB::Expr::ZERO, |acc, k| {
+                acc + (b[k] * c_ext) + (c[k] * b_ext)
+            });
+        carry[j] = AB::Expr::from(carry_divide) * (expected_limb - a[j]);
+    }
+
+    for (a, carry) in a.iter().zip(carry.iter()) {
+        range_tuple_bus
+            .send(vec![(*a).into(), carry.clone()])
+            .eval(builder, is_valid.clone());
+    }
+
+    // Check that b_ext and c_ext are correct using bitwise lookup. c is shifted
+    // left by one when c_shift is set so its sign bit is also checked.
+    let sign_mask = AB::F::from_canonical_u32(1 << (LIMB_BITS - 1));
+    let ext_inv = AB::F::from_canonical_u32((1 << LIMB_BITS) - 1).inverse();
+    let b_sign = b_ext * ext_inv;
+    let c_sign = c_ext * ext_inv;
+
+    builder.assert_bool(b_sign.clone());
+    builder.assert_bool(c_sign.clone());
+    builder.when(b_unsigned).assert_zero(b_sign.clone());
+    builder.when(c_unsigned).assert_zero(c_sign.clone());
+
+    bitwise_lookup_bus
+        .send_range(
+            AB::Expr::from_canonical_u32(2) * (b[NUM_LIMBS - 1] - b_sign * sign_mask),
+            (c_shift + AB::Expr::ONE) * (c[NUM_LIMBS - 1] - c_sign * sign_mask),
+        )
+        .eval(builder, sign_check_count);
+}
+
 #[derive(Debug)]
 pub struct MulHCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
     pub air: MulHCoreAir<NUM_LIMBS, LIMB_BITS>,
@@ -302,7 +349,7 @@ where
 }
 
 // returns mulh[[s]u], mul, carry, x_ext, y_ext
-pub(super) fn run_mulh<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_mulh<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     opcode: MulHOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
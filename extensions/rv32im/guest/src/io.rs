@@ -57,3 +57,40 @@ pub fn raw_print_str_from_bytes(msg_ptr: *const u8, len: usize) {
         PhantomImm::PrintStr as u16
     );
 }
+
+/// Start a cycle tracker span named by the UTF-8 string at `name_ptr` with length `len`.
+#[inline(always)]
+pub fn raw_cycle_tracker_start(name_ptr: *const u8, len: usize) {
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        name_ptr,
+        len,
+        PhantomImm::CycleTrackerStart as u16
+    );
+}
+
+/// End the cycle tracker span named by the UTF-8 string at `name_ptr` with length `len`.
+#[inline(always)]
+pub fn raw_cycle_tracker_end(name_ptr: *const u8, len: usize) {
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        name_ptr,
+        len,
+        PhantomImm::CycleTrackerEnd as u16
+    );
+}
+
+/// Print the backtrace stored as `len` bytes of little-endian 32-bit return addresses at `ptr`
+/// to host stderr, together with the pc of this instruction.
+#[inline(always)]
+pub fn raw_print_backtrace(ptr: *const u8, len: usize) {
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        ptr,
+        len,
+        PhantomImm::PrintBacktrace as u16
+    );
+}
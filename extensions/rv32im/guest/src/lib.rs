@@ -25,4 +25,7 @@ pub const CSRRW_FUNCT3: u8 = 0b001;
 pub enum PhantomImm {
     HintInput = 0,
     PrintStr,
+    CycleTrackerStart,
+    CycleTrackerEnd,
+    PrintBacktrace,
 }
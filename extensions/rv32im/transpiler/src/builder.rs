@@ -0,0 +1,59 @@
+use openvm_instructions::{
+    builder::InstructionBuilder, instruction::Instruction, riscv::RV32_REGISTER_AS, UsizeOpcode,
+    VmOpcode,
+};
+use openvm_stark_backend::p3_field::Field;
+
+use crate::{BaseAluOpcode, DivRemOpcode, LessThanOpcode, MulHOpcode, MulOpcode, ShiftOpcode};
+
+/// Builders for the register-to-register RV32IM instructions, with the same operands as the
+/// transpiled R-type instructions: `rd <- op(rs1, rs2)`.
+pub trait Rv32InstructionBuilder<F> {
+    fn base_alu(op: BaseAluOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+    fn shift(op: ShiftOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+    fn less_than(op: LessThanOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+    fn mul(rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+    fn mulh(op: MulHOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+    fn divrem(op: DivRemOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F>;
+}
+
+fn rv32_r_type<F: Field>(
+    op: impl UsizeOpcode,
+    rd: usize,
+    rs1: usize,
+    rs2: usize,
+) -> Instruction<F> {
+    InstructionBuilder::r_type(
+        VmOpcode::with_default_offset(op),
+        rd,
+        rs1,
+        rs2,
+        RV32_REGISTER_AS,
+    )
+}
+
+impl<F: Field> Rv32InstructionBuilder<F> for InstructionBuilder<F> {
+    fn base_alu(op: BaseAluOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(op, rd, rs1, rs2)
+    }
+
+    fn shift(op: ShiftOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(op, rd, rs1, rs2)
+    }
+
+    fn less_than(op: LessThanOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(op, rd, rs1, rs2)
+    }
+
+    fn mul(rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(MulOpcode::MUL, rd, rs1, rs2)
+    }
+
+    fn mulh(op: MulHOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(op, rd, rs1, rs2)
+    }
+
+    fn divrem(op: DivRemOpcode, rd: usize, rs1: usize, rs2: usize) -> Instruction<F> {
+        rv32_r_type(op, rd, rs1, rs2)
+    }
+}
@@ -1,8 +1,8 @@
 use std::marker::PhantomData;
 
 use openvm_instructions::{
-    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, PhantomDiscriminant, SystemOpcode,
-    VmOpcode,
+    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, PhantomDiscriminant, SysPhantom,
+    SystemOpcode, VmOpcode,
 };
 use openvm_rv32im_guest::{
     PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_STORE_W_FUNCT3, PHANTOM_FUNCT3, REVEAL_FUNCT3,
@@ -19,8 +19,10 @@ use rrs_lib::{
     process_instruction,
 };
 
+mod builder;
 mod instructions;
 pub mod rrs;
+pub use builder::*;
 pub use instructions::*;
 
 #[derive(Default)]
@@ -84,6 +86,25 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
                         F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
                         0,
                     ),
+                    // The span name is read from memory, which is signaled by `c_upper = 1`.
+                    PhantomImm::CycleTrackerStart => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::CtStart as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        1,
+                    ),
+                    PhantomImm::CycleTrackerEnd => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::CtEnd as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        1,
+                    ),
+                    PhantomImm::PrintBacktrace => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::Backtrace as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        0,
+                    ),
                 })
             }
             (RV32_ALU_OPCODE, _) => {
@@ -0,0 +1,10 @@
+//! Sorting of heap arrays for RV32 guests. The sorted order is computed during execution, and the
+//! chip proves that the output is an ascending permutation of the input.
+mod sort;
+pub use sort::*;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
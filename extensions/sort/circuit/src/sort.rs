@@ -0,0 +1,499 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cell::RefCell,
+    iter::once,
+    sync::Arc,
+};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::{assert_array_eq, next_power_of_two_or_zero, not},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_rv32im_circuit::adapters::{
+    abstract_compose, compose, read_rv32_register, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_sort_guest::MAX_SORT_LEN;
+use openvm_sort_transpiler::Rv32SortOpcode;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::{InteractionBuilder, InteractionType},
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+/// Number of register reads at the start of an instruction: `rs1`, `rs2` and `rd`.
+const NUM_REGISTER_READS: usize = 3;
+
+/// One row per word of the input. The instruction operands and register values are repeated on
+/// every row of the instruction, and the registers are read on its last row.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32SortCols<T> {
+    pub is_valid: T,
+    pub is_last: T,
+
+    pub pc: T,
+    pub start_timestamp: T,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+
+    /// The output pointer, read from `rd`.
+    pub dst: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The input pointer, read from `rs1`.
+    pub src: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The number of words, read from `rs2`.
+    pub len: [T; RV32_REGISTER_NUM_LIMBS],
+    pub dst_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub src_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub len_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+
+    /// Index of the row within the instruction.
+    pub idx: T,
+    pub input: [T; RV32_REGISTER_NUM_LIMBS],
+    pub output: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The difference between the next output and this one, or zero on the last row.
+    pub diff: [T; RV32_REGISTER_NUM_LIMBS],
+    pub input_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub output_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+}
+
+/// Proves that the output array is the input array sorted in ascending order:
+/// * the outputs are a permutation of the inputs, by a multiset check on `permutation_bus` where
+///   each message is tagged with the start timestamp of its instruction, and
+/// * each output plus a `diff` of four range checked bytes, without overflow, is the next output.
+///
+/// All inputs are read before the first output is written, so the arrays may overlap.
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32SortAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub permutation_bus: usize,
+    /// The max number of bits for an address in memory
+    address_bits: usize,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32SortAir {
+    fn width(&self) -> usize {
+        Rv32SortCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32SortAir {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32SortAir {}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32SortAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Rv32SortCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Rv32SortCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.is_valid);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_last).assert_one(local.is_valid);
+
+        // The rows of an instruction are consecutive with indices 0, 1, ..., and the padding rows
+        // come after all instructions.
+        builder.when_first_row().assert_zero(local.idx);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_valid, local.is_last);
+        builder
+            .when_transition()
+            .when(local.is_last)
+            .assert_zero(next.idx);
+        builder
+            .when_transition()
+            .when(not(local.is_valid))
+            .assert_zero(next.is_valid);
+
+        {
+            let mut when_continued = builder
+                .when_transition()
+                .when(local.is_valid - local.is_last);
+            when_continued.assert_one(next.is_valid);
+            when_continued.assert_eq(next.idx, local.idx + AB::Expr::ONE);
+            for (a, b) in [
+                (next.pc, local.pc),
+                (next.start_timestamp, local.start_timestamp),
+                (next.rd_ptr, local.rd_ptr),
+                (next.rs1_ptr, local.rs1_ptr),
+                (next.rs2_ptr, local.rs2_ptr),
+            ] {
+                when_continued.assert_eq(a, b);
+            }
+            assert_array_eq(&mut when_continued, next.dst, local.dst);
+            assert_array_eq(&mut when_continued, next.src, local.src);
+            assert_array_eq(&mut when_continued, next.len, local.len);
+
+            // next.output = output + diff, bytewise with boolean carries and no final carry
+            let carry_divide = AB::F::from_canonical_u32(1 << RV32_CELL_BITS).inverse();
+            let mut carry = AB::Expr::ZERO;
+            for i in 0..RV32_REGISTER_NUM_LIMBS {
+                carry = AB::Expr::from(carry_divide)
+                    * (local.output[i] + local.diff[i] + carry - next.output[i]);
+                if i + 1 < RV32_REGISTER_NUM_LIMBS {
+                    when_continued.assert_bool(carry.clone());
+                } else {
+                    when_continued.assert_zero(carry.clone());
+                }
+            }
+        }
+        for pair in local.diff.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0], pair[1])
+                .eval(builder, local.is_valid);
+        }
+
+        // The outputs are a permutation of the inputs. The inputs are bytes read from memory, so
+        // the outputs are bytes too.
+        builder.push_interaction(
+            self.permutation_bus,
+            once(local.start_timestamp).chain(local.input),
+            local.is_valid,
+            InteractionType::Send,
+        );
+        builder.push_interaction(
+            self.permutation_bus,
+            once(local.start_timestamp).chain(local.output),
+            local.is_valid,
+            InteractionType::Receive,
+        );
+
+        // Register reads, once per instruction
+        let register_as = AB::F::from_canonical_u32(RV32_REGISTER_AS);
+        for (i, (ptr, val, aux)) in [
+            (local.rs1_ptr, local.src, &local.src_aux),
+            (local.rs2_ptr, local.len, &local.len_aux),
+            (local.rd_ptr, local.dst, &local.dst_aux),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(register_as, ptr),
+                    val,
+                    local.start_timestamp + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, local.is_last);
+        }
+
+        // The length is less than 2^24, so it does not wrap around the field, and the pointers
+        // are less than 2^address_bits, as in the heap adapters.
+        builder.when(local.is_last).assert_zero(local.len[3]);
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.address_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                local.src[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                local.dst[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, local.is_last);
+
+        let len: AB::Expr = abstract_compose(local.len);
+        builder
+            .when(local.is_last)
+            .assert_eq(local.idx + AB::Expr::ONE, len.clone());
+
+        // Heap accesses: all inputs are read, then all outputs are written.
+        let heap_as = AB::F::from_canonical_u32(RV32_MEMORY_AS);
+        let heap_timestamp =
+            local.start_timestamp + AB::F::from_canonical_usize(NUM_REGISTER_READS);
+        let word_offset = local.idx * AB::F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS);
+        let src: AB::Expr = abstract_compose(local.src);
+        let dst: AB::Expr = abstract_compose(local.dst);
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(heap_as, src + word_offset.clone()),
+                local.input,
+                heap_timestamp.clone() + local.idx,
+                &local.input_aux,
+            )
+            .eval(builder, local.is_valid);
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(heap_as, dst + word_offset),
+                local.output,
+                heap_timestamp.clone() + len.clone() + local.idx,
+                &local.output_aux,
+            )
+            .eval(builder, local.is_valid);
+
+        self.execution_bridge
+            .execute(
+                AB::Expr::from_canonical_usize(Rv32SortOpcode::SORT_U32 as usize + self.offset),
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    register_as.into(),
+                    heap_as.into(),
+                ],
+                ExecutionState::new(local.pc, local.start_timestamp),
+                ExecutionState::<AB::Expr>::new(
+                    local.pc + AB::F::from_canonical_u32(DEFAULT_PC_STEP),
+                    heap_timestamp + len * AB::F::TWO,
+                ),
+            )
+            .eval(builder, local.is_last);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32SortRecord<F: Field> {
+    pub from_state: ExecutionState<F>,
+    pub instruction: Instruction<F>,
+    pub src_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub len_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub dst_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub input_reads: Vec<MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    pub output_writes: Vec<MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+}
+
+pub struct Rv32SortChip<F: Field> {
+    pub air: Rv32SortAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    memory: MemoryControllerRef<F>,
+    records: Vec<Rv32SortRecord<F>>,
+    /// Whether records are dropped, see [InstructionExecutor::skip_records]. `height` still counts
+    /// them.
+    skip_records: bool,
+    height: usize,
+}
+
+impl<F: PrimeField32> Rv32SortChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        permutation_bus: usize,
+        offset: usize,
+    ) -> Self {
+        let memory = RefCell::borrow(&memory_controller);
+        let address_bits = memory.mem_config().pointer_max_bits;
+        assert!(
+            RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - address_bits < RV32_CELL_BITS,
+            "address_bits={address_bits} needs to be large enough for high limb range check"
+        );
+        let air = Rv32SortAir {
+            execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+            memory_bridge: memory.memory_bridge(),
+            bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+            permutation_bus,
+            address_bits,
+            offset,
+        };
+        drop(memory);
+        Self {
+            air,
+            bitwise_lookup_chip,
+            memory: memory_controller,
+            records: vec![],
+            skip_records: false,
+            height: 0,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32SortChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        debug_assert_eq!(
+            Rv32SortOpcode::from_usize(opcode.local_opcode_idx(self.air.offset)),
+            Rv32SortOpcode::SORT_U32
+        );
+
+        let mut memory = RefCell::borrow_mut(&self.memory);
+        let (src_read, src) = read_rv32_register(&mut memory, d, b);
+        let (len_read, len) = read_rv32_register(&mut memory, d, c);
+        let (dst_read, dst) = read_rv32_register(&mut memory, d, a);
+        let len = len as usize;
+        let in_bounds =
+            |ptr: u32| ptr as usize + RV32_REGISTER_NUM_LIMBS * len <= (1 << self.air.address_bits);
+        if len == 0 || len > MAX_SORT_LEN || !in_bounds(src) || !in_bounds(dst) {
+            return Err(ExecutionError::Fail { pc: from_state.pc });
+        }
+
+        let word_address = |ptr: u32, i: usize| {
+            F::from_canonical_usize(ptr as usize + RV32_REGISTER_NUM_LIMBS * i)
+        };
+        let input_reads: Vec<_> = (0..len)
+            .map(|i| memory.read(e, word_address(src, i)))
+            .collect();
+        let mut sorted: Vec<u32> = input_reads.iter().map(|read| compose(read.data)).collect();
+        sorted.sort_unstable();
+        let output_writes: Vec<_> = sorted
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                memory.write(
+                    e,
+                    word_address(dst, i),
+                    word.to_le_bytes().map(F::from_canonical_u8),
+                )
+            })
+            .collect();
+
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.address_bits;
+        self.bitwise_lookup_chip.request_range(
+            src_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32() << limb_shift_bits,
+            dst_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32() << limb_shift_bits,
+        );
+        for i in 0..len {
+            let diff = sorted.get(i + 1).map_or(0, |next| next - sorted[i]);
+            let diff = diff.to_le_bytes().map(u32::from);
+            self.bitwise_lookup_chip.request_range(diff[0], diff[1]);
+            self.bitwise_lookup_chip.request_range(diff[2], diff[3]);
+        }
+
+        if !self.skip_records {
+            self.records.push(Rv32SortRecord {
+                from_state: from_state.map(F::from_canonical_u32),
+                instruction,
+                src_read,
+                len_read,
+                dst_read,
+                input_reads,
+                output_writes,
+            });
+        }
+        self.height += len;
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: memory.timestamp(),
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", Rv32SortOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn skip_records(&mut self) {
+        self.skip_records = true;
+    }
+}
+
+impl<F: Field> ChipUsageGetter for Rv32SortChip<F> {
+    fn air_name(&self) -> String {
+        "Rv32SortAir".to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.height
+    }
+
+    fn trace_width(&self) -> usize {
+        Rv32SortCols::<F>::width()
+    }
+}
+
+impl<F: PrimeField32> Rv32SortChip<F> {
+    fn generate_trace(self) -> RowMajorMatrix<F> {
+        assert!(
+            !self.skip_records,
+            "cannot generate the trace of a chip which skipped its records"
+        );
+        let width = self.trace_width();
+        let height = next_power_of_two_or_zero(self.height);
+        let mut flat_trace = F::zero_vec(width * height);
+        let aux_cols_factory = RefCell::borrow(&self.memory).aux_cols_factory();
+
+        let mut rows = flat_trace.chunks_mut(width);
+        for record in self.records {
+            let len = record.input_reads.len();
+            let outputs: Vec<u32> = record
+                .output_writes
+                .iter()
+                .map(|write| compose(write.data))
+                .collect();
+            for (idx, (input_read, output_write)) in record
+                .input_reads
+                .into_iter()
+                .zip(record.output_writes)
+                .enumerate()
+            {
+                let cols: &mut Rv32SortCols<F> = rows.next().unwrap().borrow_mut();
+                cols.is_valid = F::ONE;
+                cols.pc = record.from_state.pc;
+                cols.start_timestamp = record.from_state.timestamp;
+                cols.rd_ptr = record.instruction.a;
+                cols.rs1_ptr = record.instruction.b;
+                cols.rs2_ptr = record.instruction.c;
+                cols.dst = record.dst_read.data;
+                cols.src = record.src_read.data;
+                cols.len = record.len_read.data;
+                if idx + 1 == len {
+                    cols.is_last = F::ONE;
+                    cols.dst_aux = aux_cols_factory.make_read_aux_cols(record.dst_read);
+                    cols.src_aux = aux_cols_factory.make_read_aux_cols(record.src_read);
+                    cols.len_aux = aux_cols_factory.make_read_aux_cols(record.len_read);
+                } else {
+                    cols.diff = (outputs[idx + 1] - outputs[idx])
+                        .to_le_bytes()
+                        .map(F::from_canonical_u8);
+                }
+
+                cols.idx = F::from_canonical_usize(idx);
+                cols.input = input_read.data;
+                cols.output = output_write.data;
+                cols.input_aux = aux_cols_factory.make_read_aux_cols(input_read);
+                cols.output_aux = aux_cols_factory.make_write_aux_cols(output_write);
+            }
+        }
+        RowMajorMatrix::new(flat_trace, width)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32SortChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput::simple_no_pis(self.air(), self.generate_trace())
+    }
+}
@@ -0,0 +1,190 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, Tamper, VmChipTestBuilder},
+    ExecutionError, ExecutionState, InstructionExecutor, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode, VmOpcode,
+};
+use openvm_sort_guest::MAX_SORT_LEN;
+use openvm_sort_transpiler::Rv32SortOpcode;
+use openvm_stark_backend::{
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{Rv32SortChip, Rv32SortCols};
+
+type F = BabyBear;
+
+const SORT_PERMUTATION_BUS: usize = BITWISE_OP_LOOKUP_BUS + 1;
+
+fn write_register(tester: &mut VmChipTestBuilder<F>, ptr: usize, value: u32) {
+    tester.write(
+        RV32_REGISTER_AS as usize,
+        ptr,
+        value.to_le_bytes().map(F::from_canonical_u8),
+    );
+}
+
+/// Sorts `input`, stored at `src`, into `dst` and checks the output.
+fn execute_sort(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32SortChip<F>,
+    rng: &mut StdRng,
+    input: &[u32],
+    src: usize,
+    dst: usize,
+) {
+    for (i, word) in input.iter().enumerate() {
+        tester.write(
+            RV32_MEMORY_AS as usize,
+            src + 4 * i,
+            word.to_le_bytes().map(F::from_canonical_u8),
+        );
+    }
+    let [rd, rs1, rs2] = [0; 3].map(|_| gen_pointer(rng, 4));
+    write_register(tester, rd, dst as u32);
+    write_register(tester, rs1, src as u32);
+    write_register(tester, rs2, input.len() as u32);
+    tester.execute(
+        chip,
+        Instruction::from_usize(
+            VmOpcode::from_usize(Rv32SortOpcode::SORT_U32.with_default_offset()),
+            [rd, rs1, rs2, 1, 2],
+        ),
+    );
+
+    let mut expected = input.to_vec();
+    expected.sort_unstable();
+    for (i, word) in expected.into_iter().enumerate() {
+        let output = tester
+            .read::<4>(RV32_MEMORY_AS as usize, dst + 4 * i)
+            .map(|x| x.as_canonical_u32() as u8);
+        assert_eq!(u32::from_le_bytes(output), word);
+    }
+}
+
+#[test]
+fn test_sort_chip() {
+    let mut rng = create_seeded_rng();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = Rv32SortChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        SORT_PERMUTATION_BUS,
+        Rv32SortOpcode::default_offset(),
+    );
+
+    let random: Vec<u32> = (0..37).map(|_| rng.gen()).collect();
+    // Repeated words, including the extremes.
+    let repeated: Vec<u32> = (0..20).map(|i| [0, 7, u32::MAX][i % 3]).collect();
+    execute_sort(&mut tester, &mut chip, &mut rng, &random, 0x1000, 0x2000);
+    execute_sort(&mut tester, &mut chip, &mut rng, &repeated, 0x3000, 0x4000);
+    execute_sort(&mut tester, &mut chip, &mut rng, &[42], 0x5000, 0x6000);
+    // In place, and with overlapping arrays.
+    execute_sort(&mut tester, &mut chip, &mut rng, &random, 0x7000, 0x7000);
+    execute_sort(&mut tester, &mut chip, &mut rng, &random, 0x8000, 0x8004);
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_sort_out_of_order_output_negative() {
+    let mut rng = create_seeded_rng();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = Rv32SortChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        SORT_PERMUTATION_BUS,
+        Rv32SortOpcode::default_offset(),
+    );
+    execute_sort(&mut tester, &mut chip, &mut rng, &[3, 1, 2], 0x1000, 0x2000);
+
+    // Swap the first two outputs, 1 and 2, so the output is still a permutation of the input.
+    let indices = (0..Rv32SortCols::<F>::width()).collect::<Vec<_>>();
+    let cols: &Rv32SortCols<usize> = indices[..].borrow();
+    let tampers = [(0, 2), (1, 1)].map(|(row, value)| Tamper::Set {
+        row,
+        col: cols.output[0],
+        value: F::from_canonical_u32(value),
+    });
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_tamper(chip, &tampers)
+        .load(bitwise_chip)
+        .finalize();
+    // The permutation check still passes, but 2 plus a byte diff without carry is not 1.
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn test_sort_invalid_arguments() {
+    let mut rng = create_seeded_rng();
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS),
+    ));
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = Rv32SortChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip,
+        SORT_PERMUTATION_BUS,
+        Rv32SortOpcode::default_offset(),
+    );
+    let address_bits = tester
+        .memory_controller()
+        .borrow()
+        .mem_config()
+        .pointer_max_bits;
+
+    // The air requires the most significant byte of the length to be zero.
+    assert_eq!(MAX_SORT_LEN, (1 << 24) - 1);
+    for (src, dst, len) in [
+        (0x1000, 0x2000, 0),
+        (0x1000, 0x2000, MAX_SORT_LEN as u32 + 1),
+        // The arrays must end within the address space.
+        ((1 << address_bits) - 4, 0x2000, 2),
+        (0x1000, u32::MAX - 3, 1),
+    ] {
+        let [rd, rs1, rs2] = [0; 3].map(|_| gen_pointer(&mut rng, 4));
+        write_register(&mut tester, rd, dst);
+        write_register(&mut tester, rs1, src);
+        write_register(&mut tester, rs2, len);
+        let instruction = Instruction::from_usize(
+            VmOpcode::from_usize(Rv32SortOpcode::SORT_U32.with_default_offset()),
+            [rd, rs1, rs2, 1, 2],
+        );
+        let from_state = ExecutionState {
+            pc: 0,
+            timestamp: tester.memory_controller().borrow().timestamp(),
+        };
+        assert!(matches!(
+            chip.execute(instruction, from_state),
+            Err(ExecutionError::Fail { pc: 0 })
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+#![no_std]
+
+use strum_macros::FromRepr;
+
+/// This is custom-2 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x5b;
+pub const FUNCT3: u8 = 0b001;
+
+/// Maximum number of words sorted by one instruction. The chip requires the length to fit in three
+/// bytes.
+pub const MAX_SORT_LEN: usize = (1 << 24) - 1;
+
+/// The sort instructions. They take the output pointer in `rd`, the input pointer in `rs1` and
+/// the number of words in `rs2`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum SortBaseFunct7 {
+    /// Writes the input words to the output in ascending order.
+    SortU32 = 0,
+}
+
+/// Sorts `data` in ascending order.
+///
+/// The VM computes the sorted order out of circuit and only proves that the result is an
+/// ascending permutation of the input, so this costs a few trace rows per element instead of the
+/// comparisons and swaps of a sort in guest code. Panics if `data` is longer than
+/// [MAX_SORT_LEN].
+#[inline(always)]
+pub fn sort_u32(data: &mut [u32]) {
+    assert!(data.len() <= MAX_SORT_LEN);
+    #[cfg(not(target_os = "zkvm"))]
+    data.sort_unstable();
+    #[cfg(target_os = "zkvm")]
+    if !data.is_empty() {
+        openvm_platform::custom_insn_r!(
+            OPCODE,
+            FUNCT3,
+            SortBaseFunct7::SortU32 as u8,
+            data.as_mut_ptr(),
+            data.as_ptr(),
+            data.len()
+        );
+    }
+}
+
+/// Writes the words of `input` to `output` in ascending order, as [sort_u32]. Panics if the
+/// slices have different lengths.
+#[inline(always)]
+pub fn sort_u32_into(input: &[u32], output: &mut [u32]) {
+    assert_eq!(input.len(), output.len());
+    assert!(input.len() <= MAX_SORT_LEN);
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        output.copy_from_slice(input);
+        output.sort_unstable();
+    }
+    #[cfg(target_os = "zkvm")]
+    if !input.is_empty() {
+        openvm_platform::custom_insn_r!(
+            OPCODE,
+            FUNCT3,
+            SortBaseFunct7::SortU32 as u8,
+            output.as_mut_ptr(),
+            input.as_ptr(),
+            input.len()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_sort_guest::{SortBaseFunct7, FUNCT3, OPCODE};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// Sorting of the words of a heap array into another, which may be the same array.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x350]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32SortOpcode {
+    SORT_U32,
+}
+
+#[derive(Default)]
+pub struct SortTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for SortTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match SortBaseFunct7::from_repr(dec_insn.funct7 as u8)? {
+            SortBaseFunct7::SortU32 => Rv32SortOpcode::SORT_U32,
+        };
+        let instruction = from_r_type(local_opcode.with_default_offset(), 2, &dec_insn);
+        Some((instruction, 1))
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use openvm_table_transpiler::Rv32TableOpcode;
+
+use crate::{TableLookupBus, TableLookupChip};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct TableLookupCoreCols<T> {
+    pub table_id: [T; RV32_REGISTER_NUM_LIMBS],
+    pub key: [T; RV32_REGISTER_NUM_LIMBS],
+    pub value: [T; RV32_REGISTER_NUM_LIMBS],
+    pub is_valid: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TableLookupCoreAir {
+    pub bus: TableLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for TableLookupCoreAir {
+    fn width(&self) -> usize {
+        TableLookupCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for TableLookupCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for TableLookupCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &TableLookupCoreCols<_> = local_core.borrow();
+        builder.assert_bool(cols.is_valid);
+
+        // The table id and the key are register values, so their limbs are bytes. The value limbs
+        // are the bytes of a table entry.
+        let message: Vec<AB::Expr> = cols
+            .table_id
+            .into_iter()
+            .chain(cols.key)
+            .chain(cols.value)
+            .map(Into::into)
+            .collect();
+        self.bus
+            .send(builder, message.try_into().unwrap(), cols.is_valid);
+
+        let expected_opcode =
+            AB::Expr::from_canonical_usize(Rv32TableOpcode::LOOKUP as usize + self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.table_id.map(Into::into), cols.key.map(Into::into)].into(),
+            writes: [cols.value.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+/// Looks up the key in `rs2` in the table whose id is in `rs1` and writes the value to `rd`.
+#[derive(Debug)]
+pub struct TableLookupCoreChip {
+    pub air: TableLookupCoreAir,
+    /// The lookup tables, indexed by table id. They all use the same bus.
+    pub tables: Vec<Arc<TableLookupChip>>,
+}
+
+impl TableLookupCoreChip {
+    pub fn new(bus: TableLookupBus, tables: Vec<Arc<TableLookupChip>>, offset: usize) -> Self {
+        debug_assert!(tables
+            .iter()
+            .enumerate()
+            .all(|(id, table)| table.air.table_id as usize == id && table.bus() == bus));
+        Self {
+            air: TableLookupCoreAir { bus, offset },
+            tables,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TableLookupCoreRecord<T> {
+    pub table_id: [T; RV32_REGISTER_NUM_LIMBS],
+    pub key: [T; RV32_REGISTER_NUM_LIMBS],
+    pub value: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for TableLookupCoreChip
+where
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = TableLookupCoreRecord<F>;
+    type Air = TableLookupCoreAir;
+
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        assert_eq!(
+            Rv32TableOpcode::from_usize(opcode.local_opcode_idx(self.air.offset)),
+            Rv32TableOpcode::LOOKUP
+        );
+
+        let [table_id, key]: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let to_u32 = |limbs: [F; RV32_REGISTER_NUM_LIMBS]| {
+            u32::from_le_bytes(limbs.map(|x| x.as_canonical_u32() as u8))
+        };
+        let value = self
+            .tables
+            .get(to_u32(table_id) as usize)
+            .and_then(|table| table.request(to_u32(key)))
+            .ok_or(ExecutionError::Fail { pc: from_pc })?;
+        let value = value.to_le_bytes().map(F::from_canonical_u8);
+
+        let output = AdapterRuntimeContext::without_pc([value]);
+        Ok((
+            output,
+            TableLookupCoreRecord {
+                table_id,
+                key,
+                value,
+            },
+        ))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32TableOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut TableLookupCoreCols<_> = row_slice.borrow_mut();
+        row_slice.table_id = record.table_id;
+        row_slice.key = record.key;
+        row_slice.value = record.value;
+        row_slice.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
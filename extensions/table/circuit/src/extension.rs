@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
+        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::*;
+use openvm_rv32im_circuit::{
+    adapters::Rv32MultAdapterChip, Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor,
+    Rv32IoPeriphery, Rv32M, Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_table_transpiler::Rv32TableOpcode;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct TableRv32Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub table: TableExtension,
+}
+
+impl TableRv32Config {
+    pub fn with_tables(tables: Vec<Vec<(u32, u32)>>) -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            table: TableExtension::new(tables),
+        }
+    }
+}
+
+/// Constant lookup tables. Table `i` is `tables[i]`, a list of `(key, value)` entries with
+/// distinct keys, and must have between one and [MAX_TABLE_SIZE] entries.
+#[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
+pub struct TableExtension {
+    pub tables: Vec<Vec<(u32, u32)>>,
+}
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum TableExecutor<F: PrimeField32> {
+    Lookup(Rv32TableLookupChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum TablePeriphery<F: PrimeField32> {
+    TableLookup(Arc<TableLookupChip>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for TableExtension {
+    type Executor = TableExecutor<F>;
+    type Periphery = TablePeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bus = TableLookupBus::new(builder.new_lookup_bus_idx());
+
+        let tables: Vec<_> = self
+            .tables
+            .iter()
+            .enumerate()
+            .map(|(id, entries)| Arc::new(TableLookupChip::new(bus, id as u32, entries)))
+            .collect();
+        for table in &tables {
+            inventory.add_periphery_chip(table.clone());
+        }
+
+        let lookup_chip = Rv32TableLookupChip::new(
+            Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            TableLookupCoreChip::new(bus, tables, Rv32TableOpcode::default_offset()),
+            memory_controller,
+        );
+        inventory.add_executor(
+            lookup_chip,
+            Rv32TableOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
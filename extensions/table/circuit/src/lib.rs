@@ -0,0 +1,19 @@
+//! Lookups into constant tables for RV32 guests. Each table of the configuration is committed as
+//! a preprocessed trace at keygen, and an instruction looks up a key in a table by its id.
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+use openvm_rv32im_circuit::adapters::{Rv32MultAdapterAir, Rv32MultAdapterChip};
+
+mod core;
+pub use core::*;
+
+mod lookup;
+pub use lookup::*;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32TableLookupAir = VmAirWrapper<Rv32MultAdapterAir, TableLookupCoreAir>;
+pub type Rv32TableLookupChip<F> = VmChipWrapper<F, Rv32MultAdapterChip<F>, TableLookupCoreChip>;
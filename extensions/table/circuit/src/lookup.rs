@@ -0,0 +1,232 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    collections::HashMap,
+    mem::size_of,
+    sync::{atomic::AtomicU32, Arc},
+};
+
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::{InteractionBuilder, InteractionType},
+    p3_air::{Air, BaseAir, PairBuilder},
+    p3_field::{AbstractField, Field},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+/// Maximum number of entries of a lookup table.
+pub const MAX_TABLE_SIZE: usize = 1 << 20;
+
+/// Size of a message on the [TableLookupBus]: the table id, the key and the value, each as four
+/// little-endian bytes.
+pub const TABLE_LOOKUP_MESSAGE_SIZE: usize = 3 * RV32_REGISTER_NUM_LIMBS;
+
+/// Bus shared by all lookup tables. Messages are `[table_id, key, value]`, with every word split
+/// into bytes so that no word wraps around the field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableLookupBus {
+    pub index: usize,
+}
+
+impl TableLookupBus {
+    pub const fn new(index: usize) -> Self {
+        Self { index }
+    }
+
+    pub fn send<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        message: [impl Into<AB::Expr>; TABLE_LOOKUP_MESSAGE_SIZE],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_interaction(self.index, message, count, InteractionType::Send);
+    }
+
+    pub fn receive<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        message: [impl Into<AB::Expr>; TABLE_LOOKUP_MESSAGE_SIZE],
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_interaction(self.index, message, count, InteractionType::Receive);
+    }
+}
+
+#[derive(Default, AlignedBorrow, Copy, Clone)]
+#[repr(C)]
+pub struct TableLookupCols<T> {
+    pub mult: T,
+}
+
+#[derive(Default, AlignedBorrow, Copy, Clone)]
+#[repr(C)]
+pub struct TableLookupPreprocessedCols<T> {
+    pub key: [T; RV32_REGISTER_NUM_LIMBS],
+    pub value: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+pub const NUM_TABLE_LOOKUP_COLS: usize = size_of::<TableLookupCols<u8>>();
+pub const NUM_TABLE_LOOKUP_PREPROCESSED_COLS: usize = size_of::<TableLookupPreprocessedCols<u8>>();
+
+/// Receives the lookups into one table. The entries are the preprocessed trace, so they are
+/// committed at keygen and bound to the verifying key.
+#[derive(Clone, Debug)]
+pub struct TableLookupAir {
+    pub bus: TableLookupBus,
+    pub table_id: u32,
+    entries: Arc<[(u32, u32)]>,
+}
+
+impl TableLookupAir {
+    /// Height of the trace: the number of entries rounded up to a power of two.
+    pub fn height(&self) -> usize {
+        self.entries.len().next_power_of_two()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for TableLookupAir {}
+impl<F: Field> PartitionedBaseAir<F> for TableLookupAir {}
+impl<F: Field> BaseAir<F> for TableLookupAir {
+    fn width(&self) -> usize {
+        NUM_TABLE_LOOKUP_COLS
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        // The padding rows repeat the first entry, so a lookup counted on them is still a lookup
+        // of a real entry.
+        let rows: Vec<F> = (0..self.height())
+            .flat_map(|row| {
+                let (key, value) = self.entries.get(row).unwrap_or(&self.entries[0]);
+                key.to_le_bytes()
+                    .into_iter()
+                    .chain(value.to_le_bytes())
+                    .map(F::from_canonical_u8)
+            })
+            .collect();
+        Some(RowMajorMatrix::new(
+            rows,
+            NUM_TABLE_LOOKUP_PREPROCESSED_COLS,
+        ))
+    }
+}
+
+impl<AB: InteractionBuilder + PairBuilder> Air<AB> for TableLookupAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let prep_local = preprocessed.row_slice(0);
+        let prep_local: &TableLookupPreprocessedCols<AB::Var> = (*prep_local).borrow();
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &TableLookupCols<AB::Var> = (*local).borrow();
+
+        let message: Vec<AB::Expr> = self
+            .table_id
+            .to_le_bytes()
+            .map(AB::Expr::from_canonical_u8)
+            .into_iter()
+            .chain(prep_local.key.map(Into::into))
+            .chain(prep_local.value.map(Into::into))
+            .collect();
+        self.bus
+            .receive(builder, message.try_into().unwrap(), local.mult);
+    }
+}
+
+/// A constant table of `key -> value` entries, given by the VM configuration.
+#[derive(Debug)]
+pub struct TableLookupChip {
+    pub air: TableLookupAir,
+    rows: HashMap<u32, usize>,
+    count: Vec<AtomicU32>,
+}
+
+impl TableLookupChip {
+    /// Panics if `entries` is empty, has more than [MAX_TABLE_SIZE] entries or repeats a key.
+    pub fn new(bus: TableLookupBus, table_id: u32, entries: &[(u32, u32)]) -> Self {
+        assert!(!entries.is_empty(), "table {table_id} is empty");
+        assert!(
+            entries.len() <= MAX_TABLE_SIZE,
+            "table {table_id} has {} entries, more than the maximum {MAX_TABLE_SIZE}",
+            entries.len()
+        );
+        let mut rows = HashMap::with_capacity(entries.len());
+        for (row, &(key, _)) in entries.iter().enumerate() {
+            assert!(
+                rows.insert(key, row).is_none(),
+                "table {table_id} repeats the key {key:#x}"
+            );
+        }
+        Self {
+            air: TableLookupAir {
+                bus,
+                table_id,
+                entries: entries.into(),
+            },
+            rows,
+            count: (0..entries.len()).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    pub fn bus(&self) -> TableLookupBus {
+        self.air.bus
+    }
+
+    /// Records a lookup of `key` and returns its value, or `None` if the table does not contain
+    /// `key`.
+    pub fn request(&self, key: u32) -> Option<u32> {
+        let row = *self.rows.get(&key)?;
+        self.count[row].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Some(self.air.entries[row].1)
+    }
+
+    pub fn clear(&self) {
+        for count in &self.count {
+            count.store(0, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    pub fn generate_trace<F: Field>(&self) -> RowMajorMatrix<F> {
+        let mut rows = F::zero_vec(self.air.height() * NUM_TABLE_LOOKUP_COLS);
+        for (n, row) in rows
+            .chunks_mut(NUM_TABLE_LOOKUP_COLS)
+            .take(self.count.len())
+            .enumerate()
+        {
+            let cols: &mut TableLookupCols<F> = row.borrow_mut();
+            cols.mult =
+                F::from_canonical_u32(self.count[n].load(std::sync::atomic::Ordering::SeqCst));
+        }
+        RowMajorMatrix::new(rows, NUM_TABLE_LOOKUP_COLS)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for TableLookupChip {
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air.clone())
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let trace = self.generate_trace::<Val<SC>>();
+        AirProofInput::simple_no_pis(Arc::new(self.air), trace)
+    }
+}
+
+impl ChipUsageGetter for TableLookupChip {
+    fn air_name(&self) -> String {
+        format!("{}<{}>", get_air_name(&self.air), self.air.table_id)
+    }
+    fn constant_trace_height(&self) -> Option<usize> {
+        Some(self.air.height())
+    }
+    fn current_trace_height(&self) -> usize {
+        self.air.height()
+    }
+    fn trace_width(&self) -> usize {
+        NUM_TABLE_LOOKUP_COLS
+    }
+}
@@ -0,0 +1,143 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, Tamper, VmChipTestBuilder},
+    VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_instructions::{
+    instruction::Instruction, riscv::RV32_REGISTER_AS, UsizeOpcode, VmOpcode,
+};
+use openvm_rv32im_circuit::adapters::Rv32MultAdapterChip;
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use openvm_table_transpiler::Rv32TableOpcode;
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    Rv32TableLookupChip, TableLookupBus, TableLookupChip, TableLookupCoreChip, TableLookupCoreCols,
+};
+
+type F = BabyBear;
+
+const TABLE_LOOKUP_BUS: usize = BITWISE_OP_LOOKUP_BUS + 1;
+
+fn setup(tester: &VmChipTestBuilder<F>, tables: &[Vec<(u32, u32)>]) -> Rv32TableLookupChip<F> {
+    let bus = TableLookupBus::new(TABLE_LOOKUP_BUS);
+    let tables = tables
+        .iter()
+        .enumerate()
+        .map(|(id, entries)| Arc::new(TableLookupChip::new(bus, id as u32, entries)))
+        .collect();
+    Rv32TableLookupChip::new(
+        Rv32MultAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        TableLookupCoreChip::new(bus, tables, Rv32TableOpcode::default_offset()),
+        tester.memory_controller(),
+    )
+}
+
+fn write_lookup(
+    tester: &mut VmChipTestBuilder<F>,
+    rng: &mut StdRng,
+    table_id: u32,
+    key: u32,
+) -> (Instruction<F>, usize) {
+    let [rd, rs1, rs2] = [0; 3].map(|_| gen_pointer(rng, 4));
+    tester.write::<4>(
+        RV32_REGISTER_AS as usize,
+        rs1,
+        table_id.to_le_bytes().map(F::from_canonical_u8),
+    );
+    tester.write::<4>(
+        RV32_REGISTER_AS as usize,
+        rs2,
+        key.to_le_bytes().map(F::from_canonical_u8),
+    );
+    let instruction = Instruction::from_usize(
+        VmOpcode::from_usize(Rv32TableOpcode::LOOKUP.with_default_offset()),
+        [rd, rs1, rs2, 1, 1],
+    );
+    (instruction, rd)
+}
+
+#[test]
+fn test_table_lookup_chip() {
+    let mut rng = create_seeded_rng();
+    // The second table is not a power of two long, so its trace is padded.
+    let tables = vec![
+        (0..256).map(|x| (x, x * x)).collect::<Vec<_>>(),
+        (0..100).map(|_| (rng.gen(), rng.gen())).collect(),
+    ];
+
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = setup(&tester, &tables);
+    for _ in 0..50 {
+        let table_id = rng.gen_range(0..tables.len());
+        let (key, value) = tables[table_id][rng.gen_range(0..tables[table_id].len())];
+        let (instruction, rd) = write_lookup(&mut tester, &mut rng, table_id as u32, key);
+        tester.execute(&mut chip, instruction);
+
+        let output = tester
+            .read::<4>(RV32_REGISTER_AS as usize, rd)
+            .map(|x| x.as_canonical_u32() as u8);
+        assert_eq!(u32::from_le_bytes(output), value);
+    }
+
+    let tables = chip.core.tables.clone();
+    let mut tester = tester.build().load(chip);
+    for table in tables {
+        tester = tester.load(table);
+    }
+    tester
+        .finalize()
+        .simple_test()
+        .expect("Verification failed");
+}
+
+#[test]
+fn test_table_lookup_wrong_value_negative() {
+    let mut rng = create_seeded_rng();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = setup(&tester, &[vec![(1, 2), (3, 4)]]);
+    let (instruction, _) = write_lookup(&mut tester, &mut rng, 0, 3);
+    tester.execute(&mut chip, instruction);
+
+    let indices = (0..TableLookupCoreCols::<F>::width()).collect::<Vec<_>>();
+    let cols: &TableLookupCoreCols<usize> = indices[..].borrow();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let tamper = Tamper::off_by_one(0, adapter_width + cols.value[0]);
+    let tables = chip.core.tables.clone();
+    disable_debug_builder();
+    let mut tester = tester.build().load_and_tamper(chip, &[tamper]);
+    for table in tables {
+        tester = tester.load(table);
+    }
+    // The value is only constrained by the lookup and the memory write.
+    tester
+        .finalize()
+        .simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
+
+#[test]
+#[should_panic]
+fn test_table_lookup_missing_key() {
+    let mut rng = create_seeded_rng();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let mut chip = setup(&tester, &[vec![(1, 2), (3, 4)]]);
+    let (instruction, _) = write_lookup(&mut tester, &mut rng, 0, 2);
+    tester.execute(&mut chip, instruction);
+}
+
+#[test]
+#[should_panic]
+fn test_table_lookup_repeated_key() {
+    TableLookupChip::new(TableLookupBus::new(TABLE_LOOKUP_BUS), 0, &[(1, 2), (1, 3)]);
+}
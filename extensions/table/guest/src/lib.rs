@@ -0,0 +1,39 @@
+#![no_std]
+
+use strum_macros::FromRepr;
+
+/// This is custom-2 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x5b;
+pub const FUNCT3: u8 = 0b000;
+
+/// The table instructions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum TableBaseFunct7 {
+    /// Writes to `rd` the value of the key in `rs2` in the table whose id is in `rs1`.
+    Lookup = 0,
+}
+
+/// Returns the value of `key` in the lookup table `table_id`.
+///
+/// The tables are constants of the VM configuration: table `table_id` is the entry at that index
+/// in the table extension's list, so a guest usually shares the definition of its tables with the
+/// host that builds the config. Execution fails if the table does not exist or does not contain
+/// `key`.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+pub fn lookup(table_id: u32, key: u32) -> u32 {
+    let value: u32;
+    unsafe {
+        core::arch::asm!(
+            ".insn r {opcode}, {funct3}, {funct7}, {rd}, {rs1}, {rs2}",
+            opcode = const OPCODE,
+            funct3 = const FUNCT3,
+            funct7 = const TableBaseFunct7::Lookup as u8,
+            rd = out(reg) value,
+            rs1 = in(reg) table_id,
+            rs2 = in(reg) key,
+        );
+    }
+    value
+}
@@ -0,0 +1,42 @@
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_table_guest::{TableBaseFunct7, FUNCT3, OPCODE};
+use openvm_transpiler::{util::from_r_type, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// Lookup of a key in a constant table, with the table id and the key read from registers.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x340]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32TableOpcode {
+    LOOKUP,
+}
+
+#[derive(Default)]
+pub struct TableTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for TableTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match TableBaseFunct7::from_repr(dec_insn.funct7 as u8)? {
+            TableBaseFunct7::Lookup => Rv32TableOpcode::LOOKUP,
+        };
+        let instruction = from_r_type(local_opcode.with_default_offset(), 1, &dec_insn);
+        Some((instruction, 1))
+    }
+}